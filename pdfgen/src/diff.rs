@@ -0,0 +1,961 @@
+//! Structural comparison of generated PDF documents, reporting semantic [`Difference`]s instead of
+//! differing bytes, e.g. to prove that a refactoring of the writer keeps its output equivalent.
+//!
+//! Both documents are compared by walking their object graphs from the trailer in parallel, so
+//! objects are matched by their position in the graph rather than by their object numbers, and
+//! renumbered objects are not reported. Content streams are compared as sequences of operations,
+//! numbers are compared by value, and entries derived from other content, such as the `Length` of
+//! streams and the `ID` of the document, are ignored.
+//!
+//! NOTE: Only the syntax written by this crate is supported, i.e. uncompressed objects with direct
+//!       stream lengths and a single cross reference section.
+//!
+//! # Example
+//!
+//! ```
+//! # use pdfgen::{Document, diff, types::hierarchy::primitives::rectangle::Rectangle};
+//! let mut left = Document::builder().with_page_size(Rectangle::A4).build();
+//! left.create_page();
+//!
+//! let mut right = Document::builder().with_page_size(Rectangle::A5).build();
+//! right.create_page();
+//!
+//! // the width and the height of the media box differ
+//! let differences = diff::compare_documents(&left, &right).unwrap();
+//! let paths: Vec<_> = differences.iter().map(|difference| difference.path()).collect();
+//! assert_eq!(paths, ["Root/Pages/MediaBox[2]", "Root/Pages/MediaBox[3]"]);
+//! ```
+
+use std::{
+    collections::{BTreeSet, HashMap, VecDeque},
+    fmt::{self, Display},
+};
+
+use crate::Document;
+
+/// Possible errors that might be returned when comparing two documents.
+#[derive(Debug, thiserror::Error)]
+pub enum DiffErr {
+    /// Indicates that a document could not be written.
+    #[error("Document could not be written: {0}")]
+    Write(std::io::Error),
+
+    /// Indicates that a document is not a PDF file with the syntax written by this crate.
+    #[error("Malformed PDF at byte {offset}: {reason}")]
+    Malformed {
+        /// Offset of the byte at which parsing failed.
+        offset: usize,
+
+        /// Description of what was expected at the offset.
+        reason: &'static str,
+    },
+
+    /// Indicates that an object is referenced, but not defined in the document.
+    #[error("Object {0} is referenced, but not defined.")]
+    UndefinedObject(u64),
+}
+
+/// A semantic difference between two documents, found by [`compare`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Difference {
+    /// The values at the path differ, or the value is missing in one of the documents.
+    Value {
+        /// Path of the value, starting at the trailer, e.g. `Root/Pages/Kids[0]/MediaBox`.
+        path: String,
+
+        /// The value in the left document, if present.
+        left: Option<String>,
+
+        /// The value in the right document, if present.
+        right: Option<String>,
+    },
+
+    /// The operations of the content stream at the path differ. Only the first differing
+    /// operation of a content stream is reported.
+    Operation {
+        /// Path of the content stream, starting at the trailer, e.g. `Root/Pages/Kids[0]/Contents`.
+        path: String,
+
+        /// Index of the first differing operation in the content stream.
+        index: usize,
+
+        /// The operation in the left document, if the stream has that many operations.
+        left: Option<String>,
+
+        /// The operation in the right document, if the stream has that many operations.
+        right: Option<String>,
+    },
+
+    /// The data of the stream at the path differs. Reported for streams that are not content
+    /// streams, such as images and font programs.
+    StreamData {
+        /// Path of the stream, starting at the trailer.
+        path: String,
+    },
+}
+
+impl Difference {
+    /// Returns the path at which this `Difference` was found, starting at the trailer.
+    pub fn path(&self) -> &str {
+        match self {
+            Difference::Value { path, .. }
+            | Difference::Operation { path, .. }
+            | Difference::StreamData { path } => path,
+        }
+    }
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |value: &Option<String>| value.clone().unwrap_or_else(|| "missing".to_string());
+
+        match self {
+            Difference::Value { path, left, right } => {
+                write!(f, "{path}: {} != {}", side(left), side(right))
+            }
+            Difference::Operation {
+                path,
+                index,
+                left,
+                right,
+            } => write!(
+                f,
+                "{path}, operation {index}: {} != {}",
+                side(left),
+                side(right)
+            ),
+            Difference::StreamData { path } => write!(f, "{path}: stream data differs"),
+        }
+    }
+}
+
+/// Compares the given documents, written in lenient mode, returning all [`Difference`]s found.
+pub fn compare_documents(left: &Document, right: &Document) -> Result<Vec<Difference>, DiffErr> {
+    let mut left_bytes = Vec::new();
+    left.write_lenient(&mut left_bytes)
+        .map_err(DiffErr::Write)?;
+
+    let mut right_bytes = Vec::new();
+    right
+        .write_lenient(&mut right_bytes)
+        .map_err(DiffErr::Write)?;
+
+    compare(&left_bytes, &right_bytes)
+}
+
+/// Compares the given PDF files written by this crate, returning all [`Difference`]s found, in
+/// the order they were reached while walking the object graphs.
+pub fn compare(left: &[u8], right: &[u8]) -> Result<Vec<Difference>, DiffErr> {
+    let left = Pdf::parse(left)?;
+    let right = Pdf::parse(right)?;
+
+    let mut comparison = Comparison {
+        left: &left,
+        right: &right,
+        pairs: HashMap::new(),
+        paired: BTreeSet::new(),
+        queue: VecDeque::new(),
+        differences: Vec::new(),
+    };
+
+    comparison.compare_dicts("", &left.trailer, &right.trailer, Pdf::DERIVED_TRAILER_KEYS);
+    while let Some((left_number, right_number, path)) = comparison.queue.pop_front() {
+        comparison.compare_objects(&path, left_number, right_number)?;
+    }
+
+    Ok(comparison.differences)
+}
+
+/// A value of a PDF object.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    Name(Vec<u8>),
+    String(Vec<u8>),
+    Array(Vec<Value>),
+    Dict(Vec<(Vec<u8>, Value)>),
+    Ref(u64),
+}
+
+impl Value {
+    /// Returns the value of the given key, if this `Value` is a dictionary containing it.
+    fn get(&self, key: &[u8]) -> Option<&Value> {
+        match self {
+            Value::Dict(entries) => entries
+                .iter()
+                .find(|(entry_key, _)| entry_key == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if this `Value` equals the given one, comparing numbers by value.
+    fn same(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Number(left), Value::Number(right)) => (left - right).abs() < 1e-6,
+            (Value::Array(left), Value::Array(right)) => {
+                left.len() == right.len() && left.iter().zip(right).all(|(l, r)| l.same(r))
+            }
+            (Value::Dict(left), Value::Dict(right)) => {
+                left.len() == right.len()
+                    && left
+                        .iter()
+                        .all(|(key, l)| other.get(key).is_some_and(|r| l.same(r)))
+                    && right.iter().all(|(key, _)| self.get(key).is_some())
+            }
+            _ => self == other,
+        }
+    }
+}
+
+impl Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |values: &mut dyn Iterator<Item = String>| values.collect::<Vec<_>>().join(" ");
+
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{value}"),
+            Value::Number(value) => write!(f, "{value}"),
+            Value::Name(name) => write!(f, "/{}", String::from_utf8_lossy(name)),
+            Value::String(string) => write!(f, "({})", String::from_utf8_lossy(string)),
+            Value::Array(values) => {
+                write!(f, "[{}]", join(&mut values.iter().map(Value::to_string)))
+            }
+            Value::Dict(entries) => {
+                let mut entries = entries
+                    .iter()
+                    .map(|(key, value)| format!("/{} {value}", String::from_utf8_lossy(key)));
+                write!(f, "<< {} >>", join(&mut entries))
+            }
+            Value::Ref(number) => write!(f, "{number} 0 R"),
+        }
+    }
+}
+
+/// A single operation of a content stream, i.e. an operator with its operands.
+#[derive(Debug, Clone, PartialEq)]
+struct Operation {
+    operands: Vec<Value>,
+    operator: Vec<u8>,
+}
+
+impl Operation {
+    /// Returns `true` if this `Operation` equals the given one, comparing numbers by value.
+    fn same(&self, other: &Operation) -> bool {
+        self.operator == other.operator
+            && self.operands.len() == other.operands.len()
+            && self
+                .operands
+                .iter()
+                .zip(&other.operands)
+                .all(|(l, r)| l.same(r))
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for operand in &self.operands {
+            write!(f, "{operand} ")?;
+        }
+
+        write!(f, "{}", String::from_utf8_lossy(&self.operator))
+    }
+}
+
+/// An indirect object of a parsed PDF file.
+#[derive(Debug)]
+struct PdfObject {
+    /// The value of the object, which is the stream dictionary for streams.
+    value: Value,
+
+    /// Data of the stream, if the object is a stream.
+    stream: Option<Vec<u8>>,
+}
+
+/// A parsed PDF file, with its objects by object number.
+#[derive(Debug)]
+struct Pdf {
+    objects: HashMap<u64, PdfObject>,
+    trailer: Value,
+}
+
+impl Pdf {
+    /// Keys of the trailer derived from the rest of the document.
+    const DERIVED_TRAILER_KEYS: &[&[u8]] = &[b"Size", b"ID", b"Prev"];
+
+    /// Keys of stream dictionaries derived from the stream data.
+    const DERIVED_STREAM_KEYS: &[&[u8]] = &[b"Length"];
+
+    /// Parses the given bytes of a PDF file.
+    fn parse(bytes: &[u8]) -> Result<Self, DiffErr> {
+        let mut parser = Parser::new(bytes, true);
+        let mut objects = HashMap::new();
+
+        loop {
+            parser.skip_whitespace();
+            if !parser.peek().is_some_and(|byte| byte.is_ascii_digit()) {
+                break;
+            }
+
+            let number = parser.integer()?;
+            parser.integer()?;
+            parser.expect_keyword(b"obj")?;
+            let value = parser.value()?;
+
+            parser.skip_whitespace();
+            let stream = if parser.keyword_ahead(b"stream") {
+                let length = match value.get(b"Length") {
+                    Some(Value::Number(length)) => *length as usize,
+                    _ => return Err(parser.error("direct stream length")),
+                };
+                Some(parser.stream_data(length)?)
+            } else {
+                None
+            };
+
+            parser.expect_keyword(b"endobj")?;
+            objects.insert(number, PdfObject { value, stream });
+        }
+
+        parser.expect_keyword(b"xref")?;
+        parser.skip_past(b"trailer")?;
+        let trailer = parser.value()?;
+
+        Ok(Self { objects, trailer })
+    }
+
+    /// Returns the object with the given object number.
+    fn object(&self, number: u64) -> Result<&PdfObject, DiffErr> {
+        self.objects
+            .get(&number)
+            .ok_or(DiffErr::UndefinedObject(number))
+    }
+}
+
+/// State of the comparison of two parsed PDF files.
+struct Comparison<'a> {
+    left: &'a Pdf,
+    right: &'a Pdf,
+
+    /// Object numbers of the left document paired with the ones of the right document, which are
+    /// reached over the same path.
+    pairs: HashMap<u64, (u64, String)>,
+
+    /// Object numbers of the right document that are already paired.
+    paired: BTreeSet<u64>,
+
+    /// Pairs of objects left to compare, with the path they were reached over.
+    queue: VecDeque<(u64, u64, String)>,
+
+    differences: Vec<Difference>,
+}
+
+impl Comparison<'_> {
+    /// Joins the given path with the given key of a dictionary.
+    fn key_path(path: &str, key: &[u8]) -> String {
+        let key = String::from_utf8_lossy(key);
+        match path {
+            "" => key.into_owned(),
+            _ => format!("{path}/{key}"),
+        }
+    }
+
+    /// Reports that the given values at the given path differ.
+    fn value_difference(&mut self, path: &str, left: Option<&Value>, right: Option<&Value>) {
+        self.differences.push(Difference::Value {
+            path: path.to_string(),
+            left: left.map(Value::to_string),
+            right: right.map(Value::to_string),
+        });
+    }
+
+    /// Compares the given values at the given path, pairing referenced objects.
+    fn compare_values(&mut self, path: &str, left: &Value, right: &Value) {
+        match (left, right) {
+            (Value::Ref(left_number), Value::Ref(right_number)) => {
+                match self.pairs.get(left_number) {
+                    Some((paired, _)) if paired == right_number => {}
+                    Some((_, first_path)) => {
+                        let first_path = first_path.clone();
+                        self.differences.push(Difference::Value {
+                            path: path.to_string(),
+                            left: Some(format!("reference to {first_path}")),
+                            right: Some("reference to another object".to_string()),
+                        });
+                    }
+                    None if self.paired.contains(right_number) => {
+                        self.differences.push(Difference::Value {
+                            path: path.to_string(),
+                            left: Some("reference to another object".to_string()),
+                            right: Some("reference to an object reached earlier".to_string()),
+                        });
+                    }
+                    None => {
+                        self.pairs
+                            .insert(*left_number, (*right_number, path.to_string()));
+                        self.paired.insert(*right_number);
+                        self.queue
+                            .push_back((*left_number, *right_number, path.to_string()));
+                    }
+                }
+            }
+            (Value::Array(left_values), Value::Array(right_values))
+                if left_values.len() == right_values.len() =>
+            {
+                for (idx, (left, right)) in left_values.iter().zip(right_values).enumerate() {
+                    self.compare_values(&format!("{path}[{idx}]"), left, right);
+                }
+            }
+            (Value::Dict(_), Value::Dict(_)) => self.compare_dicts(path, left, right, &[]),
+            _ if left.same(right) => {}
+            _ => self.value_difference(path, Some(left), Some(right)),
+        }
+    }
+
+    /// Compares the given dictionaries at the given path, ignoring the given keys.
+    fn compare_dicts(&mut self, path: &str, left: &Value, right: &Value, ignored: &[&[u8]]) {
+        let (Value::Dict(left_entries), Value::Dict(right_entries)) = (left, right) else {
+            return self.compare_values(path, left, right);
+        };
+
+        let keys: BTreeSet<&[u8]> = left_entries
+            .iter()
+            .chain(right_entries)
+            .map(|(key, _)| key.as_slice())
+            .filter(|key| !ignored.contains(key))
+            .collect();
+
+        for key in keys {
+            let path = Self::key_path(path, key);
+            match (left.get(key), right.get(key)) {
+                (Some(left), Some(right)) => self.compare_values(&path, left, right),
+                (left, right) => self.value_difference(&path, left, right),
+            }
+        }
+    }
+
+    /// Compares the objects with the given object numbers, reached over the given path.
+    fn compare_objects(
+        &mut self,
+        path: &str,
+        left_number: u64,
+        right_number: u64,
+    ) -> Result<(), DiffErr> {
+        let left = self.left.object(left_number)?;
+        let right = self.right.object(right_number)?;
+
+        match (&left.stream, &right.stream) {
+            (Some(left_data), Some(right_data)) => {
+                self.compare_dicts(path, &left.value, &right.value, Pdf::DERIVED_STREAM_KEYS);
+                self.compare_streams(path, &left.value, left_data, right_data);
+            }
+            (None, None) => self.compare_values(path, &left.value, &right.value),
+            _ => self.differences.push(Difference::Value {
+                path: path.to_string(),
+                left: Some(Self::describe(left)),
+                right: Some(Self::describe(right)),
+            }),
+        }
+
+        Ok(())
+    }
+
+    /// Returns a short description of the kind of the given object.
+    fn describe(object: &PdfObject) -> String {
+        match object.stream {
+            Some(_) => "stream".to_string(),
+            None => object.value.to_string(),
+        }
+    }
+
+    /// Compares the data of the given streams with the given dictionary at the given path, as
+    /// operations if they are content streams.
+    fn compare_streams(&mut self, path: &str, dict: &Value, left: &[u8], right: &[u8]) {
+        // images, font programs and encoded streams are binary data
+        let is_binary = [b"Length1".as_slice(), b"Filter"]
+            .iter()
+            .any(|key| dict.get(key).is_some())
+            || dict.get(b"Subtype") == Some(&Value::Name(b"Image".to_vec()))
+            || dict.get(b"Type") == Some(&Value::Name(b"Metadata".to_vec()));
+
+        let operations = (!is_binary)
+            .then(|| Some((Parser::operations(left)?, Parser::operations(right)?)))
+            .flatten();
+
+        let Some((left, right)) = operations else {
+            if left != right {
+                self.differences.push(Difference::StreamData {
+                    path: path.to_string(),
+                });
+            }
+            return;
+        };
+
+        let differing =
+            (0..left.len().max(right.len())).find(|&idx| match (left.get(idx), right.get(idx)) {
+                (Some(left), Some(right)) => !left.same(right),
+                _ => true,
+            });
+
+        if let Some(index) = differing {
+            self.differences.push(Difference::Operation {
+                path: path.to_string(),
+                index,
+                left: left.get(index).map(Operation::to_string),
+                right: right.get(index).map(Operation::to_string),
+            });
+        }
+    }
+}
+
+/// Parser of the PDF syntax written by this crate.
+struct Parser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+
+    /// Whether `n 0 R` is parsed as a reference, which is not allowed in content streams.
+    references: bool,
+}
+
+impl<'a> Parser<'a> {
+    /// Creates a new `Parser` of the given bytes.
+    fn new(bytes: &'a [u8], references: bool) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            references,
+        }
+    }
+
+    /// Parses the given content stream data into operations, returning `None` if it's not a
+    /// valid content stream.
+    fn operations(bytes: &[u8]) -> Option<Vec<Operation>> {
+        let mut parser = Parser::new(bytes, false);
+        let mut operations = Vec::new();
+        let mut operands = Vec::new();
+
+        loop {
+            parser.skip_whitespace();
+            let Some(byte) = parser.peek() else {
+                break;
+            };
+
+            if Self::is_regular(byte) && !Self::starts_number(byte) {
+                let keyword = parser.keyword();
+                match keyword {
+                    b"true" => operands.push(Value::Bool(true)),
+                    b"false" => operands.push(Value::Bool(false)),
+                    b"null" => operands.push(Value::Null),
+                    operator => operations.push(Operation {
+                        operands: std::mem::take(&mut operands),
+                        operator: operator.to_vec(),
+                    }),
+                }
+            } else {
+                operands.push(parser.value().ok()?);
+            }
+        }
+
+        operands.is_empty().then_some(operations)
+    }
+
+    /// Returns an error at the current position, describing what was expected.
+    fn error(&self, reason: &'static str) -> DiffErr {
+        DiffErr::Malformed {
+            offset: self.pos,
+            reason,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    /// Returns `true` if the given byte is neither whitespace nor a delimiter.
+    fn is_regular(byte: u8) -> bool {
+        !byte.is_ascii_whitespace() && byte != 0 && !b"()<>[]{}/%".contains(&byte)
+    }
+
+    /// Returns `true` if the given byte starts a number.
+    fn starts_number(byte: u8) -> bool {
+        byte.is_ascii_digit() || b"+-.".contains(&byte)
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'%' => {
+                    while self
+                        .peek()
+                        .is_some_and(|byte| byte != b'\n' && byte != b'\r')
+                    {
+                        self.pos += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() || byte == 0 => self.pos += 1,
+                _ => break,
+            }
+        }
+    }
+
+    /// Skips to the position following the next occurrence of the given keyword.
+    fn skip_past(&mut self, keyword: &[u8]) -> Result<(), DiffErr> {
+        let offset = self.bytes[self.pos..]
+            .windows(keyword.len())
+            .position(|window| window == keyword)
+            .ok_or_else(|| self.error("trailer"))?;
+
+        self.pos += offset + keyword.len();
+        Ok(())
+    }
+
+    /// Parses a run of regular bytes, such as a number or an operator.
+    fn keyword(&mut self) -> &'a [u8] {
+        let start = self.pos;
+        while self.peek().is_some_and(Self::is_regular) {
+            self.pos += 1;
+        }
+
+        &self.bytes[start..self.pos]
+    }
+
+    /// Returns `true` and consumes the given keyword, if it follows.
+    fn keyword_ahead(&mut self, keyword: &[u8]) -> bool {
+        let start = self.pos;
+        self.skip_whitespace();
+        if self.keyword() == keyword {
+            return true;
+        }
+
+        self.pos = start;
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &'static [u8]) -> Result<(), DiffErr> {
+        match self.keyword_ahead(keyword) {
+            true => Ok(()),
+            false => Err(self.error("keyword")),
+        }
+    }
+
+    fn integer(&mut self) -> Result<u64, DiffErr> {
+        self.skip_whitespace();
+        std::str::from_utf8(self.keyword())
+            .ok()
+            .and_then(|integer| integer.parse().ok())
+            .ok_or_else(|| self.error("integer"))
+    }
+
+    /// Parses the data of a stream with the given length, following the `stream` keyword.
+    fn stream_data(&mut self, length: usize) -> Result<Vec<u8>, DiffErr> {
+        if self.bytes[self.pos..].starts_with(b"\r\n") {
+            self.pos += 2;
+        } else if self.peek() == Some(b'\n') {
+            self.pos += 1;
+        }
+
+        let data = self
+            .bytes
+            .get(self.pos..self.pos + length)
+            .ok_or_else(|| self.error("stream data"))?;
+        self.pos += length;
+        self.expect_keyword(b"endstream")?;
+
+        Ok(data.to_vec())
+    }
+
+    /// Parses a single value.
+    fn value(&mut self) -> Result<Value, DiffErr> {
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'<') if self.bytes[self.pos..].starts_with(b"<<") => self.dict(),
+            Some(b'<') => self.hex_string(),
+            Some(b'[') => self.array(),
+            Some(b'(') => self.literal_string(),
+            Some(b'/') => {
+                self.pos += 1;
+                Ok(Value::Name(self.name()))
+            }
+            Some(byte) if Self::starts_number(byte) => self.number_or_reference(),
+            Some(_) => match self.keyword() {
+                b"true" => Ok(Value::Bool(true)),
+                b"false" => Ok(Value::Bool(false)),
+                b"null" => Ok(Value::Null),
+                _ => Err(self.error("value")),
+            },
+            None => Err(self.error("value")),
+        }
+    }
+
+    fn dict(&mut self) -> Result<Value, DiffErr> {
+        self.pos += 2;
+        let mut entries = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            if self.bytes[self.pos..].starts_with(b">>") {
+                self.pos += 2;
+                return Ok(Value::Dict(entries));
+            }
+
+            let Value::Name(key) = self.value()? else {
+                return Err(self.error("dictionary key"));
+            };
+            entries.push((key, self.value()?));
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, DiffErr> {
+        self.pos += 1;
+        let mut values = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(b']') {
+                self.pos += 1;
+                return Ok(Value::Array(values));
+            }
+
+            values.push(self.value()?);
+        }
+    }
+
+    /// Parses a name following the `/`, decoding `#xx` escapes.
+    fn name(&mut self) -> Vec<u8> {
+        let raw = self.keyword();
+        let mut name = Vec::with_capacity(raw.len());
+
+        let mut idx = 0;
+        while idx < raw.len() {
+            let escaped = (raw[idx] == b'#')
+                .then(|| raw.get(idx + 1..idx + 3))
+                .flatten()
+                .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+
+            match escaped {
+                Some(byte) => {
+                    name.push(byte);
+                    idx += 3;
+                }
+                None => {
+                    name.push(raw[idx]);
+                    idx += 1;
+                }
+            }
+        }
+
+        name
+    }
+
+    fn hex_string(&mut self) -> Result<Value, DiffErr> {
+        self.pos += 1;
+        let mut digits = Vec::new();
+
+        loop {
+            match self.peek() {
+                Some(b'>') => break,
+                Some(byte) if byte.is_ascii_hexdigit() => digits.push(byte),
+                Some(byte) if byte.is_ascii_whitespace() => {}
+                _ => return Err(self.error("hexadecimal string")),
+            }
+            self.pos += 1;
+        }
+        self.pos += 1;
+
+        // a missing final digit is assumed to be 0
+        if digits.len() % 2 == 1 {
+            digits.push(b'0');
+        }
+
+        Ok(Value::String(
+            digits
+                .chunks(2)
+                .map(|pair| {
+                    let hex = std::str::from_utf8(pair).expect("Digits are ASCII.");
+                    u8::from_str_radix(hex, 16).expect("Digits are hexadecimal.")
+                })
+                .collect(),
+        ))
+    }
+
+    fn literal_string(&mut self) -> Result<Value, DiffErr> {
+        self.pos += 1;
+        let mut string = Vec::new();
+        let mut depth = 0;
+
+        loop {
+            let byte = self.peek().ok_or_else(|| self.error("end of string"))?;
+            self.pos += 1;
+
+            match byte {
+                b'(' => {
+                    depth += 1;
+                    string.push(byte);
+                }
+                b')' if depth == 0 => return Ok(Value::String(string)),
+                b')' => {
+                    depth -= 1;
+                    string.push(byte);
+                }
+                b'\\' => self.escape(&mut string)?,
+                _ => string.push(byte),
+            }
+        }
+    }
+
+    /// Parses an escape sequence of a literal string following the `\`.
+    fn escape(&mut self, string: &mut Vec<u8>) -> Result<(), DiffErr> {
+        let byte = self.peek().ok_or_else(|| self.error("escape sequence"))?;
+        self.pos += 1;
+
+        match byte {
+            b'n' => string.push(b'\n'),
+            b'r' => string.push(b'\r'),
+            b't' => string.push(b'\t'),
+            b'b' => string.push(0x08),
+            b'f' => string.push(0x0C),
+            b'0'..=b'7' => {
+                let mut code = u32::from(byte - b'0');
+                for _ in 0..2 {
+                    match self.peek() {
+                        Some(digit @ b'0'..=b'7') => {
+                            code = code * 8 + u32::from(digit - b'0');
+                            self.pos += 1;
+                        }
+                        _ => break,
+                    }
+                }
+                string.push(code as u8);
+            }
+            // line continuation
+            b'\r' => {
+                if self.peek() == Some(b'\n') {
+                    self.pos += 1;
+                }
+            }
+            b'\n' => {}
+            _ => string.push(byte),
+        }
+
+        Ok(())
+    }
+
+    /// Parses a number, or a reference `n 0 R` if references are allowed.
+    fn number_or_reference(&mut self) -> Result<Value, DiffErr> {
+        let number = self.number()?;
+
+        if self.references && number.fract() == 0.0 && number >= 0.0 {
+            let start = self.pos;
+            self.skip_whitespace();
+
+            let generation = self.peek().is_some_and(|byte| byte.is_ascii_digit());
+            if generation && self.number().is_ok() && self.keyword_ahead(b"R") {
+                return Ok(Value::Ref(number as u64));
+            }
+
+            self.pos = start;
+        }
+
+        Ok(Value::Number(number))
+    }
+
+    fn number(&mut self) -> Result<f64, DiffErr> {
+        std::str::from_utf8(self.keyword())
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .ok_or_else(|| self.error("number"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Document,
+        types::hierarchy::{
+            content::{color::Color, text::Text},
+            primitives::rectangle::{Position, Rectangle},
+        },
+    };
+
+    use super::{Difference, Parser, compare, compare_documents};
+
+    fn document(text: &str, fonts: &[&str]) -> Document {
+        let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+
+        let font_ids: Vec<_> = fonts
+            .iter()
+            .map(|font| document.create_font("Type1".into(), font.as_bytes().to_vec()))
+            .collect();
+
+        let page = document.create_page();
+        page.fill_path(Rectangle::from_units(10., 10., 50., 50.), Color::Gray(128));
+        page.add_text(
+            Text::builder()
+                .with_content(text)
+                .at(Position::from_units(10., 80.))
+                .build(),
+            font_ids.last().unwrap().clone(),
+        );
+
+        document
+    }
+
+    #[test]
+    fn equivalent_documents() {
+        // the unused font shifts the object numbers of the page and its content
+        let left = document("Hello", &["Helvetica"]);
+        let right = document("Hello", &["Courier", "Helvetica"]);
+
+        assert_eq!(compare_documents(&left, &right).unwrap(), []);
+    }
+
+    #[test]
+    fn different_documents() {
+        let left = document("Hello", &["Helvetica"]);
+        let right = document("Hello, World", &["Courier"]);
+
+        let differences: Vec<String> = compare_documents(&left, &right)
+            .unwrap()
+            .iter()
+            .map(Difference::to_string)
+            .collect();
+
+        insta::assert_snapshot!(differences.join("\n"), @r"
+        Root/Pages/Kids[0]/Contents, operation 9: (Hello) Tj != (Hello, World) Tj
+        Root/Pages/Kids[0]/Resources/Font/F1/BaseFont: /Helvetica != /Courier
+        ");
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(compare(b"%PDF-2.0\n1 0 obj\n<< /Type >>", b"").is_err());
+    }
+
+    #[test]
+    fn content_operations() {
+        let operations =
+            Parser::operations(b"q\n1 0 0 1 10 10 cm\n/Im1 Do\nQ\nBT (a \\(b\\)) Tj ET")
+                .unwrap()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>();
+
+        assert_eq!(
+            operations,
+            [
+                "q",
+                "1 0 0 1 10 10 cm",
+                "/Im1 Do",
+                "Q",
+                "BT",
+                "(a (b)) Tj",
+                "ET"
+            ]
+        );
+    }
+}
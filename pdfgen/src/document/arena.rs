@@ -0,0 +1,93 @@
+//! Pool of byte buffers reused across documents.
+
+use crate::Document;
+
+/// A pool of byte buffers backing the content streams of [`Document`]s, for generating many
+/// documents in batch. Buffers of a written document are returned to the arena with
+/// [`Arena::recycle`] and keep their capacity, so the content streams of the next document grow
+/// into already allocated memory instead of allocating and freeing large buffers over and over.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Arena, Document};
+/// let mut arena = Arena::new();
+///
+/// for _ in 0..3 {
+///     let mut document = Document::with_arena(&mut arena);
+///     document.create_page();
+///     // ... add content and write the document
+///
+///     arena.recycle(document);
+/// }
+/// ```
+#[derive(Debug, Default)]
+pub struct Arena {
+    /// Empty buffers that are available for reuse.
+    buffers: Vec<Vec<u8>>,
+}
+
+impl Arena {
+    /// Creates a new empty `Arena`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of buffers available for reuse.
+    pub fn available(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns the buffers of the given [`Document`] into this `Arena`, to be reused by the next
+    /// document. The buffers are cleared but keep their capacity.
+    pub fn recycle(&mut self, mut document: Document) {
+        let buffers = document
+            .pages
+            .iter_mut()
+            .flat_map(|page| page.take_buffers());
+
+        for mut buffer in buffers.chain(document.buffers) {
+            // buffers without capacity don't save any allocations
+            if buffer.capacity() > 0 {
+                buffer.clear();
+                self.buffers.push(buffer);
+            }
+        }
+    }
+
+    /// Takes all available buffers out of this `Arena`.
+    pub(crate) fn take_buffers(&mut self) -> Vec<Vec<u8>> {
+        std::mem::take(&mut self.buffers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Document,
+        types::hierarchy::{content::color::Color, primitives::rectangle::Rectangle},
+    };
+
+    use super::Arena;
+
+    #[test]
+    fn reused_buffers() {
+        let mut arena = Arena::new();
+
+        let mut document = Document::with_arena(&mut arena);
+        let page = document.create_page();
+        page.fill_path(Rectangle::A4, Color::Gray(0));
+        document.create_page();
+        arena.recycle(document);
+        assert_eq!(arena.available(), 1);
+
+        let mut document = Document::with_arena(&mut arena);
+        assert_eq!(arena.available(), 0);
+
+        let page = document.create_page();
+        assert!(page.content_stream().is_empty());
+        page.fill_path(Rectangle::A4, Color::Gray(0));
+        arena.recycle(document);
+        assert_eq!(arena.available(), 1);
+    }
+}
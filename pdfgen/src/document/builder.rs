@@ -1,8 +1,23 @@
 //! Types for easier construction of a PDF [`Document`].
 
 use crate::{
-    Document, IdManager,
-    types::hierarchy::{catalog::Catalog, page_tree::PageTree, primitives::rectangle::Rectangle},
+    Arena, Document, IdManager,
+    document::{
+        file_id::FileId,
+        font_embedding::FontEmbedding,
+        output_policy::OutputPolicy,
+        producer::Producer,
+        profile::{PdfVersion, Profile},
+        roll::Roll,
+        sanitize::SanitizePolicy,
+        text_style::TextStyle,
+    },
+    types::hierarchy::{
+        catalog::Catalog,
+        metadata::{Info, XmpMetadata},
+        page_tree::PageTree,
+        primitives::rectangle::Rectangle,
+    },
 };
 
 /// Used for construction of a PDF [`Document`], enabling streamlined configuration of the
@@ -10,6 +25,17 @@ use crate::{
 pub struct Builder {
     pub(crate) id_manager: IdManager,
     pub(crate) page_size: Option<Rectangle>,
+    pub(crate) version: PdfVersion,
+    pub(crate) font_embedding: Option<FontEmbedding>,
+    pub(crate) output_policy: OutputPolicy,
+    pub(crate) file_id: FileId,
+    pub(crate) buffers: Vec<Vec<u8>>,
+    pub(crate) producer: Option<Producer>,
+    pub(crate) cull_content: bool,
+    pub(crate) sanitize: Option<SanitizePolicy>,
+    pub(crate) roll: Option<Roll>,
+    pub(crate) text_style: Option<TextStyle>,
+    pub(crate) image_compression: Option<u8>,
 }
 
 impl Builder {
@@ -21,6 +47,120 @@ impl Builder {
         }
     }
 
+    /// Set the [`PdfVersion`] the document is written against.
+    pub fn with_version(self, version: PdfVersion) -> Self {
+        Self { version, ..self }
+    }
+
+    /// Set the [`FontEmbedding`] policy that the fonts of the document are checked against when
+    /// it's written.
+    pub fn with_font_embedding(self, font_embedding: FontEmbedding) -> Self {
+        Self {
+            font_embedding: Some(font_embedding),
+            ..self
+        }
+    }
+
+    /// Reuse the byte buffers of the given [`Arena`] for the content streams of the document's
+    /// pages. See [`Arena`] for more details.
+    pub fn with_arena(self, arena: &mut Arena) -> Self {
+        Self {
+            buffers: arena.take_buffers(),
+            ..self
+        }
+    }
+
+    /// Set the [`OutputPolicy`] on how the file structure of the document is written.
+    pub fn with_output_policy(self, output_policy: OutputPolicy) -> Self {
+        Self {
+            output_policy,
+            ..self
+        }
+    }
+
+    /// Set the [`FileId`] written as the file identifier into the trailer of the document, e.g. to
+    /// keep the identifier stable across regenerations of the same logical document.
+    pub fn with_file_id(self, file_id: FileId) -> Self {
+        Self { file_id, ..self }
+    }
+
+    /// Identify the software that produced the document with the given [`Producer`], written
+    /// into both the document information dictionary and the XMP metadata.
+    pub fn with_producer(self, producer: Producer) -> Self {
+        Self {
+            producer: Some(producer),
+            ..self
+        }
+    }
+
+    /// Set whether drawing operations that paint entirely outside of the media box of their page
+    /// are left out when the document is written, e.g. elements of a template positioned off the
+    /// page when they're not needed. This reduces the size of the content streams and the work of
+    /// renderers. Only images and filled paths are culled, texts are always written.
+    pub fn with_content_culling(self, cull_content: bool) -> Self {
+        Self {
+            cull_content,
+            ..self
+        }
+    }
+
+    /// Set the [`SanitizePolicy`] applied to untrusted strings placed into the document, such as the
+    /// content of texts, names of fonts and metadata. See [`SanitizePolicy`] for more details.
+    pub fn with_sanitization(self, sanitize: SanitizePolicy) -> Self {
+        Self {
+            sanitize: Some(sanitize),
+            ..self
+        }
+    }
+
+    /// Set the [`Roll`] of paper the document is printed on, giving it a single page as wide as the
+    /// roll and as tall as its content. See [`Roll`] for more details.
+    pub fn with_roll(self, roll: Roll) -> Self {
+        Self {
+            roll: Some(roll),
+            ..self
+        }
+    }
+
+    /// Set the default [`TextStyle`] of the document, whose font is used for texts added to a page
+    /// without one. See [`TextStyle`] for more details.
+    pub fn with_text_style(self, text_style: TextStyle) -> Self {
+        Self {
+            text_style: Some(text_style),
+            ..self
+        }
+    }
+
+    /// Set the zlib compression level, from 0 to [`Image::MAX_COMPRESSION_LEVEL`], that the
+    /// uncompressed samples of images are compressed with when they're added to a page of the
+    /// document, e.g. images built with [`ImageBuilder::with_compression_level`] set to 0. Images
+    /// that are already compressed are left as they are.
+    ///
+    /// [`Image::MAX_COMPRESSION_LEVEL`]: crate::types::hierarchy::content::image::Image::MAX_COMPRESSION_LEVEL
+    /// [`ImageBuilder::with_compression_level`]: crate::types::hierarchy::content::image::ImageBuilder::with_compression_level
+    pub fn with_image_compression(self, level: u8) -> Self {
+        Self {
+            image_compression: Some(level),
+            ..self
+        }
+    }
+
+    /// Configure the document with the preset [`Profile`] for a common output target, selecting
+    /// its PDF version, output policy, font embedding policy, image compression and producer. Options
+    /// set after the profile override the ones selected by the profile.
+    pub fn with_profile(self, profile: Profile) -> Self {
+        let builder = self
+            .with_version(profile.version())
+            .with_output_policy(profile.output_policy());
+
+        Self {
+            font_embedding: profile.font_embedding(),
+            image_compression: profile.image_compression(),
+            producer: profile.producer(),
+            ..builder
+        }
+    }
+
     /// Produce a configured PDF [`Document`].
     pub fn build(mut self) -> Document {
         let catalog_id = self.id_manager.create_id();
@@ -30,13 +170,61 @@ impl Builder {
             root_page_tree.set_page_size(rect);
         }
 
-        let catalog = Catalog::new(catalog_id, root_page_tree);
+        let mut catalog = Catalog::new(catalog_id, root_page_tree);
+
+        let producer = self.producer.map(|producer| match &self.sanitize {
+            Some(policy) => producer.sanitized(policy),
+            None => producer,
+        });
+        let (info, xmp_metadata) = match producer {
+            Some(producer) => {
+                let xmp_metadata = XmpMetadata::new(self.id_manager.create_id(), producer.clone());
+                catalog.set_metadata(xmp_metadata.obj_ref().clone());
+                let info = Info::new(self.id_manager.create_id(), producer);
 
-        Document {
+                (Some(info), Some(xmp_metadata))
+            }
+            None => (None, None),
+        };
+
+        let mut document = Document {
             catalog,
             id_manager: self.id_manager,
             pages: Vec::default(),
             fonts: Vec::default(),
+            watermark: None,
+            print_marks: None,
+            parts: None,
+            outline: None,
+            named_destinations: None,
+            form: None,
+            version: self.version,
+            font_embedding: self.font_embedding,
+            output_policy: self.output_policy,
+            file_id: self.file_id,
+            buffers: self.buffers,
+            info,
+            xmp_metadata,
+            forms: Vec::default(),
+            icc_profiles: Vec::default(),
+            cie_color_spaces: Vec::default(),
+            functions: Vec::default(),
+            cull_content: self.cull_content,
+            sanitize: self.sanitize,
+            roll: self.roll,
+            text_style: None,
+            image_compression: self.image_compression,
+            images: Vec::default(),
+        };
+
+        if let Some(text_style) = self.text_style {
+            let font_id = document.create_font(
+                b"Type1".to_vec(),
+                text_style.base_font().as_bytes().to_vec(),
+            );
+            document.text_style = Some((font_id, text_style));
         }
+
+        document
     }
 }
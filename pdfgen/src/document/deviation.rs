@@ -0,0 +1,96 @@
+//! Deviations from the PDF specification found while writing a [`Document`], which are either
+//! reported as errors, or fixed and collected into a [`WriteReport`] in lenient mode.
+//!
+//! [`Document`]: crate::Document
+
+use std::collections::BTreeMap;
+
+use crate::{Handle, types::hierarchy::primitives::rectangle::Rectangle};
+
+/// A single deviation from the PDF specification found while writing a [`Document`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[non_exhaustive]
+pub enum Deviation {
+    /// A name contains characters that are not allowed in a name, which are encoded with their
+    /// hexadecimal code in lenient mode.
+    #[error("Name '{name}' contains characters that are not allowed in a name.")]
+    InvalidName {
+        /// The name as it was given.
+        name: String,
+    },
+
+    /// The size of a page is outside of the range allowed by the specification, and it's clamped
+    /// into the range in lenient mode.
+    #[error("Size of page {page} is outside of the allowed range of 3 to 14400 units.")]
+    PageSizeOutOfRange {
+        /// Index of the page.
+        page: usize,
+
+        /// The media box of the page as it was given.
+        media_box: Rectangle,
+    },
+
+    /// A page has no media box and there is no default page size to inherit, so the A4 page size
+    /// is used. Only fails writing with [`OutputPolicy::with_strict_media_boxes`].
+    ///
+    /// [`OutputPolicy::with_strict_media_boxes`]: crate::OutputPolicy::with_strict_media_boxes
+    #[error("Page {page} has no media box.")]
+    MissingMediaBox {
+        /// Index of the page.
+        page: usize,
+    },
+}
+
+/// Collection of all [`Deviation`]s that were fixed while writing a [`Document`] in lenient
+/// mode, together with the sizes of the written objects.
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WriteReport {
+    deviations: Vec<Deviation>,
+
+    /// Number of bytes each written object takes up, by object number.
+    object_sizes: BTreeMap<u64, usize>,
+}
+
+impl WriteReport {
+    /// Adds a [`Deviation`] to this `WriteReport`.
+    pub(crate) fn push(&mut self, deviation: Deviation) {
+        self.deviations.push(deviation);
+    }
+
+    /// Returns all [`Deviation`]s that were fixed while writing.
+    pub fn deviations(&self) -> &[Deviation] {
+        &self.deviations
+    }
+
+    /// Sets the sizes of the written objects, by object number.
+    pub(crate) fn set_object_sizes(&mut self, object_sizes: BTreeMap<u64, usize>) {
+        self.object_sizes = object_sizes;
+    }
+
+    /// Returns the number of bytes the object referred to by the given [`Handle`] takes up in the
+    /// written PDF file, or `None` if the object wasn't written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::primitives::rectangle::Rectangle};
+    /// let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+    /// let font = document.create_font("Type1".into(), "Helvetica".into());
+    /// document.create_page();
+    ///
+    /// let report = document.write_lenient(&mut Vec::new()).unwrap();
+    /// assert!(report.object_size(&font).is_some_and(|size| size < 1_000));
+    /// ```
+    pub fn object_size(&self, handle: &impl Handle) -> Option<usize> {
+        self.object_sizes.get(&handle.object_number()).copied()
+    }
+
+    /// Returns `true` if the document was written without any [`Deviation`]s.
+    pub fn is_empty(&self) -> bool {
+        self.deviations.is_empty()
+    }
+}
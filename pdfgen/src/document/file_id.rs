@@ -0,0 +1,45 @@
+//! Identification of a PDF file by the `ID` entry of its trailer.
+
+use crate::types::hierarchy::cross_reference_table::CrossReferenceTable;
+
+/// The file identifier written as the `ID` entry of the trailer, which PDF processors use to tell
+/// whether two files are versions of the same document. The identifier is written as both the
+/// permanent and the changing part of the `ID`.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, FileId};
+/// // every regeneration of invoice 2025-0042 gets the same identifier
+/// let document = Document::builder()
+///     .with_file_id(FileId::Seed(b"invoice-2025-0042".to_vec()))
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum FileId {
+    /// The identifier is the MD5 hash of the byte offsets of the objects of the file, so it
+    /// changes whenever the size of any object changes.
+    #[default]
+    OffsetsHash,
+
+    /// The identifier is the MD5 hash of the given seed, e.g. the number of an invoice, so it
+    /// stays the same across regenerations of the same logical document.
+    Seed(Vec<u8>),
+
+    /// The given identifier is written as it is.
+    Fixed([u8; 16]),
+}
+
+impl FileId {
+    /// Returns the identifier of a file with the objects in the given [`CrossReferenceTable`].
+    pub(crate) fn compute(
+        &self,
+        cross_reference_table: &CrossReferenceTable,
+    ) -> Result<[u8; 16], std::io::Error> {
+        match self {
+            FileId::OffsetsHash => cross_reference_table.offsets_hash(),
+            FileId::Seed(seed) => Ok(*md5::compute(seed)),
+            FileId::Fixed(id) => Ok(*id),
+        }
+    }
+}
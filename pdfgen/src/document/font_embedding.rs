@@ -0,0 +1,94 @@
+//! Policies on embedding of fonts into a PDF [`Document`].
+//!
+//! [`Document`]: crate::Document
+
+use crate::types::hierarchy::primitives::font::Font;
+
+/// Possible errors that might be returned when the fonts of a [`Document`] violate its
+/// [`FontEmbedding`] policy.
+///
+/// [`Document`]: crate::Document
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum FontEmbeddingErr {
+    /// Indicates that a font is not embedded, although the policy requires it.
+    #[error("Font '{base_font}' is not embedded, but the font embedding policy requires it.")]
+    NotEmbedded {
+        /// Name of the font that is not embedded.
+        base_font: String,
+    },
+}
+
+/// Policy on which fonts have to be embedded into a [`Document`], checked when the document is
+/// written. Violating the policy fails writing with a [`FontEmbeddingErr`], so documents with
+/// licensing or conformance constraints never ship unembedded fonts by accident.
+///
+/// NOTE: Only composite fonts, which are available with the `composite-fonts` feature, are
+/// embedded, so besides them only the 14 standard Type 1 fonts, which every PDF processor provides,
+/// satisfy [`FontEmbedding::EmbedNonStandard`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontEmbedding {
+    /// Every font has to be embedded, as required e.g. by PDF/A.
+    EmbedAll,
+
+    /// Every font except for the 14 standard Type 1 fonts has to be embedded.
+    EmbedNonStandard,
+
+    /// Fonts are never embedded. Non-standard fonts are reported as
+    /// [`Issue::UnembeddedFont`] when the document is validated, as they might be substituted by
+    /// the PDF processor.
+    ///
+    /// [`Issue::UnembeddedFont`]: crate::validation::Issue::UnembeddedFont
+    NeverEmbed,
+}
+
+impl FontEmbedding {
+    /// Checks the given fonts against this policy, returning an error for the first font that
+    /// violates it.
+    pub(crate) fn check(self, fonts: &[Font]) -> Result<(), FontEmbeddingErr> {
+        let violation = fonts.iter().find(|font| match self {
+            FontEmbedding::EmbedAll => !font.is_embedded(),
+            FontEmbedding::EmbedNonStandard => !font.is_standard() && !font.is_embedded(),
+            FontEmbedding::NeverEmbed => false,
+        });
+
+        match violation {
+            Some(font) => Err(FontEmbeddingErr::NotEmbedded {
+                base_font: font.base_font(),
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IdManager, types::hierarchy::primitives::font::Font};
+
+    use super::{FontEmbedding, FontEmbeddingErr};
+
+    #[test]
+    fn font_embedding_policies() {
+        let mut id_manager = IdManager::new();
+        let fonts = [
+            Font::new(id_manager.create_id(), "Type1", "Helvetica"),
+            Font::new(id_manager.create_id(), "TrueType", "CorporateSans"),
+        ];
+
+        assert_eq!(
+            FontEmbedding::EmbedAll.check(&fonts[..1]),
+            Err(FontEmbeddingErr::NotEmbedded {
+                base_font: "Helvetica".to_string()
+            })
+        );
+        assert_eq!(FontEmbedding::EmbedNonStandard.check(&fonts[..1]), Ok(()));
+        assert_eq!(
+            FontEmbedding::EmbedNonStandard.check(&fonts),
+            Err(FontEmbeddingErr::NotEmbedded {
+                base_font: "CorporateSans".to_string()
+            })
+        );
+        assert_eq!(FontEmbedding::NeverEmbed.check(&fonts), Ok(()));
+    }
+}
@@ -0,0 +1,701 @@
+//! Fragments of content serialized into a compact binary form, so that content laid out once can
+//! be loaded into and drawn onto the pages of many other documents.
+
+use std::collections::HashMap;
+
+#[cfg(feature = "composite-fonts")]
+use crate::types::hierarchy::primitives::composite_font::{Addressing, CompositeFontErr};
+use crate::{
+    FontId, IdManager, ObjId,
+    types::hierarchy::{
+        content::page_form::PageForm,
+        primitives::{
+            encoding::FontEncoding,
+            font::Font,
+            identifier::Identifier,
+            object::Object,
+            rectangle::Rectangle,
+            resources::{ResourceEntry, Resources},
+        },
+    },
+};
+
+/// Errors that might be returned when creating, decoding or loading a [`Fragment`].
+#[derive(Debug, thiserror::Error)]
+pub enum FragmentErr {
+    /// Indicates that the recorded content uses a resource that can't be serialized into a
+    /// fragment, e.g. an image with a mask.
+    #[error("Fragment can't contain {0}")]
+    Unsupported(&'static str),
+
+    /// Indicates that the recorded content draws text with a font that wasn't created in the
+    /// document creating the fragment.
+    #[error("Recorded content draws text with a font that was not created in this document")]
+    UnknownFont,
+
+    /// Indicates that a resource of the recorded content could not be serialized, e.g. because
+    /// the samples of an image could not be read from its source.
+    #[error("Resource could not be serialized: {0}")]
+    Write(#[from] std::io::Error),
+
+    /// Indicates that the given bytes don't start with the header of a fragment of this version.
+    #[error("Bytes are not a fragment of this version")]
+    InvalidHeader,
+
+    /// Indicates that the given bytes end before the fragment is complete, or are otherwise not a
+    /// valid fragment.
+    #[error("Fragment is malformed: {0}")]
+    Malformed(&'static str),
+
+    /// Indicates that the embedded font program of a composite font in the fragment could not be
+    /// parsed.
+    #[cfg(feature = "composite-fonts")]
+    #[error(transparent)]
+    CompositeFont(#[from] CompositeFontErr),
+}
+
+/// Content drawn from a [`Recording`] into a form XObject, together with the fonts, images and
+/// graphics states it uses, created with [`Document::create_fragment`].
+///
+/// A `Fragment` is independent of the document that created it. It can be serialized with
+/// [`Fragment::to_bytes`], e.g. to store expensive layouts such as shaped legal boilerplate once,
+/// and loaded into any other document with [`Document::add_fragment`], which copies its fonts
+/// into the document.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{
+/// #     Document, Fragment,
+/// #     types::hierarchy::{
+/// #         content::{recording::Recording, text::Text},
+/// #         primitives::rectangle::{Position, Rectangle},
+/// #     },
+/// # };
+/// let mut assets = Document::default();
+/// let font_id = assets.create_font("Type1".into(), "Helvetica".into());
+///
+/// let mut disclaimer = Recording::new();
+/// let txt = Text::builder()
+///     .with_content("All rights reserved.")
+///     .at(Position::from_mm(0.0, 2.0))
+///     .build();
+/// disclaimer.add_text(txt, font_id);
+///
+/// let b_box = Rectangle::from_units(0.0, 0.0, 200.0, 20.0);
+/// let bytes = assets.create_fragment(b_box, &disclaimer).unwrap().to_bytes();
+///
+/// // later, in another process
+/// let mut document = Document::default();
+/// let fragment_id = document
+///     .add_fragment(&Fragment::from_bytes(&bytes).unwrap())
+///     .unwrap();
+/// for _ in 0..3 {
+///     let page = document.create_page();
+///     page.draw_fragment(&fragment_id, Position::from_mm(20.0, 10.0));
+/// }
+/// ```
+///
+/// [`Recording`]: crate::types::hierarchy::content::recording::Recording
+/// [`Document::create_fragment`]: crate::Document::create_fragment
+/// [`Document::add_fragment`]: crate::Document::add_fragment
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fragment {
+    /// Bounding box of the content in form space.
+    b_box: Rectangle,
+
+    /// Content stream drawing the recorded content.
+    content: Vec<u8>,
+
+    /// Fonts used by the content.
+    fonts: Vec<FragmentFont>,
+
+    /// Texts drawn by the content, together with the indices of the fonts they are drawn with.
+    texts: Vec<(usize, String)>,
+
+    /// Resources referred to by name in the content stream.
+    resources: Vec<FragmentResource>,
+}
+
+/// A font used by the content of a [`Fragment`].
+#[derive(Debug, Clone, PartialEq)]
+struct FragmentFont {
+    /// Subtype of the font.
+    subtype: Vec<u8>,
+
+    /// Name of the base font.
+    base_font: Vec<u8>,
+
+    /// Codes of the font's encoding that differ from WinAnsiEncoding, or `None` for the built-in
+    /// encoding of the font.
+    encoding: Option<Vec<(u8, Vec<u8>)>>,
+
+    /// Embedded TrueType font program of a composite font, and whether its glyphs are addressed
+    /// by their indices instead of by characters.
+    program: Option<(Vec<u8>, bool)>,
+}
+
+/// A resource referred to by name in the content stream of a [`Fragment`]. Images and graphics
+/// states are kept as the serialized bodies of their objects.
+#[derive(Debug, Clone, PartialEq)]
+enum FragmentResource {
+    Font { name: Vec<u8>, font: usize },
+    Image { name: Vec<u8>, body: Vec<u8> },
+    ExtGState { name: Vec<u8>, body: Vec<u8> },
+}
+
+impl Fragment {
+    /// Bytes every serialized fragment starts with, followed by the version of its format.
+    const MAGIC: &[u8] = b"PDFGENFRAG";
+
+    /// Version of the format of serialized fragments.
+    const VERSION: u8 = 1;
+
+    /// Creates a new `Fragment` with the given bounding box from the content stream, resources and
+    /// texts of a detached page, looking up the fonts they use in the given fonts of the document.
+    pub(crate) fn new(
+        b_box: Rectangle,
+        content: Vec<u8>,
+        resources: &Resources,
+        texts: &[(FontId, String)],
+        document_fonts: &[Font],
+    ) -> Result<Self, FragmentErr> {
+        if resources
+            .color_spaces()
+            .any(|cs| cs.resource_name().is_some())
+        {
+            return Err(FragmentErr::Unsupported(
+                "ICC-based or CIE-based colour spaces",
+            ));
+        }
+
+        let mut fonts = Vec::new();
+        let mut font_indices = HashMap::new();
+        let mut font_index = |id: &ObjId<Font>| -> Result<usize, FragmentErr> {
+            if let Some(idx) = font_indices.get(id) {
+                return Ok(*idx);
+            }
+
+            let font = document_fonts
+                .iter()
+                .find(|font| font.obj_ref() == id)
+                .ok_or(FragmentErr::UnknownFont)?;
+            fonts.push(FragmentFont::of(font));
+            font_indices.insert(id.clone(), fonts.len() - 1);
+
+            Ok(fonts.len() - 1)
+        };
+
+        let mut fragment_resources = Vec::with_capacity(resources.entries.len());
+        for entry in &resources.entries {
+            let resource = match entry {
+                ResourceEntry::Font { name, id } => FragmentResource::Font {
+                    name: name.as_bytes().to_vec(),
+                    font: font_index(id)?,
+                },
+                ResourceEntry::Image { name, image } => {
+                    if image.mask().is_some() {
+                        return Err(FragmentErr::Unsupported("images with masks"));
+                    }
+
+                    let mut body = Vec::new();
+                    image.write_content(&mut body)?;
+                    FragmentResource::Image {
+                        name: name.as_bytes().to_vec(),
+                        body,
+                    }
+                }
+                ResourceEntry::ExtGState { name, state } => {
+                    let mut body = Vec::new();
+                    state.write_content(&mut body)?;
+                    FragmentResource::ExtGState {
+                        name: name.as_bytes().to_vec(),
+                        body,
+                    }
+                }
+                ResourceEntry::SharedImage { .. } => {
+                    return Err(FragmentErr::Unsupported("images added to the document"));
+                }
+                _ => return Err(FragmentErr::Unsupported("nested forms")),
+            };
+            fragment_resources.push(resource);
+        }
+
+        let texts = texts
+            .iter()
+            .map(|(font_id, text)| Ok((font_index(&font_id.clone().into_obj_id())?, text.clone())))
+            .collect::<Result<_, FragmentErr>>()?;
+
+        Ok(Self {
+            b_box,
+            content,
+            fonts,
+            texts,
+            resources: fragment_resources,
+        })
+    }
+
+    /// Returns the bounding box of the content of this `Fragment`.
+    pub fn b_box(&self) -> Rectangle {
+        self.b_box
+    }
+
+    /// Serializes this `Fragment` into its compact binary form, which can be decoded with
+    /// [`Fragment::from_bytes`].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(self.content.len() + 64);
+        out.extend_from_slice(Self::MAGIC);
+        out.push(Self::VERSION);
+
+        let (low_left, top_right) = (self.b_box.low_left(), self.b_box.top_right());
+        for coord in [low_left.x, low_left.y, top_right.x, top_right.y] {
+            out.extend_from_slice(&coord.into_user_unit().to_le_bytes());
+        }
+        write_bytes(&mut out, &self.content);
+
+        write_len(&mut out, self.fonts.len());
+        for font in &self.fonts {
+            write_bytes(&mut out, &font.subtype);
+            write_bytes(&mut out, &font.base_font);
+
+            match &font.encoding {
+                Some(differences) => {
+                    out.push(1);
+                    write_len(&mut out, differences.len());
+                    for (code, glyph_name) in differences {
+                        out.push(*code);
+                        write_bytes(&mut out, glyph_name);
+                    }
+                }
+                None => out.push(0),
+            }
+
+            match &font.program {
+                Some((data, glyph_addressing)) => {
+                    out.push(1 + u8::from(*glyph_addressing));
+                    write_bytes(&mut out, data);
+                }
+                None => out.push(0),
+            }
+        }
+
+        write_len(&mut out, self.texts.len());
+        for (font, text) in &self.texts {
+            write_len(&mut out, *font);
+            write_bytes(&mut out, text.as_bytes());
+        }
+
+        write_len(&mut out, self.resources.len());
+        for resource in &self.resources {
+            match resource {
+                FragmentResource::Font { name, font } => {
+                    out.push(0);
+                    write_bytes(&mut out, name);
+                    write_len(&mut out, *font);
+                }
+                FragmentResource::Image { name, body } => {
+                    out.push(1);
+                    write_bytes(&mut out, name);
+                    write_bytes(&mut out, body);
+                }
+                FragmentResource::ExtGState { name, body } => {
+                    out.push(2);
+                    write_bytes(&mut out, name);
+                    write_bytes(&mut out, body);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Decodes a `Fragment` serialized with [`Fragment::to_bytes`]. Fails with a [`FragmentErr`]
+    /// if the bytes are not a fragment of this version, or if they are malformed.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FragmentErr> {
+        let bytes = bytes
+            .strip_prefix(Self::MAGIC)
+            .and_then(|bytes| bytes.strip_prefix(&[Self::VERSION]))
+            .ok_or(FragmentErr::InvalidHeader)?;
+        let mut reader = Reader { bytes };
+
+        let mut coords = [0.0; 4];
+        for coord in &mut coords {
+            *coord = f32::from_le_bytes(reader.take(4)?.try_into().unwrap());
+        }
+        let [ll_x, ll_y, tr_x, tr_y] = coords;
+        let b_box = Rectangle::from_units(ll_x, ll_y, tr_x, tr_y);
+        let content = reader.bytes()?.to_vec();
+
+        let mut fonts = Vec::new();
+        for _ in 0..reader.len()? {
+            let subtype = reader.bytes()?.to_vec();
+            let base_font = reader.bytes()?.to_vec();
+
+            let encoding = match reader.byte()? {
+                0 => None,
+                1 => {
+                    let mut differences = Vec::new();
+                    for _ in 0..reader.len()? {
+                        differences.push((reader.byte()?, reader.bytes()?.to_vec()));
+                    }
+                    Some(differences)
+                }
+                _ => return Err(FragmentErr::Malformed("unknown font encoding")),
+            };
+
+            let program = match reader.byte()? {
+                0 => None,
+                kind @ (1 | 2) => Some((reader.bytes()?.to_vec(), kind == 2)),
+                _ => return Err(FragmentErr::Malformed("unknown font program")),
+            };
+
+            fonts.push(FragmentFont {
+                subtype,
+                base_font,
+                encoding,
+                program,
+            });
+        }
+
+        let font_index = |idx: usize| match idx < fonts.len() {
+            true => Ok(idx),
+            false => Err(FragmentErr::Malformed("font index out of bounds")),
+        };
+
+        let mut texts = Vec::new();
+        for _ in 0..reader.len()? {
+            let font = font_index(reader.len()?)?;
+            let text = String::from_utf8(reader.bytes()?.to_vec())
+                .map_err(|_| FragmentErr::Malformed("text is not UTF-8"))?;
+            texts.push((font, text));
+        }
+
+        let mut resources = Vec::new();
+        for _ in 0..reader.len()? {
+            let kind = reader.byte()?;
+            let name = reader.bytes()?.to_vec();
+            let resource = match kind {
+                0 => FragmentResource::Font {
+                    name,
+                    font: font_index(reader.len()?)?,
+                },
+                1 => FragmentResource::Image {
+                    name,
+                    body: reader.bytes()?.to_vec(),
+                },
+                2 => FragmentResource::ExtGState {
+                    name,
+                    body: reader.bytes()?.to_vec(),
+                },
+                _ => return Err(FragmentErr::Malformed("unknown resource")),
+            };
+            resources.push(resource);
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(FragmentErr::Malformed("trailing bytes"));
+        }
+
+        Ok(Self {
+            b_box,
+            content,
+            fonts,
+            texts,
+            resources,
+        })
+    }
+
+    /// Loads this `Fragment` into a document as a [`PageForm`] with the given [`ObjId`], creating
+    /// copies of its fonts with ids allocated by the given [`IdManager`] and adding them to the
+    /// given fonts of the document.
+    pub(crate) fn load(
+        &self,
+        id: ObjId<PageForm>,
+        id_manager: &mut IdManager,
+        document_fonts: &mut Vec<Font>,
+    ) -> Result<PageForm, FragmentErr> {
+        let mut font_ids = Vec::with_capacity(self.fonts.len());
+        for font in &self.fonts {
+            let font = font.load(id_manager)?;
+            font_ids.push(FontId::new(&font));
+            document_fonts.push(font);
+        }
+
+        let mut resources = Resources::default();
+        for resource in &self.resources {
+            let entry = match resource {
+                FragmentResource::Font { name, font } => ResourceEntry::Font {
+                    name: Identifier::new(name.clone()),
+                    id: font_ids[*font].clone().into_obj_id(),
+                },
+                FragmentResource::Image { name, body } => ResourceEntry::SerializedImage {
+                    name: Identifier::new(name.clone()),
+                    body: body.clone(),
+                },
+                FragmentResource::ExtGState { name, body } => ResourceEntry::SerializedExtGState {
+                    name: Identifier::new(name.clone()),
+                    body: body.clone(),
+                },
+            };
+            resources.entries.push(entry);
+        }
+
+        let texts = self
+            .texts
+            .iter()
+            .map(|(font, text)| (font_ids[*font].clone(), text.clone()))
+            .collect();
+
+        Ok(PageForm::new(
+            id,
+            self.b_box,
+            resources,
+            self.content.clone(),
+            texts,
+        ))
+    }
+}
+
+impl FragmentFont {
+    /// Creates a new `FragmentFont` describing the given [`Font`].
+    fn of(font: &Font) -> Self {
+        let encoding = font.encoding().map(|encoding| {
+            encoding
+                .differences()
+                .map(|(code, glyph_name)| (code, glyph_name.to_vec()))
+                .collect()
+        });
+
+        #[cfg(feature = "composite-fonts")]
+        let program = font
+            .program()
+            .map(|(data, addressing)| (data.to_vec(), addressing == Addressing::Glyph));
+        #[cfg(not(feature = "composite-fonts"))]
+        let program = None;
+
+        Self {
+            subtype: font.subtype().to_vec(),
+            base_font: font.base_font().into_bytes(),
+            encoding,
+            program,
+        }
+    }
+
+    /// Creates the [`Font`] described by this `FragmentFont`, with ids allocated by the given
+    /// [`IdManager`].
+    fn load(&self, id_manager: &mut IdManager) -> Result<Font, FragmentErr> {
+        let id = id_manager.create_id();
+
+        let mut font = match &self.program {
+            None => Font::new(id, self.subtype.clone(), self.base_font.clone()),
+            #[cfg(feature = "composite-fonts")]
+            Some((data, glyph_addressing)) => {
+                let addressing = match glyph_addressing {
+                    true => Addressing::Glyph,
+                    false => Addressing::Unicode,
+                };
+                Font::composite(
+                    id,
+                    self.base_font.clone(),
+                    data.clone(),
+                    addressing,
+                    id_manager,
+                )?
+            }
+            #[cfg(not(feature = "composite-fonts"))]
+            Some(_) => return Err(FragmentErr::Unsupported("composite fonts")),
+        };
+
+        if let Some(differences) = &self.encoding {
+            let encoding = differences
+                .iter()
+                .fold(FontEncoding::win_ansi(), |encoding, (code, glyph_name)| {
+                    encoding.with_difference(*code, glyph_name.clone())
+                });
+            font.set_encoding(encoding);
+        }
+
+        Ok(font)
+    }
+}
+
+/// Writes the given length as 4 little-endian bytes.
+fn write_len(out: &mut Vec<u8>, len: usize) {
+    let len = u32::try_from(len).expect("Fragments are smaller than 4 GiB.");
+    out.extend_from_slice(&len.to_le_bytes());
+}
+
+/// Writes the given bytes prefixed with their length.
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    write_len(out, bytes.len());
+    out.extend_from_slice(bytes);
+}
+
+/// Reads the parts of a serialized [`Fragment`] from the front of its bytes.
+struct Reader<'bytes> {
+    bytes: &'bytes [u8],
+}
+
+impl<'bytes> Reader<'bytes> {
+    /// Takes the given number of bytes.
+    fn take(&mut self, count: usize) -> Result<&'bytes [u8], FragmentErr> {
+        if self.bytes.len() < count {
+            return Err(FragmentErr::Malformed("unexpected end of bytes"));
+        }
+
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    /// Takes a single byte.
+    fn byte(&mut self) -> Result<u8, FragmentErr> {
+        Ok(self.take(1)?[0])
+    }
+
+    /// Takes a length written by [`write_len`].
+    fn len(&mut self) -> Result<usize, FragmentErr> {
+        let len = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+        Ok(len as usize)
+    }
+
+    /// Takes bytes written by [`write_bytes`].
+    fn bytes(&mut self) -> Result<&'bytes [u8], FragmentErr> {
+        let len = self.len()?;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Document,
+        types::hierarchy::{
+            content::{
+                color::{Color, ColorSpace},
+                recording::Recording,
+                text::Text,
+            },
+            primitives::{
+                encoding::FontEncoding,
+                rectangle::{Position, Rectangle},
+            },
+        },
+    };
+
+    use super::{Fragment, FragmentErr};
+
+    fn recording(document: &mut Document) -> Recording {
+        let font_id = document.create_font("Type1".into(), "Times-Roman".into());
+        let mut content = Recording::new();
+        let txt = Text::builder()
+            .with_content("Terms apply")
+            .at(Position::from_units(2.0, 4.0))
+            .build();
+        content.add_text(txt, font_id);
+        content.fill_path(
+            Rectangle::from_units(0.0, 0.0, 50.0, 2.0),
+            Color::Rgb {
+                red: 0,
+                green: 0,
+                blue: 255,
+            },
+        );
+
+        content
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut document = Document::default();
+        let content = recording(&mut document);
+        let b_box = Rectangle::from_units(0.0, 0.0, 50.0, 20.0);
+        let fragment = document.create_fragment(b_box, &content).unwrap();
+
+        let bytes = fragment.to_bytes();
+        assert_eq!(Fragment::from_bytes(&bytes).unwrap(), fragment);
+        assert_eq!(fragment.b_box(), b_box);
+    }
+
+    #[test]
+    fn font_encoding() {
+        let mut source = Document::default();
+        let font_id = source.create_font("Type1".into(), "Helvetica".into());
+        source
+            .font_mut(&font_id)
+            .unwrap()
+            .set_encoding(FontEncoding::win_ansi().with_difference(128, "Euro"));
+        let mut content = Recording::new();
+        let txt = Text::builder()
+            .with_content("5 €")
+            .at(Position::from_units(0.0, 0.0))
+            .build();
+        content.add_text(txt, font_id);
+
+        let b_box = Rectangle::from_units(0.0, 0.0, 50.0, 20.0);
+        let fragment = source.create_fragment(b_box, &content).unwrap();
+        let fragment = Fragment::from_bytes(&fragment.to_bytes()).unwrap();
+
+        let mut target = Document::builder().with_page_size(Rectangle::A6).build();
+        let fragment_id = target.add_fragment(&fragment).unwrap();
+        target
+            .create_page()
+            .draw_fragment(&fragment_id, Position::from_units(0.0, 0.0));
+
+        let mut written = Vec::new();
+        target.write(&mut written).unwrap();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("/BaseFont /Helvetica"));
+        assert!(written.contains("/Differences [128 /Euro ]"));
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let mut document = Document::default();
+        let content = recording(&mut document);
+        let b_box = Rectangle::from_units(0.0, 0.0, 50.0, 20.0);
+        let bytes = document
+            .create_fragment(b_box, &content)
+            .unwrap()
+            .to_bytes();
+
+        assert!(matches!(
+            Fragment::from_bytes(b"%PDF-1.7"),
+            Err(FragmentErr::InvalidHeader)
+        ));
+        assert!(matches!(
+            Fragment::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(FragmentErr::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn unknown_font() {
+        let mut other = Document::default();
+        let content = recording(&mut other);
+
+        let document = Document::default();
+        let b_box = Rectangle::from_units(0.0, 0.0, 50.0, 20.0);
+        assert!(matches!(
+            document.create_fragment(b_box, &content),
+            Err(FragmentErr::UnknownFont)
+        ));
+    }
+
+    #[test]
+    fn named_color_spaces() {
+        let mut document = Document::default();
+        let profile = document.create_icc_profile(b"gray".to_vec(), ColorSpace::DeviceGray);
+        let mut content = Recording::new();
+        content.fill_path(
+            Rectangle::from_units(0.0, 0.0, 50.0, 2.0),
+            Color::icc(profile, &[128]),
+        );
+
+        let b_box = Rectangle::from_units(0.0, 0.0, 50.0, 20.0);
+        assert!(matches!(
+            document.create_fragment(b_box, &content),
+            Err(FragmentErr::Unsupported(_))
+        ));
+    }
+}
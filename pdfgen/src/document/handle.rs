@@ -0,0 +1,424 @@
+//! Public handles referring to objects inside of a PDF [`Document`].
+//!
+//! [`Document`]: crate::Document
+
+use std::{
+    cmp::Ordering,
+    hash::{Hash, Hasher},
+    io::{Error, Write},
+};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    ObjId,
+    types::hierarchy::{
+        content::{
+            color::{CieColorSpaceObject, CieFamily, Color, IccProfile},
+            graphics_state::ExtGState,
+            image::{Image, ImageTransform},
+            page_form::PageForm,
+        },
+        page::Page,
+        primitives::{
+            encoding::ShowEncoding, font::Font, font_metrics::FontMetrics,
+            function::FunctionObject, identifier::Identifier,
+        },
+    },
+};
+
+/// Common behavior of all handles referring to objects inside of a [`Document`].
+///
+/// [`Document`]: crate::Document
+pub trait Handle {
+    /// Returns the number of the indirect object this handle refers to in the written PDF file.
+    fn object_number(&self) -> u64;
+}
+
+/// Generates a public handle type wrapping an [`ObjId`] of the given object type, so the internal
+/// representation of ids is not exposed to users.
+macro_rules! handles {
+    ($($(#[$attr:meta])* $name:ident => $target:ty;)+) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+            pub struct $name(ObjId<$target>);
+
+            impl $name {
+                /// Creates a new handle referring to the object with the given [`ObjId`].
+                pub(crate) fn new(id: ObjId<$target>) -> Self {
+                    Self(id)
+                }
+
+                /// Consumes this handle, returning the [`ObjId`] of the object it refers to.
+                pub(crate) fn into_obj_id(self) -> ObjId<$target> {
+                    self.0
+                }
+            }
+
+            impl Handle for $name {
+                fn object_number(&self) -> u64 {
+                    self.0.number()
+                }
+            }
+        )+
+    };
+}
+
+/// Handle of a [`Font`] created with [`Document::create_font`], used to draw text with the font.
+///
+/// [`Document::create_font`]: crate::Document::create_font
+#[derive(Debug, Clone)]
+pub struct FontId {
+    /// ID of the font this handle refers to.
+    id: ObjId<Font>,
+
+    /// Encoding of the strings shown with the font, which is known when text is drawn, before the
+    /// font itself is written.
+    encoding: ShowEncoding,
+
+    /// Metrics of the font, used to lay out text when it's drawn, before the font itself is
+    /// written.
+    metrics: FontMetrics,
+}
+
+impl FontId {
+    /// Creates a new handle referring to the given [`Font`].
+    pub(crate) fn new(font: &Font) -> Self {
+        let encoding = match font.is_composite() {
+            true => ShowEncoding::Identity,
+            false => ShowEncoding::WinAnsi,
+        };
+
+        Self {
+            id: font.obj_ref().clone(),
+            encoding,
+            metrics: font.metrics(),
+        }
+    }
+
+    /// Returns a handle of the same kind of font, referring to the font with the given [`ObjId`].
+    pub(crate) fn with_id(&self, id: ObjId<Font>) -> Self {
+        Self { id, ..self.clone() }
+    }
+
+    /// Returns the [`ShowEncoding`] of the strings shown with the font.
+    pub(crate) fn encoding(&self) -> ShowEncoding {
+        self.encoding
+    }
+
+    /// Returns the [`FontMetrics`] measuring text drawn with the font.
+    pub(crate) fn metrics(&self) -> &FontMetrics {
+        &self.metrics
+    }
+
+    /// Consumes this handle, returning the [`ObjId`] of the font it refers to.
+    pub(crate) fn into_obj_id(self) -> ObjId<Font> {
+        self.id
+    }
+}
+
+// handles of the same font are equal, as the encoding and the metrics are determined by the font
+
+impl PartialEq for FontId {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for FontId {}
+
+impl PartialOrd for FontId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for FontId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl Hash for FontId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Handle for FontId {
+    fn object_number(&self) -> u64 {
+        self.id.number()
+    }
+}
+
+/// Handle of an [`Image`] added to a [`Document`] with [`Document::add_image`], used to draw the
+/// image onto any number of pages with [`Page::draw_image`], while it's only written once.
+///
+/// [`Document`]: crate::Document
+/// [`Document::add_image`]: crate::Document::add_image
+#[derive(Debug, Clone)]
+pub struct ImageId {
+    /// ID of the image this handle refers to.
+    id: ObjId<Image>,
+
+    /// Transformation the image was built with, applied whenever it's drawn.
+    transform: ImageTransform,
+
+    /// Color a stencil mask is painted with, set whenever it's drawn.
+    stencil_color: Option<Color>,
+
+    /// Whether the image has a soft mask, so that pages drawing it use transparency.
+    soft_mask: bool,
+
+    /// Graphics state parameters applied whenever the image is drawn.
+    ext_g_state: Option<ExtGState>,
+}
+
+impl ImageId {
+    /// Creates a new handle referring to the given [`Image`] with the given [`ObjId`], drawn with
+    /// the given graphics state parameters taken out of the image.
+    pub(crate) fn new(id: ObjId<Image>, image: &Image, ext_g_state: Option<ExtGState>) -> Self {
+        Self {
+            id,
+            transform: image.transform(),
+            stencil_color: image.stencil_color(),
+            soft_mask: image.soft_mask().is_some(),
+            ext_g_state,
+        }
+    }
+
+    /// Returns the [`ImageTransform`] the image is drawn with.
+    pub(crate) fn transform(&self) -> ImageTransform {
+        self.transform
+    }
+
+    /// Returns the color a stencil mask is painted with, or `None` if the image isn't a stencil
+    /// mask.
+    pub(crate) fn stencil_color(&self) -> Option<Color> {
+        self.stencil_color
+    }
+
+    /// Returns `true` if the image has a soft mask.
+    pub(crate) fn has_soft_mask(&self) -> bool {
+        self.soft_mask
+    }
+
+    /// Returns the graphics state parameters the image is drawn with.
+    pub(crate) fn ext_g_state(&self) -> Option<&ExtGState> {
+        self.ext_g_state.as_ref()
+    }
+
+    /// Returns the [`ObjId`] of the image this handle refers to.
+    pub(crate) fn obj_id(&self) -> &ObjId<Image> {
+        &self.id
+    }
+}
+
+// handles of the same image are equal, as everything else is determined by the image
+
+impl PartialEq for ImageId {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for ImageId {}
+
+impl PartialOrd for ImageId {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ImageId {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl Hash for ImageId {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+impl Handle for ImageId {
+    fn object_number(&self) -> u64 {
+        self.id.number()
+    }
+}
+
+/// Handle of a named [`Destination`] added with [`Document::add_named_destination`], used to
+/// refer to the destination by its name, e.g. as the destination of an [`OutlineItem`]. Named
+/// destinations are entries of the document's name dictionary rather than objects of their own,
+/// so this handle doesn't implement [`Handle`].
+///
+/// [`Destination`]: crate::types::hierarchy::destination::Destination
+/// [`Document::add_named_destination`]: crate::Document::add_named_destination
+/// [`OutlineItem`]: crate::types::hierarchy::outline::OutlineItem
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DestinationId(String);
+
+impl DestinationId {
+    /// Creates a new handle referring to the named destination with the given name.
+    pub(crate) fn new(name: String) -> Self {
+        Self(name)
+    }
+
+    /// Returns the name of the destination this handle refers to.
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    /// Consumes this handle, returning the name of the destination it refers to.
+    pub(crate) fn into_name(self) -> String {
+        self.0
+    }
+}
+
+/// Handle of an [`IccProfile`] created with [`Document::create_icc_profile`], used to draw colors
+/// and images in the ICCBased colour space of the profile.
+///
+/// [`Document::create_icc_profile`]: crate::Document::create_icc_profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct IccProfileId {
+    /// Number of the object of the profile this handle refers to. Only the number is stored, so
+    /// the handle can be copied together with the colors referring to it.
+    number: u64,
+
+    /// Number of colour components of the profile.
+    components: u8,
+}
+
+impl IccProfileId {
+    const_identifiers! {
+        ICC_BASED: b"ICCBased",
+    }
+
+    /// Creates a new handle referring to the profile with the given [`ObjId`] and number of colour
+    /// components.
+    pub(crate) fn new(id: &ObjId<IccProfile>, components: u8) -> Self {
+        Self {
+            number: id.number(),
+            components,
+        }
+    }
+
+    /// Returns a handle referring to the profile with the given [`ObjId`], with the same number of
+    /// colour components.
+    pub(crate) fn with_id(&self, id: &ObjId<IccProfile>) -> Self {
+        Self::new(id, self.components)
+    }
+
+    /// Returns the [`ObjId`] of the profile this handle refers to.
+    pub(crate) fn obj_id(&self) -> ObjId<IccProfile> {
+        ObjId::from_number(self.number)
+    }
+
+    /// Returns the number of colour components of the profile.
+    pub fn components(&self) -> usize {
+        usize::from(self.components)
+    }
+
+    /// Returns the name the ICCBased colour space of the profile is referred to by in content
+    /// streams.
+    pub(crate) fn resource_name(&self) -> Identifier<Vec<u8>> {
+        Identifier::new(format!("ICC{}", self.number).into_bytes())
+    }
+
+    /// Writes the ICCBased colour space array of the profile, e.g. `[/ICCBased 7 0 R]`.
+    pub(crate) fn write_color_space(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"["),
+            Self::ICC_BASED.write(writer),
+            self.obj_id().write_ref(writer),
+            writer.write(b"] "),
+        })
+    }
+}
+
+impl Handle for IccProfileId {
+    fn object_number(&self) -> u64 {
+        self.number
+    }
+}
+
+/// Handle of a [`CieColorSpace`] created with [`Document::create_cie_color_space`], used to draw
+/// colors and images in the colour space.
+///
+/// [`CieColorSpace`]: crate::types::hierarchy::content::color::CieColorSpace
+/// [`Document::create_cie_color_space`]: crate::Document::create_cie_color_space
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CieColorSpaceId {
+    /// Number of the object of the colour space this handle refers to. Only the number is stored,
+    /// so the handle can be copied together with the colors referring to it.
+    number: u64,
+
+    /// Family of the colour space.
+    family: CieFamily,
+}
+
+impl CieColorSpaceId {
+    /// Creates a new handle referring to the colour space with the given [`ObjId`] and family.
+    pub(crate) fn new(id: &ObjId<CieColorSpaceObject>, family: CieFamily) -> Self {
+        Self {
+            number: id.number(),
+            family,
+        }
+    }
+
+    /// Returns a handle referring to the colour space with the given [`ObjId`], of the same
+    /// family.
+    pub(crate) fn with_id(&self, id: &ObjId<CieColorSpaceObject>) -> Self {
+        Self::new(id, self.family)
+    }
+
+    /// Returns the [`ObjId`] of the colour space this handle refers to.
+    pub(crate) fn obj_id(&self) -> ObjId<CieColorSpaceObject> {
+        ObjId::from_number(self.number)
+    }
+
+    /// Returns the family of the colour space.
+    pub(crate) fn family(&self) -> CieFamily {
+        self.family
+    }
+
+    /// Returns the number of colour components of the colour space.
+    pub fn components(&self) -> usize {
+        self.family.components()
+    }
+
+    /// Returns the name the colour space is referred to by in content streams.
+    pub(crate) fn resource_name(&self) -> Identifier<Vec<u8>> {
+        Identifier::new(format!("CS{}", self.number).into_bytes())
+    }
+}
+
+impl Handle for CieColorSpaceId {
+    fn object_number(&self) -> u64 {
+        self.number
+    }
+}
+
+handles! {
+    /// Handle of a [`Page`] inside of a [`Document`].
+    ///
+    /// [`Document`]: crate::Document
+    PageId => Page;
+
+    /// Handle of a [`PdfFunction`] created with [`Document::create_function`], used by other
+    /// objects referring to the function.
+    ///
+    /// [`PdfFunction`]: crate::types::hierarchy::primitives::function::PdfFunction
+    /// [`Document::create_function`]: crate::Document::create_function
+    FunctionId => FunctionObject;
+
+    /// Handle of a [`Fragment`] loaded into a [`Document`] with [`Document::add_fragment`], used
+    /// to draw the fragment onto its pages with [`Page::draw_fragment`].
+    ///
+    /// [`Fragment`]: crate::Fragment
+    /// [`Document`]: crate::Document
+    /// [`Document::add_fragment`]: crate::Document::add_fragment
+    FragmentId => PageForm;
+}
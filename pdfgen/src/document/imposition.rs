@@ -0,0 +1,254 @@
+//! Imposition of the pages of a [`Document`] onto larger sheets, e.g. to print booklets or proofs.
+//!
+//! [`Document`]: crate::Document
+
+use crate::types::hierarchy::primitives::{
+    rectangle::{Position, Rectangle},
+    unit::Unit,
+};
+
+/// Order in which the pages of a [`Document`] are placed onto the sheets of an [`Imposition`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PageOrder {
+    /// Pages are placed in reading order, filling the cells of each sheet row by row.
+    Sequential,
+
+    /// Pages are placed in the order of a saddle-stitched booklet, so that folding the printed
+    /// sheets in the middle and nesting them yields the pages in reading order.
+    Booklet,
+}
+
+/// Layout of the pages of a [`Document`] on the sheets of an imposed document, created with
+/// [`Document::impose`]. Every sheet is divided into a grid of equally sized cells, and each page
+/// is scaled to fit its cell, centered in it. Pages whose orientation doesn't match the orientation
+/// of the cells are rotated by 90 degrees, so they fill the cells as much as possible.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, Imposition, types::hierarchy::primitives::rectangle::Rectangle};
+/// let mut document = Document::builder().with_page_size(Rectangle::A5).build();
+/// for _ in 0..8 {
+///     document.create_page();
+/// }
+///
+/// // A5 pages on A4 sheets, to be folded into a booklet
+/// let booklet = document.impose(&Imposition::booklet(Rectangle::A4));
+///
+/// // 4 pages on every A4 sheet, in reading order
+/// let proofs = document.impose(&Imposition::n_up(Rectangle::A4, 2, 2));
+/// ```
+///
+/// [`Document`]: crate::Document
+/// [`Document::impose`]: crate::Document::impose
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Imposition {
+    /// Size of the sheets, used as the media box of the pages of the imposed document.
+    sheet_size: Rectangle,
+
+    /// Number of cells in each row of a sheet.
+    columns: usize,
+
+    /// Number of rows of cells on a sheet.
+    rows: usize,
+
+    /// Order in which the pages are placed onto the cells.
+    order: PageOrder,
+}
+
+impl Imposition {
+    /// Creates a new `Imposition` placing `columns * rows` pages onto every sheet of the given
+    /// size, in reading order. A zero number of columns or rows is treated as 1.
+    pub fn n_up(sheet_size: Rectangle, columns: usize, rows: usize) -> Self {
+        Self {
+            sheet_size,
+            columns: columns.max(1),
+            rows: rows.max(1),
+            order: PageOrder::Sequential,
+        }
+    }
+
+    /// Creates a new `Imposition` placing two pages side by side onto every side of the sheets of
+    /// the given size, in the order of a saddle-stitched booklet. Sheets are divided along their
+    /// longer edge, and the page count is padded with blank pages to a multiple of 4, so every
+    /// sheet is printed on both sides.
+    pub fn booklet(sheet_size: Rectangle) -> Self {
+        let (columns, rows) = if sheet_size.width() >= sheet_size.height() {
+            (2, 1)
+        } else {
+            (1, 2)
+        };
+
+        Self {
+            sheet_size,
+            columns,
+            rows,
+            order: PageOrder::Booklet,
+        }
+    }
+
+    /// Returns the size of the sheets of this `Imposition`.
+    pub(crate) fn sheet_size(&self) -> Rectangle {
+        self.sheet_size
+    }
+
+    /// Returns the indices of the pages placed onto each cell of every sheet, for a document with
+    /// the given number of pages. Cells without a page are `None`.
+    pub(crate) fn sheets(&self, page_count: usize) -> Vec<Vec<Option<usize>>> {
+        let cells = self.columns * self.rows;
+        let page = |idx: usize| (idx < page_count).then_some(idx);
+
+        match self.order {
+            PageOrder::Sequential => (0..page_count.div_ceil(cells))
+                .map(|sheet| (0..cells).map(|cell| page(sheet * cells + cell)).collect())
+                .collect(),
+            PageOrder::Booklet => {
+                let padded = page_count.div_ceil(4) * 4;
+
+                // the front of each sheet has the outer pair of pages, and the back the inner one
+                (0..padded / 2)
+                    .map(|side| {
+                        let (outer, inner) = (padded - 1 - side, side);
+                        match side % 2 {
+                            0 => vec![page(outer), page(inner)],
+                            _ => vec![page(inner), page(outer)],
+                        }
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Returns the cells of a sheet row by row, starting at the top left corner.
+    pub(crate) fn cells(&self) -> Vec<Rectangle> {
+        let low_left = self.sheet_size.low_left();
+        let (left, bottom) = (low_left.x.into_points(), low_left.y.into_points());
+        let width = self.sheet_size.width().into_points() / self.columns as f64;
+        let height = self.sheet_size.height().into_points() / self.rows as f64;
+
+        (0..self.rows)
+            .rev()
+            .flat_map(|row| (0..self.columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let (x, y) = (left + column as f64 * width, bottom + row as f64 * height);
+                Rectangle::new(
+                    Position::new(Unit::from_pt(x), Unit::from_pt(y)),
+                    Position::new(Unit::from_pt(x + width), Unit::from_pt(y + height)),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns the transformation matrix `[a b c d e f]` placing content with the given bounding
+    /// box into the given cell, scaled to fit and centered, and rotated counterclockwise by 90
+    /// degrees if its orientation differs from the orientation of the cell.
+    pub(crate) fn placement(cell: Rectangle, b_box: Rectangle) -> [f64; 6] {
+        // round to avoid writing values such as `0.30000000000000004`
+        let round = |value: f64| (value * 100_000.0).round() / 100_000.0 + 0.0;
+
+        let (cell_x, cell_y) = (
+            cell.low_left().x.into_points(),
+            cell.low_left().y.into_points(),
+        );
+        let (cell_width, cell_height) = (cell.width().into_points(), cell.height().into_points());
+        let (x, y) = (
+            b_box.low_left().x.into_points(),
+            b_box.low_left().y.into_points(),
+        );
+        let (width, height) = (b_box.width().into_points(), b_box.height().into_points());
+
+        let rotate = (width > height && cell_width < cell_height)
+            || (width < height && cell_width > cell_height);
+
+        let matrix = if rotate {
+            let scale = (cell_width / height).min(cell_height / width);
+            let offset_x = cell_x + (cell_width - height * scale) / 2.0;
+            let offset_y = cell_y + (cell_height - width * scale) / 2.0;
+
+            [
+                0.0,
+                scale,
+                -scale,
+                0.0,
+                offset_x + (y + height) * scale,
+                offset_y - x * scale,
+            ]
+        } else {
+            let scale = (cell_width / width).min(cell_height / height);
+            let offset_x = cell_x + (cell_width - width * scale) / 2.0;
+            let offset_y = cell_y + (cell_height - height * scale) / 2.0;
+
+            [
+                scale,
+                0.0,
+                0.0,
+                scale,
+                offset_x - x * scale,
+                offset_y - y * scale,
+            ]
+        };
+
+        matrix.map(round)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::hierarchy::primitives::rectangle::Rectangle;
+
+    use super::Imposition;
+
+    #[test]
+    fn booklet_order() {
+        let imposition = Imposition::booklet(Rectangle::from_units(0.0, 0.0, 800.0, 600.0));
+
+        assert_eq!(
+            imposition.sheets(6),
+            [
+                [None, Some(0)],
+                [Some(1), None],
+                [Some(5), Some(2)],
+                [Some(3), Some(4)],
+            ]
+        );
+    }
+
+    #[test]
+    fn n_up_order() {
+        let imposition = Imposition::n_up(Rectangle::from_units(0.0, 0.0, 800.0, 600.0), 2, 2);
+
+        assert_eq!(
+            imposition.sheets(5),
+            [
+                vec![Some(0), Some(1), Some(2), Some(3)],
+                vec![Some(4), None, None, None],
+            ]
+        );
+        assert_eq!(
+            imposition.cells()[..2],
+            [
+                Rectangle::from_units(0.0, 300.0, 400.0, 600.0),
+                Rectangle::from_units(400.0, 300.0, 800.0, 600.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn placement() {
+        let cell = Rectangle::from_units(400.0, 0.0, 800.0, 600.0);
+
+        // portrait page into a portrait cell, scaled to fit the height
+        assert_eq!(
+            Imposition::placement(cell, Rectangle::from_units(0.0, 0.0, 200.0, 400.0)),
+            [1.5, 0.0, 0.0, 1.5, 450.0, 0.0]
+        );
+
+        // landscape page into a portrait cell, rotated to fit the height
+        assert_eq!(
+            Imposition::placement(cell, Rectangle::from_units(0.0, 0.0, 600.0, 200.0)),
+            [0.0, 1.0, -1.0, 0.0, 700.0, 0.0]
+        );
+    }
+}
@@ -0,0 +1,244 @@
+//! Layout of sheets of adhesive labels, e.g. to print address labels or name badges in batches.
+
+use crate::types::hierarchy::primitives::{
+    rectangle::{Position, Rectangle},
+    unit::Unit,
+};
+
+/// Layout of a sheet of equally sized labels arranged in a grid, used with [`Document::fill_labels`]
+/// to draw a template once for every record of a batch, e.g. an address per label. The grid starts
+/// at the top left corner of the sheet, offset by the top and left margins, and its labels are
+/// separated by gutters. Labels are filled row by row, starting with a new sheet when one is full.
+///
+/// Common label sheets are available as presets, such as [`LabelSheet::avery_l7160`], and other
+/// sheets can be described with the measurements printed on their packaging.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{
+/// #     Document, LabelSheet,
+/// #     types::hierarchy::{
+/// #         content::text::Text,
+/// #         primitives::{rectangle::Rectangle, unit::Unit},
+/// #     },
+/// # };
+/// let mut document = Document::default();
+/// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+///
+/// // 2 columns of 8 labels on an A4 sheet
+/// let sheet = LabelSheet::new(Rectangle::A4, 2, 8)
+///     .with_label_size(Unit::from_mm(99.1), Unit::from_mm(33.9))
+///     .with_margins(Unit::from_mm(12.9), Unit::from_mm(4.65))
+///     .with_gutters(Unit::from_mm(2.5), Unit::from_mm(0.0));
+///
+/// let addresses = ["Jane Doe\n1 Main Street", "John Roe\n2 High Street"];
+/// document.fill_labels(&sheet, addresses, |page, label, address| {
+///     let txt = Text::builder()
+///         .with_content(address)
+///         .in_box(label)
+///         .build();
+///     page.add_text(txt, font_id.clone());
+/// });
+/// ```
+///
+/// [`Document::fill_labels`]: crate::Document::fill_labels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabelSheet {
+    /// Size of the sheets, used as the media box of the pages the labels are drawn on.
+    sheet_size: Rectangle,
+
+    /// Number of labels in each row of a sheet.
+    columns: usize,
+
+    /// Number of rows of labels on a sheet.
+    rows: usize,
+
+    /// Width and height of each label, filling the space within the margins if not set.
+    label_size: Option<(Unit, Unit)>,
+
+    /// Distance of the top row of labels from the top edge of the sheet.
+    top_margin: Unit,
+
+    /// Distance of the left column of labels from the left edge of the sheet.
+    left_margin: Unit,
+
+    /// Horizontal space between two labels of a row.
+    column_gutter: Unit,
+
+    /// Vertical space between two rows of labels.
+    row_gutter: Unit,
+
+    /// Index of the first label filled on the first sheet.
+    first_label: usize,
+}
+
+impl LabelSheet {
+    /// Size of a US Letter sheet, 8.5 by 11 inches.
+    const LETTER: Rectangle = Rectangle::new(
+        Position::new(Unit::from_inch(0.0), Unit::from_inch(0.0)),
+        Position::new(Unit::from_inch(8.5), Unit::from_inch(11.0)),
+    );
+
+    /// Creates a new `LabelSheet` with `columns * rows` labels on every sheet of the given size,
+    /// filling the whole sheet without margins or gutters. A zero number of columns or rows is
+    /// treated as 1.
+    pub fn new(sheet_size: Rectangle, columns: usize, rows: usize) -> Self {
+        Self {
+            sheet_size,
+            columns: columns.max(1),
+            rows: rows.max(1),
+            label_size: None,
+            top_margin: Unit::from_mm(0.0),
+            left_margin: Unit::from_mm(0.0),
+            column_gutter: Unit::from_mm(0.0),
+            row_gutter: Unit::from_mm(0.0),
+            first_label: 0,
+        }
+    }
+
+    /// Avery L7160: 21 labels of 63.5 by 38.1 mm on A4 sheets, in 3 columns and 7 rows.
+    pub fn avery_l7160() -> Self {
+        Self::new(Rectangle::A4, 3, 7)
+            .with_label_size(Unit::from_mm(63.5), Unit::from_mm(38.1))
+            .with_margins(Unit::from_mm(15.15), Unit::from_mm(7.25))
+            .with_gutters(Unit::from_mm(2.5), Unit::from_mm(0.0))
+    }
+
+    /// Avery L7163: 14 labels of 99.1 by 38.1 mm on A4 sheets, in 2 columns and 7 rows.
+    pub fn avery_l7163() -> Self {
+        Self::new(Rectangle::A4, 2, 7)
+            .with_label_size(Unit::from_mm(99.1), Unit::from_mm(38.1))
+            .with_margins(Unit::from_mm(15.15), Unit::from_mm(4.65))
+            .with_gutters(Unit::from_mm(2.5), Unit::from_mm(0.0))
+    }
+
+    /// Avery L7651: 65 labels of 38.1 by 21.2 mm on A4 sheets, in 5 columns and 13 rows.
+    pub fn avery_l7651() -> Self {
+        Self::new(Rectangle::A4, 5, 13)
+            .with_label_size(Unit::from_mm(38.1), Unit::from_mm(21.2))
+            .with_margins(Unit::from_mm(10.7), Unit::from_mm(4.75))
+            .with_gutters(Unit::from_mm(2.5), Unit::from_mm(0.0))
+    }
+
+    /// Avery 5160: 30 labels of 2.625 by 1 inches on US Letter sheets, in 3 columns and 10 rows.
+    pub fn avery_5160() -> Self {
+        Self::new(Self::LETTER, 3, 10)
+            .with_label_size(Unit::from_inch(2.625), Unit::from_inch(1.0))
+            .with_margins(Unit::from_inch(0.5), Unit::from_inch(0.1875))
+            .with_gutters(Unit::from_inch(0.125), Unit::from_inch(0.0))
+    }
+
+    /// Sets the width and height of each label. Without a label size, the labels fill the space
+    /// within the margins, less the gutters.
+    pub fn with_label_size(mut self, width: Unit, height: Unit) -> Self {
+        self.label_size = Some((width, height));
+        self
+    }
+
+    /// Sets the distances of the grid of labels from the top and left edges of the sheet.
+    pub fn with_margins(mut self, top: Unit, left: Unit) -> Self {
+        self.top_margin = top;
+        self.left_margin = left;
+        self
+    }
+
+    /// Sets the horizontal space between two labels of a row, and the vertical space between two
+    /// rows of labels.
+    pub fn with_gutters(mut self, column: Unit, row: Unit) -> Self {
+        self.column_gutter = column;
+        self.row_gutter = row;
+        self
+    }
+
+    /// Sets the index of the first label filled on the first sheet, counted row by row from the top
+    /// left label, so that a partially used sheet can be printed on again. Indices past the last
+    /// label of a sheet are treated as the last label.
+    pub fn with_first_label(mut self, first_label: usize) -> Self {
+        self.first_label = first_label.min(self.columns * self.rows - 1);
+        self
+    }
+
+    /// Returns the size of the sheets of this `LabelSheet`.
+    pub(crate) fn sheet_size(&self) -> Rectangle {
+        self.sheet_size
+    }
+
+    /// Returns the index of the first label filled on the first sheet.
+    pub(crate) fn first_label(&self) -> usize {
+        self.first_label
+    }
+
+    /// Returns the labels of a sheet row by row, starting at the top left corner.
+    pub(crate) fn labels(&self) -> Vec<Rectangle> {
+        let (left, top) = (
+            self.sheet_size.low_left().x.into_points() + self.left_margin.into_points(),
+            self.sheet_size.top_right().y.into_points() - self.top_margin.into_points(),
+        );
+        let (column_gutter, row_gutter) = (
+            self.column_gutter.into_points(),
+            self.row_gutter.into_points(),
+        );
+
+        let (width, height) = match self.label_size {
+            Some((width, height)) => (width.into_points(), height.into_points()),
+            None => {
+                let (columns, rows) = (self.columns as f64, self.rows as f64);
+                let width =
+                    self.sheet_size.width().into_points() - 2.0 * self.left_margin.into_points();
+                let height =
+                    self.sheet_size.height().into_points() - 2.0 * self.top_margin.into_points();
+
+                (
+                    (width - (columns - 1.0) * column_gutter) / columns,
+                    (height - (rows - 1.0) * row_gutter) / rows,
+                )
+            }
+        };
+
+        (0..self.rows)
+            .flat_map(|row| (0..self.columns).map(move |column| (row, column)))
+            .map(|(row, column)| {
+                let x = left + column as f64 * (width + column_gutter);
+                let y = top - row as f64 * (height + row_gutter);
+                Rectangle::new(
+                    Position::new(Unit::from_pt(x), Unit::from_pt(y - height)),
+                    Position::new(Unit::from_pt(x + width), Unit::from_pt(y)),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::hierarchy::primitives::{rectangle::Rectangle, unit::Unit};
+
+    use super::LabelSheet;
+
+    #[test]
+    fn labels() {
+        let sheet = LabelSheet::new(Rectangle::from_units(0.0, 0.0, 600.0, 800.0), 2, 3)
+            .with_margins(Unit::from_pt(50.0), Unit::from_pt(40.0))
+            .with_gutters(Unit::from_pt(20.0), Unit::from_pt(5.0));
+
+        let labels = sheet.labels();
+        assert_eq!(labels.len(), 6);
+        assert_eq!(
+            labels[..3],
+            [
+                Rectangle::from_units(40.0, 520.0, 290.0, 750.0),
+                Rectangle::from_units(310.0, 520.0, 560.0, 750.0),
+                Rectangle::from_units(40.0, 285.0, 290.0, 515.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn preset() {
+        let labels = LabelSheet::avery_5160().labels();
+
+        // the bottom right label ends half an inch above the bottom edge of the sheet
+        assert_eq!(labels[29], Rectangle::from_units(409.5, 36.0, 598.5, 108.0));
+    }
+}
@@ -1,14 +1,107 @@
-use std::io::{Error, Write};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap, HashSet},
+    io::{Error, Write},
+    ops::RangeBounds,
+};
+
+#[cfg(feature = "composite-fonts")]
+use std::collections::BTreeSet;
 
-use crate::types::{
-    hierarchy::{catalog::Catalog, page_tree::PageTree, primitives::font::Font},
-    page::Page,
-    pdf_writer::PdfWriter,
+#[cfg(feature = "composite-fonts")]
+use crate::types::hierarchy::primitives::composite_font::{Addressing, CompositeFontErr};
+
+use crate::{
+    types::{
+        hierarchy::{
+            action::{Action, DocumentTrigger},
+            catalog::{Catalog, PageLayout, PageMode},
+            content::{
+                color::{
+                    CieColorSpace, CieColorSpaceObject, ColorSpace, ColorSpaceIds, IccProfile,
+                },
+                image::{Image, SharedImage},
+                page_form::PageForm,
+                print_marks::PrintMarks,
+                recording::Recording,
+                watermark::Watermark,
+            },
+            destination::{Destination, DestinationErr, DestinationRef, NamedDestinations},
+            document_part::{DocumentPart, DocumentPartErr, DocumentParts},
+            form::{FormField, FormFieldErr, InteractiveForm},
+            metadata::{Info, XmpMetadata},
+            outline::{DocumentOutline, OutlineItem},
+            page_tree::PageTree,
+            primitives::{
+                font::Font,
+                function::{FunctionObject, PdfFunction},
+                language::Language,
+                rectangle::Rectangle,
+                symbol::SymbolFont,
+                unit::Unit,
+            },
+        },
+        page::Page,
+        pdf_writer::PdfWriter,
+    },
+    validation::{self, ColorLint, Issue, ValidationReport},
 };
 
 mod builder;
 pub use builder::Builder;
 
+mod profile;
+pub use profile::{PdfVersion, Profile};
+
+mod text_encoding;
+pub use text_encoding::TextEncodingErr;
+
+mod stats;
+use stats::ByteCounter;
+pub use stats::{DocumentStats, PageStats};
+
+mod output_policy;
+pub use output_policy::{LineEnding, OutputPolicy};
+
+mod file_id;
+pub use file_id::FileId;
+
+mod producer;
+pub use producer::Producer;
+
+mod sanitize;
+pub use sanitize::SanitizePolicy;
+
+mod roll;
+pub use roll::Roll;
+
+mod arena;
+pub use arena::Arena;
+
+mod deviation;
+pub use deviation::{Deviation, WriteReport};
+
+mod fragment;
+pub use fragment::{Fragment, FragmentErr};
+
+mod font_embedding;
+pub use font_embedding::{FontEmbedding, FontEmbeddingErr};
+
+mod imposition;
+pub use imposition::Imposition;
+
+mod label_sheet;
+pub use label_sheet::LabelSheet;
+
+mod text_style;
+pub use text_style::{TextStyle, TextStyleErr};
+
+mod handle;
+pub use handle::{
+    CieColorSpaceId, DestinationId, FontId, FragmentId, FunctionId, Handle, IccProfileId, ImageId,
+    PageId,
+};
+
 mod obj_id;
 pub(crate) use obj_id::{IdManager, ObjId};
 
@@ -28,6 +121,78 @@ pub struct Document {
 
     /// Collection of all fonts in this PDF document.
     fonts: Vec<Font>,
+
+    /// Collection of all ICC profiles in this PDF document.
+    icc_profiles: Vec<IccProfile>,
+
+    /// Collection of all CIE-based colour spaces in this PDF document.
+    cie_color_spaces: Vec<CieColorSpaceObject>,
+
+    /// Collection of all function objects in this PDF document.
+    functions: Vec<FunctionObject>,
+
+    /// Watermark stamped onto every page of this PDF document.
+    watermark: Option<Watermark>,
+
+    /// Printer's marks drawn outside of the trim box of every page of this PDF document.
+    print_marks: Option<PrintMarks>,
+
+    /// Document part hierarchy of this PDF document, grouping its pages into parts.
+    parts: Option<DocumentParts>,
+
+    /// Outline (bookmarks) of this PDF document.
+    outline: Option<DocumentOutline>,
+
+    /// Named destinations of this PDF document.
+    named_destinations: Option<NamedDestinations>,
+
+    /// Interactive form of this PDF document, holding its form fields.
+    form: Option<InteractiveForm>,
+
+    /// Version of the PDF specification this document is written against.
+    version: PdfVersion,
+
+    /// Policy on which fonts have to be embedded, checked when the document is written.
+    font_embedding: Option<FontEmbedding>,
+
+    /// Policy on how the file structure of this PDF document is written.
+    output_policy: OutputPolicy,
+
+    /// File identifier written into the trailer of this PDF document.
+    file_id: FileId,
+
+    /// Spare buffers taken from an [`Arena`], used for the content streams of new pages.
+    buffers: Vec<Vec<u8>>,
+
+    /// The document information dictionary, if the document identifies its producer.
+    info: Option<Info>,
+
+    /// The XMP metadata stream, if the document identifies its producer.
+    xmp_metadata: Option<XmpMetadata>,
+
+    /// Forms holding the pages of another document, drawn onto the pages of this document when
+    /// it was created by imposing the other one, and the fragments loaded into this document.
+    forms: Vec<PageForm>,
+
+    /// Whether drawing operations painting entirely outside of the media box of their page are
+    /// left out when this PDF document is written.
+    cull_content: bool,
+
+    /// Policy on how untrusted strings placed into this PDF document are sanitized.
+    sanitize: Option<SanitizePolicy>,
+
+    /// Roll of paper this PDF document is printed on, on a single page.
+    roll: Option<Roll>,
+
+    /// Default style of the texts of this PDF document together with its font.
+    text_style: Option<(FontId, TextStyle)>,
+
+    /// zlib compression level the uncompressed samples of images are compressed with when they're
+    /// added to a page of this PDF document.
+    image_compression: Option<u8>,
+
+    /// Images added to this PDF document, written once and drawn onto any number of its pages.
+    images: Vec<SharedImage>,
 }
 
 impl Default for Document {
@@ -43,6 +208,29 @@ impl Default for Document {
             id_manager,
             pages: Vec::new(),
             fonts: Vec::new(),
+            watermark: None,
+            print_marks: None,
+            parts: None,
+            outline: None,
+            named_destinations: None,
+            form: None,
+            version: PdfVersion::default(),
+            font_embedding: None,
+            output_policy: OutputPolicy::default(),
+            file_id: FileId::default(),
+            buffers: Vec::new(),
+            info: None,
+            xmp_metadata: None,
+            forms: Vec::new(),
+            icc_profiles: Vec::new(),
+            cie_color_spaces: Vec::new(),
+            functions: Vec::new(),
+            cull_content: false,
+            sanitize: None,
+            roll: None,
+            text_style: None,
+            image_compression: None,
+            images: Vec::new(),
         }
     }
 }
@@ -52,159 +240,2052 @@ impl Document {
         Builder {
             id_manager: IdManager::new(),
             page_size: None,
+            version: PdfVersion::default(),
+            font_embedding: None,
+            output_policy: OutputPolicy::default(),
+            file_id: FileId::default(),
+            buffers: Vec::new(),
+            producer: None,
+            cull_content: false,
+            sanitize: None,
+            roll: None,
+            text_style: None,
+            image_compression: None,
         }
     }
 
-    /// Creates a new page inside the document.
+    /// Creates a new document with the default options, which reuses the byte buffers of the given
+    /// [`Arena`] for the content streams of its pages. See [`Arena`] for more details.
+    pub fn with_arena(arena: &mut Arena) -> Self {
+        Self::builder().with_arena(arena).build()
+    }
+
+    /// Creates a new page inside the document. Documents printed on a [`Roll`] have a single page,
+    /// which is created by the first call and returned by all following ones.
     pub fn create_page(&mut self) -> &mut Page {
+        if self.roll.is_some() && !self.pages.is_empty() {
+            return self.pages.last_mut().unwrap();
+        }
+
         let id = self.id_manager.create_id();
         let contents_id = self.id_manager.create_id();
         self.catalog.page_tree_mut().add_page(id.clone());
 
-        self.pages.push(Page::new(
-            id,
-            contents_id,
-            self.catalog.page_tree().obj_ref(),
-        ));
+        let mut page = Page::new(id, contents_id, self.catalog.page_tree().obj_ref());
+        if let Some(buffer) = self.buffers.pop() {
+            page.set_contents_buffer(buffer);
+        }
+        page.set_sanitization(self.sanitize);
+        page.set_text_style(self.text_style.clone());
+        page.set_image_compression(self.image_compression);
+        if let Some(roll) = &self.roll {
+            page.set_roll(roll.width(), roll.margin());
+        }
+        self.pages.push(page);
 
         self.pages.last_mut().unwrap()
     }
 
-    /// Creates a new font inside the document.
-    pub fn create_font(&mut self, subtype: Vec<u8>, base_type: Vec<u8>) -> ObjId<Font> {
+    /// Creates a new font inside the document, returning the [`FontId`] used to draw text with it.
+    pub fn create_font(&mut self, subtype: Vec<u8>, base_type: Vec<u8>) -> FontId {
         let id = self.id_manager.create_id();
+        let base_type = match &self.sanitize {
+            Some(policy) => policy
+                .name(&String::from_utf8_lossy(&base_type))
+                .into_bytes(),
+            None => base_type,
+        };
 
-        self.fonts.push(Font::new(id.clone(), subtype, base_type));
+        let font = Font::new(id, subtype, base_type);
+        let font_id = FontId::new(&font);
+        self.fonts.push(font);
 
-        id
+        font_id
     }
 
-    /// Returns a mutable reference to the current page in document.
-    pub fn current_page(&mut self) -> Option<&mut Page> {
-        self.pages.last_mut()
+    /// Creates a new ICC profile inside the document from the given profile data, returning the
+    /// [`IccProfileId`] used to draw colors and images in its ICCBased colour space. The given
+    /// device [`ColorSpace`] has the same number of colour components as the profile, and is used
+    /// by readers that don't support ICC profiles.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given [`ColorSpace`] isn't a device colour space.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::{content::{color::{Color, ColorSpace}}, primitives::rectangle::Rectangle}};
+    /// let mut document = Document::default();
+    /// # let profile_data = Vec::new();
+    /// let profile = document.create_icc_profile(profile_data, ColorSpace::DeviceCmyk);
+    ///
+    /// let page = document.create_page();
+    /// let color = Color::icc(profile, &[0, 128, 255, 0]);
+    /// page.fill_path(Rectangle::from_units(0.0, 0.0, 100.0, 100.0), color);
+    /// ```
+    pub fn create_icc_profile(
+        &mut self,
+        data: impl Into<Vec<u8>>,
+        alternate: ColorSpace,
+    ) -> IccProfileId {
+        assert!(
+            !matches!(alternate, ColorSpace::Icc(_)),
+            "The alternate colour space of an ICC profile must be a device colour space."
+        );
+
+        let id = self.id_manager.create_id();
+        let profile_id = IccProfileId::new(&id, alternate.components() as u8);
+        self.icc_profiles
+            .push(IccProfile::new(id, data.into(), alternate));
+
+        profile_id
     }
 
-    /// Write the PDF contents into the provided writer.
-    pub fn write(&self, writer: &mut impl Write) -> Result<(), Error> {
-        let mut pdf_writer = PdfWriter::new(writer);
-        let mut id_manager = self.id_manager.clone();
-        pdf_writer.write_header()?;
+    /// Creates a new CIE-based colour space inside the document, such as a [`CalRgb`] or [`Lab`]
+    /// colour space, returning the [`CieColorSpaceId`] used to draw colors and images in it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::{content::color::{CalRgb, Color, D65}, primitives::rectangle::Rectangle}};
+    /// let mut document = Document::default();
+    /// let cal_rgb = document.create_cie_color_space(CalRgb::new(D65).with_gamma([2.2, 2.2, 2.2]));
+    ///
+    /// let page = document.create_page();
+    /// let color = Color::cal_rgb(cal_rgb, 255, 128, 0);
+    /// page.fill_path(Rectangle::from_units(0.0, 0.0, 100.0, 100.0), color);
+    /// ```
+    ///
+    /// [`CalRgb`]: crate::types::hierarchy::content::color::CalRgb
+    /// [`Lab`]: crate::types::hierarchy::content::color::Lab
+    pub fn create_cie_color_space(
+        &mut self,
+        color_space: impl Into<CieColorSpace>,
+    ) -> CieColorSpaceId {
+        let color_space = color_space.into();
+        let id = self.id_manager.create_id();
+        let color_space_id = CieColorSpaceId::new(&id, color_space.family());
+        self.cie_color_spaces
+            .push(CieColorSpaceObject::new(id, color_space));
 
-        pdf_writer.write_object(&self.catalog)?;
-        pdf_writer.write_object(self.catalog.page_tree())?;
+        color_space_id
+    }
 
-        let mut content_streams = Vec::new();
+    /// Creates a new function object inside the document, returning the [`FunctionId`] used by
+    /// other objects, such as stitching functions, to refer to it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::primitives::function::ExponentialFunction};
+    /// let mut document = Document::default();
+    /// let white_to_black = ExponentialFunction::new([1.0], [0.0], 1.0);
+    /// let function = document.create_function(white_to_black);
+    /// ```
+    pub fn create_function(&mut self, function: impl Into<PdfFunction>) -> FunctionId {
+        let id = self.id_manager.create_id();
+        let function_id = FunctionId::new(id.clone());
+        self.functions
+            .push(FunctionObject::new(id, function.into()));
 
-        for page in &self.pages {
-            pdf_writer.write_page(page, &mut id_manager)?;
-            content_streams.push(page.content_stream());
+        function_id
+    }
+
+    /// Creates a new font inside the document for the given symbolic standard font, returning the
+    /// [`FontId`] used to draw its glyphs with a [`TextRun`] of [`SymbolGlyph`]s.
+    ///
+    /// [`TextRun`]: crate::types::hierarchy::content::text_run::TextRun
+    /// [`SymbolGlyph`]: crate::types::hierarchy::primitives::symbol::SymbolGlyph
+    pub fn create_symbol_font(&mut self, font: SymbolFont) -> FontId {
+        self.create_font(b"Type1".to_vec(), font.base_font().as_bytes().to_vec())
+    }
+
+    /// Creates a new composite (Type 0) font inside the document, embedding the given TrueType
+    /// font data, and returns the [`FontId`] used to draw text with it. Text drawn with a composite
+    /// font can contain any character the font has a glyph for, e.g. Cyrillic or CJK characters.
+    /// Fails with a [`CompositeFontErr`] if the font data can't be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pdfgen::Document;
+    /// let mut document = Document::default();
+    /// let data = std::fs::read("NotoSans-Regular.ttf").unwrap();
+    /// let font_id = document
+    ///     .create_composite_font("NotoSans-Regular".into(), data)
+    ///     .unwrap();
+    /// ```
+    #[cfg(feature = "composite-fonts")]
+    pub fn create_composite_font(
+        &mut self,
+        base_font: Vec<u8>,
+        data: Vec<u8>,
+    ) -> Result<FontId, CompositeFontErr> {
+        let id = self.id_manager.create_id();
+
+        let font = Font::composite(
+            id.clone(),
+            base_font,
+            data,
+            Addressing::Unicode,
+            &mut self.id_manager,
+        )?;
+        let font_id = FontId::new(&font);
+        self.fonts.push(font);
+
+        Ok(font_id)
+    }
+
+    /// Creates a new composite (Type 0) font inside the document, embedding the given TrueType
+    /// font data, whose glyphs are addressed by their indices in the font program instead of by
+    /// characters. Returns the [`FontId`] used to draw [`ShapedRun`]s with it, e.g. glyphs shaped
+    /// and positioned by HarfBuzz from the same font data. Texts with a plain content show wrong
+    /// glyphs with this font. Fails with a [`CompositeFontErr`] if the font data can't be parsed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use pdfgen::{
+    /// #     Document,
+    /// #     types::hierarchy::{
+    /// #         content::{shaped_run::ShapedRun, text::Text},
+    /// #         primitives::rectangle::Position,
+    /// #     },
+    /// # };
+    /// let mut document = Document::default();
+    /// let data = std::fs::read("NotoSansArabic-Regular.ttf").unwrap();
+    /// let font_id = document
+    ///     .create_shaping_font("NotoSansArabic-Regular".into(), data)
+    ///     .unwrap();
+    ///
+    /// // glyph indices and positions in thousandths of the font size, as returned by the shaper
+    /// let run = ShapedRun::new(vec![812, 340], vec![402.0, 611.0], vec![(0.0, 0.0); 2])
+    ///     .with_text("سلام");
+    /// let txt = Text::builder()
+    ///     .with_shaped_run(run)
+    ///     .at(Position::from_mm(20.0, 20.0))
+    ///     .build();
+    /// document.create_page().add_text(txt, font_id);
+    /// ```
+    ///
+    /// [`ShapedRun`]: crate::types::hierarchy::content::shaped_run::ShapedRun
+    #[cfg(feature = "composite-fonts")]
+    pub fn create_shaping_font(
+        &mut self,
+        base_font: Vec<u8>,
+        data: Vec<u8>,
+    ) -> Result<FontId, CompositeFontErr> {
+        let id = self.id_manager.create_id();
+
+        let font = Font::composite(
+            id.clone(),
+            base_font,
+            data,
+            Addressing::Glyph,
+            &mut self.id_manager,
+        )?;
+        let font_id = FontId::new(&font);
+        self.fonts.push(font);
+
+        Ok(font_id)
+    }
+
+    /// Extracts the pages in the given range of page indices into a new standalone `Document`,
+    /// e.g. to send only some pages of a document. The pages are copied together with the fonts
+    /// and images they use, and the new document has the same default page size, PDF version,
+    /// font embedding policy and output policy. Document-wide decorations, such as the watermark,
+    /// printer's marks, document parts, the outline, named destinations and form fields, are not
+    /// copied.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range is out of bounds of the pages in this document.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::Document;
+    /// let mut document = Document::default();
+    /// for _ in 0..6 {
+    ///     document.create_page();
+    /// }
+    ///
+    /// // pages 3-5
+    /// let extract = document.extract_pages(2..5);
+    /// ```
+    pub fn extract_pages(&self, range: impl RangeBounds<usize>) -> Document {
+        let pages = &self.pages[(range.start_bound().cloned(), range.end_bound().cloned())];
+
+        let mut builder = self.derived_builder();
+        if let Some(page_size) = self.catalog.page_tree().page_size() {
+            builder = builder.with_page_size(page_size);
         }
+        let mut document = builder.build();
+        let font_ids = self.copy_fonts(pages, &mut document);
+        let color_space_ids = self.copy_color_spaces(pages, &mut document);
+        let image_ids = self.copy_images(pages, &mut document, &color_space_ids);
 
-        for cs in content_streams.into_iter().filter(|cs| !cs.is_empty()) {
-            pdf_writer.write_object(cs)?;
+        for page in pages {
+            let id = document.id_manager.create_id();
+            let contents_id = document.id_manager.create_id();
+            document.catalog.page_tree_mut().add_page(id.clone());
+
+            let parent = document.catalog.page_tree().obj_ref();
+            document.pages.push(page.copy_with_ids(
+                id,
+                contents_id,
+                parent,
+                &font_ids,
+                &color_space_ids,
+                &image_ids,
+            ));
         }
 
-        for font in &self.fonts {
-            // TODO: should this be here or in `Page`? Both?
-            pdf_writer.write_object(font)?;
+        document
+    }
+
+    /// Imposes the pages of this document onto the sheets of a new `Document`, laid out by the given
+    /// [`Imposition`], e.g. to print a booklet or proofs with several pages per sheet. Each page
+    /// is drawn onto the sheets as a form XObject, together with the fonts and images it uses, and
+    /// the new document has the same PDF version, font embedding policy and output policy.
+    /// Document-wide decorations, such as the watermark, printer's marks, document parts, the
+    /// outline, named destinations and form fields, are not copied.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, Imposition, types::hierarchy::primitives::rectangle::Rectangle};
+    /// let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+    /// for _ in 0..4 {
+    ///     document.create_page();
+    /// }
+    ///
+    /// // a booklet of A4 pages on landscape A3 sheets
+    /// let sheet = Rectangle::from_units(0.0, 0.0, 1190.55, 841.89);
+    /// let booklet = document.impose(&Imposition::booklet(sheet));
+    /// ```
+    pub fn impose(&self, imposition: &Imposition) -> Document {
+        let mut document = self.derived_builder().build();
+        let font_ids = self.copy_fonts(&self.pages, &mut document);
+        let color_space_ids = self.copy_color_spaces(&self.pages, &mut document);
+        let image_ids = self.copy_images(&self.pages, &mut document, &color_space_ids);
+
+        let forms: Vec<PageForm> = self
+            .pages
+            .iter()
+            .map(|page| {
+                // pages without own media box inherit the default page size
+                let media_box = page
+                    .media_box()
+                    .or(self.catalog.page_tree().page_size())
+                    .unwrap_or(Rectangle::A4);
+
+                let id = document.id_manager.create_id();
+                page.to_form(id, media_box, &font_ids, &color_space_ids, &image_ids)
+            })
+            .collect();
+
+        let cells = imposition.cells();
+        for sheet in imposition.sheets(self.pages.len()) {
+            let page = document.create_page();
+            page.set_mediabox(imposition.sheet_size());
+
+            for (cell, idx) in cells.iter().zip(sheet) {
+                if let Some(form) = idx.map(|idx| &forms[idx]) {
+                    let matrix = Imposition::placement(*cell, form.b_box());
+                    page.draw_form(form.obj_ref().clone(), matrix);
+                }
+            }
         }
 
-        pdf_writer.write_crt()?;
-        pdf_writer.write_trailer(self.catalog.obj_ref())?;
-        pdf_writer.write_eof()?;
+        document.forms = forms;
+        document
+    }
 
-        Ok(())
+    /// Draws the given template once for every record onto the labels of the given [`LabelSheet`],
+    /// creating a new page with the size of the sheet whenever the labels of the current one are
+    /// filled. The template is called with the page, the bounds of the label and the record, and
+    /// anything it draws is clipped to the label. Returns the number of pages created.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, LabelSheet, types::hierarchy::content::text::Text};
+    /// let mut document = Document::default();
+    /// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    ///
+    /// let badges = (1..=30).map(|num| format!("Guest #{num}"));
+    /// let pages = document.fill_labels(&LabelSheet::avery_l7160(), badges, |page, label, name| {
+    ///     let txt = Text::builder().with_content(name).in_box(label).build();
+    ///     page.add_text(txt, font_id.clone());
+    /// });
+    /// assert_eq!(pages, 2);
+    /// ```
+    pub fn fill_labels<R>(
+        &mut self,
+        sheet: &LabelSheet,
+        records: impl IntoIterator<Item = R>,
+        mut template: impl FnMut(&mut Page, Rectangle, R),
+    ) -> usize {
+        let labels = sheet.labels();
+        let mut label_idx = sheet.first_label();
+        let mut pages = 0;
+
+        for record in records {
+            if pages == 0 || label_idx == labels.len() {
+                self.create_page().set_mediabox(sheet.sheet_size());
+                label_idx %= labels.len();
+                pages += 1;
+            }
+
+            let page = self.pages.last_mut().unwrap();
+            let label = labels[label_idx];
+            page.clip_to(label, |page| template(page, label, record));
+            label_idx += 1;
+        }
+
+        pages
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{Document, types::hierarchy::primitives::rectangle::Rectangle};
+    /// Draws the given [`Recording`] into a [`Fragment`] with the given bounding box, which can be
+    /// serialized and loaded into other documents with [`Document::add_fragment`]. The texts of
+    /// the recording must be drawn with fonts of this document, which are copied into the
+    /// fragment. Fails with a [`FragmentErr`] if the recording uses a font of another document, an
+    /// image with a mask, an image added with [`Document::add_image`] or an ICC-based or CIE-based
+    /// colour space.
+    pub fn create_fragment(
+        &self,
+        b_box: Rectangle,
+        content: &Recording,
+    ) -> Result<Fragment, FragmentErr> {
+        Page::record_fragment(b_box, content, self.sanitize, &self.fonts)
+    }
 
-    fn create_sample_doc() -> Document {
-        let mut document = Document::default();
-        document.create_page().set_mediabox(Rectangle::A4);
-        document.create_font("Type1".into(), "Helvetica".into());
+    /// Loads the given [`Fragment`] into this document, copying its fonts, and returns the
+    /// [`FragmentId`] used to draw it onto pages with [`Page::draw_fragment`]. The fragment is
+    /// written once, no matter how often it's drawn. Fails with a [`FragmentErr`] if the
+    /// fragment embeds a composite font that can't be parsed, or that can't be loaded without
+    /// the `composite-fonts` feature.
+    pub fn add_fragment(&mut self, fragment: &Fragment) -> Result<FragmentId, FragmentErr> {
+        let id = self.id_manager.create_id();
+        let form = fragment.load(id, &mut self.id_manager, &mut self.fonts)?;
+        let fragment_id = FragmentId::new(form.obj_ref().clone());
+        self.forms.push(form);
 
-        document
+        Ok(fragment_id)
     }
 
-    #[test]
-    fn parallel_but_identical() {
-        let mut left_doc = Vec::new();
-        let mut right_doc = Vec::new();
+    /// Adds the given [`Image`] to this document, returning the [`ImageId`] used to draw it onto
+    /// pages with [`Page::draw_image`]. Unlike an image added to a single page with
+    /// [`Page::add_image`], the image is written once, no matter how often it's drawn, e.g. for a
+    /// logo shown on every page.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::{content::{color::ColorSpace, image::Image}, primitives::rectangle::Position}};
+    /// let mut document = Document::default();
+    /// let logo = Image::from_raw(vec![0; 12], 2, 2, ColorSpace::DeviceRgb)
+    ///     .unwrap()
+    ///     .at(Position::from_mm(10.0, 270.0))
+    ///     .scaled(Position::from_mm(20.0, 20.0))
+    ///     .build();
+    /// let logo = document.add_image(logo);
+    ///
+    /// for _ in 0..3 {
+    ///     document.create_page().draw_image(&logo, Position::from_mm(10.0, 270.0));
+    /// }
+    /// ```
+    pub fn add_image(&mut self, mut image: Image) -> ImageId {
+        if let Some(level) = self.image_compression {
+            image.compress(level);
+        }
 
-        let document = create_sample_doc();
+        let id = self.id_manager.create_id();
+        let mask_id = image.mask().map(|_| self.id_manager.create_id());
+        let ext_g_state = image.take_ext_g_state();
+        let image_id = ImageId::new(id.clone(), &image, ext_g_state);
+        self.images.push(SharedImage { id, mask_id, image });
 
-        std::thread::scope(|scope| {
-            scope.spawn(|| {
-                document.write(&mut left_doc).unwrap();
-            });
+        image_id
+    }
 
-            scope.spawn(|| {
-                document.write(&mut right_doc).unwrap();
-            });
+    /// Returns a [`Builder`] for a new document with the same PDF version, font embedding policy,
+    /// output policy, producer and image compression as this document. The [`Roll`] isn't copied,
+    /// as the new document has pages of its own, such as the sheets of an imposition.
+    fn derived_builder(&self) -> Builder {
+        let mut builder = Document::builder().with_version(self.version);
+        builder.font_embedding = self.font_embedding;
+        builder.output_policy = self.output_policy;
+        builder.producer = self.info.as_ref().map(|info| info.producer().clone());
+        builder.cull_content = self.cull_content;
+        builder.sanitize = self.sanitize;
+        builder.image_compression = self.image_compression;
+
+        builder
+    }
+
+    /// Copies the fonts used by the given pages of this document into the given document, returning
+    /// the ids of the fonts in this document mapped to the ids of their copies.
+    fn copy_fonts(
+        &self,
+        pages: &[Page],
+        document: &mut Document,
+    ) -> HashMap<ObjId<Font>, ObjId<Font>> {
+        let used_fonts: HashSet<_> = pages.iter().flat_map(Page::font_ids).collect();
+        let mut font_ids = HashMap::new();
+
+        for font in self
+            .fonts
+            .iter()
+            .filter(|font| used_fonts.contains(&font.id))
+        {
+            let mut font = font.clone();
+            let id = document.id_manager.create_id();
+            font_ids.insert(std::mem::replace(&mut font.id, id), font.id.clone());
+            #[cfg(feature = "composite-fonts")]
+            font.create_descendant_ids(&mut document.id_manager);
+            document.fonts.push(font);
+        }
+
+        font_ids
+    }
+
+    /// Copies the ICC profiles and CIE-based colour spaces used by the given pages of this
+    /// document into the given document, returning the ids of the objects in this document mapped
+    /// to the ids of their copies.
+    fn copy_color_spaces(&self, pages: &[Page], document: &mut Document) -> ColorSpaceIds {
+        let mut used_profiles = HashSet::new();
+        let mut used_cie_color_spaces = HashSet::new();
+        let image_color_spaces = self
+            .used_images(pages)
+            .filter_map(|image| image.image.color_space());
+        for color_space in pages
+            .iter()
+            .flat_map(Page::color_spaces)
+            .chain(image_color_spaces)
+        {
+            match color_space {
+                ColorSpace::Icc(profile) => {
+                    used_profiles.insert(profile.obj_id());
+                }
+                ColorSpace::Cie(color_space) => {
+                    used_cie_color_spaces.insert(color_space.obj_id());
+                }
+                ColorSpace::DeviceRgb | ColorSpace::DeviceGray | ColorSpace::DeviceCmyk => {}
+            }
+        }
+
+        let mut ids = ColorSpaceIds::default();
+
+        for profile in self
+            .icc_profiles
+            .iter()
+            .filter(|profile| used_profiles.contains(&profile.id))
+        {
+            let mut profile = profile.clone();
+            let id = document.id_manager.create_id();
+            ids.icc_profiles
+                .insert(std::mem::replace(&mut profile.id, id), profile.id.clone());
+            document.icc_profiles.push(profile);
+        }
+
+        for color_space in self
+            .cie_color_spaces
+            .iter()
+            .filter(|color_space| used_cie_color_spaces.contains(&color_space.id))
+        {
+            let mut color_space = color_space.clone();
+            let id = document.id_manager.create_id();
+            ids.cie_color_spaces.insert(
+                std::mem::replace(&mut color_space.id, id),
+                color_space.id.clone(),
+            );
+            document.cie_color_spaces.push(color_space);
+        }
+
+        ids
+    }
+
+    /// Returns the images added to this document that are drawn onto the given pages.
+    fn used_images(&self, pages: &[Page]) -> impl Iterator<Item = &SharedImage> {
+        let used_images: HashSet<_> = pages.iter().flat_map(Page::shared_image_ids).collect();
+
+        self.images
+            .iter()
+            .filter(move |image| used_images.contains(&image.id))
+    }
+
+    /// Copies the images added to this document that are drawn onto the given pages into the given
+    /// document, referring to colour spaces by the ids they are mapped to, and returns the ids of
+    /// the images in this document mapped to the ids of their copies.
+    fn copy_images(
+        &self,
+        pages: &[Page],
+        document: &mut Document,
+        color_space_ids: &ColorSpaceIds,
+    ) -> HashMap<ObjId<Image>, ObjId<Image>> {
+        let mut image_ids = HashMap::new();
+
+        for image in self.used_images(pages) {
+            let mut image = image.clone();
+            let id = document.id_manager.create_id();
+            image_ids.insert(std::mem::replace(&mut image.id, id), image.id.clone());
+            image.mask_id = image.mask_id.map(|_| document.id_manager.create_id());
+            image.image.remap_color_space_ids(color_space_ids);
+            document.images.push(image);
+        }
+
+        image_ids
+    }
+
+    /// Returns the [`Font`] with the given [`FontId`], if it was created in this document.
+    pub fn font(&self, font_id: &FontId) -> Option<&Font> {
+        let font_id = font_id.clone().into_obj_id();
+        self.fonts.iter().find(|font| font.id == font_id)
+    }
+
+    /// Returns a mutable reference to the [`Font`] with the given [`FontId`], e.g. to set its
+    /// [`FontEncoding`], if it was created in this document.
+    ///
+    /// [`FontEncoding`]: crate::types::hierarchy::primitives::encoding::FontEncoding
+    pub fn font_mut(&mut self, font_id: &FontId) -> Option<&mut Font> {
+        let font_id = font_id.clone().into_obj_id();
+        self.fonts.iter_mut().find(|font| font.id == font_id)
+    }
+
+    /// Returns a mutable reference to the current page in document.
+    pub fn current_page(&mut self) -> Option<&mut Page> {
+        self.pages.last_mut()
+    }
+
+    /// Replaces every occurrence of the given pattern in the texts on all pages of this document,
+    /// e.g. to correct a name without building the document again, and returns the number of
+    /// texts that were changed. Texts laid out in a box are broken into lines again, while the
+    /// positions of other content stay the same.
+    ///
+    /// Only texts added to pages are replaced, not texts in annotation appearances, form fields,
+    /// fragments or texts shown with a [`ShapedRun`]. The glyph adjustments of a [`TextRun`] are
+    /// dropped from texts that are replaced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::{content::text::Text, primitives::rectangle::Position}};
+    /// let mut document = Document::default();
+    /// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    /// let txt = Text::builder()
+    ///     .with_content("Invoice for Jon Doe")
+    ///     .at(Position::from_mm(20.0, 20.0))
+    ///     .build();
+    /// document.create_page().add_text(txt, font_id);
+    ///
+    /// assert_eq!(document.replace_text("Jon", "John"), 1);
+    /// ```
+    ///
+    /// [`ShapedRun`]: crate::types::hierarchy::content::shaped_run::ShapedRun
+    /// [`TextRun`]: crate::types::hierarchy::content::text_run::TextRun
+    pub fn replace_text(&mut self, pattern: &str, replacement: &str) -> usize {
+        self.pages
+            .iter_mut()
+            .map(|page| page.replace_text(pattern, replacement))
+            .sum()
+    }
+
+    /// Sets the [`Watermark`] that is stamped onto every page of this document when it's written.
+    pub fn set_watermark(&mut self, watermark: Watermark) {
+        self.watermark = Some(watermark);
+    }
+
+    /// Sets the [`PrintMarks`] that are drawn outside of the trim box of every page of this
+    /// document when it's written. Pages without a trim box get no marks.
+    pub fn set_print_marks(&mut self, print_marks: PrintMarks) {
+        self.print_marks = Some(print_marks);
+    }
+
+    /// Adds the given [`DocumentPart`] to this document, grouping its range of pages into a part of
+    /// the document part hierarchy (PDF 2.0). Every page can only belong to a single part.
+    pub fn add_document_part(&mut self, part: DocumentPart) -> Result<(), DocumentPartErr> {
+        let part = match &self.sanitize {
+            Some(policy) => part.sanitized(policy),
+            None => part,
+        };
+        let parts = self.parts.get_or_insert_with(|| {
+            let parts = DocumentParts::new(&mut self.id_manager);
+            self.catalog.set_dpart_root(parts.obj_ref().clone());
+            parts
         });
 
-        let left_output = String::from_utf8_lossy(&left_doc);
-        let right_output = String::from_utf8_lossy(&right_doc);
+        parts.add(&mut self.id_manager, &mut self.pages, part)
+    }
 
-        pretty_assertions::assert_eq!(left_output, right_output);
+    /// Adds the given [`OutlineItem`] together with its child items as the last top-level item of
+    /// the outline (bookmarks) of this document.
+    ///
+    /// Returns [`DestinationErr`] if the destination of any of the items refers to a page that is
+    /// not in this document, or to a name that is not a named destination of this document.
+    pub fn add_outline_item(&mut self, item: OutlineItem) -> Result<(), DestinationErr> {
+        for destination in item.destinations() {
+            self.check_destination(destination)?;
+        }
+
+        let item = match &self.sanitize {
+            Some(policy) => item.sanitized(policy),
+            None => item,
+        };
+        let outline = self.outline.get_or_insert_with(|| {
+            let outline = DocumentOutline::new(&mut self.id_manager);
+            self.catalog.set_outlines(outline.obj_ref().clone());
+            outline
+        });
+
+        outline.add(&mut self.id_manager, item);
+        Ok(())
     }
 
-    #[test]
-    fn simple_document() {
-        let document = create_sample_doc();
+    /// Adds the given [`Destination`] as a named destination of this document, replacing the
+    /// destination previously added with the same name, and returns the [`DestinationId`] used to
+    /// reference it by its name, see [`DestinationRef::Named`].
+    ///
+    /// Returns [`DestinationErr`] if the destination refers to a page that is not in this
+    /// document.
+    pub fn add_named_destination(
+        &mut self,
+        name: impl Into<String>,
+        destination: Destination,
+    ) -> Result<DestinationId, DestinationErr> {
+        if !self.has_page(destination.page()) {
+            return Err(DestinationErr::UnknownPage);
+        }
 
-        let mut writer = Vec::default();
-        document.write(&mut writer).unwrap();
+        let named_destinations = self.named_destinations.get_or_insert_with(|| {
+            let named_destinations = NamedDestinations::new(&mut self.id_manager);
+            self.catalog.set_dests(named_destinations.obj_ref().clone());
+            named_destinations
+        });
 
-        let output = String::from_utf8(writer).unwrap();
+        let name = name.into();
+        named_destinations.insert(name.clone(), destination);
+        Ok(DestinationId::new(name))
+    }
 
-        insta::assert_snapshot!(output, @r"
-        %PDF-2.0
-        1 0 obj
-        << /Type /Catalog 
-        /Pages 2 0 R >>
-        endobj
+    /// Adds the given [`FormField`] to the interactive form of this document, placing its widgets
+    /// on their pages.
+    ///
+    /// Returns [`FormFieldErr`] if a widget is placed on a page that is not in this document, if
+    /// the document already has a field with the same name, or if the field itself is invalid.
+    pub fn add_form_field(&mut self, field: impl Into<FormField>) -> Result<(), FormFieldErr> {
+        let field = field.into();
+        field.check()?;
 
-        2 0 obj
-        << /Type /Pages 
-        /Kids [3 0 R]
-        /Count 1 >>
-        endobj
+        if !field.pages().all(|page| self.has_page(page)) {
+            return Err(FormFieldErr::UnknownPage);
+        }
 
-        3 0 obj
-        << /Type /Page 
-        /Parent 2 0 R
-        /Resources <<  >>
-        /MediaBox [0 0 592.441 839.0551]>>
-        endobj
+        if self
+            .form
+            .as_ref()
+            .is_some_and(|form| form.contains(field.name()))
+        {
+            return Err(FormFieldErr::DuplicateName);
+        }
 
+        let form = self.form.get_or_insert_with(|| {
+            let form = InteractiveForm::new(&mut self.id_manager);
+            self.catalog.set_acro_form(form.obj_ref().clone());
+            form
+        });
 
-        5 0 obj
-        << /Type /Font 
-        /Subtype /Type1 
-        /BaseFont /Helvetica 
-        >>
-        endobj
+        for (page_id, widget) in form.add(&mut self.id_manager, field) {
+            if let Some(page) = self.pages.iter_mut().find(|page| page.id() == page_id) {
+                page.add_widget(widget);
+            }
+        }
 
-        xref
-        0 4
-        0000000010 00000 n 
-        0000000061 00000 n 
-        0000000120 00000 n 
-        0000000220 00000 n 
-        trailer
-               << /Size 4
-               /Root 1 0 R
-               /ID [<d4e71dbc01d10dfdd9eb1e094a6f1dcd>
-                  <d4e71dbc01d10dfdd9eb1e094a6f1dcd>
-                  ]
-               >>
+        Ok(())
+    }
+
+    /// Sets the destination that is shown when this document is opened, instead of the first
+    /// page.
+    ///
+    /// Returns [`DestinationErr`] if the destination refers to a page that is not in this
+    /// document, or to a name that is not a named destination of this document.
+    pub fn set_open_action(
+        &mut self,
+        destination: impl Into<DestinationRef>,
+    ) -> Result<(), DestinationErr> {
+        let destination = destination.into();
+        self.check_destination(&destination)?;
+
+        self.catalog.set_open_action(destination);
+        Ok(())
+    }
+
+    /// Returns whether the given page is a page of this document.
+    fn has_page(&self, page: &PageId) -> bool {
+        self.pages.iter().any(|candidate| &candidate.id() == page)
+    }
+
+    /// Checks that the given [`DestinationRef`] refers to a page of this document or to a named
+    /// destination of this document.
+    fn check_destination(&self, destination: &DestinationRef) -> Result<(), DestinationErr> {
+        match destination {
+            DestinationRef::Explicit(destination) if !self.has_page(destination.page()) => {
+                Err(DestinationErr::UnknownPage)
+            }
+            DestinationRef::Named(name)
+                if !self
+                    .named_destinations
+                    .as_ref()
+                    .is_some_and(|named| named.contains(name)) =>
+            {
+                Err(DestinationErr::UnknownName)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Sets the [`PageMode`] this document is displayed with when it's opened, e.g. to show the
+    /// outline next to the pages or to open the document in full-screen mode.
+    pub fn set_page_mode(&mut self, page_mode: PageMode) {
+        self.catalog.set_page_mode(page_mode);
+    }
+
+    /// Sets the [`PageLayout`] of the pages of this document when it's opened, e.g. to show two
+    /// pages side by side.
+    pub fn set_page_layout(&mut self, page_layout: PageLayout) {
+        self.catalog.set_page_layout(page_layout);
+    }
+
+    /// Sets the natural [`Language`] of the text of this document, used by screen readers and
+    /// required by accessibility standards such as PDF/UA. The reading direction of the document is
+    /// set to the [`Language::direction`], so the pages of e.g. an Arabic document are laid out
+    /// from right to left when they are shown side by side.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::str::FromStr;
+    /// # use pdfgen::{Document, types::hierarchy::primitives::language::Language};
+    /// let mut document = Document::default();
+    /// document.set_language(Language::from_str("de-DE").unwrap());
+    /// ```
+    pub fn set_language(&mut self, language: Language) {
+        self.catalog.set_direction(language.direction());
+        self.catalog.set_lang(language);
+    }
+
+    /// Sets the [`Action`] that is performed when the given [`DocumentTrigger`] occurs, such as
+    /// closing or printing this document, replacing the action previously set for it.
+    pub fn set_action(&mut self, trigger: DocumentTrigger, action: Action) {
+        self.catalog.set_action(trigger, action);
+    }
+
+    /// Validates the colors used in this document with the given [`ColorLint`], returning a
+    /// [`ValidationReport`] with all found issues. Every text is checked for characters its font
+    /// has no glyphs for. With the [`FontEmbedding::NeverEmbed`] policy,
+    /// non-standard fonts are reported as well.
+    pub fn validate(&self, lint: &ColorLint) -> ValidationReport {
+        let mut report = ValidationReport::default();
+
+        for (idx, page) in self.pages.iter().enumerate() {
+            for color in page.text_colors() {
+                lint.check_text_color(idx, *color, &mut report);
+            }
+
+            for (font_id, text) in page.texts() {
+                if let Some(font) = self.font(font_id) {
+                    validation::check_glyph_coverage(idx, font, text, &mut report);
+                }
+            }
+        }
+
+        if self.font_embedding == Some(FontEmbedding::NeverEmbed) {
+            for font in self
+                .fonts
+                .iter()
+                .filter(|font| !font.is_standard() && !font.is_embedded())
+            {
+                report.push(Issue::UnembeddedFont {
+                    base_font: font.base_font(),
+                });
+            }
+        }
+
+        report
+    }
+
+    /// Write the PDF contents into the provided writer. Fails with a [`FontEmbeddingErr`] if the
+    /// fonts of this document violate its [`FontEmbedding`] policy, with a [`TextEncodingErr`] if
+    /// a text can't be encoded in the encoding of its font, and with a [`Deviation`] if the
+    /// document deviates from the PDF specification. Pages without a media box use the A4 page
+    /// size, unless the [`OutputPolicy`] is strict about media boxes. Use
+    /// [`Document::write_lenient`] to fix the deviations instead, which also writes texts with
+    /// characters shown as `.notdef` boxes.
+    pub fn write(&self, writer: &mut impl Write) -> Result<(), Error> {
+        self.check_font_embedding()?;
+        self.check_text_encoding()?;
+
+        // pages without a media box use the A4 page size, unless the output policy is strict
+        let strict = self.output_policy.strict_media_boxes();
+        let (pages, fonts, report) = self.fix_deviations();
+        if let Some(deviation) = report
+            .deviations()
+            .iter()
+            .find(|deviation| strict || !matches!(deviation, Deviation::MissingMediaBox { .. }))
+        {
+            return Err(Error::other(deviation.clone()));
+        }
+
+        self.write_fixed(writer, &pages, &fonts)?;
+
+        Ok(())
+    }
+
+    /// Write the PDF contents into the provided writer in lenient mode, fixing all deviations from
+    /// the PDF specification instead of failing, e.g. by using the A4 page size for pages without
+    /// a media box. Returns a [`WriteReport`] with every [`Deviation`] that was fixed, and the size
+    /// of each written object.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Deviation, Document};
+    /// let mut document = Document::default();
+    /// document.create_page();
+    ///
+    /// let report = document.write_lenient(&mut Vec::new()).unwrap();
+    /// assert_eq!(report.deviations(), [Deviation::MissingMediaBox { page: 0 }]);
+    /// ```
+    pub fn write_lenient(&self, writer: &mut impl Write) -> Result<WriteReport, Error> {
+        self.check_font_embedding()?;
+
+        let (pages, fonts, mut report) = self.fix_deviations();
+        let object_sizes = self.write_fixed(writer, &pages, &fonts)?;
+        report.set_object_sizes(object_sizes);
+
+        Ok(report)
+    }
+
+    /// Checks the fonts of this document against its [`FontEmbedding`] policy, if any.
+    fn check_font_embedding(&self) -> Result<(), Error> {
+        match self.font_embedding {
+            Some(font_embedding) => font_embedding.check(&self.fonts).map_err(Error::other),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks every text drawn on the pages of this document against the encoding of its font.
+    fn check_text_encoding(&self) -> Result<(), Error> {
+        for (idx, page) in self.pages.iter().enumerate() {
+            for (font_id, text) in page.texts() {
+                if let Some(font) = self.font(font_id) {
+                    TextEncodingErr::check(idx, font, text).map_err(Error::other)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns [`DocumentStats`] summarizing the content of this document, without writing it.
+    /// The estimated size of the document is the size it's written with in lenient mode.
+    ///
+    /// Estimating the size reads the samples of images created with [`Image::from_raw_source`],
+    /// so this returns the same error as writing the document if a source can't be read.
+    ///
+    /// [`Image::from_raw_source`]: crate::types::hierarchy::content::image::Image::from_raw_source
+    pub fn stats(&self) -> Result<DocumentStats, Error> {
+        let pages = self.pages.iter().map(Page::stats).collect();
+
+        let mut counter = ByteCounter::default();
+        let (fixed_pages, fonts, _) = self.fix_deviations();
+        self.write_fixed(&mut counter, &fixed_pages, &fonts)?;
+
+        Ok(DocumentStats::new(pages, counter.count))
+    }
+
+    /// Returns the characters shown with the given [`Font`] on any page of this document, including
+    /// the pages of another document drawn onto them, or in its watermark.
+    #[cfg(feature = "composite-fonts")]
+    fn shown_chars(&self, font: &Font) -> BTreeSet<char> {
+        let page_texts = self
+            .pages
+            .iter()
+            .flat_map(Page::texts)
+            .chain(self.forms.iter().flat_map(PageForm::texts));
+        let watermark_text = self.watermark.iter().filter_map(Watermark::text_content);
+
+        page_texts
+            .map(|(font_id, text)| (font_id, text.as_str()))
+            .chain(watermark_text)
+            .filter(|(font_id, _)| (*font_id).clone().into_obj_id() == font.id)
+            .flat_map(|(_, text)| text.chars())
+            .collect()
+    }
+
+    /// Returns the pages and fonts of this document with all deviations from the PDF
+    /// specification fixed, together with a [`WriteReport`] listing the fixed deviations. Pages and
+    /// fonts are only copied if they had to be fixed.
+    fn fix_deviations(&self) -> (Vec<Cow<'_, Page>>, Vec<Cow<'_, Font>>, WriteReport) {
+        let mut report = WriteReport::default();
+
+        let fonts = self
+            .fonts
+            .iter()
+            .map(|font| {
+                let mut fixed = font.clone();
+                let names = fixed.encode_names();
+                if names.is_empty() {
+                    return Cow::Borrowed(font);
+                }
+
+                for name in names {
+                    report.push(Deviation::InvalidName { name });
+                }
+                Cow::Owned(fixed)
+            })
+            .collect();
+
+        let pages = self
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(idx, page)| {
+                // pages without own media box inherit the default page size
+                let Some(media_box) = page.media_box().or(self.catalog.page_tree().page_size())
+                else {
+                    report.push(Deviation::MissingMediaBox { page: idx });
+                    let mut fixed = page.clone();
+                    fixed.set_mediabox(Rectangle::A4);
+                    return Cow::Owned(fixed);
+                };
+
+                let in_range =
+                    |size: Unit| (Page::MIN_SIZE..=Page::MAX_SIZE).contains(&size.into_points());
+                if in_range(media_box.width()) && in_range(media_box.height()) {
+                    return Cow::Borrowed(page);
+                }
+
+                report.push(Deviation::PageSizeOutOfRange {
+                    page: idx,
+                    media_box,
+                });
+                let mut fixed = page.clone();
+                fixed.set_mediabox(media_box.clamp_size(Page::MIN_SIZE, Page::MAX_SIZE));
+                Cow::Owned(fixed)
+            })
+            .collect();
+
+        (pages, fonts, report)
+    }
+
+    /// Returns the media box of the given page, which is the default page size of this document
+    /// for pages without their own media box.
+    fn effective_media_box(&self, page: &Page) -> Rectangle {
+        page.media_box()
+            .or(self.catalog.page_tree().page_size())
+            .unwrap_or(Rectangle::A4)
+    }
+
+    /// Returns the given pages with the drawing operations outside of their media box culled, if
+    /// this document culls its content. Pages are only copied if any operation was culled.
+    fn cull_pages<'a>(&self, pages: &'a [Cow<'_, Page>]) -> Vec<Cow<'a, Page>> {
+        pages
+            .iter()
+            .map(|page| {
+                // content of pages with a larger user space unit is scaled down to fit their media
+                // box, so the visible area is larger in the coordinates of the content
+                let visible = self
+                    .effective_media_box(page)
+                    .scaled(page.user_unit().unwrap_or(1.0));
+                let culled = self.cull_content.then(|| page.culled(visible)).flatten();
+
+                match culled {
+                    Some(culled) => Cow::Owned(culled),
+                    None => Cow::Borrowed(page.as_ref()),
+                }
+            })
+            .collect()
+    }
+
+    /// Write the PDF contents with the given pages and fonts, which have their deviations from the
+    /// PDF specification fixed, into the provided writer. Returns the size of each written object
+    /// by object number.
+    fn write_fixed(
+        &self,
+        writer: &mut impl Write,
+        pages: &[Cow<'_, Page>],
+        fonts: &[Cow<'_, Font>],
+    ) -> Result<BTreeMap<u64, usize>, Error> {
+        let pages = self.cull_pages(pages);
+        let mut pdf_writer = PdfWriter::new(writer);
+        let mut id_manager = self.id_manager.clone();
+        pdf_writer.write_header(self.version, self.output_policy)?;
+
+        pdf_writer.write_object(&self.catalog.obj_ref(), &self.catalog)?;
+        let page_tree = self.catalog.page_tree();
+        pdf_writer.write_object(&page_tree.obj_ref(), page_tree)?;
+
+        let watermark = self
+            .watermark
+            .as_ref()
+            .map(|watermark| (watermark, watermark.create_ids(&mut id_manager)));
+
+        let mut content_streams = Vec::new();
+        let mut watermark_stamps = Vec::new();
+        let mut print_marks = Vec::new();
+
+        for page in &pages {
+            let stamp = watermark.as_ref().map(|(watermark, ids)| {
+                let media_box = self.effective_media_box(page);
+
+                watermark.stamp(ids, media_box, id_manager.create_id())
+            });
+
+            let marks = self
+                .print_marks
+                .as_ref()
+                .zip(page.trim_box())
+                .map(|(marks, trim_box)| {
+                    marks.draw(trim_box, page.bleed_box(), id_manager.create_id())
+                });
+
+            pdf_writer.write_page(page, &mut id_manager, stamp.as_ref(), marks.as_ref())?;
+            content_streams.push(page.content_stream());
+            watermark_stamps.extend(stamp);
+            print_marks.extend(marks);
+        }
+
+        for cs in content_streams.into_iter().filter(|cs| !cs.is_empty()) {
+            pdf_writer.write_object(cs.obj_ref(), cs)?;
+        }
+
+        for stamp in &watermark_stamps {
+            pdf_writer.write_object(stamp.contents().obj_ref(), stamp.contents())?;
+        }
+
+        for marks in &print_marks {
+            pdf_writer.write_object(marks.obj_ref(), marks)?;
+        }
+
+        if let Some((watermark, ids)) = &watermark {
+            pdf_writer.write_watermark(watermark, ids)?;
+        }
+
+        for form in &self.forms {
+            pdf_writer.write_page_form(form, &mut id_manager)?;
+        }
+
+        for image in &self.images {
+            pdf_writer.write_shared_image(image)?;
+        }
+
+        if let Some(parts) = &self.parts {
+            pdf_writer.write_object(parts.obj_ref(), parts)?;
+
+            for node in parts.nodes() {
+                pdf_writer.write_object(node.obj_ref(), &node)?;
+            }
+        }
+
+        if let Some(outline) = &self.outline {
+            pdf_writer.write_object(outline.obj_ref(), outline)?;
+
+            for node in outline.nodes() {
+                pdf_writer.write_object(node.obj_ref(), &node)?;
+            }
+        }
+
+        if let Some(named_destinations) = &self.named_destinations {
+            pdf_writer.write_object(named_destinations.obj_ref(), named_destinations)?;
+        }
+
+        if let Some(form) = &self.form {
+            pdf_writer.write_interactive_form(form, &mut id_manager)?;
+        }
+
+        for font in fonts {
+            // TODO: should this be here or in `Page`? Both?
+            pdf_writer.write_object(font.obj_ref(), font.as_ref())?;
+
+            #[cfg(feature = "composite-fonts")]
+            for descendant in font.descendants(self.shown_chars(font))? {
+                pdf_writer.write_object(descendant.obj_ref(), &descendant)?;
+            }
+        }
+
+        for profile in &self.icc_profiles {
+            pdf_writer.write_object(&profile.id, profile)?;
+        }
+
+        for color_space in &self.cie_color_spaces {
+            pdf_writer.write_object(&color_space.id, color_space)?;
+        }
+
+        for function in &self.functions {
+            pdf_writer.write_object(&function.id, function)?;
+        }
+
+        if let Some(xmp_metadata) = &self.xmp_metadata {
+            pdf_writer.write_object(xmp_metadata.obj_ref(), xmp_metadata)?;
+        }
+
+        if let Some(info) = &self.info {
+            pdf_writer.write_object(info.obj_ref(), info)?;
+        }
+
+        let object_sizes = pdf_writer.object_sizes();
+        pdf_writer.write_crt(self.output_policy.line_ending())?;
+        pdf_writer.write_trailer(
+            self.catalog.obj_ref(),
+            self.info.as_ref().map(Info::obj_ref),
+            &self.file_id,
+        )?;
+        pdf_writer.write_eof()?;
+
+        Ok(object_sizes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Deviation, Document, FileId, FontEmbedding, FontEmbeddingErr, Imposition, LineEnding,
+        OutputPolicy, PdfVersion, Profile, Roll, TextEncodingErr,
+        types::hierarchy::{
+            content::{
+                color::{Color, ColorSpace},
+                image::Image,
+                text::Text,
+            },
+            destination::{Destination, DestinationErr, DestinationRef},
+            form::{CheckBox, FormFieldErr},
+            primitives::{
+                encoding::FontEncoding,
+                rectangle::{Position, Rectangle},
+                unit::Unit,
+            },
+        },
+        validation::{ColorLint, Issue},
+    };
+
+    fn create_sample_doc() -> Document {
+        let mut document = Document::default();
+        document.create_page().set_mediabox(Rectangle::A4);
+        document.create_font("Type1".into(), "Helvetica".into());
+
+        document
+    }
+
+    #[test]
+    fn parallel_but_identical() {
+        let mut left_doc = Vec::new();
+        let mut right_doc = Vec::new();
+
+        let document = create_sample_doc();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                document.write(&mut left_doc).unwrap();
+            });
+
+            scope.spawn(|| {
+                document.write(&mut right_doc).unwrap();
+            });
+        });
+
+        let left_output = String::from_utf8_lossy(&left_doc);
+        let right_output = String::from_utf8_lossy(&right_doc);
+
+        pretty_assertions::assert_eq!(left_output, right_output);
+    }
+
+    #[test]
+    fn profile_version() {
+        let mut writer = Vec::default();
+        Document::builder()
+            .with_profile(Profile::PrintProduction)
+            .build()
+            .write(&mut writer)
+            .unwrap();
+        assert!(writer.starts_with(b"%PDF-1.6\n"));
+
+        let mut writer = Vec::default();
+        Document::builder()
+            .with_profile(Profile::ArchivalPdfA)
+            .with_version(PdfVersion::V1_4)
+            .build()
+            .write(&mut writer)
+            .unwrap();
+        assert!(writer.starts_with(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n"));
+    }
+
+    /// Writes a document built with the given profile, holding a page with an uncompressed image,
+    /// and returns the output together with the result of writing it with a standard font added.
+    fn write_with_profile(profile: Profile) -> (String, std::io::Result<()>) {
+        let mut document = Document::builder()
+            .with_profile(profile)
+            .with_page_size(Rectangle::A6)
+            .build();
+        let image = Image::from_raw(vec![0; 12], 2, 2, ColorSpace::DeviceRgb)
+            .unwrap()
+            .with_compression_level(0)
+            .at(Position::from_mm(10.0, 10.0))
+            .build();
+        document.create_page().add_image(image);
+
+        let mut writer = Vec::default();
+        document.write(&mut writer).unwrap();
+
+        document.create_font("Type1".into(), "Helvetica".into());
+        let with_font = document.write(&mut Vec::default());
+
+        (String::from_utf8_lossy(&writer).into_owned(), with_font)
+    }
+
+    fn font_embedding_err(result: std::io::Result<()>) -> Option<FontEmbeddingErr> {
+        result
+            .unwrap_err()
+            .get_ref()
+            .and_then(|err| err.downcast_ref::<FontEmbeddingErr>())
+            .cloned()
+    }
+
+    #[test]
+    fn archival_profile() {
+        let (output, with_font) = write_with_profile(Profile::ArchivalPdfA);
+
+        assert!(output.starts_with("%PDF-1.7\n%"));
+        assert!(output.contains("/Filter /FlateDecode"));
+        assert!(output.contains(concat!(
+            "/Producer (pdfgen ",
+            env!("CARGO_PKG_VERSION"),
+            ")"
+        )));
+        assert_eq!(
+            font_embedding_err(with_font),
+            Some(FontEmbeddingErr::NotEmbedded {
+                base_font: "Helvetica".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn print_production_profile() {
+        let (output, with_font) = write_with_profile(Profile::PrintProduction);
+
+        assert!(output.starts_with("%PDF-1.6\n%"));
+        assert!(output.contains("/Filter /FlateDecode"));
+        assert!(output.contains(concat!(
+            "/Producer (pdfgen ",
+            env!("CARGO_PKG_VERSION"),
+            ")"
+        )));
+        assert_eq!(
+            font_embedding_err(with_font),
+            Some(FontEmbeddingErr::NotEmbedded {
+                base_font: "Helvetica".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn web_optimized_profile() {
+        let (output, with_font) = write_with_profile(Profile::WebOptimized);
+
+        assert!(output.starts_with("%PDF-1.7\n1 0 obj"));
+        assert!(output.contains("/Filter /FlateDecode"));
+        assert!(!output.contains("/Producer"));
+        assert!(with_font.is_ok());
+    }
+
+    #[test]
+    fn draft_profile() {
+        let (output, with_font) = write_with_profile(Profile::Draft);
+
+        assert!(output.starts_with("%PDF-2.0\n1 0 obj"));
+        assert!(!output.contains("/Filter"));
+        assert!(!output.contains("/Producer"));
+        assert!(with_font.is_ok());
+    }
+
+    #[test]
+    fn object_sizes() {
+        let mut document = Document::default();
+        document.create_page().set_mediabox(Rectangle::A4);
+        let font = document.create_font("Type1".into(), "Helvetica".into());
+
+        let mut writer = Vec::default();
+        let report = document.write_lenient(&mut writer).unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        // the font is the last object, followed by the blank line before the xref section
+        let start = output.find("5 0 obj").unwrap();
+        let end = output.find("xref").unwrap();
+        assert_eq!(report.object_size(&font), Some(end - start));
+    }
+
+    #[test]
+    fn unencodable_text() {
+        let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+        let font = document.create_font("Type1".into(), "Helvetica".into());
+        let text = Text::builder()
+            .with_content("Grüße")
+            .at(Position::from_mm(20.0, 20.0))
+            .build();
+        document.create_page().add_text(text, font.clone());
+
+        let err = document.write(&mut Vec::default()).unwrap_err();
+        assert_eq!(
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<TextEncodingErr>()),
+            Some(&TextEncodingErr::Unencodable {
+                page: 0,
+                base_font: "Helvetica".to_string(),
+                text: "Grüße".to_string(),
+                characters: vec!['ü', 'ß'],
+            })
+        );
+        assert!(document.write_lenient(&mut Vec::default()).is_ok());
+
+        let font = document.font_mut(&font).unwrap();
+        font.set_encoding(FontEncoding::win_ansi());
+        assert!(document.write(&mut Vec::default()).is_ok());
+    }
+
+    #[test]
+    fn document_stats() {
+        let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+        let font = document.create_font("Type1".into(), "Helvetica".into());
+
+        let page = document.create_page();
+        for content in ["Hello", "World"] {
+            let text = Text::builder()
+                .with_content(content)
+                .at(Position::from_mm(20.0, 20.0))
+                .build();
+            page.add_text(text, font.clone());
+        }
+        page.fill_path(Rectangle::from_units(0.0, 0.0, 10.0, 10.0), Color::Gray(0));
+        document.create_page();
+
+        let stats = document.stats().unwrap();
+        assert_eq!(stats.page_count(), 2);
+        assert_eq!(stats.pages()[0].operations(), 3);
+        assert_eq!(stats.empty_pages(), [1]);
+        assert_eq!(stats.font_usage().get(&font), Some(&2));
+        assert_eq!(stats.image_usage(), [0, 0]);
+
+        let mut writer = Vec::default();
+        document.write(&mut writer).unwrap();
+        assert_eq!(stats.estimated_size(), writer.len());
+    }
+
+    #[test]
+    fn document_stats_with_short_image_source() {
+        let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+        let image = Image::from_raw_source(|| Ok(&b"RGB"[..]), 2, 1, ColorSpace::DeviceRgb)
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+        document.create_page().add_image(image);
+
+        let result = document.stats();
+        assert_eq!(
+            result.map_err(|err| err.kind()),
+            Err(std::io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn open_action() {
+        let mut document = Document::default();
+        document.create_page().set_mediabox(Rectangle::A4);
+        let page = document.create_page().id();
+
+        // ids are only unique within a document, so the page must come after the last one
+        let mut other = Document::default();
+        for _ in 0..3 {
+            other.create_page();
+        }
+        let unknown = other.current_page().unwrap().id();
+        assert_eq!(
+            document.set_open_action(Destination::Fit { page: unknown }),
+            Err(DestinationErr::UnknownPage)
+        );
+
+        let destination = Destination::Xyz {
+            page,
+            left: None,
+            top: Some(Unit::from_unit(500.0)),
+            zoom: Some(1.5),
+        };
+        document.set_open_action(destination).unwrap();
+
+        let mut writer = Vec::default();
+        document.write_lenient(&mut writer).unwrap();
+        let output = String::from_utf8_lossy(&writer);
+        assert!(output.contains("/OpenAction [5 0 R /XYZ null 500 1.5]"));
+    }
+
+    #[test]
+    fn named_destinations() {
+        let mut document = Document::default();
+        let page = document.create_page().id();
+
+        assert_eq!(
+            document.set_open_action(DestinationRef::named("intro")),
+            Err(DestinationErr::UnknownName)
+        );
+
+        let intro = document
+            .add_named_destination("intro", Destination::Fit { page })
+            .unwrap();
+        assert_eq!(intro.name(), "intro");
+        document.set_open_action(intro).unwrap();
+
+        let mut writer = Vec::default();
+        document.write_lenient(&mut writer).unwrap();
+        let output = String::from_utf8_lossy(&writer);
+        assert!(output.contains("/Names << /Dests 5 0 R >>"));
+        assert!(output.contains("/OpenAction (intro)"));
+        assert!(output.contains("<< /Names [\n(intro) [3 0 R /Fit ]\n]"));
+    }
+
+    #[test]
+    fn form_fields() {
+        let mut document = Document::default();
+        let page = document.create_page().id();
+        let mut other = Document::default();
+        other.create_page();
+        let other_page = other.create_page().id();
+        let rect = Rectangle::from_units(20., 20., 32., 32.);
+
+        assert_eq!(
+            document.add_form_field(CheckBox::new("terms", other_page, rect)),
+            Err(FormFieldErr::UnknownPage)
+        );
+        assert_eq!(
+            document.add_form_field(CheckBox::new("terms", page.clone(), rect)),
+            Ok(())
+        );
+        assert_eq!(
+            document.add_form_field(CheckBox::new("terms", page, rect)),
+            Err(FormFieldErr::DuplicateName)
+        );
+
+        let mut writer = Vec::default();
+        document.write_lenient(&mut writer).unwrap();
+        let output = String::from_utf8_lossy(&writer);
+        assert!(output.contains("/AcroForm 5 0 R"));
+        assert!(output.contains("/Annots [7 0 R]"));
+        assert!(output.contains("<< /Fields [6 0 R] >>"));
+    }
+
+    #[test]
+    fn custom_file_id() {
+        let write = |file_id: FileId, pages: usize| {
+            let mut document = Document::builder().with_file_id(file_id).build();
+            for _ in 0..pages {
+                document.create_page().set_mediabox(Rectangle::A4);
+            }
+
+            let mut writer = Vec::default();
+            document.write_lenient(&mut writer).unwrap();
+            let output = String::from_utf8_lossy(&writer).into_owned();
+            let id = output.split_once("/ID ").unwrap().1;
+            id[..id.find(']').unwrap()].to_string()
+        };
+
+        let seed = || FileId::Seed(b"invoice-2025-0042".to_vec());
+        assert_eq!(write(seed(), 1), write(seed(), 2));
+        assert_ne!(write(FileId::OffsetsHash, 1), write(FileId::OffsetsHash, 2));
+
+        let fixed = write(FileId::Fixed(*b"0123456789abcdef"), 1);
+        assert!(fixed.starts_with("[<30313233343536373839616263646566>"));
+    }
+
+    #[test]
+    fn crlf_output_policy() {
+        let mut document = Document::builder()
+            .with_output_policy(
+                OutputPolicy::new()
+                    .with_binary_comment(true)
+                    .with_line_ending(LineEnding::CrLf),
+            )
+            .build();
+        document.create_page().set_mediabox(Rectangle::A4);
+
+        let mut writer = Vec::default();
+        document.write(&mut writer).unwrap();
+
+        assert!(writer.starts_with(b"%PDF-2.0\r\n%\xE2\xE3\xCF\xD3\r\n1 0 obj"));
+        let output = String::from_utf8_lossy(&writer);
+        assert!(output.contains("xref\r\n0 3\r\n0000000018 00000 n\r\n"));
+    }
+
+    #[test]
+    fn font_embedding_policy() {
+        let mut document = Document::builder()
+            .with_font_embedding(FontEmbedding::EmbedNonStandard)
+            .build();
+        document.create_font("Type1".into(), "Helvetica".into());
+        assert!(document.write(&mut Vec::default()).is_ok());
+
+        document.create_font("TrueType".into(), "CorporateSans".into());
+        let err = document.write(&mut Vec::default()).unwrap_err();
+        assert_eq!(
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<FontEmbeddingErr>()),
+            Some(&FontEmbeddingErr::NotEmbedded {
+                base_font: "CorporateSans".to_string()
+            })
+        );
+
+        let mut document = Document::builder()
+            .with_font_embedding(FontEmbedding::NeverEmbed)
+            .build();
+        document.create_font("TrueType".into(), "CorporateSans".into());
+        assert!(document.write(&mut Vec::default()).is_ok());
+        assert_eq!(
+            document.validate(&ColorLint::new()).issues(),
+            [Issue::UnembeddedFont {
+                base_font: "CorporateSans".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn extracted_icc_colors() {
+        let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+        let unused_profile = document.create_icc_profile(b"gray".to_vec(), ColorSpace::DeviceGray);
+        let profile = document.create_icc_profile(b"rgb".to_vec(), ColorSpace::DeviceRgb);
+
+        let square = Rectangle::from_units(0., 0., 10., 10.);
+        document
+            .create_page()
+            .fill_path(square, Color::icc(unused_profile, &[0]));
+        document
+            .create_page()
+            .fill_path(square, Color::icc(profile, &[255, 0, 0]));
+
+        let extract = document.extract_pages(1..);
+
+        let mut writer = Vec::default();
+        extract.write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        %PDF-2.0
+        1 0 obj
+        << /Type /Catalog 
+        /Pages 2 0 R >>
+        endobj
+
+        2 0 obj
+        << /Type /Pages 
+        /MediaBox [0 0 297.6378 419.52756]
+        /Kids [4 0 R]
+        /Count 1 >>
+        endobj
+
+        4 0 obj
+        << /Type /Page 
+        /Parent 2 0 R
+        /Resources << /ColorSpace << /ICC4 [/ICCBased 3 0 R] >> >>
+        /Contents 5 0 R
+        >>
+        endobj
+
+
+        5 0 obj
+        << /Length 33 >>
+        stream
+        /ICC4 cs
+        1 0 0 sc
+        0 0 10 10 re
+        f
+
+        endstream
+        endobj
+
+        3 0 obj
+        << /N 3 /Alternate /DeviceRGB /Length 3 >>
+        stream
+        rgb
+        endstream
+        endobj
+
+        xref
+        0 5
+        0000000010 00000 n 
+        0000000061 00000 n 
+        0000000155 00000 n 
+        0000000280 00000 n 
+        0000000364 00000 n 
+        trailer
+               << /Size 5
+               /Root 1 0 R
+               /ID [<b64fe0d6c4ade81f48b4c8ac44a4d3a4>
+                  <b64fe0d6c4ade81f48b4c8ac44a4d3a4>
+                  ]
+               >>
+        startxref
+        444
+        %%EOF
+        ");
+    }
+
+    #[test]
+    fn extracted_shared_images() {
+        let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+        let profile = document.create_icc_profile(b"gray".to_vec(), ColorSpace::DeviceGray);
+        let image = |color_space| {
+            Image::from_raw(b"GRAY".to_vec(), 2, 2, color_space)
+                .unwrap()
+                .with_compression_level(0)
+                .at(Position::from_units(0., 0.))
+                .build()
+        };
+        let unused_image = document.add_image(image(ColorSpace::DeviceGray));
+        let image = document.add_image(image(ColorSpace::Icc(profile)));
+
+        let position = Position::from_units(10., 10.);
+        document.create_page().draw_image(&unused_image, position);
+        document.create_page().draw_image(&image, position);
+
+        let extract = document.extract_pages(1..);
+
+        let mut writer = Vec::default();
+        extract.write(&mut writer).unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        %PDF-2.0
+        1 0 obj
+        << /Type /Catalog 
+        /Pages 2 0 R >>
+        endobj
+
+        2 0 obj
+        << /Type /Pages 
+        /MediaBox [0 0 297.6378 419.52756]
+        /Kids [5 0 R]
+        /Count 1 >>
+        endobj
+
+        5 0 obj
+        << /Type /Page 
+        /Parent 2 0 R
+        /Resources << /XObject << /Im1 4 0 R  >> >>
+        /Contents 6 0 R
+        >>
+        endobj
+
+
+        6 0 obj
+        << /Length 28 >>
+        stream
+        q
+        2 0 0 2 10 10 cm
+        /Im1 Do
+        Q
+        endstream
+        endobj
+
+        4 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 2
+        /ColorSpace [/ICCBased 3 0 R] 
+        /BitsPerComponent 8
+        /Length 4 >>
+        stream
+        GRAY
+        endstream
+        endobj
+
+        3 0 obj
+        << /N 1 /Alternate /DeviceGray /Length 4 >>
+        stream
+        gray
+        endstream
+        endobj
+
+        xref
+        0 7
+        0000000010 00000 n 
+        0000000061 00000 n 
+        0000000155 00000 n 
+        0000000264 00000 n 
+        0000000265 00000 n 
+        0000000344 00000 n 
+        0000000501 00000 n 
+        trailer
+               << /Size 7
+               /Root 1 0 R
+               /ID [<da5a0b61fbcc0adea726cc9a7e15d14d>
+                  <da5a0b61fbcc0adea726cc9a7e15d14d>
+                  ]
+               >>
+        startxref
+        583
+        %%EOF
+        ");
+    }
+
+    #[test]
+    fn extracted_pages() {
+        let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+        let unused_font = document.create_font("Type1".into(), "Courier".into());
+        let font_id = document.create_font("Type1".into(), "Helvetica".into());
+
+        for page_num in 1..=4 {
+            let txt = Text::builder()
+                .with_content(format!("Page {page_num}"))
+                .at(Position::from_mm(10., 130.))
+                .build();
+            document.create_page().add_text(txt, font_id.clone());
+        }
+        document.create_page().add_text(
+            Text::builder().at(Position::from_mm(0., 0.)).build(),
+            unused_font,
+        );
+
+        let extract = document.extract_pages(2..4);
+
+        let mut writer = Vec::default();
+        extract.write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        %PDF-2.0
+        1 0 obj
+        << /Type /Catalog 
+        /Pages 2 0 R >>
+        endobj
+
+        2 0 obj
+        << /Type /Pages 
+        /MediaBox [0 0 297.6378 419.52756]
+        /Kids [4 0 R
+               6 0 R]
+        /Count 2 >>
+        endobj
+
+        4 0 obj
+        << /Type /Page 
+        /Parent 2 0 R
+        /Resources << /Font << /F1 3 0 R  >> >>
+        /Contents 5 0 R
+        >>
+        endobj
+
+
+        6 0 obj
+        << /Type /Page 
+        /Parent 2 0 R
+        /Resources << /Font << /F1 3 0 R  >> >>
+        /Contents 7 0 R
+        >>
+        endobj
+
+
+        5 0 obj
+        << /Length 74 >>
+        stream
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        28.346457 368.50394 Td
+        (Page 3) Tj
+        ET
+
+        endstream
+        endobj
+
+        7 0 obj
+        << /Length 74 >>
+        stream
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        28.346457 368.50394 Td
+        (Page 4) Tj
+        ET
+
+        endstream
+        endobj
+
+        3 0 obj
+        << /Type /Font 
+        /Subtype /Type1 
+        /BaseFont /Helvetica 
+        >>
+        endobj
+
+        xref
+        0 9
+        0000000010 00000 n 
+        0000000061 00000 n 
+        0000000168 00000 n 
+        0000000273 00000 n 
+        0000000274 00000 n 
+        0000000379 00000 n 
+        0000000380 00000 n 
+        0000000505 00000 n 
+        0000000630 00000 n 
+        trailer
+               << /Size 9
+               /Root 1 0 R
+               /ID [<d2d9b0bb1857846d89dda22308320469>
+                  <d2d9b0bb1857846d89dda22308320469>
+                  ]
+               >>
+        startxref
+        704
+        %%EOF
+        ");
+    }
+
+    #[test]
+    fn strict_media_boxes() {
+        let mut document = Document::default();
+        document.create_page();
+        assert!(document.write(&mut Vec::default()).is_ok());
+
+        let mut document = Document::builder()
+            .with_output_policy(OutputPolicy::new().with_strict_media_boxes(true))
+            .build();
+        document.create_page();
+        let err = document.write(&mut Vec::default()).unwrap_err();
+        assert_eq!(
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<Deviation>()),
+            Some(&Deviation::MissingMediaBox { page: 0 })
+        );
+    }
+
+    #[test]
+    fn lenient_write() {
+        let mut document = Document::default();
+        document.create_font("Type1".into(), "Corporate Sans".into());
+        document.create_page();
+        document
+            .create_page()
+            .set_mediabox(Rectangle::from_units(0., 0., 20_000., 1.));
+
+        let err = document.write(&mut Vec::default()).unwrap_err();
+        assert_eq!(
+            err.get_ref()
+                .and_then(|err| err.downcast_ref::<Deviation>()),
+            Some(&Deviation::InvalidName {
+                name: "Corporate Sans".to_string()
+            })
+        );
+
+        let mut writer = Vec::default();
+        let report = document.write_lenient(&mut writer).unwrap();
+        assert_eq!(
+            report.deviations(),
+            [
+                Deviation::InvalidName {
+                    name: "Corporate Sans".to_string()
+                },
+                Deviation::MissingMediaBox { page: 0 },
+                Deviation::PageSizeOutOfRange {
+                    page: 1,
+                    media_box: Rectangle::from_units(0., 0., 20_000., 1.)
+                },
+            ]
+        );
+
+        let output = String::from_utf8(writer).unwrap();
+        assert!(output.contains("/BaseFont /Corporate#20Sans"));
+        assert!(output.contains("/MediaBox [0 0 595.2756 841.8898]"));
+        assert!(output.contains("/MediaBox [0 0 14400 3]"));
+    }
+
+    #[test]
+    fn simple_document() {
+        let document = create_sample_doc();
+
+        let mut writer = Vec::default();
+        document.write(&mut writer).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        %PDF-2.0
+        1 0 obj
+        << /Type /Catalog 
+        /Pages 2 0 R >>
+        endobj
+
+        2 0 obj
+        << /Type /Pages 
+        /Kids [3 0 R]
+        /Count 1 >>
+        endobj
+
+        3 0 obj
+        << /Type /Page 
+        /Parent 2 0 R
+        /Resources <<  >>
+        /MediaBox [0 0 595.2756 841.8898]>>
+        endobj
+
+
+        5 0 obj
+        << /Type /Font 
+        /Subtype /Type1 
+        /BaseFont /Helvetica 
+        >>
+        endobj
+
+        xref
+        0 4
+        0000000010 00000 n 
+        0000000061 00000 n 
+        0000000120 00000 n 
+        0000000221 00000 n 
+        trailer
+               << /Size 4
+               /Root 1 0 R
+               /ID [<0f91bdb053d717818b369e7972856f05>
+                  <0f91bdb053d717818b369e7972856f05>
+                  ]
+               >>
         startxref
-        294
+        295
         %%EOF
         ");
     }
+
+    #[test]
+    fn impose_roll() {
+        let mut document = Document::builder()
+            .with_roll(Roll::new(Unit::from_mm(80.0)))
+            .build();
+        let font_id = document.create_font("Type1".into(), "Helvetica".into());
+        let txt = Text::builder()
+            .with_content("Coffee")
+            .at(Position::from_mm(0.0, -6.0))
+            .build();
+        document.create_page().add_text(txt, font_id);
+
+        // the sheets of the imposed document are separate pages of their own size
+        let booklet = document.impose(&Imposition::booklet(Rectangle::A4));
+        assert_eq!(booklet.pages.len(), 2);
+        assert!(
+            booklet
+                .pages
+                .iter()
+                .all(|page| page.media_box() == Some(Rectangle::A4))
+        );
+    }
 }
@@ -1,6 +1,9 @@
 //! Implementation of PDF object reference.
 
 use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
     io::{Error, Write},
     marker::PhantomData,
 };
@@ -11,7 +14,6 @@ use std::{
 /// object number, the generation number, and the keyword R (with whitespace separating each part).
 ///
 /// Example: `4 0 R`
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct ObjId<T = ()> {
     /// Identifier of referenced object.
     id: u64,
@@ -29,6 +31,40 @@ impl<T> Clone for ObjId<T> {
     }
 }
 
+// NOTE: traits are implemented manually, because deriving them would require `T` to implement
+//       them as well, even though `T` is only a marker.
+impl<T> fmt::Debug for ObjId<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ObjId").field(&self.id).finish()
+    }
+}
+
+impl<T> PartialEq for ObjId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Eq for ObjId<T> {}
+
+impl<T> PartialOrd for ObjId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for ObjId<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T> Hash for ObjId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
 impl<T> ObjId<T> {
     /// Marker indicating start of an object section
     const START_OBJ_MARKER: &[u8] = b"obj";
@@ -54,6 +90,20 @@ impl<T> ObjId<T> {
         })
     }
 
+    /// Returns the object number of the referenced object.
+    pub(crate) fn number(&self) -> u64 {
+        self.id
+    }
+
+    /// Creates an [`ObjId`] referring to the object with the given number, e.g. for handles that
+    /// store only the object number to stay [`Copy`].
+    pub(crate) fn from_number(id: u64) -> Self {
+        Self {
+            id,
+            _marker: PhantomData,
+        }
+    }
+
     pub(crate) fn cast<U>(self) -> ObjId<U> {
         ObjId {
             id: self.id,
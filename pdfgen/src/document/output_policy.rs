@@ -0,0 +1,114 @@
+//! Policies on the layout of the bytes of a written PDF file.
+
+/// End-of-line marker used in the file structure of a PDF file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// LINE FEED only, as used on Unix systems.
+    #[default]
+    Lf,
+
+    /// CARRIAGE RETURN followed by a LINE FEED, as used on Windows and by many mail gateways.
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the bytes of this end-of-line marker.
+    pub(crate) fn marker(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+
+    /// Returns the two-byte end-of-line marker of cross-reference entries, which are required to
+    /// be exactly 20 bytes long. With [`LineEnding::Lf`], the line feed is preceded by a space.
+    pub(crate) fn xref_marker(self) -> &'static [u8] {
+        match self {
+            LineEnding::Lf => b" \n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+/// Policy on how the file structure of a PDF file is written, for the benefit of tools handling
+/// PDF files as opaque data, such as mail gateways and legacy transfer tools, and on how strictly
+/// it's checked before writing.
+///
+/// The [`LineEnding`] applies to the file header and the cross-reference section, where the
+/// specification defines the exact end-of-line markers. Objects always end their lines with a
+/// line feed, which is allowed anywhere in the file.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, LineEnding, OutputPolicy};
+/// let document = Document::builder()
+///     .with_output_policy(
+///         OutputPolicy::new()
+///             .with_binary_comment(true)
+///             .with_line_ending(LineEnding::CrLf),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct OutputPolicy {
+    /// Whether a comment line with binary characters is written after the header.
+    binary_comment: bool,
+
+    /// End-of-line marker used in the file structure.
+    line_ending: LineEnding,
+
+    /// Whether pages without a media box fail writing, instead of using the A4 page size.
+    strict_media_boxes: bool,
+}
+
+impl OutputPolicy {
+    /// Comment written after the header, containing 4 bytes above 127 so that tools sniffing for
+    /// binary content treat the file as binary data.
+    const BINARY_COMMENT: &[u8] = b"%\xE2\xE3\xCF\xD3";
+
+    /// Creates a new `OutputPolicy` without the binary comment, using [`LineEnding::Lf`], and
+    /// using the A4 page size for pages without a media box.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether the comment line with binary characters (`%âãÏÓ`) is written after the
+    /// header. The comment is recommended by the specification for any file containing binary
+    /// data, and is required by PDF/A.
+    pub fn with_binary_comment(mut self, binary_comment: bool) -> Self {
+        self.binary_comment = binary_comment;
+        self
+    }
+
+    /// Sets the [`LineEnding`] used in the file structure.
+    pub fn with_line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+
+    /// Sets whether [`Document::write`] fails with [`Deviation::MissingMediaBox`] for pages without
+    /// a media box and without a default page size to inherit, instead of using the A4 page size.
+    ///
+    /// [`Document::write`]: crate::Document::write
+    /// [`Deviation::MissingMediaBox`]: crate::Deviation::MissingMediaBox
+    pub fn with_strict_media_boxes(mut self, strict_media_boxes: bool) -> Self {
+        self.strict_media_boxes = strict_media_boxes;
+        self
+    }
+
+    /// Returns the [`LineEnding`] used in the file structure.
+    pub(crate) fn line_ending(self) -> LineEnding {
+        self.line_ending
+    }
+
+    /// Returns `true` if pages without a media box fail writing.
+    pub(crate) fn strict_media_boxes(self) -> bool {
+        self.strict_media_boxes
+    }
+
+    /// Returns the comment line written after the header, if any.
+    pub(crate) fn binary_comment(self) -> Option<&'static [u8]> {
+        self.binary_comment.then_some(Self::BINARY_COMMENT)
+    }
+}
@@ -0,0 +1,64 @@
+//! Identification of the software that produced a PDF file.
+
+use super::sanitize::SanitizePolicy;
+
+/// Identification of the software that produced a document, written as the `Producer` of both the
+/// document information dictionary and the XMP metadata, so PDF processors and archiving systems
+/// see the same value. Documents without a `Producer` carry no producer identification at all.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, Producer};
+/// // written as "ACME Billing 4.2 (pdfgen x.y.z)", with the version of this crate
+/// let document = Document::builder()
+///     .with_producer(Producer::new("ACME Billing 4.2"))
+///     .build();
+///
+/// // written as "ACME Billing" only
+/// let document = Document::builder()
+///     .with_producer(Producer::new("ACME Billing").without_version())
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Producer {
+    /// Name of the producing software.
+    name: String,
+
+    /// Whether the name and version of this crate are appended to the name.
+    append_version: bool,
+}
+
+impl Producer {
+    /// Creates a new `Producer` with the given name, which is followed by the name and version of
+    /// this crate, e.g. `ACME Billing 4.2 (pdfgen 0.4.0)`.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            append_version: true,
+        }
+    }
+
+    /// Writes the name of this `Producer` as is, without the name and version of this crate.
+    pub fn without_version(mut self) -> Self {
+        self.append_version = false;
+        self
+    }
+
+    /// Returns this `Producer` with its name sanitized according to the given [`SanitizePolicy`].
+    pub(crate) fn sanitized(self, policy: &SanitizePolicy) -> Self {
+        Self {
+            name: policy.metadata(&self.name),
+            ..self
+        }
+    }
+
+    /// Returns the producer identification that is written into the document.
+    pub(crate) fn identification(&self) -> String {
+        if self.append_version {
+            format!("{} (pdfgen {})", self.name, env!("CARGO_PKG_VERSION"))
+        } else {
+            self.name.clone()
+        }
+    }
+}
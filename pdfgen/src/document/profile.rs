@@ -0,0 +1,121 @@
+//! Ready-made configurations of a PDF [`Document`] for common output targets.
+//!
+//! [`Document`]: crate::Document
+
+use crate::types::hierarchy::content::image::Image;
+
+use super::{font_embedding::FontEmbedding, output_policy::OutputPolicy, producer::Producer};
+
+/// Version of the PDF specification a [`Document`] is written against, declared in the header of
+/// the PDF file.
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PdfVersion {
+    /// PDF 1.4, the first version supporting transparency.
+    V1_4,
+
+    /// PDF 1.6, the base version of PDF/X-4 print production files.
+    V1_6,
+
+    /// PDF 1.7, standardized as ISO 32000-1 and the base version of PDF/A-2 archival files.
+    V1_7,
+
+    /// PDF 2.0, standardized as ISO 32000-2.
+    #[default]
+    V2_0,
+}
+
+impl PdfVersion {
+    /// Returns the header marker of a PDF file with this version, e.g. `%PDF-2.0`.
+    pub(crate) fn header(self) -> &'static [u8] {
+        match self {
+            PdfVersion::V1_4 => b"%PDF-1.4",
+            PdfVersion::V1_6 => b"%PDF-1.6",
+            PdfVersion::V1_7 => b"%PDF-1.7",
+            PdfVersion::V2_0 => b"%PDF-2.0",
+        }
+    }
+}
+
+/// Preset configuration of a [`Builder`] for a common output target, applied with
+/// [`Builder::with_profile`]. A profile selects the [`PdfVersion`], [`OutputPolicy`],
+/// [`FontEmbedding`] policy, image compression and [`Producer`] of the document.
+///
+/// [`Builder`]: super::Builder
+/// [`Builder::with_profile`]: super::Builder::with_profile
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Profile {
+    /// Long-term archival of documents, based on PDF/A-2.
+    ArchivalPdfA,
+
+    /// Documents distributed over the web, targeting the widest range of viewers.
+    WebOptimized,
+
+    /// Documents sent to a print shop, based on PDF/X-4.
+    PrintProduction,
+
+    /// Drafts and other intermediate documents, using the latest PDF version.
+    Draft,
+}
+
+impl Profile {
+    /// Returns the [`PdfVersion`] used by documents built with this profile.
+    pub fn version(self) -> PdfVersion {
+        match self {
+            Profile::ArchivalPdfA | Profile::WebOptimized => PdfVersion::V1_7,
+            Profile::PrintProduction => PdfVersion::V1_6,
+            Profile::Draft => PdfVersion::V2_0,
+        }
+    }
+
+    /// Returns the [`OutputPolicy`] used by documents built with this profile. Archival and print
+    /// production files are marked as binary with the binary comment line, as required by PDF/A
+    /// and PDF/X.
+    pub fn output_policy(self) -> OutputPolicy {
+        match self {
+            Profile::ArchivalPdfA | Profile::PrintProduction => {
+                OutputPolicy::new().with_binary_comment(true)
+            }
+            Profile::WebOptimized | Profile::Draft => OutputPolicy::new(),
+        }
+    }
+
+    /// Returns the [`FontEmbedding`] policy of documents built with this profile. PDF/A and PDF/X
+    /// require every font to be embedded, so archival and print production files are checked
+    /// against [`FontEmbedding::EmbedAll`].
+    pub fn font_embedding(self) -> Option<FontEmbedding> {
+        match self {
+            Profile::ArchivalPdfA | Profile::PrintProduction => Some(FontEmbedding::EmbedAll),
+            Profile::WebOptimized | Profile::Draft => None,
+        }
+    }
+
+    /// Returns the zlib compression level that the uncompressed samples of images are compressed
+    /// with in documents built with this profile, see [`Builder::with_image_compression`]. Documents
+    /// distributed over the web are compressed with the highest level, producing the smallest
+    /// files.
+    ///
+    /// [`Builder::with_image_compression`]: super::Builder::with_image_compression
+    pub fn image_compression(self) -> Option<u8> {
+        match self {
+            Profile::ArchivalPdfA | Profile::PrintProduction => {
+                Some(Image::DEFAULT_COMPRESSION_LEVEL)
+            }
+            Profile::WebOptimized => Some(Image::MAX_COMPRESSION_LEVEL),
+            Profile::Draft => None,
+        }
+    }
+
+    /// Returns the [`Producer`] identifying this crate in the document information dictionary and
+    /// XMP metadata of documents built with this profile. PDF/A and PDF/X files carry XMP metadata,
+    /// so archival and print production files are identified.
+    pub fn producer(self) -> Option<Producer> {
+        match self {
+            Profile::ArchivalPdfA | Profile::PrintProduction => {
+                Some(Producer::new(concat!("pdfgen ", env!("CARGO_PKG_VERSION"))).without_version())
+            }
+            Profile::WebOptimized | Profile::Draft => None,
+        }
+    }
+}
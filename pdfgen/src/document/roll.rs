@@ -0,0 +1,77 @@
+//! Documents printed on a continuous roll of paper, such as receipts.
+
+use crate::types::hierarchy::primitives::unit::Unit;
+
+/// A continuous roll of paper of a fixed width, e.g. of a thermal receipt printer. A document
+/// printed on a roll, built with [`Builder::with_roll`], has a single page as wide as the roll and
+/// as tall as its content, which grows as content is added, and is cut after the content.
+///
+/// Content is laid out from the top down without knowing its length in advance, e.g. starting at
+/// a height of 0 and continuing at negative heights. Rolls longer than about 5 metres are written
+/// with a larger `UserUnit` (PDF 1.6), see [`Page::set_auto_size`].
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{
+/// #     Document, Roll,
+/// #     types::hierarchy::{
+/// #         content::text::Text,
+/// #         primitives::{rectangle::Position, unit::Unit},
+/// #     },
+/// # };
+/// let mut document = Document::builder()
+///     .with_roll(Roll::new(Unit::from_mm(80.0)))
+///     .build();
+/// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+///
+/// for (idx, item) in ["Coffee", "Bagel"].into_iter().enumerate() {
+///     // every page created in a document printed on a roll is the same page
+///     let page = document.create_page();
+///     let txt = Text::builder()
+///         .with_content(item)
+///         .at(Position::from_mm(0.0, -6.0 * idx as f32))
+///         .build();
+///     page.add_text(txt, font_id.clone());
+/// }
+/// ```
+///
+/// [`Builder::with_roll`]: crate::Builder::with_roll
+/// [`Page::set_auto_size`]: crate::types::hierarchy::page::Page::set_auto_size
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Roll {
+    /// Width of the roll.
+    width: Unit,
+
+    /// Margin left above and below the content.
+    margin: Unit,
+}
+
+impl Roll {
+    /// Default margin left above and below the content.
+    const MARGIN: Unit = Unit::from_mm(5.0);
+
+    /// Creates a new `Roll` of the given width, with a margin of 5 mm above and below the content.
+    pub fn new(width: Unit) -> Self {
+        Self {
+            width,
+            margin: Self::MARGIN,
+        }
+    }
+
+    /// Sets the margin left above and below the content.
+    pub fn with_margin(mut self, margin: Unit) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    /// Returns the width of this `Roll`.
+    pub(crate) fn width(&self) -> Unit {
+        self.width
+    }
+
+    /// Returns the margin left above and below the content.
+    pub(crate) fn margin(&self) -> Unit {
+        self.margin
+    }
+}
@@ -0,0 +1,248 @@
+//! Sanitization of untrusted strings placed into the content, names and metadata of a PDF file.
+
+/// Policy on how untrusted strings, e.g. submitted through a web form, are sanitized before they're
+/// placed into a document, so that they can't produce malformed or hostile PDF syntax, or text that
+/// displays differently from what it contains.
+///
+/// A document built with [`Builder::with_sanitization`] applies the policy to the content of texts
+/// drawn on its pages, the names of its fonts, the keys and values of document part metadata and
+/// its [`Producer`]. Other strings can be sanitized with [`SanitizePolicy::text`],
+/// [`SanitizePolicy::metadata`] and [`SanitizePolicy::name`] directly.
+///
+/// Sanitized strings:
+///
+/// * are normalized with the function set by [`SanitizePolicy::with_normalization`], if any.
+/// * have control characters removed, except for line feeds and tabs in text content, which break
+///   lines and align text at tab stops. Carriage returns, line and paragraph separators are turned
+///   into line feeds, and tabs in metadata and names into spaces.
+/// * have invisible formatting characters removed, such as bidirectional overrides and zero-width
+///   characters, which could make text display differently from what it contains.
+/// * have all other Unicode white-space characters, such as no-break spaces, turned into spaces.
+/// * are cut to a maximum length, well within the implementation limits of Annex C.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, SanitizePolicy};
+/// let policy = SanitizePolicy::new().with_max_text_length(200);
+/// assert_eq!(policy.text("Hello\u{202E}dlrow\u{0}"), "Hellodlrow");
+/// assert_eq!(policy.name("Times New Roman"), "Times#20New#20Roman");
+///
+/// let document = Document::builder().with_sanitization(policy).build();
+/// ```
+///
+/// [`Builder::with_sanitization`]: crate::Builder::with_sanitization
+/// [`Producer`]: crate::Producer
+#[derive(Debug, Clone, Copy)]
+pub struct SanitizePolicy {
+    /// Whether invisible formatting characters are kept.
+    keep_invisible: bool,
+
+    /// Maximum number of characters of text content and metadata values.
+    max_text_length: usize,
+
+    /// Maximum number of bytes of names, before their characters are escaped.
+    max_name_length: usize,
+
+    /// Function normalizing the Unicode representation of sanitized strings.
+    normalization: Option<fn(&str) -> String>,
+}
+
+impl Default for SanitizePolicy {
+    fn default() -> Self {
+        Self {
+            keep_invisible: false,
+            max_text_length: Self::MAX_TEXT_LENGTH,
+            max_name_length: Self::MAX_NAME_LENGTH,
+            normalization: None,
+        }
+    }
+}
+
+impl SanitizePolicy {
+    /// Default maximum number of characters of text, which fits into the 32767 bytes a string is
+    /// limited to in any encoding.
+    const MAX_TEXT_LENGTH: usize = 8000;
+
+    /// Default maximum number of bytes of a name, which is the limit of Annex C.
+    const MAX_NAME_LENGTH: usize = 127;
+
+    /// Name used in place of names that are empty after sanitization, which aren't valid.
+    const EMPTY_NAME: &str = "_";
+
+    /// Creates a new `SanitizePolicy`, removing invisible formatting characters and limiting text
+    /// to 8000 characters and names to 127 bytes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether invisible formatting characters, such as bidirectional overrides and zero-width
+    /// joiners, are kept. Scripts such as Arabic or Devanagari may need them to display correctly.
+    pub fn with_invisible_characters(mut self, keep_invisible: bool) -> Self {
+        self.keep_invisible = keep_invisible;
+        self
+    }
+
+    /// Sets the maximum number of characters of text content and metadata values. Longer strings
+    /// are cut.
+    pub fn with_max_text_length(mut self, max_text_length: usize) -> Self {
+        self.max_text_length = max_text_length;
+        self
+    }
+
+    /// Sets the maximum number of bytes of names, before non-regular characters are escaped.
+    /// Longer names are cut.
+    pub fn with_max_name_length(mut self, max_name_length: usize) -> Self {
+        self.max_name_length = max_name_length;
+        self
+    }
+
+    /// Sets the function normalizing the Unicode representation of sanitized strings, applied
+    /// before any other step, e.g. the NFC normalization of the `unicode-normalization` crate.
+    pub fn with_normalization(mut self, normalization: fn(&str) -> String) -> Self {
+        self.normalization = Some(normalization);
+        self
+    }
+
+    /// Returns the given text sanitized for use as the content of a text, keeping line feeds and
+    /// tabs.
+    pub fn text(&self, text: &str) -> String {
+        self.sanitize(text, true)
+            .chars()
+            .take(self.max_text_length)
+            .collect()
+    }
+
+    /// Returns the given text sanitized for use as a metadata value, on a single line.
+    pub fn metadata(&self, text: &str) -> String {
+        self.sanitize(text, false)
+            .chars()
+            .take(self.max_text_length)
+            .collect()
+    }
+
+    /// Returns the given text sanitized for use as a name, with all characters other than regular
+    /// ASCII characters escaped as `#` followed by the hexadecimal code of each of their bytes
+    /// (7.3.5). Names that are empty after sanitization are replaced with `_`.
+    pub fn name(&self, text: &str) -> String {
+        let mut length = 0;
+        let name = self
+            .sanitize(text, false)
+            .chars()
+            .take_while(|ch| {
+                length += ch.len_utf8();
+                length <= self.max_name_length
+            })
+            .collect::<String>();
+
+        if name.is_empty() {
+            return Self::EMPTY_NAME.to_string();
+        }
+
+        name.bytes().fold(String::new(), |mut escaped, byte| {
+            if Self::is_regular(byte) {
+                escaped.push(char::from(byte));
+            } else {
+                escaped.push_str(&format!("#{byte:02X}"));
+            }
+            escaped
+        })
+    }
+
+    /// Returns the given text with control, invisible and white-space characters handled as
+    /// described in [`SanitizePolicy`]. Line breaks are kept as line feeds if `multiline`, or turned
+    /// into spaces otherwise.
+    fn sanitize(&self, text: &str, multiline: bool) -> String {
+        let text = match self.normalization {
+            Some(normalization) => normalization(text),
+            None => text.to_string(),
+        };
+
+        // a carriage return followed by a line feed is a single line break
+        text.replace("\r\n", "\n")
+            .chars()
+            .filter_map(|ch| match ch {
+                '\n' | '\r' | '\u{2028}' | '\u{2029}' | '\u{85}' if multiline => Some('\n'),
+                '\t' if multiline => Some('\t'),
+                '\n' | '\r' | '\u{2028}' | '\u{2029}' | '\u{85}' | '\t' => Some(' '),
+                _ if ch.is_control() => None,
+                _ if Self::is_invisible(ch) => self.keep_invisible.then_some(ch),
+                _ if ch.is_whitespace() => Some(' '),
+                _ => Some(ch),
+            })
+            .collect()
+    }
+
+    /// Returns whether the given character is an invisible formatting character: a bidirectional
+    /// control, a zero-width character, the byte order mark or an interlinear annotation character.
+    fn is_invisible(ch: char) -> bool {
+        matches!(
+            ch,
+            '\u{061C}'
+                | '\u{200B}'..='\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{2066}'..='\u{2069}'
+                | '\u{FEFF}'
+                | '\u{FFF9}'..='\u{FFFB}'
+        )
+    }
+
+    /// Returns whether the given byte is a regular character that can be written as itself in a
+    /// name, i.e. a printable ASCII character that isn't a delimiter or the number sign.
+    fn is_regular(byte: u8) -> bool {
+        matches!(byte, b'!'..=b'~')
+            && !matches!(
+                byte,
+                b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%' | b'#'
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SanitizePolicy;
+
+    #[test]
+    fn text() {
+        let policy = SanitizePolicy::new();
+
+        assert_eq!(
+            policy.text("Line\r\nnext\u{2028}last\tword\u{A0}end\u{7}"),
+            "Line\nnext\nlast\tword end"
+        );
+        assert_eq!(
+            policy.text("invoice\u{202E}fdp.exe\u{200B}"),
+            "invoicefdp.exe"
+        );
+        assert_eq!(
+            policy
+                .with_invisible_characters(true)
+                .text("\u{644}\u{200D}"),
+            "\u{644}\u{200D}"
+        );
+    }
+
+    #[test]
+    fn metadata() {
+        let policy = SanitizePolicy::new().with_max_text_length(12);
+
+        assert_eq!(policy.metadata("ACME\nBilling\r\n4.2"), "ACME Billing");
+        assert_eq!(
+            policy
+                .with_normalization(str::to_uppercase)
+                .metadata("acme"),
+            "ACME"
+        );
+    }
+
+    #[test]
+    fn name() {
+        let policy = SanitizePolicy::new().with_max_name_length(8);
+
+        assert_eq!(policy.name("A/B (#1)"), "A#2FB#20#28#231#29");
+        assert_eq!(policy.name("Grüße"), "Gr#C3#BC#C3#9Fe");
+        assert_eq!(policy.name("ÄÄÄÄÄ"), "#C3#84#C3#84#C3#84#C3#84");
+        assert_eq!(policy.name("\u{0}\u{202E}"), "_");
+    }
+}
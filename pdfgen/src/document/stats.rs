@@ -0,0 +1,164 @@
+//! Summary statistics of a [`Document`], collected without writing it.
+//!
+//! [`Document`]: crate::Document
+
+use std::{collections::BTreeMap, io};
+
+use crate::FontId;
+
+/// Summary statistics of a single page of a [`Document`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PageStats {
+    /// Number of content operations on the page, such as drawing a text or filling a path.
+    operations: usize,
+
+    /// Number of bytes of the content streams of the page.
+    content_size: usize,
+
+    /// Number of texts drawn with each font on the page.
+    fonts: BTreeMap<FontId, usize>,
+
+    /// Number of images drawn on the page.
+    images: usize,
+}
+
+impl PageStats {
+    /// Creates new `PageStats` from the given counts.
+    pub(crate) fn new(
+        operations: usize,
+        content_size: usize,
+        fonts: BTreeMap<FontId, usize>,
+        images: usize,
+    ) -> Self {
+        Self {
+            operations,
+            content_size,
+            fonts,
+            images,
+        }
+    }
+
+    /// Returns the number of content operations on the page, such as drawing a text, drawing an
+    /// image or filling a path, including the ones that save and restore the graphics state.
+    pub fn operations(&self) -> usize {
+        self.operations
+    }
+
+    /// Returns the number of bytes of the content streams of the page, before compression.
+    pub fn content_size(&self) -> usize {
+        self.content_size
+    }
+
+    /// Returns the number of texts drawn with each font on the page.
+    pub fn fonts(&self) -> &BTreeMap<FontId, usize> {
+        &self.fonts
+    }
+
+    /// Returns the number of images drawn on the page.
+    pub fn images(&self) -> usize {
+        self.images
+    }
+
+    /// Returns `true` if nothing is drawn on the page.
+    pub fn is_empty(&self) -> bool {
+        self.operations == 0
+    }
+}
+
+/// Summary statistics of a [`Document`], returned by [`Document::stats`], used e.g. to detect
+/// anomalies such as pages without any content in automated tests of document templates.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::primitives::rectangle::Rectangle};
+/// let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+/// document.create_page();
+///
+/// let stats = document.stats()?;
+/// assert_eq!(stats.page_count(), 1);
+/// assert_eq!(stats.empty_pages(), [0]);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`Document`]: crate::Document
+/// [`Document::stats`]: crate::Document::stats
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentStats {
+    /// Statistics of each page, in order.
+    pages: Vec<PageStats>,
+
+    /// Estimated number of bytes of the written document.
+    estimated_size: usize,
+}
+
+impl DocumentStats {
+    /// Creates new `DocumentStats` from the given page statistics and estimated size.
+    pub(crate) fn new(pages: Vec<PageStats>, estimated_size: usize) -> Self {
+        Self {
+            pages,
+            estimated_size,
+        }
+    }
+
+    /// Returns the number of pages of the document.
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Returns the [`PageStats`] of each page of the document, in order.
+    pub fn pages(&self) -> &[PageStats] {
+        &self.pages
+    }
+
+    /// Returns the indices of all pages that nothing is drawn on.
+    pub fn empty_pages(&self) -> Vec<usize> {
+        self.pages
+            .iter()
+            .enumerate()
+            .filter(|(_, page)| page.is_empty())
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Returns the number of texts drawn with each font in the whole document. Fonts that are not
+    /// used by any text are not included.
+    pub fn font_usage(&self) -> BTreeMap<FontId, usize> {
+        let mut usage = BTreeMap::new();
+        for (font_id, count) in self.pages.iter().flat_map(|page| &page.fonts) {
+            *usage.entry(font_id.clone()).or_default() += count;
+        }
+
+        usage
+    }
+
+    /// Returns the number of images drawn on each page of the document, in order.
+    pub fn image_usage(&self) -> Vec<usize> {
+        self.pages.iter().map(PageStats::images).collect()
+    }
+
+    /// Returns the estimated number of bytes of the written document, without any compression.
+    pub fn estimated_size(&self) -> usize {
+        self.estimated_size
+    }
+}
+
+/// Implementor of [`io::Write`] that discards all bytes, only counting them.
+#[derive(Debug, Default)]
+pub(crate) struct ByteCounter {
+    /// Number of bytes written so far.
+    pub(crate) count: usize,
+}
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.count += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+//! Checks of the texts drawn on the pages of a [`Document`] against the encodings of their fonts.
+//!
+//! [`Document`]: crate::Document
+
+use crate::types::hierarchy::primitives::font::Font;
+
+/// Possible errors that might be returned when a text drawn on a page of a [`Document`] can't be
+/// encoded in the encoding of its font.
+///
+/// [`Document`]: crate::Document
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum TextEncodingErr {
+    /// Indicates that a text contains characters that are not covered by the encoding of its
+    /// font, which would be shown as `.notdef` boxes.
+    #[error(
+        "Text '{text}' on page {page} contains characters that font '{base_font}' can't encode: \
+         {characters:?}"
+    )]
+    Unencodable {
+        /// Index of the page on which the text is drawn.
+        page: usize,
+
+        /// Name of the font the text is drawn with.
+        base_font: String,
+
+        /// Content of the text.
+        text: String,
+
+        /// Characters of the text that can't be encoded, in order of their first occurrence.
+        characters: Vec<char>,
+    },
+}
+
+impl TextEncodingErr {
+    /// Checks the given text drawn on the page with the given index against the encoding of the
+    /// given [`Font`].
+    pub(crate) fn check(page: usize, font: &Font, text: &str) -> Result<(), Self> {
+        let characters = font.unencodable(text);
+        if characters.is_empty() {
+            return Ok(());
+        }
+
+        Err(TextEncodingErr::Unencodable {
+            page,
+            base_font: font.base_font(),
+            text: text.to_string(),
+            characters,
+        })
+    }
+}
@@ -0,0 +1,101 @@
+//! Default style of the texts of a document, used when no font is given for a text.
+
+use crate::types::hierarchy::content::color::Color;
+
+/// Errors that might be returned when drawing texts with the default [`TextStyle`] of a
+/// [`Document`].
+///
+/// [`Document`]: crate::Document
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum TextStyleErr {
+    /// Indicates that a styled text is added to a page of a document without a default
+    /// [`TextStyle`].
+    #[error("Styled texts can only be added to pages of documents with a default text style.")]
+    MissingStyle,
+}
+
+/// Default font, size and color of the texts of a [`Document`], set with
+/// [`Builder::with_text_style`]. The document creates the font when it's built, and texts added
+/// with [`Page::add_styled_text`] are drawn with it, in the size and color of the style unless
+/// the text sets its own.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{
+/// #     Document, TextStyle,
+/// #     types::hierarchy::{
+/// #         content::{color::Color, text::Text},
+/// #         primitives::rectangle::Position,
+/// #     },
+/// # };
+/// let style = TextStyle::new("Times-Roman")
+///     .with_size(10)
+///     .with_color(Color::Gray(64));
+/// let mut document = Document::builder().with_text_style(style).build();
+///
+/// let page = document.create_page();
+/// let txt = Text::builder()
+///     .with_content("Drawn in 10 point Times-Roman")
+///     .at(Position::from_mm(20.0, 20.0))
+///     .build();
+/// page.add_styled_text(txt).unwrap();
+/// ```
+///
+/// [`Document`]: crate::Document
+/// [`Builder::with_text_style`]: crate::Builder::with_text_style
+/// [`Page::add_styled_text`]: crate::types::hierarchy::page::Page::add_styled_text
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextStyle {
+    /// Name of the standard Type 1 font the texts are drawn with.
+    base_font: String,
+
+    /// Font size of the texts in user space units.
+    size: u32,
+
+    /// Color the texts are filled with.
+    color: Color,
+}
+
+impl TextStyle {
+    /// Creates a new `TextStyle` drawing texts with the standard Type 1 font of the given name, in
+    /// the default size (12) and color (black) of texts.
+    pub fn new(base_font: impl Into<String>) -> Self {
+        Self {
+            base_font: base_font.into(),
+            size: 12,
+            color: Color::Rgb {
+                red: 0,
+                green: 0,
+                blue: 0,
+            },
+        }
+    }
+
+    /// Sets the font size of the texts.
+    pub fn with_size(mut self, size: u32) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets the color the texts are filled with.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Returns the name of the font the texts are drawn with.
+    pub(crate) fn base_font(&self) -> &str {
+        &self.base_font
+    }
+
+    /// Returns the font size of the texts.
+    pub(crate) fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Returns the color the texts are filled with.
+    pub(crate) fn color(&self) -> Color {
+        self.color
+    }
+}
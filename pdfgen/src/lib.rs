@@ -3,9 +3,18 @@
 //! `pdfgen` is a low-level library that offers fine-grained control over PDF syntax and
 //! PDF file generation.
 
+#[cfg(feature = "diff")]
+pub mod diff;
 pub mod types;
+pub mod validation;
 
 mod document;
-pub use document::Document;
+pub use document::{
+    Arena, Builder, CieColorSpaceId, DestinationId, Deviation, Document, DocumentStats, FileId,
+    FontEmbedding, FontEmbeddingErr, FontId, Fragment, FragmentErr, FragmentId, FunctionId, Handle,
+    IccProfileId, ImageId, Imposition, LabelSheet, LineEnding, OutputPolicy, PageId, PageStats,
+    PdfVersion, Producer, Profile, Roll, SanitizePolicy, TextEncodingErr, TextStyle, TextStyleErr,
+    WriteReport,
+};
 pub(crate) use document::{IdManager, ObjId};
 pub(crate) mod macros;
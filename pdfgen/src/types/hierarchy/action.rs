@@ -0,0 +1,288 @@
+//! Implementation of actions, which are performed by a PDF processor in response to an event, such
+//! as opening a page, closing the document or clicking a button, e.g. to advance a kiosk
+//! presentation, to track the progress of e-learning content or to submit a form.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use super::primitives::{identifier::Identifier, string::PdfString};
+
+/// Predefined actions that are performed by name (12.6.4.11), navigating through the pages of a
+/// document.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NamedAction {
+    /// Go to the next page of the document.
+    NextPage,
+
+    /// Go to the previous page of the document.
+    PrevPage,
+
+    /// Go to the first page of the document.
+    FirstPage,
+
+    /// Go to the last page of the document.
+    LastPage,
+}
+
+impl NamedAction {
+    /// Returns the [`Identifier`] of this named action.
+    fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            NamedAction::NextPage => Identifier::from_static(b"NextPage"),
+            NamedAction::PrevPage => Identifier::from_static(b"PrevPage"),
+            NamedAction::FirstPage => Identifier::from_static(b"FirstPage"),
+            NamedAction::LastPage => Identifier::from_static(b"LastPage"),
+        }
+    }
+}
+
+/// The format the values of the fields of a form are submitted in by [`Action::SubmitForm`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SubmitFormat {
+    /// Forms Data Format (FDF), the format PDF processors submit forms in by default.
+    #[default]
+    Fdf,
+
+    /// HTML form format, i.e. the fields are submitted like an HTML form using a POST request.
+    Html,
+
+    /// XML Forms Data Format (XFDF).
+    Xfdf,
+
+    /// The whole document is submitted as a PDF file.
+    Pdf,
+}
+
+impl SubmitFormat {
+    /// Returns the flags of a submit-form action selecting this format.
+    fn flags(self) -> u32 {
+        match self {
+            SubmitFormat::Fdf => 0,
+            SubmitFormat::Html => 1 << 2,
+            SubmitFormat::Xfdf => 1 << 5,
+            SubmitFormat::Pdf => 1 << 8,
+        }
+    }
+}
+
+/// An action performed by a PDF processor when the event it's assigned to occurs.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::Document;
+/// # use pdfgen::types::hierarchy::action::{Action, NamedAction, PageTrigger};
+/// let mut document = Document::default();
+/// let page = document.create_page();
+///
+/// // report the visited page and advance the presentation when the page is left
+/// page.set_action(PageTrigger::Open, Action::JavaScript("track('intro');".into()));
+/// page.set_action(PageTrigger::Close, Action::Named(NamedAction::NextPage));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Perform the given predefined [`NamedAction`].
+    Named(NamedAction),
+
+    /// Execute the given JavaScript script. Support for JavaScript varies between PDF processors,
+    /// and it's forbidden in PDF/A documents.
+    JavaScript(String),
+
+    /// Open the given URI, e.g. a web page in the browser.
+    Uri(String),
+
+    /// Submit the values of all fields of the interactive form to the given URL, in the given
+    /// [`SubmitFormat`].
+    SubmitForm {
+        /// The URL the values are submitted to.
+        url: String,
+
+        /// The format the values are submitted in.
+        format: SubmitFormat,
+    },
+
+    /// Reset all fields of the interactive form to their default values.
+    ResetForm,
+}
+
+impl Action {
+    const_identifiers! {
+        ACTION,
+        S: b"S",
+        NAMED,
+        N: b"N",
+        JAVA_SCRIPT: b"JavaScript",
+        JS: b"JS",
+        URI: b"URI",
+        SUBMIT_FORM,
+        F: b"F",
+        FS: b"FS",
+        URL: b"URL",
+        FLAGS,
+        RESET_FORM,
+    }
+
+    /// Writes the type of this `Action` followed by its parameters into the provided implementor
+    /// of [`Write`].
+    fn write_kind(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(match self {
+            Action::Named(named) => pdfgen_macros::write_chain! {
+                Self::NAMED.write(writer),
+                Self::N.write(writer),
+                named.identifier().write(writer),
+            },
+            Action::JavaScript(script) => pdfgen_macros::write_chain! {
+                Self::JAVA_SCRIPT.write(writer),
+                Self::JS.write(writer),
+                PdfString::text_string(script.as_str()).write_content(writer),
+                writer.write(b" "),
+            },
+            Action::Uri(uri) => pdfgen_macros::write_chain! {
+                Self::URI.write(writer),
+                Self::URI.write(writer),
+                PdfString::from(uri.as_str()).write_content(writer),
+                writer.write(b" "),
+            },
+            Action::SubmitForm { url, format } => pdfgen_macros::write_chain! {
+                Self::SUBMIT_FORM.write(writer),
+                // the URL is written as a URL file specification
+                Self::F.write(writer),
+                writer.write(b"<< "),
+                Self::FS.write(writer),
+                Self::URL.write(writer),
+                Self::F.write(writer),
+                PdfString::from(url.as_str()).write_content(writer),
+                writer.write(b" >> "),
+                if format.flags() != 0 {
+                    Self::FLAGS.write(writer),
+                    crate::write_fmt!(&mut *writer, "{} ", format.flags()),
+                },
+            },
+            Action::ResetForm => Self::RESET_FORM.write(writer)?,
+        })
+    }
+
+    /// Encode and write this `Action` as a dictionary into the provided implementor of [`Write`],
+    /// e.g. `<< /Type /Action /S /Named /N /NextPage >>`.
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::ACTION.write(writer),
+            Self::S.write(writer),
+
+            self.write_kind(writer),
+            writer.write(b">>"),
+        })
+    }
+}
+
+/// Events of a [`Page`] that an [`Action`] can be assigned to.
+///
+/// [`Page`]: super::page::Page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PageTrigger {
+    /// The page is opened, i.e. it becomes the visible page in a PDF processor.
+    Open,
+
+    /// The page is closed, i.e. it's no longer the visible page in a PDF processor.
+    Close,
+}
+
+impl PageTrigger {
+    /// Returns the key of this trigger in an additional-actions dictionary.
+    pub(crate) fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            PageTrigger::Open => Identifier::from_static(b"O"),
+            PageTrigger::Close => Identifier::from_static(b"C"),
+        }
+    }
+}
+
+/// Events of a [`Document`] that an [`Action`] can be assigned to. The action performed when the
+/// document is opened is set with [`Document::set_open_action`] instead.
+///
+/// [`Document`]: crate::Document
+/// [`Document::set_open_action`]: crate::Document::set_open_action
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DocumentTrigger {
+    /// The document is about to be closed.
+    WillClose,
+
+    /// The document is about to be saved.
+    WillSave,
+
+    /// The document was saved.
+    DidSave,
+
+    /// The document is about to be printed.
+    WillPrint,
+
+    /// The document was printed.
+    DidPrint,
+}
+
+impl DocumentTrigger {
+    /// Returns the key of this trigger in an additional-actions dictionary.
+    pub(crate) fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            DocumentTrigger::WillClose => Identifier::from_static(b"WC"),
+            DocumentTrigger::WillSave => Identifier::from_static(b"WS"),
+            DocumentTrigger::DidSave => Identifier::from_static(b"DS"),
+            DocumentTrigger::WillPrint => Identifier::from_static(b"WP"),
+            DocumentTrigger::DidPrint => Identifier::from_static(b"DP"),
+        }
+    }
+}
+
+/// Writes the given triggers and their actions as an additional-actions dictionary (12.6.3) into
+/// the provided implementor of [`Write`], e.g. `<< /O << ... >> /C << ... >> >>`.
+pub(crate) fn write_additional_actions<'a>(
+    writer: &mut dyn Write,
+    actions: impl IntoIterator<Item = (Identifier<&'static [u8]>, &'a Action)>,
+) -> Result<usize, Error> {
+    let mut written = writer.write(b"<< ")?;
+    for (trigger, action) in actions {
+        written += trigger.write(writer)?;
+        written += action.write(writer)?;
+        written += writer.write(b" ")?;
+    }
+    written += writer.write(b">>")?;
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Action, NamedAction, SubmitFormat};
+
+    #[test]
+    fn actions() {
+        let actions = [
+            Action::Named(NamedAction::NextPage),
+            Action::JavaScript("app.alert('Done (100%)');".to_string()),
+            Action::Uri("https://example.com/help".to_string()),
+            Action::SubmitForm {
+                url: "https://example.com/submit".to_string(),
+                format: SubmitFormat::Html,
+            },
+            Action::ResetForm,
+        ];
+
+        let mut writer = Vec::new();
+        for action in actions {
+            action.write(&mut writer).unwrap();
+            writer.push(b'\n');
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Type /Action /S /Named /N /NextPage >>
+        << /Type /Action /S /JavaScript /JS (app.alert\('Done \(100%\)'\);) >>
+        << /Type /Action /S /URI /URI (https://example.com/help) >>
+        << /Type /Action /S /SubmitForm /F << /FS /URL /F (https://example.com/submit) >> /Flags 4 >>
+        << /Type /Action /S /ResetForm >>
+        ");
+    }
+}
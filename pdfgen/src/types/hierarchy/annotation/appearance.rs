@@ -0,0 +1,261 @@
+//! Implementation of annotation appearance streams (12.5.5), form XObjects rendered by PDF
+//! processors in place of an annotation, so that it looks the same in every viewer.
+
+use std::{
+    collections::HashMap,
+    io::{Error, Write},
+};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    IdManager, ObjId,
+    types::{
+        constants,
+        hierarchy::{
+            content::{
+                color::{Color, ColorSpace, ColorSpaceIds},
+                graphics_state::ExtGState,
+                path::Path,
+                stream::Stream,
+            },
+            primitives::{
+                encoding::ShowEncoding,
+                font::Font,
+                identifier::Identifier,
+                rectangle::{Position, Rectangle},
+                resources::{Renderable, Resources},
+                string::PdfString,
+            },
+        },
+    },
+};
+
+/// The normal appearance of an annotation, a form XObject whose bounding box is painted into the
+/// annotation's rectangle.
+#[derive(Debug, Clone)]
+pub(crate) struct Appearance {
+    /// Bounding box of the form in form space, either the rectangle of the annotation for
+    /// appearances drawn in page coordinates, or a box at the origin with its size.
+    b_box: Rectangle,
+
+    /// Resources used by the content, such as fonts and images.
+    resources: Resources,
+
+    /// Content stream painting the annotation.
+    content: Vec<u8>,
+}
+
+impl Appearance {
+    const_identifiers! {
+        SUBTYPE,
+        FORM,
+        B_BOX: b"BBox",
+        RESOURCES,
+    }
+
+    /// Creates a new `Appearance` with the given bounding box, painted by the given content.
+    pub(crate) fn new(b_box: Rectangle, content: Vec<u8>) -> Self {
+        Self {
+            b_box,
+            resources: Resources::default(),
+            content,
+        }
+    }
+
+    /// Sets the [`Resources`] used by the content of this `Appearance`.
+    pub(crate) fn with_resources(mut self, resources: Resources) -> Self {
+        self.resources = resources;
+        self
+    }
+
+    /// Returns the [`ObjId`]s of all fonts used by this `Appearance`.
+    pub(crate) fn font_ids(&self) -> impl Iterator<Item = &ObjId<Font>> {
+        self.resources.font_ids()
+    }
+
+    /// Returns all colour spaces used by this `Appearance`.
+    pub(crate) fn color_spaces(&self) -> impl Iterator<Item = ColorSpace> {
+        self.resources.color_spaces()
+    }
+
+    /// Replaces the [`ObjId`]s of all fonts and colour spaces used by this `Appearance` with the
+    /// ones they are mapped to.
+    pub(crate) fn remap_ids(
+        &mut self,
+        font_ids: &HashMap<ObjId<Font>, ObjId<Font>>,
+        color_space_ids: &ColorSpaceIds,
+    ) {
+        self.resources.remap_font_ids(font_ids);
+        self.resources.remap_color_space_ids(color_space_ids);
+    }
+
+    /// Writes this `Appearance` as an object with the given [`ObjId`], followed by the objects of
+    /// the resources it owns, into the provided implementor of [`Write`]. Returns the number of
+    /// bytes written and the offsets of all objects following the form XObject.
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        id: &ObjId<Self>,
+        id_manager: &mut IdManager,
+    ) -> Result<(usize, Vec<usize>), Error> {
+        let mut offsets = Vec::new();
+        let renderable_resources = self.resources.renderables(id_manager);
+        let form = Stream::with_bytes(self.content.as_slice());
+
+        let written = pdfgen_macros::write_chain! {
+            id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            form.write_with_dict(writer, |writer| {
+                Ok(pdfgen_macros::write_chain! {
+                    Identifier::TYPE.write(writer),
+                    Identifier::X_OBJECT.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::SUBTYPE.write(writer),
+                    Self::FORM.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::B_BOX.write(writer),
+                    self.b_box.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::RESOURCES.write(writer),
+                    self.resources.write_dict(writer, &renderable_resources, |_| Ok(0)),
+                    writer.write(constants::NL_MARKER),
+                })
+            }),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+
+            for renderable in renderable_resources.iter() {
+                {
+                    offsets.push(written);
+                    renderable.write_def(writer)
+                }
+            },
+            for (mask, id) in renderable_resources.iter().filter_map(Renderable::mask) {
+                {
+                    offsets.push(written);
+                    mask.write(writer, id)
+                }
+            },
+        };
+
+        Ok((written, offsets))
+    }
+}
+
+/// Builder of the [`Appearance`] generated for an annotation, painting paths with the colors of
+/// the annotation. Annotations of every kind draw their generated appearance with it, so that
+/// they look the same in viewers that would ignore their parameters.
+#[derive(Debug)]
+pub(crate) struct AppearanceBuilder {
+    /// Bounding box of the built appearance.
+    b_box: Rectangle,
+
+    /// Resources used by the content, such as graphics states.
+    resources: Resources,
+
+    /// Content stream painting the annotation.
+    content: Vec<u8>,
+}
+
+impl AppearanceBuilder {
+    /// Creates a new `AppearanceBuilder` for an appearance drawn in page coordinates, with the
+    /// given rectangle of the annotation as its bounding box.
+    pub(crate) fn new(b_box: Rectangle) -> Self {
+        Self {
+            b_box,
+            resources: Resources::default(),
+            content: Vec::new(),
+        }
+    }
+
+    /// Applies the given [`ExtGState`] to content that follows, e.g. to blend it with the page.
+    pub(crate) fn set_ext_g_state(&mut self, state: ExtGState) -> &mut Self {
+        let name = self.resources.add_ext_g_state(state);
+        name.write(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        self.push_operator(b"gs")
+    }
+
+    /// Sets the width of lines stroked by content that follows.
+    pub(crate) fn set_line_width(&mut self, width: f32) -> &mut Self {
+        crate::write_fmt!(&mut self.content, "{width} ")
+            .expect("Writing to Vec should never fail.");
+        self.push_operator(b"w")
+    }
+
+    /// Sets the [`Color`] used to stroke paths that follow.
+    pub(crate) fn set_stroke_color(&mut self, color: &Color) -> &mut Self {
+        color
+            .write_stroke(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        self
+    }
+
+    /// Sets the [`Color`] used to fill paths that follow.
+    pub(crate) fn set_fill_color(&mut self, color: &Color) -> &mut Self {
+        color
+            .write_non_stroke(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        self
+    }
+
+    /// Strokes the given [`Path`] with the current stroke color and line width.
+    pub(crate) fn stroke(&mut self, path: &Path) -> &mut Self {
+        path.write_stroke(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        self
+    }
+
+    /// Fills the given [`Path`] with the current fill color.
+    pub(crate) fn fill(&mut self, path: &Path) -> &mut Self {
+        path.write_fill(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        self
+    }
+
+    /// Shows the given single line of text with the simple [`Font`] with the given [`ObjId`] in
+    /// the given size, starting at the given position of its baseline, in the current fill color.
+    pub(crate) fn show_text(
+        &mut self,
+        font_id: &ObjId<Font>,
+        size: u32,
+        position: Position,
+        text: &str,
+    ) -> &mut Self {
+        let text = PdfString::text_string(text).encoded(ShowEncoding::WinAnsi);
+
+        self.push_operator(b"BT");
+        let name = self.resources.add_font(font_id.clone());
+        name.write(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        crate::write_fmt!(&mut self.content, "{size} ").expect("Writing to Vec should never fail.");
+        self.push_operator(b"Tf");
+        crate::write_fmt!(&mut self.content, "{} {} ", position.x, position.y)
+            .expect("Writing to Vec should never fail.");
+        self.push_operator(b"Td");
+        text.write_content(&mut self.content)
+            .expect("Writing to Vec should never fail.");
+        self.content.extend_from_slice(constants::SP);
+        self.push_operator(b"Tj");
+        self.push_operator(b"ET")
+    }
+
+    /// Returns the built [`Appearance`].
+    pub(crate) fn build(self) -> Appearance {
+        Appearance::new(self.b_box, self.content).with_resources(self.resources)
+    }
+
+    /// Writes the given operator, ending the current operation.
+    fn push_operator(&mut self, operator: &[u8]) -> &mut Self {
+        self.content.extend_from_slice(operator);
+        self.content.extend_from_slice(constants::NL_MARKER);
+        self
+    }
+}
@@ -0,0 +1,386 @@
+//! Implementation of text markup annotations (12.5.6.10), which highlight, underline or strike out
+//! text on a page, e.g. to mark the search results in a generated report.
+
+use std::io::{Error, Write};
+
+use crate::types::hierarchy::{
+    content::{
+        color::Color,
+        graphics_state::{BlendMode, ExtGState},
+        path::Path,
+    },
+    primitives::{
+        identifier::Identifier,
+        rectangle::{Position, Rectangle},
+    },
+};
+
+use super::{Annotation, AnnotationKind, appearance::AppearanceBuilder};
+
+/// Fraction of the height of marked up text used as the width of the lines marking it up.
+const LINE_WIDTH: f32 = 1.0 / 14.0;
+
+/// Possible errors that might be returned when creating a text markup [`Annotation`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum TextMarkupErr {
+    /// Indicates that no quadrilaterals enclosing the marked up text were given.
+    #[error("Text markup annotations must mark up at least one quadrilateral.")]
+    NoQuads,
+}
+
+/// How the text of a text markup [`Annotation`] is marked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextMarkupStyle {
+    /// The text is highlighted, as with a marker pen.
+    Highlight,
+
+    /// The text is underlined.
+    Underline,
+
+    /// The text is underlined with a jagged (squiggly) line.
+    Squiggly,
+
+    /// The text is struck out.
+    StrikeOut,
+}
+
+impl TextMarkupStyle {
+    /// Returns the annotation subtype of this style.
+    pub(super) fn subtype(self) -> Identifier<&'static [u8]> {
+        match self {
+            TextMarkupStyle::Highlight => Identifier::from_static(b"Highlight"),
+            TextMarkupStyle::Underline => Identifier::from_static(b"Underline"),
+            TextMarkupStyle::Squiggly => Identifier::from_static(b"Squiggly"),
+            TextMarkupStyle::StrikeOut => Identifier::from_static(b"StrikeOut"),
+        }
+    }
+
+    /// Returns the color text is marked up with in this style, unless set otherwise.
+    fn default_color(self) -> Color {
+        match self {
+            TextMarkupStyle::Highlight => Color::Rgb {
+                red: 255,
+                green: 255,
+                blue: 0,
+            },
+            TextMarkupStyle::Underline | TextMarkupStyle::Squiggly | TextMarkupStyle::StrikeOut => {
+                Color::Gray(0)
+            }
+        }
+    }
+}
+
+/// A quadrilateral enclosing a word or a line of text marked up by a text markup [`Annotation`].
+/// The text is oriented from the lower left to the lower right corner, so quadrilaterals can also
+/// enclose rotated text. Axis-aligned text is enclosed by a [`Rectangle`], which converts into a
+/// `Quad`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quad {
+    /// The corners of the quadrilateral, in the order upper left, upper right, lower left and
+    /// lower right, relative to the orientation of the text.
+    corners: [Position; 4],
+}
+
+impl Quad {
+    /// Creates a new `Quad` with the given corners, relative to the orientation of the enclosed
+    /// text.
+    pub fn new(
+        upper_left: Position,
+        upper_right: Position,
+        lower_left: Position,
+        lower_right: Position,
+    ) -> Self {
+        Self {
+            corners: [upper_left, upper_right, lower_left, lower_right],
+        }
+    }
+
+    /// Returns the corners of this `Quad`.
+    pub(super) fn corners(&self) -> [Position; 4] {
+        self.corners
+    }
+
+    /// Returns the point at the given fractions of the way along the text and up from its lower
+    /// edge, which follows the orientation of the text.
+    fn point(&self, along: f32, up: f32) -> Position {
+        let [upper_left, _, lower_left, lower_right] = self
+            .corners
+            .map(|pos| (pos.x.into_user_unit(), pos.y.into_user_unit()));
+
+        Position::from_units(
+            lower_left.0
+                + along * (lower_right.0 - lower_left.0)
+                + up * (upper_left.0 - lower_left.0),
+            lower_left.1
+                + along * (lower_right.1 - lower_left.1)
+                + up * (upper_left.1 - lower_left.1),
+        )
+    }
+
+    /// Returns the length of the lower and the left edge of this `Quad`, i.e. the width and the
+    /// height of the enclosed text.
+    fn size(&self) -> (f32, f32) {
+        let distance = |from: Position, to: Position| {
+            let dx = to.x.into_user_unit() - from.x.into_user_unit();
+            let dy = to.y.into_user_unit() - from.y.into_user_unit();
+            dx.hypot(dy)
+        };
+        let [upper_left, _, lower_left, lower_right] = self.corners;
+
+        (
+            distance(lower_left, lower_right),
+            distance(lower_left, upper_left),
+        )
+    }
+}
+
+impl From<Rectangle> for Quad {
+    fn from(rect: Rectangle) -> Self {
+        let (low_left, top_right) = (rect.low_left(), rect.top_right());
+
+        Self::new(
+            Position::new(low_left.x, top_right.y),
+            top_right,
+            low_left,
+            Position::new(top_right.x, low_left.y),
+        )
+    }
+}
+
+impl Annotation {
+    /// Creates a new text markup `Annotation` marking up the text enclosed by the given
+    /// quadrilaterals in the given [`TextMarkupStyle`], e.g. each line of a highlighted paragraph.
+    /// Text is highlighted in yellow and marked up in black otherwise, unless set with
+    /// [`Annotation::with_color`].
+    ///
+    /// Returns [`TextMarkupErr::NoQuads`] if no quadrilaterals are given.
+    pub fn text_markup(
+        style: TextMarkupStyle,
+        quads: impl IntoIterator<Item = impl Into<Quad>>,
+    ) -> Result<Self, TextMarkupErr> {
+        let quads: Vec<Quad> = quads.into_iter().map(Into::into).collect();
+        let rect = Rectangle::enclosing(quads.iter().flat_map(Quad::corners))
+            .ok_or(TextMarkupErr::NoQuads)?;
+
+        Ok(Self::new(AnnotationKind::TextMarkup { style, quads }, rect)
+            .with_color(style.default_color()))
+    }
+
+    /// Creates a new `Annotation` highlighting the text enclosed by the given quadrilaterals. See
+    /// [`Annotation::text_markup`] for more details.
+    pub fn highlight(
+        quads: impl IntoIterator<Item = impl Into<Quad>>,
+    ) -> Result<Self, TextMarkupErr> {
+        Self::text_markup(TextMarkupStyle::Highlight, quads)
+    }
+
+    /// Creates a new `Annotation` underlining the text enclosed by the given quadrilaterals. See
+    /// [`Annotation::text_markup`] for more details.
+    pub fn underline(
+        quads: impl IntoIterator<Item = impl Into<Quad>>,
+    ) -> Result<Self, TextMarkupErr> {
+        Self::text_markup(TextMarkupStyle::Underline, quads)
+    }
+
+    /// Creates a new `Annotation` underlining the text enclosed by the given quadrilaterals with
+    /// a squiggly line. See [`Annotation::text_markup`] for more details.
+    pub fn squiggly(
+        quads: impl IntoIterator<Item = impl Into<Quad>>,
+    ) -> Result<Self, TextMarkupErr> {
+        Self::text_markup(TextMarkupStyle::Squiggly, quads)
+    }
+
+    /// Creates a new `Annotation` striking out the text enclosed by the given quadrilaterals. See
+    /// [`Annotation::text_markup`] for more details.
+    pub fn strike_out(
+        quads: impl IntoIterator<Item = impl Into<Quad>>,
+    ) -> Result<Self, TextMarkupErr> {
+        Self::text_markup(TextMarkupStyle::StrikeOut, quads)
+    }
+}
+
+impl Annotation {
+    /// Draws the marking up of the text enclosed by the given quadrilaterals in the given
+    /// [`TextMarkupStyle`] into the given [`AppearanceBuilder`], with the color of this
+    /// annotation.
+    pub(super) fn draw_text_markup(
+        &self,
+        style: TextMarkupStyle,
+        quads: &[Quad],
+        appearance: &mut AppearanceBuilder,
+    ) {
+        let color = self.color.unwrap_or(style.default_color());
+
+        if style == TextMarkupStyle::Highlight {
+            // multiplying keeps the highlighted text readable, as if it was marked with a pen
+            appearance.set_ext_g_state(ExtGState::new().with_blend_mode(BlendMode::Multiply));
+            appearance.set_fill_color(&color);
+
+            for quad in quads {
+                let [upper_left, upper_right, lower_left, lower_right] = quad.corners();
+                let outline = Path::new()
+                    .move_to(lower_left)
+                    .line_to(lower_right)
+                    .line_to(upper_right)
+                    .line_to(upper_left)
+                    .close();
+                appearance.fill(&outline);
+            }
+
+            return;
+        }
+
+        appearance.set_stroke_color(&color);
+        for quad in quads {
+            let (width, height) = quad.size();
+            let path = match style {
+                TextMarkupStyle::Underline => Path::new()
+                    .move_to(quad.point(0.0, 1.0 / 7.0))
+                    .line_to(quad.point(1.0, 1.0 / 7.0)),
+                TextMarkupStyle::StrikeOut => Path::new()
+                    .move_to(quad.point(0.0, 0.5))
+                    .line_to(quad.point(1.0, 0.5)),
+                TextMarkupStyle::Squiggly => squiggle(quad, width, height),
+                TextMarkupStyle::Highlight => unreachable!("Highlights are filled."),
+            };
+
+            appearance.set_line_width(height * LINE_WIDTH);
+            appearance.stroke(&path);
+        }
+    }
+}
+
+/// Returns the [`Path`] of a jagged line along the lower edge of the given [`Quad`], with the
+/// given width and height of the enclosed text.
+fn squiggle(quad: &Quad, width: f32, height: f32) -> Path {
+    // each segment of the line rises or falls by an eighth of the text's height
+    let step = height / 6.0;
+    let steps = if width > 0.0 {
+        (width / step).ceil() as usize
+    } else {
+        1
+    };
+
+    let mut path = Path::new().move_to(quad.point(0.0, 1.0 / 16.0));
+    for idx in 1..=steps {
+        let along = (idx as f32 * step / width).min(1.0);
+        let up = if idx % 2 == 0 { 1.0 / 16.0 } else { 3.0 / 16.0 };
+        path = path.line_to(quad.point(along, up));
+    }
+
+    path
+}
+
+/// Writes the coordinates of the corners of the given quadrilaterals as the `QuadPoints` array of
+/// a text markup annotation.
+pub(super) fn write_quads(writer: &mut dyn Write, quads: &[Quad]) -> Result<usize, Error> {
+    let coordinates = quads
+        .iter()
+        .flat_map(Quad::corners)
+        .map(|corner| format!("{} {}", corner.x, corner.y))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    crate::write_fmt!(&mut *writer, "[{coordinates}]")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::primitives::rectangle::{Position, Rectangle},
+    };
+
+    use super::{Annotation, Quad, TextMarkupErr};
+
+    #[test]
+    fn markup_without_quads() {
+        let quads: [Quad; 0] = [];
+
+        assert_eq!(
+            Annotation::highlight(quads).unwrap_err(),
+            TextMarkupErr::NoQuads
+        );
+    }
+
+    #[test]
+    fn markup_in_mixed_units() {
+        let quad = Quad::new(
+            Position::from_mm(10.0, 20.0),
+            Position::from_units(100.0, 60.0),
+            Position::from_mm(10.0, 15.0),
+            Position::from_units(100.0, 40.0),
+        );
+        let annotation = Annotation::highlight([quad]).unwrap();
+
+        let mut writer = Vec::new();
+        annotation.rect().write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [28.346457 40 100 60]
+        ");
+    }
+
+    #[test]
+    fn markup_appearances() {
+        let mut id_manager = IdManager::new();
+        let rect = Rectangle::from_units(10.0, 10.0, 34.0, 22.0);
+
+        let mut writer = Vec::new();
+        for annotation in [Annotation::underline([rect]), Annotation::squiggly([rect])] {
+            let annotation = annotation.unwrap();
+            let appearance = annotation.appearance().unwrap();
+            let id = id_manager.create_id();
+            appearance.write(&mut writer, &id, &mut id_manager).unwrap();
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [10 10 34 22]
+        /Resources <<  >>
+        /Length 64 >>
+        stream
+        /DeviceGray CS
+        0 SC
+        0.8571429 w
+        10 11.714286 m
+        34 11.714286 l
+        S
+
+        endstream
+        endobj
+
+        2 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [10 10 34 22]
+        /Resources <<  >>
+        /Length 177 >>
+        stream
+        /DeviceGray CS
+        0 SC
+        0.8571429 w
+        10 10.75 m
+        12 12.25 l
+        14 10.75 l
+        16 12.25 l
+        18 10.75 l
+        20 12.25 l
+        22 10.75 l
+        24 12.25 l
+        26 10.75 l
+        28 12.25 l
+        30 10.75 l
+        32 12.25 l
+        34 10.75 l
+        S
+
+        endstream
+        endobj
+        ");
+    }
+}
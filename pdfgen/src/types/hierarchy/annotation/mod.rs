@@ -0,0 +1,409 @@
+//! Implementation of annotations (12.5), which associate an object such as a note, a highlight or
+//! a shape with a location on a page of a document, shown on top of the page's content.
+
+use std::{
+    collections::HashMap,
+    io::{Error, Write},
+};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{ObjId, SanitizePolicy, types::constants};
+
+use super::{
+    content::{
+        color::{Color, ColorSpace, ColorSpaceIds},
+        recording::Recording,
+    },
+    page::Page,
+    primitives::{
+        date::PdfDate,
+        font::Font,
+        identifier::Identifier,
+        rectangle::{Position, Rectangle},
+        string::PdfString,
+    },
+};
+
+mod appearance;
+mod markup;
+mod shape;
+mod stamp;
+pub(crate) use appearance::{Appearance, AppearanceBuilder};
+pub use markup::{Quad, TextMarkupErr, TextMarkupStyle};
+pub use shape::LineEndingStyle;
+
+/// The kind of an [`Annotation`], together with the entries specific to it.
+#[derive(Debug, Clone)]
+enum AnnotationKind {
+    /// Text markup annotation, marking up the text within the given quadrilaterals.
+    TextMarkup {
+        /// How the text is marked up.
+        style: TextMarkupStyle,
+
+        /// The quadrilaterals enclosing the marked up text.
+        quads: Vec<Quad>,
+    },
+
+    /// Square annotation, drawing a rectangle inside of the annotation's rectangle.
+    Square,
+
+    /// Circle annotation, drawing an ellipse inside of the annotation's rectangle.
+    Circle,
+
+    /// Line annotation, drawing a straight line between two points.
+    Line {
+        /// The point the line starts at.
+        start: Position,
+
+        /// The point the line ends at.
+        end: Position,
+
+        /// Shapes drawn at the start and at the end of the line.
+        endings: [LineEndingStyle; 2],
+    },
+
+    /// Rubber stamp annotation, showing the recorded content drawn into its appearance.
+    Stamp(Recording),
+}
+
+/// An annotation associated with a location on a [`Page`], such as a highlighted region of text.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::annotation::Annotation};
+/// # use pdfgen::types::hierarchy::{content::color::Color, primitives::rectangle::Rectangle};
+/// let mut document = Document::default();
+/// let page = document.create_page();
+///
+/// // highlight the search result in the second line of the page
+/// let result = Annotation::highlight([Rectangle::from_units(72., 700., 180., 714.)])
+///     .unwrap()
+///     .with_color(Color::Rgb { red: 255, green: 235, blue: 0 })
+///     .with_contents("Search result for \"invoice\"");
+/// page.add_annotation(result);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    /// The kind of this annotation.
+    kind: AnnotationKind,
+
+    /// Location of this annotation on the page.
+    rect: Rectangle,
+
+    /// Text shown for this annotation, e.g. in a pop-up window, or an alternate description of it.
+    contents: Option<PdfString>,
+
+    /// Color of this annotation, written in its device colour space.
+    color: Option<Color>,
+
+    /// Author of this annotation.
+    author: Option<PdfString>,
+
+    /// Short description of the subject this annotation addresses.
+    subject: Option<PdfString>,
+
+    /// Date and time this annotation was created at.
+    creation_date: Option<PdfDate>,
+
+    /// Date and time this annotation was last modified at.
+    modification_date: Option<PdfDate>,
+
+    /// Color filling the inside of shapes, written in its device colour space.
+    interior_color: Option<Color>,
+
+    /// Width of the border of shapes, in default user space units.
+    border_width: Option<f32>,
+
+    /// Appearance drawn from content given by the user, used instead of a generated one.
+    appearance: Option<Appearance>,
+}
+
+impl Annotation {
+    const_identifiers! {
+        ANNOT,
+        SUBTYPE,
+        RECT,
+        P: b"P",
+        F: b"F",
+        CONTENTS,
+        C: b"C",
+        T: b"T",
+        SUBJ,
+        CREATION_DATE,
+        M: b"M",
+        QUAD_POINTS,
+        IC: b"IC",
+        BS: b"BS",
+        W: b"W",
+        L: b"L",
+        LE: b"LE",
+        AP: b"AP",
+        N: b"N",
+    }
+
+    /// Flag of annotations that are printed together with the page.
+    const PRINT: u32 = 1 << 2;
+
+    /// Creates a new `Annotation` of the given kind, located at the given rectangle.
+    fn new(kind: AnnotationKind, rect: Rectangle) -> Self {
+        Self {
+            kind,
+            rect,
+            contents: None,
+            color: None,
+            author: None,
+            subject: None,
+            creation_date: None,
+            modification_date: None,
+            interior_color: None,
+            border_width: None,
+            appearance: None,
+        }
+    }
+
+    /// Sets the text of this `Annotation`, shown by PDF processors e.g. in a pop-up window when
+    /// the annotation is selected.
+    pub fn with_contents(mut self, contents: &str) -> Self {
+        self.contents = Some(PdfString::text_string(contents));
+        self
+    }
+
+    /// Sets the [`Color`] of this `Annotation`, e.g. the color of a highlight. Colors are written
+    /// in the device colour space approximating them, as annotations have no colour space.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color.to_device());
+        self
+    }
+
+    /// Sets the author of this `Annotation`, shown by PDF processors as the title of its pop-up
+    /// window.
+    pub fn with_author(mut self, author: &str) -> Self {
+        self.author = Some(PdfString::text_string(author));
+        self
+    }
+
+    /// Sets the subject of this `Annotation`, a short description of what it addresses, shown by
+    /// PDF processors e.g. in their list of comments.
+    pub fn with_subject(mut self, subject: &str) -> Self {
+        self.subject = Some(PdfString::text_string(subject));
+        self
+    }
+
+    /// Sets the [`PdfDate`] this `Annotation` was created at.
+    pub fn with_creation_date(mut self, date: PdfDate) -> Self {
+        self.creation_date = Some(date);
+        self
+    }
+
+    /// Sets the [`PdfDate`] this `Annotation` was last modified at.
+    pub fn with_modification_date(mut self, date: PdfDate) -> Self {
+        self.modification_date = Some(date);
+        self
+    }
+
+    /// Returns the rectangle this `Annotation` is located at.
+    pub fn rect(&self) -> Rectangle {
+        self.rect
+    }
+
+    /// Returns this `Annotation` with its contents, author and subject sanitized according to the given
+    /// [`SanitizePolicy`].
+    pub(crate) fn sanitized(self, policy: &SanitizePolicy) -> Self {
+        let sanitize = |text: Option<PdfString>| {
+            text.map(|text| PdfString::text_string(policy.text(&text.to_text())))
+        };
+
+        Self {
+            contents: sanitize(self.contents),
+            author: sanitize(self.author),
+            subject: sanitize(self.subject),
+            ..self
+        }
+    }
+
+    /// Returns the subtype of this `Annotation`.
+    fn subtype(&self) -> Identifier<&'static [u8]> {
+        match &self.kind {
+            AnnotationKind::TextMarkup { style, .. } => style.subtype(),
+            AnnotationKind::Square => Identifier::from_static(b"Square"),
+            AnnotationKind::Circle => Identifier::from_static(b"Circle"),
+            AnnotationKind::Line { .. } => Identifier::from_static(b"Line"),
+            AnnotationKind::Stamp(_) => Identifier::from_static(b"Stamp"),
+        }
+    }
+
+    /// Writes the entries specific to the kind of this `Annotation`.
+    fn write_kind(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(match &self.kind {
+            AnnotationKind::TextMarkup { quads, .. } => pdfgen_macros::write_chain! {
+                Self::QUAD_POINTS.write(writer),
+                markup::write_quads(writer, quads),
+                writer.write(constants::NL_MARKER),
+            },
+            AnnotationKind::Square | AnnotationKind::Circle | AnnotationKind::Stamp(_) => 0,
+            AnnotationKind::Line {
+                start,
+                end,
+                endings,
+            } => Self::write_line(writer, *start, *end, *endings)?,
+        })
+    }
+
+    /// Returns the [`Appearance`] of this `Annotation`, rendered by PDF processors instead of
+    /// drawing the annotation themselves. Appearances drawn from content given by the user take
+    /// precedence, and all other annotations get one generated from their parameters, as many
+    /// viewers ignore the parameters of annotations without an appearance.
+    pub(crate) fn appearance(&self) -> Option<Appearance> {
+        if let Some(appearance) = &self.appearance {
+            return Some(appearance.clone());
+        }
+
+        let mut appearance = AppearanceBuilder::new(self.rect);
+        match &self.kind {
+            AnnotationKind::TextMarkup { style, quads } => {
+                self.draw_text_markup(*style, quads, &mut appearance)
+            }
+            AnnotationKind::Square | AnnotationKind::Circle | AnnotationKind::Line { .. } => {
+                self.draw_shape(&mut appearance)
+            }
+            // stamps are drawn from their content when they are added to a page
+            AnnotationKind::Stamp(_) => return None,
+        }
+
+        Some(appearance.build())
+    }
+
+    /// Sets the [`Appearance`] drawn from content given by the user, used instead of a generated
+    /// one.
+    pub(crate) fn set_appearance(&mut self, appearance: Appearance) {
+        self.appearance = Some(appearance);
+    }
+
+    /// Returns the [`ObjId`]s of all fonts used by the appearance of this `Annotation`.
+    pub(crate) fn font_ids(&self) -> impl Iterator<Item = &ObjId<Font>> {
+        self.appearance.iter().flat_map(Appearance::font_ids)
+    }
+
+    /// Returns all colour spaces used by the appearance of this `Annotation`.
+    pub(crate) fn color_spaces(&self) -> impl Iterator<Item = ColorSpace> {
+        self.appearance.iter().flat_map(Appearance::color_spaces)
+    }
+
+    /// Replaces the [`ObjId`]s of all fonts and colour spaces used by the appearance of this
+    /// `Annotation` with the ones they are mapped to.
+    pub(crate) fn remap_ids(
+        &mut self,
+        font_ids: &HashMap<ObjId<Font>, ObjId<Font>>,
+        color_space_ids: &ColorSpaceIds,
+    ) {
+        if let Some(appearance) = &mut self.appearance {
+            appearance.remap_ids(font_ids, color_space_ids);
+        }
+    }
+
+    /// Writes this `Annotation` as an object with the given [`ObjId`], located on the page with the
+    /// given [`ObjId`] and drawn by the [`Appearance`] with the given [`ObjId`], into the provided
+    /// implementor of [`Write`].
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        id: &ObjId<Self>,
+        appearance: Option<&ObjId<Appearance>>,
+        page: &ObjId<Page>,
+    ) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::ANNOT.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::SUBTYPE.write(writer),
+            self.subtype().write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::RECT.write(writer),
+            self.rect.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::P.write(writer),
+            page.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::F.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", Self::PRINT),
+            writer.write(constants::NL_MARKER),
+
+            if let Some(contents) = &self.contents {
+                Self::CONTENTS.write(writer),
+                contents.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(date) = &self.modification_date {
+                Self::M.write(writer),
+                date.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(color) = &self.color {
+                Self::C.write(writer),
+                color.write_components(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(author) = &self.author {
+                Self::T.write(writer),
+                author.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(subject) = &self.subject {
+                Self::SUBJ.write(writer),
+                subject.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(date) = &self.creation_date {
+                Self::CREATION_DATE.write(writer),
+                date.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(color) = &self.interior_color {
+                Self::IC.write(writer),
+                color.write_components(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(width) = self.border_width {
+                Self::BS.write(writer),
+                writer.write(b"<< "),
+                Self::W.write(writer),
+                crate::write_fmt!(&mut *writer, "{width} >>"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            self.write_kind(writer),
+
+            if let Some(appearance) = appearance {
+                Self::AP.write(writer),
+                writer.write(b"<< "),
+                Self::N.write(writer),
+                appearance.write_ref(writer),
+                writer.write(b" >>"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
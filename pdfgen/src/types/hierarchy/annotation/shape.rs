@@ -0,0 +1,310 @@
+//! Implementation of square, circle and line annotations (12.5.6.7, 12.5.6.8), which draw shapes
+//! and arrows on top of a page, e.g. to point out a detail of a technical drawing.
+
+use std::io::Write;
+
+use crate::types::{
+    constants,
+    hierarchy::{
+        content::{color::Color, path::Path},
+        primitives::{
+            identifier::Identifier,
+            rectangle::{Position, Rectangle},
+            unit::Unit,
+        },
+    },
+};
+
+use super::{Annotation, AnnotationKind, appearance::AppearanceBuilder};
+
+/// Border width of shape annotations, used when none is set.
+const DEFAULT_BORDER_WIDTH: f32 = 1.0;
+
+/// The shape drawn at the start or the end of a line [`Annotation`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEndingStyle {
+    /// The line ends without any shape.
+    #[default]
+    None,
+
+    /// A square centered at the end of the line.
+    Square,
+
+    /// A circle centered at the end of the line.
+    Circle,
+
+    /// A diamond centered at the end of the line.
+    Diamond,
+
+    /// Two short lines forming an arrowhead pointing away from the line.
+    OpenArrow,
+
+    /// A triangular arrowhead pointing away from the line.
+    ClosedArrow,
+
+    /// A short line perpendicular to the line.
+    Butt,
+
+    /// Two short lines forming an arrowhead pointing back along the line.
+    ROpenArrow,
+
+    /// A triangular arrowhead pointing back along the line.
+    RClosedArrow,
+
+    /// A short line slanted by 30 degrees from the perpendicular of the line.
+    Slash,
+}
+
+impl LineEndingStyle {
+    /// Returns the name of this style, as written in the `LE` array of line annotations.
+    pub(super) fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            LineEndingStyle::None => Identifier::from_static(b"None"),
+            LineEndingStyle::Square => Identifier::from_static(b"Square"),
+            LineEndingStyle::Circle => Identifier::from_static(b"Circle"),
+            LineEndingStyle::Diamond => Identifier::from_static(b"Diamond"),
+            LineEndingStyle::OpenArrow => Identifier::from_static(b"OpenArrow"),
+            LineEndingStyle::ClosedArrow => Identifier::from_static(b"ClosedArrow"),
+            LineEndingStyle::Butt => Identifier::from_static(b"Butt"),
+            LineEndingStyle::ROpenArrow => Identifier::from_static(b"ROpenArrow"),
+            LineEndingStyle::RClosedArrow => Identifier::from_static(b"RClosedArrow"),
+            LineEndingStyle::Slash => Identifier::from_static(b"Slash"),
+        }
+    }
+
+    /// Returns `true` if the shape of this style encloses an area, filled with the interior color
+    /// of the annotation.
+    fn is_closed(self) -> bool {
+        matches!(
+            self,
+            LineEndingStyle::Square
+                | LineEndingStyle::Circle
+                | LineEndingStyle::Diamond
+                | LineEndingStyle::ClosedArrow
+                | LineEndingStyle::RClosedArrow
+        )
+    }
+
+    /// Returns the [`Path`] of this style drawn at the `tip` of a line coming from `from`, with
+    /// the given size, or `None` if nothing is drawn.
+    fn path(self, tip: Position, from: Position, size: f32) -> Option<Path> {
+        let (tip_x, tip_y) = (tip.x.into_user_unit(), tip.y.into_user_unit());
+        let (dx, dy) = (
+            tip_x - from.x.into_user_unit(),
+            tip_y - from.y.into_user_unit(),
+        );
+        let length = dx.hypot(dy);
+        // direction pointing away from the line, and its normal
+        let (dir_x, dir_y) = if length > 0.0 {
+            (dx / length, dy / length)
+        } else {
+            (1.0, 0.0)
+        };
+
+        // position `along` the direction and `across` it, relative to the tip
+        let at = |along: f32, across: f32| {
+            Position::from_units(
+                tip_x + along * dir_x - across * dir_y,
+                tip_y + along * dir_y + across * dir_x,
+            )
+        };
+        let half = size / 2.0;
+        let arrow = |along: f32| {
+            Path::new()
+                .move_to(at(along, half))
+                .line_to(at(0.0, 0.0))
+                .line_to(at(along, -half))
+        };
+
+        Some(match self {
+            LineEndingStyle::None => return None,
+            LineEndingStyle::Square => Path::new()
+                .move_to(at(-half, -half))
+                .line_to(at(half, -half))
+                .line_to(at(half, half))
+                .line_to(at(-half, half))
+                .close(),
+            LineEndingStyle::Circle => Path::ellipse(tip, half, half),
+            LineEndingStyle::Diamond => Path::new()
+                .move_to(at(half, 0.0))
+                .line_to(at(0.0, half))
+                .line_to(at(-half, 0.0))
+                .line_to(at(0.0, -half))
+                .close(),
+            LineEndingStyle::OpenArrow => arrow(-size),
+            LineEndingStyle::ClosedArrow => arrow(-size).close(),
+            LineEndingStyle::Butt => Path::new().move_to(at(0.0, half)).line_to(at(0.0, -half)),
+            LineEndingStyle::ROpenArrow => arrow(size),
+            LineEndingStyle::RClosedArrow => arrow(size).close(),
+            LineEndingStyle::Slash => {
+                // sin and cos of 30 degrees
+                let (sin, cos) = (0.5, 0.866_025_4);
+                Path::new()
+                    .move_to(at(half * sin, half * cos))
+                    .line_to(at(-half * sin, -half * cos))
+            }
+        })
+    }
+}
+
+impl Annotation {
+    /// Creates a new square `Annotation`, drawing a rectangle inside of the given [`Rectangle`].
+    /// The border is black unless set with [`Annotation::with_color`].
+    pub fn square(rect: Rectangle) -> Self {
+        Self::new(AnnotationKind::Square, rect).with_color(Color::Gray(0))
+    }
+
+    /// Creates a new circle `Annotation`, drawing an ellipse inside of the given [`Rectangle`].
+    /// The border is black unless set with [`Annotation::with_color`].
+    pub fn circle(rect: Rectangle) -> Self {
+        Self::new(AnnotationKind::Circle, rect).with_color(Color::Gray(0))
+    }
+
+    /// Creates a new line `Annotation`, drawing a straight line from `start` to `end`. The line
+    /// is black unless set with [`Annotation::with_color`].
+    pub fn line(start: Position, end: Position) -> Self {
+        let kind = AnnotationKind::Line {
+            start,
+            end,
+            endings: [LineEndingStyle::None; 2],
+        };
+
+        Self::new(kind, line_rect(start, end, DEFAULT_BORDER_WIDTH)).with_color(Color::Gray(0))
+    }
+
+    /// Creates a new line `Annotation` drawing an arrow from `start` pointing to `end`. See
+    /// [`Annotation::line`] for more details.
+    pub fn arrow(start: Position, end: Position) -> Self {
+        Self::line(start, end).with_line_endings(LineEndingStyle::None, LineEndingStyle::OpenArrow)
+    }
+
+    /// Sets the [`Color`] used to fill the inside of square and circle annotations, and the
+    /// closed line endings of line annotations. Without it, they are not filled.
+    pub fn with_interior_color(mut self, color: Color) -> Self {
+        self.interior_color = Some(color.to_device());
+        self
+    }
+
+    /// Sets the width of the border of square and circle annotations, and of the line of line
+    /// annotations, in default user space units. Defaults to 1.
+    pub fn with_border_width(mut self, width: f32) -> Self {
+        self.border_width = Some(width);
+
+        if let AnnotationKind::Line { start, end, .. } = self.kind {
+            self.rect = line_rect(start, end, width);
+        }
+
+        self
+    }
+
+    /// Sets the [`LineEndingStyle`] drawn at the start and at the end of a line annotation. Has
+    /// no effect on other annotations.
+    pub fn with_line_endings(mut self, start: LineEndingStyle, end: LineEndingStyle) -> Self {
+        if let AnnotationKind::Line { endings, .. } = &mut self.kind {
+            *endings = [start, end];
+        }
+
+        self
+    }
+
+    /// Draws the border, line endings and inside of this shape annotation into the given
+    /// [`AppearanceBuilder`], filling closed shapes with the interior color and stroking all of
+    /// them with the color of the annotation.
+    pub(super) fn draw_shape(&self, appearance: &mut AppearanceBuilder) {
+        let width = self.border_width.unwrap_or(DEFAULT_BORDER_WIDTH);
+        // stroke and fill of closed shapes, and stroke only of open ones
+        let mut shapes: Vec<(Path, bool)> = Vec::new();
+
+        match &self.kind {
+            AnnotationKind::Square => {
+                let inset = self.rect.expanded(Unit::from_unit(-width / 2.0));
+                shapes.push((Path::from(inset), true));
+            }
+            AnnotationKind::Circle => {
+                let (low_left, top_right) = (self.rect.low_left(), self.rect.top_right());
+                let (low_x, low_y) = (low_left.x.into_user_unit(), low_left.y.into_user_unit());
+                let (top_x, top_y) = (top_right.x.into_user_unit(), top_right.y.into_user_unit());
+
+                let center = Position::from_units((low_x + top_x) / 2.0, (low_y + top_y) / 2.0);
+
+                shapes.push((
+                    Path::ellipse(
+                        center,
+                        (top_x - low_x - width) / 2.0,
+                        (top_y - low_y - width) / 2.0,
+                    ),
+                    true,
+                ));
+            }
+            AnnotationKind::Line {
+                start,
+                end,
+                endings: [start_ending, end_ending],
+            } => {
+                shapes.push((Path::new().move_to(*start).line_to(*end), false));
+
+                let size = ending_size(width);
+                let endings = [(start_ending, start, end), (end_ending, end, start)];
+                shapes.extend(endings.into_iter().filter_map(|(style, tip, from)| {
+                    style
+                        .path(*tip, *from, size)
+                        .map(|path| (path, style.is_closed()))
+                }));
+            }
+            AnnotationKind::TextMarkup { .. } | AnnotationKind::Stamp(_) => {}
+        }
+
+        appearance.set_line_width(width);
+        if let Some(color) = &self.color {
+            appearance.set_stroke_color(color);
+        }
+        if let Some(color) = &self.interior_color {
+            appearance.set_fill_color(color);
+        }
+
+        for (path, closed) in &shapes {
+            if *closed && self.interior_color.is_some() {
+                appearance.fill(path);
+            }
+            if self.color.is_some() {
+                appearance.stroke(path);
+            }
+        }
+    }
+
+    /// Writes the `L` and `LE` entries of a line annotation from `start` to `end`, with the given
+    /// line endings.
+    pub(super) fn write_line(
+        writer: &mut dyn Write,
+        start: Position,
+        end: Position,
+        endings: [LineEndingStyle; 2],
+    ) -> std::io::Result<usize> {
+        Ok(pdfgen_macros::write_chain! {
+            Self::L.write(writer),
+            crate::write_fmt!(&mut *writer, "[{} {} {} {}]", start.x, start.y, end.x, end.y),
+            writer.write(constants::NL_MARKER),
+
+            Self::LE.write(writer),
+            writer.write(b"["),
+            for ending in endings.iter() {
+                ending.identifier().write(writer),
+            },
+            writer.write(b"]"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// Returns the size of line endings drawn for lines of the given width.
+fn ending_size(width: f32) -> f32 {
+    (width * 4.0).max(6.0)
+}
+
+/// Returns the rectangle of a line annotation from `start` to `end` with the given width, large
+/// enough to contain its line endings.
+fn line_rect(start: Position, end: Position, width: f32) -> Rectangle {
+    Rectangle::enclosing([start, end])
+        .expect("Line has two points.")
+        .expanded(Unit::from_unit(ending_size(width) + width))
+}
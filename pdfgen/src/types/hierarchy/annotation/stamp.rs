@@ -0,0 +1,46 @@
+//! Implementation of rubber stamp annotations (12.5.6.12), which show custom content such as an
+//! approval stamp or a QR code for signing off on top of a page.
+
+use crate::types::hierarchy::{content::recording::Recording, primitives::rectangle::Rectangle};
+
+use super::{Annotation, AnnotationKind};
+
+impl Annotation {
+    /// Creates a new stamp `Annotation` located at the given [`Rectangle`], showing the content of
+    /// the given [`Recording`], e.g. a company logo with the name of the approver. Positions in
+    /// the recording are relative to the lower left corner of the rectangle, and content outside
+    /// of it is clipped.
+    ///
+    /// The content is drawn when the annotation is added to a page, so its texts are sanitized
+    /// like the page's texts.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::annotation::Annotation};
+    /// # use pdfgen::types::hierarchy::{content::{color::Color, recording::Recording, text::Text}, primitives::rectangle::{Position, Rectangle}};
+    /// let mut document = Document::default();
+    /// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    ///
+    /// let mut approval = Recording::new();
+    /// approval.fill_path(Rectangle::from_units(0., 0., 120., 40.), Color::Rgb { red: 0, green: 160, blue: 0 });
+    /// approval.add_text(
+    ///     Text::builder().with_content("APPROVED").at(Position::from_units(10., 15.)).build(),
+    ///     font_id,
+    /// );
+    ///
+    /// let page = document.create_page();
+    /// page.add_annotation(Annotation::stamp(Rectangle::from_units(400., 50., 520., 90.), approval));
+    /// ```
+    pub fn stamp(rect: Rectangle, content: Recording) -> Self {
+        Self::new(AnnotationKind::Stamp(content), rect)
+    }
+
+    /// Returns the content shown by this stamp annotation, or `None` for other annotations.
+    pub(crate) fn stamp_content(&self) -> Option<&Recording> {
+        match &self.kind {
+            AnnotationKind::Stamp(content) => Some(content),
+            _ => None,
+        }
+    }
+}
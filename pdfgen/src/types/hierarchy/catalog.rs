@@ -1,14 +1,96 @@
-use std::io::Error;
+use std::{collections::BTreeMap, io::Error};
 
 use pdfgen_macros::const_identifiers;
 
 use crate::{ObjId, types::constants};
 
 use super::{
+    action::{self, Action, DocumentTrigger},
+    content::bidi::TextDirection,
+    destination::{DestinationRef, NamedDestinations},
+    document_part::DocumentParts,
+    form::InteractiveForm,
+    metadata::XmpMetadata,
+    outline::DocumentOutline,
     page_tree::PageTree,
-    primitives::{identifier::Identifier, object::Object},
+    primitives::{identifier::Identifier, language::Language, object::Object},
 };
 
+/// The way a document is displayed when it's opened, i.e. which navigation panel of the PDF
+/// processor is shown next to the pages, if any.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageMode {
+    /// No navigation panel is shown. This is the default of PDF processors.
+    UseNone,
+
+    /// The document outline (bookmarks) is shown.
+    UseOutlines,
+
+    /// The page thumbnail images are shown.
+    UseThumbs,
+
+    /// The document is shown in full-screen mode, with no menu bar, window controls or any other
+    /// window visible.
+    FullScreen,
+
+    /// The optional content group panel is shown (PDF 1.5).
+    UseOc,
+
+    /// The attachments panel is shown (PDF 1.6).
+    UseAttachments,
+}
+
+impl PageMode {
+    /// Returns the [`Identifier`] of this page mode.
+    fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            PageMode::UseNone => Identifier::from_static(b"UseNone"),
+            PageMode::UseOutlines => Identifier::from_static(b"UseOutlines"),
+            PageMode::UseThumbs => Identifier::from_static(b"UseThumbs"),
+            PageMode::FullScreen => Identifier::from_static(b"FullScreen"),
+            PageMode::UseOc => Identifier::from_static(b"UseOC"),
+            PageMode::UseAttachments => Identifier::from_static(b"UseAttachments"),
+        }
+    }
+}
+
+/// The layout of the pages of a document when it's opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageLayout {
+    /// A single page is shown at a time. This is the default of PDF processors.
+    SinglePage,
+
+    /// The pages are shown in one column.
+    OneColumn,
+
+    /// The pages are shown in two columns, with odd-numbered pages on the left.
+    TwoColumnLeft,
+
+    /// The pages are shown in two columns, with odd-numbered pages on the right.
+    TwoColumnRight,
+
+    /// Two pages are shown at a time, with odd-numbered pages on the left (PDF 1.5).
+    TwoPageLeft,
+
+    /// Two pages are shown at a time, with odd-numbered pages on the right (PDF 1.5), e.g. to
+    /// show the spreads of a book with a cover page.
+    TwoPageRight,
+}
+
+impl PageLayout {
+    /// Returns the [`Identifier`] of this page layout.
+    fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            PageLayout::SinglePage => Identifier::from_static(b"SinglePage"),
+            PageLayout::OneColumn => Identifier::from_static(b"OneColumn"),
+            PageLayout::TwoColumnLeft => Identifier::from_static(b"TwoColumnLeft"),
+            PageLayout::TwoColumnRight => Identifier::from_static(b"TwoColumnRight"),
+            PageLayout::TwoPageLeft => Identifier::from_static(b"TwoPageLeft"),
+            PageLayout::TwoPageRight => Identifier::from_static(b"TwoPageRight"),
+        }
+    }
+}
+
 /// The root of a document’s object hierarchy, located by means of the `Root` entry in the trailer
 /// of the PDF file.
 ///
@@ -24,12 +106,60 @@ pub struct Catalog {
 
     /// Reference to the root [`PageTree`] of the PDF Document.
     root_page_tree: PageTree,
+
+    /// Reference to the root of the document part hierarchy (PDF 2.0), if the document has parts.
+    dpart_root: Option<ObjId<DocumentParts>>,
+
+    /// Reference to the root of the document outline, if the document has one.
+    outlines: Option<ObjId<DocumentOutline>>,
+
+    /// Reference to the name tree of the named destinations of the document, if it has any.
+    dests: Option<ObjId<NamedDestinations>>,
+
+    /// The destination shown when the document is opened, instead of the first page.
+    open_action: Option<DestinationRef>,
+
+    /// The navigation panel shown when the document is opened.
+    page_mode: Option<PageMode>,
+
+    /// The layout of the pages when the document is opened.
+    page_layout: Option<PageLayout>,
+
+    /// The natural language of the text of the document.
+    lang: Option<Language>,
+
+    /// The reading direction of the document, written in its viewer preferences.
+    direction: Option<TextDirection>,
+
+    /// Reference to the XMP metadata stream of the document, if it has one.
+    metadata: Option<ObjId<XmpMetadata>>,
+
+    /// Reference to the interactive form of the document, if it has any form fields.
+    acro_form: Option<ObjId<InteractiveForm>>,
+
+    /// Actions performed when the document is closed, saved or printed.
+    actions: BTreeMap<DocumentTrigger, Action>,
 }
 
 impl Catalog {
     const_identifiers! {
         CATALOG,
         PAGES,
+        D_PART_ROOT: b"DPartRoot",
+        OUTLINES,
+        NAMES,
+        DESTS,
+        OPEN_ACTION,
+        PAGE_MODE,
+        PAGE_LAYOUT,
+        LANG,
+        VIEWER_PREFERENCES,
+        DIRECTION,
+        L2R: b"L2R",
+        R2L: b"R2L",
+        METADATA,
+        ACRO_FORM,
+        AA: b"AA",
     }
 
     /// Create a new `Catalog` with the given [`ObjId`] and [`PageTree`].
@@ -37,6 +167,17 @@ impl Catalog {
         Self {
             id: obj_ref,
             root_page_tree,
+            dpart_root: None,
+            outlines: None,
+            dests: None,
+            open_action: None,
+            page_mode: None,
+            page_layout: None,
+            lang: None,
+            direction: None,
+            metadata: None,
+            acro_form: None,
+            actions: BTreeMap::new(),
         }
     }
 
@@ -45,6 +186,62 @@ impl Catalog {
         self.id.clone()
     }
 
+    /// Sets the reference to the root of the document part hierarchy.
+    pub(crate) fn set_dpart_root(&mut self, dpart_root: ObjId<DocumentParts>) {
+        self.dpart_root = Some(dpart_root);
+    }
+
+    /// Sets the reference to the root of the document outline.
+    pub(crate) fn set_outlines(&mut self, outlines: ObjId<DocumentOutline>) {
+        self.outlines = Some(outlines);
+    }
+
+    /// Sets the reference to the name tree of the named destinations of the document.
+    pub(crate) fn set_dests(&mut self, dests: ObjId<NamedDestinations>) {
+        self.dests = Some(dests);
+    }
+
+    /// Sets the reference to the XMP metadata stream of the document.
+    pub(crate) fn set_metadata(&mut self, metadata: ObjId<XmpMetadata>) {
+        self.metadata = Some(metadata);
+    }
+
+    /// Sets the reference to the interactive form of the document.
+    pub(crate) fn set_acro_form(&mut self, acro_form: ObjId<InteractiveForm>) {
+        self.acro_form = Some(acro_form);
+    }
+
+    /// Sets the destination shown when the document is opened.
+    pub(crate) fn set_open_action(&mut self, open_action: DestinationRef) {
+        self.open_action = Some(open_action);
+    }
+
+    /// Sets the [`PageMode`] the document is displayed with when it's opened.
+    pub(crate) fn set_page_mode(&mut self, page_mode: PageMode) {
+        self.page_mode = Some(page_mode);
+    }
+
+    /// Sets the [`PageLayout`] of the pages when the document is opened.
+    pub(crate) fn set_page_layout(&mut self, page_layout: PageLayout) {
+        self.page_layout = Some(page_layout);
+    }
+
+    /// Sets the natural [`Language`] of the text of the document.
+    pub(crate) fn set_lang(&mut self, lang: Language) {
+        self.lang = Some(lang);
+    }
+
+    /// Sets the [`TextDirection`] in which the document is read, e.g. to lay out the pages of a
+    /// right-to-left document from right to left when they are shown side by side.
+    pub(crate) fn set_direction(&mut self, direction: TextDirection) {
+        self.direction = Some(direction);
+    }
+
+    /// Sets the [`Action`] performed when the given [`DocumentTrigger`] occurs.
+    pub(crate) fn set_action(&mut self, trigger: DocumentTrigger, action: Action) {
+        self.actions.insert(trigger, action);
+    }
+
     /// Returns a reference to the root [`PageTree`] that this `Catalog` holds.
     pub(crate) fn page_tree(&self) -> &PageTree {
         &self.root_page_tree
@@ -75,6 +272,86 @@ impl Object for Catalog {
             Self::PAGES.write(writer),
             self.root_page_tree.obj_ref().write_ref(writer),
 
+            if let Some(dpart_root) = &self.dpart_root {
+                writer.write(constants::NL_MARKER),
+                Self::D_PART_ROOT.write(writer),
+                dpart_root.write_ref(writer),
+            },
+
+            if let Some(outlines) = &self.outlines {
+                writer.write(constants::NL_MARKER),
+                Self::OUTLINES.write(writer),
+                outlines.write_ref(writer),
+            },
+
+            if let Some(dests) = &self.dests {
+                writer.write(constants::NL_MARKER),
+                Self::NAMES.write(writer),
+                writer.write(b"<< "),
+                Self::DESTS.write(writer),
+                dests.write_ref(writer),
+                writer.write(b" >>"),
+            },
+
+            if let Some(open_action) = &self.open_action {
+                writer.write(constants::NL_MARKER),
+                Self::OPEN_ACTION.write(writer),
+                open_action.write(writer),
+            },
+
+            if let Some(page_mode) = self.page_mode {
+                writer.write(constants::NL_MARKER),
+                Self::PAGE_MODE.write(writer),
+                page_mode.identifier().write(writer),
+            },
+
+            if let Some(page_layout) = self.page_layout {
+                writer.write(constants::NL_MARKER),
+                Self::PAGE_LAYOUT.write(writer),
+                page_layout.identifier().write(writer),
+            },
+
+            if let Some(lang) = &self.lang {
+                writer.write(constants::NL_MARKER),
+                Self::LANG.write(writer),
+                lang.write(writer),
+            },
+
+            if let Some(direction) = self.direction {
+                writer.write(constants::NL_MARKER),
+                Self::VIEWER_PREFERENCES.write(writer),
+                writer.write(b"<< "),
+                Self::DIRECTION.write(writer),
+                match direction {
+                    TextDirection::LeftToRight => Self::L2R.write(writer),
+                    TextDirection::RightToLeft => Self::R2L.write(writer),
+                },
+                writer.write(b">>"),
+            },
+
+            if let Some(metadata) = &self.metadata {
+                writer.write(constants::NL_MARKER),
+                Self::METADATA.write(writer),
+                metadata.write_ref(writer),
+            },
+
+            if let Some(acro_form) = &self.acro_form {
+                writer.write(constants::NL_MARKER),
+                Self::ACRO_FORM.write(writer),
+                acro_form.write_ref(writer),
+            },
+
+            if !self.actions.is_empty() {
+                writer.write(constants::NL_MARKER),
+                Self::AA.write(writer),
+                action::write_additional_actions(
+                    writer,
+                    self.actions
+                        .iter()
+                        .map(|(trigger, action)| (trigger.identifier(), action)),
+                ),
+            },
+
             writer.write(b" >>"),
             writer.write(constants::NL_MARKER),
         };
@@ -85,12 +362,19 @@ impl Object for Catalog {
 
 #[cfg(test)]
 mod tests {
+    use std::str::FromStr;
+
     use crate::{
         IdManager,
-        types::hierarchy::{page_tree::PageTree, primitives::object::Object},
+        types::hierarchy::{
+            action::{Action, DocumentTrigger, NamedAction},
+            content::bidi::TextDirection,
+            page_tree::PageTree,
+            primitives::{language::Language, object::Object},
+        },
     };
 
-    use super::Catalog;
+    use super::{Catalog, PageLayout, PageMode};
 
     #[test]
     fn simple_catalog() {
@@ -107,4 +391,69 @@ mod tests {
         /Pages 1 0 R >>
         ");
     }
+
+    #[test]
+    fn catalog_actions() {
+        let mut id_manager = IdManager::new();
+        let page_tree = PageTree::new(id_manager.create_id(), None);
+        let mut catalog = Catalog::new(id_manager.create_id(), page_tree);
+        catalog.set_action(
+            DocumentTrigger::WillPrint,
+            Action::JavaScript("stamp();".to_string()),
+        );
+        catalog.set_action(
+            DocumentTrigger::WillClose,
+            Action::Named(NamedAction::FirstPage),
+        );
+
+        let mut writer = Vec::default();
+        catalog.write_content(&mut writer).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        << /Type /Catalog 
+        /Pages 1 0 R
+        /AA << /WC << /Type /Action /S /Named /N /FirstPage >> /WP << /Type /Action /S /JavaScript /JS (stamp\(\);) >> >> >>
+        ");
+    }
+
+    #[test]
+    fn catalog_initial_view() {
+        let mut id_manager = IdManager::new();
+        let page_tree = PageTree::new(id_manager.create_id(), None);
+        let mut catalog = Catalog::new(id_manager.create_id(), page_tree);
+        catalog.set_page_mode(PageMode::UseOutlines);
+        catalog.set_page_layout(PageLayout::TwoPageRight);
+
+        let mut writer = Vec::default();
+        catalog.write_content(&mut writer).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        << /Type /Catalog 
+        /Pages 1 0 R
+        /PageMode /UseOutlines 
+        /PageLayout /TwoPageRight  >>
+        ");
+    }
+
+    #[test]
+    fn catalog_language() {
+        let mut id_manager = IdManager::new();
+        let page_tree = PageTree::new(id_manager.create_id(), None);
+        let mut catalog = Catalog::new(id_manager.create_id(), page_tree);
+        catalog.set_lang(Language::from_str("he-IL").unwrap());
+        catalog.set_direction(TextDirection::RightToLeft);
+
+        let mut writer = Vec::default();
+        catalog.write_content(&mut writer).unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        << /Type /Catalog 
+        /Pages 1 0 R
+        /Lang (he-IL)
+        /ViewerPreferences << /Direction /R2L >> >>
+        ");
+    }
 }
@@ -0,0 +1,164 @@
+//! Reordering of right-to-left text, such as Hebrew or Arabic, from the logical order it's stored
+//! in to the visual order it's shown in.
+
+/// Direction in which the characters of a [`Text`] are read.
+///
+/// Strings in content streams are shown from left to right, so text in a right-to-left direction is
+/// reordered into visual order when it's written: runs of right-to-left characters are reversed,
+/// with mirrored brackets, and runs of left-to-right characters, such as numbers or Latin words,
+/// keep their order. This is a simplification of the Unicode Bidirectional Algorithm, which is
+/// enough for text without explicit embedding levels.
+///
+/// Strings of a [`TextRun`] are never reordered, so text shaped and reordered by an external
+/// shaper, e.g. Arabic presentation forms in visual order, can be shown as it is.
+///
+/// [`Text`]: super::text::Text
+/// [`TextRun`]: super::text_run::TextRun
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    /// The text is read from left to right, and shown as it is.
+    #[default]
+    LeftToRight,
+
+    /// The text is read from right to left, and reordered into visual order when it's shown.
+    RightToLeft,
+}
+
+/// Bidirectional class of a single character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    /// Character of a right-to-left script.
+    Rtl,
+
+    /// Letter of a left-to-right script.
+    Ltr,
+
+    /// Digit, or a separator between two digits, e.g. the decimal point of `120.50`.
+    Number,
+
+    /// White-space or punctuation, taking the direction of the characters around it.
+    Neutral,
+}
+
+impl Class {
+    /// Returns the class of the given character.
+    fn of(ch: char) -> Self {
+        match ch {
+            // Hebrew, Arabic, Syriac, Thaana, N'Ko, Samaritan, Mandaic and their presentation
+            // forms
+            '\u{0590}'..='\u{08FF}' | '\u{FB1D}'..='\u{FDFF}' | '\u{FE70}'..='\u{FEFF}' => {
+                Class::Rtl
+            }
+            _ if ch.is_numeric() => Class::Number,
+            _ if ch.is_alphabetic() => Class::Ltr,
+            _ => Class::Neutral,
+        }
+    }
+
+    /// Returns whether characters of this class take part in a run shown from left to right.
+    fn is_left_to_right(self) -> bool {
+        matches!(self, Class::Ltr | Class::Number)
+    }
+}
+
+/// Returns the given text, stored in logical order, in the visual order it's shown in with the
+/// given [`TextDirection`].
+pub(crate) fn visual_order(text: &str, direction: TextDirection) -> String {
+    if direction == TextDirection::LeftToRight {
+        return text.to_string();
+    }
+
+    let chars: Vec<char> = text.chars().collect();
+    let mut classes: Vec<Class> = chars.iter().copied().map(Class::of).collect();
+
+    // separators between two digits are part of the number
+    for idx in 1..classes.len().saturating_sub(1) {
+        let separator = matches!(chars[idx], '.' | ',' | ':' | '/');
+        if separator && classes[idx - 1] == Class::Number && classes[idx + 1] == Class::Number {
+            classes[idx] = Class::Number;
+        }
+    }
+
+    // neutrals between two left-to-right letters are left-to-right, all others take the
+    // right-to-left direction of the text. Numbers count as right-to-left here, so that the
+    // punctuation between a word and a number stays with the right-to-left text.
+    let mut previous = Class::Rtl;
+    for idx in 0..classes.len() {
+        match classes[idx] {
+            Class::Neutral => {
+                let next = classes[idx..]
+                    .iter()
+                    .copied()
+                    .find(|class| *class != Class::Neutral)
+                    .unwrap_or(Class::Rtl);
+                classes[idx] = if previous == Class::Ltr && next == Class::Ltr {
+                    Class::Ltr
+                } else {
+                    Class::Rtl
+                };
+            }
+            class => previous = class,
+        }
+    }
+
+    // the whole text is reversed, and then each left-to-right run back into its own order
+    let mut visual = Vec::with_capacity(chars.len());
+    let mut run = Vec::new();
+    for (ch, class) in chars.into_iter().zip(classes).rev() {
+        if class.is_left_to_right() {
+            run.push(ch);
+            continue;
+        }
+
+        visual.extend(run.drain(..).rev());
+        visual.push(mirrored(ch));
+    }
+    visual.extend(run.drain(..).rev());
+
+    visual.into_iter().collect()
+}
+
+/// Returns the mirrored counterpart of the given character, e.g. `)` for `(`, which is shown in
+/// its place in right-to-left text.
+fn mirrored(ch: char) -> char {
+    match ch {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        _ => ch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TextDirection, visual_order};
+
+    #[test]
+    fn left_to_right() {
+        assert_eq!(
+            visual_order("Total (EUR)", TextDirection::LeftToRight),
+            "Total (EUR)"
+        );
+    }
+
+    #[test]
+    fn right_to_left() {
+        // "shalom olam"
+        assert_eq!(
+            visual_order("שלום עולם", TextDirection::RightToLeft),
+            "םלוע םולש"
+        );
+        // numbers and Latin words keep their order, brackets are mirrored
+        assert_eq!(
+            visual_order("מחיר (VAT incl) 120.50", TextDirection::RightToLeft),
+            "120.50 (VAT incl) ריחמ"
+        );
+    }
+}
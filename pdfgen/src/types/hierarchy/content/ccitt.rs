@@ -0,0 +1,406 @@
+//! CCITT Group 3 and Group 4 fax compressed image data, embedded in PDF without decoding it.
+
+use std::{
+    collections::HashMap,
+    io::{Error, Write},
+};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::types::hierarchy::primitives::identifier::Identifier;
+
+/// Errors that can occur when reading CCITT fax compressed image data from a TIFF file.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum CcittErr {
+    /// Indicates that the data is not a valid TIFF file.
+    #[error("Data is not a valid TIFF file.")]
+    InvalidTiff,
+
+    /// Indicates that the TIFF file is not compressed with CCITT Group 3 or Group 4 compression.
+    #[error("TIFF compression '{0}' is not CCITT Group 3 or Group 4 compression.")]
+    UnsupportedCompression(u16),
+
+    /// Indicates that the TIFF file uses the uncompressed mode of CCITT compression.
+    #[error("Uncompressed mode of CCITT compression is not supported.")]
+    UncompressedMode,
+
+    /// Indicates that the image of the TIFF file is split into more than one strip.
+    #[error("TIFF image must consist of a single strip.")]
+    MultipleStrips,
+}
+
+/// Parameters of CCITT fax compressed image data, written as the /DecodeParms of the
+/// /CCITTFaxDecode filter.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::{ccitt::CcittParams, image::Image};
+/// # let fax_data = Vec::new();
+/// // Group 4 compressed scan of an A4 page at 200 dpi
+/// let params = CcittParams::group_4(1728, 2339);
+/// let builder = Image::from_ccitt(fax_data, params);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+pub struct CcittParams {
+    /// Encoding scheme: negative for pure two-dimensional encoding (Group 4), 0 for pure
+    /// one-dimensional encoding (Group 3, 1-D) and positive for mixed one- and two-dimensional
+    /// encoding (Group 3, 2-D).
+    k: i32,
+
+    /// Width of the image in pixels.
+    columns: u32,
+
+    /// Height of the image in pixels.
+    rows: u32,
+
+    /// Whether 1 bits of the decoded data represent black pixels, instead of 0 bits.
+    black_is_1: bool,
+
+    /// Whether each encoded line begins on a byte boundary.
+    encoded_byte_align: bool,
+}
+
+impl CcittParams {
+    const_identifiers! {
+        K,
+        COLUMNS,
+        ROWS,
+        BLACK_IS_1: b"BlackIs1",
+        ENCODED_BYTE_ALIGN,
+    }
+
+    /// Value of K for mixed one- and two-dimensional encoding. Decoders read whether each line is
+    /// encoded one- or two-dimensionally from the data, so only the sign of K matters.
+    const MIXED_K: i32 = 4;
+
+    /// Creates the parameters of a Group 4 (pure two-dimensional) encoded image with the given
+    /// width and height in pixels.
+    pub fn group_4(columns: u32, rows: u32) -> Self {
+        Self::new(-1, columns, rows)
+    }
+
+    /// Creates the parameters of a Group 3 encoded image with the given width and height in
+    /// pixels, either one-dimensionally encoded, or mixed one- and two-dimensionally encoded.
+    pub fn group_3(columns: u32, rows: u32, two_dimensional: bool) -> Self {
+        let k = if two_dimensional { Self::MIXED_K } else { 0 };
+        Self::new(k, columns, rows)
+    }
+
+    fn new(k: i32, columns: u32, rows: u32) -> Self {
+        Self {
+            k,
+            columns,
+            rows,
+            black_is_1: false,
+            encoded_byte_align: false,
+        }
+    }
+
+    /// Sets whether 1 bits of the decoded data represent black pixels. By default, 0 bits are
+    /// black, which is the convention of fax data.
+    pub fn with_black_is_1(mut self, black_is_1: bool) -> Self {
+        self.black_is_1 = black_is_1;
+        self
+    }
+
+    /// Sets whether each encoded line is padded with fill bits to begin on a byte boundary.
+    pub fn with_encoded_byte_align(mut self, encoded_byte_align: bool) -> Self {
+        self.encoded_byte_align = encoded_byte_align;
+        self
+    }
+
+    /// Returns the width of the image in pixels.
+    pub(crate) fn columns(&self) -> u32 {
+        self.columns
+    }
+
+    /// Returns the height of the image in pixels.
+    pub(crate) fn rows(&self) -> u32 {
+        self.rows
+    }
+
+    /// Writes the decode parameters dictionary. Entries with default values are omitted.
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Self::K.write(writer),
+            crate::write_fmt!(&mut *writer, "{} ", self.k),
+            Self::COLUMNS.write(writer),
+            crate::write_fmt!(&mut *writer, "{} ", self.columns),
+            Self::ROWS.write(writer),
+            crate::write_fmt!(&mut *writer, "{} ", self.rows),
+
+            if self.black_is_1 {
+                Self::BLACK_IS_1.write(writer),
+                writer.write(b"true "),
+            },
+
+            if self.encoded_byte_align {
+                Self::ENCODED_BYTE_ALIGN.write(writer),
+                writer.write(b"true "),
+            },
+            writer.write(b">>"),
+        })
+    }
+}
+
+/// TIFF tags read from a CCITT compressed TIFF file.
+mod tag {
+    pub(super) const IMAGE_WIDTH: u16 = 256;
+    pub(super) const IMAGE_LENGTH: u16 = 257;
+    pub(super) const COMPRESSION: u16 = 259;
+    pub(super) const PHOTOMETRIC_INTERPRETATION: u16 = 262;
+    pub(super) const FILL_ORDER: u16 = 266;
+    pub(super) const STRIP_OFFSETS: u16 = 273;
+    pub(super) const STRIP_BYTE_COUNTS: u16 = 279;
+    pub(super) const T4_OPTIONS: u16 = 292;
+    pub(super) const T6_OPTIONS: u16 = 293;
+}
+
+/// Byte order of the values in a TIFF file.
+#[derive(Clone, Copy)]
+enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+impl ByteOrder {
+    fn u16(self, bytes: &[u8], at: usize) -> Result<u16, CcittErr> {
+        let bytes = bytes
+            .get(at..at + 2)
+            .ok_or(CcittErr::InvalidTiff)?
+            .try_into()
+            .expect("slice has 2 bytes");
+
+        Ok(match self {
+            ByteOrder::LittleEndian => u16::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn u32(self, bytes: &[u8], at: usize) -> Result<u32, CcittErr> {
+        let bytes = bytes
+            .get(at..at + 4)
+            .ok_or(CcittErr::InvalidTiff)?
+            .try_into()
+            .expect("slice has 4 bytes");
+
+        Ok(match self {
+            ByteOrder::LittleEndian => u32::from_le_bytes(bytes),
+            ByteOrder::BigEndian => u32::from_be_bytes(bytes),
+        })
+    }
+}
+
+/// Reads the CCITT compressed data and its parameters from the first image of a TIFF file.
+pub(crate) fn read_tiff(bytes: &[u8]) -> Result<(Vec<u8>, CcittParams), CcittErr> {
+    const SHORT: u16 = 3;
+    const LONG: u16 = 4;
+
+    let order = match bytes.get(..4) {
+        Some(b"II*\0") => ByteOrder::LittleEndian,
+        Some(b"MM\0*") => ByteOrder::BigEndian,
+        _ => return Err(CcittErr::InvalidTiff),
+    };
+
+    let ifd = order.u32(bytes, 4)? as usize;
+    let entries = order.u16(bytes, ifd)?;
+
+    // NOTE: only tags with a single SHORT or LONG value are read, which is stored in the entry
+    //       itself. Tags with multiple values are recorded without a value.
+    let mut values = HashMap::new();
+    for idx in 0..usize::from(entries) {
+        let entry = ifd + 2 + idx * 12;
+        let value = match (order.u16(bytes, entry + 2)?, order.u32(bytes, entry + 4)?) {
+            (SHORT, 1) => Some(u32::from(order.u16(bytes, entry + 8)?)),
+            (LONG, 1) => Some(order.u32(bytes, entry + 8)?),
+            _ => None,
+        };
+        values.insert(order.u16(bytes, entry)?, value);
+    }
+
+    let value = |tag: u16| match values.get(&tag) {
+        Some(Some(value)) => Ok(Some(*value)),
+        Some(None) => Err(CcittErr::InvalidTiff),
+        None => Ok(None),
+    };
+    let required = |tag: u16| value(tag)?.ok_or(CcittErr::InvalidTiff);
+
+    let columns = required(tag::IMAGE_WIDTH)?;
+    let rows = required(tag::IMAGE_LENGTH)?;
+
+    // NOTE: strips of two-dimensionally encoded images can't be joined, as each strip starts
+    //       encoding anew.
+    if [tag::STRIP_OFFSETS, tag::STRIP_BYTE_COUNTS]
+        .iter()
+        .any(|tag| values.get(tag) == Some(&None))
+    {
+        return Err(CcittErr::MultipleStrips);
+    }
+    let offset = required(tag::STRIP_OFFSETS)? as usize;
+    let len = required(tag::STRIP_BYTE_COUNTS)? as usize;
+
+    let params = match required(tag::COMPRESSION)? {
+        3 => {
+            let options = value(tag::T4_OPTIONS)?.unwrap_or(0);
+            if options & 0b10 != 0 {
+                return Err(CcittErr::UncompressedMode);
+            }
+
+            CcittParams::group_3(columns, rows, options & 0b1 != 0)
+                .with_encoded_byte_align(options & 0b100 != 0)
+        }
+        4 => {
+            if value(tag::T6_OPTIONS)?.unwrap_or(0) & 0b10 != 0 {
+                return Err(CcittErr::UncompressedMode);
+            }
+
+            CcittParams::group_4(columns, rows)
+        }
+        compression => return Err(CcittErr::UnsupportedCompression(compression as u16)),
+    };
+
+    // NOTE: fax data codes runs of white and black pixels, decoded into 0 bits for white by TIFF
+    //       readers. Images with BlackIsZero photometric interpretation show those bits as black,
+    //       so they are inverted.
+    let params = params.with_black_is_1(value(tag::PHOTOMETRIC_INTERPRETATION)? == Some(1));
+
+    let mut data = bytes
+        .get(offset..offset + len)
+        .ok_or(CcittErr::InvalidTiff)?
+        .to_vec();
+
+    // NOTE: fill order 2 stores the bits of each byte starting with the least significant bit.
+    if value(tag::FILL_ORDER)? == Some(2) {
+        data.iter_mut().for_each(|byte| *byte = byte.reverse_bits());
+    }
+
+    Ok((data, params))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::{content::image::Image, primitives::rectangle::Position},
+    };
+
+    use super::{CcittErr, CcittParams, read_tiff};
+
+    /// Creates a little endian TIFF file with the given data and (tag, value) entries, in
+    /// addition to the strip offset and byte count of the data.
+    fn tiff(data: &[u8], entries: &[(u16, u16)]) -> Vec<u8> {
+        let mut entries: Vec<(u16, u16, u32)> = entries
+            .iter()
+            .map(|&(tag, value)| (tag, 3, value.into()))
+            .collect();
+        let data_offset = 8 + 2 + (entries.len() + 2) * 12 + 4;
+        entries.push((273, 4, data_offset as u32));
+        entries.push((279, 4, data.len() as u32));
+        entries.sort_by_key(|(tag, ..)| *tag);
+
+        let mut bytes = b"II*\0".to_vec();
+        bytes.extend(8u32.to_le_bytes());
+        bytes.extend((entries.len() as u16).to_le_bytes());
+        for (tag, kind, value) in entries {
+            bytes.extend(tag.to_le_bytes());
+            bytes.extend(kind.to_le_bytes());
+            bytes.extend(1u32.to_le_bytes());
+            bytes.extend(value.to_le_bytes());
+        }
+        bytes.extend(0u32.to_le_bytes());
+        bytes.extend(data);
+
+        bytes
+    }
+
+    #[test]
+    fn group_4_tiff() {
+        let bytes = tiff(b"fax", &[(256, 1728), (257, 100), (259, 4), (262, 0)]);
+
+        let (data, params) = read_tiff(&bytes).unwrap();
+
+        assert_eq!(data, b"fax");
+        assert_eq!(params, CcittParams::group_4(1728, 100));
+    }
+
+    #[test]
+    fn group_3_tiff() {
+        let bytes = tiff(
+            &[0b1000_0000, 0b0000_0001],
+            &[
+                (256, 8),
+                (257, 2),
+                (259, 3),
+                (262, 1),
+                (266, 2),
+                (292, 0b101),
+            ],
+        );
+
+        let (data, params) = read_tiff(&bytes).unwrap();
+
+        assert_eq!(data, [0b0000_0001, 0b1000_0000]);
+        assert_eq!(
+            params,
+            CcittParams::group_3(8, 2, true)
+                .with_black_is_1(true)
+                .with_encoded_byte_align(true)
+        );
+    }
+
+    #[test]
+    fn unsupported_tiff() {
+        let lzw = tiff(b"", &[(256, 8), (257, 2), (259, 5)]);
+        assert_eq!(read_tiff(&lzw), Err(CcittErr::UnsupportedCompression(5)));
+
+        let uncompressed = tiff(b"", &[(256, 8), (257, 2), (259, 4), (293, 0b10)]);
+        assert_eq!(read_tiff(&uncompressed), Err(CcittErr::UncompressedMode));
+
+        assert_eq!(read_tiff(b"GIF89a"), Err(CcittErr::InvalidTiff));
+    }
+
+    #[test]
+    fn ccitt_image() {
+        let bytes = tiff(b"fax", &[(256, 1728), (257, 100), (259, 4), (262, 1)]);
+        let img = Image::from_ccitt_tiff(&bytes)
+            .unwrap()
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 1728
+        /Height 100
+        /ColorSpace /DeviceGray 
+        /BitsPerComponent 1
+        /Filter /CCITTFaxDecode 
+        /DecodeParms << /K -1 /Columns 1728 /Rows 100 /BlackIs1 true >>
+        /Length 3 >>
+        stream
+        fax
+        endstream
+        endobj
+        ");
+    }
+
+    #[test]
+    fn decode_params() {
+        let mut writer = Vec::default();
+        CcittParams::group_3(1728, 10, false)
+            .with_encoded_byte_align(true)
+            .write(&mut writer)
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @"<< /K 0 /Columns 1728 /Rows 10 /EncodedByteAlign true >>");
+    }
+}
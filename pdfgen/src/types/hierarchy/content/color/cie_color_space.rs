@@ -0,0 +1,428 @@
+//! Implementation of CIE-based colour spaces, which specify colours relative to the CIE 1931 XYZ
+//! colour space, independent of the characteristics of any particular output device.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    ObjId,
+    types::{
+        constants,
+        hierarchy::primitives::{array::WriteArray, identifier::Identifier, object::Object},
+    },
+};
+
+/// Tristimulus value of the CIE standard illuminant D50 in CIE 1931 XYZ colour space, which is the
+/// white point used by ICC profiles.
+pub const D50: [f32; 3] = [0.9642, 1.0, 0.8249];
+
+/// Tristimulus value of the CIE standard illuminant D65 in CIE 1931 XYZ colour space, which is the
+/// white point of sRGB.
+pub const D65: [f32; 3] = [0.9505, 1.0, 1.089];
+
+/// Checks that the given white point is a valid tristimulus value of a diffuse white point.
+fn assert_white_point(white_point: [f32; 3]) {
+    let [x, y, z] = white_point;
+    assert!(
+        x > 0. && y == 1. && z > 0.,
+        "The white point must have positive X and Z values and a Y value of 1.0."
+    );
+}
+
+/// Writes the given values as a PDF array, followed by a space.
+fn write_numbers(writer: &mut dyn Write, values: &[f32]) -> Result<usize, Error> {
+    Ok(pdfgen_macros::write_chain! {
+        values.write_array(writer, None),
+        writer.write(b" "),
+    })
+}
+
+/// A CalGray colour space is a CIE-based colour space with a single transformation stage, with
+/// the gray component mapped by a gamma function.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::color::{CalGray, D65};
+/// let gray = CalGray::new(D65).with_gamma(2.2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalGray {
+    /// Tristimulus value of the diffuse white point.
+    white_point: [f32; 3],
+
+    /// Tristimulus value of the diffuse black point.
+    black_point: Option<[f32; 3]>,
+
+    /// Gamma of the gray component.
+    gamma: Option<f32>,
+}
+
+impl CalGray {
+    const_identifiers! {
+        CAL_GRAY: b"CalGray",
+    }
+
+    /// Creates a new `CalGray` colour space with the given diffuse white point, such as [`D65`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the white point doesn't have positive X and Z values and a Y value of 1.0.
+    pub fn new(white_point: [f32; 3]) -> Self {
+        assert_white_point(white_point);
+
+        Self {
+            white_point,
+            black_point: None,
+            gamma: None,
+        }
+    }
+
+    /// Sets the diffuse black point of this colour space. Defaults to `[0 0 0]`.
+    pub fn with_black_point(mut self, black_point: [f32; 3]) -> Self {
+        self.black_point = Some(black_point);
+        self
+    }
+
+    /// Sets the gamma of the gray component of this colour space. Defaults to 1.
+    pub fn with_gamma(mut self, gamma: f32) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+}
+
+/// A CalRGB colour space is a CIE-based colour space with a single transformation stage, with
+/// the red, green and blue components mapped by gamma functions and a linear transformation into
+/// CIE 1931 XYZ colour space.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::color::{CalRgb, D65};
+/// let rgb = CalRgb::new(D65).with_gamma([2.2, 2.2, 2.2]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CalRgb {
+    /// Tristimulus value of the diffuse white point.
+    white_point: [f32; 3],
+
+    /// Tristimulus value of the diffuse black point.
+    black_point: Option<[f32; 3]>,
+
+    /// Gamma of the red, green and blue components.
+    gamma: Option<[f32; 3]>,
+
+    /// Linear transformation of the decoded components into CIE 1931 XYZ colour space, in
+    /// column-major order `[X_A Y_A Z_A X_B Y_B Z_B X_C Y_C Z_C]`.
+    matrix: Option<[f32; 9]>,
+}
+
+impl CalRgb {
+    const_identifiers! {
+        CAL_RGB: b"CalRGB",
+        MATRIX,
+    }
+
+    /// Creates a new `CalRgb` colour space with the given diffuse white point, such as [`D65`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the white point doesn't have positive X and Z values and a Y value of 1.0.
+    pub fn new(white_point: [f32; 3]) -> Self {
+        assert_white_point(white_point);
+
+        Self {
+            white_point,
+            black_point: None,
+            gamma: None,
+            matrix: None,
+        }
+    }
+
+    /// Sets the diffuse black point of this colour space. Defaults to `[0 0 0]`.
+    pub fn with_black_point(mut self, black_point: [f32; 3]) -> Self {
+        self.black_point = Some(black_point);
+        self
+    }
+
+    /// Sets the gamma of the red, green and blue components of this colour space. Defaults to
+    /// `[1 1 1]`.
+    pub fn with_gamma(mut self, gamma: [f32; 3]) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Sets the linear transformation of the decoded components into CIE 1931 XYZ colour space,
+    /// in column-major order `[X_A Y_A Z_A X_B Y_B Z_B X_C Y_C Z_C]`. Defaults to the identity
+    /// matrix.
+    pub fn with_matrix(mut self, matrix: [f32; 9]) -> Self {
+        self.matrix = Some(matrix);
+        self
+    }
+}
+
+/// A Lab colour space is a CIE-based colour space with two transformation stages, where colours
+/// are specified by the lightness L* and the chromaticity components a* and b* of the CIE 1976
+/// L*a*b* colour space.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::color::{D50, Lab};
+/// let lab = Lab::new(D50).with_range([-128., 127., -128., 127.]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lab {
+    /// Tristimulus value of the diffuse white point.
+    white_point: [f32; 3],
+
+    /// Tristimulus value of the diffuse black point.
+    black_point: Option<[f32; 3]>,
+
+    /// Range of the a* and b* components, `[a_min a_max b_min b_max]`.
+    range: Option<[f32; 4]>,
+}
+
+impl Lab {
+    const_identifiers! {
+        LAB: b"Lab",
+        RANGE,
+    }
+
+    /// Creates a new `Lab` colour space with the given diffuse white point, such as [`D50`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the white point doesn't have positive X and Z values and a Y value of 1.0.
+    pub fn new(white_point: [f32; 3]) -> Self {
+        assert_white_point(white_point);
+
+        Self {
+            white_point,
+            black_point: None,
+            range: None,
+        }
+    }
+
+    /// Sets the diffuse black point of this colour space. Defaults to `[0 0 0]`.
+    pub fn with_black_point(mut self, black_point: [f32; 3]) -> Self {
+        self.black_point = Some(black_point);
+        self
+    }
+
+    /// Sets the range of the a* and b* components of this colour space, `[a_min a_max b_min
+    /// b_max]`. Colour values outside of the range are clipped to it. Defaults to
+    /// `[-100 100 -100 100]`.
+    pub fn with_range(mut self, range: [f32; 4]) -> Self {
+        self.range = Some(range);
+        self
+    }
+}
+
+/// A CIE-based colour space, created in a document with [`Document::create_cie_color_space`].
+///
+/// [`Document::create_cie_color_space`]: crate::Document::create_cie_color_space
+#[derive(Debug, Clone, PartialEq)]
+pub enum CieColorSpace {
+    /// See [`CalGray`].
+    CalGray(CalGray),
+
+    /// See [`CalRgb`].
+    CalRgb(CalRgb),
+
+    /// See [`Lab`].
+    Lab(Lab),
+}
+
+impl CieColorSpace {
+    const_identifiers! {
+        WHITE_POINT,
+        BLACK_POINT,
+        GAMMA,
+    }
+
+    /// Returns the [`CieFamily`] of this colour space.
+    pub(crate) fn family(&self) -> CieFamily {
+        match self {
+            CieColorSpace::CalGray(_) => CieFamily::CalGray,
+            CieColorSpace::CalRgb(_) => CieFamily::CalRgb,
+            CieColorSpace::Lab(_) => CieFamily::Lab,
+        }
+    }
+
+    /// Writes the colour space array of this colour space, e.g. `[/CalGray << /WhitePoint [..] >>]`.
+    fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let (name, white_point, black_point) = match self {
+            CieColorSpace::CalGray(cal_gray) => (
+                CalGray::CAL_GRAY,
+                cal_gray.white_point,
+                cal_gray.black_point,
+            ),
+            CieColorSpace::CalRgb(cal_rgb) => {
+                (CalRgb::CAL_RGB, cal_rgb.white_point, cal_rgb.black_point)
+            }
+            CieColorSpace::Lab(lab) => (Lab::LAB, lab.white_point, lab.black_point),
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"["),
+            name.write(writer),
+            writer.write(b"<< "),
+
+            Self::WHITE_POINT.write(writer),
+            write_numbers(writer, &white_point),
+
+            if let Some(black_point) = black_point {
+                Self::BLACK_POINT.write(writer),
+                write_numbers(writer, &black_point),
+            },
+
+            self.write_entries(writer),
+
+            writer.write(b">>]"),
+        })
+    }
+
+    /// Writes the entries specific to the family of this colour space.
+    fn write_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(match self {
+            CieColorSpace::CalGray(CalGray { gamma, .. }) => pdfgen_macros::write_chain! {
+                if let Some(gamma) = gamma {
+                    Self::GAMMA.write(writer),
+                    crate::write_fmt!(&mut *writer, "{gamma} "),
+                },
+            },
+            CieColorSpace::CalRgb(CalRgb { gamma, matrix, .. }) => pdfgen_macros::write_chain! {
+                if let Some(gamma) = gamma {
+                    Self::GAMMA.write(writer),
+                    write_numbers(writer, gamma),
+                },
+
+                if let Some(matrix) = matrix {
+                    CalRgb::MATRIX.write(writer),
+                    write_numbers(writer, matrix),
+                },
+            },
+            CieColorSpace::Lab(Lab { range, .. }) => pdfgen_macros::write_chain! {
+                if let Some(range) = range {
+                    Lab::RANGE.write(writer),
+                    write_numbers(writer, range),
+                },
+            },
+        })
+    }
+}
+
+impl From<CalGray> for CieColorSpace {
+    fn from(cal_gray: CalGray) -> Self {
+        Self::CalGray(cal_gray)
+    }
+}
+
+impl From<CalRgb> for CieColorSpace {
+    fn from(cal_rgb: CalRgb) -> Self {
+        Self::CalRgb(cal_rgb)
+    }
+}
+
+impl From<Lab> for CieColorSpace {
+    fn from(lab: Lab) -> Self {
+        Self::Lab(lab)
+    }
+}
+
+/// Family of a [`CieColorSpace`], which determines the colour components of its colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum CieFamily {
+    CalGray,
+    CalRgb,
+    Lab,
+}
+
+impl CieFamily {
+    /// Returns the number of colour components of colour spaces of this family.
+    pub(crate) fn components(self) -> usize {
+        match self {
+            CieFamily::CalGray => 1,
+            CieFamily::CalRgb | CieFamily::Lab => 3,
+        }
+    }
+}
+
+/// A [`CieColorSpace`] written as an indirect object, so colours and images in the colour space
+/// can refer to it.
+#[derive(Debug, Clone)]
+pub(crate) struct CieColorSpaceObject {
+    /// ID of this `CieColorSpaceObject`.
+    pub(crate) id: ObjId<Self>,
+
+    /// The written colour space.
+    color_space: CieColorSpace,
+}
+
+impl CieColorSpaceObject {
+    /// Creates a new `CieColorSpaceObject` with the given [`ObjId`] and colour space.
+    pub(crate) fn new(id: ObjId<Self>, color_space: CieColorSpace) -> Self {
+        Self { id, color_space }
+    }
+}
+
+impl Object for CieColorSpaceObject {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.color_space.write(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalGray, CalRgb, CieColorSpace, D50, D65, Lab};
+
+    #[test]
+    fn cie_color_spaces() {
+        let color_spaces: [CieColorSpace; 4] = [
+            CalGray::new(D65).into(),
+            CalGray::new(D65)
+                .with_black_point([0.01, 0.01, 0.01])
+                .with_gamma(2.2)
+                .into(),
+            CalRgb::new(D65)
+                .with_gamma([1.8, 1.8, 1.8])
+                .with_matrix([
+                    0.4497, 0.2446, 0.0252, 0.3163, 0.672, 0.1412, 0.1845, 0.0833, 0.9227,
+                ])
+                .into(),
+            Lab::new(D50).with_range([-128., 127., -128., 127.]).into(),
+        ];
+
+        let mut writer = Vec::new();
+        for color_space in &color_spaces {
+            color_space.write(&mut writer).unwrap();
+            writer.push(b'\n');
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [/CalGray << /WhitePoint [0.9505 1 1.089] >>]
+        [/CalGray << /WhitePoint [0.9505 1 1.089] /BlackPoint [0.01 0.01 0.01] /Gamma 2.2 >>]
+        [/CalRGB << /WhitePoint [0.9505 1 1.089] /Gamma [1.8 1.8 1.8] /Matrix [0.4497 0.2446 0.0252 0.3163 0.672 0.1412 0.1845 0.0833 0.9227] >>]
+        [/Lab << /WhitePoint [0.9642 1 0.8249] /Range [-128 127 -128 127] >>]
+        ");
+    }
+
+    #[test]
+    #[should_panic = "The white point must have positive X and Z values and a Y value of 1.0."]
+    fn invalid_white_point() {
+        let _ = Lab::new([0.9642, 0.5, 0.8249]);
+    }
+}
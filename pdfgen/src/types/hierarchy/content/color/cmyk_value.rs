@@ -11,7 +11,7 @@ pub enum CmykValueErr<T: fmt::Display> {
 }
 
 /// Newtype for ensuring correct values are used in CMYK color space.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
 pub struct CmykValue(u8);
 
 impl CmykValue {
@@ -42,13 +42,14 @@ impl TryFrom<u8> for CmykValue {
 impl TryFrom<f32> for CmykValue {
     type Error = CmykValueErr<f32>;
 
-    /// Create a new [`CmykValue`] from an [`f32`], with valid range being `[0.0, 1.0]`.
+    /// Create a new [`CmykValue`] from an [`f32`], with valid range being `[0.0, 1.0]`. The value
+    /// is rounded to the nearest percent.
     fn try_from(value: f32) -> Result<Self, Self::Error> {
         if !(0. ..=1.).contains(&value) {
             return Err(CmykValueErr::OutOfRange(value));
         }
 
-        let value = value * 100.;
+        let value = (value * 100.).round();
         Ok(Self(value as u8))
     }
 }
@@ -0,0 +1,123 @@
+//! Implementation of PDF colour spaces.
+
+use std::{
+    collections::HashMap,
+    io::{Error, Write},
+};
+
+use crate::{
+    CieColorSpaceId, IccProfileId, ObjId,
+    types::hierarchy::primitives::identifier::{Identifier, OwnedIdentifier},
+};
+
+use super::{CieColorSpaceObject, IccProfile};
+
+/// Errors that might be returned when setting the default colour space of a [`Page`].
+///
+/// [`Page`]: crate::types::hierarchy::page::Page
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum DefaultColorSpaceErr {
+    /// Indicates that a device colour space was given, while default colour spaces must be
+    /// ICCBased or CIE-based colour spaces.
+    #[error("Default colour spaces must be ICCBased or CIE-based colour spaces.")]
+    DeviceColorSpace,
+}
+
+/// The colour space in which colour values shall be interpreted, such as image samples or the
+/// blending colour space of a transparency group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Hash)]
+pub enum ColorSpace {
+    /// Device default RGB representation.
+    DeviceRgb,
+
+    /// Device default single gray channel representation.
+    DeviceGray,
+
+    /// Device default CMYK representation.
+    DeviceCmyk,
+
+    /// Calibrated colour space described by an ICC profile, created with
+    /// [`Document::create_icc_profile`].
+    ///
+    /// [`Document::create_icc_profile`]: crate::Document::create_icc_profile
+    Icc(IccProfileId),
+
+    /// CIE-based colour space, such as CalRGB or Lab, created with
+    /// [`Document::create_cie_color_space`].
+    ///
+    /// [`Document::create_cie_color_space`]: crate::Document::create_cie_color_space
+    Cie(CieColorSpaceId),
+}
+
+/// The ids of the objects defining colour spaces of a document, mapped to the ids of their copies
+/// in another document.
+#[derive(Debug, Default)]
+pub(crate) struct ColorSpaceIds {
+    /// Ids of the ICC profiles of ICCBased colour spaces.
+    pub(crate) icc_profiles: HashMap<ObjId<IccProfile>, ObjId<IccProfile>>,
+
+    /// Ids of the CIE-based colour spaces.
+    pub(crate) cie_color_spaces: HashMap<ObjId<CieColorSpaceObject>, ObjId<CieColorSpaceObject>>,
+}
+
+impl ColorSpace {
+    /// Returns the name this `ColorSpace` is referred to by in the `ColorSpace` entry of a
+    /// resource dictionary, `None` for device colour spaces, which don't need one.
+    pub(crate) fn resource_name(&self) -> Option<OwnedIdentifier> {
+        match self {
+            ColorSpace::Icc(profile) => Some(profile.resource_name()),
+            ColorSpace::Cie(color_space) => Some(color_space.resource_name()),
+            _ => None,
+        }
+    }
+
+    /// Replaces the object defining this `ColorSpace` with the one it is mapped to.
+    pub(crate) fn remap_ids(&mut self, ids: &ColorSpaceIds) {
+        match self {
+            ColorSpace::Icc(profile) => {
+                *profile = profile.with_id(&ids.icc_profiles[&profile.obj_id()]);
+            }
+            ColorSpace::Cie(color_space) => {
+                *color_space = color_space.with_id(&ids.cie_color_spaces[&color_space.obj_id()]);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the number of colour components of this `ColorSpace`.
+    pub(crate) fn components(&self) -> usize {
+        match self {
+            ColorSpace::DeviceRgb => 3,
+            ColorSpace::DeviceGray => 1,
+            ColorSpace::DeviceCmyk => 4,
+            ColorSpace::Icc(profile) => profile.components(),
+            ColorSpace::Cie(color_space) => color_space.components(),
+        }
+    }
+
+    /// Encode this `ColorSpace` into the given implementor of [`Write`].
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match self {
+            ColorSpace::DeviceRgb | ColorSpace::DeviceGray | ColorSpace::DeviceCmyk => {
+                self.write_name(writer)
+            }
+            ColorSpace::Icc(profile) => profile.write_color_space(writer),
+            ColorSpace::Cie(color_space) => Ok(pdfgen_macros::write_chain! {
+                color_space.obj_id().write_ref(writer),
+                writer.write(b" "),
+            }),
+        }
+    }
+
+    /// Encode the operand of the colour space operators of content streams, which is the name of
+    /// a device colour space or the name of the colour space in the resource dictionary.
+    pub(crate) fn write_name(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match self {
+            ColorSpace::DeviceRgb => Identifier::from_static(b"DeviceRGB").write(writer),
+            ColorSpace::DeviceGray => Identifier::from_static(b"DeviceGray").write(writer),
+            ColorSpace::DeviceCmyk => Identifier::from_static(b"DeviceCMYK").write(writer),
+            ColorSpace::Icc(profile) => profile.resource_name().write(writer),
+            ColorSpace::Cie(color_space) => color_space.resource_name().write(writer),
+        }
+    }
+}
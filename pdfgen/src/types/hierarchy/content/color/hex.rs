@@ -0,0 +1,146 @@
+//! Parsing of [`Color`]s from hexadecimal colour notation, e.g. `#1a2b3c`.
+
+use crate::types::hierarchy::content::graphics_state::ExtGState;
+
+use super::Color;
+
+/// Possible errors that might be returned when parsing a [`Color`] from hexadecimal notation.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum HexColorErr {
+    /// Indicates that the hexadecimal colour doesn't have 6 digits, or 8 digits with alpha.
+    #[error("Hexadecimal colors must have 6 or 8 digits, found {0}.")]
+    InvalidLength(usize),
+
+    /// Indicates that the hexadecimal colour contains a character that is not a hexadecimal digit.
+    #[error("'{0}' is not a hexadecimal digit.")]
+    InvalidDigit(char),
+
+    /// Indicates that a hexadecimal colour with alpha was given where only opaque colours are
+    /// accepted.
+    #[error("Hexadecimal colors with alpha must be parsed with `Color::from_hex_alpha`.")]
+    UnexpectedAlpha,
+}
+
+impl Color {
+    /// Parses an RGB [`Color`] from hexadecimal notation with 6 digits, e.g. `#1a2b3c`, as used by
+    /// design systems and CSS. The leading `#` is optional and digits are case insensitive.
+    ///
+    /// Returns [`HexColorErr`] if the notation is invalid, or if it has 8 digits including alpha,
+    /// which should be parsed with [`Color::from_hex_alpha`] instead.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::content::color::Color;
+    /// let color = Color::from_hex("#1a2b3c").unwrap();
+    /// assert_eq!(color, Color::Rgb { red: 0x1a, green: 0x2b, blue: 0x3c });
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<Self, HexColorErr> {
+        match Self::parse_hex(hex)? {
+            (color, None) => Ok(color),
+            (_, Some(_)) => Err(HexColorErr::UnexpectedAlpha),
+        }
+    }
+
+    /// Parses an RGB [`Color`] from hexadecimal notation with 6 digits, or with 8 digits where the
+    /// last two digits are the alpha of the colour, e.g. `#1a2b3c80`. The alpha is returned as an
+    /// [`ExtGState`] with the same stroke and fill alpha, which should be applied to the content
+    /// drawn with the colour. Opaque colours with 6 digits return no [`ExtGState`].
+    ///
+    /// Returns [`HexColorErr`] if the notation is invalid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::content::{color::Color, graphics_state::ExtGState};
+    /// let (color, ext_g_state) = Color::from_hex_alpha("#1a2b3c80").unwrap();
+    /// assert_eq!(color, Color::Rgb { red: 0x1a, green: 0x2b, blue: 0x3c });
+    /// assert!(ext_g_state.is_some_and(|state| state.uses_transparency()));
+    /// ```
+    pub fn from_hex_alpha(hex: &str) -> Result<(Self, Option<ExtGState>), HexColorErr> {
+        let (color, alpha) = Self::parse_hex(hex)?;
+        let ext_g_state = alpha.map(|alpha| {
+            let alpha = f32::from(alpha) / 255.0;
+            ExtGState::new()
+                .with_stroke_alpha(alpha)
+                .with_fill_alpha(alpha)
+        });
+
+        Ok((color, ext_g_state))
+    }
+
+    /// Parses the colour and the optional alpha of the given hexadecimal notation.
+    fn parse_hex(hex: &str) -> Result<(Self, Option<u8>), HexColorErr> {
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        if let Some(invalid) = digits.chars().find(|digit| !digit.is_ascii_hexdigit()) {
+            return Err(HexColorErr::InvalidDigit(invalid));
+        }
+
+        // all digits are ASCII, so every pair of digits is on a char boundary
+        let byte = |idx: usize| {
+            u8::from_str_radix(&digits[2 * idx..2 * idx + 2], 16)
+                .expect("Hexadecimal digits were checked before.")
+        };
+
+        let alpha = match digits.len() {
+            6 => None,
+            8 => Some(byte(3)),
+            len => return Err(HexColorErr::InvalidLength(len)),
+        };
+
+        let color = Color::Rgb {
+            red: byte(0),
+            green: byte(1),
+            blue: byte(2),
+        };
+
+        Ok((color, alpha))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::hierarchy::content::{color::Color, graphics_state::ExtGState};
+
+    use super::HexColorErr;
+
+    #[test]
+    fn from_hex() {
+        let rgb = Color::Rgb {
+            red: 0x1a,
+            green: 0x2b,
+            blue: 0x3c,
+        };
+
+        assert_eq!(Color::from_hex("#1a2b3c"), Ok(rgb));
+        assert_eq!(Color::from_hex("1A2B3C"), Ok(rgb));
+        assert_eq!(Color::from_hex_alpha("#1a2b3c"), Ok((rgb, None)));
+        assert_eq!(
+            Color::from_hex_alpha("#1a2b3c33"),
+            Ok((
+                rgb,
+                Some(ExtGState::new().with_stroke_alpha(0.2).with_fill_alpha(0.2))
+            ))
+        );
+    }
+
+    #[test]
+    fn invalid_hex() {
+        assert_eq!(
+            Color::from_hex("#1a2b3"),
+            Err(HexColorErr::InvalidLength(5))
+        );
+        assert_eq!(
+            Color::from_hex("#1a2b3g"),
+            Err(HexColorErr::InvalidDigit('g'))
+        );
+        assert_eq!(
+            Color::from_hex("#1a2b3c80"),
+            Err(HexColorErr::UnexpectedAlpha)
+        );
+        assert_eq!(
+            Color::from_hex("#ü2b3c"),
+            Err(HexColorErr::InvalidDigit('ü'))
+        );
+    }
+}
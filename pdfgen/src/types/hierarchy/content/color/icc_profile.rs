@@ -0,0 +1,107 @@
+//! Implementation of ICC profile streams, used by ICCBased colour spaces.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    ObjId,
+    types::{
+        constants,
+        hierarchy::{
+            content::stream::Stream,
+            primitives::{identifier::Identifier, object::Object},
+        },
+    },
+};
+
+use super::ColorSpace;
+
+/// A stream holding an ICC profile, which describes how the colour components of an ICCBased
+/// colour space are mapped to a device-independent colour space. Profiles are created with
+/// [`Document::create_icc_profile`].
+///
+/// [`Document::create_icc_profile`]: crate::Document::create_icc_profile
+#[derive(Debug, Clone)]
+pub struct IccProfile {
+    /// ID of this `IccProfile` stream.
+    pub(crate) id: ObjId<Self>,
+
+    /// The data of the ICC profile.
+    data: Stream,
+
+    /// Device colour space with the same number of components as the profile, used by readers
+    /// that don't support ICC profiles.
+    alternate: ColorSpace,
+}
+
+impl IccProfile {
+    const_identifiers! {
+        N: b"N",
+        ALTERNATE,
+    }
+
+    /// Creates a new `IccProfile` stream with the given [`ObjId`], profile data and alternate
+    /// device [`ColorSpace`].
+    pub(crate) fn new(id: ObjId<Self>, data: Vec<u8>, alternate: ColorSpace) -> Self {
+        Self {
+            id,
+            data: Stream::with_bytes(data),
+            alternate,
+        }
+    }
+}
+
+impl Object for IccProfile {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.data.write_with_dict(writer, |writer| {
+                Ok(pdfgen_macros::write_chain! {
+                    Self::N.write(writer),
+                    crate::write_fmt!(&mut *writer, "{} ", self.alternate.components()),
+                    Self::ALTERNATE.write(writer),
+                    self.alternate.write(writer),
+                })
+            }),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::{content::color::ColorSpace, primitives::object::Object},
+    };
+
+    use super::IccProfile;
+
+    #[test]
+    fn icc_profile() {
+        let mut id_manager = IdManager::new();
+        let profile = IccProfile::new(
+            id_manager.create_id(),
+            b"profile".to_vec(),
+            ColorSpace::DeviceRgb,
+        );
+
+        let mut writer = Vec::new();
+        profile.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /N 3 /Alternate /DeviceRGB /Length 7 >>
+        stream
+        profile
+        endstream
+        ");
+    }
+}
@@ -1,16 +1,30 @@
 use std::io::Write;
 
-use crate::types::{constants, hierarchy::primitives::identifier::Identifier};
+use crate::{CieColorSpaceId, IccProfileId, types::constants};
 
 mod cmyk_value;
 pub use cmyk_value::CmykValue;
 
+mod hex;
+pub use hex::HexColorErr;
+
+mod color_space;
+pub(crate) use color_space::ColorSpaceIds;
+pub use color_space::{ColorSpace, DefaultColorSpaceErr};
+
+mod icc_profile;
+pub use icc_profile::IccProfile;
+
+mod cie_color_space;
+pub use cie_color_space::{CalGray, CalRgb, CieColorSpace, D50, D65, Lab};
+pub(crate) use cie_color_space::{CieColorSpaceObject, CieFamily};
+
 /// A PDF file may specify abstract colours in a device-independent way. Colours may be described
 /// in any of a variety of colour systems, or colour spaces. Some colour spaces are related to
 /// device colour representation (grayscale, RGB, CMYK), others to human visual perception
 /// (CIE-based). Certain special features are also modelled as colour spaces: patterns, colour
 /// mapping, separations, and high-fidelity and multitone colour.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
 pub enum Color {
     /// Colours in the DeviceRGB colour space shall be specified according to the additive RGB
     /// (red-green- blue) colour model, in which colour values shall be defined by three components
@@ -53,43 +67,122 @@ pub enum Color {
         /// Black component of the color space in range [0, 255].
         black: CmykValue,
     },
+
+    /// Colours in an ICCBased colour space are specified by one value for each colour component
+    /// of the ICC profile, which describes how the components are interpreted. Each component
+    /// shall be specified by a number in the range 0 to 255. Colours in ICCBased colour spaces can
+    /// be created with [`Color::icc`].
+    ///
+    /// Note that these values will be mapped to range [0.0, 1.0] when encoding in PDF file.
+    Icc {
+        /// Handle of the ICC profile of the colour space.
+        profile: IccProfileId,
+        /// Values of the colour components in range [0, 255]. Only the first values up to the
+        /// number of colour components of the profile are used.
+        values: [u8; 4],
+    },
+
+    /// Colours in a CalGray colour space are specified by a single gray component in the range 0
+    /// to 255, like [`Color::Gray`], and are interpreted relative to the white point and gamma of
+    /// the colour space. They can be created with [`Color::cal_gray`].
+    ///
+    /// Note that these values will be mapped to range [0.0, 1.0] when encoding in PDF file.
+    CalGray {
+        /// Handle of the CalGray colour space.
+        color_space: CieColorSpaceId,
+        /// Gray component of the color in range [0, 255].
+        gray: u8,
+    },
+
+    /// Colours in a CalRGB colour space are specified by red, green and blue components in the
+    /// range 0 to 255, like [`Color::Rgb`], and are interpreted relative to the white point, gamma
+    /// and matrix of the colour space. They can be created with [`Color::cal_rgb`].
+    ///
+    /// Note that these values will be mapped to range [0.0, 1.0] when encoding in PDF file.
+    CalRgb {
+        /// Handle of the CalRGB colour space.
+        color_space: CieColorSpaceId,
+        /// Red component of the color in range [0, 255].
+        red: u8,
+        /// Green component of the color in range [0, 255].
+        green: u8,
+        /// Blue component of the color in range [0, 255].
+        blue: u8,
+    },
+
+    /// Colours in a Lab colour space are specified by the lightness L* in the range 0 to 100 and
+    /// the chromaticity components a* and b*, which are clipped to the range of the colour space.
+    /// They can be created with [`Color::lab`].
+    Lab {
+        /// Handle of the Lab colour space.
+        color_space: CieColorSpaceId,
+        /// Lightness L* of the color in range [0, 100].
+        lightness: u8,
+        /// Chromaticity component a* of the color, from green to red.
+        a: i8,
+        /// Chromaticity component b* of the color, from blue to yellow.
+        b: i8,
+    },
 }
 
 struct ValuesIter {
-    /// Holds the values of color definition. We have at most 4 values for CMYK color space, and at
-    /// least 1 value for Gray color space. Unavailable values are marked with [`None`].
-    values: [Option<u8>; 4],
+    /// Holds the values of color definition, as they are written into the PDF file. We have at
+    /// most 4 values for CMYK color space, and at least 1 value for Gray color space. Unavailable
+    /// values are marked with [`None`].
+    values: [Option<f32>; 4],
     idx: usize,
-    max_value: u8,
+}
+
+impl ValuesIter {
+    /// Creates a new `ValuesIter` with the given values mapped from range [0, max_value] to range
+    /// [0.0, 1.0].
+    fn normalized(values: [Option<u8>; 4], max_value: u8) -> Self {
+        Self {
+            values: values.map(|value| Some(f32::from(value?) / f32::from(max_value))),
+            idx: 0,
+        }
+    }
 }
 
 impl From<Color> for ValuesIter {
     fn from(color: Color) -> Self {
         match color {
-            Color::Rgb { red, green, blue } => Self {
-                values: [Some(red), Some(green), Some(blue), None],
-                idx: 0,
-                max_value: 255,
-            },
-            Color::Gray(gray) => Self {
-                values: [Some(gray), None, None, None],
-                idx: 0,
-                max_value: 255,
-            },
+            Color::Rgb { red, green, blue }
+            | Color::CalRgb {
+                red, green, blue, ..
+            } => Self::normalized([Some(red), Some(green), Some(blue), None], 255),
+            Color::Gray(gray) | Color::CalGray { gray, .. } => {
+                Self::normalized([Some(gray), None, None, None], 255)
+            }
             Color::CMYK {
                 cyan,
                 magenta,
                 yellow,
                 black,
-            } => Self {
-                values: [
+            } => Self::normalized(
+                [
                     Some(cyan.into()),
                     Some(magenta.into()),
                     Some(yellow.into()),
                     Some(black.into()),
                 ],
+                100,
+            ),
+            Color::Icc { profile, values } => Self::normalized(
+                std::array::from_fn(|idx| (idx < profile.components()).then_some(values[idx])),
+                255,
+            ),
+            // NOTE: Lab values are written as they are, in the range of their components.
+            Color::Lab {
+                lightness, a, b, ..
+            } => Self {
+                values: [
+                    Some(f32::from(lightness)),
+                    Some(f32::from(a)),
+                    Some(f32::from(b)),
+                    None,
+                ],
                 idx: 0,
-                max_value: 100,
             },
         }
     }
@@ -102,20 +195,116 @@ impl Iterator for ValuesIter {
         let value = self.values.get(self.idx)?;
         self.idx += 1;
 
-        match value {
-            Some(value) => {
-                let value = f32::from(*value) / f32::from(self.max_value);
-                Some(value)
-            }
-            None => None,
-        }
+        *value
     }
 }
 
 impl Color {
+    /// Creates a [`Color`] in the ICCBased colour space of the given profile, with the given values
+    /// of its colour components in range [0, 255].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of values doesn't match the number of colour components of the
+    /// profile.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::content::color::{Color, ColorSpace}};
+    /// let mut document = Document::default();
+    /// # let srgb_profile_data = Vec::new();
+    /// let srgb = document.create_icc_profile(srgb_profile_data, ColorSpace::DeviceRgb);
+    /// let orange = Color::icc(srgb, &[255, 128, 0]);
+    /// ```
+    pub fn icc(profile: IccProfileId, values: &[u8]) -> Self {
+        assert_eq!(
+            values.len(),
+            profile.components(),
+            "ICCBased colors must have one value for each colour component of the profile."
+        );
+
+        let mut icc_values = [0; 4];
+        icc_values[..values.len()].copy_from_slice(values);
+
+        Self::Icc {
+            profile,
+            values: icc_values,
+        }
+    }
+
+    /// Creates a [`Color`] in the given CalGray colour space, with the given gray component in
+    /// range [0, 255].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the colour space isn't a CalGray colour space.
+    pub fn cal_gray(color_space: CieColorSpaceId, gray: u8) -> Self {
+        assert_eq!(
+            color_space.family(),
+            CieFamily::CalGray,
+            "The colour space of CalGray colors must be a CalGray colour space."
+        );
+
+        Self::CalGray { color_space, gray }
+    }
+
+    /// Creates a [`Color`] in the given CalRGB colour space, with the given red, green and blue
+    /// components in range [0, 255].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the colour space isn't a CalRGB colour space.
+    pub fn cal_rgb(color_space: CieColorSpaceId, red: u8, green: u8, blue: u8) -> Self {
+        assert_eq!(
+            color_space.family(),
+            CieFamily::CalRgb,
+            "The colour space of CalRGB colors must be a CalRGB colour space."
+        );
+
+        Self::CalRgb {
+            color_space,
+            red,
+            green,
+            blue,
+        }
+    }
+
+    /// Creates a [`Color`] in the given Lab colour space, with the given lightness L* in range
+    /// [0, 100] and chromaticity components a* and b*.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the colour space isn't a Lab colour space, or the lightness is greater than 100.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::content::color::{Color, D50, Lab}};
+    /// let mut document = Document::default();
+    /// let lab = document.create_cie_color_space(Lab::new(D50));
+    /// let teal = Color::lab(lab, 55, -37, -10);
+    /// ```
+    pub fn lab(color_space: CieColorSpaceId, lightness: u8, a: i8, b: i8) -> Self {
+        assert_eq!(
+            color_space.family(),
+            CieFamily::Lab,
+            "The colour space of Lab colors must be a Lab colour space."
+        );
+        assert!(
+            lightness <= 100,
+            "The lightness of Lab colors must be in range [0, 100]."
+        );
+
+        Self::Lab {
+            color_space,
+            lightness,
+            a,
+            b,
+        }
+    }
+
     /// Writes the color operators for stroke coloring.
-    // TODO(nfejzic): remove the `allow` attribute once we start using this method.
-    #[allow(dead_code)]
     pub(crate) fn write_stroke(&self, writer: &mut impl Write) -> std::io::Result<usize> {
         self.inner_write(writer, "CS", "SC", ValuesIter::from(*self))
     }
@@ -125,15 +314,141 @@ impl Color {
         self.inner_write(writer, "cs", "sc", ValuesIter::from(*self))
     }
 
-    /// Returns the [`Identifier`] corresponding to the color space.
-    fn identifier(&self) -> Identifier<&'static [u8]> {
+    /// Writes the components of the device color approximating this color as an array, e.g.
+    /// `[1 0 0]` for red, as used for the colors of annotations.
+    pub(crate) fn write_components(&self, writer: &mut dyn Write) -> std::io::Result<usize> {
+        let components = ValuesIter::from(self.to_device())
+            .map(|value| value.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        crate::write_fmt!(&mut *writer, "[{components}]")
+    }
+
+    /// Returns the [`ColorSpace`] of this color.
+    pub(crate) fn color_space(&self) -> ColorSpace {
         match self {
-            Color::Rgb { .. } => Identifier::from_static(b"DeviceRGB"),
-            Color::Gray(_) => Identifier::from_static(b"DeviceGray"),
-            Color::CMYK { .. } => Identifier::from_static(b"DeviceCMYK"),
+            Color::Rgb { .. } => ColorSpace::DeviceRgb,
+            Color::Gray(_) => ColorSpace::DeviceGray,
+            Color::CMYK { .. } => ColorSpace::DeviceCmyk,
+            Color::Icc { profile, .. } => ColorSpace::Icc(*profile),
+            Color::CalGray { color_space, .. }
+            | Color::CalRgb { color_space, .. }
+            | Color::Lab { color_space, .. } => ColorSpace::Cie(*color_space),
         }
     }
 
+    /// Returns the device color approximating this color, which is how colors in ICCBased and
+    /// CIE-based colour spaces are converted to other colour spaces. ICCBased, CalGray and CalRGB
+    /// colors keep their component values, and Lab colors are converted to sRGB with the D65
+    /// white point.
+    pub(crate) fn to_device(self) -> Self {
+        let (profile, values) = match self {
+            Color::Rgb { .. } | Color::Gray(_) | Color::CMYK { .. } => return self,
+            Color::CalGray { gray, .. } => return Color::Gray(gray),
+            Color::CalRgb {
+                red, green, blue, ..
+            } => return Color::Rgb { red, green, blue },
+            Color::Lab {
+                lightness, a, b, ..
+            } => return Self::lab_to_rgb(lightness, a, b),
+            Color::Icc { profile, values } => (profile, values),
+        };
+
+        let cmyk_value = |value: u8| {
+            CmykValue::try_from(f32::from(value) / 255.).expect("value is in correct range")
+        };
+
+        match profile.components() {
+            1 => Color::Gray(values[0]),
+            4 => Color::CMYK {
+                cyan: cmyk_value(values[0]),
+                magenta: cmyk_value(values[1]),
+                yellow: cmyk_value(values[2]),
+                black: cmyk_value(values[3]),
+            },
+            _ => Color::Rgb {
+                red: values[0],
+                green: values[1],
+                blue: values[2],
+            },
+        }
+    }
+
+    /// Converts the given CIE L*a*b* values to an sRGB color, using the D65 white point.
+    fn lab_to_rgb(lightness: u8, a: i8, b: i8) -> Self {
+        let [white_x, white_y, white_z] = D65;
+
+        // the inverse of the non-linear companding of CIE L*a*b*
+        let f_inv = |t: f32| {
+            const DELTA: f32 = 6. / 29.;
+            if t > DELTA {
+                t.powi(3)
+            } else {
+                3. * DELTA * DELTA * (t - 4. / 29.)
+            }
+        };
+
+        let f_y = (f32::from(lightness) + 16.) / 116.;
+        let x = white_x * f_inv(f_y + f32::from(a) / 500.);
+        let y = white_y * f_inv(f_y);
+        let z = white_z * f_inv(f_y - f32::from(b) / 200.);
+
+        // convert the linear RGB value into the sRGB component
+        let compand = |value: f32| {
+            let value = if value <= 0.0031308 {
+                12.92 * value
+            } else {
+                1.055 * value.powf(1. / 2.4) - 0.055
+            };
+
+            (value.clamp(0., 1.) * 255.).round() as u8
+        };
+
+        Self::Rgb {
+            red: compand(3.2406 * x - 1.5372 * y - 0.4986 * z),
+            green: compand(-0.9689 * x + 1.8758 * y + 0.0415 * z),
+            blue: compand(0.0557 * x - 0.2040 * y + 1.0570 * z),
+        }
+    }
+
+    /// Converts the given sRGB components to CIE L*a*b* values, using the D65 white point. This is
+    /// the inverse of [`Color::lab_to_rgb`], with the values clipped to the range of [`Color::Lab`].
+    fn rgb_to_lab(red: u8, green: u8, blue: u8) -> (u8, i8, i8) {
+        let [white_x, white_y, white_z] = D65;
+
+        // convert the sRGB component into linear RGB value
+        let linearize = |value: u8| {
+            let value = f32::from(value) / 255.;
+            if value <= 0.04045 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        let (red, green, blue) = (linearize(red), linearize(green), linearize(blue));
+
+        // the non-linear companding of CIE L*a*b*
+        let f = |t: f32| {
+            const DELTA: f32 = 6. / 29.;
+            if t > DELTA.powi(3) {
+                t.cbrt()
+            } else {
+                t / (3. * DELTA * DELTA) + 4. / 29.
+            }
+        };
+
+        let f_x = f((0.4124 * red + 0.3576 * green + 0.1805 * blue) / white_x);
+        let f_y = f((0.2126 * red + 0.7152 * green + 0.0722 * blue) / white_y);
+        let f_z = f((0.0193 * red + 0.1192 * green + 0.9505 * blue) / white_z);
+
+        let lightness = (116. * f_y - 16.).clamp(0., 100.).round() as u8;
+        let a = (500. * (f_x - f_y)).clamp(-128., 127.).round() as i8;
+        let b = (200. * (f_y - f_z)).clamp(-128., 127.).round() as i8;
+
+        (lightness, a, b)
+    }
+
     /// Encodes and writes color space and set color operations into PDF writer.
     fn inner_write(
         &self,
@@ -143,7 +458,7 @@ impl Color {
         values: ValuesIter,
     ) -> std::io::Result<usize> {
         Ok(pdfgen_macros::write_chain! {
-            self.identifier().write(writer),
+            self.color_space().write_name(writer),
             writer.write(cs_operator.as_bytes()),
             writer.write(constants::NL_MARKER),
 
@@ -156,15 +471,48 @@ impl Color {
         })
     }
 
+    /// Returns the relative luminance of this color as defined by WCAG, in range `[0.0, 1.0]`,
+    /// where `0.0` is the darkest black and `1.0` is the lightest white.
+    pub fn relative_luminance(self) -> f32 {
+        let Color::Rgb { red, green, blue } = self.to_rgb() else {
+            unreachable!("to_rgb always returns Color::Rgb");
+        };
+
+        // convert the sRGB component into linear RGB value
+        let linearize = |value: u8| {
+            let value = f32::from(value) / 255.;
+            if value <= 0.03928 {
+                value / 12.92
+            } else {
+                ((value + 0.055) / 1.055).powf(2.4)
+            }
+        };
+
+        0.2126 * linearize(red) + 0.7152 * linearize(green) + 0.0722 * linearize(blue)
+    }
+
+    /// Returns the contrast ratio between this color and the other color as defined by WCAG, in
+    /// range `[1.0, 21.0]`.
+    pub fn contrast_ratio(self, other: Color) -> f32 {
+        let this = self.relative_luminance();
+        let other = other.relative_luminance();
+
+        (this.max(other) + 0.05) / (this.min(other) + 0.05)
+    }
+
     /// Converts the current color to gray color space.
     pub fn to_gray(self) -> Self {
         match self {
             Color::Rgb { red, green, blue } => {
                 // 0.299 R + 0.587 G + 0.114 B
                 let gray = 0.299 * red as f32 + 0.587 * green as f32 + 0.114 * blue as f32;
-                Self::Gray(gray as u8)
+                Self::Gray(gray.round() as u8)
             }
             Color::Gray(_) | Color::CMYK { .. } => self.to_rgb().to_gray(),
+            Color::Icc { .. }
+            | Color::CalGray { .. }
+            | Color::CalRgb { .. }
+            | Color::Lab { .. } => self.to_device().to_gray(),
         }
     }
 
@@ -184,16 +532,18 @@ impl Color {
                 let yellow = iter.next().expect("yellow is present in CMYK");
                 let black = iter.next().expect("black is present in CMYK");
 
-                let red = (1. - cyan) * (1. - black) * 255.;
-                let green = 255. * (1. - magenta) * (1. - black);
-                let blue = 255. * (1. - yellow) * (1. - black);
+                let component = |value: f32| ((1. - value) * (1. - black) * 255.).round() as u8;
 
                 Self::Rgb {
-                    red: red as u8,
-                    green: green as u8,
-                    blue: blue as u8,
+                    red: component(cyan),
+                    green: component(magenta),
+                    blue: component(yellow),
                 }
             }
+            Color::Icc { .. }
+            | Color::CalGray { .. }
+            | Color::CalRgb { .. }
+            | Color::Lab { .. } => self.to_device().to_rgb(),
         }
     }
 
@@ -208,28 +558,117 @@ impl Color {
                 let blue = iter.next().expect("blue is present in RGB");
 
                 let black = 1. - red.max(green.max(blue));
-                let cyan = (1. - red - black) / (1. - black);
-                let magenta = (1. - green - black) / (1. - black);
-                let yellow = (1. - blue - black) / (1. - black);
+                // pure black has no chromatic components, avoid dividing by zero
+                let chromatic = |value: f32| {
+                    if black < 1. {
+                        (1. - value - black) / (1. - black)
+                    } else {
+                        0.
+                    }
+                };
+                let cmyk_value = |value: f32| {
+                    CmykValue::try_from(value.clamp(0., 1.)).expect("value is in correct range")
+                };
 
                 Self::CMYK {
-                    cyan: CmykValue::try_from(cyan).expect("we checked correct range"),
-                    magenta: CmykValue::try_from(magenta).expect("we checked correct range"),
-                    yellow: CmykValue::try_from(yellow).expect("we checked correct range"),
-                    black: CmykValue::try_from(black).expect("we checked correct range"),
+                    cyan: cmyk_value(chromatic(red)),
+                    magenta: cmyk_value(chromatic(green)),
+                    yellow: cmyk_value(chromatic(blue)),
+                    black: cmyk_value(black),
                 }
             }
             Color::Gray(_) => self.to_rgb().to_cmyk(),
             Color::CMYK { .. } => self,
+            Color::Icc { .. }
+            | Color::CalGray { .. }
+            | Color::CalRgb { .. }
+            | Color::Lab { .. } => self.to_device().to_cmyk(),
+        }
+    }
+
+    /// Converts the current color to the given [`ColorSpace`].
+    ///
+    /// Conversions into device colour spaces are naive, using the same formulas as
+    /// [`Color::to_gray`], [`Color::to_rgb`] and [`Color::to_cmyk`]. When an ICC profile or a CIE-based
+    /// colour space was registered in the document, converting into it tags the naively converted
+    /// values with the colour space, so color-managed readers interpret them with its profile or
+    /// calibration. Colors are converted into Lab colour spaces from sRGB, with the D65 white
+    /// point.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::content::color::{Color, ColorSpace}};
+    /// let mut document = Document::default();
+    /// let profile = document.create_icc_profile(b"CMYK profile".to_vec(), ColorSpace::DeviceCmyk);
+    ///
+    /// let red = Color::Rgb { red: 255, green: 0, blue: 0 };
+    /// assert_eq!(red.to_color_space(ColorSpace::DeviceCmyk), red.to_cmyk());
+    /// assert_eq!(
+    ///     red.to_color_space(ColorSpace::Icc(profile)),
+    ///     Color::icc(profile, &[0, 255, 255, 0]),
+    /// );
+    /// ```
+    pub fn to_color_space(self, color_space: ColorSpace) -> Self {
+        let device = match color_space {
+            ColorSpace::DeviceGray => return self.to_gray(),
+            ColorSpace::DeviceRgb => return self.to_rgb(),
+            ColorSpace::DeviceCmyk => return self.to_cmyk(),
+            ColorSpace::Icc(profile) => match profile.components() {
+                1 => self.to_gray(),
+                4 => self.to_cmyk(),
+                _ => self.to_rgb(),
+            },
+            ColorSpace::Cie(color_space) => match color_space.family() {
+                CieFamily::CalGray => self.to_gray(),
+                CieFamily::CalRgb | CieFamily::Lab => self.to_rgb(),
+            },
+        };
+
+        match (color_space, device) {
+            (ColorSpace::Icc(profile), Color::Gray(gray)) => Color::icc(profile, &[gray]),
+            (ColorSpace::Icc(profile), Color::Rgb { red, green, blue }) => {
+                Color::icc(profile, &[red, green, blue])
+            }
+            (
+                ColorSpace::Icc(profile),
+                Color::CMYK {
+                    cyan,
+                    magenta,
+                    yellow,
+                    black,
+                },
+            ) => {
+                // map the percentages of CMYK values to the range [0, 255]
+                let value = |value: CmykValue| (f32::from(u8::from(value)) * 2.55).round() as u8;
+                Color::icc(
+                    profile,
+                    &[value(cyan), value(magenta), value(yellow), value(black)],
+                )
+            }
+            (ColorSpace::Cie(color_space), Color::Gray(gray)) => Color::cal_gray(color_space, gray),
+            (ColorSpace::Cie(color_space), Color::Rgb { red, green, blue }) => {
+                match color_space.family() {
+                    CieFamily::Lab => {
+                        let (lightness, a, b) = Self::rgb_to_lab(red, green, blue);
+                        Color::lab(color_space, lightness, a, b)
+                    }
+                    _ => Color::cal_rgb(color_space, red, green, blue),
+                }
+            }
+            _ => unreachable!("Colors are converted to the device colour space of the target."),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::types::hierarchy::content::color::CmykValue;
+    use crate::{
+        CieColorSpaceId, IccProfileId, ObjId,
+        types::hierarchy::content::color::{CieFamily, CmykValue},
+    };
 
-    use super::Color;
+    use super::{Color, ColorSpace};
 
     macro_rules! color_tests {
         ($($test_fn:ident, $color:expr, @$expected:literal ),*) => {
@@ -282,6 +721,219 @@ mod tests {
     0.5 0.1 1 0.42 SC
     /DeviceCMYK cs
     0.5 0.1 1 0.42 sc
+    ",
+
+        icc_based,
+        Color::icc(IccProfileId::new(&ObjId::from_number(5), 3), &[255, 0, 51]),
+        @r"
+    /ICC5 CS
+    1 0 0.2 SC
+    /ICC5 cs
+    1 0 0.2 sc
+    ",
+
+        cal_rgb,
+        Color::cal_rgb(CieColorSpaceId::new(&ObjId::from_number(7), CieFamily::CalRgb), 255, 0, 51),
+        @r"
+    /CS7 CS
+    1 0 0.2 SC
+    /CS7 cs
+    1 0 0.2 sc
+    ",
+
+        lab,
+        Color::lab(CieColorSpaceId::new(&ObjId::from_number(7), CieFamily::Lab), 55, -37, 10),
+        @r"
+    /CS7 CS
+    55 -37 10 SC
+    /CS7 cs
+    55 -37 10 sc
     "
     }
+
+    #[test]
+    #[should_panic = "ICCBased colors must have one value for each colour component of the profile."]
+    fn icc_values_must_match_components() {
+        let _ = Color::icc(IccProfileId::new(&ObjId::from_number(5), 4), &[255, 0, 51]);
+    }
+
+    #[test]
+    #[should_panic = "The colour space of Lab colors must be a Lab colour space."]
+    fn lab_color_space_family() {
+        let cal_rgb = CieColorSpaceId::new(&ObjId::from_number(7), CieFamily::CalRgb);
+        let _ = Color::lab(cal_rgb, 50, 0, 0);
+    }
+
+    #[test]
+    fn lab_to_rgb() {
+        let lab = CieColorSpaceId::new(&ObjId::from_number(7), CieFamily::Lab);
+
+        let white = Color::Rgb {
+            red: 255,
+            green: 255,
+            blue: 255,
+        };
+        assert_eq!(Color::lab(lab, 100, 0, 0).to_rgb(), white);
+        assert_eq!(Color::lab(lab, 0, 0, 0).to_rgb(), Color::Gray(0).to_rgb());
+
+        let Color::Rgb { red, green, blue } = Color::lab(lab, 50, 80, 60).to_rgb() else {
+            unreachable!("to_rgb always returns Color::Rgb");
+        };
+        assert!(red > 200 && green < 50 && blue < 50);
+    }
+
+    #[test]
+    fn icc_to_device() {
+        let gray = Color::icc(IccProfileId::new(&ObjId::from_number(5), 1), &[128]);
+        assert_eq!(gray.to_rgb(), Color::Gray(128).to_rgb());
+
+        let cmyk = Color::icc(
+            IccProfileId::new(&ObjId::from_number(5), 4),
+            &[0, 0, 0, 255],
+        );
+        assert_eq!(cmyk.to_rgb(), Color::Gray(0).to_rgb());
+    }
+
+    /// Asserts that the RGB components of both colors differ at most by the given tolerance.
+    fn assert_rgb_near(left: Color, right: Color, tolerance: u8) {
+        let (
+            Color::Rgb {
+                red: l_red,
+                green: l_green,
+                blue: l_blue,
+            },
+            Color::Rgb {
+                red: r_red,
+                green: r_green,
+                blue: r_blue,
+            },
+        ) = (left.to_rgb(), right.to_rgb())
+        else {
+            unreachable!("to_rgb always returns Color::Rgb");
+        };
+
+        assert!(
+            l_red.abs_diff(r_red) <= tolerance
+                && l_green.abs_diff(r_green) <= tolerance
+                && l_blue.abs_diff(r_blue) <= tolerance,
+            "{left:?} is not near {right:?}"
+        );
+    }
+
+    /// Returns RGB colors sampled evenly from the whole RGB cube.
+    fn rgb_samples() -> impl Iterator<Item = Color> {
+        let steps = || (0..=255).step_by(17);
+        steps().flat_map(move |red| {
+            steps().flat_map(move |green| steps().map(move |blue| Color::Rgb { red, green, blue }))
+        })
+    }
+
+    #[test]
+    fn cmyk_to_rgb() {
+        let cmyk = |cyan, magenta, yellow, black| Color::CMYK {
+            cyan: CmykValue::try_from(cyan).unwrap(),
+            magenta: CmykValue::try_from(magenta).unwrap(),
+            yellow: CmykValue::try_from(yellow).unwrap(),
+            black: CmykValue::try_from(black).unwrap(),
+        };
+        let rgb = |red, green, blue| Color::Rgb { red, green, blue };
+
+        assert_eq!(cmyk(0, 0, 0, 0).to_rgb(), rgb(255, 255, 255));
+        assert_eq!(cmyk(0, 0, 0, 100).to_rgb(), rgb(0, 0, 0));
+        assert_eq!(cmyk(100, 0, 0, 0).to_rgb(), rgb(0, 255, 255));
+        assert_eq!(cmyk(0, 100, 100, 0).to_rgb(), rgb(255, 0, 0));
+        assert_eq!(cmyk(50, 0, 0, 50).to_rgb(), rgb(64, 128, 128));
+
+        assert_eq!(Color::Gray(0).to_cmyk(), cmyk(0, 0, 0, 100));
+        assert_eq!(rgb(255, 0, 0).to_cmyk(), cmyk(0, 100, 100, 0));
+    }
+
+    #[test]
+    fn conversion_roundtrips() {
+        for gray in 0..=255 {
+            assert_eq!(Color::Gray(gray).to_rgb().to_gray(), Color::Gray(gray));
+            // CMYK values are percentages, so some precision is lost
+            assert_rgb_near(Color::Gray(gray).to_cmyk(), Color::Gray(gray), 2);
+        }
+
+        for rgb in rgb_samples() {
+            // CMYK values are percentages, so some precision is lost
+            assert_rgb_near(rgb.to_cmyk(), rgb, 2);
+            assert_rgb_near(rgb.to_cmyk().to_rgb().to_cmyk(), rgb.to_cmyk(), 2);
+        }
+    }
+
+    #[test]
+    fn lab_roundtrip() {
+        let lab = ColorSpace::Cie(CieColorSpaceId::new(&ObjId::from_number(7), CieFamily::Lab));
+        let components = |color: Color| match color {
+            Color::Lab {
+                lightness, a, b, ..
+            } => [i16::from(lightness), i16::from(a), i16::from(b)],
+            _ => unreachable!("Colors are converted to the Lab colour space."),
+        };
+
+        // Lab values are integers, and dark colors differ a lot in sRGB with small differences in
+        // Lab, so the roundtrip is compared in Lab
+        for rgb in rgb_samples() {
+            let converted = rgb.to_color_space(lab);
+            let roundtrip = converted.to_rgb().to_color_space(lab);
+
+            let differences = std::iter::zip(components(converted), components(roundtrip));
+            assert!(
+                differences
+                    .into_iter()
+                    .all(|(left, right)| left.abs_diff(right) <= 1),
+                "{converted:?} is not near {roundtrip:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn to_color_space() {
+        let red = Color::Rgb {
+            red: 255,
+            green: 0,
+            blue: 0,
+        };
+
+        assert_eq!(red.to_color_space(ColorSpace::DeviceGray), red.to_gray());
+        assert_eq!(red.to_color_space(ColorSpace::DeviceCmyk), red.to_cmyk());
+
+        let gray_profile = IccProfileId::new(&ObjId::from_number(5), 1);
+        let cmyk_profile = IccProfileId::new(&ObjId::from_number(6), 4);
+        assert_eq!(
+            red.to_color_space(ColorSpace::Icc(gray_profile)),
+            Color::icc(gray_profile, &[76])
+        );
+        assert_eq!(
+            red.to_color_space(ColorSpace::Icc(cmyk_profile)),
+            Color::icc(cmyk_profile, &[0, 255, 255, 0])
+        );
+
+        let cal_rgb = CieColorSpaceId::new(&ObjId::from_number(7), CieFamily::CalRgb);
+        let lab = CieColorSpaceId::new(&ObjId::from_number(8), CieFamily::Lab);
+        assert_eq!(
+            red.to_color_space(ColorSpace::Cie(cal_rgb)),
+            Color::cal_rgb(cal_rgb, 255, 0, 0)
+        );
+        assert_eq!(
+            red.to_color_space(ColorSpace::Cie(lab)),
+            Color::lab(lab, 53, 80, 67)
+        );
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        let black = Color::Gray(0);
+        let white = Color::Rgb {
+            red: 255,
+            green: 255,
+            blue: 255,
+        };
+
+        assert!((black.contrast_ratio(white) - 21.0).abs() < 0.001);
+        assert!((white.contrast_ratio(black) - 21.0).abs() < 0.001);
+        assert!((white.contrast_ratio(white) - 1.0).abs() < 0.001);
+    }
 }
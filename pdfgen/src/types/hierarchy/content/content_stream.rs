@@ -2,11 +2,15 @@ use crate::{
     ObjId,
     types::{
         constants,
-        hierarchy::primitives::{identifier::Identifier, object::Object, rectangle::Position},
+        hierarchy::primitives::{
+            identifier::Identifier,
+            object::Object,
+            rectangle::{Position, Rectangle},
+        },
     },
 };
 
-use super::{image::ImageTransform, stream::Stream, text::Text};
+use super::{color::Color, image::ImageTransform, path::Path, stream::Stream, text::Text};
 
 /// Represents a specific operation in [`ContentStream`] such as drawing an image or text.
 pub(crate) enum Operation<'a> {
@@ -23,12 +27,31 @@ pub(crate) enum Operation<'a> {
         ///
         /// [`Image`]: super::image::Image
         transform: ImageTransform,
+
+        /// Color a stencil mask [`Image`] is painted with, `None` for other images.
+        ///
+        /// [`Image`]: super::image::Image
+        stencil_color: Option<Color>,
+    },
+
+    /// Draws a form XObject, such as a [`PageForm`], transformed by the given matrix.
+    ///
+    /// [`PageForm`]: super::page_form::PageForm
+    DrawForm {
+        /// Name of the form XObject as defined in [`Resources`] of a [`Page`].
+        ///
+        /// [`Resources`]: crate::types::hierarchy::primitives::resources::Resources
+        /// [`Page`]: crate::types::hierarchy::page::Page
+        name: Identifier<&'a [u8]>,
+
+        /// Transformation matrix `[a b c d e f]` mapping the form space to the user space.
+        matrix: [f64; 6],
     },
 
     /// Represents a text drawing operation.
     DrawText {
         /// Text object to be drawn.
-        text: Text,
+        text: Box<Text>,
 
         /// Name of the [`Font`] as defined in [`Resources`] of a [`Page`].
         ///
@@ -37,55 +60,362 @@ pub(crate) enum Operation<'a> {
         /// [`Page`]: crate::types::hierarchy::page::Page
         font_name: Identifier<&'a [u8]>,
     },
+
+    /// Saves the current graphics state on the graphics state stack (`q` operator).
+    SaveState,
+
+    /// Restores the graphics state most recently saved on the graphics state stack (`Q`
+    /// operator).
+    RestoreState,
+
+    /// Intersects the current clipping path with the given [`Path`] (`W` or `W*` operator).
+    Clip {
+        /// The path to clip with.
+        path: Path,
+    },
+
+    /// Fills the given [`Path`] with a color (`f` or `f*` operator).
+    FillPath {
+        /// The path to fill.
+        path: Path,
+
+        /// Color used to fill the path.
+        color: Color,
+    },
+
+    /// Sets the graphics state parameters from an [`ExtGState`] dictionary (`gs` operator).
+    ///
+    /// [`ExtGState`]: super::graphics_state::ExtGState
+    SetExtGState {
+        /// Name of the [`ExtGState`] as defined in [`Resources`] of a [`Page`].
+        ///
+        /// [`ExtGState`]: super::graphics_state::ExtGState
+        /// [`Resources`]: crate::types::hierarchy::primitives::resources::Resources
+        /// [`Page`]: crate::types::hierarchy::page::Page
+        name: Identifier<&'a [u8]>,
+    },
+}
+
+/// A [`Text`] drawn by an operation of a [`ContentStream`], kept so that the operation can be
+/// encoded again with a different content.
+#[derive(Debug, Clone)]
+struct DrawnText {
+    /// The drawn text.
+    text: Box<Text>,
+
+    /// Name of the font the text is drawn with, as defined in the resources of the page.
+    font_name: Vec<u8>,
+}
+
+/// Bytes of a single drawing operation in a [`ContentStream`], together with the area it paints.
+#[derive(Debug, Clone)]
+struct Extent {
+    /// Offset of the first byte of the operation.
+    start: usize,
+
+    /// Offset following the last byte of the operation.
+    end: usize,
+
+    /// Rectangle containing everything painted by the operation.
+    bounds: Rectangle,
+
+    /// Name of the image drawn by the operation, if it draws one.
+    image: Option<Vec<u8>>,
+
+    /// Whether the operation can be culled, which requires its bounds to be exact.
+    cullable: bool,
+
+    /// The text drawn by the operation, if it draws one.
+    text: Option<DrawnText>,
 }
 
 /// Represents the content stream object that is used for encoding and rendering content of a
 /// [`Page`].
 ///
 /// [`Page`]: crate::types::hierarchy::page::Page
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, Clone)]
 pub struct ContentStream {
-    id: ObjId<Self>,
+    /// ID of this `ContentStream`. Content streams of page layers have no ID until they are
+    /// written.
+    id: Option<ObjId<Self>>,
 
     /// Inner stream object containing the actual bytes of the content.
     stream: Stream,
+
+    /// Number of operations added to this `ContentStream`.
+    operations: usize,
+
+    /// Extents of the drawing operations with a known painted area, used to cull operations that
+    /// aren't visible, to size pages to their content and to replace the content of texts.
+    extents: Vec<Extent>,
 }
 
 impl ContentStream {
     /// Creates a new `ContentStream` with the given [`ObjId`].
     pub fn new(id: ObjId<Self>) -> Self {
         Self {
-            id,
+            id: Some(id),
+            stream: Stream::new(),
+            operations: 0,
+            extents: Vec::new(),
+        }
+    }
+
+    /// Creates a new `ContentStream` without an [`ObjId`], which is assigned when the stream is
+    /// written with [`ContentStream::write_with_id`].
+    pub(crate) fn without_id() -> Self {
+        Self {
+            id: None,
             stream: Stream::new(),
+            operations: 0,
+            extents: Vec::new(),
         }
     }
 
+    /// Replaces the bytes of this `ContentStream` with the given buffer, e.g. an empty buffer
+    /// reused from an [`Arena`], so content is added without growing a new buffer.
+    ///
+    /// [`Arena`]: crate::Arena
+    pub(crate) fn set_buffer(&mut self, buffer: Vec<u8>) {
+        self.stream = Stream::with_bytes(buffer);
+    }
+
+    /// Takes the buffer holding the bytes out of this `ContentStream`, leaving it empty.
+    pub(crate) fn take_buffer(&mut self) -> Vec<u8> {
+        self.extents.clear();
+        self.stream.take_bytes()
+    }
+
+    /// Sets the [`ObjId`] of this `ContentStream`.
+    pub(crate) fn set_id(&mut self, id: ObjId<Self>) {
+        self.id = Some(id);
+    }
+
     /// Adds a content to this `ContentStream` that should be displayed on a [`Page`]. Content is
     /// added in means of `Operation` that describes specific content elements.
     pub(crate) fn add_content(&mut self, operation: Operation) {
+        self.operations += 1;
+
+        let start = self.stream.len();
+        let (bounds, image, cullable) = match &operation {
+            Operation::DrawImage {
+                name, transform, ..
+            } => (
+                Rectangle::enclosing([
+                    transform.position,
+                    Position::new(
+                        transform.position.x + transform.scale.x,
+                        transform.position.y + transform.scale.y,
+                    ),
+                ]),
+                Some(name.as_bytes().to_vec()),
+                true,
+            ),
+            Operation::FillPath { path, .. } => (path.bounds(), None, true),
+            // the area painted by a text depends on its font, so its bounds are only an estimate
+            Operation::DrawText { text, .. } => (Some(text.extent()), None, false),
+            _ => (None, None, false),
+        };
+
+        let mut drawn_text = None;
         match operation {
-            Operation::DrawImage { name, transform } => self.draw_image(name, transform),
-            Operation::DrawText { text, font_name } => self.draw_text(text, font_name),
+            Operation::DrawImage {
+                name,
+                transform,
+                stencil_color,
+            } => self.draw_image(name, transform, stencil_color),
+            Operation::DrawForm { name, matrix } => self.draw_form(name, matrix),
+            Operation::DrawText { text, font_name } => {
+                self.draw_text(&text, font_name.clone());
+                drawn_text = Some(DrawnText {
+                    text,
+                    font_name: font_name.as_bytes().to_vec(),
+                });
+            }
+            Operation::SaveState => self.push_operator(b"q"),
+            Operation::RestoreState => self.push_operator(b"Q"),
+            Operation::Clip { path } => {
+                self.start_operation();
+                let mut clip = Vec::new();
+                path.write_clip(&mut clip)
+                    .expect("Writing to Vec should never fail.");
+                self.stream.push_bytes(&clip);
+            }
+            Operation::FillPath { path, color } => {
+                self.start_operation();
+                let mut fill = Vec::new();
+                color
+                    .write_non_stroke(&mut fill)
+                    .and_then(|_| path.write_fill(&mut fill))
+                    .expect("Writing to Vec should never fail.");
+                self.stream.push_bytes(&fill);
+            }
+            Operation::SetExtGState { name } => {
+                self.start_operation();
+                self.stream.write_identifier(&name);
+                self.stream.push_bytes(b"gs");
+                self.stream.push_bytes(constants::NL_MARKER);
+            }
+        }
+
+        if let Some(bounds) = bounds {
+            self.extents.push(Extent {
+                start,
+                end: self.stream.len(),
+                bounds,
+                image,
+                cullable,
+                text: drawn_text,
+            });
         }
     }
 
-    /// Encodes an image in this `ContentStream`.
-    fn draw_image(&mut self, name: Identifier<&[u8]>, transform: ImageTransform) {
-        let Position {
-            x: width,
-            y: height,
-        } = transform.scale;
+    /// Encodes the texts drawn in this `ContentStream` again, replacing each text the given
+    /// function returns a replacement for. Texts that are laid out in a bounding rectangle are
+    /// broken into lines again.
+    pub(crate) fn replace_texts(&mut self, mut replace: impl FnMut(&Text) -> Option<Text>) {
+        let bytes = self.stream.as_bytes();
+        let mut replaced_bytes = Vec::with_capacity(bytes.len());
+        let mut replaced = false;
+        let mut cursor = 0;
 
-        let Position { x, y } = transform.position;
+        for extent in &mut self.extents {
+            replaced_bytes.extend_from_slice(&bytes[cursor..extent.start]);
+            cursor = extent.end;
 
+            let start = replaced_bytes.len();
+            let replacement = extent
+                .text
+                .as_mut()
+                .and_then(|drawn| replace(&drawn.text).map(|text| (drawn, text)));
+            match replacement {
+                Some((drawn, text)) => {
+                    replaced_bytes.extend(
+                        text.to_bytes(Identifier::new(&drawn.font_name[..]))
+                            .expect("Writing to Vec should never fail."),
+                    );
+                    extent.bounds = text.extent();
+                    *drawn.text = text;
+                    replaced = true;
+                }
+                None => replaced_bytes.extend_from_slice(&bytes[extent.start..extent.end]),
+            }
+            extent.start = start;
+            extent.end = replaced_bytes.len();
+        }
+
+        if replaced {
+            replaced_bytes.extend_from_slice(&bytes[cursor..]);
+            self.stream = Stream::with_bytes(replaced_bytes);
+        }
+    }
+
+    /// Returns a copy of this `ContentStream` without the drawing operations that paint entirely
+    /// outside of the given visible area, or `None` if all of them paint inside of it. Only images
+    /// and filled paths are culled, since the area painted by a text depends on its font.
+    pub(crate) fn culled(&self, visible: Rectangle) -> Option<Self> {
+        let hidden: Vec<&Extent> = self.hidden(visible).collect();
+
+        if hidden.is_empty() {
+            return None;
+        }
+
+        let bytes = self.stream.as_bytes();
+        let mut kept = Vec::with_capacity(bytes.len());
+        let mut push_kept = |kept_bytes: &[u8]| {
+            // operations don't always end with a new line, so one is added if the operations
+            // around a culled one would otherwise be joined
+            let joined = kept
+                .last()
+                .is_some_and(|byte: &u8| !byte.is_ascii_whitespace())
+                && kept_bytes
+                    .first()
+                    .is_some_and(|byte| !byte.is_ascii_whitespace());
+            if joined {
+                kept.extend_from_slice(constants::NL_MARKER);
+            }
+            kept.extend_from_slice(kept_bytes);
+        };
+
+        let mut start = 0;
+        for extent in &hidden {
+            push_kept(&bytes[start..extent.start]);
+            start = extent.end;
+        }
+        push_kept(&bytes[start..]);
+
+        Some(Self {
+            id: self.id.clone(),
+            stream: Stream::with_bytes(kept),
+            operations: self.operations - hidden.len(),
+            // the culled copy is only written, so the offsets of its extents aren't kept
+            extents: Vec::new(),
+        })
+    }
+
+    /// Returns the names of the images drawn only by operations that paint entirely outside of
+    /// the given visible area, which are culled by [`ContentStream::culled`].
+    pub(crate) fn hidden_images(&self, visible: Rectangle) -> impl Iterator<Item = &[u8]> {
+        self.hidden(visible)
+            .filter_map(|extent| extent.image.as_deref())
+    }
+
+    /// Returns the extents of the operations that paint entirely outside of the given visible
+    /// area.
+    fn hidden(&self, visible: Rectangle) -> impl Iterator<Item = &Extent> {
+        self.extents
+            .iter()
+            .filter(move |extent| extent.cullable && !extent.bounds.overlaps(&visible))
+    }
+
+    /// Returns the smallest rectangle containing everything painted by the images, filled paths and
+    /// texts of this `ContentStream`, or `None` if it has none. The bounds of texts are estimated.
+    pub(crate) fn bounds(&self) -> Option<Rectangle> {
+        Rectangle::enclosing(
+            self.extents
+                .iter()
+                .flat_map(|extent| [extent.bounds.low_left(), extent.bounds.top_right()]),
+        )
+    }
+
+    /// Makes sure that the next operation starts on a new line. Some operations (such as drawing
+    /// an image) don't end with a new line.
+    fn start_operation(&mut self) {
+        if !self.stream.is_empty() && !self.stream.ends_with(constants::NL_MARKER) {
+            self.stream.push_bytes(constants::NL_MARKER);
+        }
+    }
+
+    /// Encodes a single operator without any operands in this `ContentStream`.
+    fn push_operator(&mut self, operator: &[u8]) {
+        self.start_operation();
+        self.stream.push_bytes(operator);
+        self.stream.push_bytes(constants::NL_MARKER);
+    }
+
+    /// Encodes an image in this `ContentStream`, painting a stencil mask with the given color.
+    fn draw_image(
+        &mut self,
+        name: Identifier<&[u8]>,
+        transform: ImageTransform,
+        stencil_color: Option<Color>,
+    ) {
         // Save graphics state
         self.stream.push_bytes(b"q");
         self.stream.push_bytes(constants::NL_MARKER);
 
+        // stencil masks are painted with the current fill color
+        if let Some(color) = stencil_color {
+            let mut fill = Vec::new();
+            color
+                .write_non_stroke(&mut fill)
+                .expect("Writing to Vec should never fail.");
+            self.stream.push_bytes(&fill);
+        }
+
         // apply transform 🤯
-        // width 0 0 height x y cm - Translate to (x, y) and scale to width x height
         self.stream
-            .push_bytes(format!("{width} 0 0 {height} {x} {y} cm").as_bytes());
+            .push_bytes(transform.to_matrix_operator().as_bytes());
         self.stream.push_bytes(constants::NL_MARKER);
 
         // /ImgName Do - Paint image
@@ -97,8 +427,28 @@ impl ContentStream {
         self.stream.push_bytes(b"Q");
     }
 
+    /// Encodes a form XObject, painted with the given transformation matrix, in this
+    /// `ContentStream`.
+    fn draw_form(&mut self, name: Identifier<&[u8]>, matrix: [f64; 6]) {
+        let [a, b, c, d, e, f] = matrix;
+
+        self.start_operation();
+        self.stream.push_bytes(b"q");
+        self.stream.push_bytes(constants::NL_MARKER);
+
+        self.stream
+            .push_bytes(format!("{a} {b} {c} {d} {e} {f} cm").as_bytes());
+        self.stream.push_bytes(constants::NL_MARKER);
+
+        self.stream.write_identifier(&name);
+        self.stream.push_bytes(b"Do");
+        self.stream.push_bytes(constants::NL_MARKER);
+
+        self.stream.push_bytes(b"Q");
+    }
+
     /// Encodes a text object in this `ContentStream`.
-    fn draw_text(&mut self, text: Text, font_name: Identifier<&[u8]>) {
+    fn draw_text(&mut self, text: &Text, font_name: Identifier<&[u8]>) {
         self.stream.push_bytes(
             &text
                 .to_bytes(font_name)
@@ -110,15 +460,50 @@ impl ContentStream {
         self.stream.is_empty()
     }
 
+    /// Returns the number of operations added to this `ContentStream`.
+    pub(crate) fn operations(&self) -> usize {
+        self.operations
+    }
+
+    /// Returns the bytes of the content of this `ContentStream`.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        self.stream.as_bytes()
+    }
+
+    /// Returns the number of bytes of the content of this `ContentStream`.
+    pub(crate) fn len(&self) -> usize {
+        self.stream.len()
+    }
+
     pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
-        &self.id
+        self.id
+            .as_ref()
+            .expect("ContentStream without ObjId must be written with `write_with_id`.")
+    }
+
+    /// Writes this `ContentStream` as an indirect object with the given [`ObjId`] into the
+    /// provided implementor of [`Write`].
+    ///
+    /// [`Write`]: std::io::Write
+    pub(crate) fn write_with_id(
+        &self,
+        writer: &mut dyn std::io::Write,
+        id: &ObjId<Self>,
+    ) -> Result<usize, std::io::Error> {
+        Ok(pdfgen_macros::write_chain! {
+            id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            self.write_content(writer),
+            self.write_end(writer),
+        })
     }
 }
 
 impl Object for ContentStream {
     fn write_def(&self, writer: &mut dyn std::io::Write) -> Result<usize, std::io::Error> {
         Ok(pdfgen_macros::write_chain! {
-            self.id.write_def(writer),
+            self.obj_ref().write_def(writer),
             writer.write(constants::NL_MARKER),
         })
     }
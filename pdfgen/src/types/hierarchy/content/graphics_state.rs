@@ -0,0 +1,484 @@
+//! Implementation of the PDF graphics state parameter dictionary (`ExtGState`).
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    ObjId,
+    types::{
+        constants,
+        hierarchy::primitives::{identifier::Identifier, object::Object},
+    },
+};
+
+/// The blend mode that shall be used in the transparent imaging model, determining how the colour
+/// of painted content (source) is combined with the colour of the content it is painted over
+/// (backdrop).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+pub enum BlendMode {
+    /// Selects the source colour, ignoring the backdrop. This is the default blend mode.
+    Normal,
+
+    /// Same as [`BlendMode::Normal`]. Kept for compatibility with PDF 1.3 and earlier.
+    Compatible,
+
+    /// Multiplies the backdrop and source colour values. The result colour is always at least as
+    /// dark as either of the two constituent colours.
+    Multiply,
+
+    /// Multiplies the complements of the backdrop and source colour values, then complements the
+    /// result. The result colour is always at least as light as either of the two constituent
+    /// colours.
+    Screen,
+
+    /// Multiplies or screens the colours, depending on the backdrop colour value. Source colours
+    /// overlay the backdrop while preserving its highlights and shadows.
+    Overlay,
+
+    /// Selects the darker of the backdrop and source colours.
+    Darken,
+
+    /// Selects the lighter of the backdrop and source colours.
+    Lighten,
+
+    /// Brightens the backdrop colour to reflect the source colour.
+    ColorDodge,
+
+    /// Darkens the backdrop colour to reflect the source colour.
+    ColorBurn,
+
+    /// Multiplies or screens the colours, depending on the source colour value. The effect is
+    /// similar to shining a harsh spotlight on the backdrop.
+    HardLight,
+
+    /// Darkens or lightens the colours, depending on the source colour value. The effect is
+    /// similar to shining a diffused spotlight on the backdrop.
+    SoftLight,
+
+    /// Subtracts the darker of the two constituent colours from the lighter colour.
+    Difference,
+
+    /// Produces an effect similar to that of [`BlendMode::Difference`] but lower in contrast.
+    Exclusion,
+
+    /// Creates a colour with the hue of the source colour and the saturation and luminosity of
+    /// the backdrop colour.
+    Hue,
+
+    /// Creates a colour with the saturation of the source colour and the hue and luminosity of
+    /// the backdrop colour.
+    Saturation,
+
+    /// Creates a colour with the hue and saturation of the source colour and the luminosity of
+    /// the backdrop colour.
+    Color,
+
+    /// Creates a colour with the luminosity of the source colour and the hue and saturation of
+    /// the backdrop colour.
+    Luminosity,
+}
+
+impl BlendMode {
+    /// Returns the [`Identifier`] corresponding to this `BlendMode`.
+    fn identifier(&self) -> Identifier<&'static [u8]> {
+        match self {
+            BlendMode::Normal => Identifier::from_static(b"Normal"),
+            BlendMode::Compatible => Identifier::from_static(b"Compatible"),
+            BlendMode::Multiply => Identifier::from_static(b"Multiply"),
+            BlendMode::Screen => Identifier::from_static(b"Screen"),
+            BlendMode::Overlay => Identifier::from_static(b"Overlay"),
+            BlendMode::Darken => Identifier::from_static(b"Darken"),
+            BlendMode::Lighten => Identifier::from_static(b"Lighten"),
+            BlendMode::ColorDodge => Identifier::from_static(b"ColorDodge"),
+            BlendMode::ColorBurn => Identifier::from_static(b"ColorBurn"),
+            BlendMode::HardLight => Identifier::from_static(b"HardLight"),
+            BlendMode::SoftLight => Identifier::from_static(b"SoftLight"),
+            BlendMode::Difference => Identifier::from_static(b"Difference"),
+            BlendMode::Exclusion => Identifier::from_static(b"Exclusion"),
+            BlendMode::Hue => Identifier::from_static(b"Hue"),
+            BlendMode::Saturation => Identifier::from_static(b"Saturation"),
+            BlendMode::Color => Identifier::from_static(b"Color"),
+            BlendMode::Luminosity => Identifier::from_static(b"Luminosity"),
+        }
+    }
+
+    /// Returns `true` if this `BlendMode` is separable, meaning that each colour component of the
+    /// result depends only on the corresponding components of the source and backdrop colours.
+    pub fn is_separable(&self) -> bool {
+        !matches!(
+            self,
+            BlendMode::Hue | BlendMode::Saturation | BlendMode::Color | BlendMode::Luminosity
+        )
+    }
+}
+
+/// The overprint mode, determining how colour components of DeviceCMYK colours that are zero
+/// affect the separations when overprinting is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd)]
+pub enum OverprintMode {
+    /// Each colour component of the painted colour replaces the corresponding separation, even if
+    /// it is zero. This is the default overprint mode.
+    Standard,
+
+    /// Colour components of DeviceCMYK colours that are zero leave the corresponding separations
+    /// unchanged, so e.g. cyan text overprinted onto a yellow background prints green.
+    NonZero,
+}
+
+impl OverprintMode {
+    /// Returns the number written for this `OverprintMode`.
+    fn number(&self) -> u8 {
+        match self {
+            OverprintMode::Standard => 0,
+            OverprintMode::NonZero => 1,
+        }
+    }
+}
+
+/// A graphics state parameter dictionary, specifying a set of graphics state parameters that can
+/// be applied at once with the `gs` operator in a content stream. Only the parameters that are
+/// set on the `ExtGState` are written, others are left unchanged in the graphics state.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::graphics_state::ExtGState;
+/// let half_transparent = ExtGState::new()
+///     .with_stroke_alpha(0.5)
+///     .with_fill_alpha(0.5);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, PartialOrd)]
+pub struct ExtGState {
+    /// The line width, in user space units (`LW` entry).
+    line_width: Option<f32>,
+
+    /// The current stroking alpha constant, specifying the constant shape or constant opacity
+    /// value that shall be used for stroking operations (`CA` entry).
+    stroke_alpha: Option<f32>,
+
+    /// Same as `stroke_alpha`, but for nonstroking operations, such as filling shapes or
+    /// painting text and images (`ca` entry).
+    fill_alpha: Option<f32>,
+
+    /// The current blend mode that shall be used in the transparent imaging model (`BM` entry).
+    blend_mode: Option<BlendMode>,
+
+    /// Whether stroking operations overprint, leaving separations that are not painted unchanged
+    /// instead of erasing them (`OP` entry).
+    stroke_overprint: Option<bool>,
+
+    /// Same as `stroke_overprint`, but for nonstroking operations (`op` entry).
+    fill_overprint: Option<bool>,
+
+    /// The overprint mode (`OPM` entry).
+    overprint_mode: Option<OverprintMode>,
+
+    /// Whether automatic stroke adjustment is applied, ensuring strokes have a uniform thickness
+    /// on the output device (`SA` entry).
+    stroke_adjustment: Option<bool>,
+}
+
+impl ExtGState {
+    const_identifiers! {
+        LW: b"LW",
+        CA: b"CA",
+        CA_LOWER: b"ca",
+        BM: b"BM",
+        OP: b"OP",
+        OP_LOWER: b"op",
+        OPM: b"OPM",
+        SA: b"SA",
+    }
+
+    /// Creates a new `ExtGState` that does not change any graphics state parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the line width in user space units.
+    pub fn with_line_width(mut self, width: f32) -> Self {
+        self.line_width = Some(width);
+        self
+    }
+
+    /// Sets the constant alpha used for stroking operations. The value is clamped to the range
+    /// `[0.0, 1.0]`, where `0.0` is fully transparent and `1.0` is fully opaque.
+    pub fn with_stroke_alpha(mut self, alpha: f32) -> Self {
+        self.stroke_alpha = Some(alpha.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets the constant alpha used for nonstroking (fill) operations, such as painting text and
+    /// images. The value is clamped to the range `[0.0, 1.0]`, where `0.0` is fully transparent
+    /// and `1.0` is fully opaque.
+    pub fn with_fill_alpha(mut self, alpha: f32) -> Self {
+        self.fill_alpha = Some(alpha.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Sets the [`BlendMode`] used in the transparent imaging model.
+    pub fn with_blend_mode(mut self, blend_mode: BlendMode) -> Self {
+        self.blend_mode = Some(blend_mode);
+        self
+    }
+
+    /// Sets whether stroking operations overprint. Overprinting leaves separations that are not
+    /// painted by the stroke unchanged, instead of erasing them, which avoids gaps between colours
+    /// when the separations are misregistered.
+    pub fn with_stroke_overprint(mut self, overprint: bool) -> Self {
+        self.stroke_overprint = Some(overprint);
+        self
+    }
+
+    /// Sets whether nonstroking (fill) operations overprint. If only the stroke overprint is set,
+    /// readers use it for nonstroking operations as well.
+    pub fn with_fill_overprint(mut self, overprint: bool) -> Self {
+        self.fill_overprint = Some(overprint);
+        self
+    }
+
+    /// Sets the [`OverprintMode`], which only has an effect if overprinting is enabled.
+    pub fn with_overprint_mode(mut self, mode: OverprintMode) -> Self {
+        self.overprint_mode = Some(mode);
+        self
+    }
+
+    /// Sets whether automatic stroke adjustment is applied, so thin lines have a uniform
+    /// thickness on the output device.
+    pub fn with_stroke_adjustment(mut self, adjustment: bool) -> Self {
+        self.stroke_adjustment = Some(adjustment);
+        self
+    }
+
+    /// Returns `true` if applying this `ExtGState` results in transparent content, either because
+    /// of constant alpha or a blend mode other than [`BlendMode::Normal`].
+    pub fn uses_transparency(&self) -> bool {
+        let is_transparent = |alpha: Option<f32>| alpha.is_some_and(|alpha| alpha < 1.0);
+        let is_blended = self
+            .blend_mode
+            .is_some_and(|mode| !matches!(mode, BlendMode::Normal | BlendMode::Compatible));
+
+        is_transparent(self.stroke_alpha) || is_transparent(self.fill_alpha) || is_blended
+    }
+
+    /// Writes this `ExtGState` as an indirect object with the given [`ObjId`] into the provided
+    /// implementor of [`Write`].
+    pub(crate) fn write(&self, writer: &mut dyn Write, id: &ObjId) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            self.write_content(writer),
+            self.write_end(writer),
+        })
+    }
+}
+
+impl Object for ExtGState {
+    fn write_def(&self, _writer: &mut dyn Write) -> Result<usize, Error> {
+        panic!("ExtGState does not fully implement the Object trait.")
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Identifier::EXT_G_STATE.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            if let Some(line_width) = self.line_width {
+                Self::LW.write(writer),
+                crate::write_fmt!(&mut *writer, "{line_width}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(stroke_alpha) = self.stroke_alpha {
+                Self::CA.write(writer),
+                crate::write_fmt!(&mut *writer, "{stroke_alpha}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(fill_alpha) = self.fill_alpha {
+                Self::CA_LOWER.write(writer),
+                crate::write_fmt!(&mut *writer, "{fill_alpha}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(blend_mode) = self.blend_mode {
+                Self::BM.write(writer),
+                blend_mode.identifier().write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(overprint) = self.stroke_overprint {
+                Self::OP.write(writer),
+                crate::write_fmt!(&mut *writer, "{overprint}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(overprint) = self.fill_overprint {
+                Self::OP_LOWER.write(writer),
+                crate::write_fmt!(&mut *writer, "{overprint}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(mode) = self.overprint_mode {
+                Self::OPM.write(writer),
+                crate::write_fmt!(&mut *writer, "{}", mode.number()),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(adjustment) = self.stroke_adjustment {
+                Self::SA.write(writer),
+                crate::write_fmt!(&mut *writer, "{adjustment}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::IdManager;
+
+    use super::{BlendMode, ExtGState, OverprintMode};
+
+    #[test]
+    fn empty_ext_g_state() {
+        let mut id_manager = IdManager::new();
+        let state = ExtGState::new();
+
+        let mut writer = Vec::default();
+        state.write(&mut writer, &id_manager.create_id()).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /ExtGState 
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn transparent_ext_g_state() {
+        let mut id_manager = IdManager::new();
+        let state = ExtGState::new()
+            .with_line_width(2.5)
+            .with_stroke_alpha(0.5)
+            .with_fill_alpha(1.5)
+            .with_blend_mode(BlendMode::Normal);
+
+        let mut writer = Vec::default();
+        state.write(&mut writer, &id_manager.create_id()).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /ExtGState 
+        /LW 2.5
+        /CA 0.5
+        /ca 1
+        /BM /Normal 
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn blend_modes() {
+        let mut id_manager = IdManager::new();
+        let mut writer = Vec::default();
+
+        for blend_mode in [
+            BlendMode::Multiply,
+            BlendMode::Screen,
+            BlendMode::Overlay,
+            BlendMode::ColorDodge,
+            BlendMode::Luminosity,
+        ] {
+            let state = ExtGState::new().with_blend_mode(blend_mode);
+            state.write(&mut writer, &id_manager.create_id()).unwrap();
+        }
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /ExtGState 
+        /BM /Multiply 
+        >>
+        endobj
+        2 0 obj
+        << /Type /ExtGState 
+        /BM /Screen 
+        >>
+        endobj
+        3 0 obj
+        << /Type /ExtGState 
+        /BM /Overlay 
+        >>
+        endobj
+        4 0 obj
+        << /Type /ExtGState 
+        /BM /ColorDodge 
+        >>
+        endobj
+        5 0 obj
+        << /Type /ExtGState 
+        /BM /Luminosity 
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn print_production_ext_g_state() {
+        let mut id_manager = IdManager::new();
+        let state = ExtGState::new()
+            .with_stroke_overprint(true)
+            .with_fill_overprint(false)
+            .with_overprint_mode(OverprintMode::NonZero)
+            .with_stroke_adjustment(true);
+
+        let mut writer = Vec::default();
+        state.write(&mut writer, &id_manager.create_id()).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /ExtGState 
+        /OP true
+        /op false
+        /OPM 1
+        /SA true
+        >>
+        endobj
+        ");
+        assert!(!state.uses_transparency());
+    }
+
+    #[test]
+    fn separable_blend_modes() {
+        assert!(BlendMode::Multiply.is_separable());
+        assert!(BlendMode::Difference.is_separable());
+        assert!(!BlendMode::Hue.is_separable());
+        assert!(!BlendMode::Luminosity.is_separable());
+    }
+
+    #[test]
+    fn uses_transparency() {
+        assert!(!ExtGState::new().uses_transparency());
+        assert!(!ExtGState::new().with_fill_alpha(1.0).uses_transparency());
+        assert!(ExtGState::new().with_stroke_alpha(0.9).uses_transparency());
+        assert!(
+            ExtGState::new()
+                .with_blend_mode(BlendMode::Multiply)
+                .uses_transparency()
+        );
+    }
+}
@@ -1,8 +1,16 @@
 //! Image PDF object types and implementations.
 
-use std::io::{BufReader, Cursor, Error, Read, Write};
+#[cfg(feature = "image-decoding")]
+use std::io::{BufReader, Cursor};
+use std::{
+    cmp::Ordering,
+    fmt,
+    io::{Error, Read, Write},
+    sync::Arc,
+};
 
-use image::ImageReader;
+#[cfg(feature = "image-decoding")]
+use image::{DynamicImage, ImageFormat, ImageReader};
 use pdfgen_macros::const_identifiers;
 
 use crate::{
@@ -10,33 +18,56 @@ use crate::{
     types::{
         constants,
         hierarchy::primitives::{
-            identifier::Identifier, object::Object, rectangle::Position, unit::Unit,
+            identifier::Identifier,
+            object::Object,
+            rectangle::{Position, Rectangle},
+            unit::Unit,
         },
     },
 };
 
-use super::stream::Stream;
+use super::{
+    ccitt::{self, CcittErr, CcittParams},
+    color::{Color, ColorSpace, ColorSpaceIds},
+    graphics_state::ExtGState,
+    jpeg::{self, JpegErr},
+    stream::Stream,
+};
 
-/// The colour space in which image samples shall be specified; it can be any type of colour space
-/// except Pattern.
-#[derive(Debug, Clone, PartialEq, PartialOrd)]
-#[allow(dead_code)]
-enum ColorSpace {
-    /// Device default RGB representation.
-    DeviceRgb,
+/// Possible errors that might be returned when creating an [`Image`].
+#[derive(Debug, thiserror::Error)]
+pub enum ImageErr {
+    /// Indicates that the bytes of the image could not be read.
+    #[error("Image could not be read: {0}")]
+    Read(std::io::Error),
 
-    /// Device default single gray channel representation.
-    DeviceGray,
-}
+    /// Indicates that the format of the image could not be detected from its bytes.
+    #[error("Image format could not be detected.")]
+    UnknownFormat,
 
-impl ColorSpace {
-    /// Encode this `ColorSpace` into the given implementor of [`Write`].
-    fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
-        match self {
-            ColorSpace::DeviceRgb => Identifier::new(b"DeviceRGB").write(writer),
-            ColorSpace::DeviceGray => Identifier::new(b"DeviceGray").write(writer),
-        }
-    }
+    /// Indicates that the image could not be decoded.
+    #[cfg(feature = "image-decoding")]
+    #[error("Image could not be decoded: {0}")]
+    Decode(image::ImageError),
+
+    /// Indicates that the number of raw samples doesn't match the dimensions and colour space of
+    /// the image.
+    #[error("Expected {expected} samples for the image, but got {actual}.")]
+    SampleCount {
+        /// Number of samples required by the dimensions and colour space of the image.
+        expected: usize,
+
+        /// Number of samples given.
+        actual: usize,
+    },
+
+    /// Indicates that the JPEG data could not be embedded.
+    #[error("JPEG image could not be embedded: {0}")]
+    Jpeg(JpegErr),
+
+    /// Indicates that the CCITT compressed TIFF file could not be embedded.
+    #[error("CCITT compressed image could not be embedded: {0}")]
+    Ccitt(CcittErr),
 }
 
 /// Represents the information that should be encoded in the dictionary of an [`Image`] stream.
@@ -48,14 +79,67 @@ struct ImageDict {
     /// The height of the image, in samples.
     height: u32,
 
-    /// The colour space in which image samples shall be specified.
-    color_space: ColorSpace,
+    /// The colour space in which image samples shall be specified; it can be any type of colour
+    /// space except Pattern. Stencil masks have no colour space, and are written with /ImageMask
+    /// true instead.
+    color_space: Option<ColorSpace>,
 
     /// The number of bits used to represent each colour component. Only a single value shall be
     /// specified; the number of bits shall be the same for all colour components. The value shall
     /// be 1, 2, 4, 8, or (from PDF 1.5) 16. If ImageMask is true, this entry is optional, but if
     /// specified, its value shall be 1.
     bits_per_comp: u8,
+
+    /// An array of numbers describing how to map image samples into the range of values
+    /// appropriate for the image's colour space, two for each colour component. Inverts the
+    /// painted areas of a stencil mask if set to `[1 0]`.
+    decode: Option<Vec<f32>>,
+
+    /// Whether image interpolation shall be performed by the PDF reader, smoothing the image when
+    /// it's drawn at a higher resolution than the one of its samples.
+    interpolate: bool,
+
+    /// The filter the samples of the image are encoded with, if any.
+    filter: Option<ImageFilter>,
+}
+
+/// Filters the samples of an [`Image`] can be encoded with. The samples are decoded by the PDF
+/// reader before the image is drawn.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+enum ImageFilter {
+    /// Samples compressed with the zlib/deflate compression method.
+    Flate,
+
+    /// Monochrome samples compressed with CCITT Group 3 or Group 4 fax compression.
+    CcittFax(CcittParams),
+
+    /// Samples compressed with the JPEG baseline format.
+    Dct,
+}
+
+impl ImageFilter {
+    const_identifiers! {
+        FLATE_DECODE,
+        CCITT_FAX_DECODE: b"CCITTFaxDecode",
+        DCT_DECODE: b"DCTDecode",
+    }
+
+    /// Writes the name of the decoding filter.
+    fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match self {
+            ImageFilter::Flate => Self::FLATE_DECODE.write(writer),
+            ImageFilter::CcittFax(_) => Self::CCITT_FAX_DECODE.write(writer),
+            ImageFilter::Dct => Self::DCT_DECODE.write(writer),
+        }
+    }
+
+    /// Returns the parameters of the decoding filter, if it has any.
+    fn params(&self) -> Option<&CcittParams> {
+        match self {
+            ImageFilter::Flate | ImageFilter::Dct => None,
+            ImageFilter::CcittFax(params) => Some(params),
+        }
+    }
 }
 
 /// Represents transformations that should be applied to the encoded [`Image`] such as position and
@@ -70,11 +154,93 @@ pub struct ImageTransform {
     ///
     /// [`Page`]: crate::types::hierarchy::page::Page
     pub scale: Position,
+
+    /// Whether the [`Image`] is mirrored, swapping its left and right sides.
+    pub flip_horizontal: bool,
+
+    /// Whether the [`Image`] is mirrored, swapping its top and bottom sides.
+    pub flip_vertical: bool,
+}
+
+impl ImageTransform {
+    /// Returns the `cm` operator mapping the unit square an image is painted in to the area
+    /// covered by the image. A flipped image is mirrored by negating its scale, and translated by
+    /// its size so that it covers the same area as the image that isn't flipped.
+    pub(crate) fn to_matrix_operator(self) -> String {
+        let Position {
+            x: mut width,
+            y: mut height,
+        } = self.scale;
+        let Position { mut x, mut y } = self.position;
+
+        if self.flip_horizontal {
+            x = x + width;
+            width = Unit::from_pt(-width.into_points());
+        }
+        if self.flip_vertical {
+            y = y + height;
+            height = Unit::from_pt(-height.into_points());
+        }
+
+        // width 0 0 height x y cm - Translate to (x, y) and scale to width x height
+        format!("{width} 0 0 {height} {x} {y} cm")
+    }
+}
+
+/// Opens the reader the samples of an [`Image`] are read from.
+type OpenSamples = dyn Fn() -> Result<Box<dyn Read>, Error> + Send + Sync;
+
+/// Source of the samples of an [`Image`] that are read when the image is written, instead of being
+/// held in memory.
+#[derive(Clone)]
+struct SampleSource {
+    /// Opens a new reader of the samples, each time the image is written.
+    open: Arc<OpenSamples>,
+
+    /// Number of bytes of samples read from the source.
+    len: usize,
+}
+
+impl fmt::Debug for SampleSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SampleSource")
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for SampleSource {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.open, &other.open) && self.len == other.len
+    }
+}
+
+impl PartialOrd for SampleSource {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self == other).then_some(Ordering::Equal)
+    }
+}
+
+/// Determines how an [`Image`] is scaled to fit within a [`Rectangle`], see
+/// [`ImageBuilder::fit_within`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FitMode {
+    /// The image is scaled to the largest size that fits within the rectangle, preserving its
+    /// aspect ratio, leaving free space on two of its sides.
+    #[default]
+    Contain,
+
+    /// The image is scaled to the smallest size that covers the whole rectangle, preserving its
+    /// aspect ratio, extending beyond two of its sides.
+    Cover,
+
+    /// The image is stretched to the size of the rectangle, ignoring its aspect ratio.
+    Stretch,
 }
 
 /// A sampled image (or just image for short) is a rectangular array of sample values, each
 /// representing a colour.
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Image {
     /// Raw bytes of the image containing the samples. For an RGB image, each sample is represented
     /// by three values, one for each color component - red, green and blue.
@@ -85,6 +251,10 @@ pub struct Image {
     //       • The number of bits per colour component
     samples: Stream,
 
+    /// Source the samples are read from when the image is written, in which case `samples` is
+    /// empty.
+    source: Option<SampleSource>,
+
     /// Contains the dictionary for the image encoding the specific information of this image such
     /// as width and height in number of samples.
     dict: ImageDict,
@@ -94,6 +264,17 @@ pub struct Image {
     ///
     /// [`ContentStream`]: super::ContentStream
     transform: ImageTransform,
+
+    /// Graphics state parameters that should be applied when drawing this image.
+    ext_g_state: Option<ExtGState>,
+
+    /// Mask of this image. A DeviceGray image holding the alpha channel of this image is written
+    /// as its /SMask so the image is composited with the content behind it, and a stencil mask
+    /// is written as its /Mask, only showing the image where the mask is painted.
+    mask: Option<Box<Image>>,
+
+    /// Color a stencil mask is painted with, `None` for images with a colour space.
+    stencil_color: Option<Color>,
 }
 
 impl Image {
@@ -104,52 +285,323 @@ impl Image {
         HEIGHT,
         COLOR_SPACE,
         BITS_PER_COMPONENT,
+        S_MASK: b"SMask",
+        MASK,
+        IMAGE_MASK,
+        DECODE,
+        INTERPOLATE,
+        FILTER,
+        DECODE_PARMS,
     }
 
+    #[cfg(feature = "image-decoding")]
+    /// Luminance below which pixels are painted in a stencil mask.
+    const STENCIL_THRESHOLD: u8 = 128;
+
+    /// Compression level used for the samples of images that aren't decoded from JPEG sources.
+    pub const DEFAULT_COMPRESSION_LEVEL: u8 = 6;
+
+    /// Highest supported compression level, producing the smallest output in the most time.
+    pub const MAX_COMPRESSION_LEVEL: u8 = 9;
+
+    #[cfg(feature = "image-decoding")]
     /// Creates a new [`Image`] by reading the bytes from the `reader` with default width and
     /// height of 100 mm and position 0, 0 (lower left corner of a page).
-    pub fn from_reader(reader: impl Read) -> ImageBuilder<false> {
+    ///
+    /// Returns an error if the bytes could not be read or decoded, see [`Image::from_bytes`].
+    pub fn from_reader(reader: impl Read) -> Result<ImageBuilder<false>, ImageErr> {
         let mut bytes = Vec::new();
-        BufReader::new(reader).read_to_end(&mut bytes).unwrap();
+        BufReader::new(reader)
+            .read_to_end(&mut bytes)
+            .map_err(ImageErr::Read)?;
         Self::from_bytes(bytes)
     }
 
-    pub fn from_file(file: &std::fs::File) -> ImageBuilder<false> {
-        let mut bytes = Vec::new();
-        BufReader::new(file).read_to_end(&mut bytes).unwrap();
-        Self::from_bytes(bytes)
+    #[cfg(feature = "image-decoding")]
+    /// Creates a new [`Image`] by reading the bytes of the `file` with default width and height of
+    /// 100 mm and position 0, 0 (lower left corner of a page).
+    ///
+    /// Returns an error if the file could not be read or decoded, see [`Image::from_bytes`].
+    pub fn from_file(file: &std::fs::File) -> Result<ImageBuilder<false>, ImageErr> {
+        Self::from_reader(file)
     }
 
+    #[cfg(feature = "image-decoding")]
     /// Creates a new [`Image`] from the given bytes with default width and height of 100 mm and
     /// position 0, 0 (lower left corner of a page).
-    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> ImageBuilder<false> {
-        let bufreader = Cursor::new(bytes.into());
+    ///
+    /// The alpha channel of an image, such as a transparent PNG, is kept as a soft mask, so the
+    /// image is composited with the content behind it.
+    ///
+    /// The samples of non-JPEG sources are compressed with [`Image::DEFAULT_COMPRESSION_LEVEL`],
+    /// see [`ImageBuilder::with_compression_level`].
+    ///
+    /// Returns an error if the format of the image could not be detected, or if the image could
+    /// not be decoded.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Result<ImageBuilder<false>, ImageErr> {
+        let (decoded_image, is_jpeg) = Self::decode(bytes.into())?;
+
+        Ok(ImageBuilder {
+            inner: Self::from_decoded(&decoded_image),
+            // NOTE: samples decoded from lossy JPEG compression barely shrink when compressed
+            //       with zlib.
+            compression_level: if is_jpeg {
+                0
+            } else {
+                Self::DEFAULT_COMPRESSION_LEVEL
+            },
+        })
+    }
+
+    #[cfg(feature = "image-decoding")]
+    /// Creates a new stencil mask [`Image`] from the given bytes with default width and height of
+    /// 100 mm and position 0, 0 (lower left corner of a page).
+    ///
+    /// A stencil mask has 1 bit per sample: dark pixels of the source image are painted with the
+    /// color set by [`ImageBuilder::with_stencil_color`] (black by default), and light pixels
+    /// leave the content behind the mask visible. This is well suited for scanned documents and
+    /// monochrome logos. A stencil mask can also be used as the mask of another image, see
+    /// [`ImageBuilder::with_mask`].
+    ///
+    /// Returns an error if the format of the image could not be detected, or if the image could
+    /// not be decoded.
+    pub fn stencil_from_bytes(bytes: impl Into<Vec<u8>>) -> Result<ImageBuilder<false>, ImageErr> {
+        let (decoded_image, _) = Self::decode(bytes.into())?;
+
+        Ok(ImageBuilder {
+            inner: Self::stencil_from_decoded(&decoded_image),
+            compression_level: Self::DEFAULT_COMPRESSION_LEVEL,
+        })
+    }
+
+    /// Creates a new [`Image`] from raw samples with 8 bits per component in the given colour
+    /// space, with default width and height of 100 mm and position 0, 0 (lower left corner of a
+    /// page). Samples are given row by row, from the top left corner of the image, with the
+    /// components of each pixel one after another. The samples are compressed with
+    /// [`Image::DEFAULT_COMPRESSION_LEVEL`].
+    ///
+    /// Returns an error if the number of samples doesn't match the width, height and colour space.
+    pub fn from_raw(
+        samples: impl Into<Vec<u8>>,
+        width: u32,
+        height: u32,
+        color_space: ColorSpace,
+    ) -> Result<ImageBuilder<false>, ImageErr> {
+        let samples = samples.into();
+        let expected = width as usize * height as usize * color_space.components();
+        if samples.len() != expected {
+            return Err(ImageErr::SampleCount {
+                expected,
+                actual: samples.len(),
+            });
+        }
+
+        Ok(ImageBuilder {
+            inner: Self::with_samples(samples, width, height, color_space),
+            compression_level: Self::DEFAULT_COMPRESSION_LEVEL,
+        })
+    }
+
+    /// Creates a new [`Image`] from raw samples with 8 bits per component in the given colour
+    /// space, like [`Image::from_raw`], but reads the samples from the reader returned by `open`
+    /// when the document is written, instead of holding them in memory. This keeps the memory
+    /// usage low for very large images, for example by opening a file of samples.
+    ///
+    /// The reader is opened each time the image is written, and its samples are written
+    /// uncompressed. Writing the document fails if the reader can't be opened, or if it ends before
+    /// all samples of the image were read.
+    pub fn from_raw_source<F, R>(
+        open: F,
+        width: u32,
+        height: u32,
+        color_space: ColorSpace,
+    ) -> ImageBuilder<false>
+    where
+        F: Fn() -> Result<R, Error> + Send + Sync + 'static,
+        R: Read + 'static,
+    {
+        let mut image = Self::with_samples(Vec::new(), width, height, color_space);
+        image.source = Some(SampleSource {
+            open: Arc::new(move || Ok(Box::new(open()?) as Box<dyn Read>)),
+            len: width as usize * height as usize * color_space.components(),
+        });
+
+        ImageBuilder {
+            inner: image,
+            compression_level: 0,
+        }
+    }
+
+    /// Creates a new [`Image`] from the bytes of a baseline JPEG file, with default width and
+    /// height of 100 mm and position 0, 0 (lower left corner of a page). The JPEG data is embedded
+    /// as is, without decoding it.
+    ///
+    /// Returns an error if the data isn't a valid JPEG file, or if it isn't a grayscale or RGB
+    /// image with 8 bits per sample.
+    pub fn from_jpeg(bytes: impl Into<Vec<u8>>) -> Result<ImageBuilder<false>, ImageErr> {
+        let bytes = bytes.into();
+        let header = jpeg::read_header(&bytes).map_err(ImageErr::Jpeg)?;
+
+        let mut image = Self::with_samples(bytes, header.width, header.height, header.color_space);
+        image.dict.filter = Some(ImageFilter::Dct);
+
+        Ok(ImageBuilder {
+            inner: image,
+            compression_level: 0,
+        })
+    }
+
+    /// Creates a new monochrome [`Image`] from CCITT Group 3 or Group 4 fax compressed data with
+    /// the given parameters, with default width and height of 100 mm and position 0, 0 (lower left
+    /// corner of a page). The data is embedded as is, without decoding it.
+    pub fn from_ccitt(data: impl Into<Vec<u8>>, params: CcittParams) -> ImageBuilder<false> {
+        let mut image = Self::with_samples(
+            data.into(),
+            params.columns(),
+            params.rows(),
+            ColorSpace::DeviceGray,
+        );
+        image.dict.bits_per_comp = 1;
+        image.dict.filter = Some(ImageFilter::CcittFax(params));
+
+        ImageBuilder {
+            inner: image,
+            compression_level: 0,
+        }
+    }
+
+    /// Creates a new monochrome [`Image`] from the bytes of a CCITT Group 3 or Group 4 compressed
+    /// TIFF file, such as the output of a document scanner, with default width and height of
+    /// 100 mm and position 0, 0 (lower left corner of a page). The compressed data of the first
+    /// image in the file is embedded as is, without decoding it.
+    ///
+    /// Returns an error if the file isn't a valid TIFF file, if it's compressed differently, or if
+    /// the image is split into multiple strips.
+    pub fn from_ccitt_tiff(bytes: &[u8]) -> Result<ImageBuilder<false>, ImageErr> {
+        let (data, params) = ccitt::read_tiff(bytes).map_err(ImageErr::Ccitt)?;
+        Ok(Self::from_ccitt(data, params))
+    }
 
-        let decoded_image = ImageReader::new(bufreader)
+    #[cfg(feature = "image-decoding")]
+    /// Decodes the given bytes of an image in any supported format. Returns the decoded image and
+    /// whether it was decoded from a JPEG source.
+    fn decode(bytes: Vec<u8>) -> Result<(DynamicImage, bool), ImageErr> {
+        let reader = ImageReader::new(Cursor::new(bytes))
             .with_guessed_format()
-            .unwrap()
-            .decode()
-            .unwrap();
+            .map_err(ImageErr::Read)?;
+        let is_jpeg = match reader.format() {
+            Some(format) => format == ImageFormat::Jpeg,
+            None => return Err(ImageErr::UnknownFormat),
+        };
+
+        let decoded_image = reader.decode().map_err(ImageErr::Decode)?;
+        Ok((decoded_image, is_jpeg))
+    }
+
+    #[cfg(feature = "image-decoding")]
+    /// Creates a new stencil mask [`Image`] from the decoded image, painting the pixels with
+    /// luminance below [`Image::STENCIL_THRESHOLD`].
+    pub(crate) fn stencil_from_decoded(decoded_image: &DynamicImage) -> Self {
+        let (width, height) = (decoded_image.width(), decoded_image.height());
+        let luma = decoded_image.to_luma8().into_raw();
 
-        let img = decoded_image.to_rgb8();
-        let (width, height) = img.dimensions();
-        let pixels = img.into_raw();
+        // NOTE: each row of samples starts at a byte boundary, and sample value 0 marks the
+        //       painted areas of a stencil mask.
+        let row_len = (width as usize).div_ceil(8);
+        let mut samples = vec![0; row_len * height as usize];
+        for (y, row) in luma.chunks_exact(width as usize).enumerate() {
+            for (x, _) in row
+                .iter()
+                .enumerate()
+                .filter(|(_, luma)| **luma >= Self::STENCIL_THRESHOLD)
+            {
+                samples[y * row_len + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+
+        let mut stencil = Self::with_samples(samples, width, height, ColorSpace::DeviceGray);
+        stencil.dict.color_space = None;
+        stencil.dict.bits_per_comp = 1;
+        stencil.stencil_color = Some(Color::Gray(0));
+
+        stencil
+    }
 
-        let img = Self {
-            samples: Stream::with_bytes(pixels),
+    #[cfg(feature = "image-decoding")]
+    /// Creates a new [`Image`] from the decoded image, in DeviceGray colour space for grayscale
+    /// images and DeviceRGB otherwise. If the decoded image has an alpha channel, it's kept as
+    /// the soft mask of the image.
+    ///
+    /// Images with more than 8 bits per channel, such as 16-bit PNG and TIFF images, are kept with
+    /// 16 bits per component.
+    pub(crate) fn from_decoded(decoded_image: &DynamicImage) -> Self {
+        let (width, height) = (decoded_image.width(), decoded_image.height());
+        let color = decoded_image.color();
+        let is_16_bit = color.bytes_per_pixel() > color.channel_count();
+
+        // NOTE: 16-bit samples are written in big-endian byte order.
+        let to_bytes = |samples: Vec<u16>| -> Vec<u8> {
+            samples.into_iter().flat_map(u16::to_be_bytes).collect()
+        };
+
+        let mask = color.has_alpha().then(|| {
+            let alpha = if is_16_bit {
+                let rgba = decoded_image.to_rgba16().into_raw();
+                to_bytes(rgba.chunks_exact(4).map(|pixel| pixel[3]).collect())
+            } else {
+                let rgba = decoded_image.to_rgba8().into_raw();
+                rgba.chunks_exact(4).map(|pixel| pixel[3]).collect()
+            };
+
+            let mut mask = Self::with_samples(alpha, width, height, ColorSpace::DeviceGray);
+            mask.dict.bits_per_comp = if is_16_bit { 16 } else { 8 };
+            Box::new(mask)
+        });
+
+        // NOTE: grayscale images only need one sample per pixel instead of three.
+        let (samples, color_space) = match (color.has_color(), is_16_bit) {
+            (true, true) => (
+                to_bytes(decoded_image.to_rgb16().into_raw()),
+                ColorSpace::DeviceRgb,
+            ),
+            (true, false) => (decoded_image.to_rgb8().into_raw(), ColorSpace::DeviceRgb),
+            (false, true) => (
+                to_bytes(decoded_image.to_luma16().into_raw()),
+                ColorSpace::DeviceGray,
+            ),
+            (false, false) => (decoded_image.to_luma8().into_raw(), ColorSpace::DeviceGray),
+        };
+
+        let mut image = Self::with_samples(samples, width, height, color_space);
+        image.dict.bits_per_comp = if is_16_bit { 16 } else { 8 };
+
+        Self { mask, ..image }
+    }
+
+    /// Creates a new 8 bits per component [`Image`] with the given samples.
+    fn with_samples(samples: Vec<u8>, width: u32, height: u32, color_space: ColorSpace) -> Self {
+        Self {
+            samples: Stream::with_bytes(samples),
+            source: None,
             dict: ImageDict {
                 width,
                 height,
-                color_space: ColorSpace::DeviceRgb,
+                color_space: Some(color_space),
                 bits_per_comp: 8,
+                decode: None,
+                interpolate: false,
+                filter: None,
             },
             transform: ImageTransform {
                 position: Position::from_mm(0.0, 0.0),
                 scale: Position::from_units(width as f32, height as f32),
+                flip_horizontal: false,
+                flip_vertical: false,
             },
-        };
-
-        ImageBuilder { inner: img }
+            ext_g_state: None,
+            mask: None,
+            stencil_color: None,
+        }
     }
 
     /// Sets the width and height of this [`Image`].
@@ -179,69 +631,315 @@ impl Image {
         self.transform
     }
 
+    /// Returns a copy of this [`Image`] downscaled to fit within `max_size` pixels in both
+    /// dimensions, for use as a page thumbnail, see [`Page::set_thumbnail`]. Each pixel of the
+    /// thumbnail is the average of the pixels of this image it covers, and an image that already
+    /// fits is copied at its size. The mask, decode array and placement of the image aren't copied.
+    ///
+    /// Returns `None` for images that can't be used as a thumbnail or can't be downscaled: images
+    /// not in the DeviceGray or DeviceRGB colour space, stencil masks, JPEG or CCITT encoded
+    /// images, images with 16 bits per component, and images read from a source when written.
+    ///
+    /// [`Page::set_thumbnail`]: crate::types::hierarchy::page::Page::set_thumbnail
+    pub fn thumbnail(&self, max_size: u32) -> Option<Image> {
+        let color_space = self.dict.color_space?;
+        if !matches!(color_space, ColorSpace::DeviceGray | ColorSpace::DeviceRgb)
+            || self.dict.bits_per_comp != 8
+            || self.source.is_some()
+        {
+            return None;
+        }
+
+        let samples = match self.dict.filter {
+            None => self.samples.as_bytes().to_vec(),
+            Some(ImageFilter::Flate) => {
+                miniz_oxide::inflate::decompress_to_vec_zlib(self.samples.as_bytes()).ok()?
+            }
+            Some(ImageFilter::CcittFax(_) | ImageFilter::Dct) => return None,
+        };
+
+        let (width, height) = (self.dict.width as usize, self.dict.height as usize);
+        let components = color_space.components();
+        let max_size = max_size.max(1) as usize;
+        let scale = (max_size as f64 / width.max(height) as f64).min(1.0);
+        let thumb_width = ((width as f64 * scale).round() as usize).max(1);
+        let thumb_height = ((height as f64 * scale).round() as usize).max(1);
+
+        // NOTE: each pixel of the thumbnail covers the pixels from its start up to the start of
+        //       the next pixel, and at least one pixel of the image.
+        let covered = |thumb_pos: usize, thumb_len: usize, len: usize| {
+            let start = thumb_pos * len / thumb_len;
+            start..((thumb_pos + 1) * len / thumb_len).max(start + 1)
+        };
+
+        let mut thumb_samples = Vec::with_capacity(thumb_width * thumb_height * components);
+        for thumb_y in 0..thumb_height {
+            let rows = covered(thumb_y, thumb_height, height);
+            for thumb_x in 0..thumb_width {
+                let columns = covered(thumb_x, thumb_width, width);
+                let count = rows.len() * columns.len();
+
+                for component in 0..components {
+                    let sum: usize = rows
+                        .clone()
+                        .flat_map(|y| columns.clone().map(move |x| (y * width + x) * components))
+                        .map(|pixel| usize::from(samples[pixel + component]))
+                        .sum();
+                    thumb_samples.push(((sum + count / 2) / count) as u8);
+                }
+            }
+        }
+
+        let mut thumbnail = Self::with_samples(
+            thumb_samples,
+            thumb_width as u32,
+            thumb_height as u32,
+            color_space,
+        );
+        if self.dict.filter.is_some() {
+            thumbnail.compress(Self::DEFAULT_COMPRESSION_LEVEL);
+        }
+
+        Some(thumbnail)
+    }
+
+    /// Takes the [`ExtGState`] out of this [`Image`], leaving `None` in its place.
+    pub(crate) fn take_ext_g_state(&mut self) -> Option<ExtGState> {
+        self.ext_g_state.take()
+    }
+
+    /// Returns `true` if this [`Image`] is a stencil mask.
+    pub(crate) fn is_stencil(&self) -> bool {
+        self.dict.color_space.is_none()
+    }
+
+    /// Returns the [`ColorSpace`] of this [`Image`], or `None` if it's a stencil mask.
+    pub(crate) fn color_space(&self) -> Option<ColorSpace> {
+        self.dict.color_space
+    }
+
+    /// Replaces the object defining the colour space of this [`Image`] with the one it is mapped
+    /// to.
+    pub(crate) fn remap_color_space_ids(&mut self, ids: &ColorSpaceIds) {
+        if let Some(color_space) = &mut self.dict.color_space {
+            color_space.remap_ids(ids);
+        }
+    }
+
+    /// Returns the color a stencil mask is painted with, or `None` if this [`Image`] isn't a
+    /// stencil mask.
+    pub(crate) fn stencil_color(&self) -> Option<Color> {
+        self.stencil_color
+    }
+
+    /// Compresses the samples of this [`Image`] and its mask with zlib at the given level.
+    /// Level 0 leaves the samples uncompressed, as do samples that are already encoded with a
+    /// filter or read from a source when written.
+    pub(crate) fn compress(&mut self, level: u8) {
+        if level == 0 || self.dict.filter.is_some() || self.source.is_some() {
+            return;
+        }
+
+        let level = level.min(Self::MAX_COMPRESSION_LEVEL);
+        let compressed = miniz_oxide::deflate::compress_to_vec_zlib(self.samples.as_bytes(), level);
+        self.samples = Stream::with_bytes(compressed);
+        self.dict.filter = Some(ImageFilter::Flate);
+
+        if let Some(mask) = &mut self.mask {
+            mask.compress(level);
+        }
+    }
+
+    /// Returns the mask of this [`Image`], either a soft mask holding its alpha channel or a
+    /// stencil mask, if it has one.
+    pub(crate) fn mask(&self) -> Option<&Image> {
+        self.mask.as_deref()
+    }
+
+    /// Returns the soft mask holding the alpha channel of this [`Image`], if it has one.
+    pub(crate) fn soft_mask(&self) -> Option<&Image> {
+        self.mask().filter(|mask| !mask.is_stencil())
+    }
+
     pub fn write(&self, writer: &mut dyn Write, id: &ObjId) -> Result<usize, Error> {
+        self.write_with_mask(writer, id, None)
+    }
+
+    /// Writes this [`Image`] referencing its mask written as the object with the given id. The
+    /// mask itself is written separately, see [`Image::mask`].
+    pub(crate) fn write_with_mask(
+        &self,
+        writer: &mut dyn Write,
+        id: &ObjId,
+        mask_id: Option<&ObjId>,
+    ) -> Result<usize, Error> {
         Ok(pdfgen_macros::write_chain! {
             id.write_def(writer),
             writer.write(constants::NL_MARKER),
 
-            self.write_content(writer),
+            self.write_dict_and_samples(writer, mask_id),
             self.write_end(writer),
         })
     }
-}
 
-impl Object for Image {
-    fn write_def(&self, _writer: &mut dyn Write) -> Result<usize, Error> {
-        panic!("Image does not fully implement the Object trait.")
-    }
-
-    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+    fn write_dict_and_samples(
+        &self,
+        writer: &mut dyn Write,
+        mask_id: Option<&ObjId>,
+    ) -> Result<usize, Error> {
         // NOTE: The image dictionary shall specify the width, height, and number of bits per
         //       component explicitly. The number of colour components shall be inferred from the
         //       colour space specified in the dictionary.
 
-        Ok(pdfgen_macros::write_chain! {
-            self.samples.write_with_dict(writer, |writer| {
-                Ok(pdfgen_macros::write_chain! {
-                    Identifier::TYPE.write(writer),
-                    Identifier::X_OBJECT.write(writer),
+        let write_dict = |writer: &mut dyn Write| {
+            Ok(pdfgen_macros::write_chain! {
+                Identifier::TYPE.write(writer),
+                Identifier::X_OBJECT.write(writer),
+                writer.write(constants::NL_MARKER),
+
+                Self::SUBTYPE.write(writer),
+                Self::IMAGE.write(writer),
+                writer.write(constants::NL_MARKER),
+
+                Self::WIDTH.write(writer),
+                crate::write_fmt!(&mut *writer, "{}", self.dict.width),
+                writer.write(constants::NL_MARKER),
+
+                Self::HEIGHT.write(writer),
+                crate::write_fmt!(&mut *writer, "{}", self.dict.height),
+                writer.write(constants::NL_MARKER),
+
+                if let Some(color_space) = &self.dict.color_space {
+                    Self::COLOR_SPACE.write(writer),
+                    color_space.write(writer),
+                    writer.write(constants::NL_MARKER),
+                },
+
+                if self.is_stencil() {
+                    Self::IMAGE_MASK.write(writer),
+                    writer.write(b"true"),
                     writer.write(constants::NL_MARKER),
+                },
+
+                Self::BITS_PER_COMPONENT.write(writer),
+                crate::write_fmt!(&mut *writer, "{}", self.dict.bits_per_comp),
+                writer.write(constants::NL_MARKER),
 
-                    Self::SUBTYPE.write(writer),
-                    Self::IMAGE.write(writer),
+                if let Some(decode) = &self.dict.decode {
+                    Self::DECODE.write(writer),
+                    crate::write_fmt!(
+                        &mut *writer,
+                        "[{}]",
+                        decode.iter().map(f32::to_string).collect::<Vec<_>>().join(" ")
+                    ),
                     writer.write(constants::NL_MARKER),
+                },
 
-                    Self::WIDTH.write(writer),
-                    crate::write_fmt!(&mut *writer, "{}", self.dict.width),
+                if self.dict.interpolate {
+                    Self::INTERPOLATE.write(writer),
+                    writer.write(b"true"),
                     writer.write(constants::NL_MARKER),
+                },
 
-                    Self::HEIGHT.write(writer),
-                    crate::write_fmt!(&mut *writer, "{}", self.dict.height),
+                if let (Some(mask), Some(mask_id)) = (&self.mask, mask_id) {
+                    (if mask.is_stencil() { Self::MASK } else { Self::S_MASK }).write(writer),
+                    mask_id.write_ref(writer),
                     writer.write(constants::NL_MARKER),
+                },
 
-                    Self::COLOR_SPACE.write(writer),
-                    self.dict.color_space.write(writer),
+                if let Some(filter) = &self.dict.filter {
+                    Self::FILTER.write(writer),
+                    filter.write(writer),
                     writer.write(constants::NL_MARKER),
+                },
 
-                    Self::BITS_PER_COMPONENT.write(writer),
-                    crate::write_fmt!(&mut *writer, "{}", self.dict.bits_per_comp),
+                if let Some(params) = self.dict.filter.as_ref().and_then(ImageFilter::params) {
+                    Self::DECODE_PARMS.write(writer),
+                    params.write(writer),
                     writer.write(constants::NL_MARKER),
-                })
-            }),
+                },
+            })
+        };
+
+        let written = match &self.source {
+            Some(source) => {
+                Stream::write_from_reader(writer, (source.open)()?, source.len, write_dict)?
+            }
+            None => self.samples.write_with_dict(writer, write_dict)?,
+        };
+
+        Ok(written + writer.write(constants::NL_MARKER)?)
+    }
+}
+
+impl Object for Image {
+    fn write_def(&self, _writer: &mut dyn Write) -> Result<usize, Error> {
+        panic!("Image does not fully implement the Object trait.")
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        self.write_dict_and_samples(writer, None)
+    }
+}
+
+/// An [`Image`] added to a [`Document`] with [`Document::add_image`], written once together with
+/// its mask and drawn onto any number of pages.
+///
+/// [`Document`]: crate::Document
+/// [`Document::add_image`]: crate::Document::add_image
+#[derive(Debug, Clone)]
+pub(crate) struct SharedImage {
+    /// ID of the image.
+    pub(crate) id: ObjId<Image>,
+
+    /// ID of the mask of the image, if it has one.
+    pub(crate) mask_id: Option<ObjId<Image>>,
+
+    /// The image itself, without its graphics state parameters, which are applied whenever it's
+    /// drawn.
+    pub(crate) image: Image,
+}
+
+impl SharedImage {
+    /// Writes the image, followed by its mask, into the given implementor of [`Write`]. Returns
+    /// the number of bytes written and the offset of the mask, if the image has one.
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<(usize, Vec<usize>), Error> {
+        let mut offsets = Vec::with_capacity(1);
+        let mask_id = self.mask_id.clone().map(ObjId::cast);
+
+        let written = pdfgen_macros::write_chain! {
+            self.image
+                .write_with_mask(writer, &self.id.clone().cast(), mask_id.as_ref()),
             writer.write(constants::NL_MARKER),
-        })
+
+            if let (Some(mask), Some(id)) = (self.image.mask(), &mask_id) {
+                {
+                    offsets.push(written);
+                    mask.write(writer, id)
+                },
+                writer.write(constants::NL_MARKER),
+            },
+        };
+
+        Ok((written, offsets))
     }
 }
 
 pub struct ImageBuilder<const IS_INIT: bool> {
     inner: Image,
+
+    /// zlib compression level of the image samples, compressed when the image is built.
+    compression_level: u8,
 }
 
 impl<const IS_INIT: bool> ImageBuilder<IS_INIT> {
     /// Sets the position of an [`Image`] on a page.
     pub fn at(mut self, pos: Position) -> ImageBuilder<true> {
         self.inner.transform.position = pos;
-        ImageBuilder { inner: self.inner }
+        ImageBuilder {
+            inner: self.inner,
+            compression_level: self.compression_level,
+        }
     }
 
     /// Sets the scaling of the image to the given width and height.
@@ -250,6 +948,135 @@ impl<const IS_INIT: bool> ImageBuilder<IS_INIT> {
         self
     }
 
+    /// Sets the scaling of the image to the size of its samples printed at the given resolution
+    /// in dots per inch, for example an image 300 pixels wide is 1 inch wide at 300 DPI.
+    pub fn at_dpi(mut self, dpi: f32) -> Self {
+        let dpi = f64::from(dpi);
+        let points_per_pixel = 72.0 / dpi;
+        self.inner.transform.scale = Position::new(
+            Unit::from_pt(f64::from(self.inner.dict.width) * points_per_pixel),
+            Unit::from_pt(f64::from(self.inner.dict.height) * points_per_pixel),
+        );
+        self
+    }
+
+    /// Scales the image to fit within the given rectangle according to the [`FitMode`], and
+    /// positions it in the center of the rectangle.
+    pub fn fit_within(mut self, rect: Rectangle, mode: FitMode) -> ImageBuilder<true> {
+        let rect_width = rect.width().into_points();
+        let rect_height = rect.height().into_points();
+        let width = f64::from(self.inner.dict.width);
+        let height = f64::from(self.inner.dict.height);
+
+        let (width, height) = match mode {
+            FitMode::Contain => {
+                let scale = (rect_width / width).min(rect_height / height);
+                (width * scale, height * scale)
+            }
+            FitMode::Cover => {
+                let scale = (rect_width / width).max(rect_height / height);
+                (width * scale, height * scale)
+            }
+            FitMode::Stretch => (rect_width, rect_height),
+        };
+
+        let low_left = rect.low_left();
+        self.inner.transform.scale = Position::new(Unit::from_pt(width), Unit::from_pt(height));
+        self.at(Position::new(
+            Unit::from_pt(low_left.x.into_points() + (rect_width - width) / 2.0),
+            Unit::from_pt(low_left.y.into_points() + (rect_height - height) / 2.0),
+        ))
+    }
+
+    /// Sets the [`ExtGState`] that should be applied when drawing the [`Image`], for example to
+    /// make it semi-transparent.
+    pub fn with_ext_g_state(mut self, ext_g_state: ExtGState) -> Self {
+        self.inner.ext_g_state = Some(ext_g_state);
+        self
+    }
+
+    /// Sets whether the PDF reader should interpolate the image, smoothing its pixels when it's
+    /// drawn larger than its resolution. Defaults to `false`.
+    pub fn with_interpolation(mut self, interpolate: bool) -> Self {
+        self.inner.dict.interpolate = interpolate;
+        self
+    }
+
+    /// Sets the decode array of the image, mapping the samples of each colour component linearly
+    /// from the range of the first to the second of its two values. For example, `[1.0, 0.0]`
+    /// inverts a grayscale image or the painted areas of a stencil mask.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the decode array doesn't have two values for each colour component of the image,
+    /// or two values for a stencil mask.
+    pub fn with_decode(mut self, decode: impl Into<Vec<f32>>) -> Self {
+        let decode = decode.into();
+        let components = self
+            .inner
+            .dict
+            .color_space
+            .as_ref()
+            .map_or(1, ColorSpace::components);
+        assert_eq!(
+            decode.len(),
+            2 * components,
+            "Decode array must have two values for each colour component of the image."
+        );
+
+        self.inner.dict.decode = Some(decode);
+        self
+    }
+
+    /// Sets the color a stencil mask is painted with. Has no effect on images that aren't stencil
+    /// masks, see [`Image::stencil_from_bytes`].
+    pub fn with_stencil_color(mut self, color: Color) -> Self {
+        if self.inner.is_stencil() {
+            self.inner.stencil_color = Some(color);
+        }
+        self
+    }
+
+    /// Sets the stencil mask of the image: the image is only shown where the mask is painted,
+    /// which are the dark pixels of the mask's source image. This replaces the soft mask of an
+    /// image with an alpha channel. The mask is stretched over the image if their sizes differ.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the given mask isn't a stencil mask created with [`Image::stencil_from_bytes`],
+    /// or if the image itself is a stencil mask.
+    pub fn with_mask(mut self, mask: Image) -> Self {
+        assert!(mask.is_stencil(), "Only stencil masks can mask an image.");
+        assert!(!self.inner.is_stencil(), "Stencil masks can't be masked.");
+
+        self.inner.mask = Some(Box::new(mask));
+        self
+    }
+
+    /// Sets the zlib compression level of the image samples, from 0 to
+    /// [`Image::MAX_COMPRESSION_LEVEL`]. Higher levels produce smaller files but take longer to
+    /// compress, and level 0 writes the samples uncompressed.
+    ///
+    /// Defaults to [`Image::DEFAULT_COMPRESSION_LEVEL`], or 0 for JPEG sources.
+    pub fn with_compression_level(mut self, level: u8) -> Self {
+        self.compression_level = level.min(Image::MAX_COMPRESSION_LEVEL);
+        self
+    }
+
+    /// Mirrors the image horizontally, swapping its left and right sides, while keeping it at the
+    /// same position and size.
+    pub fn flipped_horizontal(mut self) -> Self {
+        self.inner.transform.flip_horizontal = true;
+        self
+    }
+
+    /// Mirrors the image vertically, swapping its top and bottom sides, while keeping it at the
+    /// same position and size.
+    pub fn flipped_vertical(mut self) -> Self {
+        self.inner.transform.flip_vertical = true;
+        self
+    }
+
     /// This is not yet implemented and is a no-op for now.
     pub fn rotated(self, _degree: usize) -> Self {
         // TODO: implement rotation
@@ -258,27 +1085,37 @@ impl<const IS_INIT: bool> ImageBuilder<IS_INIT> {
 }
 
 impl ImageBuilder<true> {
-    pub fn build(self) -> Image {
+    pub fn build(mut self) -> Image {
+        self.inner.compress(self.compression_level);
         self.inner
     }
 }
 
 #[cfg(test)]
 mod tests {
+    #[cfg(feature = "image-decoding")]
     use std::path::PathBuf;
 
-    use crate::{IdManager, types::hierarchy::primitives::rectangle::Position};
+    #[cfg(feature = "image-decoding")]
+    use image::{DynamicImage, ImageBuffer};
 
-    use super::Image;
+    use crate::{
+        IdManager,
+        types::hierarchy::primitives::rectangle::{Position, Rectangle},
+    };
 
+    use super::{ColorSpace, FitMode, Image, ImageBuilder, ImageErr, ImageFilter, JpegErr};
+
+    #[cfg(feature = "image-decoding")]
     #[test]
     fn sample_image() {
         let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg");
-        let img_file = std::fs::File::open(dbg!(path)).unwrap();
+        let img_file = std::fs::File::open(path).unwrap();
 
         let mut id_mngr = IdManager::new();
 
         let img = Image::from_reader(img_file)
+            .unwrap()
             .scaled(Position::from_mm(100., 100.))
             .at(Position::from_mm(10.0, 42.0))
             .build();
@@ -290,4 +1127,582 @@ mod tests {
 
         insta::assert_snapshot!(output);
     }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn transparent_image() {
+        // NOTE: printable samples keep the snapshot readable.
+        let pixels = b"RGB~rgb@XYZ0xyz!".to_vec();
+        let decoded = DynamicImage::ImageRgba8(ImageBuffer::from_raw(2, 2, pixels).unwrap());
+        let img = Image::from_decoded(&decoded);
+
+        let mut id_mngr = IdManager::new();
+        let (id, soft_mask_id) = (id_mngr.create_id(), id_mngr.create_id());
+
+        let mut writer = Vec::default();
+        img.write_with_mask(&mut writer, &id, Some(&soft_mask_id))
+            .unwrap();
+        img.soft_mask()
+            .unwrap()
+            .write(&mut writer, &soft_mask_id)
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 2
+        /ColorSpace /DeviceRGB 
+        /BitsPerComponent 8
+        /SMask 2 0 R
+        /Length 12 >>
+        stream
+        RGBrgbXYZxyz
+        endstream
+        endobj
+        2 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 2
+        /ColorSpace /DeviceGray 
+        /BitsPerComponent 8
+        /Length 4 >>
+        stream
+        ~@0!
+        endstream
+        endobj
+        ");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn compressed_image() {
+        let pixels = b"RGB~rgb@XYZ0xyz!".to_vec();
+        let decoded = DynamicImage::ImageRgba8(ImageBuffer::from_raw(2, 2, pixels).unwrap());
+        let mut img = Image::from_decoded(&decoded);
+        img.compress(Image::DEFAULT_COMPRESSION_LEVEL);
+
+        let mut id_mngr = IdManager::new();
+        let (id, soft_mask_id) = (id_mngr.create_id(), id_mngr.create_id());
+
+        let mut writer = Vec::default();
+        img.write_with_mask(&mut writer, &id, Some(&soft_mask_id))
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+        assert!(output.contains("/SMask 2 0 R\n/Filter /FlateDecode \n/Length"));
+
+        let decompress = |image: &Image| {
+            miniz_oxide::inflate::decompress_to_vec_zlib(image.samples.as_bytes()).unwrap()
+        };
+        assert_eq!(decompress(&img), b"RGBrgbXYZxyz");
+        assert_eq!(decompress(img.soft_mask().unwrap()), b"~@0!");
+        assert_eq!(
+            img.soft_mask().unwrap().dict.filter,
+            Some(ImageFilter::Flate)
+        );
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn uncompressed_image() {
+        let pixels = b"RGBrgb".to_vec();
+        let decoded = DynamicImage::ImageRgb8(ImageBuffer::from_raw(2, 1, pixels).unwrap());
+        let mut img = Image::from_decoded(&decoded);
+        img.compress(0);
+
+        assert_eq!(img.dict.filter, None);
+        assert_eq!(img.samples.as_bytes(), b"RGBrgb");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn grayscale_image() {
+        let pixels = b"GrAy".to_vec();
+        let decoded = DynamicImage::ImageLuma8(ImageBuffer::from_raw(2, 2, pixels).unwrap());
+        let img = Image::from_decoded(&decoded);
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 2
+        /ColorSpace /DeviceGray 
+        /BitsPerComponent 8
+        /Length 4 >>
+        stream
+        GrAy
+        endstream
+        endobj
+        ");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn grayscale_image_with_alpha() {
+        let pixels = b"G~r@A0y!".to_vec();
+        let decoded = DynamicImage::ImageLumaA8(ImageBuffer::from_raw(2, 2, pixels).unwrap());
+        let img = Image::from_decoded(&decoded);
+
+        assert_eq!(img.dict.color_space, Some(ColorSpace::DeviceGray));
+        assert_eq!(img.samples.as_bytes(), b"GrAy");
+        assert_eq!(img.soft_mask().unwrap().samples.as_bytes(), b"~@0!");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn stencil_mask() {
+        // NOTE: light pixels are masked out with bit 1, packed into "A@" and "Z@" for each row
+        #[rustfmt::skip]
+        let pixels = vec![
+            0, 255, 0, 0, 0, 0, 0, 255, 0, 255,
+            0, 255, 0, 255, 255, 0, 255, 0, 0, 255,
+        ];
+        let decoded = DynamicImage::ImageLuma8(ImageBuffer::from_raw(10, 2, pixels).unwrap());
+        let img = Image::stencil_from_decoded(&decoded);
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 10
+        /Height 2
+        /ImageMask true
+        /BitsPerComponent 1
+        /Length 4 >>
+        stream
+        A@Z@
+        endstream
+        endobj
+        ");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn masked_image() {
+        let mask = DynamicImage::ImageLuma8(ImageBuffer::from_raw(2, 1, vec![0, 255]).unwrap());
+        let pixels = b"RGBrgb".to_vec();
+        let decoded = DynamicImage::ImageRgb8(ImageBuffer::from_raw(2, 1, pixels).unwrap());
+
+        let img = ImageBuilder::<false> {
+            inner: Image::from_decoded(&decoded),
+            compression_level: 0,
+        }
+        .with_mask(Image::stencil_from_decoded(&mask))
+        .at(Position::from_mm(0.0, 0.0))
+        .build();
+
+        let mut id_mngr = IdManager::new();
+        let (id, mask_id) = (id_mngr.create_id(), id_mngr.create_id());
+
+        let mut writer = Vec::default();
+        img.write_with_mask(&mut writer, &id, Some(&mask_id))
+            .unwrap();
+        img.mask().unwrap().write(&mut writer, &mask_id).unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 1
+        /ColorSpace /DeviceRGB 
+        /BitsPerComponent 8
+        /Mask 2 0 R
+        /Length 6 >>
+        stream
+        RGBrgb
+        endstream
+        endobj
+        2 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 1
+        /ImageMask true
+        /BitsPerComponent 1
+        /Length 1 >>
+        stream
+        @
+        endstream
+        endobj
+        ");
+        assert!(img.soft_mask().is_none());
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    #[should_panic(expected = "Only stencil masks can mask an image.")]
+    fn mask_must_be_stencil() {
+        let pixels = b"RGBrgb".to_vec();
+        let decoded = DynamicImage::ImageRgb8(ImageBuffer::from_raw(2, 1, pixels).unwrap());
+
+        let _ = ImageBuilder::<false> {
+            inner: Image::from_decoded(&decoded),
+            compression_level: 0,
+        }
+        .with_mask(Image::from_decoded(&decoded));
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn image_16_bit() {
+        // NOTE: big-endian samples of "RrGgBb" and "XxYyZz", with alpha of "A~" and "a@"
+        let pixels = vec![
+            0x5272, 0x4767, 0x4262, 0x417E, 0x5878, 0x5979, 0x5A7A, 0x6140,
+        ];
+        let decoded = DynamicImage::ImageRgba16(ImageBuffer::from_raw(2, 1, pixels).unwrap());
+        let img = Image::from_decoded(&decoded);
+
+        let mut id_mngr = IdManager::new();
+        let (id, soft_mask_id) = (id_mngr.create_id(), id_mngr.create_id());
+
+        let mut writer = Vec::default();
+        img.write_with_mask(&mut writer, &id, Some(&soft_mask_id))
+            .unwrap();
+        img.soft_mask()
+            .unwrap()
+            .write(&mut writer, &soft_mask_id)
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 1
+        /ColorSpace /DeviceRGB 
+        /BitsPerComponent 16
+        /SMask 2 0 R
+        /Length 12 >>
+        stream
+        RrGgBbXxYyZz
+        endstream
+        endobj
+        2 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 1
+        /ColorSpace /DeviceGray 
+        /BitsPerComponent 16
+        /Length 4 >>
+        stream
+        A~a@
+        endstream
+        endobj
+        ");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn grayscale_image_16_bit() {
+        let pixels = vec![0x4772, 0x4179];
+        let decoded = DynamicImage::ImageLuma16(ImageBuffer::from_raw(2, 1, pixels).unwrap());
+        let img = Image::from_decoded(&decoded);
+
+        assert_eq!(img.dict.color_space, Some(ColorSpace::DeviceGray));
+        assert_eq!(img.dict.bits_per_comp, 16);
+        assert_eq!(img.samples.as_bytes(), b"GrAy");
+        assert!(img.mask().is_none());
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn opaque_image_has_no_soft_mask() {
+        let pixels = vec![255, 0, 0, 0, 255, 0];
+        let decoded = DynamicImage::ImageRgb8(ImageBuffer::from_raw(2, 1, pixels).unwrap());
+
+        assert!(Image::from_decoded(&decoded).soft_mask().is_none());
+    }
+
+    #[test]
+    fn raw_image() {
+        let img = Image::from_raw(b"RGBrgb".to_vec(), 2, 1, ColorSpace::DeviceRgb)
+            .unwrap()
+            .with_compression_level(0)
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 1
+        /ColorSpace /DeviceRGB 
+        /BitsPerComponent 8
+        /Length 6 >>
+        stream
+        RGBrgb
+        endstream
+        endobj
+        ");
+    }
+
+    #[cfg(feature = "image-decoding")]
+    #[test]
+    fn unknown_image_format() {
+        assert!(matches!(
+            Image::from_bytes(b"not an image".to_vec()),
+            Err(ImageErr::UnknownFormat)
+        ));
+    }
+
+    #[test]
+    fn raw_image_with_wrong_sample_count() {
+        let result = Image::from_raw(b"RGBrg".to_vec(), 2, 1, ColorSpace::DeviceRgb);
+
+        assert!(matches!(
+            result,
+            Err(ImageErr::SampleCount {
+                expected: 6,
+                actual: 5
+            })
+        ));
+    }
+
+    #[test]
+    fn jpeg_image() {
+        #[rustfmt::skip]
+        let bytes = [
+            0xFF, 0xD8,
+            0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x00, 0x03, 0x00, 0x04, 0x01, 0x01, 0x11, 0x00,
+            0xFF, 0xD9,
+        ];
+        let img = Image::from_jpeg(bytes)
+            .unwrap()
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+        let (dict, _) = output.split_once("stream").unwrap();
+
+        insta::assert_snapshot!(dict, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 4
+        /Height 3
+        /ColorSpace /DeviceGray 
+        /BitsPerComponent 8
+        /Filter /DCTDecode 
+        /Length 17 >>
+        ");
+        assert!(writer.windows(bytes.len()).any(|window| window == bytes));
+    }
+
+    #[test]
+    fn invalid_jpeg_image() {
+        assert!(matches!(
+            Image::from_jpeg(b"GIF89a".to_vec()),
+            Err(ImageErr::Jpeg(JpegErr::InvalidJpeg))
+        ));
+    }
+
+    /// Returns the position and size of the image in points.
+    fn placement(img: &Image) -> [f64; 4] {
+        let transform = img.transform();
+        [
+            transform.position.x.into_points(),
+            transform.position.y.into_points(),
+            transform.scale.x.into_points(),
+            transform.scale.y.into_points(),
+        ]
+    }
+
+    #[test]
+    fn image_at_dpi() {
+        let img = Image::from_raw(vec![0; 600 * 150], 600, 150, ColorSpace::DeviceGray)
+            .unwrap()
+            .at_dpi(300.0)
+            .at(Position::from_units(10.0, 20.0))
+            .build();
+
+        assert_eq!(placement(&img), [10.0, 20.0, 144.0, 36.0]);
+    }
+
+    #[test]
+    fn image_fit_within() {
+        let rect = Rectangle::from_units(100.0, 100.0, 300.0, 200.0);
+        let fitted = |mode| {
+            let img = Image::from_raw(vec![0; 4 * 4], 4, 4, ColorSpace::DeviceGray)
+                .unwrap()
+                .fit_within(rect, mode)
+                .build();
+            placement(&img)
+        };
+
+        assert_eq!(fitted(FitMode::Contain), [150.0, 100.0, 100.0, 100.0]);
+        assert_eq!(fitted(FitMode::Cover), [100.0, 50.0, 200.0, 200.0]);
+        assert_eq!(fitted(FitMode::Stretch), [100.0, 100.0, 200.0, 100.0]);
+    }
+
+    #[test]
+    fn image_from_raw_source() {
+        let img = Image::from_raw_source(
+            || Ok(std::io::Cursor::new(b"RGBrgb")),
+            2,
+            1,
+            ColorSpace::DeviceRgb,
+        )
+        .at(Position::from_mm(0.0, 0.0))
+        .build();
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 1
+        /ColorSpace /DeviceRGB 
+        /BitsPerComponent 8
+        /Length 6 >>
+        stream
+        RGBrgb
+        endstream
+        endobj
+        ");
+        assert!(img.samples.is_empty());
+    }
+
+    #[test]
+    fn image_from_short_raw_source() {
+        let img = Image::from_raw_source(|| Ok(&b"RGB"[..]), 2, 1, ColorSpace::DeviceRgb)
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        let result = img.write(&mut Vec::default(), &IdManager::new().create_id());
+        assert_eq!(
+            result.map_err(|err| err.kind()),
+            Err(std::io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn interpolated_image_with_decode() {
+        let img = Image::from_raw(b"Gray".to_vec(), 2, 2, ColorSpace::DeviceGray)
+            .unwrap()
+            .with_decode([1.0, 0.0])
+            .with_interpolation(true)
+            .with_compression_level(0)
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        let mut writer = Vec::default();
+        img.write(&mut writer, &IdManager::new().create_id())
+            .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 2
+        /Height 2
+        /ColorSpace /DeviceGray 
+        /BitsPerComponent 8
+        /Decode [1 0]
+        /Interpolate true
+        /Length 4 >>
+        stream
+        Gray
+        endstream
+        endobj
+        ");
+    }
+
+    #[test]
+    #[should_panic(expected = "Decode array must have two values for each colour component")]
+    fn decode_must_match_components() {
+        let _ = Image::from_raw(b"RGBrgb".to_vec(), 2, 1, ColorSpace::DeviceRgb)
+            .unwrap()
+            .with_decode([0.0, 1.0]);
+    }
+
+    #[test]
+    fn flipped_image_matrix() {
+        let matrix = |builder: ImageBuilder<false>| {
+            builder
+                .scaled(Position::from_units(40.0, 20.0))
+                .at(Position::from_units(10.0, 30.0))
+                .build()
+                .transform()
+                .to_matrix_operator()
+        };
+        let image = || Image::from_raw(b"Gray".to_vec(), 2, 2, ColorSpace::DeviceGray).unwrap();
+
+        assert_eq!(matrix(image()), "40 0 0 20 10 30 cm");
+        assert_eq!(matrix(image().flipped_horizontal()), "-40 0 0 20 50 30 cm");
+        assert_eq!(matrix(image().flipped_vertical()), "40 0 0 -20 10 50 cm");
+        assert_eq!(
+            matrix(image().flipped_horizontal().flipped_vertical()),
+            "-40 0 0 -20 50 50 cm"
+        );
+    }
+
+    #[test]
+    fn image_thumbnail() {
+        #[rustfmt::skip]
+        let samples = [
+            0, 10, 20, 30, 40, 50, 60, 70,
+            2, 12, 22, 32, 42, 52, 62, 72,
+            4, 14, 24, 34, 44, 54, 64, 74,
+            6, 16, 26, 36, 46, 56, 66, 76,
+        ];
+        let img = Image::from_raw(samples, 8, 4, ColorSpace::DeviceGray)
+            .unwrap()
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        let thumbnail = img.thumbnail(4).unwrap();
+        assert_eq!((thumbnail.dict.width, thumbnail.dict.height), (4, 2));
+        assert_eq!(thumbnail.dict.filter, Some(ImageFilter::Flate));
+
+        let samples =
+            miniz_oxide::inflate::decompress_to_vec_zlib(thumbnail.samples.as_bytes()).unwrap();
+        assert_eq!(samples, [6, 26, 46, 66, 10, 30, 50, 70]);
+
+        // images that already fit are copied at their size
+        let copy = img.thumbnail(100).unwrap();
+        assert_eq!((copy.dict.width, copy.dict.height), (8, 4));
+    }
+
+    #[test]
+    fn no_cmyk_thumbnail() {
+        let img = Image::from_raw(b"CMYK".to_vec(), 1, 1, ColorSpace::DeviceCmyk)
+            .unwrap()
+            .at(Position::from_mm(0.0, 0.0))
+            .build();
+
+        assert!(img.thumbnail(1).is_none());
+    }
 }
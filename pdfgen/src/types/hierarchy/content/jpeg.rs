@@ -0,0 +1,145 @@
+//! Baseline JPEG image data, embedded in PDF without decoding it.
+
+use super::color::ColorSpace;
+
+/// Errors that can occur when reading the header of JPEG image data.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum JpegErr {
+    /// Indicates that the data is not a valid JPEG file.
+    #[error("Data is not a valid JPEG file.")]
+    InvalidJpeg,
+
+    /// Indicates that the JPEG image has a number of colour components other than 1 (grayscale)
+    /// or 3 (RGB).
+    #[error("JPEG images with {0} colour components are not supported.")]
+    UnsupportedComponents(u8),
+
+    /// Indicates that the samples of the JPEG image don't have 8 bits.
+    #[error("JPEG images with {0} bits per sample are not supported.")]
+    UnsupportedPrecision(u8),
+}
+
+/// Dimensions and colour space of a JPEG image, read from its frame header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct JpegHeader {
+    /// Width of the image in pixels.
+    pub(crate) width: u32,
+
+    /// Height of the image in pixels.
+    pub(crate) height: u32,
+
+    /// Colour space of the decoded samples.
+    pub(crate) color_space: ColorSpace,
+}
+
+/// Reads the frame header of the given JPEG data.
+pub(crate) fn read_header(bytes: &[u8]) -> Result<JpegHeader, JpegErr> {
+    const START_OF_IMAGE: u8 = 0xD8;
+
+    if bytes.get(..2) != Some(&[0xFF, START_OF_IMAGE]) {
+        return Err(JpegErr::InvalidJpeg);
+    }
+
+    let mut pos = 2;
+    loop {
+        // NOTE: markers may be preceded by any number of 0xFF fill bytes.
+        while bytes.get(pos..pos + 2) == Some(&[0xFF, 0xFF]) {
+            pos += 1;
+        }
+
+        let (Some(0xFF), Some(&marker)) = (bytes.get(pos), bytes.get(pos + 1)) else {
+            return Err(JpegErr::InvalidJpeg);
+        };
+        pos += 2;
+
+        // markers without a segment
+        if matches!(marker, 0x01 | 0xD0..=0xD7) {
+            continue;
+        }
+
+        let segment = bytes.get(pos..pos + 2).ok_or(JpegErr::InvalidJpeg)?;
+        let len = usize::from(u16::from_be_bytes([segment[0], segment[1]]));
+
+        // NOTE: start of frame markers, excluding the ones for Huffman table definitions (C4),
+        //       JPEG extensions (C8) and arithmetic coding conditioning (CC)
+        if matches!(marker, 0xC0..=0xCF) && !matches!(marker, 0xC4 | 0xC8 | 0xCC) {
+            let frame = bytes.get(pos + 2..pos + 8).ok_or(JpegErr::InvalidJpeg)?;
+            let precision = frame[0];
+            let height = u16::from_be_bytes([frame[1], frame[2]]);
+            let width = u16::from_be_bytes([frame[3], frame[4]]);
+
+            let color_space = match frame[5] {
+                1 => ColorSpace::DeviceGray,
+                3 => ColorSpace::DeviceRgb,
+                components => return Err(JpegErr::UnsupportedComponents(components)),
+            };
+
+            if precision != 8 {
+                return Err(JpegErr::UnsupportedPrecision(precision));
+            }
+
+            return Ok(JpegHeader {
+                width: width.into(),
+                height: height.into(),
+                color_space,
+            });
+        }
+
+        pos += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::hierarchy::content::color::ColorSpace;
+
+    use super::{JpegErr, JpegHeader, read_header};
+
+    /// Creates the start of a JPEG file with an APP0 segment and the given frame header values.
+    fn jpeg(precision: u8, height: u16, width: u16, components: u8) -> Vec<u8> {
+        let mut bytes = vec![
+            0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x04, b'J', b'F', 0xFF, 0xFF, 0xC0,
+        ];
+        bytes.extend((8 + 3 * u16::from(components)).to_be_bytes());
+        bytes.push(precision);
+        bytes.extend(height.to_be_bytes());
+        bytes.extend(width.to_be_bytes());
+        bytes.push(components);
+        for id in 1..=components {
+            bytes.extend([id, 0x11, 0]);
+        }
+        bytes.extend([0xFF, 0xD9]);
+
+        bytes
+    }
+
+    #[test]
+    fn jpeg_header() {
+        assert_eq!(
+            read_header(&jpeg(8, 480, 640, 3)),
+            Ok(JpegHeader {
+                width: 640,
+                height: 480,
+                color_space: ColorSpace::DeviceRgb,
+            })
+        );
+        assert_eq!(
+            read_header(&jpeg(8, 1, 2, 1)).map(|header| header.color_space),
+            Ok(ColorSpace::DeviceGray)
+        );
+    }
+
+    #[test]
+    fn unsupported_jpeg() {
+        assert_eq!(
+            read_header(&jpeg(8, 1, 1, 4)),
+            Err(JpegErr::UnsupportedComponents(4))
+        );
+        assert_eq!(
+            read_header(&jpeg(12, 1, 1, 1)),
+            Err(JpegErr::UnsupportedPrecision(12))
+        );
+        assert_eq!(read_header(b"\x89PNG"), Err(JpegErr::InvalidJpeg));
+        assert_eq!(read_header(&[0xFF, 0xD8]), Err(JpegErr::InvalidJpeg));
+    }
+}
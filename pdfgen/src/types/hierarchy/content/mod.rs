@@ -4,7 +4,22 @@
 mod content_stream;
 pub use content_stream::*;
 
+pub mod bidi;
+pub mod ccitt;
 pub mod color;
+pub mod graphics_state;
 pub mod image;
+pub mod jpeg;
+#[cfg(feature = "outlines")]
+pub mod outline;
+pub(crate) mod page_form;
+pub mod path;
+pub mod print_marks;
+pub mod recording;
+#[cfg(feature = "composite-fonts")]
+pub mod shaped_run;
 pub mod stream;
 pub mod text;
+pub mod text_box;
+pub mod text_run;
+pub mod watermark;
@@ -0,0 +1,204 @@
+//! Conversion of text into vector [`Path`] outlines, using the glyphs of a TrueType or OpenType
+//! font. Text converted this way is drawn as filled shapes, so the font itself is not needed in
+//! the generated PDF.
+
+use ttf_parser::{Face, FaceParsingError, OutlineBuilder};
+
+use crate::types::hierarchy::primitives::{rectangle::Position, unit::Unit};
+
+use super::path::Path;
+
+/// Possible errors that might be returned when converting text into [`Path`] outlines.
+#[derive(Debug, thiserror::Error)]
+pub enum OutlineErr {
+    /// Indicates that the provided font data could not be parsed.
+    #[error("Font data could not be parsed: {0}")]
+    InvalidFont(FaceParsingError),
+
+    /// Indicates that the font does not contain a glyph for the given character.
+    #[error("Font does not contain a glyph for character '{0}'.")]
+    MissingGlyph(char),
+}
+
+/// A TrueType or OpenType font used to convert text into filled [`Path`] outlines, e.g. for
+/// drawing logos and wordmarks as vector shapes.
+///
+/// # Example
+///
+/// ```no_run
+/// # use pdfgen::types::hierarchy::content::outline::OutlineFont;
+/// # use pdfgen::types::hierarchy::primitives::rectangle::Position;
+/// let font_data = std::fs::read("font.ttf").unwrap();
+/// let font = OutlineFont::parse(&font_data).unwrap();
+/// let wordmark = font
+///     .text_to_path("pediferrous", 24.0, Position::from_mm(20.0, 20.0))
+///     .unwrap();
+/// ```
+pub struct OutlineFont<'a> {
+    /// The parsed font face.
+    face: Face<'a>,
+}
+
+impl<'a> OutlineFont<'a> {
+    /// Parses the first font face in the given TrueType or OpenType font data.
+    pub fn parse(data: &'a [u8]) -> Result<Self, OutlineErr> {
+        let face = Face::parse(data, 0).map_err(OutlineErr::InvalidFont)?;
+        Ok(Self { face })
+    }
+
+    /// Converts the given text into a [`Path`] made out of the outlines of its glyphs, with the
+    /// baseline of the first glyph starting at `origin`. The `size` is the font size in user space
+    /// units.
+    pub fn text_to_path(
+        &self,
+        text: &str,
+        size: f32,
+        origin: Position,
+    ) -> Result<Path, OutlineErr> {
+        let scale = f64::from(size) / f64::from(self.face.units_per_em());
+        let mut builder = PathBuilder::new(scale, origin);
+
+        for ch in text.chars() {
+            let glyph = self
+                .face
+                .glyph_index(ch)
+                .ok_or(OutlineErr::MissingGlyph(ch))?;
+
+            // glyphs without an outline, such as whitespace, only advance the pen
+            self.face.outline_glyph(glyph, &mut builder);
+
+            let advance = self.face.glyph_hor_advance(glyph).unwrap_or_default();
+            builder.advance(advance);
+        }
+
+        Ok(builder.path)
+    }
+}
+
+/// Collects glyph outlines emitted by the font parser into a [`Path`], scaling them from font
+/// units into user space units.
+struct PathBuilder {
+    /// The path built so far.
+    path: Path,
+
+    /// Scale from font units into user space units.
+    scale: f64,
+
+    /// Origin of the text in user space units.
+    origin: (f64, f64),
+
+    /// Horizontal position of the current glyph relative to the origin, in font units.
+    pen_x: f64,
+
+    /// The current point in font units, relative to the current glyph.
+    current: (f32, f32),
+}
+
+impl PathBuilder {
+    /// Creates a new `PathBuilder` with the given scale and origin of the text.
+    fn new(scale: f64, origin: Position) -> Self {
+        Self {
+            path: Path::new(),
+            scale,
+            origin: (origin.x.into_points(), origin.y.into_points()),
+            pen_x: 0.0,
+            current: (0.0, 0.0),
+        }
+    }
+
+    /// Moves the pen by the given advance width of a glyph, in font units.
+    fn advance(&mut self, advance: u16) {
+        self.pen_x += f64::from(advance);
+    }
+
+    /// Converts a point in font units, relative to the current glyph, into a [`Position`].
+    fn position(&self, x: f32, y: f32) -> Position {
+        Position::new(
+            Unit::from_pt(self.origin.0 + (self.pen_x + f64::from(x)) * self.scale),
+            Unit::from_pt(self.origin.1 + f64::from(y) * self.scale),
+        )
+    }
+}
+
+impl OutlineBuilder for PathBuilder {
+    fn move_to(&mut self, x: f32, y: f32) {
+        let pos = self.position(x, y);
+        self.path = std::mem::take(&mut self.path).move_to(pos);
+        self.current = (x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let pos = self.position(x, y);
+        self.path = std::mem::take(&mut self.path).line_to(pos);
+        self.current = (x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        // PDF has no quadratic curves, so they are elevated into equivalent cubic curves
+        let (x0, y0) = self.current;
+        let ctrl1 = (x0 + 2.0 / 3.0 * (x1 - x0), y0 + 2.0 / 3.0 * (y1 - y0));
+        let ctrl2 = (x + 2.0 / 3.0 * (x1 - x), y + 2.0 / 3.0 * (y1 - y));
+
+        self.curve_to(ctrl1.0, ctrl1.1, ctrl2.0, ctrl2.1, x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let ctrl1 = self.position(x1, y1);
+        let ctrl2 = self.position(x2, y2);
+        let end = self.position(x, y);
+        self.path = std::mem::take(&mut self.path).curve_to(ctrl1, ctrl2, end);
+        self.current = (x, y);
+    }
+
+    fn close(&mut self) {
+        self.path = std::mem::take(&mut self.path).close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ttf_parser::OutlineBuilder;
+
+    use crate::types::hierarchy::primitives::rectangle::Position;
+
+    use super::{OutlineErr, OutlineFont, PathBuilder};
+
+    #[test]
+    fn invalid_font() {
+        assert!(matches!(
+            OutlineFont::parse(b"not a font"),
+            Err(OutlineErr::InvalidFont(_))
+        ));
+    }
+
+    #[test]
+    fn scaled_glyphs() {
+        // 1000 font units per em at font size 10
+        let mut builder = PathBuilder::new(0.01, Position::from_units(100.0, 50.0));
+
+        builder.move_to(0.0, 0.0);
+        builder.line_to(500.0, 0.0);
+        builder.quad_to(500.0, 600.0, 0.0, 600.0);
+        builder.close();
+        builder.advance(600);
+
+        builder.move_to(0.0, 0.0);
+        builder.curve_to(0.0, 300.0, 300.0, 300.0, 300.0, 0.0);
+        builder.close();
+
+        let mut writer = Vec::new();
+        builder.path.write_fill(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        100 50 m
+        105 50 l
+        105 54 103.333336 56 100 56 c
+        h
+        106 50 m
+        106 53 109 53 109 50 c
+        h
+        f
+        ");
+    }
+}
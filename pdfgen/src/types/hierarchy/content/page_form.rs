@@ -0,0 +1,144 @@
+//! Implementation of page forms, form XObjects holding the content of a [`Page`], so that it can
+//! be drawn onto the pages of another document, e.g. when imposing pages onto sheets.
+//!
+//! [`Page`]: crate::types::hierarchy::page::Page
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    FontId, IdManager, ObjId,
+    types::{
+        constants,
+        hierarchy::primitives::{
+            identifier::Identifier,
+            rectangle::Rectangle,
+            resources::{Renderable, Resources},
+        },
+    },
+};
+
+use super::stream::Stream;
+
+/// A form XObject holding the content of a [`Page`], together with the resources it uses.
+///
+/// [`Page`]: crate::types::hierarchy::page::Page
+#[derive(Debug, Clone)]
+pub(crate) struct PageForm {
+    /// ID of this `PageForm`.
+    id: ObjId<Self>,
+
+    /// Bounding box of the form in form space, which is the media box of the page.
+    b_box: Rectangle,
+
+    /// Resources used by the content of the page.
+    resources: Resources,
+
+    /// Content of all layers of the page.
+    content: Vec<u8>,
+
+    /// Texts drawn on the page, together with the fonts they are drawn with.
+    #[cfg_attr(not(feature = "composite-fonts"), allow(dead_code))]
+    texts: Vec<(FontId, String)>,
+}
+
+impl PageForm {
+    const_identifiers! {
+        SUBTYPE,
+        FORM,
+        B_BOX: b"BBox",
+        RESOURCES,
+    }
+
+    /// Creates a new `PageForm` with the given [`ObjId`], bounding box, resources, content and
+    /// texts of a page.
+    pub(crate) fn new(
+        id: ObjId<Self>,
+        b_box: Rectangle,
+        resources: Resources,
+        content: Vec<u8>,
+        texts: Vec<(FontId, String)>,
+    ) -> Self {
+        Self {
+            id,
+            b_box,
+            resources,
+            content,
+            texts,
+        }
+    }
+
+    /// Returns the [`ObjId`] of this `PageForm`.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Returns the bounding box of this `PageForm`.
+    pub(crate) fn b_box(&self) -> Rectangle {
+        self.b_box
+    }
+
+    /// Returns the texts drawn on the page of this `PageForm`, together with their fonts.
+    #[cfg(feature = "composite-fonts")]
+    pub(crate) fn texts(&self) -> &[(FontId, String)] {
+        &self.texts
+    }
+
+    /// Writes this `PageForm`, followed by the objects of the resources it owns, into the given
+    /// implementor of [`Write`]. Returns the number of bytes written and the offsets of all
+    /// objects following the form XObject.
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        id_manager: &mut IdManager,
+    ) -> Result<(usize, Vec<usize>), Error> {
+        let mut offsets = Vec::with_capacity(self.resources.entries.len());
+        let renderable_resources = self.resources.renderables(id_manager);
+        let form = Stream::with_bytes(self.content.as_slice());
+
+        let written = pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            form.write_with_dict(writer, |writer| {
+                Ok(pdfgen_macros::write_chain! {
+                    Identifier::TYPE.write(writer),
+                    Identifier::X_OBJECT.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::SUBTYPE.write(writer),
+                    Self::FORM.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::B_BOX.write(writer),
+                    self.b_box.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::RESOURCES.write(writer),
+                    self.resources.write_dict(writer, &renderable_resources, |_| Ok(0)),
+                    writer.write(constants::NL_MARKER),
+                })
+            }),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+
+            for renderable in renderable_resources.iter() {
+                {
+                    offsets.push(written);
+                    renderable.write_def(writer)
+                }
+            },
+            for (mask, id) in renderable_resources.iter().filter_map(Renderable::mask) {
+                {
+                    offsets.push(written);
+                    mask.write(writer, id)
+                }
+            },
+        };
+
+        Ok((written, offsets))
+    }
+}
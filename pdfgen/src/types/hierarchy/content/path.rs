@@ -0,0 +1,336 @@
+//! Implementation of PDF paths, made out of straight lines, rectangles and cubic Bézier curves.
+
+use std::io::{self, Write};
+
+use crate::types::{
+    constants,
+    hierarchy::primitives::{
+        rectangle::{Position, Rectangle},
+        unit::Unit,
+    },
+};
+
+/// The rule used to determine which points lie inside of a [`Path`], when filling it or using it
+/// as a clipping path.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FillRule {
+    /// A point is inside of the path if the number of times the path crosses a ray drawn from the
+    /// point in any direction, counting the direction of crossings, is not zero.
+    #[default]
+    NonZero,
+
+    /// A point is inside of the path if the number of times the path crosses a ray drawn from the
+    /// point in any direction is odd.
+    EvenOdd,
+}
+
+/// A single segment of a [`Path`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Segment {
+    /// Begins a new subpath by moving the current point (`m` operator).
+    MoveTo(Position),
+
+    /// Appends a straight line from the current point (`l` operator).
+    LineTo(Position),
+
+    /// Appends a cubic Bézier curve from the current point, with two control points and the end
+    /// point (`c` operator).
+    CurveTo(Position, Position, Position),
+
+    /// Appends a rectangle as a complete subpath, with the lower left corner, width and height
+    /// (`re` operator).
+    Rect(Position, Unit, Unit),
+
+    /// Closes the current subpath with a straight line to the starting point (`h` operator).
+    Close,
+}
+
+impl Segment {
+    /// Writes the path construction operator of this `Segment` into the given implementor of
+    /// [`Write`].
+    fn write(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        match self {
+            Segment::MoveTo(Position { x, y }) => crate::write_fmt!(&mut *writer, "{x} {y} m"),
+            Segment::LineTo(Position { x, y }) => crate::write_fmt!(&mut *writer, "{x} {y} l"),
+            Segment::CurveTo(c1, c2, end) => crate::write_fmt!(
+                &mut *writer,
+                "{} {} {} {} {} {} c",
+                c1.x,
+                c1.y,
+                c2.x,
+                c2.y,
+                end.x,
+                end.y
+            ),
+            Segment::Rect(Position { x, y }, width, height) => {
+                crate::write_fmt!(&mut *writer, "{x} {y} {width} {height} re")
+            }
+            Segment::Close => writer.write(b"h"),
+        }
+    }
+}
+
+/// Paths define shapes, trajectories, and regions of all sorts. A path is made up of one or more
+/// disconnected subpaths, each comprising a sequence of connected segments.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::{content::path::Path, primitives::rectangle::Position};
+/// let triangle = Path::new()
+///     .move_to(Position::from_mm(0.0, 0.0))
+///     .line_to(Position::from_mm(50.0, 0.0))
+///     .line_to(Position::from_mm(25.0, 50.0))
+///     .close();
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    /// Segments of this `Path` in the order they were added.
+    segments: Vec<Segment>,
+
+    /// The rule used to determine the inside of this `Path`.
+    fill_rule: FillRule,
+}
+
+impl Path {
+    /// Operator used to clip with the nonzero winding number rule.
+    const CLIP_NON_ZERO: &[u8] = b"W";
+    /// Operator used to clip with the even-odd rule.
+    const CLIP_EVEN_ODD: &[u8] = b"W*";
+    /// Operator that ends the path without filling or stroking it.
+    const END_PATH: &[u8] = b"n";
+    /// Operator used to stroke the path.
+    const STROKE: &[u8] = b"S";
+    /// Operator used to fill with the nonzero winding number rule.
+    const FILL_NON_ZERO: &[u8] = b"f";
+    /// Operator used to fill with the even-odd rule.
+    const FILL_EVEN_ODD: &[u8] = b"f*";
+    /// Factor of the cubic Bézier control point distance approximating a quarter of a circle.
+    const KAPPA: f32 = 0.552_284_8;
+
+    /// Creates a new empty `Path`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Begins a new subpath at the given [`Position`].
+    pub fn move_to(mut self, pos: Position) -> Self {
+        self.segments.push(Segment::MoveTo(pos));
+        self
+    }
+
+    /// Appends a straight line from the current point to the given [`Position`].
+    pub fn line_to(mut self, pos: Position) -> Self {
+        self.segments.push(Segment::LineTo(pos));
+        self
+    }
+
+    /// Appends a cubic Bézier curve from the current point to the `end` point, using `ctrl1` and
+    /// `ctrl2` as control points.
+    pub fn curve_to(mut self, ctrl1: Position, ctrl2: Position, end: Position) -> Self {
+        self.segments.push(Segment::CurveTo(ctrl1, ctrl2, end));
+        self
+    }
+
+    /// Appends the given [`Rectangle`] as a complete subpath.
+    pub fn rect(mut self, rect: Rectangle) -> Self {
+        self.segments
+            .push(Segment::Rect(rect.low_left(), rect.width(), rect.height()));
+        self
+    }
+
+    /// Creates a new `Path` of an ellipse with the given center and radii, made out of four cubic
+    /// Bézier curves.
+    pub(crate) fn ellipse(center: Position, radius_x: f32, radius_y: f32) -> Self {
+        let (center_x, center_y) = (center.x.into_user_unit(), center.y.into_user_unit());
+        let at = |x: f32, y: f32| Position::from_units(center_x + x, center_y + y);
+        let (ctrl_x, ctrl_y) = (radius_x * Self::KAPPA, radius_y * Self::KAPPA);
+
+        Path::new()
+            .move_to(at(radius_x, 0.0))
+            .curve_to(
+                at(radius_x, ctrl_y),
+                at(ctrl_x, radius_y),
+                at(0.0, radius_y),
+            )
+            .curve_to(
+                at(-ctrl_x, radius_y),
+                at(-radius_x, ctrl_y),
+                at(-radius_x, 0.0),
+            )
+            .curve_to(
+                at(-radius_x, -ctrl_y),
+                at(-ctrl_x, -radius_y),
+                at(0.0, -radius_y),
+            )
+            .curve_to(
+                at(ctrl_x, -radius_y),
+                at(radius_x, -ctrl_y),
+                at(radius_x, 0.0),
+            )
+            .close()
+    }
+
+    /// Closes the current subpath by appending a straight line to its starting point.
+    pub fn close(mut self) -> Self {
+        self.segments.push(Segment::Close);
+        self
+    }
+
+    /// Sets the [`FillRule`] used to determine the inside of this `Path`.
+    pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+        self.fill_rule = fill_rule;
+        self
+    }
+
+    /// Returns `true` if this `Path` contains no segments.
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the smallest [`Rectangle`] containing all points and control points of this
+    /// `Path`, which also contains everything painted by filling it, or `None` if it's empty.
+    pub(crate) fn bounds(&self) -> Option<Rectangle> {
+        Rectangle::enclosing(self.segments.iter().flat_map(|segment| match *segment {
+            Segment::MoveTo(pos) | Segment::LineTo(pos) => vec![pos],
+            Segment::CurveTo(ctrl1, ctrl2, end) => vec![ctrl1, ctrl2, end],
+            Segment::Rect(pos, width, height) => {
+                vec![pos, Position::new(pos.x + width, pos.y + height)]
+            }
+            Segment::Close => vec![],
+        }))
+    }
+
+    /// Writes the path construction operators of this `Path` into the given implementor of
+    /// [`Write`].
+    pub(crate) fn write_segments(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        Ok(pdfgen_macros::write_chain! {
+            for segment in self.segments.iter() {
+                segment.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+        })
+    }
+
+    /// Writes this `Path` as a clipping path, intersecting it with the current clipping path.
+    pub(crate) fn write_clip(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        let clip_operator = match self.fill_rule {
+            FillRule::NonZero => Self::CLIP_NON_ZERO,
+            FillRule::EvenOdd => Self::CLIP_EVEN_ODD,
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            self.write_segments(writer),
+            writer.write(clip_operator),
+            writer.write(constants::SP),
+            writer.write(Self::END_PATH),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Writes this `Path` and strokes it with the current stroking color and line width.
+    pub(crate) fn write_stroke(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        Ok(pdfgen_macros::write_chain! {
+            self.write_segments(writer),
+            writer.write(Self::STROKE),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Writes this `Path` and fills its inside with the current nonstroking color.
+    pub(crate) fn write_fill(&self, writer: &mut dyn Write) -> io::Result<usize> {
+        let fill_operator = match self.fill_rule {
+            FillRule::NonZero => Self::FILL_NON_ZERO,
+            FillRule::EvenOdd => Self::FILL_EVEN_ODD,
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            self.write_segments(writer),
+            writer.write(fill_operator),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+impl From<Rectangle> for Path {
+    fn from(rect: Rectangle) -> Self {
+        Path::new().rect(rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::types::hierarchy::primitives::rectangle::{Position, Rectangle};
+
+    use super::{FillRule, Path};
+
+    #[test]
+    fn rectangle_clip() {
+        let path = Path::from(Rectangle::from_units(10.0, 20.0, 110.0, 70.0));
+
+        let mut writer = Vec::new();
+        path.write_clip(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        10 20 100 50 re
+        W n
+        ");
+    }
+
+    #[test]
+    fn fill() {
+        let path = Path::new()
+            .move_to(Position::from_units(0.0, 0.0))
+            .line_to(Position::from_units(10.0, 0.0))
+            .line_to(Position::from_units(10.0, 10.0))
+            .close();
+
+        let mut writer = Vec::new();
+        path.write_fill(&mut writer).unwrap();
+        path.clone()
+            .with_fill_rule(FillRule::EvenOdd)
+            .write_fill(&mut writer)
+            .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        0 0 m
+        10 0 l
+        10 10 l
+        h
+        f
+        0 0 m
+        10 0 l
+        10 10 l
+        h
+        f*
+        ");
+    }
+
+    #[test]
+    fn even_odd_clip() {
+        let path = Path::new()
+            .move_to(Position::from_units(0.0, 0.0))
+            .line_to(Position::from_units(100.0, 0.0))
+            .curve_to(
+                Position::from_units(100.0, 50.0),
+                Position::from_units(50.0, 100.0),
+                Position::from_units(0.0, 100.0),
+            )
+            .close()
+            .with_fill_rule(FillRule::EvenOdd);
+
+        let mut writer = Vec::new();
+        path.write_clip(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        0 0 m
+        100 0 l
+        100 50 50 100 0 100 c
+        h
+        W* n
+        ");
+    }
+}
@@ -0,0 +1,510 @@
+//! Implementation of printer's marks, drawn outside of the trim box of every page to help with
+//! cutting, registering and checking the colours of printed sheets.
+
+use crate::{
+    ObjId,
+    types::hierarchy::primitives::{
+        rectangle::{Position, Rectangle},
+        unit::Unit,
+    },
+};
+
+use super::{
+    ContentStream, Operation,
+    color::{CmykValue, Color},
+    path::{FillRule, Path},
+};
+
+/// Printer's marks drawn outside of the trim box of every page in a document that has a trim box
+/// set with [`Page::set_trim_box`]. Pages with a bleed box set with [`Page::set_bleed_box`] get the
+/// marks outside of the bleed box, so they're never printed onto the bleed area. The media box of
+/// such pages should be large enough to contain the marks, which is the box returned by
+/// [`PrintMarks::media_box`].
+///
+/// Marks are drawn in registration colour (100% of every process colour), so they appear on every
+/// printing plate.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::{content::print_marks::PrintMarks, primitives::rectangle::Rectangle}};
+/// let mut document = Document::default();
+/// let marks = PrintMarks::default();
+/// let trim_box = Rectangle::from_units(0.0, 0.0, 595.0, 842.0);
+/// let bleed_box = Rectangle::from_units(-9.0, -9.0, 604.0, 851.0);
+///
+/// let page = document.create_page();
+/// page.set_mediabox(marks.media_box(trim_box, Some(bleed_box)));
+/// page.set_bleed_box(bleed_box);
+/// page.set_trim_box(trim_box);
+/// document.set_print_marks(marks);
+/// ```
+///
+/// [`Page::set_trim_box`]: crate::types::hierarchy::page::Page::set_trim_box
+/// [`Page::set_bleed_box`]: crate::types::hierarchy::page::Page::set_bleed_box
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintMarks {
+    /// Whether crop marks are drawn at the corners of the trim box.
+    crop_marks: bool,
+
+    /// Whether registration targets are drawn at the middle of each side of the trim box.
+    registration_marks: bool,
+
+    /// Whether colour bars are drawn above the trim box.
+    color_bars: bool,
+
+    /// Distance between the trim box and the marks.
+    offset: Unit,
+
+    /// Length of the crop marks, which is also the size of the other marks.
+    length: Unit,
+
+    /// Width of the lines of the marks.
+    line_width: Unit,
+}
+
+impl Default for PrintMarks {
+    fn default() -> Self {
+        Self {
+            crop_marks: true,
+            registration_marks: true,
+            color_bars: true,
+            offset: Unit::from_mm(3.0),
+            length: Unit::from_mm(5.0),
+            line_width: Unit::from_pt(0.25),
+        }
+    }
+}
+
+impl PrintMarks {
+    /// Circle approximation constant for cubic Bézier curves.
+    const KAPPA: f64 = 0.552_284_8;
+
+    /// Registration colour, which appears on every printing plate.
+    const REGISTRATION: Color = Color::CMYK {
+        cyan: CmykValue::from_const::<100>(),
+        magenta: CmykValue::from_const::<100>(),
+        yellow: CmykValue::from_const::<100>(),
+        black: CmykValue::from_const::<100>(),
+    };
+
+    /// Colours of the patches in colour bars: solid process colours, followed by tints of black.
+    const COLOR_BAR: [Color; 7] = [
+        Color::CMYK {
+            cyan: CmykValue::from_const::<100>(),
+            magenta: CmykValue::from_const::<0>(),
+            yellow: CmykValue::from_const::<0>(),
+            black: CmykValue::from_const::<0>(),
+        },
+        Color::CMYK {
+            cyan: CmykValue::from_const::<0>(),
+            magenta: CmykValue::from_const::<100>(),
+            yellow: CmykValue::from_const::<0>(),
+            black: CmykValue::from_const::<0>(),
+        },
+        Color::CMYK {
+            cyan: CmykValue::from_const::<0>(),
+            magenta: CmykValue::from_const::<0>(),
+            yellow: CmykValue::from_const::<100>(),
+            black: CmykValue::from_const::<0>(),
+        },
+        Color::CMYK {
+            cyan: CmykValue::from_const::<0>(),
+            magenta: CmykValue::from_const::<0>(),
+            yellow: CmykValue::from_const::<0>(),
+            black: CmykValue::from_const::<100>(),
+        },
+        Color::CMYK {
+            cyan: CmykValue::from_const::<0>(),
+            magenta: CmykValue::from_const::<0>(),
+            yellow: CmykValue::from_const::<0>(),
+            black: CmykValue::from_const::<75>(),
+        },
+        Color::CMYK {
+            cyan: CmykValue::from_const::<0>(),
+            magenta: CmykValue::from_const::<0>(),
+            yellow: CmykValue::from_const::<0>(),
+            black: CmykValue::from_const::<50>(),
+        },
+        Color::CMYK {
+            cyan: CmykValue::from_const::<0>(),
+            magenta: CmykValue::from_const::<0>(),
+            yellow: CmykValue::from_const::<0>(),
+            black: CmykValue::from_const::<25>(),
+        },
+    ];
+
+    /// Sets whether crop marks are drawn at the corners of the trim box.
+    pub fn with_crop_marks(mut self, crop_marks: bool) -> Self {
+        self.crop_marks = crop_marks;
+        self
+    }
+
+    /// Sets whether registration targets are drawn at the middle of each side of the trim box.
+    pub fn with_registration_marks(mut self, registration_marks: bool) -> Self {
+        self.registration_marks = registration_marks;
+        self
+    }
+
+    /// Sets whether colour bars are drawn above the trim box.
+    pub fn with_color_bars(mut self, color_bars: bool) -> Self {
+        self.color_bars = color_bars;
+        self
+    }
+
+    /// Sets the distance between the trim box and the marks. Defaults to 3 mm, matching the usual
+    /// bleed. Pages with a larger bleed get the marks at the edge of their bleed box instead.
+    pub fn with_offset(mut self, offset: Unit) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the length of the crop marks, which is also the size of the other marks. Defaults to
+    /// 5 mm.
+    pub fn with_length(mut self, length: Unit) -> Self {
+        self.length = length;
+        self
+    }
+
+    /// Sets the width of the lines of the marks. Defaults to 0.25 pt.
+    pub fn with_line_width(mut self, line_width: Unit) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    /// Returns the media box a page with the given trim box and bleed box needs, so that it's large
+    /// enough to contain the marks.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::{content::print_marks::PrintMarks, primitives::{rectangle::Rectangle, unit::Unit}};
+    /// let marks = PrintMarks::default()
+    ///     .with_offset(Unit::from_pt(6.0))
+    ///     .with_length(Unit::from_pt(12.0));
+    /// let trim_box = Rectangle::from_units(0.0, 0.0, 100.0, 100.0);
+    ///
+    /// assert_eq!(
+    ///     marks.media_box(trim_box, None),
+    ///     Rectangle::from_units(-18.0, -18.0, 118.0, 118.0)
+    /// );
+    /// ```
+    pub fn media_box(&self, trim_box: Rectangle, bleed_box: Option<Rectangle>) -> Rectangle {
+        let (left, bottom, right, top) = Self::bounds(trim_box);
+        let extent = self.offset(trim_box, bleed_box) + self.length.into_points();
+
+        Self::rect(left - extent, bottom - extent, right + extent, top + extent)
+    }
+
+    /// Returns the distance between the given trim box and the marks in points, which is the
+    /// configured offset, or the distance to the farthest edge of the given bleed box if larger.
+    fn offset(&self, trim_box: Rectangle, bleed_box: Option<Rectangle>) -> f64 {
+        let bleed = bleed_box.map_or(0.0, |bleed_box| {
+            let (left, bottom, right, top) = Self::bounds(trim_box);
+            let (bleed_left, bleed_bottom, bleed_right, bleed_top) = Self::bounds(bleed_box);
+
+            [
+                left - bleed_left,
+                bottom - bleed_bottom,
+                bleed_right - right,
+                bleed_top - top,
+            ]
+            .into_iter()
+            .fold(0.0, f64::max)
+        });
+
+        self.offset.into_points().max(bleed)
+    }
+
+    /// Creates a [`ContentStream`] with the given [`ObjId`], drawing the marks around the given
+    /// trim box, and outside of the given bleed box.
+    pub(crate) fn draw(
+        &self,
+        trim_box: Rectangle,
+        bleed_box: Option<Rectangle>,
+        id: ObjId<ContentStream>,
+    ) -> ContentStream {
+        let mut contents = ContentStream::new(id);
+        let offset = self.offset(trim_box, bleed_box);
+
+        let mut fill = |path: Path, color: Color| {
+            if !path.is_empty() {
+                contents.add_content(Operation::FillPath { path, color });
+            }
+        };
+
+        fill(self.crop_marks(trim_box, offset), Self::REGISTRATION);
+        fill(
+            self.registration_marks(trim_box, offset),
+            Self::REGISTRATION,
+        );
+
+        if self.color_bars {
+            for (idx, color) in Self::COLOR_BAR.into_iter().enumerate() {
+                fill(self.color_bar_patch(trim_box, offset, idx), color);
+            }
+        }
+
+        contents
+    }
+
+    /// Returns the bounds of the given trim box in points, as `(left, bottom, right, top)`.
+    fn bounds(trim_box: Rectangle) -> (f64, f64, f64, f64) {
+        let low_left = trim_box.low_left();
+        let top_right = trim_box.top_right();
+
+        (
+            low_left.x.into_points(),
+            low_left.y.into_points(),
+            top_right.x.into_points(),
+            top_right.y.into_points(),
+        )
+    }
+
+    /// Creates a rectangle from the given coordinates in points.
+    fn rect(left: f64, bottom: f64, right: f64, top: f64) -> Rectangle {
+        Rectangle::new(
+            Position::new(Unit::from_pt(left), Unit::from_pt(bottom)),
+            Position::new(Unit::from_pt(right), Unit::from_pt(top)),
+        )
+    }
+
+    /// Returns the path of the crop marks, two lines at each corner of the trim box extending
+    /// outwards along its edges, starting at the given offset in points.
+    fn crop_marks(&self, trim_box: Rectangle, offset: f64) -> Path {
+        if !self.crop_marks {
+            return Path::new();
+        }
+
+        let (left, bottom, right, top) = Self::bounds(trim_box);
+        let length = self.length.into_points();
+        let half_width = self.line_width.into_points() / 2.0;
+
+        [(left, -1.0), (right, 1.0)]
+            .into_iter()
+            .flat_map(|x| [(x, (bottom, -1.0)), (x, (top, 1.0))])
+            .fold(Path::new(), |path, ((x, x_dir), (y, y_dir))| {
+                let (near_x, far_x) = (x + x_dir * offset, x + x_dir * (offset + length));
+                let (near_y, far_y) = (y + y_dir * offset, y + y_dir * (offset + length));
+
+                path.rect(Self::rect(
+                    near_x.min(far_x),
+                    y - half_width,
+                    near_x.max(far_x),
+                    y + half_width,
+                ))
+                .rect(Self::rect(
+                    x - half_width,
+                    near_y.min(far_y),
+                    x + half_width,
+                    near_y.max(far_y),
+                ))
+            })
+    }
+
+    /// Returns the path of the registration targets, a ring with a crosshair centered outside of
+    /// the middle of each edge of the trim box, at the given offset in points.
+    fn registration_marks(&self, trim_box: Rectangle, offset: f64) -> Path {
+        if !self.registration_marks {
+            return Path::new();
+        }
+
+        let (left, bottom, right, top) = Self::bounds(trim_box);
+        let distance = offset + self.length.into_points() / 2.0;
+        let (center_x, center_y) = ((left + right) / 2.0, (bottom + top) / 2.0);
+
+        let centers = [
+            (center_x, bottom - distance),
+            (center_x, top + distance),
+            (left - distance, center_y),
+            (right + distance, center_y),
+        ];
+
+        let radius = self.length.into_points() / 3.0;
+        let half_width = self.line_width.into_points() / 2.0;
+        let arm = self.length.into_points() / 2.0;
+
+        centers
+            .into_iter()
+            .fold(Path::new(), |path, (x, y)| {
+                let path = Self::circle(path, x, y, radius + half_width);
+                let path = Self::circle(path, x, y, radius - half_width);
+
+                path.rect(Self::rect(x - arm, y - half_width, x + arm, y + half_width))
+                    .rect(Self::rect(x - half_width, y - arm, x + half_width, y + arm))
+            })
+            // the inner circle cuts the ring out of the outer one
+            .with_fill_rule(FillRule::EvenOdd)
+    }
+
+    /// Appends a circle with the given center and radius to the given path.
+    fn circle(path: Path, x: f64, y: f64, radius: f64) -> Path {
+        let k = radius * Self::KAPPA;
+        let pos = |x: f64, y: f64| Position::new(Unit::from_pt(x), Unit::from_pt(y));
+
+        path.move_to(pos(x + radius, y))
+            .curve_to(
+                pos(x + radius, y + k),
+                pos(x + k, y + radius),
+                pos(x, y + radius),
+            )
+            .curve_to(
+                pos(x - k, y + radius),
+                pos(x - radius, y + k),
+                pos(x - radius, y),
+            )
+            .curve_to(
+                pos(x - radius, y - k),
+                pos(x - k, y - radius),
+                pos(x, y - radius),
+            )
+            .curve_to(
+                pos(x + k, y - radius),
+                pos(x + radius, y - k),
+                pos(x + radius, y),
+            )
+            .close()
+    }
+
+    /// Returns the path of the patch with the given index in the colour bar above the trim box, at
+    /// the given offset in points.
+    fn color_bar_patch(&self, trim_box: Rectangle, offset: f64, idx: usize) -> Path {
+        let (left, _, _, top) = Self::bounds(trim_box);
+        let size = self.length.into_points();
+
+        // the bar starts after the crop mark of the upper left corner
+        let patch_left = left + offset + size + size * idx as f64;
+        let patch_bottom = top + offset;
+
+        Path::from(Self::rect(
+            patch_left,
+            patch_bottom,
+            patch_left + size,
+            patch_bottom + size,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::primitives::{object::Object, rectangle::Rectangle, unit::Unit},
+    };
+
+    use super::PrintMarks;
+
+    #[test]
+    fn crop_marks() {
+        let mut id_manager = IdManager::new();
+        let marks = PrintMarks::default()
+            .with_registration_marks(false)
+            .with_color_bars(false)
+            .with_offset(Unit::from_pt(5.0))
+            .with_length(Unit::from_pt(10.0))
+            .with_line_width(Unit::from_pt(1.0));
+
+        let contents = marks.draw(
+            Rectangle::from_units(20.0, 20.0, 120.0, 220.0),
+            None,
+            id_manager.create_id(),
+        );
+
+        let mut writer = Vec::new();
+        contents.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 160 >>
+        stream
+        /DeviceCMYK cs
+        1 1 1 1 sc
+        5 19.5 10 1 re
+        19.5 5 1 10 re
+        5 219.5 10 1 re
+        19.5 225 1 10 re
+        125 19.5 10 1 re
+        119.5 5 1 10 re
+        125 219.5 10 1 re
+        119.5 225 1 10 re
+        f
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn marks_outside_of_bleed_box() {
+        let mut id_manager = IdManager::new();
+        let marks = PrintMarks::default()
+            .with_crop_marks(false)
+            .with_registration_marks(false)
+            .with_offset(Unit::from_pt(5.0))
+            .with_length(Unit::from_pt(10.0));
+        let trim_box = Rectangle::from_units(20.0, 20.0, 120.0, 220.0);
+        let bleed_box = Rectangle::from_units(12.0, 12.0, 128.0, 228.0);
+
+        assert_eq!(
+            marks.media_box(trim_box, Some(bleed_box)),
+            Rectangle::from_units(2.0, 2.0, 138.0, 238.0)
+        );
+
+        let contents = marks.draw(trim_box, Some(bleed_box), id_manager.create_id());
+
+        let mut writer = Vec::new();
+        contents.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 316 >>
+        stream
+        /DeviceCMYK cs
+        1 0 0 0 sc
+        38 228 10 10 re
+        f
+        /DeviceCMYK cs
+        0 1 0 0 sc
+        48 228 10 10 re
+        f
+        /DeviceCMYK cs
+        0 0 1 0 sc
+        58 228 10 10 re
+        f
+        /DeviceCMYK cs
+        0 0 0 1 sc
+        68 228 10 10 re
+        f
+        /DeviceCMYK cs
+        0 0 0 0.75 sc
+        78 228 10 10 re
+        f
+        /DeviceCMYK cs
+        0 0 0 0.5 sc
+        88 228 10 10 re
+        f
+        /DeviceCMYK cs
+        0 0 0 0.25 sc
+        98 228 10 10 re
+        f
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn no_marks() {
+        let mut id_manager = IdManager::new();
+        let marks = PrintMarks::default()
+            .with_crop_marks(false)
+            .with_registration_marks(false)
+            .with_color_bars(false);
+
+        let contents = marks.draw(
+            Rectangle::from_units(20.0, 20.0, 120.0, 220.0),
+            None,
+            id_manager.create_id(),
+        );
+
+        assert!(contents.is_empty());
+    }
+}
@@ -0,0 +1,139 @@
+//! Recording of drawing operations, which can be replayed onto any number of pages.
+
+use crate::FontId;
+
+use super::{color::Color, image::Image, path::Path, text::Text};
+
+/// A single drawing operation recorded in a [`Recording`].
+#[derive(Debug, Clone)]
+pub enum RecordedOperation {
+    /// Draws a [`Text`] with the given font.
+    Text {
+        /// The text to draw.
+        text: Text,
+
+        /// Font used to draw the text.
+        font_id: FontId,
+    },
+
+    /// Draws an [`Image`].
+    Image(Image),
+
+    /// Fills a [`Path`] with a [`Color`].
+    FillPath {
+        /// The path to fill.
+        path: Path,
+
+        /// Color used to fill the path.
+        color: Color,
+    },
+
+    /// Clips the nested operations to a [`Path`].
+    Clip {
+        /// The path to clip with.
+        path: Path,
+
+        /// Operations drawn inside of the clipping path.
+        operations: Vec<RecordedOperation>,
+    },
+
+    /// Clips the nested operations to the glyph outlines of a [`Text`].
+    ClipToText {
+        /// The text whose glyph outlines are used for clipping.
+        text: Text,
+
+        /// Font used to draw the text.
+        font_id: FontId,
+
+        /// Operations drawn inside of the glyph outlines.
+        operations: Vec<RecordedOperation>,
+    },
+}
+
+/// A list of drawing operations, built once and replayed onto multiple pages with
+/// [`Page::replay`], e.g. for letterheads or footers repeated on every page.
+///
+/// NOTE: Images are written separately for every page they are replayed onto.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::{content::{color::Color, recording::Recording}, primitives::rectangle::Rectangle}};
+/// let mut footer = Recording::new();
+/// footer.fill_path(Rectangle::from_units(0.0, 0.0, 595.0, 20.0), Color::Gray(230));
+///
+/// let mut document = Document::default();
+/// document.create_page().replay(&footer);
+/// document.create_page().replay(&footer);
+/// ```
+///
+/// [`Page::replay`]: crate::types::hierarchy::page::Page::replay
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    /// The recorded operations, in drawing order.
+    operations: Vec<RecordedOperation>,
+}
+
+impl Recording {
+    /// Creates a new empty `Recording`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records drawing the given [`Text`] with the given font.
+    pub fn add_text(&mut self, text: Text, font_id: FontId) {
+        self.operations
+            .push(RecordedOperation::Text { text, font_id });
+    }
+
+    /// Records drawing the given [`Image`].
+    pub fn add_image(&mut self, image: Image) {
+        self.operations.push(RecordedOperation::Image(image));
+    }
+
+    /// Records filling the given [`Path`] with the given [`Color`].
+    pub fn fill_path(&mut self, path: impl Into<Path>, color: Color) {
+        self.operations.push(RecordedOperation::FillPath {
+            path: path.into(),
+            color,
+        });
+    }
+
+    /// Records clipping all operations recorded inside of the `content` closure to the given
+    /// [`Path`].
+    pub fn clip_to(&mut self, path: impl Into<Path>, content: impl FnOnce(&mut Self)) {
+        let operations = Self::record(content);
+        self.operations.push(RecordedOperation::Clip {
+            path: path.into(),
+            operations,
+        });
+    }
+
+    /// Records clipping all operations recorded inside of the `content` closure to the glyph
+    /// outlines of the given [`Text`].
+    pub fn clip_to_text(&mut self, text: Text, font_id: FontId, content: impl FnOnce(&mut Self)) {
+        let operations = Self::record(content);
+        self.operations.push(RecordedOperation::ClipToText {
+            text,
+            font_id,
+            operations,
+        });
+    }
+
+    /// Returns the recorded operations, in drawing order.
+    pub fn operations(&self) -> &[RecordedOperation] {
+        &self.operations
+    }
+
+    /// Returns `true` if no operations were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Records the operations of the `content` closure into a separate list.
+    fn record(content: impl FnOnce(&mut Self)) -> Vec<RecordedOperation> {
+        let mut nested = Self::new();
+        content(&mut nested);
+        nested.operations
+    }
+}
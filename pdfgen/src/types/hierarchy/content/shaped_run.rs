@@ -0,0 +1,178 @@
+//! Implementation of shaped runs, showing glyphs positioned by an external text shaper.
+
+use std::io::{Error, Write};
+
+use crate::{SanitizePolicy, types::constants};
+
+use super::text_run::TextRun;
+
+/// A run of glyphs shaped and positioned by an external text shaper, such as HarfBuzz, shown with
+/// a font created with [`Document::create_shaping_font`] from the same font data. Glyphs are
+/// addressed by their indices in the font program, so ligatures, contextual forms and marks
+/// chosen by the shaper are shown as they are, at the positions set by the shaper.
+///
+/// Advances and offsets are in thousandths of the font size, i.e. the positions returned by the
+/// shaper divided by the units per em of the font and multiplied by 1000. Offsets move a glyph
+/// without moving the glyphs after it, e.g. to place a mark above its base glyph.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::shaped_run::ShapedRun;
+/// // the "fi" ligature followed by an "x", as positioned by the shaper
+/// let run = ShapedRun::new(vec![192, 91], vec![556.0, 500.0], vec![(0.0, 0.0), (0.0, 0.0)])
+///     .with_text("fix");
+/// ```
+///
+/// [`Document::create_shaping_font`]: crate::Document::create_shaping_font
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShapedRun {
+    /// Indices of the glyphs in the font program, in visual order.
+    glyph_ids: Vec<u16>,
+
+    /// Horizontal advance of each glyph, moving the position of the following glyphs.
+    advances: Vec<f32>,
+
+    /// Horizontal and vertical offset of each glyph from its position.
+    offsets: Vec<(f32, f32)>,
+
+    /// Text the glyphs represent, used to extract it, as glyphs can't be mapped back to text.
+    text: Option<String>,
+}
+
+impl ShapedRun {
+    /// Creates a new `ShapedRun` showing the given glyphs in order, each with its advance and
+    /// offset in thousandths of the font size.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numbers of glyphs, advances and offsets differ.
+    pub fn new(glyph_ids: Vec<u16>, advances: Vec<f32>, offsets: Vec<(f32, f32)>) -> Self {
+        if glyph_ids.len() != advances.len() || glyph_ids.len() != offsets.len() {
+            panic!("Every glyph of a shaped run must have exactly one advance and one offset.");
+        }
+
+        Self {
+            glyph_ids,
+            advances,
+            offsets,
+            text: None,
+        }
+    }
+
+    /// Sets the text the glyphs of this `ShapedRun` represent, in logical order. The text is
+    /// written as the replacement text of the glyphs, so that it can be extracted and searched.
+    pub fn with_text(mut self, text: impl Into<String>) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    /// Returns the text the glyphs of this `ShapedRun` represent, if set.
+    pub(crate) fn text(&self) -> Option<&str> {
+        self.text.as_deref()
+    }
+
+    /// Returns this `ShapedRun` with its text sanitized according to the given [`SanitizePolicy`].
+    pub(crate) fn sanitized(mut self, policy: &SanitizePolicy) -> Self {
+        self.text = self.text.map(|text| policy.text(&text));
+        self
+    }
+
+    /// Returns the sum of the advances of this `ShapedRun`, in thousandths of the font size.
+    pub(crate) fn width(&self) -> f32 {
+        self.advances.iter().sum()
+    }
+
+    /// Writes the glyphs of this `ShapedRun` as the operands of `TJ` operators into the given
+    /// implementor of [`Write`], for the given font size. Glyphs of a font addressing glyphs have a
+    /// width of 0, so each glyph is followed by an adjustment of its advance. Glyphs with a
+    /// vertical offset are shown with a text rise (`Ts`), which is reset at the end of the run.
+    pub(crate) fn write(&self, writer: &mut dyn Write, size: u32) -> Result<usize, Error> {
+        // round to avoid writing values such as `0.30000000000000004`
+        let round = |value: f32| (value * 1000.0).round() / 1000.0 + 0.0;
+
+        let mut written = 0;
+        let mut rise = 0.0;
+        let mut items: Vec<String> = Vec::new();
+        let mut pending = 0.0;
+
+        let glyphs = self.glyph_ids.iter().zip(&self.advances).zip(&self.offsets);
+        for ((glyph_id, advance), (x_offset, y_offset)) in glyphs {
+            let glyph_rise = round(y_offset * size as f32 / 1000.0);
+            if glyph_rise != rise {
+                written += Self::write_items(writer, &mut items)?;
+                written += crate::write_fmt!(&mut *writer, "{glyph_rise} ")?;
+                written += writer.write(TextRun::TS_OPERATOR)?;
+                written += writer.write(constants::NL_MARKER)?;
+                rise = glyph_rise;
+            }
+
+            // adjustments are subtracted from the position, so moving right is negative
+            let adjustment = round(pending - x_offset);
+            if adjustment != 0.0 {
+                items.push(adjustment.to_string());
+            }
+            items.push(format!("<{glyph_id:04X}>"));
+            pending = x_offset - advance;
+        }
+
+        written += Self::write_items(writer, &mut items)?;
+
+        if rise != 0.0 {
+            written += writer.write(b"0 ")?;
+            written += writer.write(TextRun::TS_OPERATOR)?;
+            written += writer.write(constants::NL_MARKER)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Writes the given glyphs and adjustments with a single `TJ` operator, clearing them, or
+    /// nothing if there are none.
+    fn write_items(writer: &mut dyn Write, items: &mut Vec<String>) -> Result<usize, Error> {
+        if items.is_empty() {
+            return Ok(0);
+        }
+
+        let written = crate::write_fmt!(&mut *writer, "[{}] ", items.join(" "))?
+            + writer.write(TextRun::TJ_OPERATOR)?
+            + writer.write(constants::NL_MARKER)?;
+        items.clear();
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ShapedRun;
+
+    #[test]
+    fn positioned_glyphs() {
+        // a base glyph with a mark placed above it, followed by a kerned glyph
+        let run = ShapedRun::new(
+            vec![36, 512, 37],
+            vec![600.0, 0.0, 550.0],
+            vec![(0.0, 0.0), (-300.0, 150.0), (-20.0, 0.0)],
+        );
+
+        let mut writer = Vec::new();
+        run.write(&mut writer, 10).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [<0024>] TJ
+        1.5 Ts
+        [-300 <0200>] TJ
+        0 Ts
+        [-280 <0025>] TJ
+        ");
+        assert_eq!(run.width(), 1150.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn missing_advances() {
+        ShapedRun::new(vec![36, 37], vec![600.0], vec![(0.0, 0.0), (0.0, 0.0)]);
+    }
+}
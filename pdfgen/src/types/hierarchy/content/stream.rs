@@ -1,4 +1,4 @@
-use std::io::{Error, Write};
+use std::io::{Error, ErrorKind, Read, Write};
 
 use pdfgen_macros::const_identifiers;
 
@@ -7,7 +7,7 @@ use crate::types::{constants, hierarchy::primitives::identifier::Identifier};
 /// A stream object, like a string object, is a sequence of bytes that may be of unlimited length.
 /// Streams should be used to represent objects with potentially large amounts of data, such as
 /// images and page descriptions.
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) struct Stream {
     // NOTE: Stream dictionaries have more entries such as filter, decode parameters etc. For now,
     //       we only need the required dictionary entry 'Length', implicitly available in `Vec`
@@ -36,6 +36,11 @@ impl Stream {
         }
     }
 
+    /// Takes the bytes out of this `Stream`, leaving it empty.
+    pub fn take_bytes(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.inner)
+    }
+
     /// Writes (aditional) bytes into this `Stream`, updating it's length.
     pub fn push_bytes(&mut self, bytes: &[u8]) {
         self.inner
@@ -62,6 +67,47 @@ impl Stream {
     pub fn write_with_dict<F>(&self, writer: &mut dyn Write, write_dict: F) -> Result<usize, Error>
     where
         F: FnOnce(&mut dyn Write) -> Result<usize, Error>,
+    {
+        Self::write_parts(writer, self.inner.len(), write_dict, |writer| {
+            writer.write_all(&self.inner).map(|_| self.inner.len())
+        })
+    }
+
+    /// Write a stream object with the given length into the given implementor of [`Write`] trait,
+    /// copying the stream's bytes from the `reader` instead of holding them in memory. Only `len`
+    /// bytes are read, and an error is returned if the `reader` ends before that.
+    pub fn write_from_reader<F>(
+        writer: &mut dyn Write,
+        reader: impl Read,
+        len: usize,
+        write_dict: F,
+    ) -> Result<usize, Error>
+    where
+        F: FnOnce(&mut dyn Write) -> Result<usize, Error>,
+    {
+        Self::write_parts(writer, len, write_dict, |writer| {
+            let copied = std::io::copy(&mut reader.take(len as u64), writer)?;
+            if copied < len as u64 {
+                return Err(Error::new(
+                    ErrorKind::UnexpectedEof,
+                    format!("Stream data ended after {copied} of {len} bytes."),
+                ));
+            }
+
+            Ok(len)
+        })
+    }
+
+    /// Write the dictionary, markers and bytes of a stream object with the given length.
+    fn write_parts<D, B>(
+        writer: &mut dyn Write,
+        len: usize,
+        write_dict: D,
+        write_bytes: B,
+    ) -> Result<usize, Error>
+    where
+        D: FnOnce(&mut dyn Write) -> Result<usize, Error>,
+        B: FnOnce(&mut dyn Write) -> Result<usize, Error>,
     {
         let written = pdfgen_macros::write_chain! {
             // BEGIN_DICTIONARY:
@@ -71,7 +117,7 @@ impl Stream {
 
             // write the length
             Self::LENGTH.write(writer),
-            crate::write_fmt!(&mut *writer, "{}", self.inner.len()),
+            crate::write_fmt!(&mut *writer, "{}", len),
             writer.write(b" >>"),
             writer.write(constants::NL_MARKER),
             // END_DICTIONARY
@@ -80,7 +126,7 @@ impl Stream {
             writer.write(Self::START_STREAM),
             writer.write(constants::NL_MARKER),
 
-            writer.write_all(&self.inner).map(|_| self.inner.len()),
+            write_bytes(writer),
 
             writer.write(constants::NL_MARKER),
             writer.write(Self::END_STREAM),
@@ -89,6 +135,21 @@ impl Stream {
         Ok(written)
     }
 
+    /// Returns `true` if the bytes of this [`Stream`] end with the given bytes.
+    pub fn ends_with(&self, bytes: &[u8]) -> bool {
+        self.inner.ends_with(bytes)
+    }
+
+    /// Returns the bytes of this [`Stream`].
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+
+    /// Returns the number of bytes of this [`Stream`].
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
     /// Returns `true` if no bytes were written to this [`Stream`].
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
@@ -116,4 +177,27 @@ mod tests {
         endstream
         ");
     }
+
+    #[test]
+    fn stream_from_reader() {
+        let mut writer = Vec::default();
+        Stream::write_from_reader(&mut writer, &b"Read lazily, not the rest."[..], 11, |_| {
+            Ok(0)
+        })
+        .unwrap();
+        let output = String::from_utf8_lossy(&writer);
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 11 >>
+        stream
+        Read lazily
+        endstream
+        ");
+
+        let short = Stream::write_from_reader(&mut Vec::default(), &b"Short"[..], 11, |_| Ok(0));
+        assert_eq!(
+            short.map_err(|err| err.kind()),
+            Err(std::io::ErrorKind::UnexpectedEof)
+        );
+    }
 }
@@ -1,13 +1,40 @@
 //! Implementation of PDF Text object.
 
-use std::io::{self, Write};
+use std::{
+    borrow::Cow,
+    io::{self, Write},
+    sync::Arc,
+};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    SanitizePolicy, TextStyle,
+    types::{
+        constants,
+        hierarchy::primitives::{
+            encoding::ShowEncoding,
+            font_metrics::{FontMetrics, VerticalMetrics},
+            identifier::Identifier,
+            language::Language,
+            rectangle::{Position, Rectangle},
+            string::PdfString,
+            unit::Unit,
+        },
+    },
+};
 
-use crate::types::{
-    constants,
-    hierarchy::primitives::{identifier::Identifier, rectangle::Position, string::PdfString},
+use super::{
+    bidi::{self, TextDirection},
+    color::Color,
+    graphics_state::ExtGState,
+    path::Path,
+    text_box::{Line, LineBreaker, LineLayout, Overflow, SpaceBreaker, TabStop, TextBox},
+    text_run::TextRun,
 };
 
-use super::color::Color;
+#[cfg(feature = "composite-fonts")]
+use super::shaped_run::ShapedRun;
 
 /// Defines the transformation properties of a [`Text`] object, including its position and size on a [`Page`].
 ///
@@ -21,6 +48,186 @@ pub(crate) struct TextTransform {
 
     /// The font size of the [`Text`] in user space units.
     size: u32,
+
+    /// Linear part `[a b c d]` of the text matrix, rotating, scaling or skewing the [`Text`] around
+    /// its position. The text is only moved to its position if `None`.
+    matrix: Option<[f32; 4]>,
+}
+
+/// Rounds the given value to avoid writing values such as `6.123234e-17` instead of 0.
+fn round(value: f32) -> f32 {
+    (value * 100_000.0).round() / 100_000.0 + 0.0
+}
+
+impl TextTransform {
+    /// Concatenates the given linear transformation `[a b c d]` to the text matrix, applying it
+    /// after the transformations already set.
+    fn concat(&mut self, [a2, b2, c2, d2]: [f32; 4]) {
+        let [a1, b1, c1, d1] = self.matrix.unwrap_or([1.0, 0.0, 0.0, 1.0]);
+        self.matrix = Some([
+            round(a1 * a2 + b1 * c2),
+            round(a1 * b2 + b1 * d2),
+            round(c1 * a2 + d1 * c2),
+            round(c1 * b2 + d1 * d2),
+        ]);
+    }
+
+    /// Writes the operator moving the start of the text to the given position, shifted by
+    /// `offset` along the baseline, as `x y Td`, or as `a b c d x y Tm` if a text matrix is set.
+    fn write_position(
+        &self,
+        writer: &mut Vec<u8>,
+        x: Unit,
+        y: Unit,
+        offset: f32,
+    ) -> io::Result<()> {
+        match self.matrix {
+            Some([a, b, c, d]) => {
+                let x = x + Unit::from_unit(round(a * offset));
+                let y = y + Unit::from_unit(round(b * offset));
+                writer.write_all(format!("{a} {b} {c} {d} {x} {y} ").as_bytes())?;
+                writer.write_all(Text::TM_OPERATOR)?;
+            }
+            None => {
+                let x = x + Unit::from_unit(round(offset));
+                writer.write_all(format!("{x} {y} ").as_bytes())?;
+                writer.write_all(Text::TD_OPERATOR)?;
+            }
+        }
+
+        writer.write_all(constants::NL_MARKER)
+    }
+}
+
+/// Horizontal alignment of a [`Text`]. A single line of text is aligned relative to its position,
+/// and the lines of a text laid out in a box relative to the box.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlignment {
+    /// The text starts at its position, or at the left edge of its box.
+    #[default]
+    Left,
+
+    /// The text is centered around its position, or inside of its box.
+    Center,
+
+    /// The text ends at its position, or at the right edge of its box.
+    Right,
+
+    /// The lines of a text laid out in a box span the whole width of the box, by distributing the
+    /// free space of each line between its words. The last line of each paragraph starts at the
+    /// left edge of the box, as does a single line of text at its position.
+    Justify,
+}
+
+impl TextAlignment {
+    /// Returns the offset of the start of a line along its baseline, given the space left free by
+    /// the line, which is negative for text aligned relative to its position.
+    fn offset(self, free_space: f32) -> f32 {
+        match self {
+            TextAlignment::Left | TextAlignment::Justify => 0.0,
+            TextAlignment::Center => free_space / 2.0,
+            TextAlignment::Right => free_space,
+        }
+    }
+}
+
+/// Point of the first line of a [`Text`] that is placed at its position, or at the top of its box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAnchor {
+    /// The baseline of the first line is placed at the position, or at the top of the box. This is
+    /// the default for text at a position, so texts of different sizes share their baseline.
+    FirstBaseline,
+
+    /// The top of the first line, one font size above its baseline, is placed at the position, or
+    /// at the top of the box. This is the default for text laid out in a box.
+    Top,
+}
+
+/// Line drawn along every line of a [`Text`], e.g. to mark a link or deleted text. Decorations are
+/// stroked with the color of the text, with the underline thickness of its font, at positions
+/// taken from the metrics of its font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDecoration {
+    /// Line below the baseline, at the underline position of the font.
+    Underline,
+
+    /// Line through the middle of the lowercase letters, at half of the x-height of the font.
+    Strikethrough,
+}
+
+impl TextDecoration {
+    /// Returns the height of the center of this decoration above the baseline for a font with
+    /// the given [`VerticalMetrics`], in thousandths of the font size.
+    fn position(self, metrics: &VerticalMetrics) -> f32 {
+        match self {
+            TextDecoration::Underline => metrics.underline_position,
+            TextDecoration::Strikethrough => metrics.x_height / 2.0,
+        }
+    }
+}
+
+/// Determines whether showing [`Text`] fills, strokes or clips with the glyph outlines, or does
+/// nothing at all (`Tr` operator).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextRenderingMode {
+    /// Fill the glyphs with the fill color.
+    #[default]
+    Fill,
+
+    /// Stroke the outlines of the glyphs with the stroke color.
+    Stroke,
+
+    /// Fill the glyphs, then stroke their outlines.
+    FillStroke,
+
+    /// Neither fill nor stroke the glyphs, making the text invisible while it remains selectable
+    /// and searchable, e.g. for an OCR text layer over a scanned image.
+    Invisible,
+
+    /// Fill the glyphs and add them to the clipping path.
+    FillClip,
+
+    /// Stroke the outlines of the glyphs and add them to the clipping path.
+    StrokeClip,
+
+    /// Fill the glyphs, stroke their outlines and add them to the clipping path.
+    FillStrokeClip,
+
+    /// Add the glyphs to the clipping path.
+    Clip,
+}
+
+impl TextRenderingMode {
+    /// Returns `true` if this mode adds the glyphs to the clipping path.
+    pub fn is_clipping(self) -> bool {
+        self.operand() >= TextRenderingMode::FillClip.operand()
+    }
+
+    /// Returns the clipping counterpart of this mode, which paints the glyphs the same way and
+    /// additionally adds them to the clipping path.
+    pub fn clipping(self) -> Self {
+        match self {
+            TextRenderingMode::Fill => TextRenderingMode::FillClip,
+            TextRenderingMode::Stroke => TextRenderingMode::StrokeClip,
+            TextRenderingMode::FillStroke => TextRenderingMode::FillStrokeClip,
+            TextRenderingMode::Invisible => TextRenderingMode::Clip,
+            clipping => clipping,
+        }
+    }
+
+    /// Returns the operand of the `Tr` operator for this mode.
+    fn operand(self) -> u8 {
+        match self {
+            TextRenderingMode::Fill => 0,
+            TextRenderingMode::Stroke => 1,
+            TextRenderingMode::FillStroke => 2,
+            TextRenderingMode::Invisible => 3,
+            TextRenderingMode::FillClip => 4,
+            TextRenderingMode::StrokeClip => 5,
+            TextRenderingMode::FillStrokeClip => 6,
+            TextRenderingMode::Clip => 7,
+        }
+    }
 }
 
 /// A PDF text object, encapsulating a selected font, size, position, and content for rendering
@@ -32,11 +239,81 @@ pub struct Text {
     /// Represents the content (literal) to be rendered.
     content: PdfString,
 
+    /// Run of strings and glyph position adjustments shown instead of `content`, which then holds
+    /// the strings of the run without the adjustments.
+    run: Option<TextRun>,
+
+    /// Run of glyphs positioned by an external shaper shown instead of `content`, which then holds
+    /// the text of the run.
+    #[cfg(feature = "composite-fonts")]
+    shaped: Option<ShapedRun>,
+
     /// Represents the [`Text`] objects rendering position and scale.
     transform: TextTransform,
 
     /// Represents the color information used to render the given text.
     color: Color,
+
+    /// Whether the size and color were set explicitly, instead of being taken from the default
+    /// [`TextStyle`] of the document.
+    explicit_style: (bool, bool),
+
+    /// Color used to stroke the glyph outlines of the text.
+    stroke_color: Option<Color>,
+
+    /// Width of the line used to stroke the glyph outlines of the text, in user space units.
+    stroke_width: Option<f32>,
+
+    /// Determines whether the text is filled, stroked, used for clipping or invisible.
+    rendering_mode: TextRenderingMode,
+
+    /// Lines drawn along every line of the text, in the order they were added.
+    decorations: Vec<TextDecoration>,
+
+    /// Graphics state parameters that should be applied when rendering the given text.
+    ext_g_state: Option<ExtGState>,
+
+    /// Natural language of the text content, written as span-level marked content.
+    language: Option<Language>,
+
+    /// Bounding rectangle into which the content is broken into lines, instead of being drawn as a
+    /// single line at the position of the text.
+    bounds: Option<Rectangle>,
+
+    /// Determines what happens to lines that don't fit into `bounds`.
+    overflow: Overflow,
+
+    /// Finds the points at which the content is broken into lines inside of `bounds`, instead of
+    /// breaking lines at spaces only.
+    line_breaker: Option<Arc<dyn LineBreaker>>,
+
+    /// Horizontal alignment of the text relative to its position or `bounds`.
+    alignment: TextAlignment,
+
+    /// Direction in which the content is read, determining the order it's shown in.
+    direction: TextDirection,
+
+    /// Distance between the baselines of consecutive lines, 120% of the font size if `None`.
+    leading: Option<Unit>,
+
+    /// Point of the first line placed at the position or the top of `bounds`, which depends on
+    /// whether the text has `bounds` if `None`.
+    anchor: Option<TextAnchor>,
+
+    /// Indentation of all lines from the left edge of `bounds` or from the position.
+    indent: Unit,
+
+    /// Additional indentation of the first line of each paragraph, negative for a hanging indent.
+    first_line_indent: Unit,
+
+    /// Positions at which the text following tab characters is aligned, ordered by position.
+    tab_stops: Vec<TabStop>,
+
+    /// Encoding of the shown strings, determined by the font the text is drawn with.
+    encoding: ShowEncoding,
+
+    /// Metrics of the font the text is drawn with, measuring its lines to align and break them.
+    metrics: FontMetrics,
 }
 
 impl Text {
@@ -49,23 +326,66 @@ impl Text {
     pub const TF_OPERATOR: &[u8] = b"Tf";
     /// Represents the Td (Text Move) operator.
     pub const TD_OPERATOR: &[u8] = b"Td";
+    /// Represents the Tm (Text Matrix) operator.
+    pub const TM_OPERATOR: &[u8] = b"Tm";
     /// Represents the Tj (Text Show) operator.
     pub const TJ_OPERATOR: &[u8] = b"Tj";
+    /// Represents the Tr (Text Rendering Mode) operator.
+    pub const TR_OPERATOR: &[u8] = b"Tr";
+
+    /// Represents the Tw (Word spacing) operator.
+    pub const TW_OPERATOR: &[u8] = b"Tw";
+    /// Represents the w (Line Width) operator.
+    pub const W_OPERATOR: &[u8] = b"w";
+
+    /// Represents the BDC (Begin Marked-Content with property list) operator.
+    pub const BDC_OPERATOR: &[u8] = b"BDC";
+    /// Represents the EMC (End Marked-Content) operator.
+    pub const EMC_OPERATOR: &[u8] = b"EMC";
+
+    const_identifiers! {
+        SPAN: b"Span",
+        LANG: b"Lang",
+        ACTUAL_TEXT,
+    }
 
     /// Creates a default initialized [`TexBuilder`], providing default values for font (Helvetica) and it's
     /// size (12).
     pub fn builder() -> TextBuilder<false> {
         let txt = Self {
             content: PdfString::from(""),
+            run: None,
+            #[cfg(feature = "composite-fonts")]
+            shaped: None,
             transform: TextTransform {
                 position: Position::from_mm(0.0, 0.0),
                 size: 12,
+                matrix: None,
             },
             color: Color::Rgb {
                 red: 0,
                 green: 0,
                 blue: 0,
             },
+            explicit_style: (false, false),
+            stroke_color: None,
+            stroke_width: None,
+            rendering_mode: TextRenderingMode::default(),
+            decorations: Vec::new(),
+            ext_g_state: None,
+            language: None,
+            bounds: None,
+            overflow: Overflow::default(),
+            line_breaker: None,
+            alignment: TextAlignment::default(),
+            direction: TextDirection::default(),
+            leading: None,
+            anchor: None,
+            indent: Unit::from_unit(0.0),
+            first_line_indent: Unit::from_unit(0.0),
+            tab_stops: Vec::new(),
+            encoding: ShowEncoding::default(),
+            metrics: FontMetrics::default(),
         };
 
         TextBuilder { inner: txt }
@@ -73,50 +393,569 @@ impl Text {
 
     /// Expands the inner content with the provided one.
     fn expand(&mut self, content: impl Into<String>) {
+        let content = content.into();
+        if let Some(run) = self.run.take() {
+            self.run = Some(run.text(content.clone()));
+        }
+
         self.content.expand(content);
     }
 
+    /// Sanitizes the content of this `Text` according to the given [`SanitizePolicy`].
+    pub(crate) fn sanitize(&mut self, policy: &SanitizePolicy) {
+        self.content = PdfString::from(policy.text(&self.content.to_text()));
+        self.run = self.run.take().map(|run| run.sanitized(policy));
+        #[cfg(feature = "composite-fonts")]
+        {
+            self.shaped = self.shaped.take().map(|shaped| shaped.sanitized(policy));
+        }
+    }
+
+    /// Returns the content of this `Text`.
+    pub(crate) fn content(&self) -> Cow<'_, str> {
+        self.content.to_text()
+    }
+
+    /// Returns a copy of this `Text` with every occurrence of the given pattern in its content
+    /// replaced, or `None` if the content doesn't contain the pattern. The glyph adjustments of a
+    /// [`TextRun`] are dropped with the replaced content. Texts showing a [`ShapedRun`] aren't
+    /// replaced, since their glyphs were chosen by the shaper for the original content.
+    ///
+    /// [`ShapedRun`]: super::shaped_run::ShapedRun
+    pub(crate) fn replaced(&self, pattern: &str, replacement: &str) -> Option<Self> {
+        #[cfg(feature = "composite-fonts")]
+        if self.shaped.is_some() {
+            return None;
+        }
+
+        let content = self.content();
+        if pattern.is_empty() || !content.contains(pattern) {
+            return None;
+        }
+
+        let mut text = self.clone();
+        text.content = PdfString::from(content.replace(pattern, replacement));
+        text.run = None;
+        Some(text)
+    }
+
+    /// Returns the [`Color`] used to render this `Text`.
+    pub(crate) fn color(&self) -> Color {
+        self.color
+    }
+
+    /// Returns the [`Color`] used to stroke the glyph outlines of this `Text`, if it has one.
+    pub(crate) fn stroke_color(&self) -> Option<Color> {
+        self.stroke_color
+    }
+
+    /// Applies the size and color of the given [`TextStyle`] to this `Text`, unless they were set
+    /// explicitly.
+    pub(crate) fn apply_style(&mut self, style: &TextStyle) {
+        let (has_size, has_color) = self.explicit_style;
+        if !has_size {
+            self.transform.size = style.size();
+        }
+        if !has_color {
+            self.color = style.color();
+        }
+    }
+
+    /// Sets the [`ShowEncoding`] of the strings of this `Text`, which depends on its font.
+    pub(crate) fn set_encoding(&mut self, encoding: ShowEncoding) {
+        self.encoding = encoding;
+    }
+
+    /// Sets the [`FontMetrics`] of the font this `Text` is drawn with, which its lines are
+    /// measured with. Texts are measured with the metrics of Helvetica until they are drawn.
+    pub(crate) fn set_metrics(&mut self, metrics: FontMetrics) {
+        self.metrics = metrics;
+    }
+
+    /// Switches the [`TextRenderingMode`] of this `Text` to its clipping counterpart.
+    pub(crate) fn use_for_clipping(&mut self) {
+        self.rendering_mode = self.rendering_mode.clipping();
+    }
+
+    /// Takes the [`ExtGState`] out of this `Text`, leaving `None` in its place.
+    pub(crate) fn take_ext_g_state(&mut self) -> Option<ExtGState> {
+        self.ext_g_state.take()
+    }
+
     /// Returns a byte representation for drawing operations of this `Text` object in PDF syntax.
     pub(crate) fn to_bytes(&self, font_name: Identifier<&[u8]>) -> io::Result<Vec<u8>> {
         let mut writer = Vec::new();
 
+        // /Span << /Lang (xx-YY) /ActualText (Text) >> BDC
+        let actual_text = self.actual_text();
+        if self.language.is_some() || actual_text.is_some() {
+            Self::SPAN.write(&mut writer)?;
+            writer.write_all(b"<< ")?;
+            if let Some(language) = &self.language {
+                Self::LANG.write(&mut writer)?;
+                language.write(&mut writer)?;
+                writer.write_all(constants::SP)?;
+            }
+            if let Some(actual_text) = actual_text {
+                Self::ACTUAL_TEXT.write(&mut writer)?;
+                PdfString::text_string(actual_text).write_content(&mut writer)?;
+                writer.write_all(constants::SP)?;
+            }
+            writer.write_all(b">> ")?;
+            writer.write_all(Self::BDC_OPERATOR)?;
+            writer.write_all(constants::NL_MARKER)?;
+        }
+
         // BT
         writer.write_all(Self::BT_MARKER)?;
         writer.write_all(constants::NL_MARKER)?;
 
         self.color.write_non_stroke(&mut writer)?;
 
+        if let Some(stroke_color) = self.stroke_color {
+            stroke_color.write_stroke(&mut writer)?;
+        }
+
+        // width w
+        if let Some(stroke_width) = self.stroke_width {
+            writer.write_all(format!("{stroke_width} ").as_bytes())?;
+            writer.write_all(Self::W_OPERATOR)?;
+            writer.write_all(constants::NL_MARKER)?;
+        }
+
+        // mode Tr
+        if self.rendering_mode != TextRenderingMode::Fill {
+            writer.write_all(format!("{} ", self.rendering_mode.operand()).as_bytes())?;
+            writer.write_all(Self::TR_OPERATOR)?;
+            writer.write_all(constants::NL_MARKER)?;
+        }
+
         // /FName Size Tf
         font_name.write(&mut writer)?;
         writer.write_all(format! {"{} ", self.transform.size}.as_bytes())?;
         writer.write_all(Self::TF_OPERATOR)?;
         writer.write_all(constants::NL_MARKER)?;
 
-        // posx posy Td
-        writer.write_all(
-            format!(
-                "{} {} ",
-                self.transform.position.x, self.transform.position.y
-            )
-            .as_bytes(),
-        )?;
-        writer.write_all(Self::TD_OPERATOR)?;
-        writer.write_all(constants::NL_MARKER)?;
+        match &self.run {
+            #[cfg(feature = "composite-fonts")]
+            _ if self.shaped.is_some() => self.write_shaped(&mut writer)?,
+            Some(run) if self.bounds.is_none() => {
+                // posx posy Td
+                let Position { x, y } = self.first_baseline();
+                let width = self.width(&self.content.to_text(), run.adjustment(&self.metrics));
+                let offset = self.alignment.offset(-width);
+                self.transform.write_position(&mut writer, x, y, offset)?;
 
-        // (Text) Tj
-        self.content.write_content(&mut writer)?;
-        writer.write_all(constants::SP)?;
-        writer.write_all(Self::TJ_OPERATOR)?;
-        writer.write_all(constants::NL_MARKER)?;
+                // [(Te) 80 (xt)] TJ
+                run.write(&mut writer, self.encoding, &font_name, self.transform.size)?;
+                writer.write_all(constants::NL_MARKER)?;
+            }
+            _ => self.write_lines(&mut writer)?,
+        }
+
+        // the rendering mode is part of the text state, which outlives the text object, so it's
+        // reset for text drawn afterwards. The clipping path is only applied at ET, with the
+        // glyphs that were shown in a clipping mode.
+        if self.rendering_mode != TextRenderingMode::Fill {
+            writer.write_all(format!("{} ", TextRenderingMode::Fill.operand()).as_bytes())?;
+            writer.write_all(Self::TR_OPERATOR)?;
+            writer.write_all(constants::NL_MARKER)?;
+        }
 
         // ET
         writer.write_all(Self::ET_MARKER)?;
         writer.write_all(constants::NL_MARKER)?;
 
+        // q color width w x1 y1 m x2 y2 l S Q
+        self.write_decorations(&mut writer)?;
+
+        // EMC
+        if self.language.is_some() || actual_text.is_some() {
+            writer.write_all(Self::EMC_OPERATOR)?;
+            writer.write_all(constants::NL_MARKER)?;
+        }
+
         Ok(writer)
     }
 }
 
+impl Text {
+    /// Returns the given line of this `Text` as the string it's shown with, in visual order and
+    /// encoded with the encoding of its font.
+    fn shown(&self, line: &str) -> PdfString {
+        PdfString::from(bidi::visual_order(line, self.direction)).encoded(self.encoding)
+    }
+
+    /// Depth of the descenders of glyphs below the baseline, as a fraction of the font size.
+    const DESCENT: f32 = 0.2;
+
+    /// Returns the rectangle containing everything painted by this `Text`, estimated with the
+    /// metrics of its font, with glyphs reaching from the font size above the
+    /// baseline to [`Text::DESCENT`] below it. Lines laid out in a box span the whole width of the
+    /// box.
+    pub(crate) fn extent(&self) -> Rectangle {
+        let size = self.transform.size as f32;
+        let Position { x, y } = self.first_baseline();
+        let ([left, right], lines) = match (self.bounds, &self.run) {
+            #[cfg(feature = "composite-fonts")]
+            _ if self.shaped.is_some() => {
+                let (offset, width) = self.shaped_span();
+                ([offset, offset + width], 1)
+            }
+            (Some(rect), _) => {
+                let lines = self.lines().len();
+                ([0.0, rect.width().into_user_unit()], lines)
+            }
+            (None, Some(run)) => {
+                let width = self.width(&self.content.to_text(), run.adjustment(&self.metrics));
+                let offset = self.alignment.offset(-width);
+                ([offset, offset + width], 1)
+            }
+            (None, None) => {
+                let lines = self.lines();
+                let layout = self.layout();
+                let spans = lines.iter().map(|line| {
+                    let width = layout.width(&line.text, line.indent, self.transform.size);
+                    let offset = self.alignment.offset(-width);
+                    [offset, offset + width]
+                });
+                let [left, right] = spans.fold([f32::MAX, f32::MIN], |[left, right], span| {
+                    [left.min(span[0]), right.max(span[1])]
+                });
+                ([left, right], lines.len())
+            }
+        };
+
+        // following lines are moved along the axes of the text matrix, so the whole text is
+        // transformed around the start of its first line
+        let bottom = -(lines.saturating_sub(1) as f32) * self.leading() - size * Self::DESCENT;
+        let [a, b, c, d] = self.transform.matrix.unwrap_or([1.0, 0.0, 0.0, 1.0]);
+        let corners = [left, right].into_iter().flat_map(|u| {
+            [bottom, size].map(|v| {
+                Position::new(
+                    x + Unit::from_unit(a * u + c * v),
+                    y + Unit::from_unit(b * u + d * v),
+                )
+            })
+        });
+
+        Rectangle::enclosing(corners).expect("A text always has four corners.")
+    }
+
+    /// Returns the width of the given line of this `Text` in user space units, with the given
+    /// adjustment in thousandths of the font size, measured with the metrics of its font.
+    fn width(&self, line: &str, adjustment: f32) -> f32 {
+        (self.metrics.text_width(line) - adjustment) * self.transform.size as f32 / 1000.0
+    }
+
+    /// Returns the distance between the baselines of consecutive lines of this `Text`, in user
+    /// space units.
+    fn leading(&self) -> f32 {
+        self.leading.map_or(
+            TextBox::default_leading(self.transform.size),
+            Unit::into_user_unit,
+        )
+    }
+
+    /// Returns the [`TextAnchor`] of this `Text`, which defaults to the first baseline for text at
+    /// a position, and to the top of the box for text laid out in a box.
+    fn anchor(&self) -> TextAnchor {
+        self.anchor.unwrap_or(match self.bounds {
+            Some(_) => TextAnchor::Top,
+            None => TextAnchor::FirstBaseline,
+        })
+    }
+
+    /// Returns the start of the first baseline of this `Text`, at the left edge of its box or at
+    /// its position, before the first line is aligned.
+    fn first_baseline(&self) -> Position {
+        let Position { x, y } = match self.bounds {
+            Some(rect) => Position::new(rect.low_left().x, rect.top_right().y),
+            None => self.transform.position,
+        };
+
+        match self.anchor() {
+            TextAnchor::FirstBaseline => Position::new(x, y),
+            // the first baseline lies one font size below the top of the first line
+            TextAnchor::Top => Position::new(x, y - Unit::from_unit(self.transform.size as f32)),
+        }
+    }
+
+    /// Returns the replacement text of the glyphs shown by this `Text`, which is set for a
+    /// [`ShapedRun`] only, as its glyphs can't be mapped back to text.
+    fn actual_text(&self) -> Option<&str> {
+        #[cfg(feature = "composite-fonts")]
+        if let Some(shaped) = &self.shaped {
+            return shaped.text();
+        }
+
+        None
+    }
+
+    /// Returns the offset of the [`ShapedRun`] of this `Text` from its first baseline, aligned
+    /// relative to its position or inside of its box, and its width, in user space units.
+    #[cfg(feature = "composite-fonts")]
+    fn shaped_span(&self) -> (f32, f32) {
+        let width = self.shaped.as_ref().map_or(0.0, |shaped| {
+            shaped.width() * self.transform.size as f32 / 1000.0
+        });
+        let box_width = self
+            .bounds
+            .map_or(0.0, |rect| rect.width().into_user_unit());
+
+        (self.alignment.offset(box_width - width), width)
+    }
+
+    /// Writes the [`ShapedRun`] of this `Text` on a single line at its first baseline.
+    #[cfg(feature = "composite-fonts")]
+    fn write_shaped(&self, writer: &mut Vec<u8>) -> io::Result<()> {
+        let Some(shaped) = &self.shaped else {
+            return Ok(());
+        };
+
+        // posx posy Td
+        let Position { x, y } = self.first_baseline();
+        let (offset, _) = self.shaped_span();
+        self.transform.write_position(writer, x, y, offset)?;
+
+        // [<0024> -600 <0025>] TJ
+        shaped.write(writer, self.transform.size)?;
+
+        Ok(())
+    }
+
+    /// Returns the [`LineLayout`] of this `Text`, with its indentation, tab stops and font metrics.
+    fn layout(&self) -> LineLayout<'_> {
+        LineLayout {
+            indent: self.indent.into_user_unit(),
+            first_line_indent: self.first_line_indent.into_user_unit(),
+            tab_stops: &self.tab_stops,
+            metrics: &self.metrics,
+        }
+    }
+
+    /// Returns the lines of this `Text`, broken to fit its box if it has one, or at explicit line
+    /// breaks only otherwise.
+    fn lines(&self) -> Vec<Line> {
+        let content = self.content.to_text();
+        let Some(rect) = self.bounds else {
+            // every line starts a paragraph
+            return content
+                .split('\n')
+                .map(|line| Line {
+                    text: line.to_string(),
+                    ends_paragraph: true,
+                    indent: self.layout().start(true),
+                })
+                .collect();
+        };
+
+        let text_box = TextBox {
+            rect,
+            overflow: self.overflow,
+            line_breaker: self.line_breaker.as_deref().unwrap_or(&SpaceBreaker),
+            leading: self.leading(),
+            first_baseline: (rect.top_right().y - self.first_baseline().y).into_user_unit(),
+            layout: self.layout(),
+        };
+        text_box.lines(&content, self.transform.size)
+    }
+
+    /// Writes the lines of this `Text`, each with a separate show-text operation for every piece
+    /// between its tab characters. Lines are aligned inside of the box of this `Text`, or relative
+    /// to its position.
+    fn write_lines(&self, writer: &mut Vec<u8>) -> io::Result<()> {
+        let Position { x, y } = self.first_baseline();
+        let box_width = self
+            .bounds
+            .map_or(0.0, |rect| rect.width().into_user_unit());
+        let layout = self.layout();
+        let mut previous_offset = 0.0;
+        let mut word_spacing = 0.0;
+
+        for (idx, line) in self.lines().iter().enumerate() {
+            let segments = layout.segments(&line.text, line.indent, self.transform.size);
+            let width = layout.width(&line.text, line.indent, self.transform.size);
+            let free_space = box_width - width;
+            let offset = self.line_offset(line, free_space);
+
+            // spacing Tw
+            let spacing = match self.alignment {
+                // lines with tab stops are aligned by their tab stops instead
+                TextAlignment::Justify if segments.len() == 1 => {
+                    Self::word_spacing(line, free_space)
+                }
+                _ => 0.0,
+            };
+            if spacing != word_spacing {
+                writer.write_all(format!("{spacing} ").as_bytes())?;
+                writer.write_all(Self::TW_OPERATOR)?;
+                writer.write_all(constants::NL_MARKER)?;
+                word_spacing = spacing;
+            }
+
+            // empty pieces after tab characters aren't shown, but the first piece always is, as it
+            // moves to the start of the line
+            let shown = segments
+                .iter()
+                .enumerate()
+                .filter(|(idx, (segment, _))| *idx == 0 || !segment.is_empty())
+                .map(|(_, segment)| segment);
+            for (segment_idx, (segment, segment_offset)) in shown.enumerate() {
+                let segment_offset = round(offset + segment_offset);
+
+                // offset -leading Td
+                if idx == 0 && segment_idx == 0 {
+                    self.transform
+                        .write_position(writer, x, y, segment_offset)?;
+                } else {
+                    let shift = round(segment_offset - previous_offset);
+                    let leading = match segment_idx {
+                        0 => format!("-{}", round(self.leading())),
+                        _ => "0".to_string(),
+                    };
+                    writer.write_all(format!("{shift} {leading} ").as_bytes())?;
+                    writer.write_all(Self::TD_OPERATOR)?;
+                    writer.write_all(constants::NL_MARKER)?;
+                }
+                previous_offset = segment_offset;
+
+                // (Line) Tj
+                self.shown(segment).write_content(writer)?;
+                writer.write_all(constants::SP)?;
+                writer.write_all(Self::TJ_OPERATOR)?;
+                writer.write_all(constants::NL_MARKER)?;
+            }
+        }
+
+        // like the rendering mode, word spacing outlives the text object
+        if word_spacing != 0.0 {
+            writer.write_all(b"0 ")?;
+            writer.write_all(Self::TW_OPERATOR)?;
+            writer.write_all(constants::NL_MARKER)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the offset of the start of the given [`Line`] from the first baseline, given the
+    /// space left free by the line.
+    fn line_offset(&self, line: &Line, free_space: f32) -> f32 {
+        match (self.alignment, self.direction) {
+            // the last line of a justified right-to-left paragraph starts at the right edge
+            (TextAlignment::Justify, TextDirection::RightToLeft) if line.ends_paragraph => {
+                free_space
+            }
+            (alignment, _) => alignment.offset(free_space),
+        }
+    }
+
+    /// Returns the start and end of the shown part of every line of this `Text` along its
+    /// baseline, relative to the first baseline, in user space units.
+    fn line_spans(&self) -> Vec<[f32; 2]> {
+        #[cfg(feature = "composite-fonts")]
+        if self.shaped.is_some() {
+            let (offset, width) = self.shaped_span();
+            return vec![[offset, offset + width]];
+        }
+
+        if let (None, Some(run)) = (self.bounds, &self.run) {
+            let width = self.width(&self.content.to_text(), run.adjustment(&self.metrics));
+            let offset = self.alignment.offset(-width);
+            return vec![[offset, offset + width]];
+        }
+
+        let box_width = self
+            .bounds
+            .map_or(0.0, |rect| rect.width().into_user_unit());
+        let layout = self.layout();
+        self.lines()
+            .iter()
+            .map(|line| {
+                let width = layout.width(&line.text, line.indent, self.transform.size);
+                let free_space = box_width - width;
+
+                // justified lines are stretched to the edges of the box, unless they have tab stops
+                let justified = self.alignment == TextAlignment::Justify
+                    && !line.text.contains('\t')
+                    && Self::word_spacing(line, free_space) != 0.0;
+                match justified {
+                    true => [line.indent, box_width],
+                    false => {
+                        let offset = self.line_offset(line, free_space);
+                        [offset + line.indent, offset + width]
+                    }
+                }
+            })
+            .collect()
+    }
+
+    /// Writes the [`TextDecoration`]s of this `Text` as a path stroked along every non-empty line,
+    /// in the color of the text. Nothing is written for invisible text or text used for clipping.
+    fn write_decorations(&self, writer: &mut Vec<u8>) -> io::Result<()> {
+        let hidden = self.rendering_mode == TextRenderingMode::Invisible
+            || self.rendering_mode.is_clipping();
+        if self.decorations.is_empty() || hidden {
+            return Ok(());
+        }
+
+        // decorations follow the lines of the text, transformed around its first baseline
+        let size = self.transform.size as f32;
+        let Position { x, y } = self.first_baseline();
+        let [a, b, c, d] = self.transform.matrix.unwrap_or([1.0, 0.0, 0.0, 1.0]);
+        let (x, y) = (x.into_user_unit(), y.into_user_unit());
+        let point = |u: f32, v: f32| {
+            Position::new(
+                Unit::from_unit(round(x + a * u + c * v)),
+                Unit::from_unit(round(y + b * u + d * v)),
+            )
+        };
+
+        let metrics = self.metrics.vertical();
+        let mut path = Path::new();
+        for (idx, [start, end]) in self.line_spans().into_iter().enumerate() {
+            if end <= start {
+                continue;
+            }
+
+            let baseline = -(idx as f32) * self.leading();
+            for decoration in &self.decorations {
+                let v = baseline + decoration.position(&metrics) * size / 1000.0;
+                path = path.move_to(point(start, v)).line_to(point(end, v));
+            }
+        }
+
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        writer.write_all(b"q")?;
+        writer.write_all(constants::NL_MARKER)?;
+        self.color.write_stroke(writer)?;
+        writer.write_all(
+            format!("{} ", round(size * metrics.underline_thickness / 1000.0)).as_bytes(),
+        )?;
+        writer.write_all(Self::W_OPERATOR)?;
+        writer.write_all(constants::NL_MARKER)?;
+        path.write_stroke(writer)?;
+        writer.write_all(b"Q")?;
+        writer.write_all(constants::NL_MARKER)?;
+
+        Ok(())
+    }
+
+    /// Returns the word spacing stretching the given justified [`Line`] by its `free_space`, which
+    /// is added to each space of the line. The last line of a paragraph isn't stretched.
+    fn word_spacing(line: &Line, free_space: f32) -> f32 {
+        let spaces = line.text.matches(' ').count();
+        if line.ends_paragraph || spaces == 0 || free_space <= 0.0 {
+            return 0.0;
+        }
+
+        round(free_space / spaces as f32)
+    }
+}
+
 /// A builder for constructing a [`Text`] object, allowing incremental modifications.
 /// The `IS_INIT` const generic tracks whether initialization has been completed (if position has
 /// been set).
@@ -128,15 +967,77 @@ pub struct TextBuilder<const IS_INIT: bool> {
 
 impl<const IS_INIT: bool> TextBuilder<IS_INIT> {
     /// Sets the position of the [`Text`] on a page, after which the building of the [`Text`]
-    /// object is allowed.
+    /// object is allowed. The baseline of the first line starts at the position, unless set
+    /// otherwise with [`TextBuilder::with_anchor`], and content is broken into lines at line feeds
+    /// (`\n`) only.
     pub fn at(mut self, pos: Position) -> TextBuilder<true> {
         self.inner.transform.position = pos;
         TextBuilder { inner: self.inner }
     }
 
-    /// Sets the content of the [`Text`].
+    /// Lays out the [`Text`] inside of the given [`Rectangle`], starting at its top left corner.
+    /// The content is broken into lines at spaces, soft hyphens and explicit line breaks, or at the
+    /// breaks set with [`TextBuilder::with_line_breaker`], measured with the metrics of the font
+    /// the text is drawn with. Lines that don't fit the height of the rectangle are handled as set
+    /// with [`TextBuilder::with_overflow`].
+    pub fn in_box(mut self, rect: Rectangle) -> TextBuilder<true> {
+        self.inner.bounds = Some(rect);
+        TextBuilder { inner: self.inner }
+    }
+
+    /// Sets what happens to lines of a [`Text`] laid out with [`TextBuilder::in_box`] that don't
+    /// fit into its rectangle. Defaults to [`Overflow::Truncate`].
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.inner.overflow = overflow;
+        self
+    }
+
+    /// Sets the [`LineBreaker`] finding the points at which the content of a [`Text`] laid out
+    /// with [`TextBuilder::in_box`] is broken into lines, e.g. to hyphenate words with
+    /// [`Hyphenation`]. Defaults to breaking lines at spaces only.
+    ///
+    /// [`Hyphenation`]: super::text_box::Hyphenation
+    pub fn with_line_breaker(mut self, line_breaker: impl LineBreaker + 'static) -> Self {
+        self.inner.line_breaker = Some(Arc::new(line_breaker));
+        self
+    }
+
+    /// Sets the content of the [`Text`], replacing a [`TextRun`] set with
+    /// [`TextBuilder::with_run`].
     pub fn with_content(mut self, content: impl Into<String>) -> Self {
         self.inner.content = PdfString::from(content);
+        self.inner.run = None;
+        #[cfg(feature = "composite-fonts")]
+        {
+            self.inner.shaped = None;
+        }
+        self
+    }
+
+    /// Sets the content of the [`Text`] to the given [`TextRun`], adjusting the position of
+    /// individual glyphs, e.g. for kerning. Adjustments are dropped if the text is laid out with
+    /// [`TextBuilder::in_box`].
+    pub fn with_run(mut self, run: TextRun) -> Self {
+        self.inner.content = PdfString::from(run.content());
+        self.inner.run = Some(run);
+        #[cfg(feature = "composite-fonts")]
+        {
+            self.inner.shaped = None;
+        }
+        self
+    }
+
+    /// Sets the content of the [`Text`] to the given [`ShapedRun`] of glyphs positioned by an
+    /// external shaper, replacing any other content. The glyphs are shown on a single line at the
+    /// position of the text, or at the first baseline of its box, and the text must be drawn with a
+    /// font created with [`Document::create_shaping_font`].
+    ///
+    /// [`Document::create_shaping_font`]: crate::Document::create_shaping_font
+    #[cfg(feature = "composite-fonts")]
+    pub fn with_shaped_run(mut self, run: ShapedRun) -> Self {
+        self.inner.content = PdfString::from(run.text().unwrap_or_default());
+        self.inner.run = None;
+        self.inner.shaped = Some(run);
         self
     }
 
@@ -149,12 +1050,165 @@ impl<const IS_INIT: bool> TextBuilder<IS_INIT> {
     /// Sets the size of the [`Text`].
     pub fn with_size(mut self, size: u32) -> Self {
         self.inner.transform.size = size;
+        self.inner.explicit_style.0 = true;
+        self
+    }
+
+    /// Sets the linear part `[a b c d]` of the text matrix (`Tm`) of the [`Text`], transforming
+    /// it around its position. The translation part of the matrix is the position of the text.
+    pub fn with_matrix(mut self, matrix: [f32; 4]) -> Self {
+        self.inner.transform.matrix = Some(matrix);
+        self
+    }
+
+    /// Rotates the [`Text`] counterclockwise by the given degrees around its position, e.g. for
+    /// vertical labels. The rotation is applied after the transformations already set.
+    pub fn with_rotation(mut self, degrees: f32) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        self.inner.transform.concat([cos, sin, -sin, cos]);
+        self
+    }
+
+    /// Skews the [`Text`] by the given angles in degrees, `x_degrees` slanting the glyphs to the
+    /// right, as for an oblique style, and `y_degrees` slanting the baseline upwards. The skew is
+    /// applied after the transformations already set.
+    pub fn with_skew(mut self, x_degrees: f32, y_degrees: f32) -> Self {
+        let (tan_x, tan_y) = (x_degrees.to_radians().tan(), y_degrees.to_radians().tan());
+        self.inner.transform.concat([1.0, tan_y, tan_x, 1.0]);
+        self
+    }
+
+    /// Sets the horizontal [`TextAlignment`] of the [`Text`] relative to its position, or to its
+    /// box if laid out with [`TextBuilder::in_box`]. Text width is measured with the metrics of
+    /// the font the text is drawn with.
+    pub fn with_alignment(mut self, alignment: TextAlignment) -> Self {
+        self.inner.alignment = alignment;
+        self
+    }
+
+    /// Sets the [`TextDirection`] in which the content of the [`Text`] is read. Right-to-left
+    /// content, e.g. Hebrew or Arabic, is reordered into visual order when it's written, which
+    /// doesn't change its alignment, so it's usually aligned with [`TextAlignment::Right`].
+    pub fn with_direction(mut self, direction: TextDirection) -> Self {
+        self.inner.direction = direction;
+        self
+    }
+
+    /// Sets the distance between the baselines of consecutive lines of the [`Text`], which defaults
+    /// to 120% of the font size. Lines are broken at line feeds (`\n`) in the content, and to fit
+    /// the box of a text laid out with [`TextBuilder::in_box`].
+    pub fn with_leading(mut self, leading: Unit) -> Self {
+        self.inner.leading = Some(leading);
+        self
+    }
+
+    /// Sets the [`TextAnchor`] determining which point of the first line of the [`Text`] is
+    /// placed at its position, or at the top of its box. Following lines are placed below the
+    /// first one.
+    pub fn with_anchor(mut self, anchor: TextAnchor) -> Self {
+        self.inner.anchor = Some(anchor);
+        self
+    }
+
+    /// Sets the indentation of all lines of the [`Text`] from the left edge of its box, or from its
+    /// position.
+    pub fn with_indent(mut self, indent: Unit) -> Self {
+        self.inner.indent = indent;
+        self
+    }
+
+    /// Sets the additional indentation of the first line of each paragraph of the [`Text`], added
+    /// to the indentation set with [`TextBuilder::with_indent`]. A negative value creates a hanging
+    /// indent, e.g. for the items of a list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::{
+    /// #     content::text::Text,
+    /// #     primitives::{rectangle::Rectangle, unit::Unit},
+    /// # };
+    /// let item = Text::builder()
+    ///     .with_content("1.\tThe goods remain our property until paid in full.")
+    ///     .in_box(Rectangle::from_units(20.0, 20.0, 200.0, 120.0))
+    ///     .with_indent(Unit::from_pt(18.0))
+    ///     .with_first_line_indent(Unit::from_pt(-18.0))
+    ///     .build();
+    /// ```
+    pub fn with_first_line_indent(mut self, indent: Unit) -> Self {
+        self.inner.first_line_indent = indent;
+        self
+    }
+
+    /// Sets the [`TabStop`]s at which the text following tab characters (`\t`) in the content of
+    /// the [`Text`] is aligned, e.g. to lay out simple columns or forms. Tab characters after the
+    /// last tab stop move to the next multiple of half an inch.
+    pub fn with_tab_stops(mut self, tab_stops: impl IntoIterator<Item = TabStop>) -> Self {
+        self.inner.tab_stops = tab_stops.into_iter().collect();
+        self.inner.tab_stops.sort_by(|a, b| {
+            a.position()
+                .into_points()
+                .total_cmp(&b.position().into_points())
+        });
         self
     }
 
     /// Sets the color of the [`Text`].
     pub fn with_color(mut self, color: Color) -> Self {
         self.inner.color = color;
+        self.inner.explicit_style.1 = true;
+        self
+    }
+
+    /// Sets the color used to stroke the glyph outlines of the [`Text`]. Outlines are only stroked
+    /// with a [`TextRenderingMode`] that strokes.
+    pub fn with_stroke_color(mut self, color: Color) -> Self {
+        self.inner.stroke_color = Some(color);
+        self
+    }
+
+    /// Sets the width of the line used to stroke the glyph outlines of the [`Text`], in user space
+    /// units.
+    pub fn with_stroke_width(mut self, width: f32) -> Self {
+        self.inner.stroke_width = Some(width);
+        self
+    }
+
+    /// Underlines every line of the [`Text`]. See [`TextDecoration::Underline`].
+    pub fn with_underline(self) -> Self {
+        self.with_decoration(TextDecoration::Underline)
+    }
+
+    /// Strikes through every line of the [`Text`]. See [`TextDecoration::Strikethrough`].
+    pub fn with_strikethrough(self) -> Self {
+        self.with_decoration(TextDecoration::Strikethrough)
+    }
+
+    /// Adds the given [`TextDecoration`] to the [`Text`], unless it already has it.
+    fn with_decoration(mut self, decoration: TextDecoration) -> Self {
+        if !self.inner.decorations.contains(&decoration) {
+            self.inner.decorations.push(decoration);
+        }
+        self
+    }
+
+    /// Sets the [`TextRenderingMode`] of the [`Text`], e.g. to draw outlined or invisible text.
+    pub fn with_rendering_mode(mut self, mode: TextRenderingMode) -> Self {
+        self.inner.rendering_mode = mode;
+        self
+    }
+
+    /// Sets the [`ExtGState`] that should be applied when rendering the [`Text`], for example to
+    /// make it semi-transparent.
+    pub fn with_ext_g_state(mut self, ext_g_state: ExtGState) -> Self {
+        self.inner.ext_g_state = Some(ext_g_state);
+        self
+    }
+
+    /// Sets the natural [`Language`] of the [`Text`] content, used by screen readers and for text
+    /// extraction of multilingual documents.
+    pub fn with_language(mut self, language: Language) -> Self {
+        self.inner.language = Some(language);
         self
     }
 }
@@ -168,9 +1222,21 @@ impl TextBuilder<true> {
 
 #[cfg(test)]
 mod tests {
-    use crate::types::hierarchy::{content::text::Identifier, primitives::rectangle::Position};
+    use std::str::FromStr;
+
+    use crate::types::hierarchy::{
+        content::bidi::TextDirection,
+        content::text_box::{Overflow, TabStop},
+        content::{color::Color, text::Identifier},
+        primitives::{
+            font_metrics::{FontMetrics, Metrics},
+            language::Language,
+            rectangle::{Position, Rectangle},
+            unit::Unit,
+        },
+    };
 
-    use super::Text;
+    use super::{Text, TextAlignment, TextAnchor, TextRenderingMode, TextRun};
 
     #[test]
     pub fn default_text() {
@@ -214,4 +1280,516 @@ mod tests {
         ET
         ");
     }
+
+    #[test]
+    pub fn text_with_language() {
+        let txt = Text::builder()
+            .with_content("Guten Tag")
+            .with_language(Language::from_str("de-AT").unwrap())
+            .at(Position::from_mm(0.0, 0.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        /Span << /Lang (de-AT) >> BDC
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        0 0 Td
+        (Guten Tag) Tj
+        ET
+        EMC
+        ");
+    }
+
+    #[test]
+    pub fn outlined_text() {
+        let txt = Text::builder()
+            .with_content("Outlined")
+            .with_color(Color::Gray(255))
+            .with_stroke_color(Color::Rgb {
+                red: 255,
+                green: 0,
+                blue: 0,
+            })
+            .with_stroke_width(0.5)
+            .with_rendering_mode(TextRenderingMode::FillStroke)
+            .at(Position::from_mm(0.0, 0.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceGray cs
+        1 sc
+        /DeviceRGB CS
+        1 0 0 SC
+        0.5 w
+        2 Tr
+        /F1 12 Tf
+        0 0 Td
+        (Outlined) Tj
+        0 Tr
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn invisible_text() {
+        let txt = Text::builder()
+            .with_content("Scanned")
+            .with_rendering_mode(TextRenderingMode::Invisible)
+            .at(Position::from_mm(0.0, 0.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        3 Tr
+        /F1 12 Tf
+        0 0 Td
+        (Scanned) Tj
+        0 Tr
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn clipping_modes() {
+        assert_eq!(
+            TextRenderingMode::Fill.clipping(),
+            TextRenderingMode::FillClip
+        );
+        assert_eq!(
+            TextRenderingMode::Invisible.clipping(),
+            TextRenderingMode::Clip
+        );
+        assert_eq!(
+            TextRenderingMode::StrokeClip.clipping(),
+            TextRenderingMode::StrokeClip
+        );
+        assert!(!TextRenderingMode::Invisible.is_clipping());
+        assert!(TextRenderingMode::Clip.is_clipping());
+    }
+
+    #[test]
+    pub fn text_in_box() {
+        let txt = Text::builder()
+            .with_content("The quick brown fox jumps over the lazy dog")
+            .with_overflow(Overflow::Ellipsis)
+            .in_box(Rectangle::from_units(10.0, 10.0, 110.0, 40.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        10 28 Td
+        (The quick brown) Tj
+        0 -14.4 Td
+        (fox jumps over t...) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn multi_line_text() {
+        let at_position = Text::builder()
+            .with_content("Total\nVAT included")
+            .with_size(10)
+            .with_leading(Unit::from_unit(15.0))
+            .with_anchor(TextAnchor::Top)
+            .with_alignment(TextAlignment::Right)
+            .at(Position::from_units(100.0, 50.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+        let in_box = Text::builder()
+            .with_content("The quick brown fox jumps over the lazy dog")
+            .with_size(10)
+            .with_leading(Unit::from_unit(20.0))
+            .with_anchor(TextAnchor::FirstBaseline)
+            .in_box(Rectangle::from_units(0.0, 0.0, 100.0, 30.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&at_position) + String::from_utf8_lossy(&in_box);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 10 Tf
+        77.770004 40 Td
+        (Total) Tj
+        -37.24 -15 Td
+        (VAT included) Tj
+        ET
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 10 Tf
+        0 30 Td
+        (The quick brown fox) Tj
+        0 -20 Td
+        (jumps over the lazy) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn rotated_text() {
+        let txt = Text::builder()
+            .with_content("Vertical")
+            .with_rotation(90.0)
+            .at(Position::from_units(20.0, 10.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        0 1 -1 0 20 10 Tm
+        (Vertical) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn skewed_text() {
+        let txt = Text::builder()
+            .with_content("Slanted")
+            .with_skew(45.0, 0.0)
+            .with_rotation(180.0)
+            .at(Position::from_units(20.0, 10.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        -1 0 -1 -1 20 10 Tm
+        (Slanted) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn kerned_text() {
+        let txt = Text::builder()
+            .with_run(TextRun::new().text("A").adjust(80.0).text("VANT"))
+            .with_expanded_content("-GARDE")
+            .at(Position::from_units(20.0, 10.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        20 10 Td
+        [(A) 80 (VANT) (-GARDE)] TJ
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn aligned_text() {
+        let builder = Text::builder()
+            .with_content("Total")
+            .at(Position::from_units(100.0, 10.0));
+
+        let right = builder
+            .clone()
+            .with_alignment(TextAlignment::Right)
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+        let centered_box = builder
+            .with_content("Centered in a box, over two lines")
+            .with_alignment(TextAlignment::Center)
+            .in_box(Rectangle::from_units(0.0, 0.0, 100.0, 40.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&right) + String::from_utf8_lossy(&centered_box);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        73.324 10 Td
+        (Total) Tj
+        ET
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        0.974 28 Td
+        (Centered in a box,) Tj
+        12.348 -14.4 Td
+        (over two lines) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn aligned_text_with_font_metrics() {
+        let output: String = [b"Times-Roman".as_slice(), b"Courier"]
+            .into_iter()
+            .map(|font| {
+                let mut text = Text::builder()
+                    .with_content("Total")
+                    .with_alignment(TextAlignment::Right)
+                    .at(Position::from_units(100.0, 10.0))
+                    .build();
+                text.set_metrics(FontMetrics::Simple(Metrics::of(font).unwrap()));
+
+                let bytes = text.to_bytes(Identifier::from_static(b"F1")).unwrap();
+                String::from_utf8(bytes).unwrap()
+            })
+            .collect();
+
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        74.668 10 Td
+        (Total) Tj
+        ET
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        64 10 Td
+        (Total) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn right_aligned_monospaced_text_in_box() {
+        let mut text = Text::builder()
+            .with_content("The quick brown fox jumps over the lazy dog")
+            .with_alignment(TextAlignment::Right)
+            .in_box(Rectangle::from_units(0.0, 0.0, 100.0, 100.0))
+            .build();
+        text.set_metrics(FontMetrics::Simple(Metrics::of(b"Courier").unwrap()));
+
+        let output = text.to_bytes(Identifier::from_static(b"F1")).unwrap();
+        insta::assert_snapshot!(String::from_utf8(output).unwrap(), @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        35.2 88 Td
+        (The quick) Tj
+        0 -14.4 Td
+        (brown fox) Tj
+        -7.2 -14.4 Td
+        (jumps over) Tj
+        -14.4 -14.4 Td
+        (the lazy dog) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn right_to_left_text() {
+        // Hebrew letters aren't covered by WinAnsiEncoding, and are shown as `?`
+        let txt = Text::builder()
+            .with_content("מחיר (VAT) 12.50\nשלום")
+            .with_direction(TextDirection::RightToLeft)
+            .with_alignment(TextAlignment::Justify)
+            .in_box(Rectangle::from_units(0.0, 0.0, 100.0, 60.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        5.284 48 Td
+        (12.50 \(VAT\) ????) Tj
+        68.028 -14.4 Td
+        (????) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn justified_text() {
+        let txt = Text::builder()
+            .with_content("The quick brown fox jumps over the lazy dog.\nThe end")
+            .with_alignment(TextAlignment::Justify)
+            .in_box(Rectangle::from_units(0.0, 0.0, 100.0, 60.0))
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        5.984 Tw
+        0 48 Td
+        (The quick brown) Tj
+        0.65333 Tw
+        0 -14.4 Td
+        (fox jumps over the) Tj
+        0 Tw
+        0 -14.4 Td
+        (lazy dog.) Tj
+        0 -14.4 Td
+        (The end) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn tabulated_text() {
+        let txt = Text::builder()
+            .with_content("Item\tQty\tPrice\nCoffee\t2\t5.00\nBagel\t1\t11.00")
+            .in_box(Rectangle::from_units(20.0, 20.0, 220.0, 120.0))
+            .with_first_line_indent(Unit::from_unit(10.0))
+            .with_tab_stops([
+                TabStop::right(Unit::from_unit(200.0)),
+                TabStop::center(Unit::from_unit(120.0)),
+            ])
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        30 108 Td
+        (Item) Tj
+        100.664 0 Td
+        (Qty) Tj
+        62 0 Td
+        (Price) Tj
+        -162.664 -14.4 Td
+        (Coffee) Tj
+        106.664 0 Td
+        (2) Tj
+        59.984 0 Td
+        (5.00) Tj
+        -166.648 -14.4 Td
+        (Bagel) Tj
+        106.664 0 Td
+        (1) Tj
+        53.312 0 Td
+        (11.00) Tj
+        ET
+        ");
+    }
+
+    #[test]
+    pub fn decorated_text_with_font_metrics() {
+        let mut text = Text::builder()
+            .with_content("Sale")
+            .at(Position::from_units(20.0, 100.0))
+            .with_size(10)
+            .with_underline()
+            .with_strikethrough()
+            .build();
+        text.set_metrics(FontMetrics::Simple(Metrics::of(b"Times-Roman").unwrap()));
+
+        let output = text.to_bytes(Identifier::from_static(b"F1")).unwrap();
+        insta::assert_snapshot!(String::from_utf8(output).unwrap(), @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 10 Tf
+        20 100 Td
+        (Sale) Tj
+        ET
+        q
+        /DeviceRGB CS
+        0 0 0 SC
+        0.5 w
+        20 99 m
+        37.22 99 l
+        20 102.25 m
+        37.22 102.25 l
+        S
+        Q
+        ");
+    }
+
+    #[test]
+    pub fn decorated_text() {
+        let txt = Text::builder()
+            .with_content("Sale\nnow")
+            .at(Position::from_units(20.0, 100.0))
+            .with_size(10)
+            .with_alignment(TextAlignment::Center)
+            .with_underline()
+            .with_strikethrough()
+            .build()
+            .to_bytes(Identifier::from_static(b"F1"))
+            .unwrap();
+
+        let output = String::from_utf8_lossy(&txt);
+        insta::assert_snapshot!(output, @r"
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 10 Tf
+        9.995 100 Td
+        (Sale) Tj
+        0.835 -12 Td
+        (now) Tj
+        ET
+        q
+        /DeviceRGB CS
+        0 0 0 SC
+        0.5 w
+        9.995 99 m
+        30.005 99 l
+        9.995 102.615 m
+        30.005 102.615 l
+        10.83 87 m
+        29.17 87 l
+        10.83 90.615 m
+        29.17 90.615 l
+        S
+        Q
+        ");
+    }
 }
@@ -0,0 +1,770 @@
+//! Line breaking of [`Text`] inside of a bounding rectangle.
+//!
+//! [`Text`]: super::text::Text
+
+use std::fmt;
+
+use crate::types::hierarchy::primitives::{
+    encoding::SOFT_HYPHEN, font_metrics::FontMetrics, rectangle::Rectangle, unit::Unit,
+};
+
+/// Determines what happens to lines of a [`Text`] that don't fit into its bounding rectangle.
+///
+/// [`Text`]: super::text::Text
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Lines that don't fit are dropped.
+    #[default]
+    Truncate,
+
+    /// Lines that don't fit are dropped, and the last visible line ends with an ellipsis.
+    Ellipsis,
+
+    /// All lines are drawn, extending below the bounding rectangle.
+    Grow,
+}
+
+/// Alignment of the text following a tab character at a [`TabStop`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabAlignment {
+    /// The text starts at the tab stop.
+    #[default]
+    Left,
+
+    /// The text is centered on the tab stop.
+    Center,
+
+    /// The text ends at the tab stop, e.g. to align a column of amounts.
+    Right,
+}
+
+/// A position in a line of a [`Text`] at which the text following a tab character (`\t`) is
+/// aligned, measured from the left edge of its box or from its position. Tab characters after the
+/// last tab stop move to the next multiple of half an inch.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::{
+/// #     content::{text::Text, text_box::TabStop},
+/// #     primitives::{rectangle::Rectangle, unit::Unit},
+/// # };
+/// // a column of names followed by a column of right-aligned amounts
+/// let text = Text::builder()
+///     .with_content("Coffee\t2.50\nBagel with cream cheese\t11.00")
+///     .in_box(Rectangle::from_units(20.0, 20.0, 220.0, 120.0))
+///     .with_tab_stops([TabStop::right(Unit::from_pt(200.0))])
+///     .build();
+/// ```
+///
+/// [`Text`]: super::text::Text
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TabStop {
+    /// Distance of the tab stop from the start of the line.
+    position: Unit,
+
+    /// Alignment of the text following the tab character at the tab stop.
+    alignment: TabAlignment,
+}
+
+impl TabStop {
+    /// Distance between the default tab stops after the last set tab stop, in user space units.
+    const DEFAULT_INTERVAL: f32 = 36.0;
+
+    /// Creates a new `TabStop` at the given position with the given [`TabAlignment`].
+    pub fn new(position: Unit, alignment: TabAlignment) -> Self {
+        Self {
+            position,
+            alignment,
+        }
+    }
+
+    /// Creates a new `TabStop` at the given position, at which the following text starts.
+    pub fn left(position: Unit) -> Self {
+        Self::new(position, TabAlignment::Left)
+    }
+
+    /// Creates a new `TabStop` at the given position, on which the following text is centered.
+    pub fn center(position: Unit) -> Self {
+        Self::new(position, TabAlignment::Center)
+    }
+
+    /// Creates a new `TabStop` at the given position, at which the following text ends.
+    pub fn right(position: Unit) -> Self {
+        Self::new(position, TabAlignment::Right)
+    }
+
+    /// Returns the distance of this `TabStop` from the start of the line.
+    pub(crate) fn position(&self) -> Unit {
+        self.position
+    }
+}
+
+/// Indentation and tab stops of the lines of a [`Text`], in user space units, and the metrics of
+/// the font the lines are measured with.
+///
+/// [`Text`]: super::text::Text
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct LineLayout<'a> {
+    /// Indentation of all lines from the start of the line.
+    pub(crate) indent: f32,
+
+    /// Additional indentation of the first line of each paragraph, negative for a hanging indent.
+    pub(crate) first_line_indent: f32,
+
+    /// Tab stops of the lines, ordered by their positions.
+    pub(crate) tab_stops: &'a [TabStop],
+
+    /// Metrics of the font the lines are drawn with.
+    pub(crate) metrics: &'a FontMetrics,
+}
+
+impl LineLayout<'_> {
+    /// Returns the indentation of a line, which depends on whether it starts a paragraph.
+    pub(crate) fn start(&self, starts_paragraph: bool) -> f32 {
+        match starts_paragraph {
+            true => self.indent + self.first_line_indent,
+            false => self.indent,
+        }
+    }
+
+    /// Returns the pieces of the given line between its tab characters, each with its offset from
+    /// the start of the line, for a line with the given indentation drawn in the given font size.
+    pub(crate) fn segments<'t>(&self, line: &'t str, start: f32, size: u32) -> Vec<(&'t str, f32)> {
+        let size = size as f32;
+        let mut segments = Vec::new();
+        let mut end = start;
+
+        for (idx, segment) in line.split('\t').enumerate() {
+            let width = self.metrics.text_width(segment) * size / 1000.0;
+            let offset = if idx == 0 {
+                start
+            } else {
+                let stop = self
+                    .tab_stops
+                    .iter()
+                    .find(|stop| stop.position.into_user_unit() > end);
+                let offset = match stop {
+                    Some(stop) => {
+                        let position = stop.position.into_user_unit();
+                        match stop.alignment {
+                            TabAlignment::Left => position,
+                            TabAlignment::Center => position - width / 2.0,
+                            TabAlignment::Right => position - width,
+                        }
+                    }
+                    None => {
+                        let interval = TabStop::DEFAULT_INTERVAL;
+                        ((end / interval).floor() + 1.0) * interval
+                    }
+                };
+
+                // text aligned at a tab stop never overlaps the text before it
+                offset.max(end)
+            };
+
+            segments.push((segment, offset));
+            end = offset + width;
+        }
+
+        segments
+    }
+
+    /// Returns the width of the given line from its start to the end of its last piece, for a line
+    /// with the given indentation drawn in the given font size.
+    pub(crate) fn width(&self, line: &str, start: f32, size: u32) -> f32 {
+        let (segment, offset) = self
+            .segments(line, start, size)
+            .pop()
+            .expect("A line always has at least one segment.");
+
+        offset + self.metrics.text_width(segment) * size as f32 / 1000.0
+    }
+}
+
+/// A point at which a paragraph of a [`Text`] may be broken into lines, returned by a
+/// [`LineBreaker`].
+///
+/// [`Text`]: super::text::Text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineBreak {
+    /// Byte offset in the paragraph at which the next line starts. Spaces around the offset are
+    /// dropped from both lines.
+    offset: usize,
+
+    /// Whether a hyphen is shown at the end of the line broken here.
+    hyphen: bool,
+}
+
+impl LineBreak {
+    /// Creates a new `LineBreak` at the given byte offset in the paragraph, e.g. at a space.
+    pub fn new(offset: usize) -> Self {
+        Self {
+            offset,
+            hyphen: false,
+        }
+    }
+
+    /// Creates a new `LineBreak` at the given byte offset inside of a word, showing a hyphen at
+    /// the end of the line broken here.
+    pub fn hyphenated(offset: usize) -> Self {
+        Self {
+            offset,
+            hyphen: true,
+        }
+    }
+}
+
+/// Finds the points at which the paragraphs of a [`Text`] laid out with [`TextBuilder::in_box`]
+/// may be broken into lines. Lines are broken at the last point that keeps them within the width
+/// of the box, and words without a fitting point are broken between characters.
+///
+/// Closures taking a paragraph and returning its [`LineBreak`]s implement this trait, and
+/// [`Hyphenation`] integrates hyphenation dictionaries.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::{
+/// #     content::{text::Text, text_box::LineBreak},
+/// #     primitives::rectangle::Rectangle,
+/// # };
+/// // lines may also be broken after slashes, e.g. in paths and URLs
+/// let text = Text::builder()
+///     .with_content("Saved to /home/user/documents/invoices")
+///     .in_box(Rectangle::from_units(20.0, 20.0, 120.0, 120.0))
+///     .with_line_breaker(|paragraph: &str| {
+///         paragraph
+///             .match_indices([' ', '/'])
+///             .map(|(idx, separator)| match separator {
+///                 " " => LineBreak::new(idx),
+///                 _ => LineBreak::new(idx + 1),
+///             })
+///             .collect()
+///     })
+///     .build();
+/// ```
+///
+/// [`Text`]: super::text::Text
+/// [`TextBuilder::in_box`]: super::text::TextBuilder::in_box
+pub trait LineBreaker: Send + Sync {
+    /// Returns the points at which the given paragraph, which has no explicit line breaks, may be
+    /// broken into lines.
+    fn breaks(&self, paragraph: &str) -> Vec<LineBreak>;
+}
+
+impl<F> LineBreaker for F
+where
+    F: Fn(&str) -> Vec<LineBreak> + Send + Sync,
+{
+    fn breaks(&self, paragraph: &str) -> Vec<LineBreak> {
+        self(paragraph)
+    }
+}
+
+impl fmt::Debug for dyn LineBreaker + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("LineBreaker")
+    }
+}
+
+/// Returns the hyphenated [`LineBreak`]s after the soft hyphens (U+00AD) of the given paragraph.
+fn soft_hyphens(paragraph: &str) -> impl Iterator<Item = LineBreak> + '_ {
+    paragraph
+        .match_indices(SOFT_HYPHEN)
+        .map(|(idx, hyphen)| LineBreak::hyphenated(idx + hyphen.len()))
+}
+
+/// The default [`LineBreaker`], which breaks lines at spaces, and inside of words at soft hyphens
+/// (U+00AD) with a hyphen shown at the end of the line. Lines are never broken at no-break spaces
+/// (U+00A0).
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SpaceBreaker;
+
+impl LineBreaker for SpaceBreaker {
+    fn breaks(&self, paragraph: &str) -> Vec<LineBreak> {
+        paragraph
+            .match_indices(' ')
+            .map(|(idx, _)| LineBreak::new(idx))
+            .chain(soft_hyphens(paragraph))
+            .collect()
+    }
+}
+
+/// A [`LineBreaker`] breaking lines at spaces, and inside of words at soft hyphens and at the
+/// hyphenation points returned by the given function, e.g. the breaks found by a hyphenation
+/// dictionary. The function takes a single word and returns the byte offsets in the word at which
+/// it may be hyphenated.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::{
+/// #     content::{text::Text, text_box::Hyphenation},
+/// #     primitives::rectangle::Rectangle,
+/// # };
+/// let text = Text::builder()
+///     .with_content("Donaudampfschifffahrt")
+///     .in_box(Rectangle::from_units(20.0, 20.0, 120.0, 120.0))
+///     .with_line_breaker(Hyphenation::new(|word: &str| match word {
+///         "Donaudampfschifffahrt" => vec![5, 10, 16],
+///         _ => vec![],
+///     }))
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Hyphenation<F> {
+    hyphenate: F,
+}
+
+impl<F> Hyphenation<F>
+where
+    F: Fn(&str) -> Vec<usize> + Send + Sync,
+{
+    /// Creates a new `Hyphenation` with the given function returning the hyphenation points of a
+    /// word.
+    pub fn new(hyphenate: F) -> Self {
+        Self { hyphenate }
+    }
+}
+
+impl<F> LineBreaker for Hyphenation<F>
+where
+    F: Fn(&str) -> Vec<usize> + Send + Sync,
+{
+    fn breaks(&self, paragraph: &str) -> Vec<LineBreak> {
+        let mut breaks = Vec::new();
+        let mut word_start = 0;
+
+        for word in paragraph.split(' ') {
+            if word_start > 0 {
+                breaks.push(LineBreak::new(word_start - 1));
+            }
+
+            let hyphenation_points = (self.hyphenate)(word)
+                .into_iter()
+                .filter(|&idx| idx > 0 && idx < word.len() && word.is_char_boundary(idx));
+            breaks.extend(hyphenation_points.map(|idx| LineBreak::hyphenated(word_start + idx)));
+
+            word_start += word.len() + 1;
+        }
+
+        breaks.extend(soft_hyphens(paragraph));
+        breaks
+    }
+}
+
+/// A single line of a [`Text`] laid out in a [`TextBox`].
+///
+/// [`Text`]: super::text::Text
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Line {
+    /// The content of this line, without the space it was broken at.
+    pub(crate) text: String,
+
+    /// Whether this line is the last line of its paragraph, which is never justified.
+    pub(crate) ends_paragraph: bool,
+
+    /// Indentation of this line, in user space units.
+    pub(crate) indent: f32,
+}
+
+/// Bounding rectangle of a [`Text`], into which its content is broken into lines.
+///
+/// [`Text`]: super::text::Text
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TextBox<'a> {
+    /// The rectangle lines are laid out in, starting at its top left corner.
+    pub(crate) rect: Rectangle,
+
+    /// What happens to lines that don't fit into `rect`.
+    pub(crate) overflow: Overflow,
+
+    /// Finds the points at which paragraphs may be broken into lines.
+    pub(crate) line_breaker: &'a dyn LineBreaker,
+
+    /// Distance between the baselines of consecutive lines, in user space units.
+    pub(crate) leading: f32,
+
+    /// Distance of the first baseline below the top of `rect`, in user space units.
+    pub(crate) first_baseline: f32,
+
+    /// Indentation and tab stops of the lines.
+    pub(crate) layout: LineLayout<'a>,
+}
+
+impl TextBox<'_> {
+    /// Text appended to the last visible line with [`Overflow::Ellipsis`].
+    const ELLIPSIS: &'static str = "...";
+
+    /// Text appended to lines broken at a hyphenated [`LineBreak`].
+    const HYPHEN: &'static str = "-";
+
+    /// Returns the default distance between baselines of consecutive lines for the given font
+    /// size, which is 120% of the font size.
+    pub(crate) fn default_leading(size: u32) -> f32 {
+        (size * 6) as f32 / 5.0
+    }
+
+    /// Breaks the given content into lines fitting the width of this box when drawn in the given
+    /// font size, applying the [`Overflow`] of this box to lines that don't fit its height.
+    ///
+    /// Lines are broken at explicit line breaks and at the breaks found by the [`LineBreaker`] of
+    /// this box, and words wider than the box are broken between characters.
+    pub(crate) fn lines(&self, content: &str, size: u32) -> Vec<Line> {
+        let max_width = self.rect.width().into_user_unit() * 1000.0 / size as f32;
+        let mut lines: Vec<Line> = content
+            .split('\n')
+            .flat_map(|paragraph| self.break_paragraph(paragraph, max_width, size))
+            .collect();
+
+        if self.overflow == Overflow::Grow {
+            return lines;
+        }
+
+        let height = self.rect.height().into_user_unit();
+        let max_lines = if height < self.first_baseline {
+            0
+        } else {
+            ((height - self.first_baseline) / self.leading) as usize + 1
+        };
+
+        if lines.len() > max_lines {
+            lines.truncate(max_lines);
+
+            if let (Overflow::Ellipsis, Some(last)) = (self.overflow, lines.last_mut()) {
+                let ellipsis_width = self.layout.metrics.text_width(Self::ELLIPSIS);
+                let indent = last.indent;
+                let text = &mut last.text;
+                while !text.is_empty()
+                    && self.measure(text, indent, size) + ellipsis_width > max_width
+                {
+                    text.pop();
+                }
+                text.truncate(text.trim_end().len());
+                text.push_str(Self::ELLIPSIS);
+                last.ends_paragraph = true;
+            }
+        }
+
+        lines
+    }
+
+    /// Breaks a paragraph without explicit line breaks into lines no wider than `max_width`,
+    /// expressed in thousandths of the font size.
+    fn break_paragraph(&self, paragraph: &str, max_width: f32, size: u32) -> Vec<Line> {
+        let mut breaks = self.line_breaker.breaks(paragraph);
+        breaks.retain(|brk| brk.offset < paragraph.len() && paragraph.is_char_boundary(brk.offset));
+        breaks.sort_by_key(|brk| brk.offset);
+        breaks.dedup_by_key(|brk| brk.offset);
+
+        // pieces of the paragraph between the breaks, with whether a line broken after the piece
+        // ends with a hyphen
+        let mut pieces = Vec::with_capacity(breaks.len() + 1);
+        let mut start = 0;
+        for brk in breaks {
+            pieces.push((&paragraph[start..brk.offset], brk.hyphen));
+            start = brk.offset;
+        }
+        pieces.push((&paragraph[start..], false));
+
+        let mut lines = Vec::new();
+        let mut line = String::new();
+        let mut hyphen = false;
+        let indent = |lines: &Vec<String>| self.layout.start(lines.is_empty());
+
+        for (piece, piece_hyphen) in pieces {
+            let hyphen_width = if piece_hyphen {
+                self.layout.metrics.text_width(Self::HYPHEN)
+            } else {
+                0.0
+            };
+
+            let candidate = match line.is_empty() {
+                true => piece.trim_start_matches(' ').to_string(),
+                false => format!("{line}{piece}"),
+            };
+
+            let width = self.measure(candidate.trim_end_matches(' '), indent(&lines), size);
+            if width + hyphen_width <= max_width {
+                line = candidate;
+                hyphen = piece_hyphen;
+                continue;
+            }
+
+            if !line.is_empty() {
+                Self::end_line(&mut lines, &mut line, hyphen);
+            }
+
+            let piece = piece.trim_start_matches(' ');
+            let width = self.measure(piece.trim_end_matches(' '), indent(&lines), size);
+            if width + hyphen_width <= max_width {
+                line = piece.to_string();
+                hyphen = piece_hyphen;
+                continue;
+            }
+
+            // pieces wider than the box are broken between characters
+            for ch in piece.chars() {
+                let width = self.measure(&line, indent(&lines), size);
+                let char_width = self.layout.metrics.text_width(ch.encode_utf8(&mut [0; 4]));
+                if !line.is_empty() && width + char_width > max_width {
+                    Self::end_line(&mut lines, &mut line, false);
+                }
+                line.push(ch);
+            }
+            hyphen = piece_hyphen;
+        }
+
+        Self::end_line(&mut lines, &mut line, false);
+
+        let last = lines.len() - 1;
+        lines
+            .into_iter()
+            .enumerate()
+            .map(|(idx, text)| Line {
+                text,
+                ends_paragraph: idx == last,
+                indent: self.layout.start(idx == 0),
+            })
+            .collect()
+    }
+
+    /// Ends the given line, dropping the spaces it was broken at and appending a hyphen if the
+    /// line was broken at a hyphenated [`LineBreak`]. Soft hyphens are dropped, as they are only
+    /// shown as the hyphen of a line broken at them.
+    fn end_line(lines: &mut Vec<String>, line: &mut String, hyphen: bool) {
+        let mut text = std::mem::take(line);
+        text.retain(|ch| ch != SOFT_HYPHEN);
+        text.truncate(text.trim_end_matches(' ').len());
+        if hyphen {
+            text.push_str(Self::HYPHEN);
+        }
+        lines.push(text);
+    }
+
+    /// Returns the width of the given line with the given indentation, including the indentation,
+    /// in thousandths of the font size.
+    fn measure(&self, line: &str, indent: f32, size: u32) -> f32 {
+        self.layout.width(line, indent, size) * 1000.0 / size as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::LazyLock;
+
+    use crate::types::hierarchy::primitives::{
+        font_metrics::{FontMetrics, Metrics},
+        rectangle::Rectangle,
+        unit::Unit,
+    };
+
+    use super::{
+        Hyphenation, Line, LineBreak, LineLayout, Overflow, SpaceBreaker, TabStop, TextBox,
+    };
+
+    const CONTENT: &str = "The quick brown fox jumps over the lazy dog";
+
+    /// Metrics of Helvetica, which the lines of the tests are measured with.
+    static HELVETICA: LazyLock<FontMetrics> = LazyLock::new(FontMetrics::default);
+
+    fn layout() -> LineLayout<'static> {
+        LineLayout {
+            indent: 0.0,
+            first_line_indent: 0.0,
+            tab_stops: &[],
+            metrics: &HELVETICA,
+        }
+    }
+
+    fn text_box(height: f32, overflow: Overflow) -> TextBox<'static> {
+        TextBox {
+            rect: Rectangle::from_units(0.0, 0.0, 100.0, height),
+            overflow,
+            line_breaker: &SpaceBreaker,
+            leading: TextBox::default_leading(12),
+            first_baseline: 12.0,
+            layout: layout(),
+        }
+    }
+
+    fn texts(lines: &[Line]) -> Vec<&str> {
+        lines.iter().map(|line| line.text.as_str()).collect()
+    }
+
+    #[test]
+    fn wrapped_lines() {
+        let lines = text_box(100.0, Overflow::Truncate).lines(CONTENT, 12);
+        assert_eq!(
+            texts(&lines),
+            ["The quick brown", "fox jumps over the", "lazy dog"]
+        );
+        assert!(lines[2].ends_paragraph && !lines[1].ends_paragraph);
+    }
+
+    #[test]
+    fn wrapped_monospaced_lines() {
+        let courier = FontMetrics::Simple(Metrics::of(b"Courier").unwrap());
+        let text_box = TextBox {
+            layout: LineLayout {
+                metrics: &courier,
+                ..layout()
+            },
+            ..text_box(100.0, Overflow::Ellipsis)
+        };
+
+        // each glyph of Courier is 7.2 units wide in size 12, so 13 of them fit into a line
+        let lines = text_box.lines(CONTENT, 12);
+        assert_eq!(
+            texts(&lines),
+            ["The quick", "brown fox", "jumps over", "the lazy dog"]
+        );
+
+        let lines = text_box.lines("Donaudampfschifffahrt", 12);
+        assert_eq!(texts(&lines), ["Donaudampfsch", "ifffahrt"]);
+    }
+
+    #[test]
+    fn explicit_line_breaks() {
+        let lines = text_box(100.0, Overflow::Truncate).lines("Dear Sir,\n\nThanks", 12);
+        assert_eq!(texts(&lines), ["Dear Sir,", "", "Thanks"]);
+        assert!(lines.iter().all(|line| line.ends_paragraph));
+    }
+
+    #[test]
+    fn long_word() {
+        let lines = text_box(100.0, Overflow::Truncate).lines("Donaudampfschifffahrt", 12);
+        assert_eq!(texts(&lines), ["Donaudampfschiff", "fahrt"]);
+    }
+
+    #[test]
+    fn overflow() {
+        let truncated = text_box(30.0, Overflow::Truncate).lines(CONTENT, 12);
+        assert_eq!(texts(&truncated), ["The quick brown", "fox jumps over the"]);
+
+        let ellipsis = text_box(30.0, Overflow::Ellipsis).lines(CONTENT, 12);
+        assert_eq!(texts(&ellipsis), ["The quick brown", "fox jumps over t..."]);
+
+        let grown = text_box(10.0, Overflow::Grow).lines(CONTENT, 12);
+        assert_eq!(grown.len(), 3);
+    }
+
+    #[test]
+    fn hyphenated_lines() {
+        let hyphenation = Hyphenation::new(|word: &str| match word {
+            "Donaudampfschifffahrt" => vec![5, 10, 16],
+            _ => vec![],
+        });
+        let text_box = TextBox {
+            line_breaker: &hyphenation,
+            ..text_box(100.0, Overflow::Truncate)
+        };
+
+        let lines = text_box.lines("Eine Donaudampfschifffahrt", 12);
+        assert_eq!(texts(&lines), ["Eine Donau-", "dampfschifffahrt"]);
+    }
+
+    #[test]
+    fn soft_hyphens() {
+        let text_box = text_box(100.0, Overflow::Truncate);
+
+        let lines = text_box.lines("Eine Donau\u{AD}dampf\u{AD}schiff\u{AD}fahrt", 12);
+        assert_eq!(texts(&lines), ["Eine Donau-", "dampfschifffahrt"]);
+
+        // soft hyphens that don't break a line aren't shown
+        let lines = text_box.lines("Donau\u{AD}dampf", 12);
+        assert_eq!(texts(&lines), ["Donaudampf"]);
+    }
+
+    #[test]
+    fn no_break_spaces() {
+        let lines = text_box(100.0, Overflow::Truncate).lines("The quick brown\u{A0}fox", 12);
+        assert_eq!(texts(&lines), ["The quick", "brown\u{A0}fox"]);
+    }
+
+    #[test]
+    fn zero_width_joiners() {
+        // the joiners take no space, so the lines are broken as without them
+        let content = "The quick\u{200D} brown fox\u{200C} jumps over the lazy dog";
+        let lines = text_box(100.0, Overflow::Truncate).lines(content, 12);
+        assert_eq!(
+            texts(&lines),
+            [
+                "The quick\u{200D} brown",
+                "fox\u{200C} jumps over the",
+                "lazy dog"
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_line_breaks() {
+        let after_slashes = |paragraph: &str| {
+            paragraph
+                .match_indices('/')
+                .map(|(idx, _)| LineBreak::new(idx + 1))
+                .collect()
+        };
+        let text_box = TextBox {
+            line_breaker: &after_slashes,
+            ..text_box(100.0, Overflow::Truncate)
+        };
+
+        let lines = text_box.lines("/home/user/documents/invoices", 12);
+        assert_eq!(texts(&lines), ["/home/user/", "documents/", "invoices"]);
+    }
+
+    #[test]
+    fn indented_lines() {
+        let text_box = TextBox {
+            layout: LineLayout {
+                indent: 20.0,
+                first_line_indent: -20.0,
+                ..layout()
+            },
+            ..text_box(100.0, Overflow::Truncate)
+        };
+
+        // a hanging indent, e.g. of a list item
+        let lines = text_box.lines(CONTENT, 12);
+        assert_eq!(
+            texts(&lines),
+            ["The quick brown", "fox jumps over", "the lazy dog"]
+        );
+        assert_eq!(lines[0].indent, 0.0);
+        assert_eq!(lines[1].indent, 20.0);
+    }
+
+    #[test]
+    fn tab_stops() {
+        let tab_stops = [
+            TabStop::left(Unit::from_unit(50.0)),
+            TabStop::right(Unit::from_unit(150.0)),
+        ];
+        let layout = LineLayout {
+            tab_stops: &tab_stops,
+            ..layout()
+        };
+
+        let segments: Vec<_> = layout
+            .segments("Tea\tBlack\t2.50\tEUR", 0.0, 10)
+            .into_iter()
+            .map(|(segment, offset)| (segment, (offset * 100.0).round() / 100.0))
+            .collect();
+        assert_eq!(
+            segments,
+            [
+                ("Tea", 0.0),
+                ("Black", 50.0),
+                ("2.50", 130.54),
+                ("EUR", 180.0)
+            ]
+        );
+        // text that passed a tab stop continues at the next one
+        assert_eq!(
+            layout.segments("Long description\tx", 0.0, 10),
+            [("Long description", 0.0), ("x", 150.0 - 5.0)]
+        );
+    }
+}
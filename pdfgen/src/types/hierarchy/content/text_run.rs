@@ -0,0 +1,359 @@
+//! Implementation of text runs, interleaving strings with individual glyph position adjustments.
+
+use std::io::{Error, Write};
+
+use crate::{
+    SanitizePolicy,
+    types::{
+        constants,
+        hierarchy::{
+            content::text::Text,
+            primitives::{
+                encoding::ShowEncoding, font_metrics::FontMetrics, identifier::Identifier,
+                string::PdfString, symbol::SymbolGlyph,
+            },
+        },
+    },
+};
+
+/// Placement of a string of a [`TextRun`] above or below the baseline, in a smaller size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    /// Raised above the baseline, e.g. for footnote markers, ordinals or exponents.
+    Superscript,
+
+    /// Lowered below the baseline, e.g. for chemical formulas or indices.
+    Subscript,
+}
+
+impl Script {
+    /// Size of scripts, as a fraction of the font size.
+    const SCALE: f32 = 0.65;
+
+    /// Returns the text rise of this `Script`, as a fraction of the font size.
+    fn rise(self) -> f32 {
+        match self {
+            Script::Superscript => 0.35,
+            Script::Subscript => -0.15,
+        }
+    }
+}
+
+/// A single element of a [`TextRun`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextRunItem {
+    /// A string that is shown.
+    Text(PdfString),
+
+    /// Adjustment of the position of the following glyph in thousandths of the font size. Positive
+    /// values move the glyph to the left, tightening the text, and negative values move it to the
+    /// right.
+    Adjustment(f32),
+
+    /// A string that is shown as a superscript or subscript, in a reduced size and with a text rise
+    /// (`Ts`) computed from the font size of the text.
+    Script { script: Script, text: PdfString },
+}
+
+/// A run of text interleaving strings with adjustments of the position of individual glyphs,
+/// shown with the `TJ` operator. Used to express kerning pairs and manual letter positioning, e.g.
+/// computed by an external text shaper.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::content::text_run::TextRun;
+/// // tighten the kerning pairs "AV" and "VA"
+/// let run = TextRun::new().text("A").adjust(80.0).text("V").adjust(80.0).text("ANT");
+///
+/// // a footnote marker and a chemical formula
+/// let footnote = TextRun::new().text("See the appendix").superscript("1");
+/// let water = TextRun::new().text("H").subscript("2").text("O");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TextRun {
+    /// The strings and adjustments of this run, in order.
+    items: Vec<TextRunItem>,
+}
+
+impl TextRun {
+    /// Represents the TJ (Text Show with glyph positioning) operator.
+    pub const TJ_OPERATOR: &[u8] = b"TJ";
+    /// Represents the Ts (Text Rise) operator.
+    pub const TS_OPERATOR: &[u8] = b"Ts";
+
+    /// Creates a new empty `TextRun`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a string to this `TextRun`.
+    pub fn text(mut self, text: impl Into<String>) -> Self {
+        self.items.push(TextRunItem::Text(PdfString::from(text)));
+        self
+    }
+
+    /// Appends a glyph of Symbol or ZapfDingbats to this `TextRun`, encoded with its code in the
+    /// built-in encoding of its font. The run has to be drawn with a font created for the
+    /// [`SymbolFont`] of the glyph, see [`SymbolGlyph`].
+    ///
+    /// [`SymbolFont`]: crate::types::hierarchy::primitives::symbol::SymbolFont
+    pub fn glyph(mut self, glyph: SymbolGlyph) -> Self {
+        let text = PdfString::from(glyph.as_char().to_string());
+        self.items.push(TextRunItem::Text(text));
+        self
+    }
+
+    /// Appends an adjustment of the position of the following glyph in thousandths of the font
+    /// size to this `TextRun`. See [`TextRunItem::Adjustment`].
+    pub fn adjust(mut self, adjustment: f32) -> Self {
+        self.items.push(TextRunItem::Adjustment(adjustment));
+        self
+    }
+
+    /// Appends a string shown as a superscript to this `TextRun`. See [`Script::Superscript`].
+    pub fn superscript(self, text: impl Into<String>) -> Self {
+        self.script(Script::Superscript, text)
+    }
+
+    /// Appends a string shown as a subscript to this `TextRun`. See [`Script::Subscript`].
+    pub fn subscript(self, text: impl Into<String>) -> Self {
+        self.script(Script::Subscript, text)
+    }
+
+    /// Appends a string shown as the given [`Script`] to this `TextRun`.
+    fn script(mut self, script: Script, text: impl Into<String>) -> Self {
+        self.items.push(TextRunItem::Script {
+            script,
+            text: PdfString::from(text),
+        });
+        self
+    }
+
+    /// Returns the strings and adjustments of this `TextRun`, in order.
+    pub fn items(&self) -> &[TextRunItem] {
+        &self.items
+    }
+
+    /// Returns the concatenation of all strings of this `TextRun`, without the adjustments.
+    pub(crate) fn content(&self) -> String {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                TextRunItem::Text(text) | TextRunItem::Script { text, .. } => Some(text.to_text()),
+                TextRunItem::Adjustment(_) => None,
+            })
+            .collect()
+    }
+
+    /// Returns this `TextRun` with its strings sanitized according to the given [`SanitizePolicy`].
+    pub(crate) fn sanitized(self, policy: &SanitizePolicy) -> Self {
+        let items = self
+            .items
+            .into_iter()
+            .map(|item| match item {
+                TextRunItem::Text(text) => {
+                    TextRunItem::Text(PdfString::from(policy.text(&text.to_text())))
+                }
+                TextRunItem::Script { script, text } => TextRunItem::Script {
+                    script,
+                    text: PdfString::from(policy.text(&text.to_text())),
+                },
+                adjustment => adjustment,
+            })
+            .collect();
+
+        Self { items }
+    }
+
+    /// Returns the sum of all adjustments of this `TextRun`, in thousandths of the font size,
+    /// including the width that superscripts and subscripts take less than their full size when
+    /// measured with the given [`FontMetrics`].
+    pub(crate) fn adjustment(&self, metrics: &FontMetrics) -> f32 {
+        self.items
+            .iter()
+            .filter_map(|item| match item {
+                TextRunItem::Adjustment(adjustment) => Some(*adjustment),
+                TextRunItem::Script { text, .. } => {
+                    Some(metrics.text_width(&text.to_text()) * (1.0 - Script::SCALE))
+                }
+                TextRunItem::Text(_) => None,
+            })
+            .sum()
+    }
+
+    /// Writes this `TextRun` as the operands of the `TJ` operator, followed by the operator, into
+    /// the given implementor of [`Write`], with strings encoded with the given [`ShowEncoding`].
+    /// Superscripts and subscripts are shown with separate `TJ` operators, after switching to the
+    /// reduced size of the font with the given name and size and setting the text rise, which is
+    /// reset at the end of the run.
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        encoding: ShowEncoding,
+        font_name: &Identifier<&[u8]>,
+        size: u32,
+    ) -> Result<usize, Error> {
+        // adjustments are shown together with the strings before them
+        let mut groups: Vec<(Option<Script>, Vec<&TextRunItem>)> = Vec::new();
+        for item in &self.items {
+            let script = match item {
+                TextRunItem::Text(_) => None,
+                TextRunItem::Script { script, .. } => Some(*script),
+                TextRunItem::Adjustment(_) => groups.last().and_then(|(script, _)| *script),
+            };
+
+            match groups.last_mut() {
+                Some((current, items)) if *current == script => items.push(item),
+                _ => groups.push((script, vec![item])),
+            }
+        }
+
+        let mut written = 0;
+        let mut current = None;
+        for (idx, (script, items)) in groups.iter().enumerate() {
+            if idx > 0 {
+                written += writer.write(constants::NL_MARKER)?;
+            }
+
+            if *script != current {
+                written += Self::write_script(writer, font_name, size, *script)?;
+                written += writer.write(constants::NL_MARKER)?;
+                current = *script;
+            }
+
+            written += pdfgen_macros::write_chain! {
+                writer.write(b"["),
+                for (idx, item) in items.iter().enumerate() {
+                    {
+                        let separator: &[u8] = if idx > 0 { b" " } else { b"" };
+                        writer.write(separator)
+                    },
+                    Self::write_item(writer, item, encoding),
+                },
+                writer.write(b"] "),
+                writer.write(Self::TJ_OPERATOR),
+            };
+        }
+
+        // the text rise is part of the text state, which outlives the text object
+        if current.is_some() {
+            written += writer.write(constants::NL_MARKER)?;
+            written += Self::write_script(writer, font_name, size, None)?;
+        }
+
+        Ok(written)
+    }
+
+    /// Writes the `Tf` and `Ts` operators switching to the size and text rise of the given
+    /// [`Script`], or back to the full size on the baseline if `None`.
+    fn write_script(
+        writer: &mut dyn Write,
+        font_name: &Identifier<&[u8]>,
+        size: u32,
+        script: Option<Script>,
+    ) -> Result<usize, Error> {
+        // round to avoid writing values such as `7.7999997`
+        let round = |value: f32| (value * 100.0).round() / 100.0 + 0.0;
+        let size = size as f32;
+        let (scaled, rise) = match script {
+            Some(script) => (round(size * Script::SCALE), round(size * script.rise())),
+            None => (size, 0.0),
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            font_name.write(writer),
+            crate::write_fmt!(&mut *writer, "{scaled} "),
+            writer.write(Text::TF_OPERATOR),
+            writer.write(constants::NL_MARKER),
+            crate::write_fmt!(&mut *writer, "{rise} "),
+            writer.write(Self::TS_OPERATOR),
+        })
+    }
+
+    /// Writes a single [`TextRunItem`] into the given implementor of [`Write`].
+    fn write_item(
+        writer: &mut dyn Write,
+        item: &TextRunItem,
+        encoding: ShowEncoding,
+    ) -> Result<usize, Error> {
+        match item {
+            TextRunItem::Text(text) | TextRunItem::Script { text, .. } => {
+                text.encoded(encoding).write_content(writer)
+            }
+            TextRunItem::Adjustment(adjustment) => crate::write_fmt!(writer, "{adjustment}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Identifier, ShowEncoding, SymbolGlyph, TextRun};
+
+    const FONT: Identifier<&[u8]> = Identifier::from_static(b"F1");
+
+    #[test]
+    fn kerned_run() {
+        let run = TextRun::new()
+            .text("A")
+            .adjust(80.0)
+            .text("V")
+            .adjust(-12.5)
+            .text("ANT");
+
+        let mut writer = Vec::new();
+        run.write(&mut writer, ShowEncoding::WinAnsi, &FONT, 12)
+            .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @"[(A) 80 (V) -12.5 (ANT)] TJ");
+        assert_eq!(run.content(), "AVANT");
+    }
+
+    #[test]
+    fn scripts() {
+        let run = TextRun::new()
+            .text("H")
+            .subscript("2")
+            .text("O")
+            .adjust(20.0)
+            .superscript("1");
+
+        let mut writer = Vec::new();
+        run.write(&mut writer, ShowEncoding::WinAnsi, &FONT, 12)
+            .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [(H)] TJ
+        /F1 7.8 Tf
+        -1.8 Ts
+        [(2)] TJ
+        /F1 12 Tf
+        0 Ts
+        [(O) 20] TJ
+        /F1 7.8 Tf
+        4.2 Ts
+        [(1)] TJ
+        /F1 12 Tf
+        0 Ts
+        ");
+        assert_eq!(run.content(), "H2O1");
+    }
+
+    #[test]
+    fn symbol_glyphs() {
+        let run = TextRun::new()
+            .glyph(SymbolGlyph::BULLET)
+            .adjust(-250.0)
+            .glyph(SymbolGlyph::ARROW_RIGHT);
+
+        let mut writer = Vec::new();
+        run.write(&mut writer, ShowEncoding::WinAnsi, &FONT, 12)
+            .unwrap();
+
+        // glyphs are shown with their codes in the built-in encoding of Symbol
+        assert_eq!(writer, b"[(\xB7) -250 (\xAE)] TJ");
+        assert_eq!(run.content(), "\u{B7}\u{AE}");
+    }
+}
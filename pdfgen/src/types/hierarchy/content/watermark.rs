@@ -0,0 +1,576 @@
+//! Implementation of watermarks, stamped onto every page of a [`Document`].
+//!
+//! [`Document`]: crate::Document
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    FontId, IdManager, ObjId,
+    types::{
+        constants,
+        hierarchy::primitives::{
+            identifier::Identifier,
+            rectangle::{Position, Rectangle},
+            unit::Unit,
+        },
+    },
+};
+
+use super::{
+    ContentStream, Operation,
+    color::{Color, ColorSpace},
+    graphics_state::ExtGState,
+    image::{Image, ImageTransform},
+    stream::Stream,
+    text::{Text, TextAlignment},
+};
+
+/// Content drawn by a [`Watermark`].
+#[derive(Debug)]
+enum WatermarkContent {
+    /// A single line of text, drawn with the given font.
+    Text {
+        content: String,
+        font: FontId,
+        size: u32,
+        color: Color,
+    },
+
+    /// An image, drawn with its configured width and height.
+    Image(Image),
+}
+
+/// A rotated and semi-transparent text or image, that is stamped onto the center of every page of
+/// a [`Document`] when it's written. The watermark is written only once as a form XObject, which
+/// is then referenced by every page.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::content::watermark::Watermark};
+/// let mut document = Document::default();
+/// let font = document.create_font("Type1".into(), "Helvetica".into());
+///
+/// document.set_watermark(
+///     Watermark::text("CONFIDENTIAL", font)
+///         .with_rotation(30.0)
+///         .with_opacity(0.2),
+/// );
+/// ```
+///
+/// [`Document`]: crate::Document
+#[derive(Debug)]
+pub struct Watermark {
+    /// The text or image drawn by this `Watermark`.
+    content: WatermarkContent,
+
+    /// Counterclockwise rotation in degrees.
+    rotation: f32,
+
+    /// Constant opacity, in range `[0.0, 1.0]`.
+    opacity: f32,
+}
+
+/// Object IDs allocated for a [`Watermark`] while writing a [`Document`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug)]
+pub(crate) struct WatermarkIds {
+    /// ID of the form XObject containing the watermark.
+    form: ObjId,
+
+    /// ID of the [`ExtGState`] setting the opacity of the watermark.
+    ext_g_state: ObjId,
+
+    /// ID of the image drawn by the watermark, if it's an image watermark.
+    image: Option<ObjId>,
+
+    /// ID of the mask of the image drawn by the watermark, if the image has one.
+    mask: Option<ObjId>,
+}
+
+/// Per-page content of a [`Watermark`], placing the watermark form XObject at the center of a
+/// page.
+#[derive(Debug)]
+pub(crate) struct WatermarkStamp {
+    /// ID of the form XObject containing the watermark.
+    form: ObjId,
+
+    /// Content stream drawing the form XObject, added to the page's contents.
+    contents: ContentStream,
+
+    /// Whether the watermark is transparent.
+    uses_transparency: bool,
+}
+
+impl Watermark {
+    /// Default font size of text watermarks.
+    const DEFAULT_SIZE: u32 = 48;
+    /// Default counterclockwise rotation in degrees.
+    const DEFAULT_ROTATION: f32 = 45.0;
+    /// Default constant opacity.
+    const DEFAULT_OPACITY: f32 = 0.3;
+
+    /// Name of the watermark form XObject in the resources of a page.
+    const X_OBJECT_NAME: Identifier<&'static [u8]> = Identifier::from_static(b"Wm");
+    /// Name of the font in the resources of the watermark form XObject.
+    const FONT_NAME: Identifier<&'static [u8]> = Identifier::from_static(b"F1");
+    /// Name of the image in the resources of the watermark form XObject.
+    const IMAGE_NAME: Identifier<&'static [u8]> = Identifier::from_static(b"Im1");
+    /// Name of the [`ExtGState`] in the resources of the watermark form XObject.
+    const EXT_G_STATE_NAME: Identifier<&'static [u8]> = Identifier::from_static(b"GS1");
+
+    const_identifiers! {
+        SUBTYPE,
+        FORM,
+        B_BOX: b"BBox",
+        MATRIX,
+        RESOURCES,
+    }
+
+    /// Creates a new text `Watermark` with the given content, drawn with the given font in gray
+    /// color.
+    pub fn text(content: impl Into<String>, font: FontId) -> Self {
+        Self::new(WatermarkContent::Text {
+            content: content.into(),
+            font,
+            size: Self::DEFAULT_SIZE,
+            color: Color::Gray(128),
+        })
+    }
+
+    /// Creates a new image `Watermark`, drawn with the width and height of the given [`Image`].
+    /// The position of the [`Image`] is ignored, as the watermark is always centered.
+    pub fn image(image: Image) -> Self {
+        Self::new(WatermarkContent::Image(image))
+    }
+
+    fn new(content: WatermarkContent) -> Self {
+        Self {
+            content,
+            rotation: Self::DEFAULT_ROTATION,
+            opacity: Self::DEFAULT_OPACITY,
+        }
+    }
+
+    /// Sets the font size of a text watermark. Has no effect on image watermarks.
+    pub fn with_size(mut self, new_size: u32) -> Self {
+        if let WatermarkContent::Text { size, .. } = &mut self.content {
+            *size = new_size;
+        }
+        self
+    }
+
+    /// Sets the color of a text watermark. Has no effect on image watermarks.
+    pub fn with_color(mut self, new_color: Color) -> Self {
+        if let WatermarkContent::Text { color, .. } = &mut self.content {
+            *color = new_color;
+        }
+        self
+    }
+
+    /// Sets the counterclockwise rotation of this `Watermark` in degrees.
+    pub fn with_rotation(mut self, degrees: f32) -> Self {
+        self.rotation = degrees;
+        self
+    }
+
+    /// Sets the constant opacity of this `Watermark`. The value is clamped to the range
+    /// `[0.0, 1.0]`, where `0.0` is fully transparent and `1.0` is fully opaque.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Returns the font and content of a text watermark, or `None` for an image watermark.
+    #[cfg(feature = "composite-fonts")]
+    pub(crate) fn text_content(&self) -> Option<(&FontId, &str)> {
+        match &self.content {
+            WatermarkContent::Text { content, font, .. } => Some((font, content.as_str())),
+            WatermarkContent::Image(_) => None,
+        }
+    }
+
+    /// Allocates the object IDs required to write this `Watermark`.
+    pub(crate) fn create_ids(&self, id_manager: &mut IdManager) -> WatermarkIds {
+        WatermarkIds {
+            form: id_manager.create_id(),
+            ext_g_state: id_manager.create_id(),
+            image: matches!(self.content, WatermarkContent::Image(_))
+                .then(|| id_manager.create_id()),
+            mask: self
+                .content_image()
+                .and_then(Image::mask)
+                .map(|_| id_manager.create_id()),
+        }
+    }
+
+    /// Creates the [`WatermarkStamp`] placing this `Watermark` at the center of a page with the
+    /// given media box.
+    pub(crate) fn stamp(
+        &self,
+        ids: &WatermarkIds,
+        media_box: Rectangle,
+        id: ObjId<ContentStream>,
+    ) -> WatermarkStamp {
+        let low_left = media_box.low_left();
+        let center = Position::new(
+            Unit::from_pt(low_left.x.into_points() + media_box.width().into_points() / 2.0),
+            Unit::from_pt(low_left.y.into_points() + media_box.height().into_points() / 2.0),
+        );
+
+        let mut contents = ContentStream::new(id);
+        contents.add_content(Operation::DrawImage {
+            name: Self::X_OBJECT_NAME,
+            transform: ImageTransform {
+                position: center,
+                scale: Position::from_units(1.0, 1.0),
+                flip_horizontal: false,
+                flip_vertical: false,
+            },
+            stencil_color: None,
+        });
+
+        WatermarkStamp {
+            form: ids.form.clone(),
+            contents,
+            uses_transparency: self.ext_g_state().uses_transparency()
+                || self.content_image().and_then(Image::soft_mask).is_some(),
+        }
+    }
+
+    /// Returns the image drawn by this `Watermark`, if it's an image watermark.
+    fn content_image(&self) -> Option<&Image> {
+        match &self.content {
+            WatermarkContent::Image(image) => Some(image),
+            WatermarkContent::Text { .. } => None,
+        }
+    }
+
+    /// Returns the [`ExtGState`] applying the opacity of this `Watermark`.
+    fn ext_g_state(&self) -> ExtGState {
+        ExtGState::new()
+            .with_stroke_alpha(self.opacity)
+            .with_fill_alpha(self.opacity)
+    }
+
+    /// Returns the bounding box of the content of this `Watermark`, centered at the origin.
+    fn bbox(&self) -> Rectangle {
+        let (width, height) = match &self.content {
+            WatermarkContent::Text {
+                content,
+                font,
+                size,
+                ..
+            } => {
+                let width = f64::from(font.metrics().text_width(content) * *size as f32 / 1000.0);
+                let size = f64::from(*size);
+
+                // leave a margin of the font size, covering glyphs extending beyond their widths
+                (width + 2.0 * size, 2.0 * size)
+            }
+            WatermarkContent::Image(image) => {
+                let Position { x, y } = image.transform().scale;
+                (x.into_points(), y.into_points())
+            }
+        };
+
+        Rectangle::new(
+            Position::new(Unit::from_pt(-width / 2.0), Unit::from_pt(-height / 2.0)),
+            Position::new(Unit::from_pt(width / 2.0), Unit::from_pt(height / 2.0)),
+        )
+    }
+
+    /// Returns the content stream of the watermark form XObject, drawing the content centered at
+    /// the origin.
+    fn form_content(&self) -> Result<Vec<u8>, Error> {
+        let mut content = Vec::new();
+
+        Self::EXT_G_STATE_NAME.write(&mut content)?;
+        content.write_all(b"gs")?;
+        content.write_all(constants::NL_MARKER)?;
+
+        match &self.content {
+            WatermarkContent::Text {
+                content: text,
+                font,
+                size,
+                color,
+            } => {
+                // place the baseline a third of the font size below the center, so the text
+                // appears vertically centered
+                let mut text = Text::builder()
+                    .with_content(text.as_str())
+                    .with_size(*size)
+                    .with_color(*color)
+                    .with_alignment(TextAlignment::Center)
+                    .at(Position::new(
+                        Unit::from_pt(0.0),
+                        Unit::from_pt(-f64::from(*size) / 3.0),
+                    ))
+                    .build();
+                text.set_encoding(font.encoding());
+                text.set_metrics(font.metrics().clone());
+
+                content.write_all(&text.to_bytes(Self::FONT_NAME)?)?;
+            }
+            WatermarkContent::Image(image) => {
+                let mut transform = image.transform();
+                transform.position = Position::new(
+                    Unit::from_pt(-transform.scale.x.into_points() / 2.0),
+                    Unit::from_pt(-transform.scale.y.into_points() / 2.0),
+                );
+
+                content.write_all(b"q\n")?;
+                if let Some(color) = image.stencil_color() {
+                    color.write_non_stroke(&mut content)?;
+                }
+                content.write_all(transform.to_matrix_operator().as_bytes())?;
+                content.write_all(constants::NL_MARKER)?;
+                Self::IMAGE_NAME.write(&mut content)?;
+                content.write_all(b"Do\nQ\n")?;
+            }
+        }
+
+        Ok(content)
+    }
+
+    /// Returns the [`ColorSpace`] of the text of this watermark, if it's referred to by name and
+    /// has to be added to the resources of the form XObject.
+    fn named_color_space(&self) -> Option<ColorSpace> {
+        match &self.content {
+            WatermarkContent::Text { color, .. } => Some(color.color_space())
+                .filter(|color_space| color_space.resource_name().is_some()),
+            WatermarkContent::Image(_) => None,
+        }
+    }
+
+    /// Writes the resource dictionary of the watermark form XObject.
+    fn write_resources(&self, writer: &mut dyn Write, ids: &WatermarkIds) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            Self::RESOURCES.write(writer),
+            writer.write(b"<< "),
+
+            if let WatermarkContent::Text { font, .. } = &self.content {
+                Identifier::FONT.write(writer),
+                writer.write(b"<< "),
+                Self::FONT_NAME.write(writer),
+                font.clone().into_obj_id().write_ref(writer),
+                writer.write(b" >> "),
+            },
+
+            if let Some(image) = &ids.image {
+                Identifier::X_OBJECT.write(writer),
+                writer.write(b"<< "),
+                Self::IMAGE_NAME.write(writer),
+                image.write_ref(writer),
+                writer.write(b" >> "),
+            },
+
+            if let Some(color_space) = self.named_color_space() {
+                Identifier::COLOR_SPACE.write(writer),
+                writer.write(b"<< "),
+                color_space.write_name(writer),
+                color_space.write(writer),
+                writer.write(b">> "),
+            },
+
+            Identifier::EXT_G_STATE.write(writer),
+            writer.write(b"<< "),
+            Self::EXT_G_STATE_NAME.write(writer),
+            ids.ext_g_state.write_ref(writer),
+            writer.write(b" >> "),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Writes the watermark form XObject, followed by the objects it references, into the given
+    /// implementor of [`Write`]. Returns the number of bytes written and the offsets of all
+    /// objects following the form XObject.
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        ids: &WatermarkIds,
+    ) -> Result<(usize, Vec<usize>), Error> {
+        let mut offsets = Vec::with_capacity(2);
+
+        // round to avoid writing values such as `6.123234e-17` instead of 0
+        let round = |value: f32| (value * 100_000.0).round() / 100_000.0 + 0.0;
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let (sin, cos) = (round(sin), round(cos));
+        let form = Stream::with_bytes(self.form_content()?);
+
+        let written = pdfgen_macros::write_chain! {
+            ids.form.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            form.write_with_dict(writer, |writer| {
+                Ok(pdfgen_macros::write_chain! {
+                    Identifier::TYPE.write(writer),
+                    Identifier::X_OBJECT.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::SUBTYPE.write(writer),
+                    Self::FORM.write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::B_BOX.write(writer),
+                    self.bbox().write(writer),
+                    writer.write(constants::NL_MARKER),
+
+                    Self::MATRIX.write(writer),
+                    crate::write_fmt!(&mut *writer, "[{cos} {sin} {} {cos} 0 0]", -sin + 0.0),
+                    writer.write(constants::NL_MARKER),
+
+                    self.write_resources(writer, ids),
+                })
+            }),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+
+            {
+                offsets.push(written);
+                self.ext_g_state().write(writer, &ids.ext_g_state)
+            },
+            writer.write(constants::NL_MARKER),
+
+            if let (WatermarkContent::Image(image), Some(id)) = (&self.content, &ids.image) {
+                {
+                    offsets.push(written);
+                    image.write_with_mask(writer, id, ids.mask.as_ref())
+                },
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let (Some(mask), Some(id)) = (self.content_image().and_then(Image::mask), &ids.mask) {
+                {
+                    offsets.push(written);
+                    mask.write(writer, id)
+                },
+                writer.write(constants::NL_MARKER),
+            },
+        };
+
+        Ok((written, offsets))
+    }
+}
+
+impl WatermarkStamp {
+    /// Returns `true` if the watermark is transparent.
+    pub(crate) fn uses_transparency(&self) -> bool {
+        self.uses_transparency
+    }
+
+    /// Returns the content stream drawing the watermark on a page.
+    pub(crate) fn contents(&self) -> &ContentStream {
+        &self.contents
+    }
+
+    /// Writes the reference to the watermark form XObject as an entry of a resource dictionary.
+    pub(crate) fn write_resource_ref(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            Identifier::X_OBJECT.write(writer),
+            writer.write(b"<< "),
+            Watermark::X_OBJECT_NAME.write(writer),
+            self.form.write_ref(writer),
+            writer.write(b" >>"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        FontId, IdManager,
+        types::hierarchy::{
+            content::color::Color,
+            primitives::{font::Font, object::Object, rectangle::Rectangle},
+        },
+    };
+
+    use super::Watermark;
+
+    #[test]
+    fn text_watermark() {
+        let mut id_manager = IdManager::new();
+        let font = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+        let watermark = Watermark::text("DRAFT", font)
+            .with_size(40)
+            .with_color(Color::Gray(200))
+            .with_rotation(90.0)
+            .with_opacity(0.5);
+        let ids = watermark.create_ids(&mut id_manager);
+
+        let mut writer = Vec::new();
+        let (written, offsets) = watermark.write(&mut writer, &ids).unwrap();
+        assert_eq!(written, writer.len());
+        assert_eq!(offsets.len(), 1);
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        2 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [-106.66 -40 106.66 40]
+        /Matrix [0 1 -1 0 0 0]
+        /Resources << /Font << /F1 1 0 R >> /ExtGState << /GS1 3 0 R >> >>
+        /Length 88 >>
+        stream
+        /GS1 gs
+        BT
+        /DeviceGray cs
+        0.78431374 sc
+        /F1 40 Tf
+        -66.66001 -13.333333 Td
+        (DRAFT) Tj
+        ET
+
+        endstream
+        endobj
+
+        3 0 obj
+        << /Type /ExtGState 
+        /CA 0.5
+        /ca 0.5
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn watermark_stamp() {
+        let mut id_manager = IdManager::new();
+        let font = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+        let watermark = Watermark::text("DRAFT", font);
+        let ids = watermark.create_ids(&mut id_manager);
+
+        let stamp = watermark.stamp(
+            &ids,
+            Rectangle::from_units(0.0, 0.0, 200.0, 100.0),
+            id_manager.create_id(),
+        );
+        assert!(stamp.uses_transparency());
+
+        let mut writer = Vec::new();
+        stamp.contents().write_content(&mut writer).unwrap();
+        stamp.write_resource_ref(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 28 >>
+        stream
+        q
+        1 0 0 1 100 50 cm
+        /Wm Do
+        Q
+        endstream
+        /XObject << /Wm 2 0 R >>
+        ");
+    }
+}
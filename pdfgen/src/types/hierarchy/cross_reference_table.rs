@@ -2,6 +2,8 @@
 
 use std::io::Write;
 
+use crate::LineEnding;
+
 /// This represents the PDF-s cross-reference (xref) table, which is a crucial component that
 /// maps each object in the PDF to its location within the file (byte offset from the start).
 #[derive(Default)]
@@ -13,10 +15,7 @@ pub struct CrossReferenceTable {
 
 impl CrossReferenceTable {
     /// Marker representing the start of CRT section (4 characters “xref”).
-    const XREF_MARKER: &[u8] = b"xref\n";
-
-    /// Representing the PDF SPLF newline used for crt entries.
-    const SP_LF: &str = " \n";
+    const XREF_MARKER: &[u8] = b"xref";
 
     /// Adds a new object offset to the table.
     pub fn add_object(&mut self, byte_offset: usize) {
@@ -25,13 +24,22 @@ impl CrossReferenceTable {
 
     /// Writes the contents of the `offsets`, representing them in the format required by the PDF
     /// syntax, `10 byte offset generation(00000), n`.
-    pub fn write(&self, writer: &mut impl Write) -> Result<(), std::io::Error> {
+    pub fn write(
+        &self,
+        writer: &mut impl Write,
+        line_ending: LineEnding,
+    ) -> Result<(), std::io::Error> {
+        let eol = line_ending.marker();
+
         pdfgen_macros::write_chain! {
             writer.write(Self::XREF_MARKER),
-            crate::write_fmt!(&mut *writer, "0 {}\n", self.offsets.len()),
+            writer.write(eol),
+            crate::write_fmt!(&mut *writer, "0 {}", self.offsets.len()),
+            writer.write(eol),
 
             for offset in self.offsets.iter() {
-                crate::write_fmt!(&mut *writer, "{offset:010} 00000 n{}", Self::SP_LF),
+                crate::write_fmt!(&mut *writer, "{offset:010} 00000 n"),
+                writer.write(line_ending.xref_marker()),
             },
         };
 
@@ -43,6 +51,11 @@ impl CrossReferenceTable {
         self.offsets.len()
     }
 
+    /// Returns the byte offsets of all objects, in the order they were added.
+    pub fn offsets(&self) -> &[usize] {
+        &self.offsets
+    }
+
     /// Returns if the `offsets` collection is empty or not.
     #[must_use]
     pub fn is_empty(&self) -> bool {
@@ -0,0 +1,389 @@
+//! Implementation of explicit and named destinations, which define a view of a page of a document
+//! that is shown when the document is opened or a destination is navigated to.
+
+use std::{
+    collections::BTreeMap,
+    io::{Error, Write},
+};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{DestinationId, IdManager, ObjId, PageId, types::constants};
+
+use super::primitives::{
+    identifier::Identifier, object::Object, rectangle::Rectangle, string::PdfString, unit::Unit,
+};
+
+/// Possible errors that might be returned when using a [`Destination`] in a document.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum DestinationErr {
+    /// Indicates that the page of the destination is not a page of the document.
+    #[error("Destination refers to a page that is not in the document.")]
+    UnknownPage,
+
+    /// Indicates that no destination with the referenced name was added to the document.
+    #[error("Destination refers to a name that is not a named destination of the document.")]
+    UnknownName,
+}
+
+/// A view of a page of a document, consisting of the page, the location on the page that is shown
+/// and the magnification (zoom) it's shown with. Coordinates that are `None` leave the respective
+/// part of the view unchanged.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::destination::Destination};
+/// # use pdfgen::types::hierarchy::primitives::unit::Unit;
+/// let mut document = Document::default();
+/// let page = document.create_page().id();
+///
+/// // open the document at 150% on the section heading
+/// let heading = Destination::Xyz {
+///     page,
+///     left: Some(Unit::from_mm(20.0)),
+///     top: Some(Unit::from_mm(250.0)),
+///     zoom: Some(1.5),
+/// };
+/// document.set_open_action(heading).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Destination {
+    /// Show the page with the given coordinates at the upper left corner of the window, magnified
+    /// by the given zoom factor, where `1.0` is 100%.
+    Xyz {
+        /// The page that is shown.
+        page: PageId,
+
+        /// Horizontal coordinate shown at the left edge of the window.
+        left: Option<Unit>,
+
+        /// Vertical coordinate shown at the top edge of the window.
+        top: Option<Unit>,
+
+        /// Magnification factor, where `1.0` is 100%.
+        zoom: Option<f32>,
+    },
+
+    /// Show the whole page, magnified to fit the window.
+    Fit {
+        /// The page that is shown.
+        page: PageId,
+    },
+
+    /// Show the page with the given vertical coordinate at the top edge of the window, magnified to
+    /// fit the width of the page into the window.
+    FitH {
+        /// The page that is shown.
+        page: PageId,
+
+        /// Vertical coordinate shown at the top edge of the window.
+        top: Option<Unit>,
+    },
+
+    /// Show the page with the given horizontal coordinate at the left edge of the window,
+    /// magnified to fit the height of the page into the window.
+    FitV {
+        /// The page that is shown.
+        page: PageId,
+
+        /// Horizontal coordinate shown at the left edge of the window.
+        left: Option<Unit>,
+    },
+
+    /// Show the given rectangle of the page, magnified to fit the window.
+    FitR {
+        /// The page that is shown.
+        page: PageId,
+
+        /// The rectangle of the page that is shown.
+        rect: Rectangle,
+    },
+}
+
+impl Destination {
+    const_identifiers! {
+        XYZ: b"XYZ",
+        FIT,
+        FIT_H: b"FitH",
+        FIT_V: b"FitV",
+        FIT_R: b"FitR",
+    }
+
+    /// Returns the page shown by this `Destination`.
+    pub fn page(&self) -> &PageId {
+        match self {
+            Destination::Xyz { page, .. }
+            | Destination::Fit { page }
+            | Destination::FitH { page, .. }
+            | Destination::FitV { page, .. }
+            | Destination::FitR { page, .. } => page,
+        }
+    }
+
+    /// Returns the name identifying the kind of view of this `Destination`.
+    fn kind(&self) -> Identifier<&'static [u8]> {
+        match self {
+            Destination::Xyz { .. } => Self::XYZ,
+            Destination::Fit { .. } => Self::FIT,
+            Destination::FitH { .. } => Self::FIT_H,
+            Destination::FitV { .. } => Self::FIT_V,
+            Destination::FitR { .. } => Self::FIT_R,
+        }
+    }
+
+    /// Returns the parameters of the view of this `Destination`, where `None` is written as
+    /// `null`.
+    fn parameters(&self) -> Vec<Option<String>> {
+        let unit = |unit: &Option<Unit>| unit.map(|unit| unit.to_string());
+
+        match self {
+            Destination::Xyz {
+                left, top, zoom, ..
+            } => vec![unit(left), unit(top), zoom.map(|zoom| zoom.to_string())],
+            Destination::Fit { .. } => Vec::new(),
+            Destination::FitH { top, .. } => vec![unit(top)],
+            Destination::FitV { left, .. } => vec![unit(left)],
+            Destination::FitR { rect, .. } => {
+                let (low_left, top_right) = (rect.low_left(), rect.top_right());
+
+                [low_left.x, low_left.y, top_right.x, top_right.y]
+                    .iter()
+                    .map(|coordinate| Some(coordinate.to_string()))
+                    .collect()
+            }
+        }
+    }
+
+    /// Encode and write this `Destination` as an array into the provided implementor of [`Write`],
+    /// e.g. `[3 0 R /XYZ null 700 1.5]`.
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"["),
+            self.page().clone().into_obj_id().write_ref(writer),
+            writer.write(b" "),
+            // Identifier is followed by a space
+            self.kind().write(writer),
+            {
+                let parameters: Vec<_> = self
+                    .parameters()
+                    .into_iter()
+                    .map(|parameter| parameter.unwrap_or_else(|| "null".to_string()))
+                    .collect();
+                writer.write(parameters.join(" ").as_bytes())
+            },
+
+            writer.write(b"]"),
+        })
+    }
+}
+
+/// A reference to the [`Destination`] shown when e.g. an outline item is activated, either given
+/// explicitly or by the name it was added to the document with.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::destination::{Destination, DestinationRef}};
+/// let mut document = Document::default();
+/// let page = document.create_page().id();
+///
+/// let chapter = document.add_named_destination("chapter-1", Destination::Fit { page }).unwrap();
+/// document.set_open_action(DestinationRef::named("chapter-1")).unwrap();
+///
+/// // the same destination, referenced by the handle returned when it was added
+/// document.set_open_action(chapter).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum DestinationRef {
+    /// The given [`Destination`] itself.
+    Explicit(Destination),
+
+    /// The [`Destination`] added to the document with the given name.
+    Named(String),
+}
+
+impl DestinationRef {
+    /// Creates a new `DestinationRef` referencing the [`Destination`] added to the document with
+    /// the given name.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self::Named(name.into())
+    }
+
+    /// Encode and write this `DestinationRef` into the provided implementor of [`Write`], either
+    /// as an explicit destination array or as the string of the name.
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match self {
+            DestinationRef::Explicit(destination) => destination.write(writer),
+            DestinationRef::Named(name) => PdfString::from(name.as_str()).write_content(writer),
+        }
+    }
+}
+
+impl From<Destination> for DestinationRef {
+    fn from(destination: Destination) -> Self {
+        Self::Explicit(destination)
+    }
+}
+
+impl From<DestinationId> for DestinationRef {
+    fn from(destination_id: DestinationId) -> Self {
+        Self::Named(destination_id.into_name())
+    }
+}
+
+impl From<&DestinationId> for DestinationRef {
+    fn from(destination_id: &DestinationId) -> Self {
+        Self::Named(destination_id.name().to_string())
+    }
+}
+
+/// The named destinations of a document, written as the `Dests` name tree of the document's name
+/// dictionary.
+#[derive(Debug, Clone)]
+pub(crate) struct NamedDestinations {
+    /// ID of the root node of the name tree.
+    id: ObjId<Self>,
+
+    /// The destinations by their names, sorted as required for the keys of a name tree.
+    destinations: BTreeMap<String, Destination>,
+}
+
+impl NamedDestinations {
+    const_identifiers! {
+        NAMES,
+    }
+
+    /// Creates a new empty `NamedDestinations`.
+    pub(crate) fn new(id_manager: &mut IdManager) -> Self {
+        Self {
+            id: id_manager.create_id(),
+            destinations: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the [`ObjId`] of the root node of the name tree.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Adds the given [`Destination`] with the given name, replacing the destination previously
+    /// added with the same name.
+    pub(crate) fn insert(&mut self, name: String, destination: Destination) {
+        self.destinations.insert(name, destination);
+    }
+
+    /// Returns whether a destination with the given name was added.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.destinations.contains_key(name)
+    }
+}
+
+impl Object for NamedDestinations {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Self::NAMES.write(writer),
+            writer.write(b"["),
+
+            writer.write(constants::NL_MARKER),
+
+            for (name, destination) in &self.destinations {
+                PdfString::from(name.as_str()).write_content(writer),
+                writer.write(b" "),
+                destination.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            writer.write(b"]"),
+            writer.write(constants::NL_MARKER),
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        Document, IdManager,
+        types::hierarchy::primitives::{object::Object, rectangle::Rectangle, unit::Unit},
+    };
+
+    use super::{Destination, NamedDestinations};
+
+    #[test]
+    fn destinations() {
+        let mut document = Document::default();
+        let page = document.create_page().id();
+
+        let destinations = [
+            Destination::Xyz {
+                page: page.clone(),
+                left: None,
+                top: Some(Unit::from_unit(700.0)),
+                zoom: Some(1.5),
+            },
+            Destination::Fit { page: page.clone() },
+            Destination::FitH {
+                page: page.clone(),
+                top: Some(Unit::from_unit(500.0)),
+            },
+            Destination::FitV {
+                page: page.clone(),
+                left: None,
+            },
+            Destination::FitR {
+                page,
+                rect: Rectangle::from_units(10.0, 20.0, 300.0, 400.0),
+            },
+        ];
+
+        let mut writer = Vec::new();
+        for destination in destinations {
+            destination.write(&mut writer).unwrap();
+            writer.push(b'\n');
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [3 0 R /XYZ null 700 1.5]
+        [3 0 R /Fit ]
+        [3 0 R /FitH 500]
+        [3 0 R /FitV null]
+        [3 0 R /FitR 10 20 300 400]
+        ");
+    }
+
+    #[test]
+    fn named_destinations() {
+        let mut document = Document::default();
+        let first = document.create_page().id();
+        let second = document.create_page().id();
+
+        let mut named = NamedDestinations::new(&mut IdManager::new());
+        named.insert("summary".into(), Destination::Fit { page: second });
+        named.insert("intro".into(), Destination::Fit { page: first });
+
+        let mut writer = Vec::new();
+        named.write_def(&mut writer).unwrap();
+        named.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Names [
+        (intro) [3 0 R /Fit ]
+        (summary) [5 0 R /Fit ]
+        ]
+        >>
+        ");
+    }
+}
@@ -0,0 +1,441 @@
+//! Implementation of the document part hierarchy (PDF 2.0), which groups page ranges of a document
+//! into parts with their own metadata.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{IdManager, ObjId, PageId, SanitizePolicy, types::constants};
+
+use super::{
+    page::Page,
+    primitives::{
+        array::WriteArray,
+        identifier::{Identifier, OwnedIdentifier},
+        object::Object,
+        string::PdfString,
+    },
+};
+
+/// Possible errors that might be returned when adding a [`DocumentPart`] to a document.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum DocumentPartErr {
+    /// Indicates that the first or the last page of the part is not a page of the document.
+    #[error("Document part refers to a page that is not in the document.")]
+    UnknownPage,
+
+    /// Indicates that the last page of the part comes before its first page.
+    #[error("Last page of a document part must not come before its first page.")]
+    InvalidPageRange,
+
+    /// Indicates that a page of the part already belongs to another part.
+    #[error("Page is already part of another document part.")]
+    OverlappingParts,
+}
+
+/// A document part groups a range of consecutive pages, for example one invoice in a batch print
+/// file. Print systems use document parts and their metadata to split and route the pages of
+/// high-volume documents.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::document_part::DocumentPart};
+/// let mut document = Document::default();
+/// let first = document.create_page().id();
+/// let last = document.create_page().id();
+///
+/// let invoice = DocumentPart::new(first, last)
+///     .with_metadata("InvoiceNumber", "2025-0042")
+///     .with_metadata("Recipient", "ACME Corp.");
+/// document.add_document_part(invoice).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct DocumentPart {
+    /// The first page of this part.
+    start: PageId,
+
+    /// The last page of this part.
+    end: PageId,
+
+    /// Metadata of this part, written as the document part metadata (`DPM`) dictionary.
+    metadata: Vec<(OwnedIdentifier, PdfString)>,
+}
+
+impl DocumentPart {
+    const_identifiers! {
+        D_PART: b"DPart",
+        D_PARTS: b"DParts",
+        PARENT,
+        START,
+        END,
+        DPM: b"DPM",
+    }
+
+    /// Creates a new `DocumentPart` spanning the pages from `start` to `end`, inclusive.
+    pub fn new(start: PageId, end: PageId) -> Self {
+        Self {
+            start,
+            end,
+            metadata: Vec::new(),
+        }
+    }
+
+    /// Adds a metadata entry with the given key and value to this `DocumentPart`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key is empty or contains `'/'`.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata.push((
+            Identifier::new(key.as_bytes().to_vec()),
+            PdfString::text_string(value),
+        ));
+        self
+    }
+
+    /// Returns this `DocumentPart` with the keys and values of its metadata sanitized according to
+    /// the given [`SanitizePolicy`].
+    pub(crate) fn sanitized(self, policy: &SanitizePolicy) -> Self {
+        let metadata = self
+            .metadata
+            .into_iter()
+            .map(|(key, value)| {
+                let key = policy.name(&String::from_utf8_lossy(key.as_bytes()));
+                (
+                    Identifier::new(key.into_bytes()),
+                    PdfString::text_string(policy.metadata(&value.to_text())),
+                )
+            })
+            .collect();
+
+        Self { metadata, ..self }
+    }
+
+    /// Returns the first page of this `DocumentPart`.
+    pub fn start(&self) -> &PageId {
+        &self.start
+    }
+
+    /// Returns the last page of this `DocumentPart`.
+    pub fn end(&self) -> &PageId {
+        &self.end
+    }
+
+    /// Writes the `DPM` entry of this part, if it has any metadata.
+    fn write_metadata(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        if self.metadata.is_empty() {
+            return Ok(0);
+        }
+
+        Ok(pdfgen_macros::write_chain! {
+            Self::DPM.write(writer),
+            writer.write(b"<< "),
+            for (key, value) in &self.metadata {
+                key.write(writer),
+                value.write_content(writer),
+                writer.write(constants::SP),
+            },
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// The document part hierarchy of a document, referenced from the document's catalog as the
+/// `DPartRoot` entry. Parts form a single level below the root node, each part being a record.
+#[derive(Debug)]
+pub(crate) struct DocumentParts {
+    /// ID of the document part root dictionary.
+    id: ObjId<Self>,
+
+    /// ID of the root node of the document part hierarchy.
+    root_node: ObjId<DocumentPart>,
+
+    /// Parts of the document, in page order.
+    parts: Vec<(ObjId<DocumentPart>, DocumentPart)>,
+}
+
+impl DocumentParts {
+    const_identifiers! {
+        D_PART_ROOT: b"DPartRoot",
+        D_PART_ROOT_NODE: b"DPartRootNode",
+        RECORD_LEVEL,
+    }
+
+    /// Creates a new empty `DocumentParts` hierarchy.
+    pub(crate) fn new(id_manager: &mut IdManager) -> Self {
+        Self {
+            id: id_manager.create_id(),
+            root_node: id_manager.create_id(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// Returns the [`ObjId`] of the document part root dictionary.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Adds the given [`DocumentPart`] to this hierarchy, assigning its pages to it. The part is
+    /// placed according to the position of its pages in the document.
+    pub(crate) fn add(
+        &mut self,
+        id_manager: &mut IdManager,
+        pages: &mut [Page],
+        part: DocumentPart,
+    ) -> Result<(), DocumentPartErr> {
+        let position = |page_id: &PageId| pages.iter().position(|page| page.id() == *page_id);
+
+        let start = position(&part.start).ok_or(DocumentPartErr::UnknownPage)?;
+        let end = position(&part.end).ok_or(DocumentPartErr::UnknownPage)?;
+
+        if end < start {
+            return Err(DocumentPartErr::InvalidPageRange);
+        }
+
+        let idx = self
+            .parts
+            .partition_point(|(_, other)| position(&other.start) < Some(start));
+
+        let pages = &mut pages[start..=end];
+        if pages.iter().any(|page| page.document_part().is_some()) {
+            return Err(DocumentPartErr::OverlappingParts);
+        }
+
+        let id: ObjId<DocumentPart> = id_manager.create_id();
+        for page in pages {
+            page.set_document_part(id.clone());
+        }
+
+        self.parts.insert(idx, (id, part));
+
+        Ok(())
+    }
+
+    /// Returns the nodes of the document part hierarchy, starting with the root node.
+    pub(crate) fn nodes(&self) -> impl Iterator<Item = DocumentPartNode<'_>> {
+        std::iter::once(DocumentPartNode::Root {
+            id: &self.root_node,
+            parts: &self.parts,
+        })
+        .chain(self.parts.iter().map(|(id, part)| DocumentPartNode::Leaf {
+            id,
+            parent: &self.root_node,
+            part,
+        }))
+    }
+}
+
+impl Object for DocumentParts {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::D_PART_ROOT.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::D_PART_ROOT_NODE.write(writer),
+            self.root_node.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            // every part directly below the root node is a record
+            Self::RECORD_LEVEL.write(writer),
+            writer.write(b"1"),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// A single node of the document part hierarchy, written as a `DPart` dictionary.
+#[derive(Debug)]
+pub(crate) enum DocumentPartNode<'a> {
+    /// The root node, referencing all parts of the document.
+    Root {
+        id: &'a ObjId<DocumentPart>,
+        parts: &'a [(ObjId<DocumentPart>, DocumentPart)],
+    },
+
+    /// A node of a single part, referencing its range of pages.
+    Leaf {
+        id: &'a ObjId<DocumentPart>,
+        parent: &'a ObjId<DocumentPart>,
+        part: &'a DocumentPart,
+    },
+}
+
+impl DocumentPartNode<'_> {
+    /// Returns the [`ObjId`] of this node.
+    pub(crate) fn obj_ref(&self) -> &ObjId<DocumentPart> {
+        let (DocumentPartNode::Root { id, .. } | DocumentPartNode::Leaf { id, .. }) = self;
+        id
+    }
+
+    /// Writes the entries specific to the kind of this node.
+    fn write_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(match self {
+            DocumentPartNode::Root { parts, .. } => {
+                let ids: Vec<ObjId> = parts.iter().map(|(id, _)| id.clone().cast()).collect();
+
+                // child nodes are written as an array of page-ordered arrays of nodes
+                pdfgen_macros::write_chain! {
+                    DocumentPart::D_PARTS.write(writer),
+                    writer.write(b"["),
+                    ids.write_array(writer, None),
+                    writer.write(b"]"),
+                    writer.write(constants::NL_MARKER),
+                }
+            }
+            DocumentPartNode::Leaf { parent, part, .. } => pdfgen_macros::write_chain! {
+                DocumentPart::PARENT.write(writer),
+                parent.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+
+                DocumentPart::START.write(writer),
+                part.start.clone().into_obj_id().write_ref(writer),
+                writer.write(constants::NL_MARKER),
+
+                if part.end != part.start {
+                    DocumentPart::END.write(writer),
+                    part.end.clone().into_obj_id().write_ref(writer),
+                    writer.write(constants::NL_MARKER),
+                },
+
+                part.write_metadata(writer),
+            },
+        })
+    }
+}
+
+impl Object for DocumentPartNode<'_> {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.obj_ref().write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            DocumentPart::D_PART.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            self.write_entries(writer),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::{page::Page, primitives::object::Object},
+    };
+
+    use super::{DocumentPart, DocumentPartErr, DocumentParts};
+
+    fn create_pages(id_manager: &mut IdManager, count: usize) -> Vec<Page> {
+        let parent = id_manager.create_id();
+        (0..count)
+            .map(|_| {
+                Page::new(
+                    id_manager.create_id(),
+                    id_manager.create_id(),
+                    parent.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn invalid_parts() {
+        let mut id_manager = IdManager::new();
+        let mut pages = create_pages(&mut id_manager, 3);
+        let other_page = create_pages(&mut id_manager, 1).remove(0);
+        let mut parts = DocumentParts::new(&mut id_manager);
+
+        let part = DocumentPart::new(pages[0].id(), other_page.id());
+        assert_eq!(
+            parts.add(&mut id_manager, &mut pages, part),
+            Err(DocumentPartErr::UnknownPage)
+        );
+
+        let part = DocumentPart::new(pages[2].id(), pages[1].id());
+        assert_eq!(
+            parts.add(&mut id_manager, &mut pages, part),
+            Err(DocumentPartErr::InvalidPageRange)
+        );
+
+        let part = DocumentPart::new(pages[0].id(), pages[1].id());
+        assert_eq!(parts.add(&mut id_manager, &mut pages, part), Ok(()));
+
+        let part = DocumentPart::new(pages[1].id(), pages[2].id());
+        assert_eq!(
+            parts.add(&mut id_manager, &mut pages, part),
+            Err(DocumentPartErr::OverlappingParts)
+        );
+    }
+
+    #[test]
+    fn document_part_nodes() {
+        let mut id_manager = IdManager::new();
+        let mut pages = create_pages(&mut id_manager, 3);
+        let mut parts = DocumentParts::new(&mut id_manager);
+
+        let second = DocumentPart::new(pages[2].id(), pages[2].id());
+        parts.add(&mut id_manager, &mut pages, second).unwrap();
+
+        let first = DocumentPart::new(pages[0].id(), pages[1].id())
+            .with_metadata("InvoiceNumber", "2025-0042");
+        parts.add(&mut id_manager, &mut pages, first).unwrap();
+
+        let mut writer = Vec::new();
+        parts.write_def(&mut writer).unwrap();
+        parts.write_content(&mut writer).unwrap();
+        for node in parts.nodes() {
+            node.write_def(&mut writer).unwrap();
+            node.write_content(&mut writer).unwrap();
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        8 0 obj
+        << /Type /DPartRoot 
+        /DPartRootNode 9 0 R
+        /RecordLevel 1
+        >>
+        9 0 obj
+        << /Type /DPart 
+        /DParts [[11 0 R
+         10 0 R]]
+        >>
+        11 0 obj
+        << /Type /DPart 
+        /Parent 9 0 R
+        /Start 2 0 R
+        /End 4 0 R
+        /DPM << /InvoiceNumber (2025-0042) >>
+        >>
+        10 0 obj
+        << /Type /DPart 
+        /Parent 9 0 R
+        /Start 6 0 R
+        >>
+        ");
+    }
+}
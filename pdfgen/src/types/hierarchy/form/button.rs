@@ -0,0 +1,272 @@
+//! Implementation of check boxes and radio buttons (12.7.5.2.3, 12.7.5.2.4), button fields that
+//! are toggled between an on state, named by their export value, and the `Off` state.
+
+use crate::{
+    PageId,
+    types::hierarchy::{
+        annotation::{Appearance, AppearanceBuilder},
+        content::{color::Color, path::Path},
+        primitives::{
+            identifier::Identifier,
+            rectangle::{Position, Rectangle},
+            unit::Unit,
+        },
+    },
+};
+
+use super::{FormFieldErr, InteractiveForm, WidgetAppearance, check_export_value, on_state};
+
+/// Export value of check boxes, used when none is set.
+const DEFAULT_EXPORT_VALUE: &str = "Yes";
+
+/// Width of the border drawn around buttons, in default user space units.
+const BORDER_WIDTH: f32 = 1.0;
+
+/// A check box form field, toggled between checked and unchecked by clicking its widget. When
+/// checked, the value of the field is its export value.
+#[derive(Debug, Clone)]
+pub struct CheckBox {
+    /// Name of the field.
+    name: String,
+
+    /// The page the check box is placed on.
+    page: PageId,
+
+    /// Location of the check box on the page.
+    rect: Rectangle,
+
+    /// Name of the on state, which is the value of the field when checked.
+    export_value: String,
+
+    /// Whether the check box is checked when the document is opened.
+    checked: bool,
+}
+
+impl CheckBox {
+    /// Creates a new unchecked `CheckBox` with the given name, placed on the given page at the
+    /// given [`Rectangle`].
+    pub fn new(name: impl Into<String>, page: PageId, rect: Rectangle) -> Self {
+        Self {
+            name: name.into(),
+            page,
+            rect,
+            export_value: DEFAULT_EXPORT_VALUE.to_string(),
+            checked: false,
+        }
+    }
+
+    /// Sets the value of the field when the check box is checked. Defaults to `Yes`.
+    pub fn with_export_value(mut self, value: impl Into<String>) -> Self {
+        self.export_value = value.into();
+        self
+    }
+
+    /// Sets whether the check box is checked when the document is opened.
+    pub fn with_checked(mut self, checked: bool) -> Self {
+        self.checked = checked;
+        self
+    }
+
+    /// Returns the name of this field.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the page and the rectangle of the widget of this check box.
+    pub(super) fn widget(&self) -> (&PageId, Rectangle) {
+        (&self.page, self.rect)
+    }
+
+    /// Checks that the export value of this check box is valid.
+    pub(super) fn check(&self) -> Result<(), FormFieldErr> {
+        check_export_value(&self.export_value)
+    }
+
+    /// Returns the current state of this check box, which is also the value of the field.
+    pub(super) fn state(&self) -> Identifier<&[u8]> {
+        match self.checked {
+            true => on_state(&self.export_value),
+            false => InteractiveForm::OFF,
+        }
+    }
+
+    /// Returns the appearances of the widget of this check box.
+    pub(super) fn appearance(&self) -> WidgetAppearance<'_> {
+        let (on_appearance, off_appearance) =
+            draw_states(self.rect, |rect| (Path::from(rect), check_mark(rect)));
+
+        WidgetAppearance::Toggle {
+            on_state: on_state(&self.export_value),
+            on: self.checked,
+            on_appearance,
+            off_appearance,
+        }
+    }
+}
+
+/// A group of radio buttons forming a single form field, of which at most one is on at a time.
+/// The value of the field is the export value of the selected button.
+#[derive(Debug, Clone)]
+pub struct RadioGroup {
+    /// Name of the field.
+    name: String,
+
+    /// Pages, locations and export values of the buttons of the group.
+    buttons: Vec<(PageId, Rectangle, String)>,
+
+    /// Export value of the button that is on when the document is opened.
+    selected: Option<String>,
+}
+
+impl RadioGroup {
+    /// Flags of radio button fields, marking the buttons as radio buttons that can't be toggled
+    /// off by clicking the selected one.
+    pub(super) const FLAGS: u32 = Self::RADIO | Self::NO_TOGGLE_TO_OFF;
+
+    /// Flag of button fields whose buttons can't all be off once one of them was selected.
+    const NO_TOGGLE_TO_OFF: u32 = 1 << 14;
+
+    /// Flag of button fields that are radio buttons.
+    const RADIO: u32 = 1 << 15;
+
+    /// Creates a new `RadioGroup` with the given name, without any buttons.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            buttons: Vec::new(),
+            selected: None,
+        }
+    }
+
+    /// Adds a radio button with the given export value to this group, placed on the given page at
+    /// the given [`Rectangle`]. The buttons of a group can be placed on different pages.
+    pub fn with_button(
+        mut self,
+        page: PageId,
+        rect: Rectangle,
+        export_value: impl Into<String>,
+    ) -> Self {
+        self.buttons.push((page, rect, export_value.into()));
+        self
+    }
+
+    /// Selects the button with the given export value when the document is opened. Without it,
+    /// all buttons are off.
+    pub fn with_selected(mut self, export_value: impl Into<String>) -> Self {
+        self.selected = Some(export_value.into());
+        self
+    }
+
+    /// Returns the name of this field.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the pages and the rectangles of the widgets of the buttons of this group.
+    pub(super) fn widgets(&self) -> impl Iterator<Item = (&PageId, Rectangle)> {
+        self.buttons.iter().map(|(page, rect, _)| (page, *rect))
+    }
+
+    /// Checks that this group has buttons, that their export values are valid and that the
+    /// selected value is one of them.
+    pub(super) fn check(&self) -> Result<(), FormFieldErr> {
+        if self.buttons.is_empty() {
+            return Err(FormFieldErr::EmptyGroup);
+        }
+
+        for (_, _, value) in &self.buttons {
+            check_export_value(value)?;
+        }
+
+        match &self.selected {
+            Some(selected) if !self.buttons.iter().any(|(_, _, value)| value == selected) => {
+                Err(FormFieldErr::UnknownSelection)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns the export value of the selected button, or the off state if none is selected.
+    pub(super) fn state(&self) -> Identifier<&[u8]> {
+        match &self.selected {
+            Some(selected) => on_state(selected),
+            None => InteractiveForm::OFF,
+        }
+    }
+
+    /// Returns the appearances of the widget of the button with the given index.
+    pub(super) fn appearance(&self, idx: usize) -> WidgetAppearance<'_> {
+        let (_, rect, value) = &self.buttons[idx];
+        let (on_appearance, off_appearance) = draw_states(*rect, |rect| {
+            let (center, radius) = circle(rect);
+            (
+                Path::ellipse(center, radius, radius),
+                Path::ellipse(center, radius / 2.0, radius / 2.0),
+            )
+        });
+
+        WidgetAppearance::Toggle {
+            on_state: on_state(value),
+            on: self.selected.as_ref() == Some(value),
+            on_appearance,
+            off_appearance,
+        }
+    }
+}
+
+/// Draws the appearances of the on and the off state of a button located at the given rectangle.
+/// Both states draw the border returned by `shapes`, and the on state additionally fills the
+/// returned mark.
+fn draw_states(
+    rect: Rectangle,
+    shapes: impl Fn(Rectangle) -> (Path, Path),
+) -> (Appearance, Appearance) {
+    let inset = rect.expanded(Unit::from_unit(-BORDER_WIDTH / 2.0));
+    let (border, mark) = shapes(inset);
+    let black = Color::Gray(0);
+
+    let mut on = AppearanceBuilder::new(rect);
+    on.set_line_width(BORDER_WIDTH)
+        .set_stroke_color(&black)
+        .set_fill_color(&black)
+        .stroke(&border)
+        .fill(&mark);
+
+    let mut off = AppearanceBuilder::new(rect);
+    off.set_line_width(BORDER_WIDTH)
+        .set_stroke_color(&black)
+        .stroke(&border);
+
+    (on.build(), off.build())
+}
+
+/// Returns the center and the radius of the largest circle inside of the given rectangle.
+fn circle(rect: Rectangle) -> (Position, f32) {
+    let (low_left, top_right) = (rect.low_left(), rect.top_right());
+    let (low_x, low_y) = (low_left.x.into_user_unit(), low_left.y.into_user_unit());
+    let (top_x, top_y) = (top_right.x.into_user_unit(), top_right.y.into_user_unit());
+
+    let center = Position::from_units((low_x + top_x) / 2.0, (low_y + top_y) / 2.0);
+    (center, (top_x - low_x).min(top_y - low_y) / 2.0)
+}
+
+/// Returns the closed [`Path`] of a check mark filling the given rectangle.
+fn check_mark(rect: Rectangle) -> Path {
+    let low_left = rect.low_left();
+    let (low_x, low_y) = (low_left.x.into_user_unit(), low_left.y.into_user_unit());
+    let (width, height) = (
+        rect.width().into_user_unit(),
+        rect.height().into_user_unit(),
+    );
+    // position relative to the rectangle, as fractions of its size
+    let at = |x: f32, y: f32| Position::from_units(low_x + x * width, low_y + y * height);
+
+    Path::new()
+        .move_to(at(0.15, 0.5))
+        .line_to(at(0.25, 0.6))
+        .line_to(at(0.42, 0.38))
+        .line_to(at(0.75, 0.85))
+        .line_to(at(0.85, 0.75))
+        .line_to(at(0.42, 0.18))
+        .close()
+}
@@ -0,0 +1,250 @@
+//! Implementation of choice fields (12.7.5.4), which let the user pick one of a list of options,
+//! either from a drop-down combo box or from a scrollable list box.
+
+use std::io::{Error, Write};
+
+use crate::{
+    ObjId, PageId,
+    types::{
+        constants,
+        hierarchy::{
+            annotation::{Appearance, AppearanceBuilder},
+            content::{color::Color, path::Path},
+            primitives::{
+                font::Font,
+                rectangle::{Position, Rectangle},
+                string::PdfString,
+                unit::Unit,
+            },
+        },
+    },
+};
+
+use super::{FONT_SIZE, FormFieldErr, InteractiveForm};
+
+/// Distance between the border of choice fields and their text, in default user space units.
+const PADDING: f32 = 2.0;
+
+/// Color filling the background of the selected option of list boxes.
+const SELECTION_COLOR: Color = Color::Rgb {
+    red: 153,
+    green: 193,
+    blue: 218,
+};
+
+/// A choice form field, letting the user pick one of its options. The value of the field is the
+/// selected option.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::form::ChoiceField};
+/// # use pdfgen::types::hierarchy::primitives::rectangle::Rectangle;
+/// let mut document = Document::default();
+/// let page = document.create_page().id();
+///
+/// let country = ChoiceField::combo_box("country", page, Rectangle::from_units(72., 700., 232., 716.))
+///     .with_options(["Austria", "Germany", "Switzerland"])
+///     .with_selected("Germany");
+/// document.add_form_field(country).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ChoiceField {
+    /// Name of the field.
+    name: String,
+
+    /// The page the field is placed on.
+    page: PageId,
+
+    /// Location of the field on the page.
+    rect: Rectangle,
+
+    /// Whether the options are shown in a drop-down list instead of a list box.
+    combo: bool,
+
+    /// Whether the user can enter a value other than the options, only used by combo boxes.
+    editable: bool,
+
+    /// The options the user can choose from, in the order they are shown.
+    options: Vec<String>,
+
+    /// The option that is selected when the document is opened, and when the form is reset.
+    selected: Option<String>,
+}
+
+impl ChoiceField {
+    /// Flag of choice fields that are combo boxes.
+    const COMBO: u32 = 1 << 17;
+
+    /// Flag of combo boxes whose value can be edited by the user.
+    const EDIT: u32 = 1 << 18;
+
+    /// Creates a new combo box with the given name, placed on the given page at the given
+    /// [`Rectangle`], showing its options in a drop-down list.
+    pub fn combo_box(name: impl Into<String>, page: PageId, rect: Rectangle) -> Self {
+        Self {
+            name: name.into(),
+            page,
+            rect,
+            combo: true,
+            editable: false,
+            options: Vec::new(),
+            selected: None,
+        }
+    }
+
+    /// Creates a new list box with the given name, placed on the given page at the given
+    /// [`Rectangle`], showing as many of its options as fit into the rectangle.
+    pub fn list_box(name: impl Into<String>, page: PageId, rect: Rectangle) -> Self {
+        Self {
+            combo: false,
+            ..Self::combo_box(name, page, rect)
+        }
+    }
+
+    /// Adds the given option to the options of this field.
+    pub fn with_option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(option.into());
+        self
+    }
+
+    /// Adds all of the given options to the options of this field.
+    pub fn with_options<S: Into<String>>(mut self, options: impl IntoIterator<Item = S>) -> Self {
+        self.options.extend(options.into_iter().map(Into::into));
+        self
+    }
+
+    /// Selects the given option when the document is opened, and when the form is reset. Without
+    /// it, no option is selected.
+    pub fn with_selected(mut self, option: impl Into<String>) -> Self {
+        self.selected = Some(option.into());
+        self
+    }
+
+    /// Sets whether the user can enter a value of their own in addition to choosing one of the
+    /// options. The selected value of editable fields doesn't need to be one of the options. Has
+    /// no effect on list boxes.
+    pub fn with_editable(mut self, editable: bool) -> Self {
+        self.editable = editable;
+        self
+    }
+
+    /// Returns the name of this field.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the page and the rectangle of the widget of this field.
+    pub(super) fn widget(&self) -> (&PageId, Rectangle) {
+        (&self.page, self.rect)
+    }
+
+    /// Returns the field flags of this field.
+    pub(super) fn flags(&self) -> u32 {
+        match (self.combo, self.is_editable()) {
+            (true, true) => Self::COMBO | Self::EDIT,
+            (true, false) => Self::COMBO,
+            (false, _) => 0,
+        }
+    }
+
+    /// Returns `true` if this field is a combo box whose value can be edited by the user.
+    fn is_editable(&self) -> bool {
+        self.combo && self.editable
+    }
+
+    /// Checks that the selected value is one of the options, unless the field is editable.
+    pub(super) fn check(&self) -> Result<(), FormFieldErr> {
+        match &self.selected {
+            Some(selected) if !(self.is_editable() || self.options.contains(selected)) => {
+                Err(FormFieldErr::UnknownSelection)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the `Opt` entry with the options of this field, and its value and default value if
+    /// an option is selected.
+    pub(super) fn write_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let selected = self.selected.as_deref().map(PdfString::text_string);
+
+        Ok(pdfgen_macros::write_chain! {
+            InteractiveForm::OPT.write(writer),
+            writer.write(b"["),
+            for (idx, option) in self.options.iter().enumerate() {
+                {
+                    let separator: &[u8] = if idx > 0 { constants::SP } else { b"" };
+                    writer.write(separator)
+                },
+                PdfString::text_string(option.as_str()).write_content(writer),
+            },
+            writer.write(b"]"),
+            writer.write(constants::NL_MARKER),
+
+            if let Some(selected) = &selected {
+                InteractiveForm::V.write(writer),
+                selected.write_content(writer),
+                writer.write(constants::NL_MARKER),
+
+                InteractiveForm::DV.write(writer),
+                selected.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+        })
+    }
+
+    /// Returns the appearance of the widget of this field, showing its text with the font with
+    /// the given [`ObjId`]. Combo boxes show the selected value, and list boxes the options from
+    /// the first one, with the selected option highlighted.
+    pub(super) fn appearance(&self, font_id: &ObjId<Font>) -> Appearance {
+        let mut appearance = AppearanceBuilder::new(self.rect);
+        let inset = self.rect.expanded(Unit::from_unit(-0.5));
+        let (low_left, top_right) = (self.rect.low_left(), self.rect.top_right());
+        let (low_x, low_y) = (low_left.x.into_user_unit(), low_left.y.into_user_unit());
+        let (top_x, top_y) = (top_right.x.into_user_unit(), top_right.y.into_user_unit());
+        let size = FONT_SIZE as f32;
+        // distance of the baseline from the bottom of a line of text
+        let descent = size * 0.25;
+
+        if self.combo {
+            if let Some(selected) = &self.selected {
+                let baseline = low_y + (top_y - low_y - size) / 2.0 + descent;
+                appearance.set_fill_color(&Color::Gray(0)).show_text(
+                    font_id,
+                    FONT_SIZE,
+                    Position::from_units(low_x + PADDING, baseline),
+                    selected,
+                );
+            }
+        } else {
+            let line_height = size * 1.2;
+            let lines = ((top_y - low_y - 2.0 * PADDING) / line_height).max(0.0) as usize;
+
+            for (idx, option) in self.options.iter().take(lines).enumerate() {
+                let line_top = top_y - PADDING - idx as f32 * line_height;
+                let line_low = line_top - line_height;
+
+                if self.selected.as_ref() == Some(option) {
+                    let line = Rectangle::from_units(low_x + 1.0, line_low, top_x - 1.0, line_top);
+                    appearance
+                        .set_fill_color(&SELECTION_COLOR)
+                        .fill(&Path::from(line));
+                }
+
+                appearance.set_fill_color(&Color::Gray(0)).show_text(
+                    font_id,
+                    FONT_SIZE,
+                    Position::from_units(low_x + PADDING, line_low + descent),
+                    option,
+                );
+            }
+        }
+
+        appearance
+            .set_line_width(1.0)
+            .set_stroke_color(&Color::Gray(0))
+            .stroke(&Path::from(inset));
+
+        appearance.build()
+    }
+}
@@ -0,0 +1,1417 @@
+//! Implementation of interactive forms (12.7), which gather information from the user with fields
+//! such as check boxes, radio buttons, drop-down lists, push buttons and text fields placed on the
+//! pages of a document.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{IdManager, ObjId, PageId, types::constants};
+
+use super::{
+    annotation::Appearance,
+    primitives::{
+        array::WriteArray, encoding::FontEncoding, font::Font, identifier::Identifier,
+        object::Object, rectangle::Rectangle, string::PdfString,
+    },
+};
+
+mod button;
+mod choice;
+mod push_button;
+mod text_field;
+pub use button::{CheckBox, RadioGroup};
+pub use choice::ChoiceField;
+pub use push_button::PushButton;
+pub use text_field::TextField;
+
+/// Size of the text shown in the appearances of fields.
+const FONT_SIZE: u32 = 10;
+
+/// Possible errors that might be returned when adding a [`FormField`] to a document.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum FormFieldErr {
+    /// Indicates that a widget of the field is placed on a page that is not in the document.
+    #[error("Form field refers to a page that is not in the document.")]
+    UnknownPage,
+
+    /// Indicates that the name of the field is empty or contains a period.
+    #[error("Name of a form field must not be empty or contain '.'.")]
+    InvalidName,
+
+    /// Indicates that the document already has a field with the same name.
+    #[error("Document already has a form field with the same name.")]
+    DuplicateName,
+
+    /// Indicates that an export value is empty, contains `'/'` or a null character, or is `Off`,
+    /// which is the name of the off state of buttons.
+    #[error("Export value must not be empty, contain '/' or a null character, or be 'Off'.")]
+    InvalidExportValue,
+
+    /// Indicates that the selected value is not one of the values of the field.
+    #[error("Selected value is not a value of the form field.")]
+    UnknownSelection,
+
+    /// Indicates that the field has no widgets to show it on a page.
+    #[error("Form field must have at least one widget.")]
+    EmptyGroup,
+
+    /// Indicates that a comb text field has no cells, or is a multiline or password field.
+    #[error("Comb text field must have cells and can't be a multiline or password field.")]
+    InvalidComb,
+
+    /// Indicates that the value of a text field is longer than its maximum length.
+    #[error("Value is longer than the maximum length of the text field.")]
+    ValueTooLong,
+}
+
+/// A field of the interactive form of a document, shown on its pages by one or more widgets.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::form::{CheckBox, RadioGroup}};
+/// # use pdfgen::types::hierarchy::primitives::rectangle::Rectangle;
+/// let mut document = Document::default();
+/// let page = document.create_page().id();
+///
+/// let terms = CheckBox::new("terms", page.clone(), Rectangle::from_units(72., 700., 84., 712.))
+///     .with_checked(true);
+/// document.add_form_field(terms).unwrap();
+///
+/// let shipping = RadioGroup::new("shipping")
+///     .with_button(page.clone(), Rectangle::from_units(72., 650., 84., 662.), "Standard")
+///     .with_button(page, Rectangle::from_units(72., 630., 84., 642.), "Express")
+///     .with_selected("Standard");
+/// document.add_form_field(shipping).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub enum FormField {
+    /// A check box, toggled between on and off.
+    CheckBox(CheckBox),
+
+    /// A group of radio buttons, of which at most one is on.
+    RadioGroup(RadioGroup),
+
+    /// A combo box or list box, offering a list of options.
+    Choice(ChoiceField),
+
+    /// A push button, performing an action when clicked.
+    PushButton(PushButton),
+
+    /// A text field, letting the user enter text.
+    Text(TextField),
+}
+
+impl From<CheckBox> for FormField {
+    fn from(value: CheckBox) -> Self {
+        Self::CheckBox(value)
+    }
+}
+
+impl From<RadioGroup> for FormField {
+    fn from(value: RadioGroup) -> Self {
+        Self::RadioGroup(value)
+    }
+}
+
+impl From<ChoiceField> for FormField {
+    fn from(value: ChoiceField) -> Self {
+        Self::Choice(value)
+    }
+}
+
+impl From<PushButton> for FormField {
+    fn from(value: PushButton) -> Self {
+        Self::PushButton(value)
+    }
+}
+
+impl From<TextField> for FormField {
+    fn from(value: TextField) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl FormField {
+    /// Returns the name of this field.
+    pub(crate) fn name(&self) -> &str {
+        match self {
+            FormField::CheckBox(check_box) => check_box.name(),
+            FormField::RadioGroup(group) => group.name(),
+            FormField::Choice(choice) => choice.name(),
+            FormField::PushButton(button) => button.name(),
+            FormField::Text(text) => text.name(),
+        }
+    }
+
+    /// Returns the pages and rectangles of the widgets of this field, in the order they are
+    /// written.
+    fn widgets(&self) -> Vec<(&PageId, Rectangle)> {
+        match self {
+            FormField::CheckBox(check_box) => vec![check_box.widget()],
+            FormField::RadioGroup(group) => group.widgets().collect(),
+            FormField::Choice(choice) => vec![choice.widget()],
+            FormField::PushButton(button) => vec![button.widget()],
+            FormField::Text(text) => vec![text.widget()],
+        }
+    }
+
+    /// Returns the pages the widgets of this field are placed on.
+    pub(crate) fn pages(&self) -> impl Iterator<Item = &PageId> {
+        self.widgets().into_iter().map(|(page, _)| page)
+    }
+
+    /// Checks that this field is valid on its own, regardless of the document it's added to.
+    pub(crate) fn check(&self) -> Result<(), FormFieldErr> {
+        let name = self.name();
+        if name.is_empty() || name.contains('.') {
+            return Err(FormFieldErr::InvalidName);
+        }
+
+        match self {
+            FormField::CheckBox(check_box) => check_box.check(),
+            FormField::RadioGroup(group) => group.check(),
+            FormField::Choice(choice) => choice.check(),
+            FormField::PushButton(_) => Ok(()),
+            FormField::Text(text) => text.check(),
+        }
+    }
+
+    /// Returns `true` if the widgets of this field show text, which is drawn with the font of the
+    /// interactive form.
+    fn shows_text(&self) -> bool {
+        matches!(
+            self,
+            FormField::Choice(_) | FormField::PushButton(_) | FormField::Text(_)
+        )
+    }
+
+    /// Returns the type of this field, written as its `FT` entry.
+    fn field_type(&self) -> Identifier<&'static [u8]> {
+        match self {
+            FormField::CheckBox(_) | FormField::RadioGroup(_) | FormField::PushButton(_) => {
+                InteractiveForm::BTN
+            }
+            FormField::Choice(_) => InteractiveForm::CH,
+            FormField::Text(_) => InteractiveForm::TX,
+        }
+    }
+
+    /// Returns the field flags of this field, written as its `Ff` entry.
+    fn flags(&self) -> u32 {
+        match self {
+            FormField::CheckBox(_) => 0,
+            FormField::RadioGroup(_) => RadioGroup::FLAGS,
+            FormField::Choice(choice) => choice.flags(),
+            FormField::PushButton(_) => PushButton::FLAGS,
+            FormField::Text(text) => text.flags(),
+        }
+    }
+
+    /// Writes the entries specific to the type of this field, such as its value.
+    fn write_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let state = match self {
+            FormField::CheckBox(check_box) => check_box.state(),
+            FormField::RadioGroup(group) => group.state(),
+            FormField::Choice(choice) => return choice.write_entries(writer),
+            FormField::Text(text) => return text.write_entries(writer),
+            // push buttons have no value
+            FormField::PushButton(_) => return Ok(0),
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            InteractiveForm::V.write(writer),
+            state.write(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Writes the entries specific to the type of this field into the dictionaries of its
+    /// widgets, such as the action of push buttons.
+    fn write_widget_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match self {
+            FormField::PushButton(button) => button.write_widget_entries(writer),
+            _ => Ok(0),
+        }
+    }
+
+    /// Returns the [`WidgetAppearance`] of the widget with the given index, showing text with
+    /// the given [`Font`].
+    fn widget_appearance(&self, idx: usize, font: Option<&Font>) -> WidgetAppearance<'_> {
+        let font = || font.expect("Form has a font for fields showing text.");
+
+        match self {
+            FormField::CheckBox(check_box) => check_box.appearance(),
+            FormField::RadioGroup(group) => group.appearance(idx),
+            FormField::Choice(choice) => {
+                WidgetAppearance::Normal(choice.appearance(font().obj_ref()))
+            }
+            FormField::PushButton(button) => WidgetAppearance::Normal(button.appearance(font())),
+            FormField::Text(text) => WidgetAppearance::Normal(text.appearance(font())),
+        }
+    }
+}
+
+/// Returns the given export value of a button as the name of its on state. The value must have
+/// been checked with [`check_export_value`].
+fn on_state(value: &str) -> Identifier<&[u8]> {
+    Identifier::new(value.as_bytes())
+}
+
+/// Checks that the given export value can be used as the name of the on state of a button.
+fn check_export_value(value: &str) -> Result<(), FormFieldErr> {
+    if value.is_empty()
+        || value.contains(['/', '\0'])
+        || value.as_bytes() == InteractiveForm::OFF.as_bytes()
+    {
+        return Err(FormFieldErr::InvalidExportValue);
+    }
+
+    Ok(())
+}
+
+/// A widget annotation (12.5.6.19), showing a field of the interactive form on a page.
+#[derive(Debug)]
+pub(crate) struct Widget;
+
+/// The appearances of a widget, together with its current appearance state if it has any.
+#[derive(Debug)]
+enum WidgetAppearance<'a> {
+    /// Appearances of a button that is toggled between an on state and the off state.
+    Toggle {
+        /// The name of the on state of the widget, i.e. its export value.
+        on_state: Identifier<&'a [u8]>,
+
+        /// Whether the widget is currently in its on state.
+        on: bool,
+
+        /// The appearance of the on state.
+        on_appearance: Appearance,
+
+        /// The appearance of the off state.
+        off_appearance: Appearance,
+    },
+
+    /// The single appearance of a widget without appearance states.
+    Normal(Appearance),
+}
+
+impl WidgetAppearance<'_> {
+    /// Returns all appearances, in the order they are written.
+    fn appearances(&self) -> Vec<&Appearance> {
+        match self {
+            WidgetAppearance::Toggle {
+                on_appearance,
+                off_appearance,
+                ..
+            } => vec![on_appearance, off_appearance],
+            WidgetAppearance::Normal(appearance) => vec![appearance],
+        }
+    }
+}
+
+/// A field added to the [`InteractiveForm`], together with the [`ObjId`]s allocated to it and its
+/// widgets.
+#[derive(Debug)]
+struct FieldEntry {
+    /// ID of the field dictionary.
+    id: ObjId<FormField>,
+
+    /// IDs of the widgets of the field, in the order of [`FormField::widgets`].
+    widget_ids: Vec<ObjId<Widget>>,
+
+    /// The field itself.
+    field: FormField,
+}
+
+/// The interactive form of a document, referenced from the document's catalog as the `AcroForm`
+/// entry. Every field is written as a field dictionary with its widgets as kids.
+#[derive(Debug)]
+pub(crate) struct InteractiveForm {
+    /// ID of the interactive form dictionary.
+    id: ObjId<Self>,
+
+    /// Fields of the form, in the order they were added.
+    fields: Vec<FieldEntry>,
+
+    /// Font showing the text of fields, added to the form together with the first such field.
+    font: Option<Font>,
+}
+
+impl InteractiveForm {
+    const_identifiers! {
+        FIELDS,
+        FT: b"FT",
+        BTN: b"Btn",
+        CH: b"Ch",
+        TX: b"Tx",
+        T: b"T",
+        FF: b"Ff",
+        V: b"V",
+        DV: b"DV",
+        OPT,
+        MAX_LEN,
+        DR: b"DR",
+        DA: b"DA",
+        HELV: b"Helv",
+        KIDS,
+        ANNOT,
+        SUBTYPE,
+        WIDGET,
+        RECT,
+        P: b"P",
+        F: b"F",
+        PARENT,
+        AS: b"AS",
+        AP: b"AP",
+        N: b"N",
+        OFF,
+        MK: b"MK",
+        CA: b"CA",
+        A: b"A",
+    }
+
+    /// Flag of widgets that are printed together with the page.
+    const PRINT: u32 = 1 << 2;
+
+    /// Base font of the font showing the text of fields.
+    const FONT: &'static str = "Helvetica";
+
+    /// Creates a new empty `InteractiveForm`.
+    pub(crate) fn new(id_manager: &mut IdManager) -> Self {
+        Self {
+            id: id_manager.create_id(),
+            fields: Vec::new(),
+            font: None,
+        }
+    }
+
+    /// Returns the [`ObjId`] of the interactive form dictionary.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Returns `true` if this form has a field with the given name.
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.fields.iter().any(|entry| entry.field.name() == name)
+    }
+
+    /// Adds the given [`FormField`] to this form. Returns the pages of its widgets together with
+    /// the [`ObjId`]s allocated to them, to be referenced from the pages.
+    pub(crate) fn add(
+        &mut self,
+        id_manager: &mut IdManager,
+        field: FormField,
+    ) -> Vec<(PageId, ObjId<Widget>)> {
+        if field.shows_text() && self.font.is_none() {
+            let mut font = Font::new(id_manager.create_id(), "Type1", Self::FONT);
+            font.set_encoding(FontEncoding::win_ansi());
+            self.font = Some(font);
+        }
+
+        let id = id_manager.create_id();
+        let widgets: Vec<(PageId, ObjId<Widget>)> = field
+            .pages()
+            .map(|page| (page.clone(), id_manager.create_id()))
+            .collect();
+
+        self.fields.push(FieldEntry {
+            id,
+            widget_ids: widgets.iter().map(|(_, id)| id.clone()).collect(),
+            field,
+        });
+
+        widgets
+    }
+
+    /// Writes the interactive form dictionary, followed by the field dictionaries, the widgets,
+    /// their appearances and the font showing the text of fields, into the provided implementor
+    /// of [`Write`]. Returns the number of bytes written and the offsets of all objects following
+    /// the interactive form dictionary.
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        id_manager: &mut IdManager,
+    ) -> Result<(usize, Vec<usize>), Error> {
+        let mut offsets = Vec::new();
+        let field_ids: Vec<ObjId> = self
+            .fields
+            .iter()
+            .map(|entry| entry.id.clone().cast())
+            .collect();
+        let font_id = self.font.as_ref().map(Font::obj_ref);
+        let widgets: Vec<WidgetEntry> = self
+            .fields
+            .iter()
+            .flat_map(|entry| {
+                let widgets = entry.field.widgets().into_iter().zip(&entry.widget_ids);
+                widgets
+                    .enumerate()
+                    .map(|(idx, ((page, rect), id))| {
+                        let appearance = entry.field.widget_appearance(idx, self.font.as_ref());
+                        let appearance_ids = appearance
+                            .appearances()
+                            .iter()
+                            .map(|_| id_manager.create_id())
+                            .collect();
+
+                        WidgetEntry {
+                            id,
+                            field: &entry.field,
+                            parent: &entry.id,
+                            page,
+                            rect,
+                            appearance,
+                            appearance_ids,
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        // appearances are written after all widgets, in the order of the widgets
+        let appearances = widgets.iter().flat_map(|widget| {
+            let appearances = widget.appearance.appearances();
+            appearances.into_iter().zip(&widget.appearance_ids)
+        });
+
+        let written = pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b"<< "),
+            Self::FIELDS.write(writer),
+            field_ids.write_array(writer, None),
+
+            if let Some(font_id) = font_id {
+                writer.write(constants::NL_MARKER),
+                Self::DR.write(writer),
+                writer.write(b"<< "),
+                Identifier::FONT.write(writer),
+                writer.write(b"<< "),
+                Self::HELV.write(writer),
+                font_id.write_ref(writer),
+                writer.write(b" >> >>"),
+                writer.write(constants::NL_MARKER),
+
+                Self::DA.write(writer),
+                PdfString::from(format!("/Helv {} Tf 0 g", FONT_SIZE)).write_content(writer),
+            },
+
+            writer.write(b" >>"),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+
+            for entry in &self.fields {
+                {
+                    offsets.push(written);
+                    Self::write_field(writer, entry)
+                },
+            },
+
+            for widget in &widgets {
+                {
+                    offsets.push(written);
+                    widget.write(writer)
+                },
+            },
+
+            for (appearance, id) in appearances {
+                {
+                    offsets.push(written);
+                    let (appearance_written, appearance_offsets) =
+                        appearance.write(writer, id, id_manager)?;
+                    offsets.extend(appearance_offsets.into_iter().map(|offset| written + offset));
+                    Ok::<_, Error>(appearance_written)
+                },
+            },
+
+            if let Some(font) = &self.font {
+                {
+                    offsets.push(written);
+                    font.write_def(writer)
+                },
+                font.write_content(writer),
+                font.write_end(writer),
+                writer.write(constants::NL_MARKER),
+            },
+        };
+
+        Ok((written, offsets))
+    }
+
+    /// Writes the field dictionary of the given [`FieldEntry`].
+    fn write_field(writer: &mut dyn Write, entry: &FieldEntry) -> Result<usize, Error> {
+        let flags = entry.field.flags();
+        let kids: Vec<ObjId> = entry
+            .widget_ids
+            .iter()
+            .map(|id| id.clone().cast())
+            .collect();
+
+        Ok(pdfgen_macros::write_chain! {
+            entry.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b"<< "),
+            Self::FT.write(writer),
+            entry.field.field_type().write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::T.write(writer),
+            PdfString::text_string(entry.field.name()).write_content(writer),
+            writer.write(constants::NL_MARKER),
+
+            if flags != 0 {
+                Self::FF.write(writer),
+                crate::write_fmt!(&mut *writer, "{flags}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            entry.field.write_entries(writer),
+
+            Self::KIDS.write(writer),
+            kids.write_array(writer, None),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// A widget of a field, together with the [`ObjId`]s it's written with.
+#[derive(Debug)]
+struct WidgetEntry<'a> {
+    /// ID of the widget annotation.
+    id: &'a ObjId<Widget>,
+
+    /// The field the widget belongs to.
+    field: &'a FormField,
+
+    /// ID of the field the widget belongs to.
+    parent: &'a ObjId<FormField>,
+
+    /// The page the widget is placed on.
+    page: &'a PageId,
+
+    /// Location of the widget on the page.
+    rect: Rectangle,
+
+    /// Appearances of the widget and its current state.
+    appearance: WidgetAppearance<'a>,
+
+    /// IDs of the appearances, in the order of [`WidgetAppearance::appearances`].
+    appearance_ids: Vec<ObjId<Appearance>>,
+}
+
+impl WidgetEntry<'_> {
+    /// Writes this widget as a widget annotation into the provided implementor of [`Write`].
+    fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            InteractiveForm::ANNOT.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            InteractiveForm::SUBTYPE.write(writer),
+            InteractiveForm::WIDGET.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            InteractiveForm::RECT.write(writer),
+            self.rect.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            InteractiveForm::P.write(writer),
+            self.page.clone().into_obj_id().write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            InteractiveForm::F.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", InteractiveForm::PRINT),
+            writer.write(constants::NL_MARKER),
+
+            InteractiveForm::PARENT.write(writer),
+            self.parent.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            self.field.write_widget_entries(writer),
+            self.write_appearance(writer),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Writes the `AS` entry with the current appearance state of this widget if it has any, and
+    /// the `AP` entry referencing its appearances.
+    fn write_appearance(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(match (&self.appearance, self.appearance_ids.as_slice()) {
+            (WidgetAppearance::Toggle { on_state, on, .. }, [on_id, off_id]) => {
+                pdfgen_macros::write_chain! {
+                    InteractiveForm::AS.write(writer),
+                    match on {
+                        true => on_state.write(writer),
+                        false => InteractiveForm::OFF.write(writer),
+                    },
+                    writer.write(constants::NL_MARKER),
+
+                    InteractiveForm::AP.write(writer),
+                    writer.write(b"<< "),
+                    InteractiveForm::N.write(writer),
+                    writer.write(b"<< "),
+                    on_state.write(writer),
+                    on_id.write_ref(writer),
+                    writer.write(constants::SP),
+                    InteractiveForm::OFF.write(writer),
+                    off_id.write_ref(writer),
+                    writer.write(b" >> >>"),
+                    writer.write(constants::NL_MARKER),
+                }
+            }
+            (WidgetAppearance::Normal(_), [id]) => pdfgen_macros::write_chain! {
+                InteractiveForm::AP.write(writer),
+                writer.write(b"<< "),
+                InteractiveForm::N.write(writer),
+                id.write_ref(writer),
+                writer.write(b" >>"),
+                writer.write(constants::NL_MARKER),
+            },
+            _ => unreachable!("Widgets have an ID for each of their appearances."),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager, PageId,
+        types::hierarchy::{action::Action, primitives::rectangle::Rectangle},
+    };
+
+    use super::{
+        CheckBox, ChoiceField, FormField, FormFieldErr, InteractiveForm, PushButton, RadioGroup,
+        TextField,
+    };
+
+    fn check(field: impl Into<FormField>) -> Result<(), FormFieldErr> {
+        field.into().check()
+    }
+
+    #[test]
+    fn invalid_fields() {
+        let mut id_manager = IdManager::new();
+        let page = PageId::new(id_manager.create_id());
+        let rect = Rectangle::from_units(0., 0., 10., 10.);
+
+        let check_box = |name: &str| CheckBox::new(name, page.clone(), rect);
+        assert_eq!(check(check_box("")), Err(FormFieldErr::InvalidName));
+        assert_eq!(check(check_box("a.b")), Err(FormFieldErr::InvalidName));
+        assert_eq!(
+            check(check_box("terms").with_export_value("Off")),
+            Err(FormFieldErr::InvalidExportValue)
+        );
+        assert_eq!(
+            check(check_box("terms").with_export_value("")),
+            Err(FormFieldErr::InvalidExportValue)
+        );
+        assert_eq!(
+            check(check_box("terms").with_export_value("a/b")),
+            Err(FormFieldErr::InvalidExportValue)
+        );
+        assert_eq!(check(check_box("terms")), Ok(()));
+
+        assert_eq!(
+            check(RadioGroup::new("shipping")),
+            Err(FormFieldErr::EmptyGroup)
+        );
+        let group = RadioGroup::new("shipping").with_button(page.clone(), rect, "Standard");
+        assert_eq!(
+            check(group.clone().with_selected("Express")),
+            Err(FormFieldErr::UnknownSelection)
+        );
+        assert_eq!(check(group.with_selected("Standard")), Ok(()));
+
+        let combo_box = ChoiceField::combo_box("country", page.clone(), rect)
+            .with_options(["Austria", "Germany"])
+            .with_selected("France");
+        assert_eq!(
+            check(combo_box.clone()),
+            Err(FormFieldErr::UnknownSelection)
+        );
+        assert_eq!(check(combo_box.with_editable(true)), Ok(()));
+        let list_box = ChoiceField::list_box("country", page.clone(), rect)
+            .with_option("Austria")
+            .with_selected("France")
+            .with_editable(true);
+        assert_eq!(check(list_box), Err(FormFieldErr::UnknownSelection));
+
+        let comb = TextField::new("code", page.clone(), rect).with_comb(5);
+        assert_eq!(check(comb.clone().with_value("12345")), Ok(()));
+        assert_eq!(
+            check(comb.clone().with_value("123456")),
+            Err(FormFieldErr::ValueTooLong)
+        );
+        assert_eq!(
+            check(comb.clone().with_multiline(true)),
+            Err(FormFieldErr::InvalidComb)
+        );
+        assert_eq!(
+            check(comb.with_password(true)),
+            Err(FormFieldErr::InvalidComb)
+        );
+        let empty_comb = TextField::new("code", page, rect);
+        assert_eq!(
+            check(empty_comb.with_comb(0)),
+            Err(FormFieldErr::InvalidComb)
+        );
+    }
+
+    #[test]
+    fn radio_group() {
+        let mut id_manager = IdManager::new();
+        let page = PageId::new(id_manager.create_id());
+        let mut form = InteractiveForm::new(&mut id_manager);
+
+        let group = RadioGroup::new("shipping")
+            .with_button(
+                page.clone(),
+                Rectangle::from_units(0., 0., 10., 10.),
+                "Standard",
+            )
+            .with_button(page, Rectangle::from_units(0., 20., 10., 30.), "Express")
+            .with_selected("Express");
+        let widgets = form.add(&mut id_manager, group.into());
+        assert_eq!(widgets.len(), 2);
+        assert!(form.contains("shipping"));
+
+        let mut writer = Vec::new();
+        let (_, offsets) = form.write(&mut writer, &mut id_manager).unwrap();
+        assert_eq!(offsets.len(), 7);
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        2 0 obj
+        << /Fields [3 0 R] >>
+        endobj
+
+        3 0 obj
+        << /FT /Btn 
+        /T (shipping)
+        /Ff 49152
+        /V /Express 
+        /Kids [4 0 R
+         5 0 R]
+        >>
+        endobj
+
+        4 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 0 10 10]
+        /P 1 0 R
+        /F 4
+        /Parent 3 0 R
+        /AS /Off 
+        /AP << /N << /Standard 6 0 R /Off 7 0 R >> >>
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 20 10 30]
+        /P 1 0 R
+        /F 4
+        /Parent 3 0 R
+        /AS /Express 
+        /AP << /N << /Express 8 0 R /Off 9 0 R >> >>
+        >>
+        endobj
+
+        6 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 0 10 10]
+        /Resources <<  >>
+        /Length 369 >>
+        stream
+        1 w
+        /DeviceGray CS
+        0 SC
+        /DeviceGray cs
+        0 sc
+        9.5 5 m
+        9.5 7.4852815 7.4852815 9.5 5 9.5 c
+        2.5147185 9.5 0.5 7.4852815 0.5 5 c
+        0.5 2.5147185 2.5147185 0.5 5 0.5 c
+        7.4852815 0.5 9.5 2.5147185 9.5 5 c
+        h
+        S
+        7.25 5 m
+        7.25 6.2426405 6.2426405 7.25 5 7.25 c
+        3.7573593 7.25 2.75 6.2426405 2.75 5 c
+        2.75 3.7573593 3.7573593 2.75 5 2.75 c
+        6.2426405 2.75 7.25 3.7573593 7.25 5 c
+        h
+        f
+
+        endstream
+        endobj
+
+        7 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 0 10 10]
+        /Resources <<  >>
+        /Length 180 >>
+        stream
+        1 w
+        /DeviceGray CS
+        0 SC
+        9.5 5 m
+        9.5 7.4852815 7.4852815 9.5 5 9.5 c
+        2.5147185 9.5 0.5 7.4852815 0.5 5 c
+        0.5 2.5147185 2.5147185 0.5 5 0.5 c
+        7.4852815 0.5 9.5 2.5147185 9.5 5 c
+        h
+        S
+
+        endstream
+        endobj
+
+        8 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 20 10 30]
+        /Resources <<  >>
+        /Length 387 >>
+        stream
+        1 w
+        /DeviceGray CS
+        0 SC
+        /DeviceGray cs
+        0 sc
+        9.5 25 m
+        9.5 27.485281 7.4852815 29.5 5 29.5 c
+        2.5147185 29.5 0.5 27.485281 0.5 25 c
+        0.5 22.514719 2.5147185 20.5 5 20.5 c
+        7.4852815 20.5 9.5 22.514719 9.5 25 c
+        h
+        S
+        7.25 25 m
+        7.25 26.242641 6.2426405 27.25 5 27.25 c
+        3.7573593 27.25 2.75 26.242641 2.75 25 c
+        2.75 23.757359 3.7573593 22.75 5 22.75 c
+        6.2426405 22.75 7.25 23.757359 7.25 25 c
+        h
+        f
+
+        endstream
+        endobj
+
+        9 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 20 10 30]
+        /Resources <<  >>
+        /Length 189 >>
+        stream
+        1 w
+        /DeviceGray CS
+        0 SC
+        9.5 25 m
+        9.5 27.485281 7.4852815 29.5 5 29.5 c
+        2.5147185 29.5 0.5 27.485281 0.5 25 c
+        0.5 22.514719 2.5147185 20.5 5 20.5 c
+        7.4852815 20.5 9.5 22.514719 9.5 25 c
+        h
+        S
+
+        endstream
+        endobj
+        ");
+    }
+
+    #[test]
+    fn choice_fields() {
+        let mut id_manager = IdManager::new();
+        let page = PageId::new(id_manager.create_id());
+        let mut form = InteractiveForm::new(&mut id_manager);
+
+        let combo_box = ChoiceField::combo_box(
+            "title",
+            page.clone(),
+            Rectangle::from_units(0., 40., 60., 56.),
+        )
+        .with_options(["Mr.", "Ms."])
+        .with_selected("Dr.")
+        .with_editable(true);
+        form.add(&mut id_manager, combo_box.into());
+        let list_box = ChoiceField::list_box("size", page, Rectangle::from_units(0., 0., 60., 30.))
+            .with_options(["S", "M", "L"])
+            .with_selected("M");
+        form.add(&mut id_manager, list_box.into());
+
+        let mut writer = Vec::new();
+        let (_, offsets) = form.write(&mut writer, &mut id_manager).unwrap();
+        // fields, widgets, appearances with a font resource for each shown text, and the font
+        assert_eq!(offsets.len(), 10);
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        2 0 obj
+        << /Fields [4 0 R
+         6 0 R]
+        /DR << /Font << /Helv 3 0 R >> >>
+        /DA (/Helv 10 Tf 0 g) >>
+        endobj
+
+        4 0 obj
+        << /FT /Ch 
+        /T (title)
+        /Ff 393216
+        /Opt [(Mr.) (Ms.)]
+        /V (Dr.)
+        /DV (Dr.)
+        /Kids [5 0 R]
+        >>
+        endobj
+
+        6 0 obj
+        << /FT /Ch 
+        /T (size)
+        /Opt [(S) (M) (L)]
+        /V (M)
+        /DV (M)
+        /Kids [7 0 R]
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 40 60 56]
+        /P 1 0 R
+        /F 4
+        /Parent 4 0 R
+        /AP << /N 8 0 R >>
+        >>
+        endobj
+
+        7 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 0 60 30]
+        /P 1 0 R
+        /F 4
+        /Parent 6 0 R
+        /AP << /N 9 0 R >>
+        >>
+        endobj
+
+        8 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 40 60 56]
+        /Resources << /Font << /F1 3 0 R  >> >>
+        /Length 99 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        BT
+        /F1 10 Tf
+        2 45.5 Td
+        (Dr.) Tj
+        ET
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 40.5 59 15 re
+        S
+
+        endstream
+        endobj
+
+        9 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 0 60 30]
+        /Resources << /Font << /F1 3 0 R /F2 3 0 R  >> >>
+        /Length 206 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        BT
+        /F1 10 Tf
+        2 18.5 Td
+        (S) Tj
+        ET
+        /DeviceRGB cs
+        0.6 0.75686276 0.85490197 sc
+        1 4 58 12 re
+        f
+        /DeviceGray cs
+        0 sc
+        BT
+        /F2 10 Tf
+        2 6.5 Td
+        (M) Tj
+        ET
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 0.5 59 29 re
+        S
+
+        endstream
+        endobj
+
+        3 0 obj
+        << /Type /Font 
+        /Subtype /Type1 
+        /BaseFont /Helvetica 
+        /Encoding /WinAnsiEncoding 
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn push_button() {
+        let mut id_manager = IdManager::new();
+        let page = PageId::new(id_manager.create_id());
+        let mut form = InteractiveForm::new(&mut id_manager);
+
+        let button = PushButton::new(
+            "reset",
+            page,
+            Rectangle::from_units(0., 0., 60., 20.),
+            "Reset",
+        )
+        .with_action(Action::ResetForm);
+        form.add(&mut id_manager, button.into());
+
+        let mut writer = Vec::new();
+        let (_, offsets) = form.write(&mut writer, &mut id_manager).unwrap();
+        assert_eq!(offsets.len(), 5);
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        2 0 obj
+        << /Fields [4 0 R]
+        /DR << /Font << /Helv 3 0 R >> >>
+        /DA (/Helv 10 Tf 0 g) >>
+        endobj
+
+        4 0 obj
+        << /FT /Btn 
+        /T (reset)
+        /Ff 65536
+        /Kids [5 0 R]
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 0 60 20]
+        /P 1 0 R
+        /F 4
+        /Parent 4 0 R
+        /MK << /CA (Reset) >>
+        /A << /Type /Action /S /ResetForm >>
+        /AP << /N 6 0 R >>
+        >>
+        endobj
+
+        6 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 0 60 20]
+        /Resources << /Font << /F1 3 0 R  >> >>
+        /Length 153 >>
+        stream
+        /DeviceGray cs
+        0.827451 sc
+        0.5 0.5 59 19 re
+        f
+        /DeviceGray cs
+        0 sc
+        BT
+        /F1 10 Tf
+        16.939999 7.5 Td
+        (Reset) Tj
+        ET
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 0.5 59 19 re
+        S
+
+        endstream
+        endobj
+
+        3 0 obj
+        << /Type /Font 
+        /Subtype /Type1 
+        /BaseFont /Helvetica 
+        /Encoding /WinAnsiEncoding 
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn text_fields() {
+        let mut id_manager = IdManager::new();
+        let page = PageId::new(id_manager.create_id());
+        let mut form = InteractiveForm::new(&mut id_manager);
+
+        let name = TextField::new(
+            "name",
+            page.clone(),
+            Rectangle::from_units(0., 60., 80., 76.),
+        )
+        .with_value("Jane")
+        .with_auto_size(true);
+        form.add(&mut id_manager, name.into());
+        let pin = TextField::new(
+            "pin",
+            page.clone(),
+            Rectangle::from_units(0., 40., 60., 56.),
+        )
+        .with_value("1234")
+        .with_password(true);
+        form.add(&mut id_manager, pin.into());
+        let code = TextField::new(
+            "code",
+            page.clone(),
+            Rectangle::from_units(0., 20., 30., 36.),
+        )
+        .with_value("AB")
+        .with_comb(3);
+        form.add(&mut id_manager, code.into());
+        let notes = TextField::new("notes", page, Rectangle::from_units(0., 0., 60., 16.))
+            .with_multiline(true);
+        form.add(&mut id_manager, notes.into());
+
+        let mut writer = Vec::new();
+        let (_, offsets) = form.write(&mut writer, &mut id_manager).unwrap();
+        // fields, widgets, appearances with a font resource for each shown text, and the font
+        assert_eq!(offsets.len(), 17);
+
+        let output = String::from_utf8(writer).unwrap();
+        insta::assert_snapshot!(output, @r"
+        2 0 obj
+        << /Fields [4 0 R
+         6 0 R
+         8 0 R
+         10 0 R]
+        /DR << /Font << /Helv 3 0 R >> >>
+        /DA (/Helv 10 Tf 0 g) >>
+        endobj
+
+        4 0 obj
+        << /FT /Tx 
+        /T (name)
+        /DA (/Helv 0 Tf 0 g)
+        /V (Jane)
+        /DV (Jane)
+        /Kids [5 0 R]
+        >>
+        endobj
+
+        6 0 obj
+        << /FT /Tx 
+        /T (pin)
+        /Ff 8192
+        /V (1234)
+        /DV (1234)
+        /Kids [7 0 R]
+        >>
+        endobj
+
+        8 0 obj
+        << /FT /Tx 
+        /T (code)
+        /Ff 16777216
+        /MaxLen 3
+        /V (AB)
+        /DV (AB)
+        /Kids [9 0 R]
+        >>
+        endobj
+
+        10 0 obj
+        << /FT /Tx 
+        /T (notes)
+        /Ff 4096
+        /Kids [11 0 R]
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 60 80 76]
+        /P 1 0 R
+        /F 4
+        /Parent 4 0 R
+        /AP << /N 12 0 R >>
+        >>
+        endobj
+
+        7 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 40 60 56]
+        /P 1 0 R
+        /F 4
+        /Parent 6 0 R
+        /AP << /N 13 0 R >>
+        >>
+        endobj
+
+        9 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 20 30 36]
+        /P 1 0 R
+        /F 4
+        /Parent 8 0 R
+        /AP << /N 14 0 R >>
+        >>
+        endobj
+
+        11 0 obj
+        << /Type /Annot 
+        /Subtype /Widget 
+        /Rect [0 0 60 16]
+        /P 1 0 R
+        /F 4
+        /Parent 10 0 R
+        /AP << /N 15 0 R >>
+        >>
+        endobj
+
+        12 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 60 80 76]
+        /Resources << /Font << /F1 3 0 R  >> >>
+        /Length 98 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        BT
+        /F1 12 Tf
+        2 65 Td
+        (Jane) Tj
+        ET
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 60.5 79 15 re
+        S
+
+        endstream
+        endobj
+
+        13 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 40 60 56]
+        /Resources << /Font << /F1 3 0 R  >> >>
+        /Length 100 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        BT
+        /F1 10 Tf
+        2 45.5 Td
+        (****) Tj
+        ET
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 40.5 59 15 re
+        S
+
+        endstream
+        endobj
+
+        14 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 20 30 36]
+        /Resources << /Font << /F1 3 0 R /F2 3 0 R  >> >>
+        /Length 197 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        BT
+        /F1 10 Tf
+        1.665 25.5 Td
+        (A) Tj
+        ET
+        BT
+        /F2 10 Tf
+        11.665 25.5 Td
+        (B) Tj
+        ET
+        1 w
+        /DeviceGray CS
+        0 SC
+        10 20 m
+        10 36 l
+        20 20 m
+        20 36 l
+        S
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 20.5 29 15 re
+        S
+
+        endstream
+        endobj
+
+        15 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 0 60 16]
+        /Resources <<  >>
+        /Length 63 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        1 w
+        /DeviceGray CS
+        0 SC
+        0.5 0.5 59 15 re
+        S
+
+        endstream
+        endobj
+
+        3 0 obj
+        << /Type /Font 
+        /Subtype /Type1 
+        /BaseFont /Helvetica 
+        /Encoding /WinAnsiEncoding 
+        >>
+        endobj
+        ");
+    }
+}
@@ -0,0 +1,154 @@
+//! Implementation of push buttons (12.7.5.2.2), button fields that don't retain a value and
+//! instead trigger an action when clicked, such as submitting or resetting the form.
+
+use std::io::{Error, Write};
+
+use crate::{
+    PageId,
+    types::{
+        constants,
+        hierarchy::{
+            action::Action,
+            annotation::{Appearance, AppearanceBuilder},
+            content::{color::Color, path::Path},
+            primitives::{
+                font::Font,
+                rectangle::{Position, Rectangle},
+                string::PdfString,
+                unit::Unit,
+            },
+        },
+    },
+};
+
+use super::{FONT_SIZE, InteractiveForm};
+
+/// Color filling the background of push buttons.
+const BACKGROUND_COLOR: Color = Color::Gray(211);
+
+/// A push button form field, showing a caption and performing its [`Action`] when clicked. Push
+/// buttons have no value.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::{action::{Action, SubmitFormat}, form::PushButton}};
+/// # use pdfgen::types::hierarchy::primitives::rectangle::Rectangle;
+/// let mut document = Document::default();
+/// let page = document.create_page().id();
+///
+/// let submit = PushButton::new("submit", page, Rectangle::from_units(72., 600., 144., 620.), "Submit")
+///     .with_action(Action::SubmitForm {
+///         url: "https://example.com/submit".to_string(),
+///         format: SubmitFormat::Html,
+///     });
+/// document.add_form_field(submit).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PushButton {
+    /// Name of the field.
+    name: String,
+
+    /// The page the button is placed on.
+    page: PageId,
+
+    /// Location of the button on the page.
+    rect: Rectangle,
+
+    /// Text shown on the button.
+    caption: String,
+
+    /// Action performed when the button is clicked.
+    action: Option<Action>,
+}
+
+impl PushButton {
+    /// Flag of button fields that are push buttons.
+    pub(super) const FLAGS: u32 = 1 << 16;
+
+    /// Creates a new `PushButton` with the given name and caption, placed on the given page at the
+    /// given [`Rectangle`]. Without an action, clicking the button has no effect.
+    pub fn new(
+        name: impl Into<String>,
+        page: PageId,
+        rect: Rectangle,
+        caption: impl Into<String>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            page,
+            rect,
+            caption: caption.into(),
+            action: None,
+        }
+    }
+
+    /// Sets the [`Action`] performed when the button is clicked.
+    pub fn with_action(mut self, action: Action) -> Self {
+        self.action = Some(action);
+        self
+    }
+
+    /// Returns the name of this field.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the page and the rectangle of the widget of this button.
+    pub(super) fn widget(&self) -> (&PageId, Rectangle) {
+        (&self.page, self.rect)
+    }
+
+    /// Writes the entries of the widget of this button, which are its caption as the `MK` entry
+    /// and its action as the `A` entry.
+    pub(super) fn write_widget_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            InteractiveForm::MK.write(writer),
+            writer.write(b"<< "),
+            InteractiveForm::CA.write(writer),
+            PdfString::text_string(self.caption.as_str()).write_content(writer),
+            writer.write(b" >>"),
+            writer.write(constants::NL_MARKER),
+
+            if let Some(action) = &self.action {
+                InteractiveForm::A.write(writer),
+                action.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+        })
+    }
+
+    /// Returns the appearance of the widget of this button, showing its caption centered on the
+    /// button with the given [`Font`].
+    pub(super) fn appearance(&self, font: &Font) -> Appearance {
+        let mut appearance = AppearanceBuilder::new(self.rect);
+        let inset = self.rect.expanded(Unit::from_unit(-0.5));
+        let low_left = self.rect.low_left();
+        let (low_x, low_y) = (low_left.x.into_user_unit(), low_left.y.into_user_unit());
+        let (width, height) = (
+            self.rect.width().into_user_unit(),
+            self.rect.height().into_user_unit(),
+        );
+        let size = FONT_SIZE as f32;
+        let caption_width = font
+            .measure(&self.caption, FONT_SIZE)
+            .width
+            .into_user_unit();
+        // the baseline is a quarter of the font size above the bottom of the centered text
+        let position = Position::from_units(
+            low_x + (width - caption_width) / 2.0,
+            low_y + (height - size) / 2.0 + size * 0.25,
+        );
+
+        appearance
+            .set_fill_color(&BACKGROUND_COLOR)
+            .fill(&Path::from(inset))
+            .set_fill_color(&Color::Gray(0))
+            .show_text(font.obj_ref(), FONT_SIZE, position, &self.caption)
+            .set_line_width(1.0)
+            .set_stroke_color(&Color::Gray(0))
+            .stroke(&Path::from(inset));
+
+        appearance.build()
+    }
+}
@@ -0,0 +1,367 @@
+//! Implementation of text fields (12.7.5.3), boxes the user can type text into, either on a single
+//! line, on multiple lines or into the cells of a comb.
+
+use std::io::{Error, Write};
+
+use crate::{
+    PageId,
+    types::{
+        constants,
+        hierarchy::{
+            annotation::{Appearance, AppearanceBuilder},
+            content::{color::Color, path::Path},
+            primitives::{
+                font::Font,
+                font_metrics::FontMetrics,
+                rectangle::{Position, Rectangle},
+                string::PdfString,
+                unit::Unit,
+            },
+        },
+    },
+};
+
+use super::{FONT_SIZE, FormFieldErr, InteractiveForm};
+
+/// Distance between the border of text fields and their text, in default user space units.
+const PADDING: f32 = 2.0;
+
+/// Largest font size of the text of auto-sized fields.
+const MAX_AUTO_SIZE: u32 = 12;
+
+/// Smallest font size of the text of auto-sized fields, used when the text doesn't fit in any
+/// larger size.
+const MIN_AUTO_SIZE: u32 = 4;
+
+/// A text form field, letting the user enter text. The value of the field is the entered text.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::form::TextField};
+/// # use pdfgen::types::hierarchy::primitives::rectangle::Rectangle;
+/// let mut document = Document::default();
+/// let page = document.create_page().id();
+///
+/// let name = TextField::new("name", page.clone(), Rectangle::from_units(72., 700., 272., 716.))
+///     .with_value("Jane Doe")
+///     .with_auto_size(true);
+/// document.add_form_field(name).unwrap();
+///
+/// // one character per cell, e.g. for a postal code
+/// let postal_code = TextField::new("postal-code", page, Rectangle::from_units(72., 670., 152., 686.))
+///     .with_comb(5);
+/// document.add_form_field(postal_code).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct TextField {
+    /// Name of the field.
+    name: String,
+
+    /// The page the field is placed on.
+    page: PageId,
+
+    /// Location of the field on the page.
+    rect: Rectangle,
+
+    /// The text of the field when the document is opened, and when the form is reset.
+    value: Option<String>,
+
+    /// Whether the text can span multiple lines, which are wrapped to the width of the field.
+    multiline: bool,
+
+    /// Whether the text is hidden, showing an asterisk for each of its characters.
+    password: bool,
+
+    /// Number of cells the field is divided into, each showing a single character.
+    comb: Option<u32>,
+
+    /// Whether the text is shown in the largest font size it fits into the field with, instead
+    /// of the fixed size of the form.
+    auto_size: bool,
+}
+
+impl TextField {
+    /// Flag of text fields whose text can span multiple lines.
+    const MULTILINE: u32 = 1 << 12;
+
+    /// Flag of text fields whose text is hidden.
+    const PASSWORD: u32 = 1 << 13;
+
+    /// Flag of text fields divided into as many cells as the maximum length of their text.
+    const COMB: u32 = 1 << 24;
+
+    /// Creates a new single-line `TextField` with the given name, placed on the given page at the
+    /// given [`Rectangle`]. Without a value, the field is empty.
+    pub fn new(name: impl Into<String>, page: PageId, rect: Rectangle) -> Self {
+        Self {
+            name: name.into(),
+            page,
+            rect,
+            value: None,
+            multiline: false,
+            password: false,
+            comb: None,
+            auto_size: false,
+        }
+    }
+
+    /// Sets the text of this field when the document is opened, and when the form is reset.
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Sets whether the text of this field can span multiple lines. The text is wrapped to the
+    /// width of the field, and new lines in the value start new lines.
+    pub fn with_multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Sets whether the text of this field is hidden while it's entered, showing an asterisk for
+    /// each of its characters instead.
+    pub fn with_password(mut self, password: bool) -> Self {
+        self.password = password;
+        self
+    }
+
+    /// Divides this field into the given number of equally wide cells, each showing a single
+    /// character, e.g. for postal codes or the boxes of government forms. The number of cells is
+    /// also the maximum length of the text. Combs can't be multiline or password fields.
+    pub fn with_comb(mut self, cells: u32) -> Self {
+        self.comb = Some(cells);
+        self
+    }
+
+    /// Sets whether the text of this field is shown in the largest font size it fits into the
+    /// field with, up to 12 points, instead of the fixed size of the form. Viewers keep fitting
+    /// the text while it's edited.
+    pub fn with_auto_size(mut self, auto_size: bool) -> Self {
+        self.auto_size = auto_size;
+        self
+    }
+
+    /// Returns the name of this field.
+    pub(super) fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the page and the rectangle of the widget of this field.
+    pub(super) fn widget(&self) -> (&PageId, Rectangle) {
+        (&self.page, self.rect)
+    }
+
+    /// Returns the field flags of this field.
+    pub(super) fn flags(&self) -> u32 {
+        [
+            (self.multiline, Self::MULTILINE),
+            (self.password, Self::PASSWORD),
+            (self.comb.is_some(), Self::COMB),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .fold(0, |flags, (_, flag)| flags | flag)
+    }
+
+    /// Checks that a comb has cells and is neither a multiline nor a password field, and that the
+    /// value fits into its cells.
+    pub(super) fn check(&self) -> Result<(), FormFieldErr> {
+        let Some(cells) = self.comb else {
+            return Ok(());
+        };
+
+        if cells == 0 || self.multiline || self.password {
+            return Err(FormFieldErr::InvalidComb);
+        }
+
+        match &self.value {
+            Some(value) if value.chars().count() > cells as usize => {
+                Err(FormFieldErr::ValueTooLong)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the `MaxLen` entry of combs, the `DA` entry with the automatic font size of
+    /// auto-sized fields, and the value and default value of this field if it has one.
+    pub(super) fn write_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let value = self.value.as_deref().map(PdfString::text_string);
+
+        Ok(pdfgen_macros::write_chain! {
+            if let Some(cells) = self.comb {
+                InteractiveForm::MAX_LEN.write(writer),
+                crate::write_fmt!(&mut *writer, "{cells}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            // a font size of 0 lets viewers fit the text into the field while it's edited
+            if self.auto_size {
+                InteractiveForm::DA.write(writer),
+                PdfString::from("/Helv 0 Tf 0 g").write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(value) = &value {
+                InteractiveForm::V.write(writer),
+                value.write_content(writer),
+                writer.write(constants::NL_MARKER),
+
+                InteractiveForm::DV.write(writer),
+                value.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+        })
+    }
+
+    /// Returns the text shown by this field, which is an asterisk for each character of password
+    /// fields.
+    fn shown_text(&self) -> String {
+        let value = self.value.as_deref().unwrap_or_default();
+        match self.password {
+            true => "*".repeat(value.chars().count()),
+            false => value.to_string(),
+        }
+    }
+
+    /// Returns the appearance of the widget of this field, showing its text with the given
+    /// [`Font`]. Single-line fields show the text vertically centered, multiline fields wrapped
+    /// from the top, and combs each character centered in its cell, with the cells separated by
+    /// lines.
+    pub(super) fn appearance(&self, font: &Font) -> Appearance {
+        let mut appearance = AppearanceBuilder::new(self.rect);
+        let inset = self.rect.expanded(Unit::from_unit(-0.5));
+        let (low_left, top_right) = (self.rect.low_left(), self.rect.top_right());
+        let (low_x, low_y) = (low_left.x.into_user_unit(), low_left.y.into_user_unit());
+        let (top_x, top_y) = (top_right.x.into_user_unit(), top_right.y.into_user_unit());
+        let (width, height) = (top_x - low_x, top_y - low_y);
+        let text = self.shown_text();
+        let metrics = font.metrics();
+        let font_id = font.obj_ref();
+
+        appearance.set_fill_color(&Color::Gray(0));
+
+        if let Some(cells) = self.comb.filter(|cells| *cells > 0) {
+            let cell_width = width / cells as f32;
+            let size = self.font_size(|size| {
+                let widest = text
+                    .chars()
+                    .map(|ch| text_width(&metrics, ch.encode_utf8(&mut [0; 4]), size))
+                    .fold(0.0, f32::max);
+                widest <= cell_width && size as f32 <= height - 2.0 * PADDING
+            });
+            let baseline = Self::centered_baseline(low_y, height, size);
+
+            for (idx, ch) in text.chars().enumerate() {
+                let ch = ch.encode_utf8(&mut [0; 4]).to_string();
+                let x = low_x
+                    + idx as f32 * cell_width
+                    + (cell_width - text_width(&metrics, &ch, size)) / 2.0;
+                appearance.show_text(font_id, size, Position::from_units(x, baseline), &ch);
+            }
+
+            let dividers = (1..cells).fold(Path::new(), |path, idx| {
+                let x = low_x + idx as f32 * cell_width;
+                path.move_to(Position::from_units(x, low_y))
+                    .line_to(Position::from_units(x, top_y))
+            });
+            if !dividers.is_empty() {
+                appearance
+                    .set_line_width(1.0)
+                    .set_stroke_color(&Color::Gray(0))
+                    .stroke(&dividers);
+            }
+        } else if self.multiline {
+            let line_width = width - 2.0 * PADDING;
+            let size = self.font_size(|size| {
+                let lines = wrap(&metrics, &text, size, line_width).len();
+                lines as f32 * size as f32 * 1.2 <= height - 2.0 * PADDING
+            });
+            let line_height = size as f32 * 1.2;
+
+            for (idx, line) in wrap(&metrics, &text, size, line_width).iter().enumerate() {
+                let line_low = top_y - PADDING - (idx + 1) as f32 * line_height;
+                if line_low < low_y {
+                    break;
+                }
+                if line.is_empty() {
+                    continue;
+                }
+
+                let position = Position::from_units(low_x + PADDING, line_low + size as f32 * 0.25);
+                appearance.show_text(font_id, size, position, line);
+            }
+        } else if !text.is_empty() {
+            let size = self.font_size(|size| {
+                text_width(&metrics, &text, size) <= width - 2.0 * PADDING
+                    && size as f32 <= height - 2.0 * PADDING
+            });
+            let position = Position::from_units(
+                low_x + PADDING,
+                Self::centered_baseline(low_y, height, size),
+            );
+            appearance.show_text(font_id, size, position, &text);
+        }
+
+        appearance
+            .set_line_width(1.0)
+            .set_stroke_color(&Color::Gray(0))
+            .stroke(&Path::from(inset));
+
+        appearance.build()
+    }
+
+    /// Returns the font size the text of this field is shown in, which is the fixed size of the
+    /// form, or for auto-sized fields the largest size the given function reports the text to
+    /// fit in.
+    fn font_size(&self, fits: impl Fn(u32) -> bool) -> u32 {
+        if !self.auto_size {
+            return FONT_SIZE;
+        }
+
+        (MIN_AUTO_SIZE..=MAX_AUTO_SIZE)
+            .rev()
+            .find(|size| fits(*size))
+            .unwrap_or(MIN_AUTO_SIZE)
+    }
+
+    /// Returns the baseline of a single line of text in the given size, vertically centered in a
+    /// field at the given height. The baseline is a quarter of the font size above the bottom of
+    /// the centered text.
+    fn centered_baseline(low_y: f32, height: f32, size: u32) -> f32 {
+        let size = size as f32;
+        low_y + (height - size) / 2.0 + size * 0.25
+    }
+}
+
+/// Returns the width of the given text shown in the given font size with the given
+/// [`FontMetrics`].
+fn text_width(metrics: &FontMetrics, text: &str, size: u32) -> f32 {
+    metrics.text_width(text) * size as f32 / 1000.0
+}
+
+/// Wraps the given text into lines no wider than the given width when shown in the given font
+/// size, breaking lines at spaces and new lines. Words wider than a line are kept on a line of
+/// their own.
+fn wrap(metrics: &FontMetrics, text: &str, size: u32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ') {
+            let candidate = match line.is_empty() {
+                true => word.to_string(),
+                false => format!("{line} {word}"),
+            };
+
+            if line.is_empty() || text_width(metrics, &candidate, size) <= width {
+                line = candidate;
+            } else {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
@@ -0,0 +1,180 @@
+//! Implementation of document-level metadata, which is written both as the document information
+//! dictionary (14.3.3) and as an XMP metadata stream (14.3.2) referenced from the [`Catalog`].
+//!
+//! [`Catalog`]: super::catalog::Catalog
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{ObjId, Producer, types::constants};
+
+use super::{
+    content::stream::Stream,
+    primitives::{identifier::Identifier, object::Object, string::PdfString},
+};
+
+/// The document information dictionary, referenced by the `Info` entry of the trailer.
+#[derive(Debug, Clone)]
+pub struct Info {
+    /// ID of this `Info` dictionary.
+    id: ObjId<Self>,
+
+    /// Identification of the software that produced the document.
+    producer: Producer,
+}
+
+impl Info {
+    const_identifiers! {
+        PRODUCER,
+    }
+
+    /// Creates a new `Info` dictionary with the given [`ObjId`] and [`Producer`].
+    pub(crate) fn new(id: ObjId<Self>, producer: Producer) -> Self {
+        Self { id, producer }
+    }
+
+    /// Returns the [`ObjId`] of this `Info` dictionary.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Returns the [`Producer`] of this `Info` dictionary.
+    pub(crate) fn producer(&self) -> &Producer {
+        &self.producer
+    }
+}
+
+impl Object for Info {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Self::PRODUCER.write(writer),
+            PdfString::text_string(self.producer.identification()).write_content(writer),
+            writer.write(b" >>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// The XMP metadata stream of the document, referenced by the `Metadata` entry of the
+/// [`Catalog`].
+///
+/// [`Catalog`]: super::catalog::Catalog
+#[derive(Debug, Clone)]
+pub struct XmpMetadata {
+    /// ID of this `XmpMetadata` stream.
+    id: ObjId<Self>,
+
+    /// Identification of the software that produced the document.
+    producer: Producer,
+}
+
+impl XmpMetadata {
+    const_identifiers! {
+        METADATA,
+        SUBTYPE,
+        XML: b"XML",
+    }
+
+    /// Creates a new `XmpMetadata` stream with the given [`ObjId`] and [`Producer`].
+    pub(crate) fn new(id: ObjId<Self>, producer: Producer) -> Self {
+        Self { id, producer }
+    }
+
+    /// Returns the [`ObjId`] of this `XmpMetadata` stream.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Returns the XMP packet holding the metadata, encoded in UTF-8.
+    fn packet(&self) -> String {
+        let producer = self
+            .producer
+            .identification()
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;");
+
+        [
+            "<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>",
+            "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\">",
+            "<rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">",
+            "<rdf:Description rdf:about=\"\" xmlns:pdf=\"http://ns.adobe.com/pdf/1.3/\">",
+            &format!("<pdf:Producer>{producer}</pdf:Producer>"),
+            "</rdf:Description>",
+            "</rdf:RDF>",
+            "</x:xmpmeta>",
+            "<?xpacket end=\"w\"?>",
+        ]
+        .join("\n")
+    }
+}
+
+impl Object for XmpMetadata {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let stream = Stream::with_bytes(self.packet());
+
+        Ok(pdfgen_macros::write_chain! {
+            stream.write_with_dict(writer, |writer| {
+                Ok(pdfgen_macros::write_chain! {
+                    Identifier::TYPE.write(writer),
+                    Self::METADATA.write(writer),
+                    Self::SUBTYPE.write(writer),
+                    Self::XML.write(writer),
+                })
+            }),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IdManager, Producer, types::hierarchy::primitives::object::Object};
+
+    use super::{Info, XmpMetadata};
+
+    #[test]
+    fn producer_metadata() {
+        let mut id_manager = IdManager::new();
+        let producer = Producer::new("Invoices <ACME & Co.>").without_version();
+        let info = Info::new(id_manager.create_id(), producer.clone());
+        let metadata = XmpMetadata::new(id_manager.create_id(), producer);
+
+        let mut writer = Vec::default();
+        info.write_content(&mut writer).unwrap();
+        metadata.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r#"
+        << /Producer (Invoices <ACME & Co.>) >>
+        << /Type /Metadata /Subtype /XML /Length 352 >>
+        stream
+        <?xpacket begin="﻿" id="W5M0MpCehiHzreSzNTczkc9d"?>
+        <x:xmpmeta xmlns:x="adobe:ns:meta/">
+        <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+        <rdf:Description rdf:about="" xmlns:pdf="http://ns.adobe.com/pdf/1.3/">
+        <pdf:Producer>Invoices &lt;ACME &amp; Co.&gt;</pdf:Producer>
+        </rdf:Description>
+        </rdf:RDF>
+        </x:xmpmeta>
+        <?xpacket end="w"?>
+        endstream
+        "#);
+    }
+}
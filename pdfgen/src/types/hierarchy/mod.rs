@@ -10,9 +10,16 @@
 //!
 //! Reference: ISO 32000-2:2020 (PDF 2.0); page 114
 
+pub mod action;
+pub mod annotation;
 pub mod catalog;
 pub mod content;
 pub mod cross_reference_table;
+pub mod destination;
+pub mod document_part;
+pub mod form;
+pub mod metadata;
+pub mod outline;
 pub mod page;
 pub mod page_tree;
 pub mod primitives;
@@ -0,0 +1,515 @@
+//! Implementation of the document outline (12.3.3), a tree of bookmarks that lets the user
+//! navigate the document, shown by PDF viewers next to the pages.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{IdManager, ObjId, SanitizePolicy, types::constants};
+
+use super::{
+    content::color::Color,
+    destination::DestinationRef,
+    primitives::{identifier::Identifier, object::Object, string::PdfString},
+};
+
+/// A single item (bookmark) of the document outline, showing a destination in the document when
+/// it's activated. Items can have child items, which are shown below them when they are open.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::{destination::Destination, outline::OutlineItem}};
+/// let mut document = Document::default();
+/// let intro = document.create_page().id();
+/// let usage = document.create_page().id();
+///
+/// let chapter = OutlineItem::new("Getting started", Destination::Fit { page: intro })
+///     .with_bold(true)
+///     .with_open(true)
+///     .with_child(OutlineItem::new("Usage", Destination::Fit { page: usage }));
+/// document.add_outline_item(chapter).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineItem {
+    /// The text shown for this item.
+    title: PdfString,
+
+    /// The destination shown when this item is activated.
+    destination: DestinationRef,
+
+    /// Child items of this item, in order.
+    children: Vec<OutlineItem>,
+
+    /// Whether the child items are shown when the document is opened.
+    open: bool,
+
+    /// Whether the title is shown in bold.
+    bold: bool,
+
+    /// Whether the title is shown in italic.
+    italic: bool,
+
+    /// The color the title is shown in.
+    color: Option<Color>,
+}
+
+impl OutlineItem {
+    const_identifiers! {
+        TITLE,
+        PARENT,
+        PREV,
+        NEXT,
+        FIRST,
+        LAST,
+        COUNT,
+        DEST,
+        F: b"F",
+        C: b"C",
+    }
+
+    /// Flag of items whose title is shown in italic.
+    const ITALIC: u8 = 1 << 0;
+
+    /// Flag of items whose title is shown in bold.
+    const BOLD: u8 = 1 << 1;
+
+    /// Creates a new closed `OutlineItem` with the given title, showing the given destination when
+    /// it's activated.
+    pub fn new(title: &str, destination: impl Into<DestinationRef>) -> Self {
+        Self {
+            title: PdfString::text_string(title),
+            destination: destination.into(),
+            children: Vec::new(),
+            open: false,
+            bold: false,
+            italic: false,
+            color: None,
+        }
+    }
+
+    /// Adds the given child item below this `OutlineItem`, after the child items added before.
+    pub fn with_child(mut self, child: OutlineItem) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Sets whether the child items of this `OutlineItem` are shown when the document is opened.
+    /// Items are closed by default.
+    pub fn with_open(mut self, open: bool) -> Self {
+        self.open = open;
+        self
+    }
+
+    /// Sets whether the title of this `OutlineItem` is shown in bold.
+    pub fn with_bold(mut self, bold: bool) -> Self {
+        self.bold = bold;
+        self
+    }
+
+    /// Sets whether the title of this `OutlineItem` is shown in italic.
+    pub fn with_italic(mut self, italic: bool) -> Self {
+        self.italic = italic;
+        self
+    }
+
+    /// Sets the [`Color`] the title of this `OutlineItem` is shown in. The color is converted to
+    /// RGB, as colors of outline items are always written in the DeviceRGB colour space.
+    pub fn with_color(mut self, color: Color) -> Self {
+        self.color = Some(color.to_rgb());
+        self
+    }
+
+    /// Returns the destinations shown by this `OutlineItem` and all of its descendants.
+    pub(crate) fn destinations(&self) -> Vec<&DestinationRef> {
+        std::iter::once(&self.destination)
+            .chain(self.children.iter().flat_map(OutlineItem::destinations))
+            .collect()
+    }
+
+    /// Returns this `OutlineItem` with the titles of it and its descendants sanitized according
+    /// to the given [`SanitizePolicy`].
+    pub(crate) fn sanitized(self, policy: &SanitizePolicy) -> Self {
+        Self {
+            title: PdfString::text_string(policy.metadata(&self.title.to_text())),
+            children: self
+                .children
+                .into_iter()
+                .map(|child| child.sanitized(policy))
+                .collect(),
+            ..self
+        }
+    }
+
+    /// Writes the `F` and `C` entries of this item, if its title is styled.
+    fn write_style(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let flags = match (self.italic, self.bold) {
+            (false, false) => 0,
+            (true, false) => Self::ITALIC,
+            (false, true) => Self::BOLD,
+            (true, true) => Self::ITALIC | Self::BOLD,
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            if flags != 0 {
+                Self::F.write(writer),
+                crate::write_fmt!(&mut *writer, "{flags}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(Color::Rgb { red, green, blue }) = self.color {
+                Self::C.write(writer),
+                crate::write_fmt!(
+                    &mut *writer,
+                    "[{} {} {}]",
+                    f32::from(red) / 255.,
+                    f32::from(green) / 255.,
+                    f32::from(blue) / 255.
+                ),
+                writer.write(constants::NL_MARKER),
+            },
+        })
+    }
+}
+
+/// An [`OutlineItem`] together with the [`ObjId`] it's written with, and the entries of its child
+/// items.
+#[derive(Debug, Clone)]
+struct OutlineEntry {
+    /// ID of the outline item dictionary.
+    id: ObjId<OutlineItem>,
+
+    /// The outline item. Its children are held by `children` instead.
+    item: OutlineItem,
+
+    /// Entries of the child items of the outline item.
+    children: Vec<OutlineEntry>,
+}
+
+impl OutlineEntry {
+    /// Creates a new `OutlineEntry` of the given item and its descendants, with new IDs created
+    /// by the given [`IdManager`].
+    fn new(id_manager: &mut IdManager, mut item: OutlineItem) -> Self {
+        let id = id_manager.create_id();
+        let children = std::mem::take(&mut item.children)
+            .into_iter()
+            .map(|child| Self::new(id_manager, child))
+            .collect();
+
+        Self { id, item, children }
+    }
+
+    /// Returns the number of descendants of this entry that are shown if it's open.
+    fn visible_descendants(&self) -> usize {
+        Self::visible(&self.children)
+    }
+
+    /// Returns the number of the given sibling entries and their descendants that are shown,
+    /// which are all siblings and the visible descendants of open siblings.
+    fn visible(entries: &[OutlineEntry]) -> usize {
+        entries
+            .iter()
+            .map(|entry| match entry.item.open {
+                true => 1 + entry.visible_descendants(),
+                false => 1,
+            })
+            .sum()
+    }
+}
+
+/// The document outline, referenced from the document's catalog as the `Outlines` entry.
+#[derive(Debug, Clone)]
+pub(crate) struct DocumentOutline {
+    /// ID of the outline dictionary, the root of the outline tree.
+    id: ObjId<Self>,
+
+    /// Entries of the top-level items of the outline.
+    items: Vec<OutlineEntry>,
+}
+
+impl DocumentOutline {
+    const_identifiers! {
+        OUTLINES,
+    }
+
+    /// Creates a new empty `DocumentOutline`.
+    pub(crate) fn new(id_manager: &mut IdManager) -> Self {
+        Self {
+            id: id_manager.create_id(),
+            items: Vec::new(),
+        }
+    }
+
+    /// Returns the [`ObjId`] of the outline dictionary.
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Adds the given [`OutlineItem`] as the last top-level item of this outline.
+    pub(crate) fn add(&mut self, id_manager: &mut IdManager, item: OutlineItem) {
+        self.items.push(OutlineEntry::new(id_manager, item));
+    }
+
+    /// Returns the nodes of all outline items, in the order of the outline.
+    pub(crate) fn nodes(&self) -> Vec<OutlineNode<'_>> {
+        let mut nodes = Vec::new();
+        OutlineNode::collect(&mut nodes, self.id.clone().cast(), &self.items);
+
+        nodes
+    }
+}
+
+impl Object for DocumentOutline {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let count = OutlineEntry::visible(&self.items);
+
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::OUTLINES.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            write_children(writer, &self.items),
+
+            OutlineItem::COUNT.write(writer),
+            crate::write_fmt!(&mut *writer, "{count}"),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// Writes the `First` and `Last` entries referencing the first and last of the given entries, if
+/// there are any.
+fn write_children(writer: &mut dyn Write, children: &[OutlineEntry]) -> Result<usize, Error> {
+    let (Some(first), Some(last)) = (children.first(), children.last()) else {
+        return Ok(0);
+    };
+
+    Ok(pdfgen_macros::write_chain! {
+        OutlineItem::FIRST.write(writer),
+        first.id.write_ref(writer),
+        writer.write(constants::NL_MARKER),
+
+        OutlineItem::LAST.write(writer),
+        last.id.write_ref(writer),
+        writer.write(constants::NL_MARKER),
+    })
+}
+
+/// A single outline item dictionary, linked to its parent and siblings.
+#[derive(Debug)]
+pub(crate) struct OutlineNode<'a> {
+    /// The entry of the written item.
+    entry: &'a OutlineEntry,
+
+    /// ID of the parent item, or of the outline dictionary for top-level items.
+    parent: ObjId,
+
+    /// ID of the previous sibling of the item.
+    prev: Option<&'a ObjId<OutlineItem>>,
+
+    /// ID of the next sibling of the item.
+    next: Option<&'a ObjId<OutlineItem>>,
+}
+
+impl<'a> OutlineNode<'a> {
+    /// Collects the nodes of the given sibling entries and all of their descendants into `nodes`,
+    /// each item followed by its descendants.
+    fn collect(nodes: &mut Vec<Self>, parent: ObjId, entries: &'a [OutlineEntry]) {
+        for (idx, entry) in entries.iter().enumerate() {
+            nodes.push(Self {
+                entry,
+                parent: parent.clone(),
+                prev: idx.checked_sub(1).map(|prev| &entries[prev].id),
+                next: entries.get(idx + 1).map(|next| &next.id),
+            });
+
+            Self::collect(nodes, entry.id.clone().cast(), &entry.children);
+        }
+    }
+
+    /// Returns the [`ObjId`] of the written item.
+    pub(crate) fn obj_ref(&self) -> &ObjId<OutlineItem> {
+        &self.entry.id
+    }
+}
+
+impl Object for OutlineNode<'_> {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.entry.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let OutlineEntry { item, children, .. } = self.entry;
+
+        // open items count their visible descendants, closed items count the descendants that
+        // would be visible if they were opened as a negative number
+        let count = self.entry.visible_descendants() as i64;
+        let count = if item.open { count } else { -count };
+
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            OutlineItem::TITLE.write(writer),
+            item.title.write_content(writer),
+            writer.write(constants::NL_MARKER),
+
+            OutlineItem::PARENT.write(writer),
+            self.parent.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            if let Some(prev) = self.prev {
+                OutlineItem::PREV.write(writer),
+                prev.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(next) = self.next {
+                OutlineItem::NEXT.write(writer),
+                next.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if !children.is_empty() {
+                write_children(writer, children),
+                OutlineItem::COUNT.write(writer),
+                crate::write_fmt!(&mut *writer, "{count}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            OutlineItem::DEST.write(writer),
+            item.destination.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            item.write_style(writer),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::{
+            content::color::Color, destination::Destination, page::Page, primitives::object::Object,
+        },
+    };
+
+    use super::{DocumentOutline, OutlineItem};
+
+    #[test]
+    fn outline_nodes() {
+        let mut id_manager = IdManager::new();
+        let parent = id_manager.create_id();
+        let pages: Vec<_> = (0..2)
+            .map(|_| {
+                Page::new(
+                    id_manager.create_id(),
+                    id_manager.create_id(),
+                    parent.clone(),
+                )
+            })
+            .collect();
+        let mut outline = DocumentOutline::new(&mut id_manager);
+
+        let chapter = OutlineItem::new(
+            "Chapter 1",
+            Destination::Fit {
+                page: pages[0].id(),
+            },
+        )
+        .with_open(true)
+        .with_bold(true)
+        .with_child(OutlineItem::new(
+            "Section 1.1",
+            Destination::Fit {
+                page: pages[1].id(),
+            },
+        ));
+        outline.add(&mut id_manager, chapter);
+
+        let appendix = OutlineItem::new(
+            "Appendix",
+            Destination::Fit {
+                page: pages[1].id(),
+            },
+        )
+        .with_italic(true)
+        .with_color(Color::Rgb {
+            red: 255,
+            green: 0,
+            blue: 0,
+        })
+        .with_child(OutlineItem::new(
+            "Sources",
+            Destination::Fit {
+                page: pages[1].id(),
+            },
+        ));
+        outline.add(&mut id_manager, appendix);
+
+        let mut writer = Vec::new();
+        outline.write_def(&mut writer).unwrap();
+        outline.write_content(&mut writer).unwrap();
+        for node in outline.nodes() {
+            node.write_def(&mut writer).unwrap();
+            node.write_content(&mut writer).unwrap();
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        6 0 obj
+        << /Type /Outlines 
+        /First 7 0 R
+        /Last 9 0 R
+        /Count 3
+        >>
+        7 0 obj
+        << /Title (Chapter 1)
+        /Parent 6 0 R
+        /Next 9 0 R
+        /First 8 0 R
+        /Last 8 0 R
+        /Count 1
+        /Dest [2 0 R /Fit ]
+        /F 2
+        >>
+        8 0 obj
+        << /Title (Section 1.1)
+        /Parent 7 0 R
+        /Dest [4 0 R /Fit ]
+        >>
+        9 0 obj
+        << /Title (Appendix)
+        /Parent 6 0 R
+        /Prev 7 0 R
+        /First 10 0 R
+        /Last 10 0 R
+        /Count -1
+        /Dest [4 0 R /Fit ]
+        /F 1
+        /C [1 0 0]
+        >>
+        10 0 obj
+        << /Title (Sources)
+        /Parent 9 0 R
+        /Dest [4 0 R /Fit ]
+        >>
+        ");
+    }
+}
@@ -1,17 +1,98 @@
-use std::io::{Error, Write};
+use std::{
+    collections::{BTreeMap, HashMap},
+    io::{Error, Write},
+};
 
 use pdfgen_macros::const_identifiers;
 
-use crate::{IdManager, ObjId, types::constants};
+use crate::{
+    FontId, Fragment, FragmentErr, FragmentId, IdManager, ImageId, ObjId, PageId, PageStats,
+    SanitizePolicy, TextStyle, TextStyleErr, types::constants,
+};
 
 use super::{
-    content::{ContentStream, Operation, image::Image, text::Text},
+    action::{self, Action, PageTrigger},
+    annotation::{Annotation, Appearance},
+    content::{
+        ContentStream, Operation,
+        color::{Color, ColorSpace, ColorSpaceIds, DefaultColorSpaceErr},
+        graphics_state::ExtGState,
+        image::{Image, ImageTransform},
+        page_form::PageForm,
+        path::Path,
+        recording::{RecordedOperation, Recording},
+        text::Text,
+        watermark::WatermarkStamp,
+    },
+    document_part::DocumentPart,
+    form::Widget,
     page_tree::PageTree,
-    primitives::{font::Font, identifier::Identifier, rectangle::Rectangle, resources::Resources},
+    primitives::{
+        array::WriteArray,
+        font::Font,
+        identifier::Identifier,
+        output_intent::OutputIntent,
+        rectangle::{Position, Rectangle},
+        resources::{Renderable, Resources},
+        unit::Unit,
+    },
 };
 
+/// Layers of content on a [`Page`], painted in order from bottom to top, each written as a
+/// separate content stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    /// Content painted beneath the page's content, such as background fills, ruled lines or
+    /// grids.
+    Underlay,
+
+    /// The page's content. Content is added to this layer by default.
+    Content,
+
+    /// Content painted on top of the page's content, such as annotations or stamps.
+    Overlay,
+}
+
+/// The order in which a PDF processor navigates through the annotations of a [`Page`], e.g. when
+/// tabbing through form fields. Tagged documents conforming to PDF/UA are required to use
+/// [`TabOrder::Structure`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TabOrder {
+    /// Annotations are visited in rows running in the direction of the page's text.
+    Row,
+
+    /// Annotations are visited in columns running in the direction of the page's text.
+    Column,
+
+    /// Annotations are visited in the order they appear in the structure tree.
+    Structure,
+}
+
+impl TabOrder {
+    /// Returns the [`Identifier`] of this tab order.
+    fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            TabOrder::Row => Identifier::from_static(b"R"),
+            TabOrder::Column => Identifier::from_static(b"C"),
+            TabOrder::Structure => Identifier::from_static(b"S"),
+        }
+    }
+}
+
+/// Sizing of a [`Page`] to its content, see [`Page::set_auto_size`].
+#[derive(Debug, Clone, Copy)]
+struct AutoSize {
+    /// Margin around the content.
+    margin: Unit,
+
+    /// Fixed width of the page, e.g. the width of a roll of paper, starting at 0. The width is
+    /// computed from the content as well if `None`.
+    width: Option<Unit>,
+}
+
 /// Page objects are the leaves of the page tree, each of which is a dictionary specifying the
 /// attributes of a single page of the document.
+#[derive(Clone)]
 pub struct Page {
     /// ID of this Page object.
     id: ObjId<Self>,
@@ -27,8 +108,77 @@ pub struct Page {
     /// the physical medium on which the page shall be displayed or printed.
     media_box: Option<Rectangle>,
 
+    /// Sizing of this page to its content, if its media box is computed from the bounds of its
+    /// content when it's written.
+    auto_size: Option<AutoSize>,
+
+    /// Size of the default user space unit in points, if it isn't 1 point.
+    user_unit: Option<f32>,
+
+    /// A [`Rectangle`], expressed in default user space units, that shall define the intended
+    /// dimensions of the finished page after trimming.
+    trim_box: Option<Rectangle>,
+
+    /// A [`Rectangle`], expressed in default user space units, that shall define the region to
+    /// which the contents of the page shall be clipped when output in a production environment.
+    bleed_box: Option<Rectangle>,
+
     /// Content stream holds the encoded bytes with various contents added to the page.
     contents: ContentStream,
+
+    /// Content stream painted beneath `contents`.
+    underlay: ContentStream,
+
+    /// Content stream painted on top of `contents`.
+    overlay: ContentStream,
+
+    /// Colors of all texts added to the page, used for validation of the page.
+    text_colors: Vec<Color>,
+
+    /// Contents of all texts added to the page together with their fonts, used for validation of
+    /// the page.
+    texts: Vec<(FontId, String)>,
+
+    /// Whether any content on the page uses transparency, in which case the page is written as a
+    /// transparency group.
+    uses_transparency: bool,
+
+    /// The colour space in which the page's transparent content is blended.
+    blending_color_space: ColorSpace,
+
+    /// Output intents of this page, overriding the output intents of the document.
+    output_intents: Vec<OutputIntent>,
+
+    /// The document part this page belongs to.
+    document_part: Option<ObjId<DocumentPart>>,
+
+    /// The order in which annotations on this page are navigated.
+    tab_order: Option<TabOrder>,
+
+    /// Actions performed when this page is opened or closed.
+    actions: BTreeMap<PageTrigger, Action>,
+
+    /// Policy on how the content of texts added to this page is sanitized.
+    sanitize: Option<SanitizePolicy>,
+
+    /// zlib compression level the uncompressed samples of images added to this page are
+    /// compressed with.
+    image_compression: Option<u8>,
+
+    /// Default [`TextStyle`] of the document together with its font, used for texts added without
+    /// a font.
+    text_style: Option<(FontId, TextStyle)>,
+
+    /// Small image of the page shown by PDF readers instead of rendering the page, e.g. in their
+    /// page navigation panel.
+    thumbnail: Option<Image>,
+
+    /// Annotations shown on top of the content of this page.
+    annotations: Vec<Annotation>,
+
+    /// Widgets of the form fields placed on this page, written by the interactive form of the
+    /// document.
+    widgets: Vec<ObjId<Widget>>,
 }
 
 impl Page {
@@ -37,9 +187,28 @@ impl Page {
         PARENT,
         RESOURCES,
         MEDIA_BOX,
+        TRIM_BOX,
+        BLEED_BOX,
         CONTENTS,
+        GROUP,
+        S: b"S",
+        TRANSPARENCY,
+        CS: b"CS",
+        OUTPUT_INTENTS,
+        D_PART: b"DPart",
+        TABS,
+        AA: b"AA",
+        USER_UNIT,
+        THUMB,
+        ANNOTS,
     }
 
+    /// Minimum width and height of a page in default user space units.
+    pub(crate) const MIN_SIZE: f64 = 3.0;
+
+    /// Maximum width and height of a page in default user space units.
+    pub(crate) const MAX_SIZE: f64 = 14_400.0;
+
     /// Create a new blank page that belongs to the given parent and media box.
     pub fn new(
         id: ObjId<Self>,
@@ -51,17 +220,275 @@ impl Page {
             parent,
             resources: Resources::default(),
             media_box: None,
+            auto_size: None,
+            user_unit: None,
+            trim_box: None,
+            bleed_box: None,
             contents: ContentStream::new(contents_id),
+            underlay: ContentStream::without_id(),
+            overlay: ContentStream::without_id(),
+            text_colors: Vec::new(),
+            texts: Vec::new(),
+            uses_transparency: false,
+            blending_color_space: ColorSpace::DeviceRgb,
+            output_intents: Vec::new(),
+            document_part: None,
+            tab_order: None,
+            actions: BTreeMap::new(),
+            sanitize: None,
+            image_compression: None,
+            text_style: None,
+            thumbnail: None,
+            annotations: Vec::new(),
+            widgets: Vec::new(),
         }
     }
 
     pub fn set_mediabox(&mut self, media_box: impl Into<Rectangle>) {
         self.media_box = Some(media_box.into());
+        self.auto_size = None;
+        self.user_unit = None;
+    }
+
+    /// Sizes this page to its content, computing its media box from the bounds of the content when
+    /// the page is written, extended by the given margin on every side. Useful for receipts and
+    /// labels of variable length, laid out without knowing their size in advance. Pages without
+    /// content keep their media box.
+    ///
+    /// The bounds of images, filled paths and texts are known, the latter estimated with the
+    /// metrics of the font they are drawn with. Other content, e.g. imposed pages, doesn't extend
+    /// the media box. Setting a media box afterwards stops sizing the page to its content.
+    ///
+    /// Pages larger than the limit of 14 400 units (about 5 metres) are written with a larger
+    /// `UserUnit` (PDF 1.6), and their content scaled down accordingly, so they keep their size.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{
+    /// #     Document,
+    /// #     types::hierarchy::{
+    /// #         content::text::Text,
+    /// #         primitives::{rectangle::Position, unit::Unit},
+    /// #     },
+    /// # };
+    /// let mut document = Document::default();
+    /// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    /// let page = document.create_page();
+    /// page.set_auto_size(Unit::from_mm(5.0));
+    ///
+    /// for (idx, item) in ["Coffee  3.20", "Bagel  2.80", "Total  6.00"].into_iter().enumerate() {
+    ///     let txt = Text::builder()
+    ///         .with_content(item)
+    ///         .at(Position::from_mm(0.0, -6.0 * idx as f32))
+    ///         .build();
+    ///     page.add_text(txt, font_id.clone());
+    /// }
+    ///
+    /// let bounds = page.content_bounds().unwrap();
+    /// assert!(bounds.height() > Unit::from_mm(12.0));
+    /// ```
+    pub fn set_auto_size(&mut self, margin: Unit) {
+        self.auto_size = Some(AutoSize {
+            margin,
+            width: None,
+        });
+    }
+
+    /// Sizes this page to the height of its content like [`Page::set_auto_size`], with the given
+    /// fixed width, e.g. of a roll of paper.
+    pub(crate) fn set_roll(&mut self, width: Unit, margin: Unit) {
+        self.auto_size = Some(AutoSize {
+            margin,
+            width: Some(width),
+        });
+    }
+
+    /// Returns the media box of this page sized to its content in points, and the size of the
+    /// default user space unit it's written with, if the page is sized to its content.
+    fn auto_sized(&self) -> Option<(Rectangle, f32)> {
+        let AutoSize { margin, width } = self.auto_size?;
+        let bounds = self.content_bounds()?.expanded(margin);
+        let media_box = match width {
+            Some(width) => Rectangle::new(
+                Position::new(Unit::from_unit(0.0), bounds.low_left().y),
+                Position::new(width, bounds.top_right().y),
+            ),
+            None => bounds,
+        };
+
+        // the smallest whole number of points per unit fitting the page into the size limit
+        let size = f64::max(
+            media_box.width().into_points(),
+            media_box.height().into_points(),
+        );
+        let user_unit = (size / Self::MAX_SIZE).ceil().max(1.0) as f32;
+
+        Some((media_box, user_unit))
+    }
+
+    /// Returns the smallest rectangle containing the content laid out on this page so far, or
+    /// `None` if the page has no content with known bounds. See [`Page::set_auto_size`].
+    pub fn content_bounds(&self) -> Option<Rectangle> {
+        let corners = [&self.underlay, &self.contents, &self.overlay]
+            .into_iter()
+            .filter_map(ContentStream::bounds)
+            .flat_map(|bounds| [bounds.low_left(), bounds.top_right()]);
+
+        Rectangle::enclosing(corners)
+    }
+
+    /// Sets the trim box of this page, defining the dimensions of the finished page after
+    /// trimming. The trim box should lie within the media box.
+    pub fn set_trim_box(&mut self, trim_box: impl Into<Rectangle>) {
+        self.trim_box = Some(trim_box.into());
+    }
+
+    /// Sets the bleed box of this page, defining the area content extends to beyond the trim box,
+    /// so no unprinted edge remains after trimming. The bleed box should lie between the trim box
+    /// and the media box. Printer's marks are drawn outside of the bleed box.
+    pub fn set_bleed_box(&mut self, bleed_box: impl Into<Rectangle>) {
+        self.bleed_box = Some(bleed_box.into());
+    }
+
+    /// Sets the colour space in which transparent content on this page is blended. Defaults to
+    /// [`ColorSpace::DeviceRgb`]. The blending colour space is only written if the page contains
+    /// transparent content.
+    pub fn set_blending_color_space(&mut self, color_space: ColorSpace) {
+        self.blending_color_space = color_space;
+    }
+
+    /// Sets the default colour space of this page, to which device colours with the same number
+    /// of colour components are remapped, e.g. `DefaultRGB` for ICCBased colour spaces with three
+    /// components. This allows color-managed output of content drawn with device colours.
+    ///
+    /// Fails with [`DefaultColorSpaceErr::DeviceColorSpace`] if the given colour space is a device
+    /// colour space.
+    pub fn set_default_color_space(
+        &mut self,
+        color_space: ColorSpace,
+    ) -> Result<(), DefaultColorSpaceErr> {
+        if !matches!(color_space, ColorSpace::Icc(_) | ColorSpace::Cie(_)) {
+            return Err(DefaultColorSpaceErr::DeviceColorSpace);
+        }
+
+        self.resources.set_default_color_space(color_space);
+
+        Ok(())
+    }
+
+    /// Adds an [`OutputIntent`] to this page, describing the output condition this page's content
+    /// is prepared for. Page-level output intents override the ones of the document, e.g. for
+    /// press-ready inserts in an office document (PDF 2.0).
+    pub fn add_output_intent(&mut self, output_intent: OutputIntent) {
+        self.output_intents.push(output_intent);
+    }
+
+    /// Sets the [`TabOrder`] in which annotations on this page are navigated with the keyboard.
+    pub fn set_tab_order(&mut self, tab_order: TabOrder) {
+        self.tab_order = Some(tab_order);
+    }
+
+    /// Sets the [`Action`] that is performed when the given [`PageTrigger`] occurs, replacing the
+    /// action previously set for it.
+    pub fn set_action(&mut self, trigger: PageTrigger, action: Action) {
+        self.actions.insert(trigger, action);
+    }
+
+    /// Sets the thumbnail image of this page, shown by PDF readers in their page navigation panel
+    /// instead of rendering the page, which is faster for pages with large images. The thumbnail
+    /// should be a small DeviceGray or DeviceRGB image, e.g. created with [`Image::thumbnail`] from
+    /// the main image of a scanned page. Its position, size and mask are ignored.
+    pub fn set_thumbnail(&mut self, thumbnail: Image) {
+        self.thumbnail = Some(thumbnail);
+    }
+
+    /// Adds the given [`Annotation`] to this page, shown on top of the page's content in the order
+    /// the annotations are added.
+    pub fn add_annotation(&mut self, annotation: Annotation) {
+        let mut annotation = match &self.sanitize {
+            Some(policy) => annotation.sanitized(policy),
+            None => annotation,
+        };
+
+        let appearance = annotation
+            .stamp_content()
+            .map(|content| self.draw_appearance(annotation.rect(), content));
+        if let Some(appearance) = appearance {
+            annotation.set_appearance(appearance);
+        }
+
+        self.annotations.push(annotation);
+    }
+
+    /// Draws the given [`Recording`] into the [`Appearance`] of an annotation located at the given
+    /// rectangle, relative to its lower left corner. The drawn texts count as texts of this page,
+    /// so that they are checked and subset together with the page's texts.
+    fn draw_appearance(&mut self, rect: Rectangle, content: &Recording) -> Appearance {
+        let mut canvas = Page::detached(content, self.sanitize);
+
+        self.texts.append(&mut canvas.texts);
+        self.text_colors.append(&mut canvas.text_colors);
+
+        let b_box = Rectangle::new(
+            Position::from_units(0.0, 0.0),
+            Position::new(rect.width(), rect.height()),
+        );
+        Appearance::new(b_box, canvas.contents.as_bytes().to_vec()).with_resources(canvas.resources)
+    }
+
+    /// Draws the given [`Recording`] onto a detached page, which is only used for its resources and
+    /// content stream, applying the given [`SanitizePolicy`] to its texts.
+    fn detached(content: &Recording, sanitize: Option<SanitizePolicy>) -> Self {
+        let mut id_manager = IdManager::new();
+        let mut canvas = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        canvas.sanitize = sanitize;
+        canvas.replay(content);
+
+        canvas
+    }
+
+    /// Draws the given [`Recording`] into a [`Fragment`] with the given bounding box, applying the
+    /// given [`SanitizePolicy`] to its texts and looking up the fonts they are drawn with in the
+    /// given fonts of the document.
+    pub(crate) fn record_fragment(
+        b_box: Rectangle,
+        content: &Recording,
+        sanitize: Option<SanitizePolicy>,
+        fonts: &[Font],
+    ) -> Result<Fragment, FragmentErr> {
+        let canvas = Page::detached(content, sanitize);
+
+        Fragment::new(
+            b_box,
+            canvas.contents.as_bytes().to_vec(),
+            &canvas.resources,
+            &canvas.texts,
+            fonts,
+        )
+    }
+
+    /// Returns the [`PageId`] handle of this page.
+    pub fn id(&self) -> PageId {
+        PageId::new(self.id.clone())
     }
 
-    /// Returns the object reference of this Page object.
-    pub fn obj_ref(&self) -> ObjId<Self> {
-        self.id.clone()
+    /// Writes the `OutputIntents` entry of this page.
+    fn write_output_intents(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            Self::OUTPUT_INTENTS.write(writer),
+            writer.write(b"["),
+            for output_intent in &self.output_intents {
+                output_intent.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+            writer.write(b"]"),
+            writer.write(constants::NL_MARKER),
+        })
     }
 
     fn write_mediabox(writer: &mut dyn Write, rect: Rectangle) -> Result<usize, Error> {
@@ -71,123 +498,2016 @@ impl Page {
         })
     }
 
+    /// Writes the transparency group dictionary of this page, declaring the blending colour space
+    /// so that blending results are consistent across viewers.
+    fn write_group(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            Self::GROUP.write(writer),
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::GROUP.write(writer),
+            Self::S.write(writer),
+            Self::TRANSPARENCY.write(writer),
+            Self::CS.write(writer),
+            self.blending_color_space.write(writer),
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
     // ids = vec[17, 18]
     // add_image -> id = ids.len() = 0; ids.push(0);
     // add_image -> id = ids.len() = 1; ids.push(0);
     //
     // when rendering:
     // for each id in ids.iter_mut() { *id = id_manager.create_id() }
-    pub fn add_image(&mut self, image: Image) {
+    pub fn add_image(&mut self, mut image: Image) {
         // /Im1 <-> ids[0] -> /Im1 17
         // ids[0] obj    -> 17 0 obj
+        if let Some(level) = self.image_compression {
+            image.compress(level);
+        }
+
+        let ext_g_state = image.take_ext_g_state();
+        let has_ext_g_state = ext_g_state.is_some();
+        self.begin_ext_g_state(ext_g_state);
+
         let transform = image.transform();
+        let stencil_color = image.stencil_color();
+        if let Some(color) = stencil_color {
+            self.resources.add_color_space(color.color_space());
+        }
+        self.uses_transparency |= image.soft_mask().is_some();
         let name = self.resources.add_image(image);
 
-        self.contents
-            .add_content(Operation::DrawImage { name, transform });
+        self.contents.add_content(Operation::DrawImage {
+            name,
+            transform,
+            stencil_color,
+        });
+
+        self.end_ext_g_state(has_ext_g_state);
+    }
+
+    /// Draws the [`Image`] with the given [`ImageId`], added with [`Document::add_image`], onto
+    /// this page at the given position, with the scale it was built with. The image is written
+    /// once, no matter how many pages it's drawn onto.
+    ///
+    /// [`Document::add_image`]: crate::Document::add_image
+    pub fn draw_image(&mut self, image_id: &ImageId, position: Position) {
+        let ext_g_state = image_id.ext_g_state().cloned();
+        let has_ext_g_state = ext_g_state.is_some();
+        self.begin_ext_g_state(ext_g_state);
+
+        let transform = ImageTransform {
+            position,
+            ..image_id.transform()
+        };
+        let stencil_color = image_id.stencil_color();
+        if let Some(color) = stencil_color {
+            self.resources.add_color_space(color.color_space());
+        }
+        self.uses_transparency |= image_id.has_soft_mask();
+        let name = self.resources.add_shared_image(image_id.obj_id().clone());
+
+        self.contents.add_content(Operation::DrawImage {
+            name,
+            transform,
+            stencil_color,
+        });
+
+        self.end_ext_g_state(has_ext_g_state);
     }
 
     /// Adds a text to the PDF page.
-    pub fn add_text(&mut self, text: Text, font_id: ObjId<Font>) {
-        let font_name = self.resources.add_font(font_id);
+    pub fn add_text(&mut self, mut text: Text, font_id: FontId) {
+        let ext_g_state = text.take_ext_g_state();
+        let has_ext_g_state = ext_g_state.is_some();
+        self.begin_ext_g_state(ext_g_state);
+        self.draw_text(text, font_id);
+        self.end_ext_g_state(has_ext_g_state);
+    }
+
+    /// Adds a text to the PDF page, drawn with the default [`TextStyle`] of the document. The text
+    /// is drawn with the font of the style, and in the size and color of the style unless it sets
+    /// its own. Fails with [`TextStyleErr::MissingStyle`] if the document has no default
+    /// [`TextStyle`]. See [`TextStyle`] for more details.
+    pub fn add_styled_text(&mut self, mut text: Text) -> Result<(), TextStyleErr> {
+        let Some((font_id, style)) = &self.text_style else {
+            return Err(TextStyleErr::MissingStyle);
+        };
+
+        text.apply_style(style);
+        self.add_text(text, font_id.clone());
+
+        Ok(())
+    }
+
+    /// Draws the given [`Text`] in the current graphics state of the page.
+    fn draw_text(&mut self, mut text: Text, font_id: FontId) {
+        if let Some(policy) = &self.sanitize {
+            text.sanitize(policy);
+        }
+        text.set_encoding(font_id.encoding());
+        text.set_metrics(font_id.metrics().clone());
+        self.text_colors.push(text.color());
+        self.resources.add_color_space(text.color().color_space());
+        if let Some(stroke_color) = text.stroke_color() {
+            self.resources.add_color_space(stroke_color.color_space());
+        }
+        self.texts
+            .push((font_id.clone(), text.content().into_owned()));
+        let font_name = self.resources.add_font(font_id.into_obj_id());
+
+        self.contents.add_content(Operation::DrawText {
+            text: Box::new(text),
+            font_name,
+        });
+    }
 
+    /// Fills the given [`Path`] with the given [`Color`] on this page.
+    pub fn fill_path(&mut self, path: impl Into<Path>, color: Color) {
+        self.resources.add_color_space(color.color_space());
+        self.contents.add_content(Operation::FillPath {
+            path: path.into(),
+            color,
+        });
+    }
+
+    /// Clips all content added inside of the `content` closure to the given [`Path`]. The clipping
+    /// is scoped by saving and restoring the graphics state, so content added after this call is
+    /// not clipped. Calls can be nested, in which case the clipping paths are intersected.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::primitives::rectangle::Rectangle};
+    /// # let mut document = Document::default();
+    /// # let page = document.create_page();
+    /// page.clip_to(Rectangle::from_units(0.0, 0.0, 100.0, 100.0), |page| {
+    ///     // Content added to `page` here is only visible inside of the rectangle.
+    ///     # let _ = page;
+    /// });
+    /// ```
+    pub fn clip_to(&mut self, path: impl Into<Path>, content: impl FnOnce(&mut Self)) {
+        self.contents.add_content(Operation::SaveState);
         self.contents
-            .add_content(Operation::DrawText { text, font_name });
+            .add_content(Operation::Clip { path: path.into() });
+
+        content(self);
+
+        self.contents.add_content(Operation::RestoreState);
     }
 
-    pub(crate) fn content_stream(&self) -> &ContentStream {
-        &self.contents
+    /// Clips all content added inside of the `content` closure to the glyph outlines of the given
+    /// [`Text`], e.g. to draw an image or a gradient inside of a headline. The text is drawn with
+    /// the clipping counterpart of its [`TextRenderingMode`], so an invisible text only clips, while
+    /// a filled text is painted as well. As with [`Page::clip_to`], the clipping is scoped to the
+    /// closure, and so is an [`ExtGState`] of the text, which also applies to the clipped content.
+    ///
+    /// [`TextRenderingMode`]: super::content::text::TextRenderingMode
+    pub fn clip_to_text(
+        &mut self,
+        mut text: Text,
+        font_id: FontId,
+        content: impl FnOnce(&mut Self),
+    ) {
+        text.use_for_clipping();
+
+        self.contents.add_content(Operation::SaveState);
+        if let Some(state) = text.take_ext_g_state() {
+            self.set_ext_g_state(state);
+        }
+        self.draw_text(text, font_id);
+
+        content(self);
+
+        self.contents.add_content(Operation::RestoreState);
     }
 
-    /// Encode the PDF Page into the given implementor of [`Write`].
-    pub(crate) fn write(
-        &self,
-        writer: &mut dyn Write,
-        id_manager: &mut IdManager,
-    ) -> Result<(usize, Vec<usize>), Error> {
-        let mut offsets = Vec::with_capacity(self.resources.entries.len());
+    /// Draws all operations of the given [`Recording`] onto this page.
+    pub fn replay(&mut self, recording: &Recording) {
+        self.replay_operations(recording.operations());
+    }
 
-        let mut renderable_resources = self.resources.renderables(id_manager);
+    /// Draws the given recorded operations onto this page, in order.
+    fn replay_operations(&mut self, operations: &[RecordedOperation]) {
+        for operation in operations {
+            match operation {
+                RecordedOperation::Text { text, font_id } => {
+                    self.add_text(text.clone(), font_id.clone())
+                }
+                RecordedOperation::Image(image) => self.add_image(image.clone()),
+                RecordedOperation::FillPath { path, color } => self.fill_path(path.clone(), *color),
+                RecordedOperation::Clip { path, operations } => {
+                    self.clip_to(path.clone(), |page| page.replay_operations(operations))
+                }
+                RecordedOperation::ClipToText {
+                    text,
+                    font_id,
+                    operations,
+                } => self.clip_to_text(text.clone(), font_id.clone(), |page| {
+                    page.replay_operations(operations)
+                }),
+            }
+        }
+    }
 
-        let written = pdfgen_macros::write_chain! {
-            self.id.write_def(writer),
-            writer.write(constants::NL_MARKER),
+    /// Adds all content added inside of the `content` closure to the given [`Layer`] of this page,
+    /// instead of the page's content.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::{Document, types::hierarchy::{content::{color::Color, path::Path}, page::Layer, primitives::rectangle::Rectangle}};
+    /// # let mut document = Document::default();
+    /// # let page = document.create_page();
+    /// page.with_layer(Layer::Underlay, |page| {
+    ///     page.fill_path(Rectangle::from_units(0.0, 0.0, 100.0, 100.0), Color::Gray(230));
+    /// });
+    /// ```
+    pub fn with_layer(&mut self, layer: Layer, content: impl FnOnce(&mut Self)) {
+        self.swap_layer(layer);
+        content(self);
+        self.swap_layer(layer);
+    }
 
-            writer.write(b"<< "),
-            Identifier::TYPE.write(writer),
-            Self::PAGE.write(writer),
-            writer.write(constants::NL_MARKER),
+    /// Swaps the content stream of the given [`Layer`] with the page's content stream, so that
+    /// added content ends up in the layer.
+    fn swap_layer(&mut self, layer: Layer) {
+        match layer {
+            Layer::Underlay => std::mem::swap(&mut self.contents, &mut self.underlay),
+            Layer::Content => {}
+            Layer::Overlay => std::mem::swap(&mut self.contents, &mut self.overlay),
+        }
+    }
 
-            Self::PARENT.write(writer),
-            self.parent.write_ref(writer),
-            writer.write(constants::NL_MARKER),
+    /// Saves the current graphics state and applies the given [`ExtGState`] to content that
+    /// follows. Does nothing if no [`ExtGState`] is given.
+    fn begin_ext_g_state(&mut self, ext_g_state: Option<ExtGState>) {
+        if let Some(state) = ext_g_state {
+            self.contents.add_content(Operation::SaveState);
+            self.set_ext_g_state(state);
+        }
+    }
 
-            Self::RESOURCES.write(writer),
-            self.resources.write_dict(writer, &renderable_resources),
-            writer.write(constants::NL_MARKER),
+    /// Applies the given [`ExtGState`] to the current graphics state of the page.
+    fn set_ext_g_state(&mut self, state: ExtGState) {
+        self.uses_transparency |= state.uses_transparency();
+        let name = self.resources.add_ext_g_state(state);
+        self.contents.add_content(Operation::SetExtGState { name });
+    }
 
-            if let Some(media_box) = self.media_box {
-                Self::write_mediabox(writer, media_box),
-            },
+    /// Restores the graphics state saved by `begin_ext_g_state`, if an [`ExtGState`] was applied.
+    fn end_ext_g_state(&mut self, has_ext_g_state: bool) {
+        if has_ext_g_state {
+            self.contents.add_content(Operation::RestoreState);
+        }
+    }
 
-            if !self.contents.is_empty() {
-                Self::CONTENTS.write(writer),
-                self.contents.obj_ref().write_ref(writer),
-                writer.write(constants::NL_MARKER),
-            },
+    /// Returns the colors of all texts added to this page.
+    pub(crate) fn text_colors(&self) -> &[Color] {
+        &self.text_colors
+    }
 
-            writer.write(b">>"),
-            writer.write(constants::NL_MARKER),
+    /// Returns a copy of this page without the drawing operations that paint entirely outside of
+    /// the given visible area, or `None` if there are no such operations. See
+    /// [`ContentStream::culled`] for the operations that are culled.
+    pub(crate) fn culled(&self, visible: Rectangle) -> Option<Self> {
+        let contents = self.contents.culled(visible);
+        let underlay = self.underlay.culled(visible);
+        let overlay = self.overlay.culled(visible);
+        if contents.is_none() && underlay.is_none() && overlay.is_none() {
+            return None;
+        }
 
-            // endobj\n
-            writer.write(constants::END_OBJ_MARKER),
-            writer.write(constants::NL_MARKER),
-            writer.write(constants::NL_MARKER),
+        // every drawn image is a resource of its own, so images that are no longer drawn are
+        // removed as well
+        let hidden_images: Vec<&[u8]> = [&self.contents, &self.underlay, &self.overlay]
+            .into_iter()
+            .flat_map(|layer| layer.hidden_images(visible))
+            .collect();
 
-            for renderable_entry in renderable_resources.iter_mut() {
-                {
-                    offsets.push(written);
-                    renderable_entry.write_def(writer)
-                }
-            },
+        let mut page = self.clone();
+        // the culled content streams have no extents, so a page sized to its content keeps the
+        // media box computed from the original ones
+        if let Some(media_box) = self.media_box() {
+            page.set_mediabox(media_box);
+            page.user_unit = self.user_unit();
+        }
+        page.resources.remove_images(&hidden_images);
+        page.contents = contents.unwrap_or(page.contents);
+        page.underlay = underlay.unwrap_or(page.underlay);
+        page.overlay = overlay.unwrap_or(page.overlay);
 
-            writer.write(constants::NL_MARKER),
-        };
+        Some(page)
+    }
 
-        Ok((written, offsets))
+    /// Returns a copy of this page with the given ids, belonging to the given parent and referring
+    /// to fonts, colour spaces and images added to the document by the ids they are mapped to,
+    /// e.g. to copy the page into another document. The copy doesn't belong to any document part
+    /// and has no form fields.
+    pub(crate) fn copy_with_ids(
+        &self,
+        id: ObjId<Self>,
+        contents_id: ObjId<ContentStream>,
+        parent: ObjId<PageTree>,
+        font_ids: &HashMap<ObjId<Font>, ObjId<Font>>,
+        color_space_ids: &ColorSpaceIds,
+        image_ids: &HashMap<ObjId<Image>, ObjId<Image>>,
+    ) -> Self {
+        let mut page = self.clone();
+        page.id = id;
+        page.parent = parent;
+        page.contents.set_id(contents_id);
+        page.resources.remap_font_ids(font_ids);
+        page.resources.remap_color_space_ids(color_space_ids);
+        page.resources.remap_image_ids(image_ids);
+        page.blending_color_space.remap_ids(color_space_ids);
+        page.document_part = None;
+        page.widgets.clear();
+
+        for annotation in &mut page.annotations {
+            annotation.remap_ids(font_ids, color_space_ids);
+        }
+
+        for (font_id, _) in &mut page.texts {
+            *font_id = font_id.with_id(font_ids[&font_id.clone().into_obj_id()].clone());
+        }
+
+        // the default font is only copied if the page used it
+        page.text_style = self.text_style.as_ref().and_then(|(font_id, style)| {
+            let id = font_ids.get(&font_id.clone().into_obj_id())?;
+            Some((font_id.with_id(id.clone()), style.clone()))
+        });
+
+        page
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::Page;
-    use crate::{IdManager, types::hierarchy::primitives::rectangle::Rectangle};
+    /// Creates a [`PageForm`] with the given [`ObjId`] and bounding box, holding the content of
+    /// all layers of this page, with its fonts, colour spaces and images added to the document
+    /// referenced by the ids they are mapped to, e.g. to draw the page onto a sheet of another
+    /// document.
+    pub(crate) fn to_form(
+        &self,
+        id: ObjId<PageForm>,
+        b_box: Rectangle,
+        font_ids: &HashMap<ObjId<Font>, ObjId<Font>>,
+        color_space_ids: &ColorSpaceIds,
+        image_ids: &HashMap<ObjId<Image>, ObjId<Image>>,
+    ) -> PageForm {
+        let mut resources = self.resources.clone();
+        resources.remap_font_ids(font_ids);
+        resources.remap_color_space_ids(color_space_ids);
+        resources.remap_image_ids(image_ids);
 
-    #[test]
-    fn basic_page() {
-        let mut id_manager = IdManager::new();
-        let mut page = Page::new(
-            id_manager.create_id(),
-            id_manager.create_id(),
-            id_manager.create_id(),
-        );
-        page.set_mediabox(Rectangle::from_units(0.0, 0.0, 100.0, 100.0));
+        let content = [&self.underlay, &self.contents, &self.overlay]
+            .into_iter()
+            .filter(|layer| !layer.is_empty())
+            .map(ContentStream::as_bytes)
+            .collect::<Vec<_>>()
+            .join(constants::NL_MARKER);
 
-        let mut writer = Vec::new();
-        page.write(&mut writer, &mut id_manager).unwrap();
+        let texts = self
+            .texts
+            .iter()
+            .map(|(font_id, text)| {
+                let id = font_ids[&font_id.clone().into_obj_id()].clone();
+                (font_id.with_id(id), text.clone())
+            })
+            .collect();
 
-        let output = String::from_utf8(writer).unwrap();
+        PageForm::new(id, b_box, resources, content, texts)
+    }
 
-        insta::assert_snapshot!(
-            output,
-            @r"
-        1 0 obj
-        << /Type /Page 
-        /Parent 3 0 R
-        /Resources <<  >>
-        /MediaBox [0 0 100 100]>>
-        endobj
-        "
+    /// Draws the [`PageForm`] with the given [`ObjId`] onto this page, transformed by the given
+    /// matrix `[a b c d e f]`.
+    pub(crate) fn draw_form(&mut self, form_id: ObjId<PageForm>, matrix: [f64; 6]) {
+        let name = self.resources.add_form(form_id);
+
+        self.contents
+            .add_content(Operation::DrawForm { name, matrix });
+    }
+
+    /// Draws the [`Fragment`] with the given [`FragmentId`], loaded with
+    /// [`Document::add_fragment`], onto this page, with the origin of the fragment's coordinate
+    /// space placed at the given position.
+    ///
+    /// [`Fragment`]: crate::Fragment
+    /// [`Document::add_fragment`]: crate::Document::add_fragment
+    pub fn draw_fragment(&mut self, fragment_id: &FragmentId, position: Position) {
+        // round to avoid writing values such as `56.69291338582677`
+        let round = |value: f64| (value * 100_000.0).round() / 100_000.0 + 0.0;
+        let (x, y) = (
+            round(position.x.into_points()),
+            round(position.y.into_points()),
+        );
+
+        self.draw_form(
+            fragment_id.clone().into_obj_id(),
+            [1.0, 0.0, 0.0, 1.0, x, y],
         );
     }
+
+    /// Uses the given buffer for the bytes of the page's content stream.
+    pub(crate) fn set_contents_buffer(&mut self, buffer: Vec<u8>) {
+        self.contents.set_buffer(buffer);
+    }
+
+    /// Sets the [`SanitizePolicy`] applied to the content of texts added to this page.
+    pub(crate) fn set_sanitization(&mut self, sanitize: Option<SanitizePolicy>) {
+        self.sanitize = sanitize;
+    }
+
+    /// Sets the zlib compression level the uncompressed samples of images added to this page are
+    /// compressed with.
+    pub(crate) fn set_image_compression(&mut self, level: Option<u8>) {
+        self.image_compression = level;
+    }
+
+    /// Sets the default [`TextStyle`] of the document and its font, used by texts added to this
+    /// page with [`Page::add_styled_text`].
+    pub(crate) fn set_text_style(&mut self, text_style: Option<(FontId, TextStyle)>) {
+        self.text_style = text_style;
+    }
+
+    /// Takes the buffers out of all content streams of this page, leaving them empty.
+    pub(crate) fn take_buffers(&mut self) -> [Vec<u8>; 3] {
+        [
+            self.underlay.take_buffer(),
+            self.contents.take_buffer(),
+            self.overlay.take_buffer(),
+        ]
+    }
+
+    /// Returns the [`ObjId`]s of all fonts used on this page.
+    pub(crate) fn font_ids(&self) -> impl Iterator<Item = &ObjId<Font>> {
+        self.resources
+            .font_ids()
+            .chain(self.annotations.iter().flat_map(Annotation::font_ids))
+    }
+
+    /// Returns the [`ObjId`]s of all images added to the document that are drawn onto this page.
+    pub(crate) fn shared_image_ids(&self) -> impl Iterator<Item = &ObjId<Image>> {
+        self.resources.shared_image_ids()
+    }
+
+    /// Returns all colour spaces used on this page.
+    pub(crate) fn color_spaces(&self) -> impl Iterator<Item = ColorSpace> {
+        self.resources
+            .color_spaces()
+            .chain(self.annotations.iter().flat_map(Annotation::color_spaces))
+            .chain([self.blending_color_space])
+    }
+
+    /// Returns the [`PageStats`] summarizing the content of this page.
+    pub(crate) fn stats(&self) -> PageStats {
+        let streams = [&self.underlay, &self.contents, &self.overlay];
+
+        let mut fonts = BTreeMap::new();
+        for (font_id, _) in &self.texts {
+            *fonts.entry(font_id.clone()).or_default() += 1;
+        }
+
+        PageStats::new(
+            streams.iter().map(|stream| stream.operations()).sum(),
+            streams.iter().map(|stream| stream.len()).sum(),
+            fonts,
+            self.resources.image_count(),
+        )
+    }
+
+    /// Replaces every occurrence of the given pattern in the content of the texts drawn on this
+    /// page, and returns the number of texts that were changed. The replacements are sanitized
+    /// with the [`SanitizePolicy`] of the page.
+    pub(crate) fn replace_text(&mut self, pattern: &str, replacement: &str) -> usize {
+        let sanitize = self.sanitize;
+        let mut replaced = Vec::new();
+
+        for layer in [&mut self.underlay, &mut self.contents, &mut self.overlay] {
+            layer.replace_texts(|text| {
+                let mut new_text = text.replaced(pattern, replacement)?;
+                if let Some(policy) = &sanitize {
+                    new_text.sanitize(policy);
+                }
+                replaced.push((text.content().into_owned(), new_text.content().into_owned()));
+                Some(new_text)
+            });
+        }
+
+        // the fonts are checked and subset with the replaced contents
+        let mut updated = vec![false; self.texts.len()];
+        for (old, new) in &replaced {
+            let position = self
+                .texts
+                .iter()
+                .zip(&updated)
+                .position(|((_, content), updated)| !updated && content == old);
+            if let Some(idx) = position {
+                self.texts[idx].1 = new.clone();
+                updated[idx] = true;
+            }
+        }
+
+        replaced.len()
+    }
+
+    /// Returns the contents of all texts added to this page, together with their fonts.
+    pub(crate) fn texts(&self) -> &[(FontId, String)] {
+        &self.texts
+    }
+
+    pub(crate) fn content_stream(&self) -> &ContentStream {
+        &self.contents
+    }
+
+    /// Returns the document part this page belongs to, if any.
+    pub(crate) fn document_part(&self) -> Option<&ObjId<DocumentPart>> {
+        self.document_part.as_ref()
+    }
+
+    /// Sets the document part this page belongs to.
+    pub(crate) fn set_document_part(&mut self, document_part: ObjId<DocumentPart>) {
+        self.document_part = Some(document_part);
+    }
+
+    /// Adds the widget of a form field with the given [`ObjId`] to the annotations of this page.
+    pub(crate) fn add_widget(&mut self, widget: ObjId<Widget>) {
+        self.widgets.push(widget);
+    }
+
+    /// Returns the media box of this page, if set, which is computed from the bounds of its content
+    /// if the page is sized to its content.
+    pub(crate) fn media_box(&self) -> Option<Rectangle> {
+        let auto_sized = self
+            .auto_sized()
+            .map(|(media_box, user_unit)| media_box.scaled(1.0 / user_unit));
+
+        auto_sized.or(self.media_box)
+    }
+
+    /// Returns the size of the default user space unit of this page in points, if it isn't 1.
+    pub(crate) fn user_unit(&self) -> Option<f32> {
+        match self.auto_sized() {
+            Some((_, user_unit)) => (user_unit > 1.0).then_some(user_unit),
+            None => self.user_unit,
+        }
+    }
+
+    /// Returns the trim box of this page, if set.
+    pub(crate) fn trim_box(&self) -> Option<Rectangle> {
+        self.trim_box
+    }
+
+    /// Returns the bleed box of this page, if set.
+    pub(crate) fn bleed_box(&self) -> Option<Rectangle> {
+        self.bleed_box
+    }
+
+    /// Writes the `Contents` entry of this page, referencing the given content streams. Nothing is
+    /// written if there are no content streams.
+    fn write_contents(writer: &mut dyn Write, contents: &Vec<ObjId>) -> Result<usize, Error> {
+        Ok(match contents.as_slice() {
+            [] => 0,
+            [contents] => pdfgen_macros::write_chain! {
+                Self::CONTENTS.write(writer),
+                contents.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+            },
+            _ => pdfgen_macros::write_chain! {
+                Self::CONTENTS.write(writer),
+                contents.write_array(writer, None),
+                writer.write(constants::NL_MARKER),
+            },
+        })
+    }
+
+    /// Encode the PDF Page into the given implementor of [`Write`]. If a [`WatermarkStamp`] is
+    /// given, the watermark is drawn on top of the page's content, as are the given printer's
+    /// marks.
+    pub(crate) fn write(
+        &self,
+        writer: &mut dyn Write,
+        id_manager: &mut IdManager,
+        watermark: Option<&WatermarkStamp>,
+        print_marks: Option<&ContentStream>,
+    ) -> Result<(usize, Vec<usize>), Error> {
+        let mut offsets = Vec::with_capacity(self.resources.entries.len());
+
+        let mut renderable_resources = self.resources.renderables(id_manager);
+
+        let mut create_layer_id = |layer: &ContentStream| {
+            (!layer.is_empty()).then(|| id_manager.create_id::<ContentStream>())
+        };
+        // the content of a page with a larger user space unit is scaled down by the same factor,
+        // so that it keeps its size. Decorations are drawn in the scaled user space.
+        let [scale, restore] = self.user_unit().map_or([None, None], |user_unit| {
+            let factor = 1.0 / user_unit;
+            let scale = format!("q\n{factor} 0 0 {factor} 0 0 cm\n").into_bytes();
+            [scale, b"Q\n".to_vec()].map(|bytes| {
+                let mut layer = ContentStream::without_id();
+                layer.set_buffer(bytes);
+                Some(layer)
+            })
+        });
+
+        // ids are created in the order the layers are written in
+        let scale_id = scale.as_ref().and_then(&mut create_layer_id);
+        let underlay_id = create_layer_id(&self.underlay);
+        let overlay_id = create_layer_id(&self.overlay);
+        let restore_id = restore.as_ref().and_then(&mut create_layer_id);
+        let thumbnail_id = self.thumbnail.as_ref().map(|_| id_manager.create_id());
+        let annotation_ids: Vec<ObjId<Annotation>> = self
+            .annotations
+            .iter()
+            .map(|_| id_manager.create_id())
+            .collect();
+        let appearances: Vec<Option<(Appearance, ObjId<Appearance>)>> = self
+            .annotations
+            .iter()
+            .map(|annotation| {
+                let appearance = annotation.appearance()?;
+                Some((appearance, id_manager.create_id()))
+            })
+            .collect();
+        let annotations = self
+            .annotations
+            .iter()
+            .zip(&annotation_ids)
+            .zip(&appearances);
+
+        // content streams are painted in order, so the underlay comes first and the decorations
+        // last
+        let contents: Vec<ObjId> = scale_id
+            .iter()
+            .chain(underlay_id.iter())
+            .chain((!self.contents.is_empty()).then(|| self.contents.obj_ref()))
+            .chain(overlay_id.iter())
+            .chain(restore_id.iter())
+            .chain(watermark.map(|watermark| watermark.contents().obj_ref()))
+            .chain(print_marks.map(ContentStream::obj_ref))
+            .map(|id| id.clone().cast())
+            .collect();
+
+        let layers = [
+            (scale.as_ref(), &scale_id),
+            (Some(&self.underlay), &underlay_id),
+            (Some(&self.overlay), &overlay_id),
+            (restore.as_ref(), &restore_id),
+        ]
+        .into_iter()
+        .filter_map(|(layer, id)| Some((layer?, id.as_ref()?)));
+
+        let written = pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::PAGE.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::PARENT.write(writer),
+            self.parent.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::RESOURCES.write(writer),
+            self.resources.write_dict(writer, &renderable_resources, |writer| {
+                match watermark {
+                    Some(watermark) => watermark.write_resource_ref(writer),
+                    None => Ok(0),
+                }
+            }),
+            writer.write(constants::NL_MARKER),
+
+            if self.uses_transparency || watermark.is_some_and(WatermarkStamp::uses_transparency) {
+                self.write_group(writer),
+            },
+
+            if let Some(media_box) = self.media_box() {
+                Self::write_mediabox(writer, media_box),
+            },
+
+            if let Some(user_unit) = self.user_unit() {
+                Self::USER_UNIT.write(writer),
+                crate::write_fmt!(&mut *writer, "{user_unit}"),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(bleed_box) = self.bleed_box {
+                Self::BLEED_BOX.write(writer),
+                bleed_box.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(trim_box) = self.trim_box {
+                Self::TRIM_BOX.write(writer),
+                trim_box.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if !self.output_intents.is_empty() {
+                self.write_output_intents(writer),
+            },
+
+            if let Some(document_part) = &self.document_part {
+                Self::D_PART.write(writer),
+                document_part.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(tab_order) = self.tab_order {
+                Self::TABS.write(writer),
+                tab_order.identifier().write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if !self.actions.is_empty() {
+                Self::AA.write(writer),
+                action::write_additional_actions(
+                    writer,
+                    self.actions
+                        .iter()
+                        .map(|(trigger, action)| (trigger.identifier(), action)),
+                ),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(thumbnail_id) = &thumbnail_id {
+                Self::THUMB.write(writer),
+                thumbnail_id.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if !annotation_ids.is_empty() || !self.widgets.is_empty() {
+                Self::ANNOTS.write(writer),
+                annotation_ids
+                    .iter()
+                    .map(|id| id.clone().cast())
+                    .chain(self.widgets.iter().map(|id| id.clone().cast()))
+                    .collect::<Vec<ObjId>>()
+                    .write_array(writer, None),
+                writer.write(constants::NL_MARKER),
+            },
+
+            Self::write_contents(writer, &contents),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+
+            // endobj\n
+            writer.write(constants::END_OBJ_MARKER),
+            writer.write(constants::NL_MARKER),
+            writer.write(constants::NL_MARKER),
+
+            for renderable_entry in renderable_resources.iter_mut() {
+                {
+                    offsets.push(written);
+                    renderable_entry.write_def(writer)
+                }
+            },
+            for (mask, id) in renderable_resources.iter().filter_map(Renderable::mask) {
+                {
+                    offsets.push(written);
+                    mask.write(writer, id)
+                }
+            },
+
+            for (layer, id) in layers {
+                {
+                    offsets.push(written);
+                    layer.write_with_id(writer, id)
+                },
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let (Some(thumbnail), Some(id)) = (&self.thumbnail, &thumbnail_id) {
+                {
+                    offsets.push(written);
+                    thumbnail.write(writer, id)
+                },
+            },
+
+            for ((annotation, id), appearance) in annotations {
+                {
+                    offsets.push(written);
+                    let appearance_id = appearance.as_ref().map(|(_, id)| id);
+                    annotation.write(writer, id, appearance_id, &self.id)
+                },
+            },
+
+            for (appearance, id) in appearances.iter().flatten() {
+                {
+                    offsets.push(written);
+                    let (appearance_written, appearance_offsets) =
+                        appearance.write(writer, id, id_manager)?;
+                    offsets.extend(appearance_offsets.into_iter().map(|offset| written + offset));
+                    Ok::<_, Error>(appearance_written)
+                },
+            },
+
+            writer.write(constants::NL_MARKER),
+        };
+
+        Ok((written, offsets))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Layer, Page, TabOrder};
+    use crate::{
+        FontId, IccProfileId, IdManager, TextStyleErr,
+        types::hierarchy::primitives::object::Object,
+        types::hierarchy::{
+            action::{Action, NamedAction, PageTrigger},
+            annotation::{Annotation, LineEndingStyle, Quad},
+            content::{
+                color::{Color, ColorSpace, DefaultColorSpaceErr},
+                graphics_state::ExtGState,
+                image::Image,
+                path::{FillRule, Path},
+                recording::Recording,
+                text::{Text, TextRenderingMode},
+            },
+            primitives::{
+                date::PdfDate,
+                font::Font,
+                output_intent::{OutputIntent, OutputIntentSubtype},
+                rectangle::{Position, Rectangle},
+                unit::Unit,
+            },
+        },
+    };
+
+    #[test]
+    fn basic_page() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_mediabox(Rectangle::from_units(0.0, 0.0, 100.0, 100.0));
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(
+            output,
+            @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /MediaBox [0 0 100 100]>>
+        endobj
+        "
+        );
+    }
+
+    #[test]
+    fn structure_tab_order() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_tab_order(TabOrder::Structure);
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /Tabs /S 
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn page_actions() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_action(PageTrigger::Close, Action::Named(NamedAction::NextPage));
+        page.set_action(
+            PageTrigger::Open,
+            Action::JavaScript("track('intro');".to_string()),
+        );
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /AA << /O << /Type /Action /S /JavaScript /JS (track\('intro'\);) >> /C << /Type /Action /S /Named /N /NextPage >> >>
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn transparent_page() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_mediabox(Rectangle::from_units(0.0, 0.0, 100.0, 100.0));
+        page.set_blending_color_space(ColorSpace::DeviceCmyk);
+        page.add_text(
+            Text::builder()
+                .with_ext_g_state(ExtGState::new().with_fill_alpha(0.5))
+                .at(Position::from_units(10.0, 10.0))
+                .build(),
+            FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica")),
+        );
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources << /Font << /F2 4 0 R  >>/ExtGState << /GS1 5 0 R  >> >>
+        /Group << /Type /Group /S /Transparency /CS /DeviceCMYK >>
+        /MediaBox [0 0 100 100]/Contents 2 0 R
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /ExtGState 
+        /ca 0.5
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn default_color_spaces() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let rgb_profile = IccProfileId::new(&id_manager.create_id(), 3);
+        let cmyk_profile = IccProfileId::new(&id_manager.create_id(), 4);
+
+        page.set_default_color_space(ColorSpace::Icc(cmyk_profile))
+            .unwrap();
+        page.set_default_color_space(ColorSpace::Icc(rgb_profile))
+            .unwrap();
+        page.fill_path(
+            Rectangle::from_units(0.0, 0.0, 10.0, 10.0),
+            Color::icc(rgb_profile, &[255, 0, 0]),
+        );
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources << /ColorSpace << /DefaultCMYK [/ICCBased 5 0 R] /DefaultRGB [/ICCBased 4 0 R] /ICC4 [/ICCBased 4 0 R] >> >>
+        /Contents 2 0 R
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn styled_text_without_style() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+
+        let txt = Text::builder()
+            .with_content("Unstyled")
+            .at(Position::from_units(10.0, 10.0))
+            .build();
+        assert_eq!(page.add_styled_text(txt), Err(TextStyleErr::MissingStyle));
+        assert!(page.contents.is_empty());
+    }
+
+    #[test]
+    fn device_default_color_space() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+
+        assert_eq!(
+            page.set_default_color_space(ColorSpace::DeviceRgb),
+            Err(DefaultColorSpaceErr::DeviceColorSpace)
+        );
+    }
+
+    #[test]
+    fn clipped_content() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        page.clip_to(Rectangle::from_units(0.0, 0.0, 50.0, 50.0), |page| {
+            let triangle = Path::new()
+                .move_to(Position::from_units(0.0, 0.0))
+                .line_to(Position::from_units(50.0, 0.0))
+                .line_to(Position::from_units(0.0, 50.0))
+                .close()
+                .with_fill_rule(FillRule::EvenOdd);
+
+            page.clip_to(triangle, |page| {
+                page.add_text(
+                    Text::builder()
+                        .with_content("Clipped")
+                        .at(Position::from_units(10.0, 10.0))
+                        .build(),
+                    font_id,
+                );
+            });
+        });
+
+        let mut writer = Vec::new();
+        page.content_stream().write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 113 >>
+        stream
+        q
+        0 0 50 50 re
+        W n
+        q
+        0 0 m
+        50 0 l
+        0 50 l
+        h
+        W* n
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        10 10 Td
+        (Clipped) Tj
+        ET
+        Q
+        Q
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn layered_content() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+
+        page.fill_path(
+            Rectangle::from_units(10.0, 10.0, 20.0, 20.0),
+            Color::Rgb {
+                red: 255,
+                green: 0,
+                blue: 0,
+            },
+        );
+        page.with_layer(Layer::Overlay, |page| {
+            page.fill_path(
+                Rectangle::from_units(15.0, 15.0, 25.0, 25.0),
+                Color::Rgb {
+                    red: 0,
+                    green: 0,
+                    blue: 255,
+                },
+            );
+        });
+        page.with_layer(Layer::Underlay, |page| {
+            page.fill_path(
+                Rectangle::from_units(0.0, 0.0, 100.0, 100.0),
+                Color::Gray(230),
+            );
+        });
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /Contents [4 0 R
+         2 0 R
+         5 0 R]
+        >>
+        endobj
+
+        4 0 obj
+        << /Length 45 >>
+        stream
+        /DeviceGray cs
+        0.9019608 sc
+        0 0 100 100 re
+        f
+
+        endstream
+        endobj
+
+        5 0 obj
+        << /Length 40 >>
+        stream
+        /DeviceRGB cs
+        0 0 1 sc
+        15 15 10 10 re
+        f
+
+        endstream
+        endobj
+        ");
+    }
+
+    #[test]
+    fn auto_sized_media_box() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_mediabox(Rectangle::A4);
+        page.set_auto_size(Unit::from_unit(10.0));
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        page.fill_path(
+            Rectangle::from_units(0.0, -40.0, 50.0, -30.0),
+            Color::Gray(0),
+        );
+        page.add_text(
+            Text::builder()
+                .with_content("Total")
+                .with_size(10)
+                .at(Position::from_units(0.0, 0.0))
+                .build(),
+            font_id,
+        );
+
+        let mut writer = Vec::new();
+        page.content_bounds().unwrap().write(&mut writer).unwrap();
+        writer.push(b'\n');
+        page.media_box().unwrap().write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [0 -40 50 10]
+        [-10 -50 60 20]
+        ");
+    }
+
+    #[test]
+    fn auto_sized_media_box_in_mixed_units() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_auto_size(Unit::from_unit(0.0));
+
+        page.fill_path(
+            Rectangle::new(Position::from_mm(10.0, 10.0), Position::from_mm(50.0, 20.0)),
+            Color::Gray(0),
+        );
+        page.fill_path(
+            Rectangle::from_units(0.0, 0.0, 20.0, 20.0),
+            Color::Gray(128),
+        );
+
+        let mut writer = Vec::new();
+        page.content_bounds().unwrap().write(&mut writer).unwrap();
+        writer.push(b'\n');
+        page.media_box().unwrap().write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        [0 0 141.73228 56.692913]
+        [0 0 141.73228 56.692913]
+        ");
+    }
+
+    #[test]
+    fn long_roll() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.set_roll(Unit::from_unit(200.0), Unit::from_unit(0.0));
+        page.fill_path(
+            Rectangle::from_units(10.0, -20_000.0, 190.0, 0.0),
+            Color::Gray(0),
+        );
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /MediaBox [0 -10000 100 0]/UserUnit 2
+        /Contents [4 0 R
+         2 0 R
+         5 0 R]
+        >>
+        endobj
+
+        4 0 obj
+        << /Length 21 >>
+        stream
+        q
+        0.5 0 0 0.5 0 0 cm
+
+        endstream
+        endobj
+
+        5 0 obj
+        << /Length 2 >>
+        stream
+        Q
+
+        endstream
+        endobj
+        ");
+    }
+
+    #[test]
+    fn culled_content() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        page.clip_to(Rectangle::from_units(0.0, 0.0, 100.0, 100.0), |page| {
+            // only touches the visible area at its corner
+            page.fill_path(
+                Rectangle::from_units(-20.0, -20.0, 0.0, 0.0),
+                Color::Gray(0),
+            );
+            page.fill_path(
+                Rectangle::from_units(90.0, 90.0, 120.0, 120.0),
+                Color::Gray(128),
+            );
+        });
+        page.add_text(
+            Text::builder()
+                .with_content("Kept")
+                .at(Position::from_units(150.0, 10.0))
+                .build(),
+            font_id,
+        );
+
+        let visible = Rectangle::from_units(0.0, 0.0, 100.0, 100.0);
+        let culled = page.culled(visible).unwrap();
+
+        let mut writer = Vec::new();
+        culled.content_stream().write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 127 >>
+        stream
+        q
+        0 0 100 100 re
+        W n
+        /DeviceGray cs
+        0.5019608 sc
+        90 90 30 30 re
+        f
+        Q
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        150 10 Td
+        (Kept) Tj
+        ET
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn culled_content_in_mm() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+
+        // far off the page, but in a different unit than the visible area
+        page.fill_path(
+            Rectangle::new(
+                Position::from_mm(500.0, 500.0),
+                Position::from_mm(520.0, 520.0),
+            ),
+            Color::Gray(0),
+        );
+        page.fill_path(
+            Rectangle::new(Position::from_mm(10.0, 10.0), Position::from_mm(20.0, 20.0)),
+            Color::Gray(128),
+        );
+
+        let visible = Rectangle::from_units(0.0, 0.0, 100.0, 100.0);
+        let culled = page.culled(visible).unwrap();
+
+        let mut writer = Vec::new();
+        culled.content_stream().write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 73 >>
+        stream
+        /DeviceGray cs
+        0.5019608 sc
+        28.346457 28.346457 28.346457 28.346457 re
+        f
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn text_clipped_content() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        let headline = Text::builder()
+            .with_content("Headline")
+            .with_rendering_mode(TextRenderingMode::Invisible)
+            .at(Position::from_units(10.0, 10.0))
+            .build();
+
+        page.clip_to_text(headline, font_id, |page| {
+            page.fill_path(Rectangle::from_units(0.0, 0.0, 100.0, 30.0), Color::Gray(0));
+        });
+
+        let mut writer = Vec::new();
+        page.content_stream().write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 112 >>
+        stream
+        q
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        7 Tr
+        /F1 12 Tf
+        10 10 Td
+        (Headline) Tj
+        0 Tr
+        ET
+        /DeviceGray cs
+        0 sc
+        0 0 100 30 re
+        f
+        Q
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn replaced_text() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        page.fill_path(Rectangle::from_units(0.0, 0.0, 10.0, 10.0), Color::Gray(0));
+        page.add_text(
+            Text::builder()
+                .with_content("Dear Jon,")
+                .at(Position::from_units(10.0, 80.0))
+                .build(),
+            font_id.clone(),
+        );
+        page.add_text(
+            Text::builder()
+                .with_content("Thank you Jon")
+                .in_box(Rectangle::from_units(10.0, 20.0, 90.0, 60.0))
+                .build(),
+            font_id.clone(),
+        );
+        page.add_text(
+            Text::builder()
+                .with_content("Kind regards")
+                .at(Position::from_units(10.0, 10.0))
+                .build(),
+            font_id,
+        );
+        page.fill_path(
+            Rectangle::from_units(90.0, 90.0, 100.0, 100.0),
+            Color::Gray(0),
+        );
+
+        assert_eq!(page.replace_text("Jon", "Jonathan Appleseed"), 2);
+        assert_eq!(page.replace_text("Jane", "John"), 0);
+
+        let texts: Vec<_> = page.texts().iter().map(|(_, content)| content).collect();
+        assert_eq!(
+            texts,
+            [
+                "Dear Jonathan Appleseed,",
+                "Thank you Jonathan Appleseed",
+                "Kind regards"
+            ]
+        );
+
+        let mut writer = Vec::new();
+        page.content_stream().write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Length 304 >>
+        stream
+        /DeviceGray cs
+        0 sc
+        0 0 10 10 re
+        f
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        10 80 Td
+        (Dear Jonathan Appleseed,) Tj
+        ET
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F2 12 Tf
+        10 48 Td
+        (Thank you) Tj
+        0 -14.4 Td
+        (Jonathan) Tj
+        ET
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F3 12 Tf
+        10 10 Td
+        (Kind regards) Tj
+        ET
+        /DeviceGray cs
+        0 sc
+        90 90 10 10 re
+        f
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn replayed_recording() {
+        let mut id_manager = IdManager::new();
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        let mut recording = Recording::new();
+        recording.clip_to(Rectangle::from_units(0.0, 0.0, 50.0, 20.0), |recording| {
+            recording.fill_path(
+                Rectangle::from_units(0.0, 0.0, 100.0, 20.0),
+                Color::Gray(230),
+            );
+            recording.add_text(
+                Text::builder()
+                    .with_content("Footer")
+                    .at(Position::from_units(5.0, 5.0))
+                    .build(),
+                font_id,
+            );
+        });
+
+        let mut pages = [(); 2].map(|_| {
+            Page::new(
+                id_manager.create_id(),
+                id_manager.create_id(),
+                id_manager.create_id(),
+            )
+        });
+        for page in &mut pages {
+            page.replay(&recording);
+        }
+
+        let [first, second] = pages.map(|page| {
+            let mut writer = Vec::new();
+            page.content_stream().write_content(&mut writer).unwrap();
+            String::from_utf8(writer).unwrap()
+        });
+
+        assert_eq!(first, second);
+        insta::assert_snapshot!(first, @r"
+        << /Length 123 >>
+        stream
+        q
+        0 0 50 20 re
+        W n
+        /DeviceGray cs
+        0.9019608 sc
+        0 0 100 20 re
+        f
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        5 5 Td
+        (Footer) Tj
+        ET
+        Q
+
+        endstream
+        ");
+    }
+
+    #[test]
+    fn page_output_intents() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.add_output_intent(OutputIntent::new(OutputIntentSubtype::PdfX, "FOGRA39"));
+        page.add_output_intent(OutputIntent::new(OutputIntentSubtype::PdfA, "sRGB"));
+
+        let mut writer = Vec::new();
+        page.write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /OutputIntents [<< /Type /OutputIntent /S /GTS_PDFX 
+        /OutputConditionIdentifier (FOGRA39)
+        >>
+        << /Type /OutputIntent /S /GTS_PDFA1 
+        /OutputConditionIdentifier (sRGB)
+        >>
+        ]
+        >>
+        endobj
+        ");
+    }
+
+    #[test]
+    fn page_thumbnail() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let image = Image::from_raw(b"RGBrgbXYZxyz".to_vec(), 2, 2, ColorSpace::DeviceRgb)
+            .unwrap()
+            .with_compression_level(0)
+            .at(Position::from_units(0.0, 0.0))
+            .build();
+        page.set_thumbnail(image.thumbnail(1).unwrap());
+
+        let mut writer = Vec::new();
+        let (_, offsets) = page
+            .write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /Thumb 4 0 R
+        >>
+        endobj
+
+        4 0 obj
+        << /Type /XObject 
+        /Subtype /Image 
+        /Width 1
+        /Height 1
+        /ColorSpace /DeviceRGB 
+        /BitsPerComponent 8
+        /Length 3 >>
+        stream
+        e`^
+        endstream
+        endobj
+        ");
+        assert_eq!(offsets.len(), 1);
+    }
+
+    #[test]
+    fn page_annotations() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.add_annotation(
+            Annotation::highlight([
+                Rectangle::from_units(72.0, 700.0, 300.0, 714.0),
+                Rectangle::from_units(72.0, 686.0, 180.0, 700.0),
+            ])
+            .unwrap()
+            .with_color(Color::Rgb {
+                red: 255,
+                green: 255,
+                blue: 0,
+            })
+            .with_contents("Search result")
+            .with_author("Indexer")
+            .with_subject("Invoice")
+            .with_creation_date(PdfDate::new(2024, 3, 18).unwrap())
+            .with_modification_date(
+                PdfDate::new(2024, 3, 19)
+                    .and_then(|date| date.with_time(9, 30, 0))
+                    .and_then(|date| date.with_utc_offset(60))
+                    .unwrap(),
+            ),
+        );
+        let quad = Quad::new(
+            Position::from_units(110.0, 520.0),
+            Position::from_units(200.0, 540.0),
+            Position::from_units(114.0, 500.0),
+            Position::from_units(204.0, 520.0),
+        );
+        page.add_annotation(
+            Annotation::strike_out([quad])
+                .unwrap()
+                .with_color(Color::Gray(0)),
+        );
+
+        let mut writer = Vec::new();
+        let (_, offsets) = page
+            .write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /Annots [4 0 R
+         5 0 R]
+        >>
+        endobj
+
+        4 0 obj
+        << /Type /Annot 
+        /Subtype /Highlight 
+        /Rect [72 686 300 714]
+        /P 1 0 R
+        /F 4
+        /Contents (Search result)
+        /M (D:20240319093000+01'00')
+        /C [1 1 0]
+        /T (Indexer)
+        /Subj (Invoice)
+        /CreationDate (D:20240318000000Z)
+        /QuadPoints [72 714 300 714 72 700 300 700 72 700 180 700 72 686 180 686]
+        /AP << /N 6 0 R >>
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /StrikeOut 
+        /Rect [110 500 204 540]
+        /P 1 0 R
+        /F 4
+        /C [0]
+        /QuadPoints [110 520 200 540 114 500 204 520]
+        /AP << /N 7 0 R >>
+        >>
+        endobj
+
+        6 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [72 686 300 714]
+        /Resources << /ExtGState << /GS1 8 0 R  >> >>
+        /Length 115 >>
+        stream
+        /GS1 gs
+        /DeviceRGB cs
+        1 1 0 sc
+        72 700 m
+        300 700 l
+        300 714 l
+        72 714 l
+        h
+        f
+        72 686 m
+        180 686 l
+        180 700 l
+        72 700 l
+        h
+        f
+
+        endstream
+        endobj
+
+        8 0 obj
+        << /Type /ExtGState 
+        /BM /Multiply 
+        >>
+        endobj
+        7 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [110 500 204 540]
+        /Resources <<  >>
+        /Length 54 >>
+        stream
+        /DeviceGray CS
+        0 SC
+        1.4568628 w
+        112 510 m
+        202 530 l
+        S
+
+        endstream
+        endobj
+        ");
+        assert_eq!(offsets.len(), 5);
+    }
+
+    #[test]
+    fn shape_annotations() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        page.add_annotation(
+            Annotation::square(Rectangle::from_units(100.0, 100.0, 200.0, 150.0))
+                .with_interior_color(Color::Gray(255))
+                .with_border_width(2.0),
+        );
+        page.add_annotation(
+            Annotation::line(
+                Position::from_units(10.0, 10.0),
+                Position::from_units(90.0, 10.0),
+            )
+            .with_line_endings(LineEndingStyle::Butt, LineEndingStyle::ClosedArrow)
+            .with_interior_color(Color::Gray(0)),
+        );
+
+        let mut writer = Vec::new();
+        let (_, offsets) = page
+            .write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /Annots [4 0 R
+         5 0 R]
+        >>
+        endobj
+
+        4 0 obj
+        << /Type /Annot 
+        /Subtype /Square 
+        /Rect [100 100 200 150]
+        /P 1 0 R
+        /F 4
+        /C [0]
+        /IC [1]
+        /BS << /W 2 >>
+        /AP << /N 6 0 R >>
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /Line 
+        /Rect [3 3 97 17]
+        /P 1 0 R
+        /F 4
+        /C [0]
+        /IC [0]
+        /L [10 10 90 10]
+        /LE [/Butt /ClosedArrow ]
+        /AP << /N 7 0 R >>
+        >>
+        endobj
+
+        6 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [100 100 200 150]
+        /Resources <<  >>
+        /Length 82 >>
+        stream
+        2 w
+        /DeviceGray CS
+        0 SC
+        /DeviceGray cs
+        1 sc
+        101 101 98 48 re
+        f
+        101 101 98 48 re
+        S
+
+        endstream
+        endobj
+
+        7 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [3 3 97 17]
+        /Resources <<  >>
+        /Length 133 >>
+        stream
+        1 w
+        /DeviceGray CS
+        0 SC
+        /DeviceGray cs
+        0 sc
+        10 10 m
+        90 10 l
+        S
+        10 7 m
+        10 13 l
+        S
+        84 13 m
+        90 10 l
+        84 7 l
+        h
+        f
+        84 13 m
+        90 10 l
+        84 7 l
+        h
+        S
+
+        endstream
+        endobj
+        ");
+        assert_eq!(offsets.len(), 4);
+    }
+
+    #[test]
+    fn stamp_annotation() {
+        let mut id_manager = IdManager::new();
+        let mut page = Page::new(
+            id_manager.create_id(),
+            id_manager.create_id(),
+            id_manager.create_id(),
+        );
+        let font_id = FontId::new(&Font::new(id_manager.create_id(), "Type1", "Helvetica"));
+
+        let mut content = Recording::new();
+        content.fill_path(
+            Rectangle::from_units(0.0, 0.0, 100.0, 30.0),
+            Color::Gray(200),
+        );
+        content.add_text(
+            Text::builder()
+                .with_content("APPROVED")
+                .at(Position::from_units(10.0, 10.0))
+                .build(),
+            font_id,
+        );
+        page.add_annotation(
+            Annotation::stamp(Rectangle::from_units(400.0, 50.0, 500.0, 80.0), content)
+                .with_author("Reviewer"),
+        );
+
+        let mut writer = Vec::new();
+        let (_, offsets) = page
+            .write(&mut writer, &mut id_manager, None, None)
+            .unwrap();
+
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        1 0 obj
+        << /Type /Page 
+        /Parent 3 0 R
+        /Resources <<  >>
+        /Annots [5 0 R]
+        >>
+        endobj
+
+        5 0 obj
+        << /Type /Annot 
+        /Subtype /Stamp 
+        /Rect [400 50 500 80]
+        /P 1 0 R
+        /F 4
+        /T (Reviewer)
+        /AP << /N 6 0 R >>
+        >>
+        endobj
+
+        6 0 obj
+        << /Type /XObject 
+        /Subtype /Form 
+        /BBox [0 0 100 30]
+        /Resources << /Font << /F1 4 0 R  >> >>
+        /Length 107 >>
+        stream
+        /DeviceGray cs
+        0.78431374 sc
+        0 0 100 30 re
+        f
+        BT
+        /DeviceRGB cs
+        0 0 0 sc
+        /F1 12 Tf
+        10 10 Td
+        (APPROVED) Tj
+        ET
+
+        endstream
+        endobj
+        ");
+        assert_eq!(page.texts().len(), 1);
+        assert_eq!(page.font_ids().count(), 1);
+        assert_eq!(offsets.len(), 3);
+    }
 }
@@ -91,6 +91,13 @@ impl PageTree {
     pub(crate) fn set_page_size(&mut self, rect: Rectangle) {
         self.default_mediabox = Some(rect);
     }
+
+    /// Returns the default page size of [`Page`]s that are descendants of this `PageTree`.
+    ///
+    /// [`Page`]: super::page::Page
+    pub(crate) fn page_size(&self) -> Option<Rectangle> {
+        self.default_mediabox
+    }
 }
 
 impl Object for PageTree {
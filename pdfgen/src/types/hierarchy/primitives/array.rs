@@ -2,6 +2,8 @@ use std::io::{Error, Write};
 
 use crate::{ObjId, types::constants};
 
+use super::string::PdfString;
+
 /// Extension trait for implementations of arrays. This trait should be implemented for array-like
 /// data structures that can be used to represent PDF's array primitive type.
 pub trait WriteArray {
@@ -31,20 +33,30 @@ impl WriteArray for Vec<ObjId> {
     }
 }
 
+impl WriteArray for [f32] {
+    fn write_array(&self, writer: &mut dyn Write, _indent: Option<usize>) -> Result<usize, Error> {
+        let numbers = self
+            .iter()
+            .map(f32::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        crate::write_fmt!(&mut *writer, "[{numbers}]")
+    }
+}
+
 impl WriteArray for [u8; 16] {
     fn write_array(&self, writer: &mut dyn Write, indent: Option<usize>) -> Result<usize, Error> {
         let indent = " ".repeat(indent.unwrap_or(0));
 
+        let id = PdfString::from_bytes(*self);
+
         let written = pdfgen_macros::write_chain! {
             writer.write(b"["),
-            writer.write(b"<"),
-            writer.write(hex::encode(self).as_bytes()),
-            writer.write(b">"),
+            id.write_content(writer),
             writer.write(constants::NL_MARKER),
             writer.write(indent.as_bytes()),
-            writer.write(b"<"),
-            writer.write(hex::encode(self).as_bytes()),
-            writer.write(b">"),
+            id.write_content(writer),
             writer.write(constants::NL_MARKER),
             writer.write(indent.as_bytes()),
             writer.write(b"]"),
@@ -0,0 +1,607 @@
+//! Implementation of composite (Type 0) fonts, which show text in any script with a single font
+//! object, by addressing the glyphs of an embedded TrueType font program with two-byte codes.
+//!
+//! Strings shown with a composite font are encoded with the `Identity-H` encoding, where each code
+//! (CID) is the Unicode code point of a character. The `CIDToGIDMap` of the font maps the codes to
+//! the glyphs of the font program, and its `ToUnicode` map makes the text extractable again.
+//!
+//! Composite fonts showing runs shaped by an external shaper address glyphs directly instead, with
+//! codes equal to the glyph indices.
+
+use std::{
+    collections::BTreeMap,
+    io::{Error, Write},
+    sync::Arc,
+};
+
+use pdfgen_macros::const_identifiers;
+use ttf_parser::{Face, FaceParsingError, GlyphId};
+
+use crate::{
+    IdManager, ObjId,
+    types::{constants, hierarchy::content::stream::Stream},
+};
+
+use super::{
+    encoding::is_format_character,
+    font_descriptor::FontDescriptor,
+    font_metrics::{FontMetrics, Metrics, VerticalMetrics},
+    identifier::Identifier,
+    object::Object,
+};
+
+/// Possible errors that might be returned when creating a composite font.
+#[derive(Debug, thiserror::Error)]
+pub enum CompositeFontErr {
+    /// Indicates that the provided font data could not be parsed.
+    #[error("Font data could not be parsed: {0}")]
+    InvalidFont(FaceParsingError),
+}
+
+/// Glyph of the embedded font program shown for a character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Glyph {
+    /// Index of the glyph in the font program.
+    id: u16,
+
+    /// Advance width of the glyph in thousandths of the font size.
+    width: u16,
+}
+
+/// How the codes of the strings shown with a composite font address the glyphs of its font
+/// program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Addressing {
+    /// Codes are Unicode code points, mapped to glyph indices by the `CIDToGIDMap` stream.
+    Unicode,
+
+    /// Codes are glyph indices, e.g. of runs shaped by an external shaper. The advance of each
+    /// glyph is set by the run, so all glyphs have a width of 0.
+    Glyph,
+}
+
+/// IDs of the objects describing the embedded font program of a composite font.
+#[derive(Debug, Clone)]
+struct DescendantIds {
+    /// ID of the descendant `CIDFontType2` font dictionary.
+    cid_font: ObjId,
+
+    /// ID of the font descriptor.
+    descriptor: ObjId,
+
+    /// ID of the stream holding the font program.
+    font_file: ObjId,
+
+    /// ID of the `ToUnicode` CMap stream.
+    to_unicode: ObjId,
+
+    /// ID of the stream mapping codes to glyph indices, if codes aren't glyph indices already.
+    cid_to_gid_map: Option<ObjId>,
+}
+
+impl DescendantIds {
+    /// Creates new `DescendantIds` for a font with the given [`Addressing`], with ids allocated by
+    /// the given [`IdManager`].
+    fn new(id_manager: &mut IdManager, addressing: Addressing) -> Self {
+        Self {
+            cid_font: id_manager.create_id(),
+            descriptor: id_manager.create_id(),
+            font_file: id_manager.create_id(),
+            to_unicode: id_manager.create_id(),
+            cid_to_gid_map: match addressing {
+                Addressing::Unicode => Some(id_manager.create_id()),
+                Addressing::Glyph => None,
+            },
+        }
+    }
+}
+
+/// The embedded TrueType font program of a composite [`Font`], together with the ids of the
+/// objects describing it.
+///
+/// [`Font`]: super::font::Font
+#[derive(Debug, Clone)]
+pub(crate) struct CompositeFont {
+    /// Bytes of the TrueType font program, shared with the [`FontMetrics`] measuring text drawn
+    /// with the font.
+    data: Arc<[u8]>,
+
+    /// IDs of the objects describing the font program.
+    ids: DescendantIds,
+
+    /// How the codes of shown strings address the glyphs of the font program.
+    addressing: Addressing,
+}
+
+impl CompositeFont {
+    const_identifiers! {
+        FONT,
+        SUBTYPE,
+        BASE_FONT,
+        ENCODING,
+        IDENTITY_H: b"Identity-H",
+        IDENTITY,
+        DESCENDANT_FONTS,
+        TO_UNICODE,
+        CID_FONT_TYPE2: b"CIDFontType2",
+        CID_SYSTEM_INFO: b"CIDSystemInfo",
+        FONT_DESCRIPTOR,
+        CID_TO_GID_MAP: b"CIDToGIDMap",
+        W: b"W",
+        DW: b"DW",
+        LENGTH1: b"Length1",
+    }
+
+    /// Maximum number of mappings in a single `bfchar` section of a CMap.
+    const BF_CHAR_LIMIT: usize = 100;
+
+    /// Parses the given TrueType font data, whose glyphs are addressed with the given
+    /// [`Addressing`], allocating the ids of the objects describing it with the given
+    /// [`IdManager`].
+    pub(crate) fn parse(
+        data: Vec<u8>,
+        addressing: Addressing,
+        id_manager: &mut IdManager,
+    ) -> Result<Self, CompositeFontErr> {
+        Face::parse(&data, 0).map_err(CompositeFontErr::InvalidFont)?;
+
+        Ok(Self {
+            data: data.into(),
+            ids: DescendantIds::new(id_manager, addressing),
+            addressing,
+        })
+    }
+
+    /// Allocates new ids for the objects describing the font program, e.g. when the font is
+    /// copied into another document.
+    pub(crate) fn create_ids(&mut self, id_manager: &mut IdManager) {
+        self.ids = DescendantIds::new(id_manager, self.addressing);
+    }
+
+    /// Returns the bytes of the TrueType font program.
+    pub(crate) fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns how the codes of shown strings address the glyphs of the font program.
+    pub(crate) fn addressing(&self) -> Addressing {
+        self.addressing
+    }
+
+    /// Returns the parsed face of the font program.
+    fn face(&self) -> Face<'_> {
+        parse_face(&self.data)
+    }
+
+    /// Returns the [`FontMetrics`] measuring text with the widths of the glyphs of the font
+    /// program.
+    pub(crate) fn metrics(&self) -> FontMetrics {
+        FontMetrics::Composite(Arc::clone(&self.data))
+    }
+
+    /// Returns the [`Glyph`] shown for each of the given characters by its code, leaving out the
+    /// characters the font program has no glyph for, or that have no two-byte code.
+    pub(crate) fn glyphs(&self, chars: impl IntoIterator<Item = char>) -> BTreeMap<u16, Glyph> {
+        let face = self.face();
+
+        chars
+            .into_iter()
+            .filter_map(|ch| {
+                let cid = u16::try_from(u32::from(ch)).ok()?;
+                let glyph = face.glyph_index(ch)?;
+                let width = glyph_width(&face, glyph);
+
+                Some((cid, Glyph { id: glyph.0, width }))
+            })
+            .collect()
+    }
+
+    /// Returns `true` if the font program has a glyph for the given character.
+    pub(crate) fn covers(&self, ch: char) -> bool {
+        !self.glyphs([ch]).is_empty()
+    }
+
+    /// Writes the entries of the Type 0 font dictionary specific to composite fonts into the
+    /// provided implementor of [`Write`].
+    pub(crate) fn write_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            // /Encoding /Identity-H
+            Self::ENCODING.write(writer),
+            Self::IDENTITY_H.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            // /DescendantFonts [n 0 R]
+            Self::DESCENDANT_FONTS.write(writer),
+            writer.write(b"["),
+            self.ids.cid_font.write_ref(writer),
+            writer.write(b"]"),
+            writer.write(constants::NL_MARKER),
+
+            // /ToUnicode n 0 R
+            Self::TO_UNICODE.write(writer),
+            self.ids.to_unicode.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Returns the objects describing the font program with the given base font name, covering
+    /// the given characters shown with the font. Fonts addressing glyphs directly cover all glyphs
+    /// of the font program, and their text is extractable from the shown runs instead.
+    pub(crate) fn descendants(
+        &self,
+        base_font: &Identifier<Vec<u8>>,
+        chars: impl IntoIterator<Item = char>,
+    ) -> Result<Vec<DescendantObject>, Error> {
+        let glyphs = match self.addressing {
+            Addressing::Unicode => self.glyphs(chars),
+            Addressing::Glyph => BTreeMap::new(),
+        };
+
+        let mut cid_font = Vec::new();
+        self.write_cid_font(&mut cid_font, base_font, &glyphs)?;
+
+        let mut descriptor = Vec::new();
+        FontDescriptor::from_face(&self.face(), base_font.clone(), self.ids.font_file.clone())
+            .write(&mut descriptor)?;
+
+        let mut font_file = Vec::new();
+        Stream::with_bytes(&self.data[..]).write_with_dict(&mut font_file, |writer| {
+            Ok(pdfgen_macros::write_chain! {
+                Self::LENGTH1.write(writer),
+                crate::write_fmt!(&mut *writer, "{} ", self.data.len()),
+            })
+        })?;
+
+        let mut to_unicode = Vec::new();
+        Stream::with_bytes(Self::to_unicode_cmap(&glyphs)).write(&mut to_unicode)?;
+
+        let mut objects = vec![
+            (&self.ids.cid_font, cid_font),
+            (&self.ids.descriptor, descriptor),
+            (&self.ids.font_file, font_file),
+            (&self.ids.to_unicode, to_unicode),
+        ];
+
+        if let Some(id) = &self.ids.cid_to_gid_map {
+            let mut cid_to_gid_map = Vec::new();
+            Stream::with_bytes(Self::cid_to_gid_map(&glyphs)).write(&mut cid_to_gid_map)?;
+            objects.push((id, cid_to_gid_map));
+        }
+
+        Ok(objects
+            .into_iter()
+            .map(|(id, content)| DescendantObject {
+                id: id.clone(),
+                content,
+            })
+            .collect())
+    }
+
+    /// Writes the descendant `CIDFontType2` font dictionary with the widths of the given glyphs.
+    fn write_cid_font(
+        &self,
+        writer: &mut dyn Write,
+        base_font: &Identifier<Vec<u8>>,
+        glyphs: &BTreeMap<u16, Glyph>,
+    ) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::FONT.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::SUBTYPE.write(writer),
+            Self::CID_FONT_TYPE2.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::BASE_FONT.write(writer),
+            base_font.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::CID_SYSTEM_INFO.write(writer),
+            writer.write(b"<< /Registry (Adobe) /Ordering (Identity) /Supplement 0 >>"),
+            writer.write(constants::NL_MARKER),
+
+            Self::FONT_DESCRIPTOR.write(writer),
+            self.ids.descriptor.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::CID_TO_GID_MAP.write(writer),
+            {
+                match &self.ids.cid_to_gid_map {
+                    Some(id) => id.write_ref(writer),
+                    None => Self::IDENTITY.write(writer),
+                }
+            },
+            writer.write(constants::NL_MARKER),
+
+            // glyphs addressed directly are advanced by the run showing them
+            if self.addressing == Addressing::Unicode {
+                Self::W.write(writer),
+                Self::write_widths(writer, glyphs),
+            },
+            if self.addressing == Addressing::Glyph {
+                Self::DW.write(writer),
+                writer.write(b"0"),
+            },
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    /// Writes the widths of the given glyphs as a `W` array, where each run of consecutive codes is
+    /// written as its first code followed by an array of the widths, e.g. `[65 [667 667] 97 [556]]`.
+    fn write_widths(writer: &mut dyn Write, glyphs: &BTreeMap<u16, Glyph>) -> Result<usize, Error> {
+        let mut runs: Vec<(u16, Vec<u16>)> = Vec::new();
+        for (cid, glyph) in glyphs {
+            match runs.last_mut() {
+                Some((first, widths))
+                    if usize::from(*first) + widths.len() == usize::from(*cid) =>
+                {
+                    widths.push(glyph.width)
+                }
+                _ => runs.push((*cid, vec![glyph.width])),
+            }
+        }
+
+        let runs: Vec<String> = runs
+            .iter()
+            .map(|(first, widths)| {
+                let widths: Vec<String> = widths.iter().map(u16::to_string).collect();
+                format!("{first} [{}]", widths.join(" "))
+            })
+            .collect();
+
+        crate::write_fmt!(writer, "[{}]", runs.join(" "))
+    }
+
+    /// Returns the `ToUnicode` CMap mapping the codes of the given glyphs back to their Unicode
+    /// code points.
+    fn to_unicode_cmap(glyphs: &BTreeMap<u16, Glyph>) -> Vec<u8> {
+        let mut cmap = String::from(
+            "/CIDInit /ProcSet findresource begin\n\
+             12 dict begin\n\
+             begincmap\n\
+             /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def\n\
+             /CMapName /Adobe-Identity-UCS def\n\
+             /CMapType 2 def\n\
+             1 begincodespacerange\n\
+             <0000> <FFFF>\n\
+             endcodespacerange\n",
+        );
+
+        let cids: Vec<u16> = glyphs.keys().copied().collect();
+        for chunk in cids.chunks(Self::BF_CHAR_LIMIT) {
+            cmap.push_str(&format!("{} beginbfchar\n", chunk.len()));
+            for cid in chunk {
+                cmap.push_str(&format!("<{cid:04X}> <{cid:04X}>\n"));
+            }
+            cmap.push_str("endbfchar\n");
+        }
+
+        cmap.push_str(
+            "endcmap\n\
+             CMapName currentdict /CMap defineresource pop\n\
+             end\n\
+             end",
+        );
+
+        cmap.into_bytes()
+    }
+
+    /// Returns the `CIDToGIDMap` stream content, holding the two-byte glyph index of each code up
+    /// to the highest code of the given glyphs, where codes without a glyph map to glyph 0.
+    fn cid_to_gid_map(glyphs: &BTreeMap<u16, Glyph>) -> Vec<u8> {
+        let Some(&max_cid) = glyphs.keys().last() else {
+            return Vec::new();
+        };
+
+        (0..=max_cid)
+            .flat_map(|cid| glyphs.get(&cid).map_or(0, |glyph| glyph.id).to_be_bytes())
+            .collect()
+    }
+}
+
+/// An object describing the font program of a composite font, such as its descendant font or
+/// font descriptor, with its content already encoded.
+#[derive(Debug)]
+pub(crate) struct DescendantObject {
+    /// ID of this object.
+    id: ObjId,
+
+    /// Encoded content of this object.
+    content: Vec<u8>,
+}
+
+impl DescendantObject {
+    /// Returns the [`ObjId`] of this object.
+    pub(crate) fn obj_ref(&self) -> &ObjId {
+        &self.id
+    }
+}
+
+impl Object for DescendantObject {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(&self.content),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+/// Parses the face of the given TrueType font program, which was validated when the font was
+/// created.
+fn parse_face(data: &[u8]) -> Face<'_> {
+    Face::parse(data, 0).expect("Font data is validated when the font is created.")
+}
+
+/// Returns the advance width of the given glyph of the given face in thousandths of the font size.
+fn glyph_width(face: &Face, glyph: GlyphId) -> u16 {
+    let advance = face.glyph_hor_advance(glyph).unwrap_or_default();
+    (f32::from(advance) * 1000.0 / f32::from(face.units_per_em())).round() as u16
+}
+
+/// Returns the [`VerticalMetrics`] of the given TrueType font program, in thousandths of the font
+/// size. Programs without underline metrics or x-height use the ones of Helvetica.
+pub(crate) fn vertical_metrics(data: &[u8]) -> VerticalMetrics {
+    let face = parse_face(data);
+    let scale = 1000.0 / f32::from(face.units_per_em());
+    let helvetica = Metrics::default().vertical();
+
+    // the underline position of TrueType fonts is the top of the underline, not its center
+    let (underline_position, underline_thickness) = match face.underline_metrics() {
+        Some(metrics) => {
+            let thickness = f32::from(metrics.thickness) * scale;
+            (
+                f32::from(metrics.position) * scale - thickness / 2.0,
+                thickness,
+            )
+        }
+        None => (helvetica.underline_position, helvetica.underline_thickness),
+    };
+
+    VerticalMetrics {
+        ascent: f32::from(face.ascender()) * scale,
+        descent: f32::from(face.descender()) * scale,
+        underline_position,
+        underline_thickness,
+        x_height: face
+            .x_height()
+            .map_or(helvetica.x_height, |x_height| f32::from(x_height) * scale),
+    }
+}
+
+/// Returns the width of the given text drawn with the given TrueType font program, in thousandths
+/// of the font size. Characters the font program has no glyph for, that have no two-byte code, or
+/// that are format characters, take no space.
+pub(crate) fn text_width(data: &[u8], text: &str) -> f32 {
+    let face = parse_face(data);
+    text.chars()
+        .filter(|ch| !is_format_character(*ch))
+        .filter(|ch| u16::try_from(u32::from(*ch)).is_ok())
+        .filter_map(|ch| face.glyph_index(ch))
+        .map(|glyph| f32::from(glyph_width(&face, glyph)))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use super::{Addressing, CompositeFont, CompositeFontErr, DescendantIds, Glyph};
+    use crate::{IdManager, types::hierarchy::primitives::identifier::Identifier};
+
+    fn glyphs() -> BTreeMap<u16, Glyph> {
+        BTreeMap::from([
+            (0x41, Glyph { id: 3, width: 667 }),
+            (0x42, Glyph { id: 4, width: 650 }),
+            (0x61, Glyph { id: 5, width: 556 }),
+            (0x416, Glyph { id: 9, width: 900 }),
+        ])
+    }
+
+    #[test]
+    fn invalid_font() {
+        assert!(matches!(
+            CompositeFont::parse(
+                b"not a font".to_vec(),
+                Addressing::Unicode,
+                &mut IdManager::new()
+            ),
+            Err(CompositeFontErr::InvalidFont(_))
+        ));
+    }
+
+    #[test]
+    fn widths() {
+        let mut writer = Vec::new();
+        CompositeFont::write_widths(&mut writer, &glyphs()).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @"[65 [667 650] 97 [556] 1046 [900]]");
+    }
+
+    #[test]
+    fn to_unicode_cmap() {
+        let output = String::from_utf8(CompositeFont::to_unicode_cmap(&glyphs())).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        /CIDInit /ProcSet findresource begin
+        12 dict begin
+        begincmap
+        /CIDSystemInfo << /Registry (Adobe) /Ordering (UCS) /Supplement 0 >> def
+        /CMapName /Adobe-Identity-UCS def
+        /CMapType 2 def
+        1 begincodespacerange
+        <0000> <FFFF>
+        endcodespacerange
+        4 beginbfchar
+        <0041> <0041>
+        <0042> <0042>
+        <0061> <0061>
+        <0416> <0416>
+        endbfchar
+        endcmap
+        CMapName currentdict /CMap defineresource pop
+        end
+        end
+        ");
+    }
+
+    #[test]
+    fn cid_to_gid_map() {
+        let glyphs = BTreeMap::from([
+            (1, Glyph { id: 3, width: 500 }),
+            (
+                3,
+                Glyph {
+                    id: 0x102,
+                    width: 500,
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            CompositeFont::cid_to_gid_map(&glyphs),
+            [0, 0, 0, 3, 0, 0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn glyph_addressed_cid_font() {
+        let font = CompositeFont {
+            data: Arc::from([]),
+            ids: DescendantIds::new(&mut IdManager::new(), Addressing::Glyph),
+            addressing: Addressing::Glyph,
+        };
+
+        let mut writer = Vec::new();
+        font.write_cid_font(
+            &mut writer,
+            &Identifier::new(b"NotoSansArabic".to_vec()),
+            &BTreeMap::new(),
+        )
+        .unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Type /Font 
+        /Subtype /CIDFontType2 
+        /BaseFont /NotoSansArabic 
+        /CIDSystemInfo << /Registry (Adobe) /Ordering (Identity) /Supplement 0 >>
+        /FontDescriptor 2 0 R
+        /CIDToGIDMap /Identity 
+        /DW 0
+        >>
+        ");
+    }
+}
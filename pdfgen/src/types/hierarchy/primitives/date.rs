@@ -0,0 +1,312 @@
+//! Implementation of PDF dates, written as strings of the form `D:YYYYMMDDHHmmSSOHH'mm'`.
+
+use std::{
+    fmt,
+    io::{Error, Write},
+};
+
+use super::string::PdfString;
+
+/// Possible errors that might be returned when creating a [`PdfDate`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum PdfDateErr {
+    /// Indicates that the year is negative or has more than four digits.
+    #[error("Year {0} is outside of the range 0 to 9999.")]
+    InvalidYear(i32),
+
+    /// Indicates that the month or the day doesn't exist.
+    #[error("Month {month} doesn't have a day {day}.")]
+    InvalidDay {
+        /// Month of the date, from 1 to 12.
+        month: u32,
+        /// Day of the month.
+        day: u32,
+    },
+
+    /// Indicates that the hour, minute or second is out of range.
+    #[error("Time {hour}:{minute}:{second} is not a valid time of day.")]
+    InvalidTime {
+        /// Hour of the time.
+        hour: u32,
+        /// Minute of the time.
+        minute: u32,
+        /// Second of the time.
+        second: u32,
+    },
+
+    /// Indicates that the offset from UTC is a day or more.
+    #[error("Offset of {0} minutes from UTC is a day or more.")]
+    InvalidOffset(i32),
+}
+
+/// A date and time, written as a string of the form `D:YYYYMMDDHHmmSSOHH'mm'` as required by the
+/// document information dictionary, annotations and signatures (7.9.4). `O` is the relationship
+/// of the local time to UTC, written as `+` or `-` followed by the offset in hours and minutes,
+/// or `Z` if the local time is UTC.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::primitives::date::PdfDate;
+/// let date = PdfDate::new(1998, 12, 23)
+///     .and_then(|date| date.with_time(19, 52, 0))
+///     .and_then(|date| date.with_utc_offset(-8 * 60))
+///     .unwrap();
+///
+/// assert_eq!(date.to_string(), "D:19981223195200-08'00'");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PdfDate {
+    /// Year, from 0 to 9999.
+    year: u16,
+
+    /// Month, from 1 to 12.
+    month: u8,
+
+    /// Day of the month, from 1 to 31.
+    day: u8,
+
+    /// Hour, from 0 to 23.
+    hour: u8,
+
+    /// Minute, from 0 to 59.
+    minute: u8,
+
+    /// Second, from 0 to 59.
+    second: u8,
+
+    /// Offset of the local time from UTC, in minutes.
+    utc_offset: i16,
+}
+
+impl PdfDate {
+    /// Creates a new `PdfDate` at midnight UTC of the given day.
+    ///
+    /// Returns [`PdfDateErr`] if the year is outside of the range 0 to 9999, or if the date
+    /// doesn't exist, e.g. February 30.
+    pub fn new(year: i32, month: u32, day: u32) -> Result<Self, PdfDateErr> {
+        let year = u16::try_from(year)
+            .ok()
+            .filter(|year| *year <= 9999)
+            .ok_or(PdfDateErr::InvalidYear(year))?;
+
+        let is_leap_year = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days = match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year => 29,
+            2 => 28,
+            _ => 0,
+        };
+
+        if !(1..=days).contains(&day) {
+            return Err(PdfDateErr::InvalidDay { month, day });
+        }
+
+        Ok(Self {
+            year,
+            month: month as u8,
+            day: day as u8,
+            hour: 0,
+            minute: 0,
+            second: 0,
+            utc_offset: 0,
+        })
+    }
+
+    /// Sets the time of day of this `PdfDate`, in local time.
+    ///
+    /// Returns [`PdfDateErr`] if the hour, minute or second is out of range. Leap seconds are not
+    /// supported.
+    pub fn with_time(mut self, hour: u32, minute: u32, second: u32) -> Result<Self, PdfDateErr> {
+        if hour > 23 || minute > 59 || second > 59 {
+            return Err(PdfDateErr::InvalidTime {
+                hour,
+                minute,
+                second,
+            });
+        }
+
+        self.hour = hour as u8;
+        self.minute = minute as u8;
+        self.second = second as u8;
+        Ok(self)
+    }
+
+    /// Sets the offset of the local time of this `PdfDate` from UTC, in minutes. Positive offsets
+    /// are east of Greenwich, e.g. `120` for Central European Summer Time. Defaults to `0`, which
+    /// is UTC.
+    ///
+    /// Returns [`PdfDateErr`] if the offset is a day or more.
+    pub fn with_utc_offset(mut self, minutes: i32) -> Result<Self, PdfDateErr> {
+        self.utc_offset = i16::try_from(minutes)
+            .ok()
+            .filter(|minutes| minutes.unsigned_abs() < 24 * 60)
+            .ok_or(PdfDateErr::InvalidOffset(minutes))?;
+
+        Ok(self)
+    }
+
+    /// Creates a new `PdfDate` from the year, the other components of a date-time and its offset
+    /// from UTC in seconds. Seconds of the offset that don't make up a whole minute are dropped.
+    #[cfg(any(feature = "chrono", feature = "time"))]
+    fn from_components(
+        year: i32,
+        [month, day, hour, minute, second]: [u32; 5],
+        utc_offset: i32,
+    ) -> Result<Self, PdfDateErr> {
+        Self::new(year, month, day)?
+            .with_time(hour, minute, second)?
+            .with_utc_offset(utc_offset / 60)
+    }
+
+    /// Writes this `PdfDate` as a PDF string into the given implementor of [`Write`].
+    pub fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        PdfString::from(self.to_string()).write_content(writer)
+    }
+}
+
+impl fmt::Display for PdfDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "D:{:04}{:02}{:02}{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )?;
+
+        if self.utc_offset == 0 {
+            return write!(f, "Z");
+        }
+
+        let sign = if self.utc_offset < 0 { '-' } else { '+' };
+        let offset = self.utc_offset.unsigned_abs();
+        write!(f, "{sign}{:02}'{:02}'", offset / 60, offset % 60)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> TryFrom<chrono::DateTime<Tz>> for PdfDate {
+    type Error = PdfDateErr;
+
+    /// Converts a `chrono` date-time into a [`PdfDate`] in the same time zone.
+    fn try_from(date_time: chrono::DateTime<Tz>) -> Result<Self, Self::Error> {
+        use chrono::{Datelike, Offset, Timelike};
+
+        Self::from_components(
+            date_time.year(),
+            [
+                date_time.month(),
+                date_time.day(),
+                date_time.hour(),
+                date_time.minute(),
+                date_time.second(),
+            ],
+            date_time.offset().fix().local_minus_utc(),
+        )
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<time::OffsetDateTime> for PdfDate {
+    type Error = PdfDateErr;
+
+    /// Converts a `time` date-time into a [`PdfDate`] with the same offset from UTC.
+    fn try_from(date_time: time::OffsetDateTime) -> Result<Self, Self::Error> {
+        Self::from_components(
+            date_time.year(),
+            [
+                u8::from(date_time.month()),
+                date_time.day(),
+                date_time.hour(),
+                date_time.minute(),
+                date_time.second(),
+            ]
+            .map(u32::from),
+            date_time.offset().whole_seconds(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PdfDate, PdfDateErr};
+
+    #[test]
+    fn pdf_date() {
+        let date = PdfDate::new(2024, 2, 29).unwrap();
+        assert_eq!(date.to_string(), "D:20240229000000Z");
+
+        let date = date.with_time(9, 5, 30).unwrap();
+        assert_eq!(date.to_string(), "D:20240229090530Z");
+
+        let date = date.with_utc_offset(5 * 60 + 30).unwrap();
+        assert_eq!(date.to_string(), "D:20240229090530+05'30'");
+
+        let date = date.with_utc_offset(-45).unwrap();
+        assert_eq!(date.to_string(), "D:20240229090530-00'45'");
+
+        let mut writer = Vec::new();
+        date.write(&mut writer).unwrap();
+        assert_eq!(writer, b"(D:20240229090530-00'45')");
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono_date() {
+        use chrono::TimeZone;
+
+        let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let date_time = offset.with_ymd_and_hms(2024, 6, 1, 12, 30, 15).unwrap();
+
+        let date = PdfDate::try_from(date_time).unwrap();
+        assert_eq!(date.to_string(), "D:20240601123015+02'00'");
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time_date() {
+        let date_time = time::Date::from_calendar_date(2024, time::Month::June, 1)
+            .and_then(|date| date.with_hms(12, 30, 15))
+            .unwrap()
+            .assume_offset(time::UtcOffset::from_hms(-3, -30, 0).unwrap());
+
+        let date = PdfDate::try_from(date_time).unwrap();
+        assert_eq!(date.to_string(), "D:20240601123015-03'30'");
+    }
+
+    #[test]
+    fn invalid_pdf_date() {
+        assert_eq!(
+            PdfDate::new(10_000, 1, 1),
+            Err(PdfDateErr::InvalidYear(10_000))
+        );
+        assert_eq!(PdfDate::new(-1, 1, 1), Err(PdfDateErr::InvalidYear(-1)));
+        assert_eq!(
+            PdfDate::new(2023, 2, 29),
+            Err(PdfDateErr::InvalidDay { month: 2, day: 29 })
+        );
+        assert_eq!(
+            PdfDate::new(1900, 2, 29),
+            Err(PdfDateErr::InvalidDay { month: 2, day: 29 })
+        );
+        assert_eq!(
+            PdfDate::new(2023, 13, 1),
+            Err(PdfDateErr::InvalidDay { month: 13, day: 1 })
+        );
+        assert!(PdfDate::new(2000, 2, 29).is_ok());
+
+        let date = PdfDate::new(2024, 1, 1).unwrap();
+        assert_eq!(
+            date.with_time(24, 0, 0),
+            Err(PdfDateErr::InvalidTime {
+                hour: 24,
+                minute: 0,
+                second: 0
+            })
+        );
+        assert_eq!(
+            date.with_utc_offset(-24 * 60),
+            Err(PdfDateErr::InvalidOffset(-24 * 60))
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Implementation of font encodings, mapping the character codes of shown strings to the glyphs of
+//! a simple font.
+
+use std::{
+    collections::BTreeMap,
+    io::{Error, Write},
+};
+
+use pdfgen_macros::const_identifiers;
+
+use super::identifier::Identifier;
+
+/// Characters of the codes `0x80..=0x9F` in WinAnsiEncoding, where `None` marks an unused code. The
+/// codes `0x20..=0x7E` and `0xA0..=0xFF` map to the characters with the same code point.
+const WIN_ANSI_SPECIALS: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+/// Soft hyphen (U+00AD), marking a point inside of a word at which a line may be broken. A hyphen
+/// is only shown at the end of a line broken there.
+pub(crate) const SOFT_HYPHEN: char = '\u{AD}';
+
+/// Returns `true` if the given character only controls the layout of the text around it and is
+/// never shown, which applies to the soft hyphen, the zero-width non-joiner and the zero-width
+/// joiner. These characters take no space and are dropped from shown strings.
+pub(crate) fn is_format_character(ch: char) -> bool {
+    matches!(ch, SOFT_HYPHEN | '\u{200C}' | '\u{200D}')
+}
+
+/// Returns the code of the given character in WinAnsiEncoding (Annex D), if it has one.
+pub(crate) fn win_ansi_code(ch: char) -> Option<u8> {
+    match ch {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => Some(ch as u8),
+        _ => WIN_ANSI_SPECIALS
+            .iter()
+            .position(|special| *special == Some(ch))
+            .map(|idx| 0x80 + idx as u8),
+    }
+}
+
+/// Encodes the given text in WinAnsiEncoding, replacing characters without a code with `?` and
+/// dropping format characters.
+pub(crate) fn encode_win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter(|ch| !is_format_character(*ch))
+        .map(|ch| win_ansi_code(ch).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Encodes the given text with two-byte codes equal to the Unicode code points of its characters,
+/// replacing characters outside of the Basic Multilingual Plane with code 0 and dropping format
+/// characters.
+pub(crate) fn encode_identity(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter(|ch| !is_format_character(*ch))
+        .flat_map(|ch| u16::try_from(u32::from(ch)).unwrap_or(0).to_be_bytes())
+        .collect()
+}
+
+/// Encoding of the strings shown in content streams, which depends on the kind of their font.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum ShowEncoding {
+    /// Single-byte codes of WinAnsiEncoding, used with simple fonts.
+    #[default]
+    WinAnsi,
+
+    /// Two-byte codes equal to the Unicode code points of the characters, used with composite
+    /// fonts, which map the codes to glyphs of their embedded font program.
+    #[cfg_attr(not(feature = "composite-fonts"), allow(dead_code))]
+    Identity,
+}
+
+/// The encoding of a simple [`Font`], written as its `/Encoding` entry. Strings in content streams
+/// are always encoded in WinAnsiEncoding, so a `FontEncoding` maps these codes to the glyphs of the
+/// font. Without an encoding, the built-in encoding of a font is used, which only covers ASCII.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::Document;
+/// # use pdfgen::types::hierarchy::primitives::encoding::FontEncoding;
+/// let mut document = Document::default();
+/// let font_id = document.create_font("Type1".into(), "Helvetica".into());
+///
+/// // show accented Latin characters, and the euro sign in place of the currency sign
+/// let encoding = FontEncoding::win_ansi().with_difference(0xA4, "Euro");
+/// document.font_mut(&font_id).unwrap().set_encoding(encoding);
+/// ```
+///
+/// [`Font`]: super::font::Font
+#[derive(Debug, Clone, Default)]
+pub struct FontEncoding {
+    /// Glyph names of the codes that differ from WinAnsiEncoding.
+    differences: BTreeMap<u8, Identifier<Vec<u8>>>,
+}
+
+impl FontEncoding {
+    const_identifiers! {
+        ENCODING,
+        BASE_ENCODING,
+        WIN_ANSI_ENCODING: b"WinAnsiEncoding",
+        DIFFERENCES,
+    }
+
+    /// Creates a new `FontEncoding` that maps codes to glyphs as WinAnsiEncoding, covering the
+    /// accented characters of Western European languages.
+    pub fn win_ansi() -> Self {
+        Self::default()
+    }
+
+    /// Maps the given code to the glyph with the given name, e.g. `Euro`, instead of the glyph of
+    /// WinAnsiEncoding, so the character with this code in WinAnsiEncoding is shown with that
+    /// glyph. The font must contain a glyph with the name, otherwise nothing is shown.
+    pub fn with_difference(mut self, code: u8, glyph_name: impl Into<Vec<u8>>) -> Self {
+        self.differences
+            .insert(code, Identifier::new(glyph_name.into()));
+        self
+    }
+
+    /// Returns the codes that are mapped to other glyphs than in WinAnsiEncoding, together with
+    /// the names of their glyphs.
+    pub(crate) fn differences(&self) -> impl Iterator<Item = (u8, &[u8])> {
+        self.differences
+            .iter()
+            .map(|(code, glyph_name)| (*code, glyph_name.as_bytes()))
+    }
+
+    /// Encode and write this `FontEncoding` into the provided implementor of [`Write`], either as
+    /// the name `/WinAnsiEncoding`, or as an encoding dictionary if any code is remapped, e.g.
+    /// `<< /Type /Encoding /BaseEncoding /WinAnsiEncoding /Differences [128 /Euro /Lslash] >>`.
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        if self.differences.is_empty() {
+            return Self::WIN_ANSI_ENCODING.write(writer);
+        }
+
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::ENCODING.write(writer),
+            Self::BASE_ENCODING.write(writer),
+            Self::WIN_ANSI_ENCODING.write(writer),
+            Self::DIFFERENCES.write(writer),
+            writer.write(b"["),
+            self.write_differences(writer),
+            writer.write(b"] >>"),
+        })
+    }
+
+    /// Writes the remapped codes as the content of a `/Differences` array, where each run of
+    /// consecutive codes is written as its first code followed by the glyph names.
+    fn write_differences(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let mut written = 0;
+        let mut next_code = None;
+
+        for (code, glyph_name) in &self.differences {
+            if next_code != Some(*code) {
+                written += crate::write_fmt!(&mut *writer, "{code} ")?;
+            }
+            written += glyph_name.write(writer)?;
+            next_code = code.checked_add(1);
+        }
+
+        Ok(written)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FontEncoding, encode_identity, encode_win_ansi};
+
+    #[test]
+    fn win_ansi_text() {
+        assert_eq!(encode_win_ansi("Café – 5 €"), b"Caf\xE9 \x96 5 \x80");
+        assert_eq!(encode_win_ansi("Łódź"), b"?\xF3d?");
+        assert_eq!(encode_identity("Жa😀"), b"\x04\x16\x00a\x00\x00");
+    }
+
+    #[test]
+    fn format_characters() {
+        assert_eq!(encode_win_ansi("Donau\u{AD}dampf"), b"Donaudampf");
+        assert_eq!(encode_win_ansi("Auf\u{200C}lage\u{200D}"), b"Auflage");
+        assert_eq!(encode_identity("a\u{AD}b"), b"\x00a\x00b");
+    }
+
+    #[test]
+    fn font_encodings() {
+        let encodings = [
+            FontEncoding::win_ansi(),
+            FontEncoding::win_ansi()
+                .with_difference(0x81, "Lslash")
+                .with_difference(0x8D, "lslash")
+                .with_difference(0x80, "Euro")
+                .with_difference(0x8F, "Zacute"),
+        ];
+
+        let mut writer = Vec::new();
+        for encoding in encodings {
+            encoding.write(&mut writer).unwrap();
+            writer.push(b'\n');
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        /WinAnsiEncoding 
+        << /Type /Encoding /BaseEncoding /WinAnsiEncoding /Differences [128 /Euro /Lslash 141 /lslash 143 /Zacute ] >>
+        ");
+    }
+}
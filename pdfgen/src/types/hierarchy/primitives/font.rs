@@ -4,16 +4,48 @@ use std::io::{Error, Write};
 
 use pdfgen_macros::const_identifiers;
 
+#[cfg(feature = "composite-fonts")]
+use crate::IdManager;
 use crate::{ObjId, types::constants};
 
-use super::{identifier::Identifier, object::Object};
+#[cfg(feature = "composite-fonts")]
+use super::composite_font::{Addressing, CompositeFont, CompositeFontErr, DescendantObject};
+
+use super::{
+    encoding::{FontEncoding, is_format_character, win_ansi_code},
+    font_metrics::{FontMetrics, Metrics},
+    identifier::Identifier,
+    object::Object,
+    symbol::SymbolFont,
+    unit::Unit,
+};
+
+/// Extent of a text drawn with a [`Font`], as returned by [`Font::measure`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextExtent {
+    /// Width of the text.
+    pub width: Unit,
+
+    /// Height of the ascenders of the font above the baseline.
+    pub ascent: Unit,
+
+    /// Depth of the descenders of the font below the baseline, which is negative.
+    pub descent: Unit,
+}
+
+impl TextExtent {
+    /// Returns the height of a line of the text, from the descenders to the ascenders.
+    pub fn height(&self) -> Unit {
+        self.ascent - self.descent
+    }
+}
 
 /// Represents a font object in a PDF document.
 /// This struct represents a font object in a PDF document, encapsulating the info required to
 /// define and reference a font, including its unique ID, subtype, and base font type.
 /// Fonts are essential for rendering text in PDFs and specify the appearance and
 /// characteristics of text elements.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Font {
     /// ID of this [`Font`] object.
     pub(crate) id: ObjId<Self>,
@@ -23,6 +55,14 @@ pub struct Font {
 
     /// Represents the base font type, identifying the general font family or format.
     base_font: Identifier<Vec<u8>>,
+
+    /// Encoding mapping the codes of shown strings to glyphs, or `None` for the built-in encoding
+    /// of the font.
+    encoding: Option<FontEncoding>,
+
+    /// Embedded font program of a composite font, or `None` for a simple font.
+    #[cfg(feature = "composite-fonts")]
+    composite: Option<CompositeFont>,
 }
 
 impl Font {
@@ -30,8 +70,28 @@ impl Font {
         FONT,
         SUBTYPE,
         BASE_FONT,
+        ENCODING,
     }
 
+    /// Names of the 14 standard Type 1 fonts, which every PDF processor provides without the font
+    /// being embedded.
+    const STANDARD_FONTS: [&[u8]; 14] = [
+        b"Times-Roman",
+        b"Times-Bold",
+        b"Times-Italic",
+        b"Times-BoldItalic",
+        b"Helvetica",
+        b"Helvetica-Bold",
+        b"Helvetica-Oblique",
+        b"Helvetica-BoldOblique",
+        b"Courier",
+        b"Courier-Bold",
+        b"Courier-Oblique",
+        b"Courier-BoldOblique",
+        b"Symbol",
+        b"ZapfDingbats",
+    ];
+
     /// Create a new [`Font`] object with the provided id, subtype and base_font.
     pub fn new<S, B>(id: ObjId<Self>, subtype: S, base_font: B) -> Self
     where
@@ -45,7 +105,225 @@ impl Font {
             id,
             subtype,
             base_font,
+            encoding: None,
+            #[cfg(feature = "composite-fonts")]
+            composite: None,
+        }
+    }
+
+    /// Create a new composite (Type 0) [`Font`] with the provided id and base_font, embedding the
+    /// given TrueType font data, whose glyphs are addressed with the given [`Addressing`].
+    #[cfg(feature = "composite-fonts")]
+    pub(crate) fn composite<B>(
+        id: ObjId<Self>,
+        base_font: B,
+        data: Vec<u8>,
+        addressing: Addressing,
+        id_manager: &mut IdManager,
+    ) -> Result<Self, CompositeFontErr>
+    where
+        B: Into<Vec<u8>>,
+    {
+        let composite = CompositeFont::parse(data, addressing, id_manager)?;
+
+        Ok(Font {
+            composite: Some(composite),
+            ..Font::new(id, "Type0", base_font)
+        })
+    }
+
+    /// Sets the [`FontEncoding`] of this [`Font`], so that characters outside of ASCII, such as
+    /// accented Latin characters, are shown with the right glyphs.
+    pub fn set_encoding(&mut self, encoding: FontEncoding) {
+        self.encoding = Some(encoding);
+    }
+
+    /// Returns the [`TextExtent`] of the given text drawn with this [`Font`] in the given font
+    /// size: its width, and the ascent and descent of the font. Text is measured with the metrics
+    /// of the standard font, and with the metrics of Helvetica for any other font. Composite fonts
+    /// are measured with the metrics of their embedded font program.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::Document;
+    /// let mut document = Document::default();
+    /// let font_id = document.create_font("Type1".into(), "Times-Roman".into());
+    ///
+    /// let font = document.font(&font_id).unwrap();
+    /// let extent = font.measure("Hello", 10);
+    /// assert_eq!(extent.width.into_user_unit(), 22.22);
+    /// assert_eq!(extent.height().into_user_unit(), 9.0);
+    /// ```
+    pub fn measure(&self, text: &str, size: u32) -> TextExtent {
+        let metrics = self.metrics();
+        let vertical = metrics.vertical();
+        let scale = |thousandths: f32| Unit::from_unit(thousandths * size as f32 / 1000.0);
+
+        TextExtent {
+            width: scale(metrics.text_width(text)),
+            ascent: scale(vertical.ascent),
+            descent: scale(vertical.descent),
+        }
+    }
+
+    /// Returns the [`FontMetrics`] measuring text drawn with this [`Font`], which are the metrics
+    /// of the standard font, of Helvetica for any other simple font, or of the embedded font
+    /// program of a composite font.
+    pub(crate) fn metrics(&self) -> FontMetrics {
+        #[cfg(feature = "composite-fonts")]
+        if let Some(composite) = &self.composite {
+            return composite.metrics();
+        }
+
+        FontMetrics::Simple(Metrics::of(self.base_font.as_bytes()).unwrap_or_default())
+    }
+
+    /// Returns `true` if this [`Font`] is a composite font.
+    pub(crate) fn is_composite(&self) -> bool {
+        #[cfg(feature = "composite-fonts")]
+        if self.composite.is_some() {
+            return true;
+        }
+
+        false
+    }
+
+    /// Returns the [`ObjId`] allocated to this [`Font`].
+    pub(crate) fn obj_ref(&self) -> &ObjId<Self> {
+        &self.id
+    }
+
+    /// Returns the name of the base font of this [`Font`].
+    pub(crate) fn base_font(&self) -> String {
+        String::from_utf8_lossy(self.base_font.as_bytes()).into_owned()
+    }
+
+    /// Returns the subtype of this [`Font`], e.g. `Type1`.
+    pub(crate) fn subtype(&self) -> &[u8] {
+        self.subtype.as_bytes()
+    }
+
+    /// Returns the [`FontEncoding`] of this [`Font`], or `None` if text is shown in the built-in
+    /// encoding of the font.
+    pub(crate) fn encoding(&self) -> Option<&FontEncoding> {
+        self.encoding.as_ref()
+    }
+
+    /// Returns the embedded font program of this [`Font`] together with how its glyphs are
+    /// addressed, or `None` for a simple font.
+    #[cfg(feature = "composite-fonts")]
+    pub(crate) fn program(&self) -> Option<(&[u8], Addressing)> {
+        self.composite
+            .as_ref()
+            .map(|composite| (composite.data(), composite.addressing()))
+    }
+
+    /// Encodes all characters of the names of this [`Font`] that are not allowed in a name with
+    /// their hexadecimal code, returning the original names that had to be encoded.
+    pub(crate) fn encode_names(&mut self) -> Vec<String> {
+        let mut encoded = Vec::new();
+
+        for name in [&mut self.subtype, &mut self.base_font] {
+            if let Some(encoded_name) = name.encoded() {
+                encoded.push(String::from_utf8_lossy(name.as_bytes()).into_owned());
+                *name = encoded_name;
+            }
+        }
+
+        encoded
+    }
+
+    /// Returns `true` if this [`Font`] has a glyph for the given character. Without a
+    /// [`FontEncoding`], text is shown in the built-in encoding of the font, which only covers the
+    /// printable ASCII characters, so any other character is shown as the `.notdef` glyph. The
+    /// built-in encodings of Symbol and ZapfDingbats cover the characters whose WinAnsiEncoding code
+    /// selects one of their glyphs. With an encoding, all characters of WinAnsiEncoding are covered.
+    /// Composite fonts cover the characters their embedded font program has glyphs for.
+    pub(crate) fn covers(&self, ch: char) -> bool {
+        #[cfg(feature = "composite-fonts")]
+        if let Some(composite) = &self.composite {
+            return composite.covers(ch);
+        }
+
+        match &self.encoding {
+            Some(_) => win_ansi_code(ch).is_some(),
+            None => match SymbolFont::of(self.base_font.as_bytes()) {
+                Some(font) => win_ansi_code(ch).is_some_and(|code| font.glyph_name(code).is_some()),
+                None => matches!(ch, ' '..='~'),
+            },
+        }
+    }
+
+    /// Returns the characters of the given text this [`Font`] has no glyphs for, in order of their
+    /// first occurrence. Line breaks and format characters are ignored, as they are consumed by the
+    /// layout of texts and never shown.
+    pub(crate) fn unencodable(&self, text: &str) -> Vec<char> {
+        let mut missing = Vec::new();
+
+        let shown = text
+            .chars()
+            .filter(|ch| *ch != '\n' && !is_format_character(*ch));
+        for ch in shown {
+            if !self.covers(ch) && !missing.contains(&ch) {
+                missing.push(ch);
+            }
+        }
+
+        missing
+    }
+
+    /// Returns `true` if this [`Font`] is one of the 14 standard Type 1 fonts.
+    pub(crate) fn is_standard(&self) -> bool {
+        self.subtype.as_bytes() == b"Type1"
+            && Self::STANDARD_FONTS.contains(&self.base_font.as_bytes())
+    }
+
+    /// Returns `true` if the font program of this [`Font`] is embedded into the document.
+    pub(crate) fn is_embedded(&self) -> bool {
+        #[cfg(feature = "composite-fonts")]
+        if self.composite.is_some() {
+            return true;
         }
+
+        false
+    }
+
+    /// Allocates new ids for the objects describing the embedded font program of this [`Font`],
+    /// e.g. when the font is copied into another document.
+    #[cfg(feature = "composite-fonts")]
+    pub(crate) fn create_descendant_ids(&mut self, id_manager: &mut IdManager) {
+        if let Some(composite) = &mut self.composite {
+            composite.create_ids(id_manager);
+        }
+    }
+
+    /// Returns the objects describing the embedded font program of this [`Font`], covering the
+    /// given characters shown with it. Simple fonts have no such objects.
+    #[cfg(feature = "composite-fonts")]
+    pub(crate) fn descendants(
+        &self,
+        chars: impl IntoIterator<Item = char>,
+    ) -> Result<Vec<DescendantObject>, Error> {
+        match &self.composite {
+            Some(composite) => composite.descendants(&self.base_font, chars),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Writes the entries specific to composite fonts, if this [`Font`] is one.
+    #[cfg(feature = "composite-fonts")]
+    fn write_composite_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match &self.composite {
+            Some(composite) => composite.write_entries(writer),
+            None => Ok(0),
+        }
+    }
+
+    /// Writes the entries specific to composite fonts, which simple fonts don't have.
+    #[cfg(not(feature = "composite-fonts"))]
+    fn write_composite_entries(&self, _writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(0)
     }
 }
 
@@ -76,6 +354,16 @@ impl Object for Font {
             self.base_font.write(writer),
             writer.write(constants::NL_MARKER),
 
+            // /Encoding /Identity-H /DescendantFonts [n 0 R] /ToUnicode n 0 R
+            self.write_composite_entries(writer),
+
+            // /Encoding /WinAnsiEncoding
+            if let Some(encoding) = &self.encoding {
+                Self::ENCODING.write(writer),
+                encoding.write(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
             writer.write(b">>"),
             writer.write(constants::NL_MARKER),
         };
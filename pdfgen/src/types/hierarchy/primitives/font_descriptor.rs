@@ -0,0 +1,237 @@
+//! Implementation of the font descriptor, which describes the metrics of an embedded font program,
+//! and is required for every font that embeds one.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+use ttf_parser::Face;
+
+use crate::{ObjId, types::constants};
+
+use super::identifier::Identifier;
+
+/// Font descriptor of an embedded TrueType font program, with metrics in thousandths of the font
+/// size, populated from the parsed font data with [`FontDescriptor::from_face`].
+#[derive(Debug, Clone)]
+pub(crate) struct FontDescriptor {
+    /// PostScript name of the font, the same as the base font of the font dictionary.
+    font_name: Identifier<Vec<u8>>,
+
+    /// Characteristics of the font, as a combination of the `FontDescriptor` flag constants.
+    flags: u32,
+
+    /// Bounding box of all glyphs of the font, as `[x_min y_min x_max y_max]`.
+    font_b_box: [f32; 4],
+
+    /// Angle of the vertical strokes of the font in degrees counterclockwise from the vertical,
+    /// negative for fonts slanting to the right.
+    italic_angle: f32,
+
+    /// Maximum height above the baseline reached by glyphs of the font.
+    ascent: f32,
+
+    /// Maximum depth below the baseline reached by glyphs of the font, as a negative value.
+    descent: f32,
+
+    /// Height of flat capital letters above the baseline.
+    cap_height: f32,
+
+    /// Thickness of the dominant vertical stems of the glyphs.
+    stem_v: f32,
+
+    /// ID of the stream holding the font program.
+    font_file: ObjId,
+}
+
+impl FontDescriptor {
+    const_identifiers! {
+        FONT_DESCRIPTOR,
+        FONT_NAME,
+        FLAGS,
+        FONT_B_BOX: b"FontBBox",
+        ITALIC_ANGLE,
+        ASCENT,
+        DESCENT,
+        CAP_HEIGHT,
+        STEM_V: b"StemV",
+        FONT_FILE2: b"FontFile2",
+    }
+
+    /// All glyphs have the same width.
+    const FIXED_PITCH: u32 = 1 << 0;
+
+    /// Glyphs have serifs.
+    const SERIF: u32 = 1 << 1;
+
+    /// Font uses the standard Latin character set.
+    const NONSYMBOLIC: u32 = 1 << 5;
+
+    /// Glyphs have dominant vertical strokes that are slanted.
+    const ITALIC: u32 = 1 << 6;
+
+    /// Bold glyphs are painted with extra pixels even at small sizes.
+    const FORCE_BOLD: u32 = 1 << 18;
+
+    /// Sans serif classes of the IBM font family classification in the OS/2 table of a font.
+    const SANS_SERIF_CLASS: u8 = 8;
+
+    /// Creates a new `FontDescriptor` of the given font program with the given name, populated
+    /// from its metrics, where the font program is embedded in the stream with the given id.
+    pub(crate) fn from_face(
+        face: &Face<'_>,
+        font_name: Identifier<Vec<u8>>,
+        font_file: ObjId,
+    ) -> Self {
+        let scale =
+            |value: i16| (f32::from(value) * 1000.0 / f32::from(face.units_per_em())).round();
+        let bbox = face.global_bounding_box();
+        let ascent = scale(face.ascender());
+
+        Self {
+            font_name,
+            flags: Self::flags(face),
+            font_b_box: [
+                scale(bbox.x_min),
+                scale(bbox.y_min),
+                scale(bbox.x_max),
+                scale(bbox.y_max),
+            ],
+            italic_angle: face.italic_angle(),
+            ascent,
+            descent: scale(face.descender()),
+            // fonts without a cap height use the ascent, as suggested for fonts without Latin
+            // glyphs
+            cap_height: face.capital_height().map_or(ascent, scale),
+            stem_v: Self::stem_v(face.weight().to_number()),
+            font_file,
+        }
+    }
+
+    /// Returns the flags describing the characteristics of the given font program.
+    fn flags(face: &Face<'_>) -> u32 {
+        // the family class is the high byte of `sFamilyClass` in the OS/2 table, where classes 1
+        // to 7 are serif styles
+        let family_class = face
+            .raw_face()
+            .table(ttf_parser::Tag::from_bytes(b"OS/2"))
+            .and_then(|os2| os2.get(30).copied());
+
+        [
+            (face.is_monospaced(), Self::FIXED_PITCH),
+            (
+                family_class.is_some_and(|class| (1..Self::SANS_SERIF_CLASS).contains(&class)),
+                Self::SERIF,
+            ),
+            (true, Self::NONSYMBOLIC),
+            (face.is_italic() || face.is_oblique(), Self::ITALIC),
+            (face.is_bold(), Self::FORCE_BOLD),
+        ]
+        .into_iter()
+        .filter(|(is_set, _)| *is_set)
+        .fold(0, |flags, (_, flag)| flags | flag)
+    }
+
+    /// Returns the estimated stem width of glyphs with the given weight class. The stem width
+    /// isn't stored in TrueType font programs, so it's approximated from the weight, which gives
+    /// 88 for regular and 166 for bold glyphs.
+    fn stem_v(weight: u16) -> f32 {
+        (50.0 + (f32::from(weight) / 65.0).powi(2)).round()
+    }
+
+    /// Encodes this `FontDescriptor` as a dictionary into the provided implementor of [`Write`].
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let [x_min, y_min, x_max, y_max] = self.font_b_box;
+
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::FONT_DESCRIPTOR.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::FONT_NAME.write(writer),
+            self.font_name.write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::FLAGS.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", self.flags),
+            writer.write(constants::NL_MARKER),
+
+            Self::FONT_B_BOX.write(writer),
+            crate::write_fmt!(&mut *writer, "[{x_min} {y_min} {x_max} {y_max}]"),
+            writer.write(constants::NL_MARKER),
+
+            Self::ITALIC_ANGLE.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", self.italic_angle),
+            writer.write(constants::NL_MARKER),
+
+            Self::ASCENT.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", self.ascent),
+            writer.write(constants::NL_MARKER),
+
+            Self::DESCENT.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", self.descent),
+            writer.write(constants::NL_MARKER),
+
+            Self::CAP_HEIGHT.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", self.cap_height),
+            writer.write(constants::NL_MARKER),
+
+            Self::STEM_V.write(writer),
+            crate::write_fmt!(&mut *writer, "{}", self.stem_v),
+            writer.write(constants::NL_MARKER),
+
+            Self::FONT_FILE2.write(writer),
+            self.font_file.write_ref(writer),
+            writer.write(constants::NL_MARKER),
+
+            writer.write(b">>"),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{IdManager, types::hierarchy::primitives::identifier::Identifier};
+
+    use super::FontDescriptor;
+
+    #[test]
+    fn stem_v() {
+        assert_eq!(FontDescriptor::stem_v(400), 88.0);
+        assert_eq!(FontDescriptor::stem_v(700), 166.0);
+    }
+
+    #[test]
+    fn write() {
+        let descriptor = FontDescriptor {
+            font_name: Identifier::new(b"NotoSans-Italic".to_vec()),
+            flags: FontDescriptor::NONSYMBOLIC | FontDescriptor::ITALIC,
+            font_b_box: [-621.0, -389.0, 2800.0, 1067.0],
+            italic_angle: -12.0,
+            ascent: 1069.0,
+            descent: -293.0,
+            cap_height: 714.0,
+            stem_v: 88.0,
+            font_file: IdManager::new().create_id(),
+        };
+
+        let mut writer = Vec::new();
+        descriptor.write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Type /FontDescriptor 
+        /FontName /NotoSans-Italic 
+        /Flags 96
+        /FontBBox [-621 -389 2800 1067]
+        /ItalicAngle -12
+        /Ascent 1069
+        /Descent -293
+        /CapHeight 714
+        /StemV 88
+        /FontFile2 1 0 R
+        >>
+        ");
+    }
+}
@@ -0,0 +1,479 @@
+//! Metrics of the 14 standard Type 1 fonts, as defined by their AFM (Adobe Font Metrics) files,
+//! and of the fonts texts are laid out with.
+
+#[cfg(feature = "composite-fonts")]
+use std::sync::Arc;
+
+use super::encoding::{is_format_character, win_ansi_code};
+
+/// Widths of the printable ASCII characters, starting with the space, in thousandths of the font
+/// size.
+type Widths = [u16; 95];
+
+/// Widths of the codes `0x80..=0xFF`, in thousandths of the font size. Codes without a glyph have
+/// no width.
+type HighWidths = [u16; 128];
+
+/// Widths of Helvetica and Helvetica-Oblique.
+#[rustfmt::skip]
+const HELVETICA: Widths = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556,
+    1015, 667, 667, 722, 722, 667, 611, 778, 722, 278, 500, 667, 556, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 278, 278, 278, 469, 556,
+    333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500, 222, 833, 556, 556,
+    556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+/// Widths of Helvetica-Bold and Helvetica-BoldOblique.
+#[rustfmt::skip]
+const HELVETICA_BOLD: Widths = [
+    278, 333, 474, 556, 556, 889, 722, 238, 333, 333, 389, 584, 278, 333, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 556, 556, 556, 333, 333, 584, 584, 584, 611,
+    975, 722, 722, 722, 722, 667, 611, 778, 722, 278, 556, 722, 611, 833, 722, 778,
+    667, 778, 722, 667, 611, 722, 667, 944, 667, 667, 611, 333, 278, 333, 584, 556,
+    333, 556, 611, 556, 611, 556, 333, 611, 611, 278, 278, 556, 278, 889, 611, 611,
+    611, 611, 389, 556, 333, 611, 556, 778, 556, 556, 500, 389, 280, 389, 584,
+];
+
+/// Widths of Times-Roman.
+#[rustfmt::skip]
+const TIMES_ROMAN: Widths = [
+    250, 333, 408, 500, 500, 833, 778, 180, 333, 333, 500, 564, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 564, 564, 564, 444,
+    921, 722, 667, 667, 722, 611, 556, 722, 722, 333, 389, 722, 611, 889, 722, 722,
+    556, 722, 667, 556, 611, 722, 722, 944, 722, 722, 611, 333, 278, 333, 469, 500,
+    333, 444, 500, 444, 500, 444, 333, 500, 500, 278, 278, 500, 278, 778, 500, 500,
+    500, 500, 333, 389, 278, 500, 500, 722, 500, 500, 444, 480, 200, 480, 541,
+];
+
+/// Widths of Times-Bold.
+#[rustfmt::skip]
+const TIMES_BOLD: Widths = [
+    250, 333, 555, 500, 500, 1000, 833, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    930, 722, 667, 722, 722, 667, 611, 778, 778, 389, 500, 778, 667, 944, 722, 778,
+    611, 778, 722, 556, 667, 722, 722, 1000, 722, 722, 667, 333, 278, 333, 581, 500,
+    333, 500, 556, 444, 556, 444, 333, 500, 556, 278, 333, 556, 278, 833, 556, 500,
+    556, 556, 444, 389, 333, 556, 500, 722, 500, 500, 444, 394, 220, 394, 520,
+];
+
+/// Widths of Times-Italic.
+#[rustfmt::skip]
+const TIMES_ITALIC: Widths = [
+    250, 333, 420, 500, 500, 833, 778, 214, 333, 333, 500, 675, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 675, 675, 675, 500,
+    920, 611, 611, 667, 722, 611, 611, 722, 722, 333, 444, 667, 556, 833, 667, 722,
+    611, 722, 611, 500, 556, 722, 611, 833, 611, 556, 556, 389, 278, 389, 422, 500,
+    333, 500, 500, 444, 500, 444, 278, 500, 500, 278, 278, 444, 278, 722, 500, 500,
+    500, 500, 389, 389, 278, 500, 444, 667, 444, 444, 389, 400, 275, 400, 541,
+];
+
+/// Widths of Times-BoldItalic.
+#[rustfmt::skip]
+const TIMES_BOLD_ITALIC: Widths = [
+    250, 389, 555, 500, 500, 833, 778, 278, 333, 333, 500, 570, 250, 333, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 333, 333, 570, 570, 570, 500,
+    832, 667, 667, 667, 722, 667, 667, 722, 778, 389, 500, 667, 611, 889, 722, 722,
+    611, 722, 667, 556, 611, 722, 667, 889, 667, 611, 611, 333, 278, 333, 570, 500,
+    333, 500, 500, 444, 500, 444, 333, 500, 556, 278, 278, 500, 278, 778, 556, 500,
+    500, 500, 389, 389, 278, 556, 444, 667, 500, 444, 389, 348, 220, 348, 570,
+];
+
+/// Widths of Symbol, in its built-in encoding.
+#[rustfmt::skip]
+const SYMBOL: Widths = [
+    250, 333, 713, 500, 549, 833, 778, 439, 333, 333, 500, 549, 250, 549, 250, 278,
+    500, 500, 500, 500, 500, 500, 500, 500, 500, 500, 278, 278, 549, 549, 549, 444,
+    549, 722, 667, 722, 612, 611, 763, 603, 722, 333, 631, 722, 686, 889, 722, 722,
+    768, 741, 556, 592, 611, 690, 439, 768, 645, 795, 611, 333, 863, 333, 658, 500,
+    500, 631, 549, 549, 494, 439, 521, 411, 603, 329, 603, 549, 549, 576, 521, 549,
+    549, 521, 549, 603, 439, 576, 713, 686, 493, 686, 494, 480, 200, 480, 549,
+];
+
+/// Widths of ZapfDingbats, in its built-in encoding.
+#[rustfmt::skip]
+const ZAPF_DINGBATS: Widths = [
+    278, 974, 961, 974, 980, 719, 789, 790, 791, 690, 960, 939, 549, 855, 911, 933,
+    911, 945, 974, 755, 846, 762, 761, 571, 677, 763, 760, 759, 754, 494, 552, 537,
+    577, 692, 786, 788, 788, 790, 793, 794, 816, 823, 789, 841, 823, 833, 816, 831,
+    923, 744, 723, 749, 790, 792, 695, 776, 768, 792, 759, 707, 708, 682, 701, 826,
+    815, 789, 789, 707, 687, 696, 689, 786, 787, 713, 791, 785, 791, 873, 761, 762,
+    762, 759, 759, 892, 892, 788, 784, 438, 138, 277, 415, 392, 392, 668, 668,
+];
+
+/// Widths of the codes `0x80..=0xFF` of WinAnsiEncoding in Helvetica and Helvetica-Oblique.
+#[rustfmt::skip]
+const HELVETICA_HIGH: HighWidths = [
+    556, 0, 222, 556, 333, 1000, 556, 556, 333, 1000, 667, 333, 1000, 0, 611, 0,
+    0, 222, 222, 333, 333, 350, 556, 1000, 333, 1000, 500, 333, 944, 0, 500, 667,
+    278, 333, 556, 556, 556, 556, 260, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+    400, 584, 333, 333, 333, 556, 537, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+    667, 667, 667, 667, 667, 667, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+    722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+    556, 556, 556, 556, 556, 556, 889, 500, 556, 556, 556, 556, 278, 278, 278, 278,
+    556, 556, 556, 556, 556, 556, 556, 584, 611, 556, 556, 556, 556, 500, 556, 500,
+];
+
+/// Widths of the codes `0x80..=0xFF` of WinAnsiEncoding in Helvetica-Bold and Helvetica-BoldOblique.
+#[rustfmt::skip]
+const HELVETICA_BOLD_HIGH: HighWidths = [
+    556, 0, 278, 556, 500, 1000, 556, 556, 333, 1000, 667, 333, 1000, 0, 611, 0,
+    0, 278, 278, 500, 500, 350, 556, 1000, 333, 1000, 556, 333, 944, 0, 500, 667,
+    278, 333, 556, 556, 556, 556, 280, 556, 333, 737, 370, 556, 584, 333, 737, 333,
+    400, 584, 333, 333, 333, 611, 556, 278, 333, 333, 365, 556, 834, 834, 834, 611,
+    722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667, 278, 278, 278, 278,
+    722, 722, 778, 778, 778, 778, 778, 584, 778, 722, 722, 722, 722, 667, 667, 611,
+    556, 556, 556, 556, 556, 556, 889, 556, 556, 556, 556, 556, 278, 278, 278, 278,
+    611, 611, 611, 611, 611, 611, 611, 584, 611, 611, 611, 611, 611, 556, 611, 556,
+];
+
+/// Widths of the codes `0x80..=0xFF` of WinAnsiEncoding in Times-Roman.
+#[rustfmt::skip]
+const TIMES_ROMAN_HIGH: HighWidths = [
+    500, 0, 333, 500, 444, 1000, 500, 500, 333, 1000, 556, 333, 889, 0, 611, 0,
+    0, 333, 333, 444, 444, 350, 500, 1000, 333, 980, 389, 333, 722, 0, 444, 722,
+    250, 333, 500, 500, 500, 500, 200, 500, 333, 760, 276, 500, 564, 333, 760, 333,
+    400, 564, 300, 300, 333, 500, 453, 250, 333, 300, 310, 500, 750, 750, 750, 444,
+    722, 722, 722, 722, 722, 722, 889, 667, 611, 611, 611, 611, 333, 333, 333, 333,
+    722, 722, 722, 722, 722, 722, 722, 564, 722, 722, 722, 722, 722, 722, 556, 500,
+    444, 444, 444, 444, 444, 444, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+    500, 500, 500, 500, 500, 500, 500, 564, 500, 500, 500, 500, 500, 500, 500, 500,
+];
+
+/// Widths of the codes `0x80..=0xFF` of WinAnsiEncoding in Times-Bold.
+#[rustfmt::skip]
+const TIMES_BOLD_HIGH: HighWidths = [
+    500, 0, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 1000, 0, 667, 0,
+    0, 333, 333, 500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 0, 444, 722,
+    250, 333, 500, 500, 500, 500, 220, 500, 333, 747, 300, 500, 570, 333, 747, 333,
+    400, 570, 300, 300, 333, 556, 540, 250, 333, 300, 330, 500, 750, 750, 750, 500,
+    722, 722, 722, 722, 722, 722, 1000, 722, 667, 667, 667, 667, 389, 389, 389, 389,
+    722, 722, 778, 778, 778, 778, 778, 570, 778, 722, 722, 722, 722, 722, 611, 556,
+    500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+    500, 556, 500, 500, 500, 500, 500, 570, 500, 556, 556, 556, 556, 500, 556, 500,
+];
+
+/// Widths of the codes `0x80..=0xFF` of WinAnsiEncoding in Times-Italic.
+#[rustfmt::skip]
+const TIMES_ITALIC_HIGH: HighWidths = [
+    500, 0, 333, 500, 556, 889, 500, 500, 333, 1000, 500, 333, 944, 0, 556, 0,
+    0, 333, 333, 556, 556, 350, 500, 889, 333, 980, 389, 333, 667, 0, 389, 556,
+    250, 389, 500, 500, 500, 500, 275, 500, 333, 760, 276, 500, 675, 333, 760, 333,
+    400, 675, 300, 300, 333, 500, 523, 250, 333, 300, 310, 500, 750, 750, 750, 500,
+    611, 611, 611, 611, 611, 611, 889, 667, 611, 611, 611, 611, 333, 333, 333, 333,
+    722, 667, 722, 722, 722, 722, 722, 675, 722, 722, 722, 722, 722, 556, 611, 500,
+    500, 500, 500, 500, 500, 500, 667, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+    500, 500, 500, 500, 500, 500, 500, 675, 500, 500, 500, 500, 500, 444, 500, 444,
+];
+
+/// Widths of the codes `0x80..=0xFF` of WinAnsiEncoding in Times-BoldItalic.
+#[rustfmt::skip]
+const TIMES_BOLD_ITALIC_HIGH: HighWidths = [
+    500, 0, 333, 500, 500, 1000, 500, 500, 333, 1000, 556, 333, 944, 0, 611, 0,
+    0, 333, 333, 500, 500, 350, 500, 1000, 333, 1000, 389, 333, 722, 0, 389, 611,
+    250, 389, 500, 500, 500, 500, 220, 500, 333, 747, 266, 500, 606, 333, 747, 333,
+    400, 570, 300, 300, 333, 576, 500, 250, 333, 300, 300, 500, 750, 750, 750, 500,
+    667, 667, 667, 667, 667, 667, 944, 667, 667, 667, 667, 667, 389, 389, 389, 389,
+    722, 722, 722, 722, 722, 722, 722, 570, 722, 722, 722, 722, 722, 611, 611, 500,
+    500, 500, 500, 500, 500, 500, 722, 444, 444, 444, 444, 444, 278, 278, 278, 278,
+    500, 556, 500, 500, 500, 500, 500, 570, 500, 556, 556, 556, 556, 444, 500, 444,
+];
+
+/// Widths of the codes `0x80..=0xFF` in the built-in encoding of Symbol.
+#[rustfmt::skip]
+const SYMBOL_HIGH: HighWidths = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 620, 247, 549, 167, 713, 500, 753, 753, 753, 753, 1042, 987, 603, 987, 603,
+    400, 549, 411, 549, 549, 713, 494, 460, 549, 549, 549, 549, 1000, 603, 1000, 658,
+    823, 686, 795, 987, 768, 768, 823, 768, 768, 713, 713, 713, 713, 713, 713, 713,
+    768, 713, 790, 790, 890, 823, 549, 250, 713, 603, 603, 1042, 987, 603, 987, 603,
+    494, 329, 790, 790, 786, 713, 384, 384, 384, 384, 384, 384, 494, 494, 494, 494,
+    0, 329, 274, 686, 686, 686, 384, 384, 384, 384, 384, 384, 494, 494, 494, 0,
+];
+
+/// Widths of the codes `0x80..=0xFF` in the built-in encoding of ZapfDingbats.
+#[rustfmt::skip]
+const ZAPF_DINGBATS_HIGH: HighWidths = [
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 732, 544, 544, 910, 667, 760, 760, 776, 595, 694, 626, 788, 788, 788, 788,
+    788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788,
+    788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788, 788,
+    788, 788, 788, 788, 894, 838, 1016, 458, 748, 924, 748, 918, 927, 928, 928, 834,
+    873, 828, 924, 924, 917, 930, 931, 463, 883, 836, 836, 867, 867, 696, 696, 874,
+    0, 874, 760, 946, 771, 865, 771, 888, 967, 888, 831, 873, 927, 970, 918, 0,
+];
+
+/// Width of all glyphs of the monospaced Courier fonts.
+const COURIER: u16 = 600;
+
+/// Distance of the center of underlines from the baseline, shared by all standard fonts.
+const UNDERLINE_POSITION: f32 = -100.0;
+
+/// Thickness of underlines, shared by all standard fonts.
+const UNDERLINE_THICKNESS: f32 = 50.0;
+
+/// Vertical metrics of a font, in thousandths of the font size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct VerticalMetrics {
+    /// Height of the ascenders above the baseline.
+    pub(crate) ascent: f32,
+
+    /// Depth of the descenders below the baseline, which is negative.
+    pub(crate) descent: f32,
+
+    /// Distance of the center of underlines from the baseline, which is negative.
+    pub(crate) underline_position: f32,
+
+    /// Thickness of underlines.
+    pub(crate) underline_thickness: f32,
+
+    /// Height of the lowercase letters without ascenders, such as `x`.
+    pub(crate) x_height: f32,
+}
+
+/// Widths of the glyphs of a standard font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum GlyphWidths {
+    /// Every glyph has its own width, given for printable ASCII and for the codes `0x80..=0xFF`.
+    Proportional(&'static Widths, &'static HighWidths),
+
+    /// All glyphs have the same width.
+    Monospaced(u16),
+}
+
+/// Metrics of a standard font, used to measure text. All values are in thousandths of the font
+/// size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Metrics {
+    /// Widths of the glyphs of the font.
+    widths: GlyphWidths,
+
+    /// Height of the ascenders above the baseline.
+    ascent: i16,
+
+    /// Depth of the descenders below the baseline, which is negative.
+    descent: i16,
+
+    /// Height of the lowercase letters without ascenders.
+    x_height: i16,
+}
+
+impl Default for Metrics {
+    /// Metrics of Helvetica, the default font of most PDF processors.
+    fn default() -> Self {
+        Metrics {
+            widths: GlyphWidths::Proportional(&HELVETICA, &HELVETICA_HIGH),
+            ascent: 718,
+            descent: -207,
+            x_height: 523,
+        }
+    }
+}
+
+impl Metrics {
+    /// Returns the metrics of the standard font with the given name, or `None` for any other font.
+    /// Symbol and ZapfDingbats have no ascender, descender and x-height, so the extent of their
+    /// glyphs and the x-height of Helvetica are used instead.
+    pub(crate) fn of(base_font: &[u8]) -> Option<Self> {
+        use GlyphWidths::{Monospaced, Proportional};
+
+        let (widths, ascent, descent, x_height) = match base_font {
+            b"Helvetica" | b"Helvetica-Oblique" => {
+                (Proportional(&HELVETICA, &HELVETICA_HIGH), 718, -207, 523)
+            }
+            b"Helvetica-Bold" | b"Helvetica-BoldOblique" => (
+                Proportional(&HELVETICA_BOLD, &HELVETICA_BOLD_HIGH),
+                718,
+                -207,
+                532,
+            ),
+            b"Times-Roman" => (
+                Proportional(&TIMES_ROMAN, &TIMES_ROMAN_HIGH),
+                683,
+                -217,
+                450,
+            ),
+            b"Times-Bold" => (Proportional(&TIMES_BOLD, &TIMES_BOLD_HIGH), 683, -217, 461),
+            b"Times-Italic" => (
+                Proportional(&TIMES_ITALIC, &TIMES_ITALIC_HIGH),
+                683,
+                -217,
+                441,
+            ),
+            b"Times-BoldItalic" => (
+                Proportional(&TIMES_BOLD_ITALIC, &TIMES_BOLD_ITALIC_HIGH),
+                683,
+                -217,
+                462,
+            ),
+            b"Courier" | b"Courier-Oblique" => (Monospaced(COURIER), 629, -157, 426),
+            b"Courier-Bold" | b"Courier-BoldOblique" => (Monospaced(COURIER), 629, -157, 439),
+            b"Symbol" => (Proportional(&SYMBOL, &SYMBOL_HIGH), 1010, -293, 523),
+            b"ZapfDingbats" => (
+                Proportional(&ZAPF_DINGBATS, &ZAPF_DINGBATS_HIGH),
+                820,
+                -143,
+                523,
+            ),
+            _ => return None,
+        };
+
+        Some(Metrics {
+            widths,
+            ascent,
+            descent,
+            x_height,
+        })
+    }
+
+    /// Returns the width of the given text in thousandths of the font size.
+    pub(crate) fn text_width(self, text: &str) -> f32 {
+        text.chars().map(|ch| self.char_width(ch)).sum()
+    }
+
+    /// Returns the width of the given character in thousandths of the font size. Characters are
+    /// measured with the glyph of their WinAnsiEncoding code, format characters take no space, and
+    /// characters without a glyph are measured as the question mark they are shown with.
+    pub(crate) fn char_width(self, ch: char) -> f32 {
+        if is_format_character(ch) {
+            return 0.0;
+        }
+
+        let (low, high) = match self.widths {
+            GlyphWidths::Proportional(low, high) => (low, high),
+            GlyphWidths::Monospaced(width) => return width as f32,
+        };
+
+        let width = |code: u8| match code {
+            0x20..=0x7E => low[usize::from(code - 0x20)],
+            0x80..=0xFF => high[usize::from(code - 0x80)],
+            _ => 0,
+        };
+
+        match win_ansi_code(ch).map(width) {
+            Some(width) if width != 0 => width as f32,
+            _ => width(b'?') as f32,
+        }
+    }
+
+    /// Returns the [`VerticalMetrics`] of the font.
+    pub(crate) fn vertical(self) -> VerticalMetrics {
+        VerticalMetrics {
+            ascent: f32::from(self.ascent),
+            descent: f32::from(self.descent),
+            underline_position: UNDERLINE_POSITION,
+            underline_thickness: UNDERLINE_THICKNESS,
+            x_height: f32::from(self.x_height),
+        }
+    }
+}
+
+/// Metrics of the font a text is drawn with, used to lay out the text on a page before the font
+/// itself is written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum FontMetrics {
+    /// Metrics of a simple font, which are the metrics of Helvetica for fonts other than the
+    /// standard fonts.
+    Simple(Metrics),
+
+    /// The embedded TrueType font program of a composite font, measuring text with the widths of
+    /// its glyphs.
+    #[cfg(feature = "composite-fonts")]
+    Composite(Arc<[u8]>),
+}
+
+impl Default for FontMetrics {
+    /// Metrics of Helvetica, the default font of most PDF processors.
+    fn default() -> Self {
+        FontMetrics::Simple(Metrics::default())
+    }
+}
+
+impl FontMetrics {
+    /// Returns the width of the given text in thousandths of the font size.
+    pub(crate) fn text_width(&self, text: &str) -> f32 {
+        match self {
+            FontMetrics::Simple(metrics) => metrics.text_width(text),
+            #[cfg(feature = "composite-fonts")]
+            FontMetrics::Composite(data) => super::composite_font::text_width(data, text),
+        }
+    }
+
+    /// Returns the [`VerticalMetrics`] of the font, which are the metrics of Helvetica for simple
+    /// fonts other than the standard fonts.
+    pub(crate) fn vertical(&self) -> VerticalMetrics {
+        match self {
+            FontMetrics::Simple(metrics) => metrics.vertical(),
+            #[cfg(feature = "composite-fonts")]
+            FontMetrics::Composite(data) => super::composite_font::vertical_metrics(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metrics;
+
+    #[test]
+    fn standard_fonts() {
+        let width = |font: &[u8]| Metrics::of(font).unwrap().text_width("Hello");
+
+        assert_eq!(width(b"Helvetica"), 2278.0);
+        assert_eq!(width(b"Helvetica-BoldOblique"), 2445.0);
+        assert_eq!(width(b"Times-Roman"), 2222.0);
+        assert_eq!(width(b"Courier-Bold"), 3000.0);
+        assert_eq!(Metrics::of(b"Arial"), None);
+    }
+
+    #[test]
+    fn win_ansi_characters() {
+        let helvetica = Metrics::of(b"Helvetica").unwrap();
+        let times = Metrics::of(b"Times-Roman").unwrap();
+
+        assert_eq!(helvetica.text_width("é"), 556.0);
+        assert_eq!(helvetica.text_width("Ü"), 722.0);
+        assert_eq!(helvetica.text_width("“—€"), 1889.0);
+        assert_eq!(times.text_width("Ærø"), 1722.0);
+        assert_eq!(times.text_width("\u{A0}"), times.text_width(" "));
+
+        // Characters without a glyph are shown, and measured, as a question mark.
+        assert_eq!(times.text_width("Ж"), times.text_width("?"));
+        assert_eq!(Metrics::of(b"Symbol").unwrap().text_width("€"), 444.0);
+    }
+
+    #[test]
+    fn vertical_metrics() {
+        let times = Metrics::of(b"Times-Roman").unwrap().vertical();
+        assert_eq!(
+            (times.ascent, times.descent, times.x_height),
+            (683.0, -217.0, 450.0)
+        );
+
+        let courier = Metrics::of(b"Courier-BoldOblique").unwrap().vertical();
+        assert_eq!(
+            (courier.ascent, courier.descent, courier.x_height),
+            (629.0, -157.0, 439.0)
+        );
+        assert_eq!(
+            (courier.underline_position, courier.underline_thickness),
+            (-100.0, 50.0)
+        );
+    }
+
+    #[test]
+    fn format_characters() {
+        let metrics = Metrics::of(b"Times-Roman").unwrap();
+
+        assert_eq!(
+            metrics.text_width("Auf\u{200C}lage"),
+            metrics.text_width("Auflage")
+        );
+        assert_eq!(
+            metrics.text_width("Donau\u{AD}dampf\u{200D}"),
+            metrics.text_width("Donaudampf")
+        );
+    }
+}
@@ -0,0 +1,527 @@
+//! Implementation of PDF function objects, which map input values to output values, e.g. the
+//! colour components of a shading or the tint transform of a colour space.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::{
+    FunctionId, ObjId,
+    types::{constants, hierarchy::content::stream::Stream},
+};
+
+use super::{array::WriteArray, identifier::Identifier, object::Object};
+
+/// Operators allowed in the code of a [`PostScriptFunction`].
+const POSTSCRIPT_OPERATORS: &[&str] = &[
+    "abs", "add", "and", "atan", "bitshift", "ceiling", "copy", "cos", "cvi", "cvr", "div", "dup",
+    "eq", "exch", "exp", "false", "floor", "ge", "gt", "idiv", "if", "ifelse", "index", "le", "ln",
+    "log", "lt", "mod", "mul", "ne", "neg", "not", "or", "pop", "roll", "round", "sin", "sqrt",
+    "sub", "true", "truncate", "xor",
+];
+
+/// Possible errors that might be returned when creating a [`PdfFunction`].
+#[derive(Clone, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum FunctionErr {
+    /// Indicates that the code of a PostScript calculator function isn't enclosed in braces, or
+    /// that its braces are not balanced.
+    #[error("PostScript calculator code must be enclosed in balanced braces.")]
+    UnbalancedBraces,
+
+    /// Indicates that the code of a PostScript calculator function uses an operator that is not
+    /// allowed in PostScript calculator functions.
+    #[error("Operator '{0}' is not allowed in PostScript calculator functions.")]
+    UnknownOperator(String),
+}
+
+/// Exponential interpolation function (type 2), which maps a single input value `x` to the output
+/// values `C0 + x^N * (C1 - C0)`. Exponent 1 interpolates linearly between the values `C0` and
+/// `C1`.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::primitives::function::ExponentialFunction;
+/// // linear interpolation from red to blue
+/// let red_to_blue = ExponentialFunction::new([1.0, 0.0, 0.0], [0.0, 0.0, 1.0], 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExponentialFunction {
+    /// Interval of the input value, `[x0 x1]`.
+    domain: [f32; 2],
+
+    /// Intervals the output values are clipped to, `[min0 max0 min1 max1 ...]`.
+    range: Option<Vec<f32>>,
+
+    /// Output values for input value 0.
+    c0: Vec<f32>,
+
+    /// Output values for input value 1.
+    c1: Vec<f32>,
+
+    /// Interpolation exponent.
+    exponent: f32,
+}
+
+impl ExponentialFunction {
+    const_identifiers! {
+        C0: b"C0",
+        C1: b"C1",
+        N: b"N",
+    }
+
+    /// Creates a new `ExponentialFunction` interpolating between the given output values with the
+    /// given exponent, with domain `[0 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the output values are empty or differ in length.
+    pub fn new(c0: impl Into<Vec<f32>>, c1: impl Into<Vec<f32>>, exponent: f32) -> Self {
+        let (c0, c1) = (c0.into(), c1.into());
+        assert!(
+            !c0.is_empty() && c0.len() == c1.len(),
+            "Output values of an exponential function must have the same, non-zero length."
+        );
+
+        Self {
+            domain: [0., 1.],
+            range: None,
+            c0,
+            c1,
+            exponent,
+        }
+    }
+
+    /// Sets the interval of the input value of this function. Defaults to `[0 1]`.
+    pub fn with_domain(mut self, start: f32, end: f32) -> Self {
+        self.domain = [start, end];
+        self
+    }
+
+    /// Sets the intervals the output values of this function are clipped to, `[min0 max0 min1
+    /// max1 ...]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the range doesn't have two values for each output value.
+    pub fn with_range(mut self, range: impl Into<Vec<f32>>) -> Self {
+        let range = range.into();
+        assert_eq!(
+            range.len(),
+            2 * self.c0.len(),
+            "Range of a function must have two values for each output value."
+        );
+
+        self.range = Some(range);
+        self
+    }
+}
+
+/// Stitching function (type 3), which combines several single input functions into a single new
+/// function, by dividing its domain into subdomains at the given bounds. Each subdomain is mapped
+/// to the domain of its function by the encode array.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, types::hierarchy::primitives::function::{ExponentialFunction, StitchingFunction}};
+/// let mut document = Document::default();
+/// let red_to_green = document.create_function(ExponentialFunction::new([1., 0., 0.], [0., 1., 0.], 1.));
+/// let green_to_blue = document.create_function(ExponentialFunction::new([0., 1., 0.], [0., 0., 1.], 1.));
+///
+/// let rainbow = StitchingFunction::new(vec![red_to_green, green_to_blue], [0.5]);
+/// let rainbow = document.create_function(rainbow);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct StitchingFunction {
+    /// Interval of the input value, `[x0 x1]`.
+    domain: [f32; 2],
+
+    /// Intervals the output values are clipped to, `[min0 max0 min1 max1 ...]`.
+    range: Option<Vec<f32>>,
+
+    /// Functions of the subdomains.
+    functions: Vec<FunctionId>,
+
+    /// Bounds between the subdomains, in increasing order.
+    bounds: Vec<f32>,
+
+    /// Intervals each subdomain is mapped to, `[e0 e1 ...]`, two values for each function.
+    encode: Vec<f32>,
+}
+
+impl StitchingFunction {
+    const_identifiers! {
+        FUNCTIONS,
+        BOUNDS,
+        ENCODE,
+    }
+
+    /// Creates a new `StitchingFunction` of the given functions, with domain `[0 1]` divided into
+    /// subdomains at the given bounds. Each subdomain is mapped to the interval `[0 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no functions, if the number of bounds isn't one less than the number of
+    /// functions, or if the bounds are not in increasing order.
+    pub fn new(functions: Vec<FunctionId>, bounds: impl Into<Vec<f32>>) -> Self {
+        let bounds = bounds.into();
+        assert!(
+            !functions.is_empty() && bounds.len() + 1 == functions.len(),
+            "Stitching functions must have one bound less than functions."
+        );
+        assert!(
+            bounds.is_sorted(),
+            "Bounds of a stitching function must be in increasing order."
+        );
+
+        Self {
+            domain: [0., 1.],
+            range: None,
+            encode: [0., 1.].repeat(functions.len()),
+            functions,
+            bounds,
+        }
+    }
+
+    /// Sets the interval of the input value of this function. Defaults to `[0 1]`.
+    pub fn with_domain(mut self, start: f32, end: f32) -> Self {
+        self.domain = [start, end];
+        self
+    }
+
+    /// Sets the intervals the output values of this function are clipped to, `[min0 max0 min1
+    /// max1 ...]`.
+    pub fn with_range(mut self, range: impl Into<Vec<f32>>) -> Self {
+        self.range = Some(range.into());
+        self
+    }
+
+    /// Sets the intervals each subdomain is mapped to, before its function is called with the
+    /// mapped value. Defaults to `[0 1]` for each subdomain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the encode array doesn't have two values for each function.
+    pub fn with_encode(mut self, encode: impl Into<Vec<f32>>) -> Self {
+        let encode = encode.into();
+        assert_eq!(
+            encode.len(),
+            2 * self.functions.len(),
+            "Encode array of a stitching function must have two values for each function."
+        );
+
+        self.encode = encode;
+        self
+    }
+}
+
+/// PostScript calculator function (type 4), which computes its output values with code written
+/// in a small subset of the PostScript language. The code is enclosed in braces and may only use
+/// numbers, booleans and the arithmetic, relational, boolean, conditional and stack operators of
+/// PostScript.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::primitives::function::PostScriptFunction;
+/// // inverts a single value
+/// let invert = PostScriptFunction::new([0.0, 1.0], [0.0, 1.0], "{ 1 exch sub }").unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PostScriptFunction {
+    /// Intervals of the input values, `[min0 max0 min1 max1 ...]`.
+    domain: Vec<f32>,
+
+    /// Intervals the output values are clipped to, `[min0 max0 min1 max1 ...]`.
+    range: Vec<f32>,
+
+    /// The PostScript calculator code of the function.
+    code: Stream,
+}
+
+impl PostScriptFunction {
+    /// Creates a new `PostScriptFunction` with the given intervals of its input and output values,
+    /// computing its output values with the given code.
+    ///
+    /// Returns [`FunctionErr`] if the code isn't enclosed in balanced braces or uses an operator
+    /// that isn't allowed in PostScript calculator functions.
+    pub fn new(
+        domain: impl Into<Vec<f32>>,
+        range: impl Into<Vec<f32>>,
+        code: &str,
+    ) -> Result<Self, FunctionErr> {
+        Self::check_code(code)?;
+
+        Ok(Self {
+            domain: domain.into(),
+            range: range.into(),
+            code: Stream::with_bytes(code.trim()),
+        })
+    }
+
+    /// Checks that the given code is enclosed in balanced braces and only uses allowed operators.
+    fn check_code(code: &str) -> Result<(), FunctionErr> {
+        let code = code.replace('{', " { ").replace('}', " } ");
+        let mut tokens = code.split_whitespace().peekable();
+        let mut depth = 0usize;
+
+        while let Some(token) = tokens.next() {
+            match token {
+                "{" => depth += 1,
+                "}" => {
+                    depth = depth.checked_sub(1).ok_or(FunctionErr::UnbalancedBraces)?;
+                    // the outermost braces enclose the whole code
+                    if depth == 0 && tokens.peek().is_some() {
+                        return Err(FunctionErr::UnbalancedBraces);
+                    }
+                }
+                _ if depth == 0 => return Err(FunctionErr::UnbalancedBraces),
+                _ if token.parse::<f64>().is_ok() || POSTSCRIPT_OPERATORS.contains(&token) => {}
+                _ => return Err(FunctionErr::UnknownOperator(token.to_string())),
+            }
+        }
+
+        if depth == 0 {
+            Ok(())
+        } else {
+            Err(FunctionErr::UnbalancedBraces)
+        }
+    }
+}
+
+/// A function object, mapping input values to output values. Functions are created in a document
+/// with [`Document::create_function`], and referred to by other objects with their [`FunctionId`].
+///
+/// [`Document::create_function`]: crate::Document::create_function
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfFunction {
+    /// See [`ExponentialFunction`].
+    Exponential(ExponentialFunction),
+
+    /// See [`StitchingFunction`].
+    Stitching(StitchingFunction),
+
+    /// See [`PostScriptFunction`].
+    PostScript(PostScriptFunction),
+}
+
+impl PdfFunction {
+    const_identifiers! {
+        FUNCTION_TYPE,
+        DOMAIN,
+        RANGE,
+    }
+
+    /// Returns the number of the type of this function.
+    fn function_type(&self) -> u8 {
+        match self {
+            PdfFunction::Exponential(_) => 2,
+            PdfFunction::Stitching(_) => 3,
+            PdfFunction::PostScript(_) => 4,
+        }
+    }
+
+    /// Writes the entries common to all types of functions into the given implementor of
+    /// [`Write`].
+    fn write_common_entries(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        let (domain, range) = match self {
+            PdfFunction::Exponential(function) => (&function.domain[..], function.range.as_ref()),
+            PdfFunction::Stitching(function) => (&function.domain[..], function.range.as_ref()),
+            PdfFunction::PostScript(function) => (&function.domain[..], Some(&function.range)),
+        };
+
+        Ok(pdfgen_macros::write_chain! {
+            Self::FUNCTION_TYPE.write(writer),
+            crate::write_fmt!(&mut *writer, "{} ", self.function_type()),
+
+            Self::DOMAIN.write(writer),
+            domain.write_array(writer, None),
+            writer.write(b" "),
+
+            if let Some(range) = range {
+                Self::RANGE.write(writer),
+                range.write_array(writer, None),
+                writer.write(b" "),
+            },
+        })
+    }
+
+    /// Writes this function as a dictionary or stream into the given implementor of [`Write`].
+    fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        match self {
+            PdfFunction::Exponential(function) => Ok(pdfgen_macros::write_chain! {
+                writer.write(b"<< "),
+                self.write_common_entries(writer),
+
+                ExponentialFunction::C0.write(writer),
+                function.c0.write_array(writer, None),
+                writer.write(b" "),
+
+                ExponentialFunction::C1.write(writer),
+                function.c1.write_array(writer, None),
+                writer.write(b" "),
+
+                ExponentialFunction::N.write(writer),
+                crate::write_fmt!(&mut *writer, "{}", function.exponent),
+                writer.write(b" >>"),
+            }),
+            PdfFunction::Stitching(function) => {
+                let functions: Vec<ObjId> = function
+                    .functions
+                    .iter()
+                    .map(|function| function.clone().into_obj_id().cast())
+                    .collect();
+
+                Ok(pdfgen_macros::write_chain! {
+                    writer.write(b"<< "),
+                    self.write_common_entries(writer),
+
+                    StitchingFunction::FUNCTIONS.write(writer),
+                    functions.write_array(writer, None),
+                    writer.write(b" "),
+
+                    StitchingFunction::BOUNDS.write(writer),
+                    function.bounds.write_array(writer, None),
+                    writer.write(b" "),
+
+                    StitchingFunction::ENCODE.write(writer),
+                    function.encode.write_array(writer, None),
+                    writer.write(b" >>"),
+                })
+            }
+            PdfFunction::PostScript(function) => function
+                .code
+                .write_with_dict(writer, |writer| self.write_common_entries(writer)),
+        }
+    }
+}
+
+impl From<ExponentialFunction> for PdfFunction {
+    fn from(function: ExponentialFunction) -> Self {
+        Self::Exponential(function)
+    }
+}
+
+impl From<StitchingFunction> for PdfFunction {
+    fn from(function: StitchingFunction) -> Self {
+        Self::Stitching(function)
+    }
+}
+
+impl From<PostScriptFunction> for PdfFunction {
+    fn from(function: PostScriptFunction) -> Self {
+        Self::PostScript(function)
+    }
+}
+
+/// A [`PdfFunction`] written as an indirect object, so other objects can refer to it.
+#[derive(Debug, Clone)]
+pub(crate) struct FunctionObject {
+    /// ID of this `FunctionObject`.
+    pub(crate) id: ObjId<Self>,
+
+    /// The written function.
+    function: PdfFunction,
+}
+
+impl FunctionObject {
+    /// Creates a new `FunctionObject` with the given [`ObjId`] and function.
+    pub(crate) fn new(id: ObjId<Self>, function: PdfFunction) -> Self {
+        Self { id, function }
+    }
+}
+
+impl Object for FunctionObject {
+    fn write_def(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.id.write_def(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+
+    fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            self.function.write(writer),
+            writer.write(constants::NL_MARKER),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{FunctionId, IdManager, types::hierarchy::primitives::object::Object};
+
+    use super::{
+        ExponentialFunction, FunctionErr, FunctionObject, PdfFunction, PostScriptFunction,
+        StitchingFunction,
+    };
+
+    #[test]
+    fn functions() {
+        let mut id_manager = IdManager::new();
+        let red_to_green = FunctionId::new(id_manager.create_id());
+        let green_to_blue = FunctionId::new(id_manager.create_id());
+
+        let functions: [PdfFunction; 3] = [
+            ExponentialFunction::new([1., 0., 0.], [0., 1., 0.], 2.)
+                .with_domain(0., 10.)
+                .with_range([0., 1., 0., 1., 0., 1.])
+                .into(),
+            StitchingFunction::new(vec![red_to_green, green_to_blue], [0.25])
+                .with_encode([1., 0., 0., 1.])
+                .into(),
+            PostScriptFunction::new([0., 1., 0., 1.], [0., 1.], "{ add 2 div }")
+                .unwrap()
+                .into(),
+        ];
+
+        let mut writer = Vec::new();
+        for function in functions {
+            FunctionObject::new(id_manager.create_id(), function)
+                .write_content(&mut writer)
+                .unwrap();
+        }
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /FunctionType 2 /Domain [0 10] /Range [0 1 0 1 0 1] /C0 [1 0 0] /C1 [0 1 0] /N 2 >>
+        << /FunctionType 3 /Domain [0 1] /Functions [1 0 R
+         2 0 R] /Bounds [0.25] /Encode [1 0 0 1] >>
+        << /FunctionType 4 /Domain [0 1 0 1] /Range [0 1] /Length 13 >>
+        stream
+        { add 2 div }
+        endstream
+        ");
+    }
+
+    #[test]
+    fn invalid_postscript_code() {
+        let function = |code| PostScriptFunction::new([0., 1.], [0., 1.], code);
+
+        assert!(function("{ dup 0.5 gt { pop 1 } { pop 0 } ifelse }").is_ok());
+        assert_eq!(
+            function("{ 1 exch sub").unwrap_err(),
+            FunctionErr::UnbalancedBraces
+        );
+        assert_eq!(
+            function("{ 1 } { 2 }").unwrap_err(),
+            FunctionErr::UnbalancedBraces
+        );
+        assert_eq!(
+            function("1 exch sub").unwrap_err(),
+            FunctionErr::UnbalancedBraces
+        );
+        assert_eq!(
+            function("{ 1 exch showpage }").unwrap_err(),
+            FunctionErr::UnknownOperator("showpage".to_string())
+        );
+    }
+
+    #[test]
+    #[should_panic = "Stitching functions must have one bound less than functions."]
+    fn stitching_bounds() {
+        let mut id_manager = IdManager::new();
+        let _ = StitchingFunction::new(vec![FunctionId::new(id_manager.create_id())], [0.5]);
+    }
+}
@@ -80,6 +80,33 @@ impl<T: AsRef<[u8]>> Identifier<T> {
         self.inner.as_ref().len() + 1
     }
 
+    /// Returns the bytes of this [`Identifier`], without the leading '/'.
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        self.inner.as_ref()
+    }
+
+    /// Returns a copy of this [`Identifier`] with all bytes that are not regular characters
+    /// encoded with their 2-digit hexadecimal code, or `None` if all bytes are regular characters.
+    /// The NULL character can't be part of a name at all, so it's replaced with `_`.
+    pub(crate) fn encoded(&self) -> Option<OwnedIdentifier> {
+        let bytes = self.inner.as_ref();
+        let is_regular = |byte: u8| (0x21..=0x7e).contains(&byte) && !is_delimiter(byte);
+        if bytes.iter().all(|byte| is_regular(*byte)) {
+            return None;
+        }
+
+        let mut output = Vec::with_capacity(bytes.len());
+        for &byte in bytes {
+            match byte {
+                b'\0' => output.push(b'_'),
+                regular if is_regular(regular) => output.push(regular),
+                other => output.extend(format!("#{other:02x}").bytes()),
+            }
+        }
+
+        Some(Identifier::new(output))
+    }
+
     /// Returns the referenced version to this [`Identifier`].
     pub fn as_ref(&self) -> Identifier<&[u8]> {
         Identifier {
@@ -92,7 +119,9 @@ impl Identifier<&'static [u8]> {
     const_identifiers! {
         pub(crate) TYPE,
         pub(crate) X_OBJECT,
-        pub(crate) FONT
+        pub(crate) FONT,
+        pub(crate) EXT_G_STATE: b"ExtGState",
+        pub(crate) COLOR_SPACE,
     }
 
     /// Create a new [`Identifier`] from a static byte slice.
@@ -114,6 +143,15 @@ impl Identifier<&'static [u8]> {
     }
 }
 
+/// Returns `true` if the given byte is a delimiter character.
+#[inline(always)]
+fn is_delimiter(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
 /// Error returned on failure when parsing an [`Identifier`] from a [`String`] or [`str`].
 #[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
 pub enum ParseIdentifierErr {
@@ -180,14 +218,6 @@ impl FromStr for Identifier<String> {
 
         let mut output = String::with_capacity(input.len());
 
-        #[inline(always)]
-        fn is_delimiter(byte: u8) -> bool {
-            matches!(
-                byte,
-                b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
-            )
-        }
-
         for ch in input.bytes() {
             match ch {
                 b'\0' => return Err(ParseIdentifierErr::ContainsNull),
@@ -0,0 +1,183 @@
+//! Implementation of natural language identifiers, used for tagging content with its language.
+
+use std::{
+    io::{Error, Write},
+    str::FromStr,
+};
+
+use crate::types::hierarchy::content::bidi::TextDirection;
+
+use super::string::PdfString;
+
+/// Error returned on failure when parsing a [`Language`] from a [`str`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug, thiserror::Error)]
+pub enum ParseLanguageErr {
+    #[error("Language tag must not be empty.")]
+    Empty,
+
+    #[error("Primary language subtag must consist of 1 to 8 ASCII letters.")]
+    InvalidPrimarySubtag,
+
+    #[error("Language subtags must consist of 1 to 8 ASCII letters or digits.")]
+    InvalidSubtag,
+}
+
+/// A natural language, specified as a language tag as defined in BCP 47 (e.g. `en-US`, `de` or
+/// `sr-Latn`). The language is used by screen readers for correct pronunciation and by search
+/// engines for indexing of multilingual documents.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Language {
+    /// The language tag, e.g. `en-US`.
+    tag: String,
+}
+
+impl Language {
+    /// Returns the language tag of this `Language`.
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns the primary language subtag, e.g. `sr` for `sr-Latn-RS`.
+    pub fn primary(&self) -> &str {
+        self.tag.split('-').next().unwrap_or_default()
+    }
+
+    /// Returns the script subtag if present, e.g. `Latn` for `sr-Latn-RS`.
+    pub fn script(&self) -> Option<&str> {
+        self.tag
+            .split('-')
+            .nth(1)
+            .filter(|subtag| subtag.len() == 4 && subtag.bytes().all(|b| b.is_ascii_alphabetic()))
+    }
+
+    /// Returns the [`TextDirection`] in which text of this `Language` is read, e.g. right to left
+    /// for `ar` or `he`. An explicit script subtag takes precedence over the primary language
+    /// subtag, e.g. `az-Arab` is read right to left, while `ku-Latn` is read left to right.
+    pub fn direction(&self) -> TextDirection {
+        /// Primary language subtags of languages written in a right-to-left script by default.
+        const RTL_LANGUAGES: &[&str] = &[
+            "ar", "arc", "ckb", "dv", "fa", "he", "iw", "ks", "ku", "ps", "sd", "syr", "ug", "ur",
+            "yi",
+        ];
+
+        /// Script subtags of right-to-left scripts.
+        const RTL_SCRIPTS: &[&str] = &["Adlm", "Arab", "Hebr", "Nkoo", "Rohg", "Syrc", "Thaa"];
+
+        let is_rtl = match self.script() {
+            Some(script) => RTL_SCRIPTS
+                .iter()
+                .any(|rtl| rtl.eq_ignore_ascii_case(script)),
+            None => RTL_LANGUAGES
+                .iter()
+                .any(|rtl| rtl.eq_ignore_ascii_case(self.primary())),
+        };
+
+        match is_rtl {
+            true => TextDirection::RightToLeft,
+            false => TextDirection::LeftToRight,
+        }
+    }
+
+    /// Writes this `Language` as a PDF text string into the given implementor of [`Write`].
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        PdfString::from(self.tag.as_str()).write_content(writer)
+    }
+}
+
+impl FromStr for Language {
+    type Err = ParseLanguageErr;
+
+    /// Parses a [`Language`] from a BCP 47 language tag. The tag consists of subtags separated by
+    /// a hyphen (`'-'`), where:
+    ///
+    /// * The primary language subtag consists of 1 to 8 ASCII letters.
+    /// * Any other subtag consists of 1 to 8 ASCII letters or digits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use pdfgen::types::hierarchy::primitives::language::{Language, ParseLanguageErr};
+    /// # use std::str::FromStr;
+    /// #
+    /// let lang = Language::from_str("sr-Latn-RS").unwrap();
+    /// assert_eq!(lang.primary(), "sr");
+    /// assert_eq!(lang.script(), Some("Latn"));
+    ///
+    /// let lang = Language::from_str("en_US");
+    /// assert!(matches!(lang, Err(ParseLanguageErr::InvalidPrimarySubtag)));
+    /// ```
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(ParseLanguageErr::Empty);
+        }
+
+        let is_valid_subtag = |subtag: &str, is_valid_byte: fn(&u8) -> bool| {
+            (1..=8).contains(&subtag.len()) && subtag.bytes().all(|b| is_valid_byte(&b))
+        };
+
+        let mut subtags = input.split('-');
+
+        let primary = subtags.next().unwrap_or_default();
+        if !is_valid_subtag(primary, u8::is_ascii_alphabetic) {
+            return Err(ParseLanguageErr::InvalidPrimarySubtag);
+        }
+
+        if !subtags.all(|subtag| is_valid_subtag(subtag, u8::is_ascii_alphanumeric)) {
+            return Err(ParseLanguageErr::InvalidSubtag);
+        }
+
+        Ok(Self {
+            tag: input.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::types::hierarchy::content::bidi::TextDirection;
+
+    use super::{Language, ParseLanguageErr};
+
+    #[test]
+    fn parse_language() {
+        let lang = Language::from_str("de-CH-1996").unwrap();
+        assert_eq!(lang.as_str(), "de-CH-1996");
+        assert_eq!(lang.primary(), "de");
+        assert_eq!(lang.script(), None);
+
+        assert_eq!(Language::from_str("  "), Err(ParseLanguageErr::Empty));
+        assert_eq!(
+            Language::from_str("en--US"),
+            Err(ParseLanguageErr::InvalidSubtag)
+        );
+        assert_eq!(
+            Language::from_str("1en"),
+            Err(ParseLanguageErr::InvalidPrimarySubtag)
+        );
+    }
+
+    #[test]
+    fn language_direction() {
+        let direction = |tag| Language::from_str(tag).unwrap().direction();
+
+        assert_eq!(direction("de-DE"), TextDirection::LeftToRight);
+        assert_eq!(direction("ar-EG"), TextDirection::RightToLeft);
+        assert_eq!(direction("HE"), TextDirection::RightToLeft);
+        assert_eq!(direction("az-Arab"), TextDirection::RightToLeft);
+        assert_eq!(direction("ku-Latn"), TextDirection::LeftToRight);
+    }
+
+    #[test]
+    fn write_language() {
+        let lang = Language::from_str("zh-Hant").unwrap();
+
+        let mut writer = Vec::new();
+        lang.write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @"(zh-Hant)");
+    }
+}
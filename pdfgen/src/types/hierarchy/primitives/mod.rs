@@ -2,10 +2,21 @@
 //! values for various entries in object dictionaries (such as Page Tree and Page).
 
 pub mod array;
+#[cfg(feature = "composite-fonts")]
+pub mod composite_font;
+pub mod date;
+pub mod encoding;
 pub mod font;
+#[cfg(feature = "composite-fonts")]
+pub(crate) mod font_descriptor;
+pub(crate) mod font_metrics;
+pub mod function;
 pub mod identifier;
+pub mod language;
 pub mod object;
+pub mod output_intent;
 pub mod rectangle;
 pub mod resources;
 pub mod string;
+pub mod symbol;
 pub mod unit;
@@ -0,0 +1,170 @@
+//! Implementation of output intents, describing the colour characteristics of the output device
+//! that content is prepared for.
+
+use std::io::{Error, Write};
+
+use pdfgen_macros::const_identifiers;
+
+use crate::types::constants;
+
+use super::{identifier::Identifier, string::PdfString};
+
+/// The standard an [`OutputIntent`] conforms to, written as its `/S` subtype.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputIntentSubtype {
+    /// Output intent of a PDF/X file, prepared for print production (`GTS_PDFX`).
+    PdfX,
+
+    /// Output intent of a PDF/A file, prepared for archival (`GTS_PDFA1`).
+    PdfA,
+
+    /// Output intent of a PDF/E file, prepared for engineering workflows (`ISO_PDFE1`).
+    PdfE,
+}
+
+impl OutputIntentSubtype {
+    /// Returns the [`Identifier`] of this subtype.
+    fn identifier(self) -> Identifier<&'static [u8]> {
+        match self {
+            OutputIntentSubtype::PdfX => Identifier::from_static(b"GTS_PDFX"),
+            OutputIntentSubtype::PdfA => Identifier::from_static(b"GTS_PDFA1"),
+            OutputIntentSubtype::PdfE => Identifier::from_static(b"ISO_PDFE1"),
+        }
+    }
+}
+
+/// An output intent describes the final destination device for which the content is prepared,
+/// such as a specific printing condition. In PDF 2.0, output intents may be attached to individual
+/// pages, overriding the document's output intents for that page. This allows e.g. press-ready
+/// inserts with a different printing condition than the rest of the document.
+///
+/// The printing condition is identified by name, usually from a registry of characterized
+/// printing conditions such as the ICC registry.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::types::hierarchy::primitives::output_intent::{OutputIntent, OutputIntentSubtype};
+/// let intent = OutputIntent::new(OutputIntentSubtype::PdfX, "FOGRA39")
+///     .with_output_condition("Offset printing, coated paper")
+///     .with_registry_name("http://www.color.org");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputIntent {
+    /// The standard this output intent conforms to.
+    subtype: OutputIntentSubtype,
+
+    /// Name of the intended output device or production condition, e.g. `FOGRA39`.
+    output_condition_identifier: PdfString,
+
+    /// Human-readable description of the intended output condition.
+    output_condition: Option<PdfString>,
+
+    /// Registry in which the output condition identifier is defined.
+    registry_name: Option<PdfString>,
+
+    /// Additional information about the intended output condition.
+    info: Option<PdfString>,
+}
+
+impl OutputIntent {
+    const_identifiers! {
+        OUTPUT_INTENT,
+        S: b"S",
+        OUTPUT_CONDITION_IDENTIFIER,
+        OUTPUT_CONDITION,
+        REGISTRY_NAME,
+        INFO,
+    }
+
+    /// Creates a new `OutputIntent` of the given subtype for the output condition with the given
+    /// identifier.
+    pub fn new(subtype: OutputIntentSubtype, output_condition_identifier: &str) -> Self {
+        Self {
+            subtype,
+            output_condition_identifier: PdfString::text_string(output_condition_identifier),
+            output_condition: None,
+            registry_name: None,
+            info: None,
+        }
+    }
+
+    /// Sets the human-readable description of the intended output condition.
+    pub fn with_output_condition(mut self, output_condition: &str) -> Self {
+        self.output_condition = Some(PdfString::text_string(output_condition));
+        self
+    }
+
+    /// Sets the registry in which the output condition identifier is defined, e.g.
+    /// `http://www.color.org`.
+    pub fn with_registry_name(mut self, registry_name: &str) -> Self {
+        self.registry_name = Some(PdfString::from(registry_name));
+        self
+    }
+
+    /// Sets additional information about the intended output condition.
+    pub fn with_info(mut self, info: &str) -> Self {
+        self.info = Some(PdfString::text_string(info));
+        self
+    }
+
+    /// Writes this `OutputIntent` as a dictionary into the given implementor of [`Write`].
+    pub(crate) fn write(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        Ok(pdfgen_macros::write_chain! {
+            writer.write(b"<< "),
+            Identifier::TYPE.write(writer),
+            Self::OUTPUT_INTENT.write(writer),
+            Self::S.write(writer),
+            self.subtype.identifier().write(writer),
+            writer.write(constants::NL_MARKER),
+
+            Self::OUTPUT_CONDITION_IDENTIFIER.write(writer),
+            self.output_condition_identifier.write_content(writer),
+            writer.write(constants::NL_MARKER),
+
+            if let Some(output_condition) = &self.output_condition {
+                Self::OUTPUT_CONDITION.write(writer),
+                output_condition.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(registry_name) = &self.registry_name {
+                Self::REGISTRY_NAME.write(writer),
+                registry_name.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            if let Some(info) = &self.info {
+                Self::INFO.write(writer),
+                info.write_content(writer),
+                writer.write(constants::NL_MARKER),
+            },
+
+            writer.write(b">>"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{OutputIntent, OutputIntentSubtype};
+
+    #[test]
+    fn pdf_x_output_intent() {
+        let intent = OutputIntent::new(OutputIntentSubtype::PdfX, "FOGRA39")
+            .with_output_condition("Offset printing, coated paper")
+            .with_registry_name("http://www.color.org");
+
+        let mut writer = Vec::new();
+        intent.write(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        << /Type /OutputIntent /S /GTS_PDFX 
+        /OutputConditionIdentifier (FOGRA39)
+        /OutputCondition (Offset printing, coated paper)
+        /RegistryName (http://www.color.org)
+        >>
+        ");
+    }
+}
@@ -44,29 +44,53 @@ pub struct Rectangle {
     top_right: Position,
 }
 
+/// Width and height in millimetres of the largest size in the ISO 216 A series (4A0). Every
+/// following size is derived by halving the longer side of the previous one.
+const ISO_216_4A0: (u32, u32) = (1682, 2378);
+
+/// Returns the width and height in millimetres of the ISO 216 A series size that is `halvings`
+/// times smaller than 4A0. The longer side is halved and rounded down to the whole millimetre,
+/// as defined by ISO 216.
+const fn iso_216_a_series(halvings: u32) -> (u32, u32) {
+    let (mut width, mut height) = ISO_216_4A0;
+    let mut i = 0;
+    while i < halvings {
+        (width, height) = (height / 2, width);
+        i += 1;
+    }
+
+    (width, height)
+}
+
 macro_rules! gen_page_constants {
-    ($($name:ident, $width:literal, $height:literal),* $(,)?) => {
+    ($($name:ident, $halvings:literal),* $(,)?) => {
         $(
-        pub const $name: Self = Self::new(Position::from_mm(0.0, 0.0), Position::from_mm($width - 1.0, $height - 1.0));
+        pub const $name: Self = {
+            let (width, height) = iso_216_a_series($halvings);
+            Self::new(
+                Position::from_mm(0.0, 0.0),
+                Position::from_mm(width as f32, height as f32),
+            )
+        };
         )*
     }
 }
 
 impl Rectangle {
     gen_page_constants! {
-        A0_4, 1682.0, 2378.0,
-        A0_2, 1189.0, 1682.0,
-          A0, 841.0, 1189.0,
-          A1, 594.0, 841.0,
-          A2, 420.0, 594.0,
-          A3, 297.0, 420.0,
-          A4, 210.0, 297.0,
-          A5, 148.0, 210.0,
-          A6, 105.0, 148.0,
-          A7, 74.0, 105.0,
-          A8, 52.0, 74.0,
-          A9, 37.0, 52.0,
-         A10, 26.0, 37.0,
+        A0_4, 0,
+        A0_2, 1,
+          A0, 2,
+          A1, 3,
+          A2, 4,
+          A3, 5,
+          A4, 6,
+          A5, 7,
+          A6, 8,
+          A7, 9,
+          A8, 10,
+          A9, 11,
+         A10, 12,
     }
 
     /// Create a new [`Rectangle`] with given [`Position`]s as its corners.
@@ -93,6 +117,16 @@ impl Rectangle {
         }
     }
 
+    /// Returns the lower left corner of this `Rectangle`.
+    pub fn low_left(&self) -> Position {
+        self.low_left
+    }
+
+    /// Returns the upper right corner of this `Rectangle`.
+    pub fn top_right(&self) -> Position {
+        self.top_right
+    }
+
     /// Returns the width of this `Rectangle`.
     pub fn width(&self) -> Unit {
         self.top_right.x - self.low_left.x
@@ -102,6 +136,69 @@ impl Rectangle {
     pub fn height(&self) -> Unit {
         self.top_right.y - self.low_left.y
     }
+
+    /// Returns the smallest `Rectangle` containing all of the given positions, or `None` if there
+    /// are no positions.
+    pub(crate) fn enclosing(positions: impl IntoIterator<Item = Position>) -> Option<Self> {
+        positions.into_iter().fold(None, |rect, pos| {
+            let Some(Rectangle {
+                low_left,
+                top_right,
+            }) = rect
+            else {
+                return Some(Rectangle::new(pos, pos));
+            };
+
+            let min = |a: Unit, b: Unit| if b < a { b } else { a };
+            let max = |a: Unit, b: Unit| if b > a { b } else { a };
+
+            Some(Rectangle::new(
+                Position::new(min(low_left.x, pos.x), min(low_left.y, pos.y)),
+                Position::new(max(top_right.x, pos.x), max(top_right.y, pos.y)),
+            ))
+        })
+    }
+
+    /// Returns `true` if this `Rectangle` and the given one share an area, as opposed to being
+    /// apart or only touching at their edges.
+    pub(crate) fn overlaps(&self, other: &Rectangle) -> bool {
+        self.low_left.x < other.top_right.x
+            && other.low_left.x < self.top_right.x
+            && self.low_left.y < other.top_right.y
+            && other.low_left.y < self.top_right.y
+    }
+
+    /// Returns this `Rectangle` extended by the given margin on every side.
+    pub(crate) fn expanded(self, margin: Unit) -> Self {
+        Self {
+            low_left: Position::new(self.low_left.x - margin, self.low_left.y - margin),
+            top_right: Position::new(self.top_right.x + margin, self.top_right.y + margin),
+        }
+    }
+
+    /// Returns this `Rectangle` with the coordinates of its corners multiplied by the given factor.
+    pub(crate) fn scaled(self, factor: f32) -> Self {
+        let scale = |unit: Unit| Unit::from_unit(unit.into_user_unit() * factor);
+
+        Self {
+            low_left: Position::new(scale(self.low_left.x), scale(self.low_left.y)),
+            top_right: Position::new(scale(self.top_right.x), scale(self.top_right.y)),
+        }
+    }
+
+    /// Returns this `Rectangle` with its width and height clamped into the given range of user
+    /// space units, keeping its lower left corner in place.
+    pub(crate) fn clamp_size(self, min: f64, max: f64) -> Self {
+        let clamp = |size: Unit| Unit::from_pt(size.into_points().clamp(min, max));
+
+        Self {
+            low_left: self.low_left,
+            top_right: Position::new(
+                self.low_left.x + clamp(self.width()),
+                self.low_left.y + clamp(self.height()),
+            ),
+        }
+    }
 }
 
 impl From<(u32, u32, u32, u32)> for Rectangle {
@@ -123,7 +220,7 @@ impl From<(f32, f32, f32, f32)> for Rectangle {
 mod tests {
     use crate::types::hierarchy::primitives::unit::Unit;
 
-    use super::Rectangle;
+    use super::{Position, Rectangle};
 
     #[test]
     fn new_rectangle() {
@@ -135,6 +232,49 @@ mod tests {
         assert_eq!(rect.top_right.y, Unit::from_mm(43.0));
     }
 
+    #[test]
+    fn a_series_sizes() {
+        let sizes_in_mm = [
+            (Rectangle::A0_4, 1682, 2378),
+            (Rectangle::A0_2, 1189, 1682),
+            (Rectangle::A0, 841, 1189),
+            (Rectangle::A1, 594, 841),
+            (Rectangle::A2, 420, 594),
+            (Rectangle::A3, 297, 420),
+            (Rectangle::A4, 210, 297),
+            (Rectangle::A5, 148, 210),
+            (Rectangle::A6, 105, 148),
+            (Rectangle::A7, 74, 105),
+            (Rectangle::A8, 52, 74),
+            (Rectangle::A9, 37, 52),
+            (Rectangle::A10, 26, 37),
+        ];
+
+        for (rect, width, height) in sizes_in_mm {
+            assert_eq!(rect.low_left, Position::from_mm(0.0, 0.0));
+            assert_eq!(
+                rect.top_right,
+                Position::from_mm(width as f32, height as f32)
+            );
+        }
+    }
+
+    #[test]
+    fn a_series_points() {
+        let sizes_in_pt = [
+            (Rectangle::A0, 2383.937, 3370.394),
+            (Rectangle::A3, 841.89, 1190.551),
+            (Rectangle::A4, 595.276, 841.89),
+            (Rectangle::A5, 419.528, 595.276),
+            (Rectangle::A6, 297.638, 419.528),
+        ];
+
+        for (rect, width, height) in sizes_in_pt {
+            assert!((rect.width().into_points() - width).abs() < 0.001);
+            assert!((rect.height().into_points() - height).abs() < 0.001);
+        }
+    }
+
     #[test]
     fn output() {
         let rect = Rectangle::from_units(24.0, 25.0, 42.0, 43.0);
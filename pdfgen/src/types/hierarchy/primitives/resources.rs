@@ -1,8 +1,22 @@
 //! Implementation of Resources Dictionary data type.
 
-use std::io::{Error, Write};
+use std::{
+    collections::HashMap,
+    io::{Error, Write},
+};
 
-use crate::{IdManager, ObjId, types::hierarchy::content::image::Image};
+use crate::{
+    IdManager, ObjId,
+    types::{
+        constants,
+        hierarchy::content::{
+            color::{ColorSpace, ColorSpaceIds},
+            graphics_state::ExtGState,
+            image::Image,
+            page_form::PageForm,
+        },
+    },
+};
 
 use super::{
     font::Font,
@@ -10,17 +24,47 @@ use super::{
 };
 
 /// Represents a single entry in the [`Resources`] dictionary.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[non_exhaustive]
 pub(crate) enum ResourceEntry {
     Image {
         name: OwnedIdentifier,
-        image: Image,
+        image: Box<Image>,
     },
     Font {
         name: OwnedIdentifier,
         id: ObjId<Font>,
     },
+    ExtGState {
+        name: OwnedIdentifier,
+        state: ExtGState,
+    },
+    Form {
+        name: OwnedIdentifier,
+        id: ObjId<PageForm>,
+    },
+    /// An image added to the document with [`Document::add_image`], written once by the document.
+    ///
+    /// [`Document::add_image`]: crate::Document::add_image
+    SharedImage {
+        name: OwnedIdentifier,
+        id: ObjId<Image>,
+    },
+    /// An image loaded from a [`Fragment`], written from the serialized body of its object.
+    ///
+    /// [`Fragment`]: crate::Fragment
+    SerializedImage {
+        name: OwnedIdentifier,
+        body: Vec<u8>,
+    },
+    /// A graphics state parameter dictionary loaded from a [`Fragment`], written from its
+    /// serialized body.
+    ///
+    /// [`Fragment`]: crate::Fragment
+    SerializedExtGState {
+        name: OwnedIdentifier,
+        body: Vec<u8>,
+    },
 }
 
 /// Resource dictionary enumerates the named resources needed by the operators in the content
@@ -30,10 +74,14 @@ pub(crate) enum ResourceEntry {
 /// the content stream, such as a font dictionary or a stream containing image data. This shall be
 /// accomplished by defining such objects as named resources and referring to them by name from
 /// within the content stream.
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone)]
 pub struct Resources {
     counter: usize,
     pub(crate) entries: Vec<ResourceEntry>,
+
+    /// Colour spaces referred to by name in content streams, written inline in the `ColorSpace`
+    /// entry.
+    color_spaces: Vec<(OwnedIdentifier, ColorSpace)>,
 }
 
 impl Resources {
@@ -56,7 +104,10 @@ impl Resources {
     /// [`Image`]: crate::types::hierarchy::content::image::Image
     pub(crate) fn add_image(&mut self, image: Image) -> Identifier<&[u8]> {
         let name = self.create_name("Im");
-        let img = ResourceEntry::Image { name, image };
+        let img = ResourceEntry::Image {
+            name,
+            image: Box::new(image),
+        };
 
         self.entries.push(img);
 
@@ -83,24 +134,203 @@ impl Resources {
         name.as_ref()
     }
 
-    /// Encode and write this resource dictionary into the provided implementor of [`Write`].
-    pub(crate) fn write_dict(
+    /// Returns the [`ObjId`]s of all fonts referenced by this `Resources` dictionary.
+    pub(crate) fn font_ids(&self) -> impl Iterator<Item = &ObjId<Font>> {
+        self.entries.iter().filter_map(|entry| match entry {
+            ResourceEntry::Font { id, .. } => Some(id),
+            _ => None,
+        })
+    }
+
+    /// Returns the number of images in this `Resources` dictionary.
+    pub(crate) fn image_count(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| {
+                matches!(
+                    entry,
+                    ResourceEntry::Image { .. } | ResourceEntry::SharedImage { .. }
+                )
+            })
+            .count()
+    }
+
+    /// Removes the images with the given names from this `Resources` dictionary, e.g. when the
+    /// operations drawing them were culled.
+    pub(crate) fn remove_images(&mut self, names: &[&[u8]]) {
+        self.entries.retain(|entry| match entry {
+            ResourceEntry::Image { name, .. } | ResourceEntry::SharedImage { name, .. } => {
+                !names.contains(&name.as_bytes())
+            }
+            _ => true,
+        });
+    }
+
+    /// Replaces the [`ObjId`]s of all referenced fonts with the ones they are mapped to.
+    pub(crate) fn remap_font_ids(&mut self, font_ids: &HashMap<ObjId<Font>, ObjId<Font>>) {
+        for entry in &mut self.entries {
+            if let ResourceEntry::Font { id, .. } = entry {
+                *id = font_ids[id].clone();
+            }
+        }
+    }
+
+    /// Adds an [`ExtGState`] to this `Resources` dictionary.
+    ///
+    /// [`ExtGState`]: crate::types::hierarchy::content::graphics_state::ExtGState
+    pub(crate) fn add_ext_g_state(&mut self, state: ExtGState) -> Identifier<&[u8]> {
+        let name = self.create_name("GS");
+        let gs = ResourceEntry::ExtGState { name, state };
+
+        self.entries.push(gs);
+
+        let ResourceEntry::ExtGState { name, .. } = self.entries.last().unwrap() else {
+            unreachable!("We added an ExtGState.")
+        };
+
+        name.as_ref()
+    }
+
+    /// Adds a reference to a [`PageForm`] to this `Resources` dictionary.
+    ///
+    /// [`PageForm`]: crate::types::hierarchy::content::page_form::PageForm
+    pub(crate) fn add_form(&mut self, form_id: ObjId<PageForm>) -> Identifier<&[u8]> {
+        let name = self.create_name("Fm");
+        let form = ResourceEntry::Form { name, id: form_id };
+
+        self.entries.push(form);
+
+        let ResourceEntry::Form { name, .. } = self.entries.last().unwrap() else {
+            unreachable!("We added a form.")
+        };
+
+        name.as_ref()
+    }
+
+    /// Adds a reference to an [`Image`] added to the document with [`Document::add_image`] to this
+    /// `Resources` dictionary.
+    ///
+    /// [`Document::add_image`]: crate::Document::add_image
+    pub(crate) fn add_shared_image(&mut self, image_id: ObjId<Image>) -> Identifier<&[u8]> {
+        let name = self.create_name("Im");
+        let img = ResourceEntry::SharedImage { name, id: image_id };
+
+        self.entries.push(img);
+
+        let ResourceEntry::SharedImage { name, .. } = self.entries.last().unwrap() else {
+            unreachable!("We added an image.")
+        };
+
+        name.as_ref()
+    }
+
+    /// Returns the [`ObjId`]s of all images added to the document referenced by this `Resources`
+    /// dictionary.
+    pub(crate) fn shared_image_ids(&self) -> impl Iterator<Item = &ObjId<Image>> {
+        self.entries.iter().filter_map(|entry| match entry {
+            ResourceEntry::SharedImage { id, .. } => Some(id),
+            _ => None,
+        })
+    }
+
+    /// Replaces the [`ObjId`]s of all referenced images added to the document with the ones they
+    /// are mapped to.
+    pub(crate) fn remap_image_ids(&mut self, image_ids: &HashMap<ObjId<Image>, ObjId<Image>>) {
+        for entry in &mut self.entries {
+            if let ResourceEntry::SharedImage { id, .. } = entry {
+                *id = image_ids[id].clone();
+            }
+        }
+    }
+
+    /// Adds the given [`ColorSpace`] to this `Resources` dictionary, if it's referred to by name in
+    /// content streams and wasn't added yet.
+    pub(crate) fn add_color_space(&mut self, color_space: ColorSpace) {
+        let Some(name) = color_space.resource_name() else {
+            return;
+        };
+
+        if !self
+            .color_spaces
+            .iter()
+            .any(|(cs_name, _)| cs_name.as_bytes() == name.as_bytes())
+        {
+            self.color_spaces.push((name, color_space));
+        }
+    }
+
+    /// Sets the given [`ColorSpace`] as the default colour space of this `Resources` dictionary,
+    /// which replaces the device colour space with the same number of colour components, e.g.
+    /// `DefaultRGB` for colour spaces with three components. An earlier default colour space with
+    /// the same number of components is replaced.
+    pub(crate) fn set_default_color_space(&mut self, color_space: ColorSpace) {
+        let name: &[u8] = match color_space.components() {
+            1 => b"DefaultGray",
+            3 => b"DefaultRGB",
+            4 => b"DefaultCMYK",
+            other => unreachable!("Colour spaces have 1, 3 or 4 components, not {other}."),
+        };
+
+        self.color_spaces
+            .retain(|(cs_name, _)| cs_name.as_bytes() != name);
+        self.color_spaces
+            .push((OwnedIdentifier::new(name.to_vec()), color_space));
+    }
+
+    /// Returns all colour spaces used by this `Resources` dictionary, including the ones of images.
+    pub(crate) fn color_spaces(&self) -> impl Iterator<Item = ColorSpace> {
+        let images = self.entries.iter().filter_map(|entry| match entry {
+            ResourceEntry::Image { image, .. } => image.color_space(),
+            _ => None,
+        });
+
+        self.color_spaces
+            .iter()
+            .map(|(_, color_space)| *color_space)
+            .chain(images)
+    }
+
+    /// Replaces the objects defining all referenced colour spaces with the ones they are mapped
+    /// to. The names of the colour spaces are kept, as content streams refer to them.
+    pub(crate) fn remap_color_space_ids(&mut self, ids: &ColorSpaceIds) {
+        for (_, color_space) in &mut self.color_spaces {
+            color_space.remap_ids(ids);
+        }
+
+        for entry in &mut self.entries {
+            if let ResourceEntry::Image { image, .. } = entry {
+                image.remap_color_space_ids(ids);
+            }
+        }
+    }
+
+    /// Encode and write this resource dictionary into the provided implementor of [`Write`], with
+    /// function that writes entries for resources that are not owned by this dictionary.
+    pub(crate) fn write_dict<F>(
         &self,
         writer: &mut dyn Write,
         renderables: &[Renderable],
-    ) -> Result<usize, Error> {
+        write_entries: F,
+    ) -> Result<usize, Error>
+    where
+        F: FnOnce(&mut dyn Write) -> Result<usize, Error>,
+    {
         let mut font_renderables = Vec::new();
-        let write_fonts_dict = |writer: &mut dyn Write, font_renderables: Vec<&Renderable<'_>>| {
-            if font_renderables.is_empty() {
+        let mut ext_g_state_renderables = Vec::new();
+        let mut form_renderables = Vec::new();
+        let write_sub_dict = |writer: &mut dyn Write,
+                              key: Identifier<&'static [u8]>,
+                              sub_renderables: Vec<&Renderable<'_>>| {
+            if sub_renderables.is_empty() {
                 return std::io::Result::Ok(0);
             }
 
             Ok(pdfgen_macros::write_chain! {
-                Identifier::FONT.write(writer),
+                key.write(writer),
                 writer.write(b"<< "),
 
-                for fr in font_renderables.into_iter() {
-                     fr.write_ref(writer),
+                for sr in sub_renderables.into_iter() {
+                     sr.write_ref(writer),
                 },
 
                 writer.write(b" >>"),
@@ -111,59 +341,152 @@ impl Resources {
             writer.write(b"<< "),
 
             for renderable in renderables.iter() {
-                if matches!(renderable.entry, ResourceEntry::Font { .. }) {
-                    font_renderables.push(renderable);
-                    Ok(0)
-                } else {
-                    renderable.write_ref(writer)
+                match renderable.entry {
+                    ResourceEntry::Font { .. } => {
+                        font_renderables.push(renderable);
+                        Ok(0)
+                    }
+                    ResourceEntry::ExtGState { .. } | ResourceEntry::SerializedExtGState { .. } => {
+                        ext_g_state_renderables.push(renderable);
+                        Ok(0)
+                    }
+                    ResourceEntry::Form { .. } | ResourceEntry::SharedImage { .. } => {
+                        form_renderables.push(renderable);
+                        Ok(0)
+                    }
+                    _ => renderable.write_ref(writer),
                 },
             },
 
-            write_fonts_dict(writer, font_renderables),
+            write_sub_dict(writer, Identifier::FONT, font_renderables),
+            write_sub_dict(writer, Identifier::EXT_G_STATE, ext_g_state_renderables),
+            write_sub_dict(writer, Identifier::X_OBJECT, form_renderables),
+
+            self.write_color_spaces(writer),
+            write_entries(writer),
 
             writer.write(b" >>"),
         })
     }
 
-    pub(crate) fn renderables(&self, id_manager: &mut IdManager) -> Vec<Renderable> {
-        self.entries
+    /// Writes the `ColorSpace` entry of this resource dictionary, if it has any named colour
+    /// spaces.
+    fn write_color_spaces(&self, writer: &mut dyn Write) -> Result<usize, Error> {
+        if self.color_spaces.is_empty() {
+            return Ok(0);
+        }
+
+        Ok(pdfgen_macros::write_chain! {
+            Identifier::COLOR_SPACE.write(writer),
+            writer.write(b"<< "),
+
+            for (name, color_space) in self.color_spaces.iter() {
+                name.write(writer),
+                color_space.write(writer),
+            },
+
+            writer.write(b">>"),
+        })
+    }
+
+    pub(crate) fn renderables(&self, id_manager: &mut IdManager) -> Vec<Renderable<'_>> {
+        let mut renderables: Vec<_> = self
+            .entries
             .iter()
             .map(|entry| Renderable {
                 // TODO: skip creating ids for Fonts (global objects).
                 id: id_manager.create_id(),
+                mask_id: None,
                 entry,
             })
-            .collect()
+            .collect();
+
+        // NOTE: masks of images are written after all other resources, so their ids are created
+        //       last.
+        for renderable in &mut renderables {
+            if let ResourceEntry::Image { image, .. } = renderable.entry
+                && image.mask().is_some()
+            {
+                renderable.mask_id = Some(id_manager.create_id());
+            }
+        }
+
+        renderables
     }
 }
 
 #[derive(Debug)]
 pub(crate) struct Renderable<'entry> {
     id: ObjId,
+
+    /// ID of the mask of an image entry, written as a separate object after all other resources.
+    mask_id: Option<ObjId>,
+
     entry: &'entry ResourceEntry,
 }
 
 impl Renderable<'_> {
     pub(crate) fn write_def(&self, writer: &mut dyn Write) -> std::io::Result<usize> {
         match self.entry {
-            ResourceEntry::Image { image, .. } => image.write(writer, &self.id),
+            ResourceEntry::Image { image, .. } => {
+                image.write_with_mask(writer, &self.id, self.mask_id.as_ref())
+            }
             ResourceEntry::Font { .. } => Ok(0),
+            ResourceEntry::ExtGState { state, .. } => state.write(writer, &self.id),
+            ResourceEntry::Form { .. } | ResourceEntry::SharedImage { .. } => Ok(0),
+            ResourceEntry::SerializedImage { body, .. }
+            | ResourceEntry::SerializedExtGState { body, .. } => Ok(pdfgen_macros::write_chain! {
+                self.id.write_def(writer),
+                writer.write(constants::NL_MARKER),
+                writer.write(body),
+                writer.write(constants::END_OBJ_MARKER),
+                writer.write(constants::NL_MARKER),
+            }),
+        }
+    }
+
+    /// Returns the mask of an image entry together with the ID it's written with.
+    pub(crate) fn mask(&self) -> Option<(&Image, &ObjId)> {
+        match (self.entry, &self.mask_id) {
+            (ResourceEntry::Image { image, .. }, Some(id)) => Some((image.mask()?, id)),
+            _ => None,
         }
     }
 
     pub(crate) fn write_ref(&self, writer: &mut dyn Write) -> std::io::Result<usize> {
         match self.entry {
-            ResourceEntry::Image { name, .. } => Ok(pdfgen_macros::write_chain! {
-                Identifier::X_OBJECT.write(writer),
+            ResourceEntry::Image { name, .. } | ResourceEntry::SerializedImage { name, .. } => {
+                Ok(pdfgen_macros::write_chain! {
+                    Identifier::X_OBJECT.write(writer),
 
-                writer.write(b"<< "),
+                    writer.write(b"<< "),
+                    name.write(writer),
+                    self.id
+                        .write_ref(writer),
+                    writer.write(b" >>"),
+                })
+            }
+
+            ResourceEntry::Font { name, id } => Ok(pdfgen_macros::write_chain! {
                 name.write(writer),
-                self.id
-                    .write_ref(writer),
-                writer.write(b" >>"),
+                id.write_ref(writer),
+                writer.write(b" "),
             }),
 
-            ResourceEntry::Font { name, id } => Ok(pdfgen_macros::write_chain! {
+            ResourceEntry::ExtGState { name, .. }
+            | ResourceEntry::SerializedExtGState { name, .. } => Ok(pdfgen_macros::write_chain! {
+                name.write(writer),
+                self.id.write_ref(writer),
+                writer.write(b" "),
+            }),
+
+            ResourceEntry::Form { name, id } => Ok(pdfgen_macros::write_chain! {
+                name.write(writer),
+                id.write_ref(writer),
+                writer.write(b" "),
+            }),
+
+            ResourceEntry::SharedImage { name, id } => Ok(pdfgen_macros::write_chain! {
                 name.write(writer),
                 id.write_ref(writer),
                 writer.write(b" "),
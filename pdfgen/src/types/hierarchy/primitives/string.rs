@@ -1,42 +1,159 @@
-use std::io::{Error, Write};
+use std::{
+    borrow::Cow,
+    io::{Error, Write},
+};
 
 use pdfgen_macros::write_chain;
 
-/// Represents a PDF String with UTF-8 encoding.
+use super::encoding::{ShowEncoding, encode_identity, encode_win_ansi};
+
+/// Syntax a [`PdfString`] is written with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum StringFormat {
+    /// Bytes enclosed in parentheses, e.g. `(Hello)`, with special characters escaped.
+    #[default]
+    Literal,
+
+    /// Bytes written as pairs of hexadecimal digits enclosed in angle brackets, e.g.
+    /// `<48656c6c6f>`. Safe for arbitrary binary data, such as document IDs and encrypted values.
+    Hexadecimal,
+}
+
+/// Represents a PDF String, holding UTF-8 encoded text or binary data.
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct PdfString {
-    /// Inner [`String`] is the object that actually stores the bytes of a `PdfString`.
-    inner: String,
+    /// Inner bytes of this `PdfString`.
+    inner: Vec<u8>,
+
+    /// Syntax this `PdfString` is written with.
+    format: StringFormat,
 }
 
 impl PdfString {
-    /// Creates a new `PdfString` with the given content that can be converted to UTF-8 encoded
-    /// [`String`].
+    /// Byte order mark marking a text string encoded as UTF-16BE.
+    const UTF16_BOM: [u8; 2] = [0xFE, 0xFF];
+
+    /// Creates a new literal `PdfString` with the given content that can be converted to UTF-8
+    /// encoded [`String`].
     pub fn from(content: impl Into<String>) -> Self {
         Self {
-            inner: content.into(),
+            inner: content.into().into_bytes(),
+            format: StringFormat::Literal,
         }
     }
 
+    /// Creates a new text string (7.9.2.2) with the given content, for use in the document
+    /// hierarchy, e.g. as metadata. Text strings are read as Unicode text by PDF processors, so
+    /// content outside of ASCII is encoded as UTF-16BE with a byte order mark, written as a
+    /// hexadecimal string.
+    ///
+    /// NOTE: Strings shown in content streams are encoded with the encoding of their font instead,
+    /// so text strings must not be used there.
+    pub fn text_string(content: impl Into<String>) -> Self {
+        let content = content.into();
+        if content.is_ascii() {
+            return Self::from(content);
+        }
+
+        let utf16 = content.encode_utf16().flat_map(u16::to_be_bytes);
+        Self::from_bytes(Self::UTF16_BOM.into_iter().chain(utf16).collect::<Vec<_>>())
+    }
+
+    /// Creates a new hexadecimal `PdfString` with the given binary content.
+    pub fn from_bytes(bytes: impl Into<Vec<u8>>) -> Self {
+        Self {
+            inner: bytes.into(),
+            format: StringFormat::Hexadecimal,
+        }
+    }
+
+    /// Sets the [`StringFormat`] this `PdfString` is written with.
+    pub fn with_format(mut self, format: StringFormat) -> Self {
+        self.format = format;
+        self
+    }
+
     /// Expands the `PdfString` with the given content that can be converted to UTF-8 encoded
     /// [`String`].
     pub fn expand(&mut self, content: impl Into<String>) {
-        self.inner.push_str(&content.into())
+        self.inner.extend_from_slice(content.into().as_bytes())
+    }
+
+    /// Returns the content of this `PdfString` as text, replacing invalid UTF-8 sequences of binary
+    /// content.
+    pub(crate) fn to_text(&self) -> Cow<'_, str> {
+        match self.inner.strip_prefix(&Self::UTF16_BOM) {
+            Some(utf16) => {
+                let units = utf16
+                    .chunks_exact(2)
+                    .map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+                Cow::Owned(
+                    char::decode_utf16(units)
+                        .map(|ch| ch.unwrap_or('\u{FFFD}'))
+                        .collect(),
+                )
+            }
+            None => String::from_utf8_lossy(&self.inner),
+        }
+    }
+
+    /// Returns a copy of this `PdfString` for showing its text in a content stream, encoded with
+    /// the given [`ShowEncoding`]. Single-byte codes are written as a literal string, two-byte
+    /// codes as a hexadecimal one.
+    pub(crate) fn encoded(&self, encoding: ShowEncoding) -> Self {
+        let text = self.to_text();
+        match encoding {
+            ShowEncoding::WinAnsi => {
+                Self::from_bytes(encode_win_ansi(&text)).with_format(StringFormat::Literal)
+            }
+            ShowEncoding::Identity => Self::from_bytes(encode_identity(&text)),
+        }
     }
 
-    /// Writes the inner content in the PDF String syntax format to the provided writer.
+    /// Writes the inner content in the PDF String syntax format to the provided writer. Literal
+    /// strings have delimiters, the backslash and control characters escaped, so that any content
+    /// produces a valid string.
     pub fn write_content(&self, writer: &mut dyn Write) -> Result<usize, Error> {
-        Ok(write_chain! {
-            writer.write(b"("),
-            writer.write(self.inner.as_bytes()),
-            writer.write(b")"),
+        Ok(match self.format {
+            StringFormat::Literal => write_chain! {
+                writer.write(b"("),
+                writer.write(&Self::escape(&self.inner)),
+                writer.write(b")"),
+            },
+            StringFormat::Hexadecimal => write_chain! {
+                writer.write(b"<"),
+                writer.write(hex::encode(&self.inner).as_bytes()),
+                writer.write(b">"),
+            },
         })
     }
+
+    /// Escapes the given bytes for use in a literal string (7.3.4.2). Parentheses and the reverse
+    /// solidus are preceded by a reverse solidus, and control characters are written as their
+    /// escape sequence, or as an octal character code if they have none.
+    fn escape(bytes: &[u8]) -> Vec<u8> {
+        let mut escaped = Vec::with_capacity(bytes.len());
+
+        for &byte in bytes {
+            match byte {
+                b'(' | b')' | b'\\' => escaped.extend_from_slice(&[b'\\', byte]),
+                b'\n' => escaped.extend_from_slice(b"\\n"),
+                b'\r' => escaped.extend_from_slice(b"\\r"),
+                b'\t' => escaped.extend_from_slice(b"\\t"),
+                0x08 => escaped.extend_from_slice(b"\\b"),
+                0x0C => escaped.extend_from_slice(b"\\f"),
+                0x00..=0x1F | 0x7F => escaped.extend_from_slice(format!("\\{byte:03o}").as_bytes()),
+                _ => escaped.push(byte),
+            }
+        }
+
+        escaped
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::PdfString;
+    use super::{PdfString, StringFormat};
 
     #[test]
     fn simple_string() {
@@ -60,4 +177,44 @@ mod tests {
 
         insta::assert_snapshot!(output, @"(This is an expanded text.)");
     }
+
+    #[test]
+    fn escaped_string() {
+        let pdf_string = PdfString::from("Smile :) (or \\ not)\n\tend\u{1}\u{7F}");
+
+        let mut writer = Vec::default();
+        pdf_string.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @r"
+        (Smile :\) \(or \\ not\)\n\tend\001\177)
+        ");
+    }
+
+    #[test]
+    fn hexadecimal_string() {
+        let binary = PdfString::from_bytes([0x00, 0x28, 0xff]);
+        let text = PdfString::from("Nov shmoz").with_format(StringFormat::Hexadecimal);
+
+        let mut writer = Vec::default();
+        binary.write_content(&mut writer).unwrap();
+        text.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @"<0028ff><4e6f762073686d6f7a>");
+    }
+
+    #[test]
+    fn utf16_text_string() {
+        let ascii = PdfString::text_string("Invoice");
+        let unicode = PdfString::text_string("Grüße €");
+
+        let mut writer = Vec::default();
+        ascii.write_content(&mut writer).unwrap();
+        unicode.write_content(&mut writer).unwrap();
+        let output = String::from_utf8(writer).unwrap();
+
+        insta::assert_snapshot!(output, @"(Invoice)<feff0047007200fc00df0065002020ac>");
+        assert_eq!(unicode.to_text(), "Grüße €");
+    }
 }
@@ -0,0 +1,265 @@
+//! Implementation of the glyphs of the symbolic standard fonts, Symbol and ZapfDingbats, which
+//! show text in their own built-in encodings instead of a Latin text encoding.
+
+/// Glyph names of the codes `0x20..=0x7E` in the built-in encoding of Symbol.
+#[rustfmt::skip]
+const SYMBOL_LOW: [&str; 95] = [
+    "space", "exclam", "universal", "numbersign", "existential", "percent", "ampersand",
+    "suchthat", "parenleft", "parenright", "asteriskmath", "plus", "comma", "minus", "period",
+    "slash", "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+    "colon", "semicolon", "less", "equal", "greater", "question", "congruent", "Alpha", "Beta",
+    "Chi", "Delta", "Epsilon", "Phi", "Gamma", "Eta", "Iota", "theta1", "Kappa", "Lambda", "Mu",
+    "Nu", "Omicron", "Pi", "Theta", "Rho", "Sigma", "Tau", "Upsilon", "sigma1", "Omega", "Xi",
+    "Psi", "Zeta", "bracketleft", "therefore", "bracketright", "perpendicular", "underscore",
+    "radicalex", "alpha", "beta", "chi", "delta", "epsilon", "phi", "gamma", "eta", "iota", "phi1",
+    "kappa", "lambda", "mu", "nu", "omicron", "pi", "theta", "rho", "sigma", "tau", "upsilon",
+    "omega1", "omega", "xi", "psi", "zeta", "braceleft", "bar", "braceright", "similar",
+];
+
+/// Glyph names of the codes `0xA1..=0xFE` in the built-in encoding of Symbol, where an empty name
+/// marks an unused code.
+#[rustfmt::skip]
+const SYMBOL_HIGH: [&str; 94] = [
+    "Upsilon1", "minute", "lessequal", "fraction", "infinity", "florin", "club", "diamond",
+    "heart", "spade", "arrowboth", "arrowleft", "arrowup", "arrowright", "arrowdown", "degree",
+    "plusminus", "second", "greaterequal", "multiply", "proportional", "partialdiff", "bullet",
+    "divide", "notequal", "equivalence", "approxequal", "ellipsis", "arrowvertex", "arrowhorizex",
+    "carriagereturn", "aleph", "Ifraktur", "Rfraktur", "weierstrass", "circlemultiply",
+    "circleplus", "emptyset", "intersection", "union", "propersuperset", "reflexsuperset",
+    "notsubset", "propersubset", "reflexsubset", "element", "notelement", "angle", "gradient",
+    "registerserif", "copyrightserif", "trademarkserif", "product", "radical", "dotmath",
+    "logicalnot", "logicaland", "logicalor", "arrowdblboth", "arrowdblleft", "arrowdblup",
+    "arrowdblright", "arrowdbldown", "lozenge", "angleleft", "registersans", "copyrightsans",
+    "trademarksans", "summation", "parenlefttp", "parenleftex", "parenleftbt", "bracketlefttp",
+    "bracketleftex", "bracketleftbt", "bracelefttp", "braceleftmid", "braceleftbt", "braceex", "",
+    "angleright", "integral", "integraltp", "integralex", "integralbt", "parenrighttp",
+    "parenrightex", "parenrightbt", "bracketrighttp", "bracketrightex", "bracketrightbt",
+    "bracerighttp", "bracerightmid", "bracerightbt",
+];
+
+/// Glyph names of the codes `0x20..=0x7E` in the built-in encoding of ZapfDingbats.
+#[rustfmt::skip]
+const DINGBATS_LOW: [&str; 95] = [
+    "space", "a1", "a2", "a202", "a3", "a4", "a5", "a119", "a118", "a117", "a11", "a12", "a13",
+    "a14", "a15", "a16", "a105", "a17", "a18", "a19", "a20", "a21", "a22", "a23", "a24", "a25",
+    "a26", "a27", "a28", "a6", "a7", "a8", "a9", "a10", "a29", "a30", "a31", "a32", "a33", "a34",
+    "a35", "a36", "a37", "a38", "a39", "a40", "a41", "a42", "a43", "a44", "a45", "a46", "a47",
+    "a48", "a49", "a50", "a51", "a52", "a53", "a54", "a55", "a56", "a57", "a58", "a59", "a60",
+    "a61", "a62", "a63", "a64", "a65", "a66", "a67", "a68", "a69", "a70", "a71", "a72", "a73",
+    "a74", "a203", "a75", "a204", "a76", "a77", "a78", "a79", "a81", "a82", "a83", "a84", "a97",
+    "a98", "a99", "a100",
+];
+
+/// Glyph names of the codes `0xA1..=0xFE` in the built-in encoding of ZapfDingbats, where an empty
+/// name marks an unused code.
+#[rustfmt::skip]
+const DINGBATS_HIGH: [&str; 94] = [
+    "a101", "a102", "a103", "a104", "a106", "a107", "a108", "a112", "a111", "a110", "a109",
+    "a120", "a121", "a122", "a123", "a124", "a125", "a126", "a127", "a128", "a129", "a130",
+    "a131", "a132", "a133", "a134", "a135", "a136", "a137", "a138", "a139", "a140", "a141",
+    "a142", "a143", "a144", "a145", "a146", "a147", "a148", "a149", "a150", "a151", "a152",
+    "a153", "a154", "a155", "a156", "a157", "a158", "a159", "a160", "a161", "a163", "a164",
+    "a196", "a165", "a192", "a166", "a167", "a168", "a169", "a170", "a171", "a172", "a173",
+    "a162", "a174", "a175", "a176", "a177", "a178", "a179", "a193", "a180", "a199", "a181",
+    "a200", "a182", "", "a201", "a183", "a184", "a197", "a185", "a194", "a198", "a186", "a195",
+    "a187", "a188", "a189", "a190", "a191",
+];
+
+/// One of the two symbolic standard fonts, whose glyphs are selected by codes of their own
+/// built-in encodings rather than by Latin characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolFont {
+    /// Greek letters and mathematical symbols, such as arrows, operators and bullets.
+    Symbol,
+
+    /// Ornaments, such as check marks, crosses, stars, pointing hands and circled numbers.
+    ZapfDingbats,
+}
+
+impl SymbolFont {
+    /// Returns the name of the standard font, used as the base font of a font drawing its glyphs.
+    pub fn base_font(self) -> &'static str {
+        match self {
+            SymbolFont::Symbol => "Symbol",
+            SymbolFont::ZapfDingbats => "ZapfDingbats",
+        }
+    }
+
+    /// Returns the symbolic standard font with the given base font name, if it is one.
+    pub(crate) fn of(base_font: &[u8]) -> Option<Self> {
+        match base_font {
+            b"Symbol" => Some(SymbolFont::Symbol),
+            b"ZapfDingbats" => Some(SymbolFont::ZapfDingbats),
+            _ => None,
+        }
+    }
+
+    /// Returns the name of the glyph with the given code in the built-in encoding of this font, or
+    /// `None` if the code is unused.
+    pub(crate) fn glyph_name(self, code: u8) -> Option<&'static str> {
+        let (low, high) = match self {
+            SymbolFont::Symbol => (&SYMBOL_LOW, &SYMBOL_HIGH),
+            SymbolFont::ZapfDingbats => (&DINGBATS_LOW, &DINGBATS_HIGH),
+        };
+
+        let name = match code {
+            0x20..=0x7E => low[(code - 0x20) as usize],
+            0xA1..=0xFE => high[(code - 0xA1) as usize],
+            _ => return None,
+        };
+
+        (!name.is_empty()).then_some(name)
+    }
+}
+
+/// A glyph of one of the symbolic standard fonts, selected by its code in the built-in encoding of
+/// the font or by its glyph name. Appended to a [`TextRun`] with [`TextRun::glyph`], and drawn with
+/// a font created with [`Document::create_symbol_font`] for the [`SymbolFont`] of the glyph.
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{
+/// #     Document,
+/// #     types::hierarchy::{
+/// #         content::{text::Text, text_run::TextRun},
+/// #         primitives::{
+/// #             rectangle::Position,
+/// #             symbol::{SymbolFont, SymbolGlyph},
+/// #         },
+/// #     },
+/// # };
+/// let mut document = Document::default();
+/// let dingbats = document.create_symbol_font(SymbolFont::ZapfDingbats);
+///
+/// // a check mark followed by the circled number one
+/// let circled_one = SymbolGlyph::by_name(SymbolFont::ZapfDingbats, "a120").unwrap();
+/// let run = TextRun::new().glyph(SymbolGlyph::CHECK_MARK).glyph(circled_one);
+///
+/// let page = document.create_page();
+/// let txt = Text::builder()
+///     .with_run(run)
+///     .at(Position::from_mm(20.0, 20.0))
+///     .build();
+/// page.add_text(txt, dingbats);
+/// ```
+///
+/// [`TextRun`]: crate::types::hierarchy::content::text_run::TextRun
+/// [`TextRun::glyph`]: crate::types::hierarchy::content::text_run::TextRun::glyph
+/// [`Document::create_symbol_font`]: crate::Document::create_symbol_font
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SymbolGlyph {
+    /// Font the glyph belongs to.
+    font: SymbolFont,
+
+    /// Code of the glyph in the built-in encoding of its font.
+    code: u8,
+}
+
+impl SymbolGlyph {
+    /// Check mark (✓) of ZapfDingbats, `a19`.
+    pub const CHECK_MARK: Self = Self::new(SymbolFont::ZapfDingbats, 0x33);
+    /// Heavy check mark (✔) of ZapfDingbats, `a20`.
+    pub const HEAVY_CHECK_MARK: Self = Self::new(SymbolFont::ZapfDingbats, 0x34);
+    /// Ballot X (✗) of ZapfDingbats, `a23`.
+    pub const BALLOT_X: Self = Self::new(SymbolFont::ZapfDingbats, 0x37);
+    /// Heavy ballot X (✘) of ZapfDingbats, `a24`.
+    pub const HEAVY_BALLOT_X: Self = Self::new(SymbolFont::ZapfDingbats, 0x38);
+    /// Black star (★) of ZapfDingbats, `a35`.
+    pub const BLACK_STAR: Self = Self::new(SymbolFont::ZapfDingbats, 0x48);
+    /// Black circle (●) of ZapfDingbats, `a71`.
+    pub const BLACK_CIRCLE: Self = Self::new(SymbolFont::ZapfDingbats, 0x6C);
+    /// Black square (■) of ZapfDingbats, `a73`.
+    pub const BLACK_SQUARE: Self = Self::new(SymbolFont::ZapfDingbats, 0x6E);
+    /// Black diamond (◆) of ZapfDingbats, `a78`.
+    pub const BLACK_DIAMOND: Self = Self::new(SymbolFont::ZapfDingbats, 0x75);
+    /// Bullet (•) of Symbol, `bullet`.
+    pub const BULLET: Self = Self::new(SymbolFont::Symbol, 0xB7);
+    /// Leftwards arrow (←) of Symbol, `arrowleft`.
+    pub const ARROW_LEFT: Self = Self::new(SymbolFont::Symbol, 0xAC);
+    /// Upwards arrow (↑) of Symbol, `arrowup`.
+    pub const ARROW_UP: Self = Self::new(SymbolFont::Symbol, 0xAD);
+    /// Rightwards arrow (→) of Symbol, `arrowright`.
+    pub const ARROW_RIGHT: Self = Self::new(SymbolFont::Symbol, 0xAE);
+    /// Downwards arrow (↓) of Symbol, `arrowdown`.
+    pub const ARROW_DOWN: Self = Self::new(SymbolFont::Symbol, 0xAF);
+
+    /// Creates a new `SymbolGlyph` of the given font, with a code known to be used.
+    const fn new(font: SymbolFont, code: u8) -> Self {
+        Self { font, code }
+    }
+
+    /// Returns the glyph with the given code in the built-in encoding of the given font, or `None`
+    /// if the font has no glyph with this code.
+    pub fn by_code(font: SymbolFont, code: u8) -> Option<Self> {
+        font.glyph_name(code).map(|_| Self::new(font, code))
+    }
+
+    /// Returns the glyph of the given font with the given glyph name, e.g. `arrowright` of Symbol
+    /// or `a20` of ZapfDingbats, or `None` if the font has no glyph with this name.
+    pub fn by_name(font: SymbolFont, name: &str) -> Option<Self> {
+        (0x20..=0xFE)
+            .find(|code| font.glyph_name(*code) == Some(name))
+            .map(|code| Self::new(font, code))
+    }
+
+    /// Returns the font this `SymbolGlyph` belongs to.
+    pub fn font(&self) -> SymbolFont {
+        self.font
+    }
+
+    /// Returns the code of this `SymbolGlyph` in the built-in encoding of its font.
+    pub fn code(&self) -> u8 {
+        self.code
+    }
+
+    /// Returns the glyph name of this `SymbolGlyph`.
+    pub fn name(&self) -> &'static str {
+        self.font.glyph_name(self.code).unwrap_or_default()
+    }
+
+    /// Returns the character that is encoded with the code of this `SymbolGlyph`. Strings are shown
+    /// in WinAnsiEncoding, which encodes the characters `U+0020..=U+007E` and `U+00A1..=U+00FE` with
+    /// their code points, i.e. with all codes the symbolic fonts use.
+    pub(crate) fn as_char(&self) -> char {
+        char::from(self.code)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SymbolFont, SymbolGlyph};
+
+    #[test]
+    fn glyph_names() {
+        let glyphs = [
+            (SymbolGlyph::CHECK_MARK, "a19"),
+            (SymbolGlyph::HEAVY_BALLOT_X, "a24"),
+            (SymbolGlyph::BLACK_STAR, "a35"),
+            (SymbolGlyph::BLACK_CIRCLE, "a71"),
+            (SymbolGlyph::BLACK_DIAMOND, "a78"),
+            (SymbolGlyph::BULLET, "bullet"),
+            (SymbolGlyph::ARROW_RIGHT, "arrowright"),
+        ];
+
+        for (glyph, name) in glyphs {
+            assert_eq!(glyph.name(), name);
+            assert_eq!(SymbolGlyph::by_name(glyph.font(), name), Some(glyph));
+        }
+    }
+
+    #[test]
+    fn glyph_codes() {
+        let alpha = SymbolGlyph::by_code(SymbolFont::Symbol, b'a').unwrap();
+        assert_eq!(alpha.name(), "alpha");
+        assert_eq!(alpha.as_char(), 'a');
+
+        let arrow = SymbolGlyph::by_name(SymbolFont::ZapfDingbats, "a161").unwrap();
+        assert_eq!(arrow.code(), 0xD5);
+        assert_eq!(SymbolGlyph::by_name(SymbolFont::Symbol, "a161"), None);
+
+        // unused codes and codes outside of the printable ranges have no glyph
+        assert_eq!(SymbolGlyph::by_code(SymbolFont::Symbol, 0xF0), None);
+        assert_eq!(SymbolGlyph::by_code(SymbolFont::ZapfDingbats, 0x0A), None);
+    }
+}
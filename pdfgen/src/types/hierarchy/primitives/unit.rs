@@ -8,73 +8,83 @@ use std::{
 /// Internal representation options for the [`Unit`] type. By default, the default user space unit
 /// is 1/72th of an inch. `Inner` allows us to use other measurement units for the value and to
 /// convert between them.
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+///
+/// Values are stored as [`f64`] in the unit they were created with, and only converted into
+/// default user space units when needed, so that chained conversions don't accumulate errors.
+#[derive(Debug, Clone, Copy)]
 enum Inner {
     /// Size that is equivalent to default user space unit converted into milimeters.
-    Mm(f32),
+    Mm(f64),
 
     /// Size that is equivalent to default user space unit converted into centimeters.
-    Cm(f32),
+    Cm(f64),
 
     /// Size that is equivalent to default user space unit converted into inches.
-    In(f32),
+    In(f64),
+
+    /// Size in points, which is exactly the default user space unit.
+    Pt(f64),
 }
 
 impl Inner {
-    /// Converts the `Unit` into default user space unit to be specified in a PDF document,
-    /// regardless of how this `Unit` is currently internally represented.
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # use pdfgen::types::hierarchy::primitives::unit::Unit;
-    /// let unit = Unit::from_inch(1.0);
-    /// assert_eq!(unit.into_user_unit(), 72.0);
-    /// ```
-    pub const fn into_user_unit(self) -> f32 {
-        match self {
-            Inner::Mm(_) => self.into_inch().into_user_unit(),
-            Inner::Cm(_) => self.into_inch().into_user_unit(),
-            // by default 1 user space unit is 1/72th of an inch
-            Inner::In(inch) => inch * 72.0,
-        }
-    }
+    /// Number of default user space units (points) in one inch.
+    const POINTS_PER_INCH: f64 = 72.0;
 
-    /// Converts the internal representation of the `Unit` into inch representation. This is
-    /// useful, becuase the default user space unit is 1/72th of an inch, and we intend to keep the
-    /// default representation.
-    const fn into_inch(self) -> Self {
+    /// Number of milimeters in one inch.
+    const MM_PER_INCH: f64 = 25.4;
+
+    /// Converts the `Inner` representation into default user space units (points), regardless
+    /// of how it is currently represented.
+    const fn into_points(self) -> f64 {
         match self {
-            Inner::Mm(mm) => Self::In(mm / 25.4),
-            Inner::Cm(cm) => Self::Mm(cm * 10_f32).into_inch(),
-            Inner::In(_) => self,
+            Inner::Mm(mm) => mm / Self::MM_PER_INCH * Self::POINTS_PER_INCH,
+            Inner::Cm(cm) => cm * 10.0 / Self::MM_PER_INCH * Self::POINTS_PER_INCH,
+            // by default 1 user space unit is 1/72th of an inch
+            Inner::In(inch) => inch * Self::POINTS_PER_INCH,
+            Inner::Pt(pt) => pt,
         }
     }
 }
 
 impl Display for Inner {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let unit = self.into_user_unit();
+        // NOTE: PDF readers are not required to handle more precision than single precision
+        //       floating point numbers, so we write the shortest representation of the `f32`
+        //       value.
+        let unit = self.into_points() as f32;
         f.write_fmt(format_args!("{unit}"))
     }
 }
 
 /// `Unit` represents a value that is used for various options in PDF where the default user space
 /// unit is required.
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+///
+/// `Unit`s are compared by the length they represent in default user space units, with the
+/// precision they are written into the document with, regardless of the unit they were created
+/// with. This way `Unit::from_mm(25.4)` equals `Unit::from_inch(1.0)`, even though `25.4` can't be
+/// represented exactly as [`f32`].
+#[derive(Debug, Clone, Copy)]
 pub struct Unit {
     inner: Inner,
 }
 
+impl PartialEq for Unit {
+    fn eq(&self, other: &Self) -> bool {
+        self.into_user_unit() == other.into_user_unit()
+    }
+}
+
+impl PartialOrd for Unit {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.into_user_unit().partial_cmp(&other.into_user_unit())
+    }
+}
+
 impl Add for Unit {
     type Output = Unit;
 
     fn add(self, rhs: Self) -> Self::Output {
-        let this = self.into_user_unit();
-        let other = rhs.into_user_unit();
-        let result = this + other;
-
-        Unit::from_unit(result)
+        Unit::from_pt(self.into_points() + rhs.into_points())
     }
 }
 
@@ -82,11 +92,7 @@ impl Sub for Unit {
     type Output = Unit;
 
     fn sub(self, rhs: Self) -> Self::Output {
-        let this = self.into_user_unit();
-        let other = rhs.into_user_unit();
-        let result = this - other;
-
-        Unit::from_unit(result)
+        Unit::from_pt(self.into_points() - rhs.into_points())
     }
 }
 
@@ -102,7 +108,7 @@ impl Unit {
     /// ```
     pub const fn from_mm(mm: f32) -> Self {
         Self {
-            inner: Inner::Mm(mm),
+            inner: Inner::Mm(mm as f64),
         }
     }
 
@@ -117,7 +123,7 @@ impl Unit {
     /// ```
     pub const fn from_cm(cm: f32) -> Self {
         Self {
-            inner: Inner::Cm(cm),
+            inner: Inner::Cm(cm as f64),
         }
     }
 
@@ -132,14 +138,44 @@ impl Unit {
     /// ```
     pub const fn from_inch(inch: f32) -> Self {
         Self {
-            inner: Inner::In(inch),
+            inner: Inner::In(inch as f64),
         }
     }
 
     /// Creates a new `Unit` from the specified number of default user space units.
     pub const fn from_unit(unit: f32) -> Unit {
+        Self::from_pt(unit as f64)
+    }
+
+    /// Creates a new `Unit` from the exact number of points, which is the default user space
+    /// unit (1/72th of an inch).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::primitives::unit::Unit;
+    /// let unit = Unit::from_pt(595.2756);
+    /// assert_eq!(unit.into_points(), 595.2756);
+    /// ```
+    pub const fn from_pt(pt: f64) -> Unit {
         Self {
-            inner: Inner::In(unit / 72.0),
+            inner: Inner::Pt(pt),
+        }
+    }
+
+    /// Creates a new `Unit` from the exact number of milimeters, without loss of precision for
+    /// values that can't be represented as [`f32`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::primitives::unit::Unit;
+    /// let unit = Unit::from_mm_f64(25.4);
+    /// assert_eq!(unit.into_points(), 72.0);
+    /// ```
+    pub const fn from_mm_f64(mm: f64) -> Unit {
+        Self {
+            inner: Inner::Mm(mm),
         }
     }
 
@@ -154,25 +190,21 @@ impl Unit {
     /// assert_eq!(unit.into_user_unit(), 72.0);
     /// ```
     pub const fn into_user_unit(self) -> f32 {
-        match self.inner {
-            Inner::Mm(_) => self.into_inch().into_user_unit(),
-            Inner::Cm(_) => self.into_inch().into_user_unit(),
-            // by default 1 user space unit is 1/72th of an inch
-            Inner::In(inch) => inch * 72.0,
-        }
+        self.into_points() as f32
     }
 
-    /// Converts the internal representation of the `Unit` into inch representation. This is
-    /// useful, becuase the default user space unit is 1/72th of an inch, and we intend to keep the
-    /// default representation.
-    const fn into_inch(mut self) -> Self {
-        self.inner = match self.inner {
-            Inner::Mm(mm) => Inner::In(mm / 25.4),
-            Inner::Cm(cm) => Inner::Mm(cm * 10_f32).into_inch(),
-            Inner::In(_) => self.inner,
-        };
-
-        self
+    /// Converts the `Unit` into the exact number of points (default user space units),
+    /// regardless of how this `Unit` is currently internally represented.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use pdfgen::types::hierarchy::primitives::unit::Unit;
+    /// let unit = Unit::from_mm(210.0);
+    /// assert!((unit.into_points() - 595.2756).abs() < 0.0001);
+    /// ```
+    pub const fn into_points(self) -> f64 {
+        self.inner.into_points()
     }
 }
 
@@ -181,3 +213,48 @@ impl Display for Unit {
         self.inner.fmt(f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Unit;
+
+    #[test]
+    fn exact_conversions() {
+        assert_eq!(Unit::from_inch(1.0).into_points(), 72.0);
+        assert_eq!(Unit::from_mm(25.4).into_user_unit(), 72.0);
+        assert_eq!(Unit::from_cm(2.54).into_user_unit(), 72.0);
+        assert_eq!(Unit::from_pt(12.5).into_points(), 12.5);
+    }
+
+    #[test]
+    fn a4_in_points() {
+        let width = Unit::from_mm(210.0);
+        let height = Unit::from_mm(297.0);
+
+        assert_eq!(width.to_string(), "595.2756");
+        assert_eq!(height.to_string(), "841.8898");
+    }
+
+    #[test]
+    fn compare_across_units() {
+        assert_eq!(Unit::from_mm(25.4), Unit::from_inch(1.0));
+        assert_eq!(Unit::from_cm(2.54), Unit::from_pt(72.0));
+        assert_eq!(Unit::from_mm(10.0), Unit::from_cm(1.0));
+
+        assert!(Unit::from_mm(1000.0) > Unit::from_pt(1.0));
+        assert!(Unit::from_pt(1.0) < Unit::from_mm(1.0));
+        assert!(Unit::from_cm(3.0) > Unit::from_inch(1.0));
+        assert!(Unit::from_inch(1.0) < Unit::from_mm(26.0));
+        assert!(Unit::from_pt(73.0) > Unit::from_cm(2.54));
+    }
+
+    #[test]
+    fn no_accumulated_error() {
+        let mut sum = Unit::from_pt(0.0);
+        for _ in 0..1000 {
+            sum = sum + Unit::from_mm(0.1);
+        }
+
+        assert!((sum.into_points() - Unit::from_mm(100.0).into_points()).abs() < 1e-4);
+    }
+}
@@ -9,6 +9,7 @@ use crate::{ObjId, types::constants};
 use super::{
     catalog::Catalog,
     cross_reference_table::CrossReferenceTable,
+    metadata::Info,
     primitives::{array::WriteArray, identifier::Identifier},
 };
 
@@ -22,6 +23,7 @@ pub trait WriteTrailer {
         offset: usize,
         size: usize,
         root: ObjId<Catalog>,
+        info: Option<&ObjId<Info>>,
         id: [u8; 16],
     ) -> Result<(), std::io::Error>;
 }
@@ -33,11 +35,13 @@ impl WriteTrailer for CrossReferenceTable {
         offset: usize,
         size: usize,
         root: ObjId<Catalog>,
+        info: Option<&ObjId<Info>>,
         id: [u8; 16],
     ) -> Result<(), std::io::Error> {
         const_identifiers! {
             SIZE,
             ROOT,
+            INFO,
             ID: b"ID",
         }
 
@@ -61,6 +65,13 @@ impl WriteTrailer for CrossReferenceTable {
             ROOT.write(writer),
             root.write_ref(writer),
             writer.write(constants::NL_MARKER),
+            // Info
+            if let Some(info) = info {
+                writer.write(indent),
+                INFO.write(writer),
+                info.write_ref(writer),
+                writer.write(constants::NL_MARKER),
+            },
             // ID
             writer.write(indent),
             ID.write(writer),
@@ -1,16 +1,29 @@
 //! Implementation of the [`PdfWriter`] wrapper.
 
-use crate::{IdManager, ObjId};
+use crate::{FileId, Handle, IdManager, LineEnding, ObjId, OutputPolicy, PdfVersion};
 
 use super::{
     constants,
     hierarchy::{
-        catalog::Catalog, cross_reference_table::CrossReferenceTable, primitives::object::Object,
+        catalog::Catalog,
+        content::{
+            ContentStream,
+            image::SharedImage,
+            page_form::PageForm,
+            watermark::{Watermark, WatermarkIds, WatermarkStamp},
+        },
+        cross_reference_table::CrossReferenceTable,
+        form::InteractiveForm,
+        metadata::Info,
+        primitives::object::Object,
         trailer::WriteTrailer,
     },
     page::Page,
 };
-use std::io::{self, Write};
+use std::{
+    collections::BTreeMap,
+    io::{self, Write},
+};
 
 /// A wrapper around any type that implements [`Write`], adding pdf specific functionality to keep a
 /// clear and consistent CrossReferenceTable state
@@ -22,12 +35,12 @@ pub struct PdfWriter<W: Write> {
     /// CrossReferenceTable member, representing the current state of the cross_reference_table
     /// for the document
     cross_reference_table: CrossReferenceTable,
+    /// Object numbers of the written objects, together with the index of their byte offset in the
+    /// `cross_reference_table`. Objects written as part of a page or watermark are not included.
+    object_numbers: Vec<(u64, usize)>,
 }
 
 impl<W: Write> PdfWriter<W> {
-    /// The PDF file begins with the 5 characters “%PDF–X.X” and byte offsets shall be calculated
-    /// from the PERCENT SIGN.
-    const PDF_HEADER: &[u8] = b"%PDF-2.0";
     /// The last line of the file shall contain only the end-of-file marker, %%EOF
     const EOF_MARKER: &[u8] = b"%%EOF";
 
@@ -38,26 +51,46 @@ impl<W: Write> PdfWriter<W> {
             // NOTE: The current byte is included in offset.
             current_offset: 1,
             cross_reference_table: CrossReferenceTable::default(),
+            object_numbers: Vec::new(),
         }
     }
 
-    /// Write the PDF documents header marker updating the `cursor`s byte offset with the number of
-    /// bytes written.
-    pub fn write_header(&mut self) -> Result<(), io::Error> {
+    /// Write the PDF documents header marker of the given [`PdfVersion`] updating the `cursor`s
+    /// byte offset with the number of bytes written. The PDF file begins with the 5 characters
+    /// “%PDF–X.X” and byte offsets shall be calculated from the PERCENT SIGN. The header is
+    /// followed by the binary comment line, if required by the given [`OutputPolicy`].
+    pub fn write_header(
+        &mut self,
+        version: PdfVersion,
+        policy: OutputPolicy,
+    ) -> Result<(), io::Error> {
+        let eol = policy.line_ending().marker();
+
         // Delegate the actual writing to the inner writer incrementing the current_offset to
         // reflect current `cursor` position.
-        self.current_offset += self.inner.write(Self::PDF_HEADER)?;
-        self.current_offset += self.inner.write(constants::NL_MARKER)?;
+        self.current_offset += self.inner.write(version.header())?;
+        self.current_offset += self.inner.write(eol)?;
+
+        if let Some(binary_comment) = policy.binary_comment() {
+            self.current_offset += self.inner.write(binary_comment)?;
+            self.current_offset += self.inner.write(eol)?;
+        }
 
         Ok(())
     }
 
     /// Writes the object start marker(`X X obj`), following with the structured data of the object
     /// itself, finalizing with object end marker(`endobj`), ensuring correct CrossReferenceTable
-    /// and cursor update.
-    pub(crate) fn write_object(&mut self, obj: &dyn Object) -> Result<(), io::Error> {
+    /// and cursor update. The object is recorded under the given [`ObjId`], which is its own.
+    pub(crate) fn write_object<T>(
+        &mut self,
+        id: &ObjId<T>,
+        obj: &dyn Object,
+    ) -> Result<(), io::Error> {
         // Save the objects byte offset in the CrossReferenceTable.
         self.cross_reference_table.add_object(self.current_offset);
+        self.object_numbers
+            .push((id.number(), self.cross_reference_table.len() - 1));
 
         // X Y obj\n
         self.current_offset += obj.write_def(&mut self.inner)?;
@@ -74,21 +107,43 @@ impl<W: Write> PdfWriter<W> {
         Ok(())
     }
 
-    /// Writes the cross reference table contents.
-    pub fn write_crt(&mut self) -> Result<(), io::Error> {
-        self.cross_reference_table.write(&mut self.inner)?;
+    /// Returns the number of bytes each written object takes up in the output, including the
+    /// whitespace following it, by object number. Must be called before the cross reference table
+    /// is written.
+    pub(crate) fn object_sizes(&self) -> BTreeMap<u64, usize> {
+        let offsets = self.cross_reference_table.offsets();
+
+        self.object_numbers
+            .iter()
+            .map(|&(number, idx)| {
+                let end = offsets.get(idx + 1).copied().unwrap_or(self.current_offset);
+                (number, end - offsets[idx])
+            })
+            .collect()
+    }
+
+    /// Writes the cross reference table contents, with lines ending in the given [`LineEnding`].
+    pub fn write_crt(&mut self, line_ending: LineEnding) -> Result<(), io::Error> {
+        self.cross_reference_table
+            .write(&mut self.inner, line_ending)?;
 
         Ok(())
     }
 
-    /// Writes the trailer for the PdfWriter's CRT.
-    pub fn write_trailer(&mut self, root: ObjId<Catalog>) -> Result<(), io::Error> {
+    /// Writes the trailer for the PdfWriter's CRT, identifying the file with the given [`FileId`].
+    pub fn write_trailer(
+        &mut self,
+        root: ObjId<Catalog>,
+        info: Option<&ObjId<Info>>,
+        file_id: &FileId,
+    ) -> Result<(), io::Error> {
         self.cross_reference_table.write_trailer(
             &mut self.inner,
             self.current_offset,
             self.cross_reference_table.len(),
             root,
-            self.cross_reference_table.offsets_hash()?,
+            info,
+            file_id.compute(&self.cross_reference_table)?,
         )?;
 
         Ok(())
@@ -105,10 +160,105 @@ impl<W: Write> PdfWriter<W> {
         &mut self,
         page: &Page,
         id_manager: &mut IdManager,
+        watermark: Option<&WatermarkStamp>,
+        print_marks: Option<&ContentStream>,
+    ) -> Result<(), io::Error> {
+        self.cross_reference_table.add_object(self.current_offset);
+        self.object_numbers.push((
+            page.id().object_number(),
+            self.cross_reference_table.len() - 1,
+        ));
+
+        let (bytes_written, offsets) =
+            page.write(&mut self.inner, id_manager, watermark, print_marks)?;
+
+        for offset in offsets {
+            self.cross_reference_table
+                .add_object(self.current_offset + offset);
+        }
+
+        self.current_offset += bytes_written;
+
+        Ok(())
+    }
+
+    /// Writes the watermark form XObject and the objects it references into the PDF document.
+    pub(crate) fn write_watermark(
+        &mut self,
+        watermark: &Watermark,
+        ids: &WatermarkIds,
+    ) -> Result<(), io::Error> {
+        self.cross_reference_table.add_object(self.current_offset);
+
+        let (bytes_written, offsets) = watermark.write(&mut self.inner, ids)?;
+
+        for offset in offsets {
+            self.cross_reference_table
+                .add_object(self.current_offset + offset);
+        }
+
+        self.current_offset += bytes_written;
+
+        Ok(())
+    }
+
+    /// Writes the given [`PageForm`] and the objects of the resources it owns into the PDF
+    /// document.
+    pub(crate) fn write_page_form(
+        &mut self,
+        form: &PageForm,
+        id_manager: &mut IdManager,
+    ) -> Result<(), io::Error> {
+        self.cross_reference_table.add_object(self.current_offset);
+        self.object_numbers.push((
+            form.obj_ref().number(),
+            self.cross_reference_table.len() - 1,
+        ));
+
+        let (bytes_written, offsets) = form.write(&mut self.inner, id_manager)?;
+
+        for offset in offsets {
+            self.cross_reference_table
+                .add_object(self.current_offset + offset);
+        }
+
+        self.current_offset += bytes_written;
+
+        Ok(())
+    }
+
+    /// Writes the given [`SharedImage`] together with its mask into the PDF document.
+    pub(crate) fn write_shared_image(&mut self, image: &SharedImage) -> Result<(), io::Error> {
+        self.cross_reference_table.add_object(self.current_offset);
+        self.object_numbers
+            .push((image.id.number(), self.cross_reference_table.len() - 1));
+
+        let (bytes_written, offsets) = image.write(&mut self.inner)?;
+
+        for offset in offsets {
+            self.cross_reference_table
+                .add_object(self.current_offset + offset);
+        }
+
+        self.current_offset += bytes_written;
+
+        Ok(())
+    }
+
+    /// Writes the given [`InteractiveForm`] together with its fields, widgets and their
+    /// appearances into the PDF document.
+    pub(crate) fn write_interactive_form(
+        &mut self,
+        form: &InteractiveForm,
+        id_manager: &mut IdManager,
     ) -> Result<(), io::Error> {
         self.cross_reference_table.add_object(self.current_offset);
+        self.object_numbers.push((
+            form.obj_ref().number(),
+            self.cross_reference_table.len() - 1,
+        ));
 
-        let (bytes_written, offsets) = page.write(&mut self.inner, id_manager)?;
+        let (bytes_written, offsets) = form.write(&mut self.inner, id_manager)?;
 
         for offset in offsets {
             self.cross_reference_table
@@ -124,7 +274,7 @@ impl<W: Write> PdfWriter<W> {
 #[cfg(test)]
 mod tests {
     use crate::{
-        IdManager, ObjId,
+        FileId, IdManager, LineEnding, ObjId, OutputPolicy, PdfVersion,
         types::{constants, pdf_writer::PdfWriter},
     };
 
@@ -151,7 +301,9 @@ mod tests {
         let mut writer = Vec::new();
         let mut pdf_writer = PdfWriter::new(&mut writer);
 
-        pdf_writer.write_header().unwrap();
+        pdf_writer
+            .write_header(PdfVersion::default(), OutputPolicy::default())
+            .unwrap();
 
         let output = String::from_utf8(writer).unwrap();
 
@@ -183,7 +335,7 @@ mod tests {
         let mut id_manager = IdManager::new();
 
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
 
         let output = String::from_utf8(writer).unwrap();
 
@@ -204,17 +356,19 @@ mod tests {
         let mut pdf_writer = PdfWriter::new(&mut writer);
         let mut id_manager = IdManager::new();
 
-        pdf_writer.write_header().unwrap();
+        pdf_writer
+            .write_header(PdfVersion::default(), OutputPolicy::default())
+            .unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
 
-        pdf_writer.write_crt().unwrap();
+        pdf_writer.write_crt(LineEnding::default()).unwrap();
         pdf_writer.write_eof().unwrap();
 
         let output = String::from_utf8(writer).unwrap();
@@ -260,17 +414,21 @@ mod tests {
         let mut pdf_writer = PdfWriter::new(&mut writer);
         let mut id_manager = IdManager::new();
 
-        pdf_writer.write_header().unwrap();
+        pdf_writer
+            .write_header(PdfVersion::default(), OutputPolicy::default())
+            .unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
         let dummy = Dummy(id_manager.create_id());
-        pdf_writer.write_object(&dummy).unwrap();
-        pdf_writer.write_crt().unwrap();
-        pdf_writer.write_trailer(id_manager.create_id()).unwrap();
+        pdf_writer.write_object(&dummy.0, &dummy).unwrap();
+        pdf_writer.write_crt(LineEnding::default()).unwrap();
+        pdf_writer
+            .write_trailer(id_manager.create_id(), None, &FileId::default())
+            .unwrap();
         pdf_writer.write_eof().unwrap();
 
         let output = String::from_utf8(writer).unwrap();
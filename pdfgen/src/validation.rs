@@ -0,0 +1,337 @@
+//! Validation of a PDF [`Document`] before it's written, collecting potential issues into a
+//! [`ValidationReport`].
+//!
+//! [`Document`]: crate::Document
+
+use crate::types::hierarchy::{content::color::Color, primitives::font::Font};
+
+/// A single issue found while validating a [`Document`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Issue {
+    /// The contrast ratio between the text color and the background color is below the minimum
+    /// configured in the [`ColorLint`].
+    LowContrast {
+        /// Index of the page on which the text is drawn.
+        page: usize,
+
+        /// Color of the text.
+        foreground: Color,
+
+        /// Color of the background the text is drawn on.
+        background: Color,
+
+        /// The computed contrast ratio, in range `[1.0, 21.0]`.
+        ratio: f32,
+    },
+
+    /// The RGB color can't be reproduced within the [`CmykGamut`] configured in the
+    /// [`ColorLint`].
+    OutOfGamut {
+        /// Index of the page on which the color is used.
+        page: usize,
+
+        /// The offending color.
+        color: Color,
+
+        /// Total ink coverage in percent, required to reproduce the color in CMYK.
+        total_ink: f32,
+    },
+
+    /// The font is not embedded and isn't one of the 14 standard Type 1 fonts, so PDF processors
+    /// might substitute it with a different font.
+    UnembeddedFont {
+        /// Name of the font that is not embedded.
+        base_font: String,
+    },
+
+    /// The font of a text has no glyphs for some of its characters, which are shown as `.notdef`
+    /// boxes instead.
+    MissingGlyphs {
+        /// Index of the page on which the text is drawn.
+        page: usize,
+
+        /// Name of the font the text is drawn with.
+        base_font: String,
+
+        /// Content of the text.
+        text: String,
+
+        /// Characters of the text not covered by the font, in order of their first occurrence.
+        missing: Vec<char>,
+    },
+}
+
+/// Collection of all [`Issue`]s found while validating a [`Document`].
+///
+/// [`Document`]: crate::Document
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ValidationReport {
+    issues: Vec<Issue>,
+}
+
+impl ValidationReport {
+    /// Adds an [`Issue`] to this `ValidationReport`.
+    pub(crate) fn push(&mut self, issue: Issue) {
+        self.issues.push(issue);
+    }
+
+    /// Returns all [`Issue`]s found during validation.
+    pub fn issues(&self) -> &[Issue] {
+        &self.issues
+    }
+
+    /// Returns `true` if no [`Issue`]s were found during validation.
+    pub fn is_empty(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+/// Checks that the given [`Font`] covers every character of the text drawn with it on the page
+/// with the given index, and adds an [`Issue`] with the missing characters into the
+/// [`ValidationReport`] otherwise.
+pub(crate) fn check_glyph_coverage(
+    page: usize,
+    font: &Font,
+    text: &str,
+    report: &mut ValidationReport,
+) {
+    let missing = font.unencodable(text);
+    if !missing.is_empty() {
+        report.push(Issue::MissingGlyphs {
+            page,
+            base_font: font.base_font(),
+            text: text.to_string(),
+            missing,
+        });
+    }
+}
+
+/// Describes the CMYK gamut of the output device by limiting the total ink coverage, which is the
+/// sum of all four process colourants in percent (maximum being 400%).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CmykGamut {
+    /// Maximum allowed total ink coverage in percent.
+    total_ink_limit: f32,
+}
+
+impl CmykGamut {
+    /// Creates a new `CmykGamut` with the given total ink limit in percent.
+    pub fn with_total_ink_limit(total_ink_limit: f32) -> Self {
+        Self { total_ink_limit }
+    }
+
+    /// Returns the total ink coverage in percent required to reproduce the given color in CMYK.
+    fn total_ink(color: Color) -> f32 {
+        let (cyan, magenta, yellow, black) = match color {
+            Color::Rgb { red, green, blue } => {
+                let red = f32::from(red) / 255.;
+                let green = f32::from(green) / 255.;
+                let blue = f32::from(blue) / 255.;
+
+                let black = 1. - red.max(green).max(blue);
+                if black >= 1. {
+                    return 100.;
+                }
+
+                (
+                    (1. - red - black) / (1. - black),
+                    (1. - green - black) / (1. - black),
+                    (1. - blue - black) / (1. - black),
+                    black,
+                )
+            }
+            Color::Gray(gray) => (0., 0., 0., 1. - f32::from(gray) / 255.),
+            Color::Icc { .. }
+            | Color::CalGray { .. }
+            | Color::CalRgb { .. }
+            | Color::Lab { .. } => {
+                return Self::total_ink(color.to_device());
+            }
+            Color::CMYK {
+                cyan,
+                magenta,
+                yellow,
+                black,
+            } => (
+                f32::from(u8::from(cyan)) / 100.,
+                f32::from(u8::from(magenta)) / 100.,
+                f32::from(u8::from(yellow)) / 100.,
+                f32::from(u8::from(black)) / 100.,
+            ),
+        };
+
+        (cyan + magenta + yellow + black) * 100.
+    }
+}
+
+/// Optional lint checking the colors used in a [`Document`], flagging text colors with too low
+/// contrast against the background, and RGB colors that fall outside of the chosen
+/// [`CmykGamut`].
+///
+/// # Example
+///
+/// ```
+/// # use pdfgen::{Document, validation::{CmykGamut, ColorLint}};
+/// let lint = ColorLint::new()
+///     .with_min_contrast(7.0)
+///     .with_gamut(CmykGamut::with_total_ink_limit(240.0));
+///
+/// let document = Document::default();
+/// assert!(document.validate(&lint).is_empty());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorLint {
+    /// Minimum contrast ratio between text and background colors.
+    min_contrast: f32,
+
+    /// Color of the background that text is drawn on.
+    background: Color,
+
+    /// Gamut that RGB colors are checked against. No gamut check is done if `None`.
+    gamut: Option<CmykGamut>,
+}
+
+impl Default for ColorLint {
+    fn default() -> Self {
+        Self {
+            min_contrast: 4.5,
+            background: Color::Rgb {
+                red: 255,
+                green: 255,
+                blue: 255,
+            },
+            gamut: None,
+        }
+    }
+}
+
+impl ColorLint {
+    /// Creates a new `ColorLint` requiring a minimum contrast ratio of 4.5 against a white
+    /// background, without checking the gamut of colors.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the minimum contrast ratio between text and background colors.
+    pub fn with_min_contrast(mut self, min_contrast: f32) -> Self {
+        self.min_contrast = min_contrast;
+        self
+    }
+
+    /// Sets the color of the background that text is drawn on.
+    pub fn with_background(mut self, background: Color) -> Self {
+        self.background = background;
+        self
+    }
+
+    /// Sets the [`CmykGamut`] that RGB colors are checked against.
+    pub fn with_gamut(mut self, gamut: CmykGamut) -> Self {
+        self.gamut = Some(gamut);
+        self
+    }
+
+    /// Checks the given text color used on the page with the given index, and adds any found
+    /// [`Issue`]s into the [`ValidationReport`].
+    pub(crate) fn check_text_color(
+        &self,
+        page: usize,
+        color: Color,
+        report: &mut ValidationReport,
+    ) {
+        let ratio = color.contrast_ratio(self.background);
+        if ratio < self.min_contrast {
+            report.push(Issue::LowContrast {
+                page,
+                foreground: color,
+                background: self.background,
+                ratio,
+            });
+        }
+
+        if let (Some(gamut), Color::Rgb { .. }) = (self.gamut, color) {
+            let total_ink = CmykGamut::total_ink(color);
+            if total_ink > gamut.total_ink_limit {
+                report.push(Issue::OutOfGamut {
+                    page,
+                    color,
+                    total_ink,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        IdManager,
+        types::hierarchy::{content::color::Color, primitives::font::Font},
+    };
+
+    use super::{CmykGamut, ColorLint, Issue, ValidationReport, check_glyph_coverage};
+
+    #[test]
+    fn low_contrast() {
+        let lint = ColorLint::new();
+        let mut report = ValidationReport::default();
+
+        lint.check_text_color(0, Color::Gray(0), &mut report);
+        assert!(report.is_empty());
+
+        lint.check_text_color(1, Color::Gray(220), &mut report);
+        assert!(matches!(
+            report.issues(),
+            [Issue::LowContrast { page: 1, ratio, .. }] if *ratio < 4.5
+        ));
+    }
+
+    #[test]
+    fn out_of_gamut() {
+        let lint = ColorLint::new()
+            .with_min_contrast(1.0)
+            .with_gamut(CmykGamut::with_total_ink_limit(240.0));
+        let mut report = ValidationReport::default();
+
+        let red = Color::Rgb {
+            red: 255,
+            green: 0,
+            blue: 0,
+        };
+        lint.check_text_color(0, red, &mut report);
+        assert!(report.is_empty());
+
+        let dark_red = Color::Rgb {
+            red: 100,
+            green: 0,
+            blue: 0,
+        };
+        lint.check_text_color(0, dark_red, &mut report);
+        assert!(matches!(
+            report.issues(),
+            [Issue::OutOfGamut { total_ink, .. }] if *total_ink > 240.0
+        ));
+    }
+
+    #[test]
+    fn missing_glyphs() {
+        let font = Font::new(IdManager::new().create_id(), "Type1", "Helvetica");
+        let mut report = ValidationReport::default();
+
+        check_glyph_coverage(0, &font, "Plain (ASCII) text.", &mut report);
+        assert!(report.is_empty());
+
+        check_glyph_coverage(2, &font, "Grüße aus Wien – 5 €", &mut report);
+        assert_eq!(
+            report.issues(),
+            [Issue::MissingGlyphs {
+                page: 2,
+                base_font: "Helvetica".to_string(),
+                text: "Grüße aus Wien – 5 €".to_string(),
+                missing: vec!['ü', 'ß', '–', '€'],
+            }]
+        );
+    }
+}
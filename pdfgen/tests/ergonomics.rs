@@ -13,6 +13,7 @@ use pdfgen::{
 mod macros;
 
 #[test]
+#[cfg(feature = "image-decoding")]
 fn ergonomic_api() {
     // create document with title and default page size.
     // let mut doc = Document::new("Document title", Rectangle::A4);
@@ -43,6 +44,7 @@ fn ergonomic_api() {
 
     page.add_image(
         Image::from_file(&file)
+            .unwrap()
             .at(Position::from_units(40., 40.))
             .scaled(Position::from_units(120., 120.))
             // NOTE: not supported right now
@@ -1,15 +1,34 @@
+// NOTE: imports only used by tests which decode images
+#![cfg_attr(not(feature = "image-decoding"), allow(unused_imports))]
+
 use std::{fs::File, path::PathBuf};
 
 use pdfgen::{
-    Document,
+    Document, Fragment, Imposition, LabelSheet, Producer, Roll, SanitizePolicy, TextStyle,
     types::hierarchy::{
+        action::{Action, SubmitFormat},
+        annotation::{Annotation, LineEndingStyle},
         content::{
-            color::{CmykValue, Color},
+            color::{CalGray, CalRgb, CmykValue, Color, ColorSpace, D50, D65, Lab},
+            graphics_state::ExtGState,
             image::Image,
-            text::Text,
+            path::Path,
+            print_marks::PrintMarks,
+            recording::Recording,
+            text::{Text, TextAlignment, TextRenderingMode},
+            text_box::{Hyphenation, Overflow},
+            text_run::TextRun,
+            watermark::Watermark,
         },
+        destination::Destination,
+        document_part::DocumentPart,
+        form::{CheckBox, ChoiceField, PushButton, RadioGroup, TextField},
+        outline::OutlineItem,
+        page::Layer,
         primitives::{
+            encoding::FontEncoding,
             rectangle::{Position, Rectangle},
+            symbol::{SymbolFont, SymbolGlyph},
             unit::Unit,
         },
     },
@@ -48,6 +67,7 @@ fn three_pages_different_size() {
 }
 
 #[test]
+#[cfg(feature = "image-decoding")]
 fn page_with_image() {
     let page_size = 64.;
     let mut document = Document::builder()
@@ -59,6 +79,7 @@ fn page_with_image() {
     let img = Image::from_file(
         &File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg")).unwrap(),
     )
+    .unwrap()
     .at(Position::from_units(0., 0.))
     .build();
 
@@ -70,6 +91,7 @@ fn page_with_image() {
 }
 
 #[test]
+#[cfg(feature = "image-decoding")]
 fn page_image_moved() {
     let page_size = 128.;
     let mut document = Document::builder()
@@ -81,6 +103,7 @@ fn page_image_moved() {
     let img = Image::from_file(
         &File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg")).unwrap(),
     )
+    .unwrap()
     .at(Position::from_units(64. - 88. / 2., 13.))
     .build();
 
@@ -92,6 +115,7 @@ fn page_image_moved() {
 }
 
 #[test]
+#[cfg(feature = "image-decoding")]
 fn page_image_moved_and_scaled() {
     let page_size = 128.;
     let mut document = Document::builder()
@@ -103,6 +127,7 @@ fn page_image_moved_and_scaled() {
     let img = Image::from_file(
         &File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg")).unwrap(),
     )
+    .unwrap()
     .at(Position::from_units(64. - 88. / 2., 13.))
     .scaled(Position::from_units(88., 88.))
     .build();
@@ -271,3 +296,1157 @@ fn multi_color_space_text() {
 
     macros::snap_test!(document);
 }
+
+#[test]
+#[cfg(feature = "image-decoding")]
+fn transparent_text_over_image() {
+    let page_size = 128.;
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., page_size, page_size))
+        .build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    let img = Image::from_file(
+        &File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg")).unwrap(),
+    )
+    .unwrap()
+    .at(Position::from_units(0., 0.))
+    .scaled(Position::from_units(page_size, page_size))
+    .with_ext_g_state(ExtGState::new().with_fill_alpha(0.5))
+    .build();
+
+    page.add_image(img);
+
+    let txt = Text::builder()
+        .with_content("Half transparent")
+        .with_size(14)
+        .with_ext_g_state(ExtGState::new().with_fill_alpha(0.25))
+        .at(Position::from_units(10., 60.))
+        .build();
+
+    page.add_text(txt, font_id);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn watermark_on_all_pages() {
+    let mut document = Document::builder().with_page_size(Rectangle::A5).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    document.set_watermark(Watermark::text("CONFIDENTIAL", font_id.clone()).with_size(36));
+
+    let page = document.create_page();
+    let txt = Text::builder()
+        .with_content("Page with content")
+        .at(Position::from_mm(20., 180.))
+        .build();
+    page.add_text(txt, font_id);
+
+    document.create_page().set_mediabox(Rectangle::A6);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn page_layers() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    let txt = Text::builder()
+        .with_content("Ruled page")
+        .at(Position::from_mm(10., 130.))
+        .build();
+    page.add_text(txt, font_id.clone());
+
+    page.with_layer(Layer::Underlay, |page| {
+        page.fill_path(Rectangle::A6, Color::Gray(245));
+
+        let rules = (1..14).fold(Path::new(), |rules, line| {
+            let y = Unit::from_mm(line as f32 * 10.);
+            rules.rect(Rectangle::new(
+                Position::new(Unit::from_mm(10.), y),
+                Position::new(Unit::from_mm(95.), y + Unit::from_mm(0.2)),
+            ))
+        });
+        page.fill_path(rules, Color::Gray(180));
+    });
+
+    page.with_layer(Layer::Overlay, |page| {
+        let txt = Text::builder()
+            .with_content("Reviewed")
+            .with_color(Color::Rgb {
+                red: 200,
+                green: 0,
+                blue: 0,
+            })
+            .at(Position::from_mm(70., 10.))
+            .build();
+        page.add_text(txt, font_id);
+    });
+
+    macros::snap_test!(document);
+}
+
+#[test]
+#[cfg(feature = "image-decoding")]
+fn image_inside_text() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., 200., 64.))
+        .build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica-Bold".into());
+    let page = document.create_page();
+
+    let headline = Text::builder()
+        .with_content("PDF")
+        .with_size(60)
+        .with_rendering_mode(TextRenderingMode::Invisible)
+        .at(Position::from_units(10., 10.))
+        .build();
+
+    page.clip_to_text(headline, font_id, |page| {
+        let img = Image::from_file(
+            &File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg"))
+                .unwrap(),
+        )
+        .unwrap()
+        .at(Position::from_units(0., 0.))
+        .scaled(Position::from_units(200., 64.))
+        .build();
+
+        page.add_image(img);
+    });
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn document_parts() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+
+    for invoice in ["2025-0041", "2025-0042"] {
+        let mut page_ids = Vec::new();
+
+        for page_num in 1..=2 {
+            let page = document.create_page();
+            let txt = Text::builder()
+                .with_content(format!("Invoice {invoice}, page {page_num}"))
+                .at(Position::from_mm(10., 130.))
+                .build();
+            page.add_text(txt, font_id.clone());
+            page_ids.push(page.id());
+        }
+
+        let [first, last] = page_ids.try_into().unwrap();
+        let part = DocumentPart::new(first, last).with_metadata("InvoiceNumber", invoice);
+        document.add_document_part(part).unwrap();
+    }
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn document_outline() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+
+    let mut page_ids = Vec::new();
+    for heading in ["Introduction", "Installation", "Usage"] {
+        let page = document.create_page();
+        let txt = Text::builder()
+            .with_content(heading)
+            .at(Position::from_mm(10., 130.))
+            .build();
+        page.add_text(txt, font_id.clone());
+        page_ids.push(page.id());
+    }
+
+    let [intro, installation, usage] = page_ids.try_into().unwrap();
+    let intro = OutlineItem::new("Introduction", Destination::Fit { page: intro });
+    document.add_outline_item(intro).unwrap();
+
+    let guide = OutlineItem::new(
+        "Guide",
+        Destination::FitH {
+            page: installation.clone(),
+            top: None,
+        },
+    )
+    .with_bold(true)
+    .with_open(true)
+    .with_child(OutlineItem::new(
+        "Installation",
+        Destination::Fit { page: installation },
+    ))
+    .with_child(
+        OutlineItem::new("Usage", Destination::Fit { page: usage }).with_color(Color::Rgb {
+            red: 0,
+            green: 0,
+            blue: 255,
+        }),
+    );
+    document.add_outline_item(guide).unwrap();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn print_marks_on_all_pages() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., 300., 400.))
+        .build();
+    document.set_print_marks(PrintMarks::default());
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    for page_num in 1..=2 {
+        let page = document.create_page();
+        page.set_trim_box(Rectangle::from_units(40., 40., 260., 360.));
+
+        let txt = Text::builder()
+            .with_content(format!("Page {page_num}"))
+            .at(Position::from_units(60., 320.))
+            .build();
+        page.add_text(txt, font_id.clone());
+    }
+
+    // pages without a trim box get no marks
+    document.create_page();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn print_marks_around_bleed_box() {
+    let mut document = Document::default();
+    let marks = PrintMarks::default().with_registration_marks(false);
+
+    let trim_box = Rectangle::from_units(0., 0., 200., 300.);
+    let bleed_box = Rectangle::from_units(-12., -12., 212., 312.);
+
+    let page = document.create_page();
+    page.set_mediabox(marks.media_box(trim_box, Some(bleed_box)));
+    page.set_bleed_box(bleed_box);
+    page.set_trim_box(trim_box);
+    page.fill_path(bleed_box, Color::Gray(200));
+
+    document.set_print_marks(marks);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn booklet_imposition() {
+    let mut document = Document::builder().with_page_size(Rectangle::A5).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    for page_num in 1..=3 {
+        let page = document.create_page();
+        page.fill_path(Rectangle::from_units(20., 20., 100., 60.), Color::Gray(200));
+
+        let txt = Text::builder()
+            .with_content(format!("Page {page_num}"))
+            .at(Position::from_units(30., 35.))
+            .build();
+        page.add_text(txt, font_id.clone());
+    }
+
+    // landscape pages are rotated to fit the portrait cells of the booklet
+    let page = document.create_page();
+    page.set_mediabox(Rectangle::from_units(0., 0., 595., 420.));
+
+    let booklet = document.impose(&Imposition::booklet(Rectangle::A4));
+
+    macros::snap_test!(booklet);
+}
+
+#[test]
+fn text_in_box() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    let text_box = Rectangle::new(Position::from_mm(10., 100.), Position::from_mm(95., 138.));
+    page.fill_path(text_box, Color::Gray(235));
+
+    let txt = Text::builder()
+        .with_content(
+            "Dear customer,\n\nThank you for your order. The goods will be shipped within the \
+             next three working days, and you will receive a notification with the tracking \
+             number as soon as the parcel has left our warehouse.",
+        )
+        .with_size(10)
+        .with_overflow(Overflow::Ellipsis)
+        .in_box(text_box)
+        .build();
+    page.add_text(txt, font_id);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn aligned_text_in_standard_fonts() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_ids: Vec<_> = ["Helvetica", "Times-Roman", "Courier"]
+        .into_iter()
+        .map(|font| document.create_font("Type1".into(), font.into()))
+        .collect();
+    let page = document.create_page();
+
+    for (idx, font_id) in font_ids.into_iter().enumerate() {
+        let top = 138. - idx as f32 * 40.;
+        let text_box = Rectangle::new(
+            Position::from_mm(10., top - 35.),
+            Position::from_mm(95., top),
+        );
+        page.fill_path(text_box, Color::Gray(235));
+
+        let txt = Text::builder()
+            .with_content(
+                "The goods will be shipped within the next three working days, and you will \
+                 receive a notification with the tracking number.",
+            )
+            .with_size(10)
+            .with_alignment(TextAlignment::Right)
+            .in_box(text_box)
+            .build();
+        page.add_text(txt, font_id);
+    }
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn hyphenated_text_in_box() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    let text_box = Rectangle::new(Position::from_mm(10., 100.), Position::from_mm(50., 138.));
+    page.fill_path(text_box, Color::Gray(235));
+
+    // hyphenation points as found by a hyphenation dictionary
+    let hyphenation = Hyphenation::new(|word: &str| match word {
+        "Versicherungsbedingungen" => vec![3, 5, 8, 13, 15, 18, 21],
+        "Haftpflichtversicherung" => vec![4, 11, 14, 16, 19],
+        _ => vec![],
+    });
+
+    let txt = Text::builder()
+        .with_content("Die Versicherungsbedingungen der Haftpflichtversicherung gelten ab sofort.")
+        .with_size(10)
+        .with_line_breaker(hyphenation)
+        .in_box(text_box)
+        .build();
+    page.add_text(txt, font_id);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn win_ansi_encoded_text() {
+    let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let encoding = FontEncoding::win_ansi().with_difference(0xA4, "Euro");
+    document.font_mut(&font_id).unwrap().set_encoding(encoding);
+
+    let page = document.create_page();
+    let txt = Text::builder()
+        .with_content("Café crème – ¤ 4,50")
+        .with_size(14)
+        .at(Position::from_mm(20., 270.))
+        .build();
+    page.add_text(txt, font_id);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn producer_identification() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::A4)
+        .with_producer(Producer::new("ACME Billing").without_version())
+        .build();
+    document.create_page();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+#[cfg(feature = "image-decoding")]
+fn culled_off_page_content() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., 200., 200.))
+        .with_content_culling(true)
+        .build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    // template elements moved off the page when they're not needed
+    page.fill_path(
+        Rectangle::from_units(250., 20., 300., 60.),
+        Color::Gray(200),
+    );
+    let img = Image::from_file(
+        &File::open(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg")).unwrap(),
+    )
+    .unwrap()
+    .at(Position::from_units(-100., 0.))
+    .scaled(Position::from_units(50., 50.))
+    .build();
+    page.add_image(img);
+
+    page.fill_path(Rectangle::from_units(20., 20., 180., 60.), Color::Gray(230));
+    let txt = Text::builder()
+        .with_content("Visible content")
+        .at(Position::from_units(30., 35.))
+        .build();
+    page.add_text(txt, font_id);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn object_size_budgets() {
+    let mut document = Document::builder().with_page_size(Rectangle::A4).build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+    let page_id = page.id();
+
+    let txt = Text::builder()
+        .with_content("Hello, World!")
+        .at(Position::from_mm(20., 270.))
+        .build();
+    page.add_text(txt, font_id.clone());
+
+    let report = document.write_lenient(&mut Vec::new()).unwrap();
+
+    macros::assert_object_size!(report, font_id, < 1_000);
+    macros::assert_object_size!(report, page_id, < 1_000);
+}
+
+#[test]
+fn sanitized_untrusted_input() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::A6)
+        .with_producer(Producer::new("ACME\r\nBilling\u{0}").without_version())
+        .with_sanitization(SanitizePolicy::new())
+        .build();
+
+    let font_id = document.create_font("Type1".into(), "Helvetica Bold)".into());
+    let page = document.create_page();
+    let page_id = page.id();
+
+    // submitted through a web form, hiding the real extension behind a right-to-left override
+    let txt = Text::builder()
+        .with_content("invoice\u{202E}fdp.exe\u{7}")
+        .at(Position::from_mm(10.0, 120.0))
+        .build();
+    page.add_text(txt, font_id);
+
+    let part = DocumentPart::new(page_id.clone(), page_id)
+        .with_metadata("Customer Name", "Jane\u{200B} Doe\n");
+    document.add_document_part(part).unwrap();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn auto_sized_receipt() {
+    let mut document = Document::default();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+    page.set_auto_size(Unit::from_mm(4.0));
+
+    // laid out from the top down without knowing the number of items in advance
+    let items = [
+        ("Coffee", "3.20"),
+        ("Bagel", "2.80"),
+        ("Orange juice", "4.50"),
+    ];
+    for (idx, (item, price)) in items.into_iter().enumerate() {
+        let y = -6.0 * idx as f32;
+        let txt = Text::builder()
+            .with_content(item)
+            .with_size(9)
+            .at(Position::from_mm(0.0, y))
+            .build();
+        page.add_text(txt, font_id.clone());
+
+        let txt = Text::builder()
+            .with_content(price)
+            .with_size(9)
+            .at(Position::from_mm(60.0, y))
+            .with_alignment(TextAlignment::Right)
+            .build();
+        page.add_text(txt, font_id.clone());
+    }
+
+    let y = -6.0 * items.len() as f32;
+    page.fill_path(
+        Rectangle::new(
+            Position::from_mm(0.0, y + 3.0),
+            Position::from_mm(60.0, y + 3.3),
+        ),
+        Color::Gray(0),
+    );
+    let txt = Text::builder()
+        .with_content("Total 10.50")
+        .with_size(9)
+        .at(Position::from_mm(60.0, y))
+        .with_alignment(TextAlignment::Right)
+        .build();
+    page.add_text(txt, font_id);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn roll_receipt() {
+    let mut document = Document::builder()
+        .with_roll(Roll::new(Unit::from_mm(80.0)).with_margin(Unit::from_mm(3.0)))
+        .build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+
+    let items = ["Coffee", "Bagel", "Orange juice", "Muffin", "Tea"];
+    for (idx, item) in items.into_iter().enumerate() {
+        // every item lands on the same page of the roll
+        let page = document.create_page();
+        let txt = Text::builder()
+            .with_content(item)
+            .with_size(9)
+            .at(Position::from_mm(4.0, -5.0 * idx as f32))
+            .build();
+        page.add_text(txt, font_id.clone());
+    }
+
+    assert_eq!(document.stats().unwrap().page_count(), 1);
+    macros::snap_test!(document);
+}
+
+#[test]
+fn address_labels() {
+    let mut document = Document::default();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+
+    // the first 2 labels of the sheet were used before
+    let sheet = LabelSheet::avery_l7163().with_first_label(2);
+    let addresses = (1..=14).map(|num| format!("Customer {num}\n{num} Main Street\nSpringfield"));
+
+    let pages = document.fill_labels(&sheet, addresses, |page, label, address| {
+        page.fill_path(label, Color::Gray(240));
+        let txt = Text::builder().with_content(address).in_box(label).build();
+        page.add_text(txt, font_id.clone());
+    });
+    assert_eq!(pages, 2);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn symbol_checklist() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let dingbats = document.create_symbol_font(SymbolFont::ZapfDingbats);
+
+    let page = document.create_page();
+    let tasks = [
+        ("Order supplies", true),
+        ("Confirm venue", true),
+        ("Send invites", false),
+    ];
+    for (idx, (task, done)) in tasks.into_iter().enumerate() {
+        let y = 130.0 - 10.0 * idx as f32;
+        let mark = match done {
+            true => SymbolGlyph::HEAVY_CHECK_MARK,
+            false => SymbolGlyph::HEAVY_BALLOT_X,
+        };
+
+        let txt = Text::builder()
+            .with_run(TextRun::new().glyph(mark))
+            .at(Position::from_mm(20.0, y))
+            .build();
+        page.add_text(txt, dingbats.clone());
+
+        let txt = Text::builder()
+            .with_content(task)
+            .at(Position::from_mm(27.0, y))
+            .build();
+        page.add_text(txt, font_id.clone());
+    }
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn default_text_style() {
+    let style = TextStyle::new("Times-Roman")
+        .with_size(10)
+        .with_color(Color::Gray(64));
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::A6)
+        .with_text_style(style)
+        .build();
+
+    let page = document.create_page();
+    let txt = Text::builder()
+        .with_content("Drawn in the style of the document")
+        .at(Position::from_mm(10.0, 130.0))
+        .build();
+    page.add_styled_text(txt).unwrap();
+
+    // the size and color set on a text take precedence over the style
+    let txt = Text::builder()
+        .with_content("Heading")
+        .with_size(16)
+        .at(Position::from_mm(10.0, 140.0))
+        .build();
+    page.add_styled_text(txt).unwrap();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+#[cfg(feature = "image-decoding")]
+fn stencil_masks() {
+    let page_size = 128.;
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., page_size, page_size))
+        .build();
+
+    let page = document.create_page();
+    let bytes =
+        std::fs::read(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("sample_image.jpg")).unwrap();
+
+    // samples are left uncompressed to keep the snapshot independent of the zlib implementation
+    let stencil = Image::stencil_from_bytes(bytes.clone())
+        .unwrap()
+        .with_stencil_color(Color::Rgb {
+            red: 0,
+            green: 0,
+            blue: 200,
+        })
+        .with_compression_level(0)
+        .at(Position::from_units(0., 64.))
+        .build();
+    page.add_image(stencil);
+
+    let mask = Image::stencil_from_bytes(bytes.clone())
+        .unwrap()
+        .with_compression_level(0)
+        .at(Position::from_units(0., 0.))
+        .build();
+    let masked = Image::from_bytes(bytes)
+        .unwrap()
+        .with_mask(mask)
+        .at(Position::from_units(64., 0.))
+        .build();
+    page.add_image(masked);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn flipped_images() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., 128., 128.))
+        .build();
+    let page = document.create_page();
+
+    // a gradient from black in the lower left to white in the upper right corner
+    #[rustfmt::skip]
+    let samples = [
+        0x80, 0xC0, 0xFF,
+        0x40, 0x80, 0xC0,
+        0x00, 0x40, 0x80,
+    ];
+    let image = || {
+        Image::from_raw(samples, 3, 3, ColorSpace::DeviceGray)
+            .unwrap()
+            .with_compression_level(0)
+            .scaled(Position::from_units(64., 64.))
+    };
+
+    page.add_image(image().at(Position::from_units(0., 0.)).build());
+    page.add_image(
+        image()
+            .flipped_horizontal()
+            .at(Position::from_units(64., 0.))
+            .build(),
+    );
+    page.add_image(
+        image()
+            .flipped_vertical()
+            .at(Position::from_units(0., 64.))
+            .build(),
+    );
+    page.add_image(
+        image()
+            .flipped_horizontal()
+            .flipped_vertical()
+            .at(Position::from_units(64., 64.))
+            .build(),
+    );
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn icc_based_colors() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., 100., 100.))
+        .build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    // NOTE: the profile data isn't a real ICC profile, as only the structure of the document is
+    //       tested.
+    let rgb_profile = document.create_icc_profile(b"RGB profile".to_vec(), ColorSpace::DeviceRgb);
+    let gray_profile =
+        document.create_icc_profile(b"Gray profile".to_vec(), ColorSpace::DeviceGray);
+    let page = document.create_page();
+
+    page.fill_path(
+        Rectangle::from_units(0., 0., 100., 50.),
+        Color::icc(rgb_profile, &[0, 128, 255]),
+    );
+    page.add_text(
+        Text::builder()
+            .with_content("Calibrated")
+            .with_color(Color::icc(gray_profile, &[255]))
+            .at(Position::from_units(10., 20.))
+            .build(),
+        font_id,
+    );
+
+    let image = Image::from_raw(b"RGBrgb".to_vec(), 2, 1, ColorSpace::Icc(rgb_profile))
+        .unwrap()
+        .with_compression_level(0)
+        .scaled(Position::from_units(80., 40.))
+        .at(Position::from_units(10., 55.))
+        .build();
+    page.add_image(image);
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn cie_based_colors() {
+    let mut document = Document::builder()
+        .with_page_size(Rectangle::from_units(0., 0., 100., 100.))
+        .build();
+    let cal_gray = document.create_cie_color_space(CalGray::new(D65).with_gamma(2.2));
+    let cal_rgb = document.create_cie_color_space(
+        CalRgb::new(D65).with_gamma([2.2, 2.2, 2.2]).with_matrix([
+            0.4124, 0.2126, 0.0193, 0.3576, 0.7152, 0.1192, 0.1805, 0.0722, 0.9505,
+        ]),
+    );
+    let lab = document.create_cie_color_space(Lab::new(D50).with_range([-128., 127., -128., 127.]));
+    let page = document.create_page();
+
+    page.fill_path(
+        Rectangle::from_units(0., 0., 100., 30.),
+        Color::cal_gray(cal_gray, 200),
+    );
+    page.fill_path(
+        Rectangle::from_units(0., 30., 100., 60.),
+        Color::cal_rgb(cal_rgb, 255, 128, 0),
+    );
+    page.fill_path(
+        Rectangle::from_units(0., 60., 100., 100.),
+        Color::lab(lab, 55, -37, -10),
+    );
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn highlighted_search_results() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    let txt = Text::builder()
+        .with_content("Invoice 2025-0042 was paid in full.")
+        .at(Position::from_units(20., 350.))
+        .build();
+    page.add_text(txt, font_id);
+
+    let result = Annotation::highlight([Rectangle::from_units(20., 347., 106., 361.)])
+        .unwrap()
+        .with_color(Color::Rgb {
+            red: 255,
+            green: 235,
+            blue: 0,
+        })
+        .with_contents("Search result for \"2025-0042\"");
+    page.add_annotation(result);
+    page.add_annotation(
+        Annotation::underline([Rectangle::from_units(130., 347., 190., 361.)]).unwrap(),
+    );
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn marked_up_drawing() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let page = document.create_page();
+
+    page.add_annotation(
+        Annotation::square(Rectangle::from_units(20., 300., 120., 360.))
+            .with_color(Color::Rgb {
+                red: 200,
+                green: 0,
+                blue: 0,
+            })
+            .with_border_width(2.)
+            .with_contents("Check the dimensions"),
+    );
+    page.add_annotation(
+        Annotation::circle(Rectangle::from_units(160., 300., 260., 360.)).with_interior_color(
+            Color::Rgb {
+                red: 255,
+                green: 235,
+                blue: 0,
+            },
+        ),
+    );
+    page.add_annotation(
+        Annotation::arrow(
+            Position::from_units(70., 200.),
+            Position::from_units(70., 290.),
+        )
+        .with_border_width(1.5),
+    );
+    page.add_annotation(
+        Annotation::line(
+            Position::from_units(20., 150.),
+            Position::from_units(260., 150.),
+        )
+        .with_line_endings(LineEndingStyle::Circle, LineEndingStyle::RClosedArrow)
+        .with_interior_color(Color::Gray(128)),
+    );
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn approval_stamp() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+
+    // a small pattern of modules, standing in for the QR code of the sign-off
+    #[rustfmt::skip]
+    let modules = [
+        0x00, 0xFF, 0x00,
+        0xFF, 0x00, 0xFF,
+        0x00, 0x00, 0xFF,
+    ];
+    let code = Image::from_raw(modules, 3, 3, ColorSpace::DeviceGray)
+        .unwrap()
+        .with_compression_level(0)
+        .scaled(Position::from_units(30., 30.))
+        .at(Position::from_units(5., 5.))
+        .build();
+
+    let mut approval = Recording::new();
+    approval.fill_path(
+        Rectangle::from_units(0., 0., 120., 40.),
+        Color::Rgb {
+            red: 220,
+            green: 245,
+            blue: 220,
+        },
+    );
+    approval.add_image(code);
+    approval.add_text(
+        Text::builder()
+            .with_content("APPROVED")
+            .with_size(14)
+            .at(Position::from_units(42., 15.))
+            .build(),
+        font_id,
+    );
+
+    page.add_annotation(
+        Annotation::stamp(Rectangle::from_units(160., 20., 280., 60.), approval)
+            .with_contents("Approved by J. Doe"),
+    );
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn fragment_stamped_into_another_document() {
+    let mut assets = Document::default();
+    let font_id = assets.create_font("Type1".into(), "Times-Roman".into());
+
+    #[rustfmt::skip]
+    let modules = [
+        0x00, 0xFF, 0x00,
+        0xFF, 0x00, 0xFF,
+        0x00, 0x00, 0xFF,
+    ];
+    let seal = Image::from_raw(modules, 3, 3, ColorSpace::DeviceGray)
+        .unwrap()
+        .with_compression_level(0)
+        .scaled(Position::from_units(20., 20.))
+        .at(Position::from_units(0., 0.))
+        .build();
+
+    let mut boilerplate = Recording::new();
+    boilerplate.fill_path(Rectangle::from_units(0., 0., 200., 1.), Color::Gray(128));
+    boilerplate.add_image(seal);
+    boilerplate.add_text(
+        Text::builder()
+            .with_content("Subject to the general terms and conditions.")
+            .with_size(8)
+            .at(Position::from_units(25., 6.))
+            .build(),
+        font_id,
+    );
+
+    let b_box = Rectangle::from_units(0., 0., 200., 20.);
+    let bytes = assets
+        .create_fragment(b_box, &boilerplate)
+        .unwrap()
+        .to_bytes();
+
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let fragment_id = document
+        .add_fragment(&Fragment::from_bytes(&bytes).unwrap())
+        .unwrap();
+    for _ in 0..2 {
+        document
+            .create_page()
+            .draw_fragment(&fragment_id, Position::from_units(50., 20.));
+    }
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn image_drawn_onto_every_page() {
+    #[rustfmt::skip]
+    let modules = [
+        0x00, 0xFF, 0x00,
+        0xFF, 0x00, 0xFF,
+        0x00, 0x00, 0xFF,
+    ];
+    let logo = Image::from_raw(modules, 3, 3, ColorSpace::DeviceGray)
+        .unwrap()
+        .with_compression_level(0)
+        .scaled(Position::from_units(20., 20.))
+        .at(Position::from_units(0., 0.))
+        .build();
+
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let logo = document.add_image(logo);
+    for idx in 0..2 {
+        let page = document.create_page();
+        page.draw_image(&logo, Position::from_units(20., 20.));
+        page.draw_image(&logo, Position::from_units(50. + 30. * idx as f32, 20.));
+    }
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn check_boxes_and_radio_buttons() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+    let page_id = page.id();
+
+    let labels = [
+        ("I accept the terms", 360.),
+        ("Standard shipping", 320.),
+        ("Express shipping", 300.),
+    ];
+    for (label, y) in labels {
+        page.add_text(
+            Text::builder()
+                .with_content(label)
+                .at(Position::from_units(40., y))
+                .build(),
+            font_id.clone(),
+        );
+    }
+
+    let terms = CheckBox::new(
+        "terms",
+        page_id.clone(),
+        Rectangle::from_units(20., 357., 32., 369.),
+    )
+    .with_export_value("Accepted")
+    .with_checked(true);
+    document.add_form_field(terms).unwrap();
+
+    let shipping = RadioGroup::new("shipping")
+        .with_button(
+            page_id.clone(),
+            Rectangle::from_units(20., 317., 32., 329.),
+            "Standard",
+        )
+        .with_button(
+            page_id,
+            Rectangle::from_units(20., 297., 32., 309.),
+            "Express",
+        )
+        .with_selected("Express");
+    document.add_form_field(shipping).unwrap();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn choice_fields() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+    let page_id = page.id();
+
+    for (label, y) in [("Country", 360.), ("T-shirt size", 320.)] {
+        page.add_text(
+            Text::builder()
+                .with_content(label)
+                .at(Position::from_units(20., y))
+                .build(),
+            font_id.clone(),
+        );
+    }
+
+    let country = ChoiceField::combo_box(
+        "country",
+        page_id.clone(),
+        Rectangle::from_units(100., 355., 220., 371.),
+    )
+    .with_options(["Österreich", "Deutschland", "Schweiz"])
+    .with_selected("Österreich")
+    .with_editable(true);
+    document.add_form_field(country).unwrap();
+
+    let size = ChoiceField::list_box(
+        "size",
+        page_id,
+        Rectangle::from_units(100., 276., 220., 332.),
+    )
+    .with_options(["S", "M", "L", "XL"])
+    .with_selected("L");
+    document.add_form_field(size).unwrap();
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn push_buttons() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let page_id = document.create_page().id();
+
+    let buttons = [
+        (
+            "submit",
+            "Submit",
+            Action::SubmitForm {
+                url: "https://example.com/submit".to_string(),
+                format: SubmitFormat::Html,
+            },
+        ),
+        ("reset", "Reset", Action::ResetForm),
+        (
+            "help",
+            "Help",
+            Action::Uri("https://example.com/help".to_string()),
+        ),
+        (
+            "greet",
+            "Say hello",
+            Action::JavaScript("app.alert('Hello!');".to_string()),
+        ),
+    ];
+
+    for (idx, (name, caption, action)) in buttons.into_iter().enumerate() {
+        let low_y = 360. - idx as f32 * 30.;
+        let button = PushButton::new(
+            name,
+            page_id.clone(),
+            Rectangle::from_units(20., low_y, 120., low_y + 20.),
+            caption,
+        )
+        .with_action(action);
+        document.add_form_field(button).unwrap();
+    }
+
+    macros::snap_test!(document);
+}
+
+#[test]
+fn text_fields() {
+    let mut document = Document::builder().with_page_size(Rectangle::A6).build();
+    let font_id = document.create_font("Type1".into(), "Helvetica".into());
+    let page = document.create_page();
+    let page_id = page.id();
+
+    for (label, y) in [
+        ("Name", 360.),
+        ("Password", 330.),
+        ("Postal code", 300.),
+        ("Comments", 270.),
+    ] {
+        page.add_text(
+            Text::builder()
+                .with_content(label)
+                .at(Position::from_units(20., y))
+                .build(),
+            font_id.clone(),
+        );
+    }
+
+    let name = TextField::new(
+        "name",
+        page_id.clone(),
+        Rectangle::from_units(100., 355., 270., 371.),
+    )
+    .with_value("Jane Doe")
+    .with_auto_size(true);
+    document.add_form_field(name).unwrap();
+
+    let password = TextField::new(
+        "password",
+        page_id.clone(),
+        Rectangle::from_units(100., 325., 270., 341.),
+    )
+    .with_value("secret")
+    .with_password(true);
+    document.add_form_field(password).unwrap();
+
+    let postal_code = TextField::new(
+        "postal-code",
+        page_id.clone(),
+        Rectangle::from_units(100., 295., 180., 311.),
+    )
+    .with_value("1010")
+    .with_comb(5);
+    document.add_form_field(postal_code).unwrap();
+
+    let comments = TextField::new(
+        "comments",
+        page_id,
+        Rectangle::from_units(100., 200., 270., 281.),
+    )
+    .with_value("Please deliver the parcel to the neighbours if nobody is at home.\nThank you!")
+    .with_multiline(true);
+    document.add_form_field(comments).unwrap();
+
+    macros::snap_test!(document);
+}
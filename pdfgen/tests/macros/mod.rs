@@ -20,6 +20,9 @@ macro_rules! function_name {
 /// Snapshot tests a given [`Document`], producing a PDF file with test (function) name as it's
 /// name, inside of a directory that corresponds to the module path.
 ///
+/// The generated document is compared byte by byte, so binary content such as encrypted streams
+/// must be generated deterministically for the snapshot to be stable.
+///
 /// [`Document`]: pdfgen::Document
 #[macro_export]
 macro_rules! snap_test {
@@ -56,6 +59,13 @@ macro_rules! snap_test {
                 ::std::fs::write(&file_path, &writer).unwrap();
             } else {
                 ::pretty_assertions::assert_str_eq!(file_content, doc_content);
+
+                // lossy UTF-8 conversion hides differences in binary content, such as encrypted
+                // or compressed streams, so the raw bytes must be compared as well
+                ::std::assert!(
+                    buf == writer,
+                    "Binary content of snapshot '{file_path}' differs from the generated document."
+                );
                 ::std::println!("To update snapshots, run tests again with 'cargo bless'")
             }
         } else {
@@ -83,4 +93,31 @@ macro_rules! snap_test {
     }};
 }
 
-pub use {function_name, snap_test};
+/// Asserts that the object referred to by the given handle takes up less bytes than the given
+/// budget in the PDF file written with the given [`WriteReport`]. Unlike snapshots, budgets aren't
+/// updated along with the generated documents, so size regressions, such as a filter that is no
+/// longer applied, fail the test.
+///
+/// Example: `assert_object_size!(report, font, < 1_000)`
+///
+/// [`WriteReport`]: pdfgen::WriteReport
+#[macro_export]
+macro_rules! assert_object_size {
+    ($report:expr, $handle:expr, < $budget:expr) => {{
+        let handle = &$handle;
+        let budget: usize = $budget;
+
+        match $report.object_size(handle) {
+            ::std::option::Option::Some(size) => ::std::assert!(
+                size < budget,
+                "Object {} takes up {size} bytes, exceeding its budget of {budget} bytes.",
+                ::std::stringify!($handle),
+            ),
+            ::std::option::Option::None => {
+                ::std::panic!("Object {} was not written.", ::std::stringify!($handle),)
+            }
+        }
+    }};
+}
+
+pub use {assert_object_size, function_name, snap_test};
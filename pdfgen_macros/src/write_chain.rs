@@ -33,7 +33,7 @@ impl Parse for LoopWrite {
         let pattern = Pat::parse_multi_with_leading_vert(input)?;
 
         let _ = input.parse::<Token![in]>()?;
-        let loop_expr = input.parse()?;
+        let loop_expr = Expr::parse_without_eager_brace(input)?;
 
         let body;
         syn::braced!(body in input);
@@ -113,7 +113,7 @@ impl Parse for IfWrite {
             let _ = input.parse::<Token![=]>()?;
         }
 
-        let if_expr = input.parse()?;
+        let if_expr = Expr::parse_without_eager_brace(input)?;
 
         let body;
         syn::braced!(body in input);